@@ -0,0 +1,183 @@
+//! Price alerts on the watched market, independent of the strategy. A rule
+//! names one side of one book, a threshold and optionally how much of the
+//! market must be left:
+//!
+//!   BOT_ALERTS="yes_bid>=0.90 left>=180; no_ask<0.05"
+//!
+//! Rules fire once when their condition becomes true and re-arm when it
+//! stops holding, so a price hovering at the threshold doesn't repeat.
+
+use std::collections::HashMap;
+
+use crate::strategy::{OrderBook, Outcome};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quote {
+    Bid,
+    Ask,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cmp {
+    Above,
+    AtLeast,
+    Below,
+    AtMost,
+}
+
+impl Cmp {
+    pub(crate) fn parse(s: &str) -> Option<Self> {
+        match s {
+            ">" => Some(Self::Above),
+            ">=" => Some(Self::AtLeast),
+            "<" => Some(Self::Below),
+            "<=" => Some(Self::AtMost),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn holds(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            Self::Above => value > threshold,
+            Self::AtLeast => value >= threshold,
+            Self::Below => value < threshold,
+            Self::AtMost => value <= threshold,
+        }
+    }
+}
+
+/// `<term><op><number>`, with the operator split out.
+pub(crate) fn split_comparison(term: &str) -> Option<(&str, Cmp, &str)> {
+    let at = term.find(['<', '>'])?;
+    let op_len = if term[at + 1..].starts_with('=') { 2 } else { 1 };
+    Some((&term[..at], Cmp::parse(&term[at..at + op_len])?, &term[at + op_len..]))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertRule {
+    // The rule as written, used as its name in notifications
+    pub text: String,
+    pub outcome: Outcome,
+    pub quote: Quote,
+    pub cmp: Cmp,
+    pub threshold: f64,
+    // Seconds before market close the rule applies in, both ends inclusive
+    pub min_left: Option<u64>,
+    pub max_left: Option<u64>,
+}
+
+impl AlertRule {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let mut terms = text.split_whitespace();
+        let first = terms.next().ok_or("empty alert rule")?;
+        let (field, cmp, value) = split_comparison(first).ok_or_else(|| format!("'{}': expected e.g. yes_bid>=0.90", first))?;
+        let (outcome, quote) = match field.to_ascii_lowercase().as_str() {
+            "yes_bid" => (Outcome::Yes, Quote::Bid),
+            "yes_ask" => (Outcome::Yes, Quote::Ask),
+            "no_bid" => (Outcome::No, Quote::Bid),
+            "no_ask" => (Outcome::No, Quote::Ask),
+            other => return Err(format!("unknown field '{}' (yes_bid, yes_ask, no_bid, no_ask)", other)),
+        };
+        let threshold: f64 = value.parse().map_err(|_| format!("'{}': bad price", first))?;
+        if !(0.0..=1.0).contains(&threshold) {
+            return Err(format!("'{}': price must be within 0-1", first));
+        }
+
+        let mut rule = Self { text: text.to_string(), outcome, quote, cmp, threshold, min_left: None, max_left: None };
+        for term in terms {
+            let (field, cmp, value) = split_comparison(term).ok_or_else(|| format!("'{}': expected e.g. left>=180", term))?;
+            let secs: u64 = value.parse().map_err(|_| format!("'{}': bad number of seconds", term))?;
+            if field != "left" {
+                return Err(format!("unknown condition '{}'; only left<op>seconds is supported", term));
+            }
+            match cmp {
+                Cmp::AtLeast => rule.min_left = Some(secs),
+                Cmp::Above => rule.min_left = Some(secs + 1),
+                Cmp::AtMost => rule.max_left = Some(secs),
+                Cmp::Below => rule.max_left = Some(secs.saturating_sub(1)),
+            }
+        }
+        Ok(rule)
+    }
+
+    /// The quoted price, if that side of the book has any.
+    pub fn value(&self, yes: &OrderBook, no: &OrderBook) -> Option<f64> {
+        let book = match self.outcome {
+            Outcome::Yes => yes,
+            Outcome::No => no,
+        };
+        match self.quote {
+            Quote::Bid => book.best_bid,
+            Quote::Ask => book.best_ask,
+        }
+    }
+
+    fn holds(&self, secs_left: u64, yes: &OrderBook, no: &OrderBook) -> Option<f64> {
+        if self.min_left.is_some_and(|min| secs_left < min) || self.max_left.is_some_and(|max| secs_left > max) {
+            return None;
+        }
+        self.value(yes, no).filter(|v| self.cmp.holds(*v, self.threshold))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alert {
+    pub rule: String,
+    pub market: String,
+    pub value: f64,
+    pub secs_left: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AlertWatcher {
+    rules: Vec<AlertRule>,
+    // (rule index, market) pairs whose condition currently holds
+    firing: HashMap<(usize, String), bool>,
+}
+
+impl AlertWatcher {
+    pub fn new(rules: Vec<AlertRule>) -> Self {
+        Self { rules, firing: HashMap::new() }
+    }
+
+    /// Semicolon-separated rules.
+    pub fn parse(list: &str) -> Result<Self, String> {
+        let rules = list.split(';').filter(|r| !r.trim().is_empty()).map(AlertRule::parse).collect::<Result<_, _>>()?;
+        Ok(Self::new(rules))
+    }
+
+    /// BOT_ALERTS; no rules when unset.
+    pub fn from_env() -> Result<Self, String> {
+        match std::env::var("BOT_ALERTS") {
+            Ok(v) => Self::parse(&v).map_err(|e| format!("Invalid BOT_ALERTS: {}", e)),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    pub fn rules(&self) -> &[AlertRule] {
+        &self.rules
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Rules that just started holding for `market`.
+    pub fn on_books(&mut self, market: &str, secs_left: u64, yes: &OrderBook, no: &OrderBook) -> Vec<Alert> {
+        let mut alerts = Vec::new();
+        for (i, rule) in self.rules.iter().enumerate() {
+            let holds = rule.holds(secs_left, yes, no);
+            let was = self.firing.insert((i, market.to_string()), holds.is_some()).unwrap_or(false);
+            if let (Some(value), false) = (holds, was) {
+                alerts.push(Alert { rule: rule.text.clone(), market: market.to_string(), value, secs_left });
+            }
+        }
+        alerts
+    }
+
+    /// Drop state for markets that are over.
+    pub fn forget(&mut self, market: &str) {
+        self.firing.retain(|(_, m), _| m != market);
+    }
+}
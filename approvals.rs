@@ -0,0 +1,90 @@
+use ethers::types::{Address, U256};
+
+use crate::chain::{self, RpcClient};
+use crate::network::NetworkProfile;
+use crate::tx_manager::TxRequest;
+
+// Anything below 1M USDC counts as "not approved" for trading purposes
+const MIN_USDC_ALLOWANCE: u64 = 1_000_000 * 1_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApprovalKind {
+    // ERC-20 approve(spender, amount) on the collateral token
+    Collateral,
+    // ERC-1155 setApprovalForAll(operator, true) on the CTF contract
+    ConditionalTokens,
+}
+
+#[derive(Debug, Clone)]
+pub struct ApprovalStatus {
+    pub kind: ApprovalKind,
+    pub token: Address,
+    pub spender: Address,
+    pub spender_name: &'static str,
+    pub approved: bool,
+}
+
+impl ApprovalStatus {
+    pub fn describe(&self) -> String {
+        match self.kind {
+            ApprovalKind::Collateral => format!("USDC allowance for {}", self.spender_name),
+            ApprovalKind::ConditionalTokens => format!("CTF setApprovalForAll for {}", self.spender_name),
+        }
+    }
+
+    pub fn approval_tx(&self) -> TxRequest {
+        let data = match self.kind {
+            ApprovalKind::Collateral => chain::encode_call(
+                "approve(address,uint256)",
+                &[chain::address_word(self.spender), chain::uint_word(U256::MAX)],
+            ),
+            ApprovalKind::ConditionalTokens => chain::encode_call(
+                "setApprovalForAll(address,bool)",
+                &[chain::address_word(self.spender), chain::uint_word(U256::one())],
+            ),
+        };
+        TxRequest {
+            label: self.describe(),
+            to: self.token,
+            data: data.into(),
+            value: U256::zero(),
+        }
+    }
+}
+
+/// Every approval the exchanges need from `owner`: collateral spend and
+/// conditional-token transfer rights for the CTF exchange, the neg-risk
+/// exchange and the neg-risk adapter.
+pub fn check_approvals(rpc: &RpcClient, network: &NetworkProfile, owner: Address, collateral: Address)
+    -> Result<Vec<ApprovalStatus>, Box<dyn std::error::Error>> {
+    let ctf = network.ctf;
+    let spenders: [(&'static str, Address); 3] = [
+        ("CTF Exchange", network.exchange),
+        ("Neg Risk Exchange", network.neg_risk_exchange),
+        ("Neg Risk Adapter", network.neg_risk_adapter),
+    ];
+
+    let mut statuses = Vec::new();
+    for (spender_name, spender) in spenders {
+        let allowance_call = chain::encode_call("allowance(address,address)", &[chain::address_word(owner), chain::address_word(spender)]);
+        let allowance = chain::decode_uint(&rpc.eth_call(collateral, &allowance_call)?)?;
+        statuses.push(ApprovalStatus {
+            kind: ApprovalKind::Collateral,
+            token: collateral,
+            spender,
+            spender_name,
+            approved: allowance >= U256::from(MIN_USDC_ALLOWANCE),
+        });
+
+        let operator_call = chain::encode_call("isApprovedForAll(address,address)", &[chain::address_word(owner), chain::address_word(spender)]);
+        let approved = !chain::decode_uint(&rpc.eth_call(ctf, &operator_call)?)?.is_zero();
+        statuses.push(ApprovalStatus {
+            kind: ApprovalKind::ConditionalTokens,
+            token: ctf,
+            spender,
+            spender_name,
+            approved,
+        });
+    }
+    Ok(statuses)
+}
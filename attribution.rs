@@ -0,0 +1,169 @@
+//! PnL attribution per decision, by replaying journaled ticks under
+//! counterfactual policies. For one market it answers:
+//!   - entry timing: did waiting for the trigger beat entering the same side
+//!     as soon as the window opened?
+//!   - stop vs hold: did the stop loss save money compared with holding to
+//!     resolution?
+//!   - abort avoidance: what would the entry the abort prevented have made?
+//!
+//! Each figure is "actual minus counterfactual" in USDC, so positive means
+//! the decision paid off. Fills come from the same `FillSimulator` the
+//! replay uses; stops sell at the touch.
+
+use serde::{Deserialize, Serialize};
+
+use crate::fill_model::{FillModel, FillSimulator};
+use crate::strategy::{self, OrderBook, Outcome, ReplayOutcome, StrategyParams, Tick};
+
+/// How a held position is valued at the end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    Won(Outcome),
+    // Winner taken from the last tick: the side bid at 0.5 or more
+    FromLastTick,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Exit {
+    StopLoss,
+    Resolution,
+}
+
+/// Result of holding a position from `entry_ts` under one exit policy.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PositionResult {
+    pub entry_ts: u64,
+    pub entry_price: f64,
+    pub size: u32,
+    pub exit: Exit,
+    pub exit_ts: u64,
+    pub exit_price: f64,
+    pub pnl: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Attribution {
+    pub decision: ReplayOutcome,
+    // PnL of what the strategy actually did; 0 without an entry
+    pub realized: f64,
+    pub position: Option<PositionResult>,
+    pub entry_timing: Option<f64>,
+    pub stop_vs_hold: Option<f64>,
+    pub abort_avoidance: Option<f64>,
+}
+
+fn book(tick: &Tick, outcome: Outcome) -> &OrderBook {
+    match outcome {
+        Outcome::Yes => &tick.yes,
+        Outcome::No => &tick.no,
+    }
+}
+
+fn settlement(outcome: Outcome, resolution: Resolution, ticks: &[Tick]) -> f64 {
+    let winner = match resolution {
+        Resolution::Won(winner) => Some(winner),
+        Resolution::FromLastTick => ticks.last().map(|t| if t.yes.best_bid.unwrap_or(0.0) >= 0.5 { Outcome::Yes } else { Outcome::No }),
+    };
+    if winner == Some(outcome) { 1.0 } else { 0.0 }
+}
+
+/// Hold `size` of `outcome` bought at `entry_price` on the tick at
+/// `entry_ts`, selling at the bid on the first later tick at or below
+/// `stop_loss` (when given), else settling at resolution.
+pub fn hold(outcome: Outcome, entry_ts: u64, entry_price: f64, size: u32, stop_loss: Option<f64>, ticks: &[Tick], resolution: Resolution) -> PositionResult {
+    let shares = size as f64;
+    if let Some(stop) = stop_loss {
+        for tick in ticks.iter().filter(|t| t.ts > entry_ts) {
+            if let Some(bid) = book(tick, outcome).best_bid.filter(|bid| *bid <= stop) {
+                return PositionResult {
+                    entry_ts, entry_price, size,
+                    exit: Exit::StopLoss,
+                    exit_ts: tick.ts,
+                    exit_price: bid,
+                    pnl: (bid - entry_price) * shares,
+                };
+            }
+        }
+    }
+    let value = settlement(outcome, resolution, ticks);
+    PositionResult {
+        entry_ts, entry_price, size,
+        exit: Exit::Resolution,
+        exit_ts: ticks.last().map(|t| t.ts).unwrap_or(entry_ts),
+        exit_price: value,
+        pnl: (value - entry_price) * shares,
+    }
+}
+
+/// Buy `outcome` on the first tick of the trading window that can fill,
+/// ignoring the entry trigger.
+fn enter_at_open(params: &StrategyParams, market_start_ts: u64, outcome: Outcome, ticks: &[Tick], model: &FillModel) -> Option<(u64, f64, u32)> {
+    let opens_at = market_start_ts + strategy::MARKET_DURATION - params.market_window;
+    let size = strategy::entry_size(params, outcome, params.position_size);
+    let mut fills = FillSimulator::new(model.clone());
+    ticks.iter().filter(|t| t.ts >= opens_at && t.ts < market_start_ts + strategy::MARKET_DURATION).find_map(|tick| {
+        let book = book(tick, outcome);
+        let fill = fills.take(book, size as f64, book.best_ask?, true);
+        (fill.size > 0.0).then_some((tick.ts, fill.price, fill.size as u32))
+    })
+}
+
+pub fn attribute(params: &StrategyParams, market_start_ts: u64, ticks: &[Tick], stop_loss: f64, resolution: Resolution, model: &FillModel) -> Attribution {
+    let decision = strategy::replay_with(params, market_start_ts, ticks, &mut FillSimulator::new(model.clone()));
+    let mut result = Attribution { decision: decision.clone(), realized: 0.0, position: None, entry_timing: None, stop_vs_hold: None, abort_avoidance: None };
+
+    match decision {
+        ReplayOutcome::Entered { ts, outcome, ask, size } => {
+            let actual = hold(outcome, ts, ask, size, Some(stop_loss), ticks, resolution);
+            result.realized = actual.pnl;
+            result.position = Some(actual);
+
+            if actual.exit == Exit::StopLoss {
+                result.stop_vs_hold = Some(actual.pnl - hold(outcome, ts, ask, size, None, ticks, resolution).pnl);
+            }
+            if let Some((open_ts, open_price, open_size)) = enter_at_open(params, market_start_ts, outcome, ticks, model) {
+                let at_open = hold(outcome, open_ts, open_price, open_size, Some(stop_loss), ticks, resolution);
+                result.entry_timing = Some(actual.pnl - at_open.pnl);
+            }
+        }
+        ReplayOutcome::Aborted { .. } => {
+            // Same run with an abort threshold no ask can reach
+            let ignore_abort = StrategyParams { abort_ask_price: f64::INFINITY, ..params.clone() };
+            let counterfactual = strategy::replay_with(&ignore_abort, market_start_ts, ticks, &mut FillSimulator::new(model.clone()));
+            let pnl = match counterfactual {
+                ReplayOutcome::Entered { ts, outcome, ask, size } => hold(outcome, ts, ask, size, Some(stop_loss), ticks, resolution).pnl,
+                _ => 0.0,
+            };
+            result.abort_avoidance = Some(-pnl);
+        }
+        _ => {}
+    }
+    result
+}
+
+/// Sums over many markets, for a journal-level summary.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AttributionTotals {
+    pub markets: usize,
+    pub entries: usize,
+    pub realized: f64,
+    pub entry_timing: f64,
+    pub stops: usize,
+    pub stop_vs_hold: f64,
+    pub aborts: usize,
+    pub abort_avoidance: f64,
+}
+
+impl AttributionTotals {
+    pub fn add(&mut self, a: &Attribution) {
+        self.markets += 1;
+        self.entries += usize::from(a.position.is_some());
+        self.realized += a.realized;
+        self.entry_timing += a.entry_timing.unwrap_or(0.0);
+        self.stops += usize::from(a.stop_vs_hold.is_some());
+        self.stop_vs_hold += a.stop_vs_hold.unwrap_or(0.0);
+        self.aborts += usize::from(a.abort_avoidance.is_some());
+        self.abort_avoidance += a.abort_avoidance.unwrap_or(0.0);
+    }
+}
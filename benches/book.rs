@@ -0,0 +1,85 @@
+//! `/book` processing: the streaming top-of-book scan the bot uses, against a
+//! fully materialized serde_json::Value parse as the baseline it replaced.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use serde_json::{json, Value};
+
+use eth_no_trend_bot::book_parser::{self, TopOfBook};
+
+/// A `/book` response shaped like the CLOB's, with `depth` levels per side
+/// listed worst-to-best the way the API returns them.
+fn book_json(depth: usize) -> Vec<u8> {
+    let level = |price: f64, i: usize| json!({ "price": format!("{:.3}", price), "size": format!("{}.{:02}", 10 + i * 7 % 500, i % 100) });
+    let bids: Vec<Value> = (0..depth).map(|i| level(0.01 + 0.94 * i as f64 / depth as f64, i)).collect();
+    let asks: Vec<Value> = (0..depth).map(|i| level(0.99 - 0.03 * i as f64 / depth as f64, i)).collect();
+    serde_json::to_vec(&json!({
+        "market": "0x5f65177b394277fd294cd75650044e32ba009a95022d88a0c1d565897d72f8f1",
+        "asset_id": "71321045679252212594626385532706912750332728571942532289631379312455583992563",
+        "timestamp": "1760000400123",
+        "hash": "0x1f0c5e5a8c2b7a9f4d3e6b1a0c9d8e7f6a5b4c3d",
+        "bids": bids,
+        "asks": asks,
+        "min_order_size": "5",
+        "tick_size": "0.001",
+        "neg_risk": false,
+    }))
+    .unwrap()
+}
+
+/// What the strategy reads off a book each tick: touch prices, mid, spread
+/// and top-level imbalance, in f64 as the decision code consumes them.
+fn book_features(top: &TopOfBook) -> Option<(f64, f64, f64)> {
+    let (ask, ask_size) = top.best_ask?;
+    let (bid, bid_size) = top.best_bid?;
+    let (ask, bid) = (book_parser::fixed_to_f64(ask), book_parser::fixed_to_f64(bid));
+    let (ask_size, bid_size) = (book_parser::fixed_to_f64(ask_size), book_parser::fixed_to_f64(bid_size));
+    let imbalance = (bid_size - ask_size) / (bid_size + ask_size).max(f64::EPSILON);
+    Some(((ask + bid) / 2.0, ask - bid, imbalance))
+}
+
+fn best_from_value(book: &Value) -> TopOfBook {
+    let best = |side: &str, lowest: bool| {
+        side_levels(book, side).fold(None, |best: Option<(u64, u64)>, (price, size)| match best {
+            Some((p, _)) if (lowest && p <= price) || (!lowest && p >= price) => best,
+            _ => Some((price, size)),
+        })
+    };
+    TopOfBook { best_ask: best("asks", true), best_bid: best("bids", false) }
+}
+
+fn side_levels<'a>(book: &'a Value, side: &str) -> impl Iterator<Item = (u64, u64)> + 'a {
+    book[side].as_array().into_iter().flatten().filter_map(|level| {
+        Some((book_parser::parse_fixed(level["price"].as_str()?)?, book_parser::parse_fixed(level["size"].as_str()?)?))
+    })
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("book/parse");
+    for depth in [10, 100, 1000] {
+        let bytes = book_json(depth);
+        group.throughput(Throughput::Bytes(bytes.len() as u64));
+        group.bench_with_input(BenchmarkId::new("top_of_book", depth), &bytes, |b, bytes| {
+            b.iter(|| book_parser::parse_top_of_book(black_box(bytes)).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("value", depth), &bytes, |b, bytes| {
+            b.iter(|| best_from_value(&serde_json::from_slice::<Value>(black_box(bytes)).unwrap()))
+        });
+    }
+    group.finish();
+}
+
+fn bench_features(c: &mut Criterion) {
+    let bytes = book_json(100);
+    let top = book_parser::parse_top_of_book(&bytes).unwrap();
+
+    c.bench_function("book/features", |b| b.iter(|| book_features(black_box(&top))));
+    c.bench_function("book/parse_and_features", |b| {
+        b.iter(|| book_features(&book_parser::parse_top_of_book(black_box(&bytes)).unwrap()))
+    });
+    c.bench_function("book/parse_fixed", |b| b.iter(|| book_parser::parse_fixed(black_box("0.955"))));
+}
+
+criterion_group!(benches, bench_parse, bench_features);
+criterion_main!(benches);
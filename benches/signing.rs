@@ -0,0 +1,67 @@
+//! Per-order signing cost: EIP-712 hashing and signing on the order path,
+//! and the HMAC headers every authenticated request pays for.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::Address;
+
+use eth_no_trend_bot::network::NetworkProfile;
+use eth_no_trend_bot::signing::{build_hmac_signature, ApiCredentials, Eip712Signer, OrderAmounts, OrderSide, PolymarketOrder};
+
+// Anvil's first dev account; any valid key will do
+const KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+const TOKEN_ID: &str = "71321045679252212594626385532706912750332728571942532289631379312455583992563";
+const SECRET: &str = "c2VjcmV0LXNlY3JldC1zZWNyZXQtc2VjcmV0LXNlY3I=";
+
+fn order(maker: Address) -> PolymarketOrder {
+    let amounts = OrderAmounts::new(OrderSide::Buy, 0.96, 5);
+    PolymarketOrder {
+        salt: "1760000400123".to_string(),
+        maker: format!("{:?}", maker),
+        signer: format!("{:?}", maker),
+        taker: format!("{:?}", Address::zero()),
+        token_id: TOKEN_ID.to_string(),
+        maker_amount: amounts.maker_amount.to_string(),
+        taker_amount: amounts.taker_amount.to_string(),
+        expiration: "0".to_string(),
+        nonce: "0".to_string(),
+        fee_rate_bps: "0".to_string(),
+        side: OrderSide::Buy.as_str().to_string(),
+        signature_type: 0,
+    }
+}
+
+fn bench_eip712(c: &mut Criterion) {
+    let wallet: LocalWallet = KEY.parse().unwrap();
+    let maker = wallet.address();
+    let network = NetworkProfile::polygon();
+    let signer = Eip712Signer::new(wallet, network.chain_id, network.exchange);
+    let order = order(maker);
+
+    c.bench_function("eip712/domain_hash", |b| b.iter(|| signer.hash_domain()));
+    c.bench_function("eip712/order_hash", |b| b.iter(|| signer.order_hash(black_box(&order))));
+    c.bench_function("eip712/sign_order", |b| b.iter(|| signer.sign_order(black_box(&order)).unwrap()));
+    c.bench_function("eip712/sign_clob_auth", |b| b.iter(|| signer.sign_clob_auth(black_box("1760000400"), 0).unwrap()));
+}
+
+fn bench_hmac(c: &mut Criterion) {
+    let creds = ApiCredentials {
+        api_key: "00000000-0000-0000-0000-000000000000".to_string(),
+        secret: SECRET.to_string(),
+        passphrase: "passphrase".to_string(),
+    };
+    let address: Address = "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266".parse().unwrap();
+    let body = r#"{"order":{"salt":1760000400123,"side":"BUY"},"owner":"00000000-0000-0000-0000-000000000000","orderType":"FOK"}"#;
+
+    c.bench_function("hmac/signature", |b| {
+        b.iter(|| build_hmac_signature(SECRET, black_box("1760000400"), "POST", "/order", black_box(body)).unwrap())
+    });
+    c.bench_function("hmac/l2_headers", |b| {
+        b.iter(|| creds.l2_headers(address, black_box("1760000400"), "POST", "/order", black_box(body)).unwrap())
+    });
+}
+
+criterion_group!(benches, bench_eip712, bench_hmac);
+criterion_main!(benches);
@@ -0,0 +1,139 @@
+use std::fmt;
+
+use serde::de::{self, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+
+// Prices and sizes are kept as 6-decimal fixed point (same scale as USDC)
+pub const FIXED_SCALE: u64 = 1_000_000;
+
+/// Parse a non-negative decimal string ("0.955", "12", "1.5e-3" is rejected)
+/// into 6-decimal fixed point without going through f64.
+pub fn parse_fixed(s: &str) -> Option<u64> {
+    let (int_part, frac_part) = match s.find('.') {
+        Some(dot) => (&s[..dot], &s[dot + 1..]),
+        None => (s, ""),
+    };
+    if int_part.is_empty() && frac_part.is_empty() {
+        return None;
+    }
+
+    let mut value: u64 = 0;
+    for b in int_part.bytes() {
+        if !b.is_ascii_digit() {
+            return None;
+        }
+        value = value.checked_mul(10)?.checked_add((b - b'0') as u64)?;
+    }
+    value = value.checked_mul(FIXED_SCALE)?;
+
+    let mut scale = FIXED_SCALE / 10;
+    for b in frac_part.bytes() {
+        if !b.is_ascii_digit() {
+            return None;
+        }
+        // Digits beyond 6 decimals are truncated
+        if scale > 0 {
+            value = value.checked_add((b - b'0') as u64 * scale)?;
+            scale /= 10;
+        }
+    }
+    Some(value)
+}
+
+pub fn fixed_to_f64(value: u64) -> f64 {
+    value as f64 / FIXED_SCALE as f64
+}
+
+/// Best level on each side as (price, size) in fixed point.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TopOfBook {
+    pub best_ask: Option<(u64, u64)>,
+    pub best_bid: Option<(u64, u64)>,
+}
+
+/// Scan a `/book` response for the touch without materializing the levels.
+/// Level strings are borrowed straight from `bytes`, so the only allocation
+/// is whatever the caller already owns.
+pub fn parse_top_of_book(bytes: &[u8]) -> Result<TopOfBook, serde_json::Error> {
+    let mut de = serde_json::Deserializer::from_slice(bytes);
+    let top = de.deserialize_map(BookVisitor)?;
+    de.end()?;
+    Ok(top)
+}
+
+struct BookVisitor;
+
+impl<'de> Visitor<'de> for BookVisitor {
+    type Value = TopOfBook;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an order book object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<TopOfBook, A::Error> {
+        let mut top = TopOfBook::default();
+        while let Some(key) = map.next_key::<&'de str>()? {
+            match key {
+                "asks" => top.best_ask = map.next_value_seed(BestLevel { lowest: true })?,
+                "bids" => top.best_bid = map.next_value_seed(BestLevel { lowest: false })?,
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(top)
+    }
+}
+
+/// Seed that walks a level array keeping only the best price.
+struct BestLevel {
+    lowest: bool,
+}
+
+impl<'de> DeserializeSeed<'de> for BestLevel {
+    type Value = Option<(u64, u64)>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        // deserialize_seq would reject null before visit_unit sees it
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for BestLevel {
+    type Value = Option<(u64, u64)>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an array of price levels")
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        // "asks": null is treated like an empty side
+        Ok(None)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut best: Option<(u64, u64)> = None;
+        while let Some(level) = seq.next_element::<RawLevel<'de>>()? {
+            let price = parse_fixed(level.price)
+                .ok_or_else(|| de::Error::custom(format!("bad price {:?}", level.price)))?;
+            let size = parse_fixed(level.size)
+                .ok_or_else(|| de::Error::custom(format!("bad size {:?}", level.size)))?;
+
+            let better = match best {
+                None => true,
+                Some((best_price, _)) => if self.lowest { price < best_price } else { price > best_price },
+            };
+            if better {
+                best = Some((price, size));
+            }
+        }
+        Ok(best)
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct RawLevel<'a> {
+    #[serde(borrow)]
+    price: &'a str,
+    #[serde(borrow)]
+    size: &'a str,
+}
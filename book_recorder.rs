@@ -0,0 +1,123 @@
+//! Book recordings (BOT_RECORD_DIR): every YES/NO pair of books the trading
+//! loop acts on, plus the trades printed on the market stream, as JSON lines
+//! in one file per UTC day (`books-2025-10-09.jsonl`).
+//!
+//!   {"type":"book","ts_ms":1760000460250,"market":"eth-updown-15m-1760000400","yes":{...},"no":{...}}
+//!   {"type":"trade","ts_ms":1760000460812,"market":"eth-updown-15m-1760000400","token_id":"1002","price":0.97,"size":12.0,"side":"BUY"}
+//!
+//! Each record is a single append, so a crash loses at most the line being
+//! written; `read` skips a torn last line. `ticks` turns a market's book
+//! records back into the `strategy::Tick`s replays and the backtester use.
+//! Trades only exist while books are streamed; polling `/book` sees none.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::strategy::{OrderBook, Tick};
+use crate::timestamps;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Record {
+    Book { ts_ms: u64, market: String, yes: OrderBook, no: OrderBook },
+    Trade { ts_ms: u64, market: String, token_id: String, price: f64, size: f64, side: String },
+}
+
+impl Record {
+    pub fn ts_ms(&self) -> u64 {
+        match self {
+            Record::Book { ts_ms, .. } | Record::Trade { ts_ms, .. } => *ts_ms,
+        }
+    }
+}
+
+/// Appends records to the day file for their timestamp.
+pub struct BookRecorder {
+    dir: PathBuf,
+    // Day of the open file, as YYYY-MM-DD
+    day: String,
+    file: Option<File>,
+}
+
+impl BookRecorder {
+    pub fn new(dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir, day: String::new(), file: None })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The file records at `ts_ms` go to.
+    pub fn path_for(&self, ts_ms: u64) -> PathBuf {
+        self.dir.join(format!("books-{}.jsonl", day_of(ts_ms)))
+    }
+
+    pub fn record(&mut self, record: &Record) -> io::Result<()> {
+        let day = day_of(record.ts_ms());
+        if self.file.is_none() || day != self.day {
+            let path = self.path_for(record.ts_ms());
+            self.file = Some(OpenOptions::new().create(true).append(true).open(path)?);
+            self.day = day;
+        }
+        let mut line = serde_json::to_string(record).map_err(io::Error::other)?;
+        line.push('\n');
+        match self.file.as_mut() {
+            Some(file) => file.write_all(line.as_bytes()),
+            None => Ok(()),
+        }
+    }
+}
+
+fn day_of(ts_ms: u64) -> String {
+    timestamps::rfc3339(ts_ms / 1000)[..10].to_string()
+}
+
+/// Every record in a recording. A malformed last line is the write a crash
+/// cut short and is skipped; one anywhere else is an error.
+pub fn read(path: &str) -> Result<Vec<Record>, String> {
+    let file = File::open(path).map_err(|e| format!("Cannot read {}: {}", path, e))?;
+    let lines: Vec<String> = BufReader::new(file).lines().collect::<Result<_, _>>().map_err(|e| format!("Cannot read {}: {}", path, e))?;
+    let mut records = Vec::with_capacity(lines.len());
+    for (i, line) in lines.iter().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(line) {
+            Ok(record) => records.push(record),
+            Err(_) if i + 1 == lines.len() => {}
+            Err(e) => return Err(format!("{} line {}: {}", path, i + 1, e)),
+        }
+    }
+    Ok(records)
+}
+
+/// Book records of `market` as replay ticks, in time order.
+pub fn ticks(records: &[Record], market: &str) -> Vec<Tick> {
+    let mut ticks: Vec<Tick> = records.iter()
+        .filter_map(|r| match r {
+            Record::Book { ts_ms, market: m, yes, no } if m == market => Some(Tick { ts: ts_ms / 1000, yes: *yes, no: *no }),
+            _ => None,
+        })
+        .collect();
+    ticks.sort_by_key(|t| t.ts);
+    ticks
+}
+
+/// Markets with book records, in order of first appearance.
+pub fn markets(records: &[Record]) -> Vec<&str> {
+    let mut markets: Vec<&str> = Vec::new();
+    for record in records {
+        if let Record::Book { market, .. } = record {
+            if !markets.contains(&market.as_str()) {
+                markets.push(market);
+            }
+        }
+    }
+    markets
+}
@@ -0,0 +1,54 @@
+//! What the bot did, as structured events for external consumers (the SSE
+//! stream in `event_stream`). Serialized with a `type` tag, e.g.
+//! `{"type":"fill","order_id":"0x…","size":5.0,…}`.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BotEvent {
+    /// Top of both books as polled while monitoring a market.
+    Tick {
+        market: String,
+        secs_left: u64,
+        yes_bid: Option<f64>,
+        yes_ask: Option<f64>,
+        no_bid: Option<f64>,
+        no_ask: Option<f64>,
+    },
+    /// The exchange accepted an order.
+    OrderAccepted {
+        order_id: String,
+        token_id: String,
+        side: String,
+        price: f64,
+        size: u32,
+        order_type: String,
+    },
+    OrderRejected {
+        token_id: String,
+        side: String,
+        reason: String,
+    },
+    /// New fill on a tracked order; `size` is the increment.
+    Fill {
+        order_id: String,
+        token_id: String,
+        side: String,
+        size: f64,
+        filled: f64,
+        avg_price: f64,
+    },
+    /// A market moved through the loop: monitoring, window_open, entering,
+    /// or the reason it was marked traded (entered, aborted, closed, …).
+    State {
+        market: String,
+        state: String,
+    },
+    Alert {
+        market: String,
+        rule: String,
+        value: f64,
+        secs_left: u64,
+    },
+}
@@ -0,0 +1,39 @@
+// Generates the gRPC server/client stubs for the `grpc` feature. Uses
+// tonic-build's manual mode so no protoc is needed; the messages themselves
+// live in grpc.rs and mirror proto/execution.proto.
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    #[cfg(feature = "grpc")]
+    grpc::compile();
+}
+
+#[cfg(feature = "grpc")]
+mod grpc {
+    use tonic_build::manual::{Builder, Method, Service};
+
+    const CODEC: &str = "tonic::codec::ProstCodec";
+
+    fn unary(name: &str, route: &str, input: &str, output: &str) -> Method {
+        Method::builder()
+            .name(name)
+            .route_name(route)
+            .input_type(format!("crate::grpc::{}", input))
+            .output_type(format!("crate::grpc::{}", output))
+            .codec_path(CODEC)
+            .build()
+    }
+
+    pub fn compile() {
+        let service = Service::builder()
+            .name("Execution")
+            .package("execution")
+            .method(unary("place_order", "PlaceOrder", "PlaceOrderRequest", "PlaceOrderReply"))
+            .method(unary("cancel_order", "CancelOrder", "CancelOrderRequest", "CancelOrderReply"))
+            .method(unary("get_order_status", "GetOrderStatus", "OrderStatusRequest", "OrderStatusReply"))
+            .method(unary("get_positions", "GetPositions", "PositionsRequest", "PositionsReply"))
+            .method(unary("get_book", "GetBook", "BookRequest", "BookReply"))
+            .build();
+        Builder::new().compile(&[service]);
+    }
+}
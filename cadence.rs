@@ -0,0 +1,105 @@
+//! How long to sleep between looks at the market. Books are polled at the
+//! fast `active` rate only inside the trading window; before it the bot
+//! sleeps straight to the window opening (or polls at the slower `watching`
+//! rate while price alerts need books), and between markets it sleeps to
+//! the next cycle boundary plus the listing delay rather than in flat steps
+//! that can overshoot an open.
+//!
+//! No single sleep exceeds `max_sleep`, so status lines, clock resyncs and
+//! resolution polls in the outer loop keep happening; the last step of a
+//! long wait still lands exactly on the boundary.
+//!
+//!   BOT_POLL_ACTIVE_MS   in-window book poll interval (default 1000)
+//!   BOT_POLL_STREAM_MS   the same while books stream over the WebSocket (default 100)
+//!   BOT_POLL_WATCH_SECS  pre-window poll interval with alerts armed (default 5)
+
+use std::time::Duration;
+
+/// Length of one market cycle unless the market schedule says otherwise.
+pub const CYCLE: u64 = 900;
+
+// Floor for computed sleeps, so a wait that rounds to nothing still moves
+// a simulated clock forward
+const MIN_SLEEP: Duration = Duration::from_millis(1);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cadence {
+    // Book poll interval inside the trading window
+    pub active: Duration,
+    // In-window interval when books are local reads off the market stream
+    pub streaming: Duration,
+    // Book poll interval before the window while alerts need books
+    pub watching: Duration,
+    // Longest single sleep
+    pub max_sleep: Duration,
+    // How long after a cycle opens its market is looked up; listings lag
+    pub listing_delay: Duration,
+    // Market cycle length, seconds
+    pub cycle: u64,
+}
+
+impl Default for Cadence {
+    fn default() -> Self {
+        Self {
+            active: Duration::from_secs(1),
+            streaming: Duration::from_millis(100),
+            watching: Duration::from_secs(5),
+            max_sleep: Duration::from_secs(60),
+            listing_delay: Duration::from_secs(5),
+            cycle: CYCLE,
+        }
+    }
+}
+
+impl Cadence {
+    pub fn from_env() -> Result<Self, String> {
+        let mut cadence = Self::default();
+        if let Ok(v) = std::env::var("BOT_POLL_ACTIVE_MS") {
+            let ms = v.trim().parse::<u64>().ok().filter(|ms| *ms > 0)
+                .ok_or_else(|| format!("Invalid BOT_POLL_ACTIVE_MS '{}': expected milliseconds above 0", v))?;
+            cadence.active = Duration::from_millis(ms);
+        }
+        if let Ok(v) = std::env::var("BOT_POLL_STREAM_MS") {
+            let ms = v.trim().parse::<u64>().ok().filter(|ms| *ms > 0)
+                .ok_or_else(|| format!("Invalid BOT_POLL_STREAM_MS '{}': expected milliseconds above 0", v))?;
+            cadence.streaming = Duration::from_millis(ms);
+        }
+        if let Ok(v) = std::env::var("BOT_POLL_WATCH_SECS") {
+            let secs = v.trim().parse::<u64>().ok().filter(|s| *s > 0)
+                .ok_or_else(|| format!("Invalid BOT_POLL_WATCH_SECS '{}': expected seconds above 0", v))?;
+            cadence.watching = Duration::from_secs(secs);
+        }
+        Ok(cadence)
+    }
+
+    /// Sleep before a trading window that opens at `opens_at`; `watching`
+    /// when something still needs books before then.
+    pub fn before_window(&self, now: f64, opens_at: u64, watching: bool) -> Duration {
+        let until = until(now, opens_at as f64);
+        let step = if watching { until.min(self.watching) } else { until };
+        step.min(self.max_sleep)
+    }
+
+    /// Sleep between book polls inside the trading window; shorter when
+    /// both books are `streaming` and a look costs no request.
+    pub fn in_window(&self, streaming: bool) -> Duration {
+        if streaming { self.streaming } else { self.active }
+    }
+
+    /// Sleep until the next cycle's market can be looked up.
+    pub fn until_next_cycle(&self, now: f64) -> Duration {
+        let next = (now.max(0.0) as u64 / self.cycle + 1) * self.cycle;
+        (until(now, next as f64) + self.listing_delay).min(self.max_sleep)
+    }
+
+    /// Sleep until the cycle that opened at `cycle_start` is listed, or
+    /// zero once it should be.
+    pub fn until_listed(&self, now: f64, cycle_start: u64) -> Duration {
+        let listed = cycle_start as f64 + self.listing_delay.as_secs_f64();
+        if now >= listed { Duration::ZERO } else { until(now, listed).min(self.max_sleep) }
+    }
+}
+
+fn until(now: f64, target: f64) -> Duration {
+    Duration::from_secs_f64((target - now).max(0.0)).max(MIN_SLEEP)
+}
@@ -0,0 +1,166 @@
+use std::cell::Cell;
+
+use ethers::types::{Address, Bytes, Log, H256, U256};
+use ethers::utils::keccak256;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::rpc_pool::RpcPool;
+
+/// Minimal blocking JSON-RPC client for Polygon, in the same request/response
+/// style as the REST calls in main.rs. Requests are spread over an `RpcPool`.
+pub struct RpcClient {
+    pool: RpcPool,
+    next_id: Cell<u64>,
+}
+
+/// A JSON-RPC error response. Kept typed (rather than flattened into a
+/// string) so revert data from eth_call / eth_estimateGas can be decoded.
+#[derive(Debug, Deserialize)]
+pub struct RpcCallError {
+    #[serde(skip)]
+    pub method: String,
+    pub code: i64,
+    pub message: String,
+    #[serde(default)]
+    pub data: Option<Value>,
+}
+
+impl std::fmt::Display for RpcCallError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} failed ({}): {}", self.method, self.code, self.message)
+    }
+}
+
+impl std::error::Error for RpcCallError {}
+
+#[derive(Debug, Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcCallError>,
+}
+
+impl RpcClient {
+    pub fn new(urls: &[String]) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self {
+            pool: RpcPool::new(urls)?,
+            next_id: Cell::new(1),
+        })
+    }
+
+    pub fn url(&self) -> &str {
+        self.pool.primary_url()
+    }
+
+    pub fn pool(&self) -> &RpcPool {
+        &self.pool
+    }
+
+    pub fn call<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T, Box<dyn std::error::Error>> {
+        self.call_optional(method, params)?
+            .ok_or_else(|| format!("{} returned no result", method).into())
+    }
+
+    /// Like `call`, but a null result (e.g. a pending receipt) is Ok(None).
+    pub fn call_optional<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<Option<T>, Box<dyn std::error::Error>> {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": params,
+        });
+
+        let resp: RpcResponse<T> = serde_json::from_value(self.pool.send(&request)?)?;
+
+        if let Some(mut err) = resp.error {
+            err.method = method.to_string();
+            return Err(Box::new(err));
+        }
+        Ok(resp.result)
+    }
+
+    pub fn block_number(&self) -> Result<u64, Box<dyn std::error::Error>> {
+        let hex: U256 = self.call("eth_blockNumber", json!([]))?;
+        Ok(hex.as_u64())
+    }
+
+    pub fn get_logs(&self, address: Address, topics: &[Option<H256>], from_block: u64, to_block: u64)
+        -> Result<Vec<Log>, Box<dyn std::error::Error>> {
+        let filter = json!({
+            "address": format!("{:?}", address),
+            "topics": topics,
+            "fromBlock": format!("0x{:x}", from_block),
+            "toBlock": format!("0x{:x}", to_block),
+        });
+        self.call("eth_getLogs", json!([filter]))
+    }
+
+    pub fn eth_call(&self, to: Address, data: &[u8]) -> Result<Bytes, Box<dyn std::error::Error>> {
+        let tx = json!({
+            "to": format!("{:?}", to),
+            "data": format!("0x{}", hex::encode(data)),
+        });
+        self.call("eth_call", json!([tx, "latest"]))
+    }
+}
+
+/// Left-pad an address into a 32-byte topic/word.
+pub fn address_topic(address: Address) -> H256 {
+    let mut word = [0u8; 32];
+    word[12..].copy_from_slice(address.as_bytes());
+    H256::from(word)
+}
+
+/// First four bytes of keccak256 of the function signature.
+pub fn function_selector(signature: &str) -> [u8; 4] {
+    let hash = keccak256(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Selector followed by 32-byte words, i.e. ABI encoding for static arguments.
+pub fn encode_call(signature: &str, words: &[[u8; 32]]) -> Vec<u8> {
+    let mut data = function_selector(signature).to_vec();
+    for word in words {
+        data.extend_from_slice(word);
+    }
+    data
+}
+
+pub fn address_word(address: Address) -> [u8; 32] {
+    address_topic(address).0
+}
+
+pub fn uint_word(value: U256) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    value.to_big_endian(&mut word);
+    word
+}
+
+pub fn decode_uint(data: &[u8]) -> Result<U256, Box<dyn std::error::Error>> {
+    if data.len() < 32 {
+        return Err(format!("expected a 32-byte word, got {} bytes", data.len()).into());
+    }
+    Ok(U256::from_big_endian(&data[..32]))
+}
+
+pub fn decode_address(data: &[u8]) -> Result<Address, Box<dyn std::error::Error>> {
+    if data.len() < 32 {
+        return Err(format!("expected a 32-byte word, got {} bytes", data.len()).into());
+    }
+    Ok(Address::from_slice(&data[12..32]))
+}
+
+pub fn erc20_balance_of(rpc: &RpcClient, token: Address, owner: Address) -> Result<U256, Box<dyn std::error::Error>> {
+    let data = encode_call("balanceOf(address)", &[address_word(owner)]);
+    decode_uint(&rpc.eth_call(token, &data)?)
+}
+
+/// ERC-1155 `balanceOf(address,uint256)` — conditional token positions on the CTF contract.
+pub fn erc1155_balance_of(rpc: &RpcClient, contract: Address, owner: Address, token_id: U256) -> Result<U256, Box<dyn std::error::Error>> {
+    let data = encode_call("balanceOf(address,uint256)", &[address_word(owner), uint_word(token_id)]);
+    decode_uint(&rpc.eth_call(contract, &data)?)
+}
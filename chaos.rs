@@ -0,0 +1,129 @@
+//! Fault injection for resilience testing. With BOT_CHAOS set, every API
+//! request rolls for added latency and at most one fault: a timeout, a 5xx
+//! that never reaches the exchange, or a real response whose body arrives
+//! mangled. The rolls come from a seeded generator, so a run that went
+//! wrong can be replayed exactly.
+//!
+//!   BOT_CHAOS="latency=0.3,latency_ms=800,timeout=0.05,5xx=0.1,malformed=0.05,seed=7,paths=/book|/order"
+//!
+//! Rates are probabilities per request. `paths` limits injection to
+//! requests whose path starts with one of the listed prefixes; without it
+//! every request is fair game.
+
+use std::cell::Cell;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    // Hang for the timeout, then fail without a response
+    Timeout,
+    // 503 without the request being sent
+    ServerError,
+    // Send the request, then cut and corrupt the response body
+    Malformed,
+}
+
+/// What to do to one request.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Injection {
+    pub delay: Option<Duration>,
+    pub fault: Option<Fault>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chaos {
+    pub latency: f64,
+    pub latency_by: Duration,
+    pub timeout: f64,
+    pub server_error: f64,
+    pub malformed: f64,
+    pub seed: u64,
+    pub paths: Vec<String>,
+    state: Cell<u64>,
+}
+
+impl Chaos {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut chaos = Self {
+            latency: 0.0,
+            latency_by: Duration::from_millis(500),
+            timeout: 0.0,
+            server_error: 0.0,
+            malformed: 0.0,
+            seed: 1,
+            paths: Vec::new(),
+            state: Cell::new(0),
+        };
+        for part in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (key, value) = part.split_once('=').ok_or_else(|| format!("expected key=value, got '{}'", part))?;
+            let rate = || value.parse::<f64>().ok().filter(|r| (0.0..=1.0).contains(r))
+                .ok_or_else(|| format!("{} rate '{}' must be between 0 and 1", key, value));
+            match key {
+                "latency" => chaos.latency = rate()?,
+                "timeout" => chaos.timeout = rate()?,
+                "5xx" => chaos.server_error = rate()?,
+                "malformed" => chaos.malformed = rate()?,
+                "latency_ms" => {
+                    let ms = value.parse::<u64>().map_err(|_| format!("latency_ms '{}' must be milliseconds", value))?;
+                    chaos.latency_by = Duration::from_millis(ms);
+                }
+                "seed" => chaos.seed = value.parse().map_err(|_| format!("seed '{}' must be a number", value))?,
+                "paths" => chaos.paths = value.split('|').filter(|p| !p.is_empty()).map(str::to_string).collect(),
+                _ => return Err(format!("unknown chaos setting '{}'", key)),
+            }
+        }
+        if chaos.timeout + chaos.server_error + chaos.malformed > 1.0 {
+            return Err("timeout, 5xx and malformed rates add up to more than 1".to_string());
+        }
+        // xorshift gets stuck on zero
+        chaos.state.set(chaos.seed.max(1));
+        Ok(chaos)
+    }
+
+    /// Roll for one request to `path`.
+    pub fn roll(&self, path: &str) -> Injection {
+        if !self.paths.is_empty() && !self.paths.iter().any(|p| path.starts_with(p.as_str())) {
+            return Injection::default();
+        }
+        let delay = (self.next() < self.latency).then_some(self.latency_by);
+        let r = self.next();
+        let fault = if r < self.timeout {
+            Some(Fault::Timeout)
+        } else if r < self.timeout + self.server_error {
+            Some(Fault::ServerError)
+        } else if r < self.timeout + self.server_error + self.malformed {
+            Some(Fault::Malformed)
+        } else {
+            None
+        };
+        Injection { delay, fault }
+    }
+
+    // Uniform in [0, 1)
+    fn next(&self) -> f64 {
+        let mut x = self.state.get();
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state.set(x);
+        (x >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// The first half of `body` followed by bytes no JSON parser accepts.
+pub fn mangle(body: &[u8]) -> Vec<u8> {
+    let mut mangled = body[..body.len() / 2].to_vec();
+    mangled.extend_from_slice(b"\x00<!-- chaos -->");
+    mangled
+}
+
+impl std::fmt::Display for Chaos {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "latency={} latency_ms={} timeout={} 5xx={} malformed={} seed={}",
+            self.latency, self.latency_by.as_millis(), self.timeout, self.server_error, self.malformed, self.seed)?;
+        if !self.paths.is_empty() {
+            write!(f, " paths={}", self.paths.join("|"))?;
+        }
+        Ok(())
+    }
+}
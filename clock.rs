@@ -0,0 +1,101 @@
+use std::cell::Cell;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Where the trading loop gets the time and how it waits. `SystemClock` is
+/// the real thing; `SimClock` starts at a chosen instant and its sleeps
+/// return immediately after moving time forward, so a whole market window
+/// (ENTRY_TIMEOUT, the 15-minute rollover, ...) plays out in milliseconds.
+pub trait Clock {
+    /// Wall-clock seconds since the unix epoch.
+    fn unix_secs_f64(&self) -> f64;
+
+    /// Monotonic time since the clock was created; for measuring intervals.
+    fn elapsed(&self) -> Duration;
+
+    fn sleep(&self, duration: Duration);
+
+    /// Simulated clocks have no relation to the exchange's `/time`.
+    fn is_simulated(&self) -> bool {
+        false
+    }
+
+    fn now_secs(&self) -> u64 {
+        self.unix_secs_f64() as u64
+    }
+}
+
+pub struct SystemClock {
+    origin: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        Self { origin: Instant::now() }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn unix_secs_f64(&self) -> f64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs_f64()).unwrap_or(0.0)
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.origin.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+/// Deterministic clock: time only moves when someone sleeps or advances it.
+pub struct SimClock {
+    start: f64,
+    elapsed: Cell<Duration>,
+}
+
+impl SimClock {
+    pub fn new(start_unix_secs: f64) -> Self {
+        Self { start: start_unix_secs, elapsed: Cell::new(Duration::ZERO) }
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.elapsed.set(self.elapsed.get() + duration);
+    }
+}
+
+impl Clock for SimClock {
+    fn unix_secs_f64(&self) -> f64 {
+        self.start + self.elapsed.get().as_secs_f64()
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.elapsed.get()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration);
+    }
+
+    fn is_simulated(&self) -> bool {
+        true
+    }
+}
+
+/// BOT_SIM_START=<unix seconds> switches to a simulated clock starting then.
+pub fn from_env() -> Result<Box<dyn Clock>, Box<dyn std::error::Error>> {
+    match std::env::var("BOT_SIM_START") {
+        Ok(v) => {
+            let start = v.parse::<f64>().map_err(|_| format!("Invalid BOT_SIM_START '{}'", v))?;
+            Ok(Box::new(SimClock::new(start)))
+        }
+        Err(_) => Ok(Box::new(SystemClock::new())),
+    }
+}
@@ -0,0 +1,75 @@
+use ethers::types::{Address, U256};
+
+use crate::chain::{self, RpcClient};
+use crate::network::NetworkProfile;
+
+// Bridged USDC (PoS) — what Polymarket's exchange has always settled in
+pub const USDC_E_ADDRESS: &str = "0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174";
+// Circle-issued native USDC on Polygon
+pub const NATIVE_USDC_ADDRESS: &str = "0x3c499c542cEF5E3811e1192ce70d8cC03d5c3359";
+
+// Ignore dust when deciding funds are "in the wrong token"
+const MISPLACED_FUNDS_THRESHOLD: f64 = 1.0;
+
+/// Optional integration that moves funds from the wrong USDC variant into
+/// the exchange collateral (DEX swap, bridge, manual prompt, ...).
+pub trait CollateralSwapHook {
+    fn swap_to_collateral(&self, from_token: Address, to_token: Address, amount: U256) -> Result<(), Box<dyn std::error::Error>>;
+}
+
+#[derive(Debug, Clone)]
+pub struct CollateralReport {
+    pub collateral_token: Address,
+    pub collateral_balance: f64,
+    pub other_token: Address,
+    pub other_balance_raw: U256,
+    pub other_balance: f64,
+}
+
+impl CollateralReport {
+    pub fn has_misplaced_funds(&self) -> bool {
+        self.other_balance >= MISPLACED_FUNDS_THRESHOLD
+    }
+}
+
+/// Ask the exchange which token it settles in (`getCollateral()`), falling
+/// back to the network profile's collateral if the call isn't available.
+pub fn exchange_collateral(rpc: &RpcClient, network: &NetworkProfile) -> Address {
+    let data = chain::encode_call("getCollateral()", &[]);
+    rpc.eth_call(network.exchange, &data)
+        .ok()
+        .and_then(|raw| chain::decode_address(&raw).ok())
+        .filter(|a| !a.is_zero())
+        .unwrap_or(network.collateral)
+}
+
+pub fn check_collateral(rpc: &RpcClient, network: &NetworkProfile, owner: Address) -> Result<CollateralReport, Box<dyn std::error::Error>> {
+    let usdc_e: Address = USDC_E_ADDRESS.parse()?;
+    let native: Address = NATIVE_USDC_ADDRESS.parse()?;
+
+    let collateral_token = exchange_collateral(rpc, network);
+    let other_token = if collateral_token == native { usdc_e } else { native };
+
+    let collateral_raw = chain::erc20_balance_of(rpc, collateral_token, owner)?;
+    let other_raw = chain::erc20_balance_of(rpc, other_token, owner)?;
+
+    Ok(CollateralReport {
+        collateral_token,
+        collateral_balance: to_usdc(collateral_raw),
+        other_token,
+        other_balance_raw: other_raw,
+        other_balance: to_usdc(other_raw),
+    })
+}
+
+pub fn token_label(token: Address) -> &'static str {
+    match format!("{:?}", token).as_str() {
+        t if t.eq_ignore_ascii_case(USDC_E_ADDRESS) => "USDC.e",
+        t if t.eq_ignore_ascii_case(NATIVE_USDC_ADDRESS) => "USDC",
+        _ => "collateral",
+    }
+}
+
+fn to_usdc(raw: U256) -> f64 {
+    raw.as_u128() as f64 / 1_000_000.0
+}
@@ -0,0 +1,538 @@
+//! Settings that used to be compile-time constants, read from a TOML file:
+//! BOT_CONFIG names it, else `config.toml` in the working directory is used
+//! when present. Every key is optional and defaults to the value the bot
+//! always shipped with; unknown keys are errors, so a typo can't silently
+//! leave the default in place.
+//!
+//!   dry_run = false              # log orders instead of submitting them
+//!   paper = false                # fill orders against the live book, never submit
+//!   log_format = "text"          # "json" adds JSON lines on stderr; see `json_log`
+//!
+//!   [strategy]
+//!   trade_side = "BOTH"          # YES, NO or BOTH
+//!   entry_price = 0.96
+//!   stop_loss_price = 0.89
+//!   position_size = 5
+//!   sizing = { balance_pct = 5.0, min_shares = 1, max_shares = 50 }   # unset: position_size
+//!   market_window = 240          # seconds before close trading starts
+//!   entry_timeout = 210
+//!   abort_ask_price = 0.99
+//!   tie_break = ["bid"]
+//!   entry_exec = { style = "fok", cross_at = 0.75 }   # fok, passive or limit
+//!   partial_fill = "requote"     # remainder after a fill wait: accept, cancel or requote
+//!   trailing_stop = { trail = 0.03, activation = 0.01 }   # unset: hold to resolution
+//!   exit_before_close = 0        # sell this many seconds before close; 0 = hold
+//!   tranches = [{ price = 0.95, fraction = 0.5 }, { price = 0.97, fraction = 0.5 }]
+//!                                # scale in with a bid per tranche instead of entry_exec
+//!
+//!   [timing]                     # all in seconds
+//!   notification_poll_interval = 10
+//!   exchange_status_interval = 15
+//!   market_cache_interval = 3600 # re-sync the CLOB /markets cache; 0 = never
+//!
+//!   [risk]
+//!   low_balance_threshold = 25.0
+//!   liquidate_on_shutdown = false  # sell held positions on SIGINT/SIGTERM
+//!   liquidation_slippage = 0.03  # exits and liquidations sell down this far under the bid
+//!   liquidation_rungs = 3        # in this many FAK steps; 1 sweeps straight there
+//!   max_open_positions = 0       # across all markets traded at once; 0 = no cap
+//!
+//!   [market]
+//!   asset = "eth"
+//!   assets = ["eth", "btc"]      # several at once, one thread each; wins over asset
+//!   cadence = "15m"              # cycle length: 15m, 1h, 4h, 1d, ...
+//!   slug = "{asset}-updown-{cadence}-{ts}"   # see market_schedule
+//!   discover = false             # find markets on Gamma by close time instead
+//!   tag = "crypto"               # Gamma tag_slug filter for discovery
+//!   series_id = "10192"          # Gamma series_id filter for discovery
+//!
+//!   [assets.btc]                 # [strategy] keys that differ for one asset
+//!   entry_price = 0.95
+//!   position_size = 3
+//!
+//! Any key can also be set from the environment as BOT__<SECTION>__<KEY>,
+//! e.g. BOT__STRATEGY__ENTRY_PRICE=0.94 or BOT__ASSETS__BTC__POSITION_SIZE=3
+//! (BOT__DRY_RUN for the top-level keys). The value is read as TOML, else as
+//! a string, and replaces what the file says, as if written there.
+//!
+//! BOT_TIE_BREAK and BOT_ENTRY_EXEC still override the file, as do the
+//! `--asset`, `--entry-price`, `--size`, `--dry-run` and `--log-format`
+//! command-line flags, which also win over BOT__ variables. `--entry-price`
+//! and `--size` win over `[assets.*]`.
+//!
+//! The signing key never goes here: a file holding anything shaped like a
+//! private key is refused outright.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::discovery::{GammaFilters, MarketFinder};
+use crate::market_schedule::{self, MarketSchedule};
+use crate::strategy::{BalanceSizing, EntryExecution, PartialFillAction, StrategyParams, TieBreak, TradeSide, TrailingStop, Tranche};
+
+pub const DEFAULT_PATH: &str = "config.toml";
+// Variables under this prefix set one config key each; see `parse_with_env`
+pub const ENV_PREFIX: &str = "BOT__";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StrategyConfig {
+    pub trade_side: TradeSide,
+    pub entry_price: f64,
+    pub stop_loss_price: f64,
+    // Seconds a stop must hold before acting on it
+    pub sustain_time: u64,
+    pub position_size: u32,
+    // Size entries off the USDC balance instead; None buys position_size
+    pub sizing: Option<BalanceSizing>,
+    pub market_window: u64,
+    pub entry_timeout: u64,
+    pub abort_ask_price: f64,
+    pub tie_break: TieBreak,
+    pub entry_exec: EntryExecution,
+    // What happens to the rest of an order that filled only partly
+    pub partial_fill: PartialFillAction,
+    // Watch the position after entry and sell on a stop that trails the
+    // bid up from stop_loss_price; None holds to resolution
+    pub trailing_stop: Option<TrailingStop>,
+    // Sell at the bid this many seconds before close; 0 holds to resolution
+    pub exit_before_close: u64,
+    // Split the entry into bids at several prices; empty works one order
+    // with entry_exec
+    pub tranches: Vec<Tranche>,
+}
+
+impl Default for StrategyConfig {
+    fn default() -> Self {
+        Self {
+            trade_side: TradeSide::Both,
+            entry_price: 0.96,
+            stop_loss_price: 0.89,
+            sustain_time: 3,
+            position_size: 5,
+            sizing: None,
+            market_window: 240,
+            entry_timeout: 210,
+            abort_ask_price: 0.99,
+            tie_break: TieBreak::default(),
+            entry_exec: EntryExecution::default(),
+            partial_fill: PartialFillAction::default(),
+            trailing_stop: None,
+            exit_before_close: 0,
+            tranches: Vec::new(),
+        }
+    }
+}
+
+impl StrategyConfig {
+    pub fn params(&self) -> StrategyParams {
+        StrategyParams {
+            trade_side: self.trade_side,
+            entry_price: self.entry_price,
+            abort_ask_price: self.abort_ask_price,
+            position_size: self.position_size,
+            market_window: self.market_window,
+            entry_timeout: self.entry_timeout,
+            tie_break: self.tie_break.clone(),
+            entry_exec: self.entry_exec,
+        }
+    }
+}
+
+/// `[assets.<symbol>]`: strategy settings for one asset. Unset keys keep
+/// the `[strategy]` value.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StrategyOverrides {
+    pub trade_side: Option<TradeSide>,
+    pub entry_price: Option<f64>,
+    pub stop_loss_price: Option<f64>,
+    pub sustain_time: Option<u64>,
+    pub position_size: Option<u32>,
+    pub sizing: Option<BalanceSizing>,
+    pub market_window: Option<u64>,
+    pub entry_timeout: Option<u64>,
+    pub abort_ask_price: Option<f64>,
+    pub tie_break: Option<TieBreak>,
+    pub entry_exec: Option<EntryExecution>,
+    pub partial_fill: Option<PartialFillAction>,
+    pub trailing_stop: Option<TrailingStop>,
+    pub exit_before_close: Option<u64>,
+    pub tranches: Option<Vec<Tranche>>,
+}
+
+impl StrategyOverrides {
+    pub fn apply(&self, base: &StrategyConfig) -> StrategyConfig {
+        StrategyConfig {
+            trade_side: self.trade_side.unwrap_or(base.trade_side),
+            entry_price: self.entry_price.unwrap_or(base.entry_price),
+            stop_loss_price: self.stop_loss_price.unwrap_or(base.stop_loss_price),
+            sustain_time: self.sustain_time.unwrap_or(base.sustain_time),
+            position_size: self.position_size.unwrap_or(base.position_size),
+            sizing: self.sizing.or(base.sizing),
+            market_window: self.market_window.unwrap_or(base.market_window),
+            entry_timeout: self.entry_timeout.unwrap_or(base.entry_timeout),
+            abort_ask_price: self.abort_ask_price.unwrap_or(base.abort_ask_price),
+            tie_break: self.tie_break.clone().unwrap_or_else(|| base.tie_break.clone()),
+            entry_exec: self.entry_exec.unwrap_or(base.entry_exec),
+            partial_fill: self.partial_fill.unwrap_or(base.partial_fill),
+            trailing_stop: self.trailing_stop.or(base.trailing_stop),
+            exit_before_close: self.exit_before_close.unwrap_or(base.exit_before_close),
+            tranches: self.tranches.clone().unwrap_or_else(|| base.tranches.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MarketConfig {
+    // Symbol the up/down market family is named after: eth, btc, sol, ...
+    pub asset: String,
+    // Assets to trade side by side instead of just `asset`
+    pub assets: Vec<String>,
+    pub cadence: String,
+    // Slug template; placeholders in market_schedule
+    pub slug: String,
+    // Query Gamma for the market closing with each cycle instead of `slug`
+    pub discover: bool,
+    pub tag: String,
+    pub series_id: String,
+}
+
+impl Default for MarketConfig {
+    fn default() -> Self {
+        Self {
+            asset: "eth".to_string(),
+            assets: Vec::new(),
+            cadence: "15m".to_string(),
+            slug: market_schedule::DEFAULT_TEMPLATE.to_string(),
+            discover: false,
+            tag: String::new(),
+            series_id: String::new(),
+        }
+    }
+}
+
+impl MarketConfig {
+    pub fn schedule(&self) -> Result<MarketSchedule, String> {
+        MarketSchedule::new(&self.asset, &self.slug, market_schedule::parse_cadence(&self.cadence)?)
+    }
+
+    pub fn finder(&self) -> Result<MarketFinder, String> {
+        let schedule = self.schedule()?;
+        if !self.discover {
+            return Ok(MarketFinder::by_slug(schedule));
+        }
+        let filters = GammaFilters { tag_slug: self.tag.trim().to_string(), series_id: self.series_id.trim().to_string() };
+        if filters.is_empty() {
+            return Err("discover needs a tag or series_id, or it would trade any market closing on time".to_string());
+        }
+        Ok(MarketFinder::discover(schedule, filters))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TimingConfig {
+    pub notification_poll_interval: u64,
+    pub balance_cache_ttl: u64,
+    pub http_timeout: u64,
+    // Local clock this far off the exchange's gets a warning
+    pub max_clock_skew_secs: f64,
+    pub clock_resync_interval: u64,
+    pub resolution_poll_interval: u64,
+    pub exchange_status_interval: u64,
+    // How long a traded market is remembered across restarts
+    pub traded_markets_ttl: u64,
+    // Incremental sync of the CLOB /markets cache; 0 never syncs
+    pub market_cache_interval: u64,
+}
+
+impl Default for TimingConfig {
+    fn default() -> Self {
+        Self {
+            notification_poll_interval: 10,
+            balance_cache_ttl: 5,
+            http_timeout: 30,
+            max_clock_skew_secs: 5.0,
+            clock_resync_interval: 600,
+            resolution_poll_interval: 60,
+            exchange_status_interval: 15,
+            traded_markets_ttl: 86_400,
+            market_cache_interval: 3600,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RiskConfig {
+    // Below this much USDC, entries shrink to what the balance can cover
+    pub low_balance_threshold: f64,
+    // Sell held positions into the bid on SIGINT/SIGTERM instead of leaving them
+    pub liquidate_on_shutdown: bool,
+    // Worst price an exit or liquidation takes, under the best bid it started from
+    pub liquidation_slippage: f64,
+    // FAK sells stepping down to that price, each sweeping the bids above it
+    pub liquidation_rungs: u32,
+    // Positions held at once over every market traded concurrently; 0 is no cap
+    pub max_open_positions: usize,
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self {
+            low_balance_threshold: 25.0,
+            liquidate_on_shutdown: false,
+            liquidation_slippage: 0.03,
+            liquidation_rungs: 3,
+            max_open_positions: 0,
+        }
+    }
+}
+
+/// What the console gets besides the usual text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogOutput {
+    #[default]
+    Text,
+    // Text on stdout plus one JSON object per event on stderr
+    Json,
+}
+
+impl LogOutput {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "text" => Some(Self::Text),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Json => "json",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub strategy: StrategyConfig,
+    pub timing: TimingConfig,
+    pub risk: RiskConfig,
+    pub market: MarketConfig,
+    // Strategy overrides by asset symbol; see `select_asset`
+    pub assets: BTreeMap<String, StrategyOverrides>,
+    // Orders are printed, never submitted
+    pub dry_run: bool,
+    // Orders fill against the live book instead of being submitted
+    pub paper: bool,
+    pub log_format: LogOutput,
+    // File this was read from; None for the built-in defaults
+    #[serde(skip)]
+    pub source: Option<String>,
+}
+
+impl Config {
+    /// The file BOT_CONFIG names, else `config.toml` if there is one, else
+    /// the defaults, with BOT__ variables applied. Not validated; see
+    /// `validate`.
+    pub fn from_env() -> Result<Self, String> {
+        match std::env::var("BOT_CONFIG") {
+            Ok(path) => Self::load(&path),
+            Err(_) if Path::new(DEFAULT_PATH).exists() => Self::load(DEFAULT_PATH),
+            Err(_) => Self::parse_with_env("", std::env::vars()),
+        }
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("Cannot read config {}: {}", path, e))?;
+        if let Some(line) = find_private_key(&text) {
+            return Err(format!("{} line {} looks like a private key; keys go in BOT_KEYSTORE or PRIVATE_KEY, never the config", path, line));
+        }
+        Self::parse(&text).map_err(|e| format!("Invalid config {}: {}", path, e))?;
+        let mut config = Self::parse_with_env(&text, std::env::vars())?;
+        config.source = Some(path.to_string());
+        Ok(config)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        toml::from_str(text).map_err(|e| e.to_string().trim_end().to_string())
+    }
+
+    /// `text` with every BOT__<SECTION>__<KEY> variable in `vars` set on top.
+    /// Other variables are ignored; a variable naming an unknown key, or a
+    /// value the key can't take, is an error naming the variable.
+    pub fn parse_with_env(text: &str, vars: impl IntoIterator<Item = (String, String)>) -> Result<Self, String> {
+        let config = Self::parse(text)?;
+        let mut vars: Vec<(String, String)> = vars.into_iter().filter(|(name, _)| name.starts_with(ENV_PREFIX)).collect();
+        if vars.is_empty() {
+            return Ok(config);
+        }
+        vars.sort();
+        let mut table: toml::Table = toml::from_str(text).map_err(|e| e.to_string().trim_end().to_string())?;
+        for (name, raw) in vars {
+            let path: Vec<String> = name[ENV_PREFIX.len()..].split("__").map(str::to_ascii_lowercase).collect();
+            if path.iter().any(String::is_empty) {
+                return Err(format!("{}: expected BOT__<SECTION>__<KEY>", name));
+            }
+            // "0.94" is a number and "[1, 2]" an array, but a string key
+            // still takes "123" or "eth" as written
+            let parsed = format!("v = {}", raw).parse::<toml::Table>().ok().and_then(|mut t| t.remove("v"));
+            let mut error = String::new();
+            for value in parsed.into_iter().chain([toml::Value::String(raw.clone())]) {
+                let mut candidate = table.clone();
+                set_key(&mut candidate, &path, value).map_err(|e| format!("{}: {}", name, e))?;
+                match toml::Value::Table(candidate.clone()).try_into::<Self>() {
+                    Ok(_) => {
+                        table = candidate;
+                        error.clear();
+                        break;
+                    }
+                    Err(e) => error = e.to_string().trim_end().to_string(),
+                }
+            }
+            if !error.is_empty() {
+                return Err(format!("{}: {}", name, error));
+            }
+        }
+        toml::Value::Table(table).try_into().map_err(|e| e.to_string().trim_end().to_string())
+    }
+
+    /// Every asset this configuration trades: `market.assets`, or just
+    /// `market.asset` when that's empty.
+    pub fn traded_assets(&self) -> Vec<String> {
+        if self.market.assets.is_empty() {
+            vec![self.market.asset.clone()]
+        } else {
+            self.market.assets.clone()
+        }
+    }
+
+    /// Trade `asset` (or keep `market.asset` when None), with its
+    /// `[assets.<asset>]` overrides applied on top of `[strategy]`. Call once,
+    /// after the file is read and before any flag that sets strategy keys.
+    pub fn select_asset(&mut self, asset: Option<&str>) {
+        if let Some(asset) = asset {
+            self.market.asset = asset.trim().to_ascii_lowercase();
+        }
+        if let Some(overrides) = self.assets.get(&self.market.asset) {
+            self.strategy = overrides.apply(&self.strategy);
+        }
+    }
+
+    /// Every problem that would make the bot misbehave, one message each;
+    /// empty when the config is usable.
+    pub fn validate(&self) -> Vec<String> {
+        let s = &self.strategy;
+        let duration = self.market.schedule().map(|m| m.cadence).unwrap_or(market_schedule::DEFAULT_CADENCE);
+        let mut errors = s.params().validate_within(duration);
+        if let Err(e) = self.market.finder() {
+            errors.push(format!("market: {}", e));
+        }
+        if !(s.stop_loss_price > 0.0 && s.stop_loss_price < s.entry_price) {
+            errors.push(format!("stop_loss_price {} must be above 0 and below entry_price {}", s.stop_loss_price, s.entry_price));
+        }
+        if let Some(sizing) = s.sizing {
+            if !(sizing.balance_pct > 0.0 && sizing.balance_pct <= 100.0) {
+                errors.push(format!("sizing.balance_pct {} must be above 0 and at most 100", sizing.balance_pct));
+            }
+            if sizing.max_shares == 0 || sizing.min_shares > sizing.max_shares {
+                errors.push(format!("sizing.max_shares {} must be at least 1 and at least min_shares {}", sizing.max_shares, sizing.min_shares));
+            }
+        }
+        if let Some(t) = s.trailing_stop {
+            if !(t.trail > 0.0 && t.trail < 1.0) {
+                errors.push(format!("trailing_stop.trail {} must be between 0 and 1", t.trail));
+            }
+            if !(t.activation >= 0.0 && t.activation < 1.0) {
+                errors.push(format!("trailing_stop.activation {} must be at least 0 and below 1", t.activation));
+            }
+        }
+        if s.exit_before_close > 0 && s.exit_before_close >= s.market_window {
+            errors.push(format!("exit_before_close {}s must be under market_window {}s, or every entry is sold at once",
+                s.exit_before_close, s.market_window));
+        }
+        for (i, t) in s.tranches.iter().enumerate() {
+            if !(t.price > 0.0 && t.price <= s.abort_ask_price) {
+                errors.push(format!("tranches[{}].price {} must be above 0 and at most abort_ask_price {}", i, t.price, s.abort_ask_price));
+            }
+            if !(t.fraction > 0.0 && t.fraction <= 1.0) {
+                errors.push(format!("tranches[{}].fraction {} must be above 0 and at most 1", i, t.fraction));
+            }
+        }
+        let fractions: f64 = s.tranches.iter().map(|t| t.fraction).sum();
+        if !s.tranches.is_empty() && (fractions - 1.0).abs() > 1e-6 {
+            errors.push(format!("tranches fractions add up to {}, not 1", fractions));
+        }
+        if !(s.entry_exec.cross_at >= 0.0 && s.entry_exec.cross_at <= 1.0) {
+            errors.push(format!("entry_exec.cross_at {} must be between 0 and 1", s.entry_exec.cross_at));
+        }
+        if s.tie_break.0.is_empty() {
+            errors.push("tie_break needs at least one criterion".to_string());
+        }
+
+        let t = &self.timing;
+        for (name, value) in [
+            ("notification_poll_interval", t.notification_poll_interval),
+            ("http_timeout", t.http_timeout),
+            ("clock_resync_interval", t.clock_resync_interval),
+            ("resolution_poll_interval", t.resolution_poll_interval),
+            ("exchange_status_interval", t.exchange_status_interval),
+        ] {
+            if value == 0 {
+                errors.push(format!("timing.{} must be at least 1 second", name));
+            }
+        }
+        if t.max_clock_skew_secs.is_nan() || t.max_clock_skew_secs <= 0.0 {
+            errors.push(format!("timing.max_clock_skew_secs {} must be above 0", t.max_clock_skew_secs));
+        }
+        if self.risk.low_balance_threshold.is_nan() || self.risk.low_balance_threshold < 0.0 {
+            errors.push(format!("risk.low_balance_threshold {} must not be negative", self.risk.low_balance_threshold));
+        }
+        if !(self.risk.liquidation_slippage >= 0.0 && self.risk.liquidation_slippage < 1.0) {
+            errors.push(format!("risk.liquidation_slippage {} must be at least 0 and below 1", self.risk.liquidation_slippage));
+        }
+        if self.risk.liquidation_rungs == 0 {
+            errors.push("risk.liquidation_rungs must be at least 1".to_string());
+        }
+        if self.dry_run && self.paper {
+            errors.push("dry_run and paper are exclusive".to_string());
+        }
+        for asset in std::iter::once(&self.market.asset).chain(&self.market.assets).chain(self.assets.keys()) {
+            if asset.is_empty() || !asset.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit()) {
+                errors.push(format!("asset '{}' must be a lowercase symbol such as eth or btc", asset));
+            }
+        }
+        errors
+    }
+}
+
+// Sets `path` (section names, then the key) in `table`, creating sections
+fn set_key(table: &mut toml::Table, path: &[String], value: toml::Value) -> Result<(), String> {
+    let (key, sections) = path.split_last().ok_or("no key")?;
+    let mut table = table;
+    for section in sections {
+        let entry = table.entry(section.clone()).or_insert_with(|| toml::Value::Table(toml::Table::new()));
+        table = entry.as_table_mut().ok_or_else(|| format!("{} is a key, not a section", section))?;
+    }
+    table.insert(key.clone(), value);
+    Ok(())
+}
+
+/// 1-based line of the first 32-byte hex string in `text`, with or without
+/// 0x: the shape of a raw secp256k1 key. Transaction and condition hashes
+/// look the same, but neither belongs in a config file either.
+pub fn find_private_key(text: &str) -> Option<usize> {
+    text.lines().position(|line| {
+        line.split(|c: char| !c.is_ascii_alphanumeric())
+            .map(|word| word.strip_prefix("0x").unwrap_or(word))
+            .any(|word| word.len() == 64 && word.chars().all(|c| c.is_ascii_hexdigit()))
+    }).map(|i| i + 1)
+}
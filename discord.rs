@@ -0,0 +1,83 @@
+//! Discord channel for `notify`: subscribed notifications are posted to a
+//! channel webhook (Server Settings → Integrations → Webhooks) as one
+//! message each, through an `Outbox` so delivery never holds up the
+//! trading loop.
+//!
+//! Errors never include the webhook URL, which is its own credential.
+
+use std::time::Duration;
+
+use reqwest::blocking::Client;
+use serde_json::json;
+
+use crate::notify::{self, Notification, Notifier, Outbox};
+use crate::telegram;
+
+// Longest message Discord accepts, in characters
+pub const MAX_CONTENT: usize = 2000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscordConfig {
+    pub webhook_url: String,
+    // Notification events to deliver; "*" for all
+    pub events: Vec<String>,
+}
+
+impl DiscordConfig {
+    /// Subscribed to the same events as Telegram by default.
+    pub fn new(webhook_url: &str) -> Self {
+        Self { webhook_url: webhook_url.to_string(), events: telegram::DEFAULT_EVENTS.iter().map(|e| e.to_string()).collect() }
+    }
+
+    pub fn wants(&self, notification: &Notification) -> bool {
+        notify::subscribed(&self.events, notification)
+    }
+}
+
+/// The message: severity marker and bold title, then the body, cut to
+/// `MAX_CONTENT`.
+pub fn content(notification: &Notification) -> String {
+    let mut text = format!("{} **{}**", notification.severity.marker(), notification.title);
+    if !notification.body.is_empty() {
+        text.push('\n');
+        text.push_str(&notification.body);
+    }
+    match text.char_indices().nth(MAX_CONTENT) {
+        Some((cut, _)) => text[..cut].to_string(),
+        None => text,
+    }
+}
+
+pub struct DiscordNotifier {
+    config: DiscordConfig,
+    outbox: Outbox,
+}
+
+impl DiscordNotifier {
+    pub fn new(config: DiscordConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        let client = Client::builder().timeout(Duration::from_secs(10)).build()?;
+        let url = config.webhook_url.clone();
+        let outbox = Outbox::spawn("discord", move |message| {
+            let body = json!({ "content": message, "allowed_mentions": { "parse": [] } });
+            let resp = client.post(&url).json(&body).send().map_err(|e| e.without_url().to_string())?;
+            match resp.status() {
+                status if status.is_success() => Ok(()),
+                status => Err(format!("HTTP {}", status.as_u16())),
+            }
+        })?;
+        Ok(Self { config, outbox })
+    }
+}
+
+impl Notifier for DiscordNotifier {
+    fn name(&self) -> &str {
+        "discord"
+    }
+
+    fn send(&self, notification: &Notification) -> Result<(), Box<dyn std::error::Error>> {
+        if !self.config.wants(notification) {
+            return Ok(());
+        }
+        Ok(self.outbox.push(content(notification))?)
+    }
+}
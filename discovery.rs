@@ -0,0 +1,103 @@
+//! Finding each cycle's market on Gamma. By default the slug comes from the
+//! market schedule's template and is looked up directly. With `[market]
+//! discover = true` the bot asks `/events` instead for open events ending
+//! when the cycle does, narrowed by tag and series, and trades whichever
+//! one it gets back. A renamed slug then changes nothing. The filters are
+//! what pick the asset: a tag like `crypto` alone matches every coin's
+//! market, so give the asset's series too.
+//!
+//!   /events?closed=false&end_date_min=...&end_date_max=...&tag_slug=crypto&series_id=10192
+
+use chrono::DateTime;
+use serde_json::Value;
+
+use crate::market_schedule::MarketSchedule;
+use crate::responses::{self, MarketData};
+use crate::timestamps;
+
+// How far an event's end date may sit from the cycle's close
+pub const END_DATE_TOLERANCE: u64 = 60;
+
+/// `/events` filters for discovery; empty ones are left out of the query.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GammaFilters {
+    pub tag_slug: String,
+    pub series_id: String,
+}
+
+impl GammaFilters {
+    pub fn is_empty(&self) -> bool {
+        self.tag_slug.is_empty() && self.series_id.is_empty()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MarketFinder {
+    schedule: MarketSchedule,
+    // None looks markets up by their templated slug
+    discover: Option<GammaFilters>,
+}
+
+impl MarketFinder {
+    pub fn by_slug(schedule: MarketSchedule) -> Self {
+        Self { schedule, discover: None }
+    }
+
+    pub fn discover(schedule: MarketSchedule, filters: GammaFilters) -> Self {
+        Self { schedule, discover: Some(filters) }
+    }
+
+    pub fn is_discovering(&self) -> bool {
+        self.discover.is_some()
+    }
+
+    /// The `/events` path and query for the cycle starting at `start`.
+    pub fn query(&self, start: u64) -> String {
+        let Some(filters) = &self.discover else {
+            return format!("/events?slug={}", self.schedule.slug(start));
+        };
+        let end = self.schedule.cycle_end(start);
+        let mut query = format!("/events?closed=false&end_date_min={}&end_date_max={}&order=endDate&ascending=true&limit=20",
+            timestamps::rfc3339(end.saturating_sub(END_DATE_TOLERANCE)), timestamps::rfc3339(end + END_DATE_TOLERANCE));
+        if !filters.tag_slug.is_empty() {
+            query.push_str(&format!("&tag_slug={}", filters.tag_slug));
+        }
+        if !filters.series_id.is_empty() {
+            query.push_str(&format!("&series_id={}", filters.series_id));
+        }
+        query
+    }
+
+    /// The market in a `query(start)` response, if one is tradable yet.
+    /// Discovered events must end within `END_DATE_TOLERANCE` of the cycle's
+    /// close, whatever Gamma's own filtering let through.
+    pub fn parse(&self, start: u64, bytes: &[u8]) -> Result<Option<MarketData>, Box<dyn std::error::Error>> {
+        if self.discover.is_none() {
+            return responses::parse_market_event(&self.schedule.slug(start), bytes);
+        }
+        let end = self.schedule.cycle_end(start);
+        let events: Vec<Value> = serde_json::from_slice(bytes)?;
+        for event in &events {
+            let Some(slug) = event["slug"].as_str() else { continue };
+            let ends_at = event["endDate"].as_str()
+                .and_then(|d| DateTime::parse_from_rfc3339(d).ok())
+                .and_then(|d| u64::try_from(d.timestamp()).ok());
+            if ends_at.is_none_or(|ts| ts.abs_diff(end) > END_DATE_TOLERANCE) {
+                continue;
+            }
+            if let Some(market) = responses::market_from_event(slug, event)? {
+                return Ok(Some(market));
+            }
+        }
+        Ok(None)
+    }
+
+    /// What the cycle's market is called before it's found: the slug, or
+    /// the close time being searched for.
+    pub fn describe(&self, start: u64) -> String {
+        match self.discover {
+            None => self.schedule.slug(start),
+            Some(_) => format!("{} market closing {}", self.schedule.asset, timestamps::rfc3339(self.schedule.cycle_end(start))),
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! Deduplication and rate limiting for warnings. During an API incident the
+//! same "order book fetch error" comes back every poll; the first one is
+//! shown, identical ones inside the window are counted, and once the window
+//! ends a single "repeated N times" line stands in for them. A cap on
+//! distinct messages per window keeps varied errors (one per order id, say)
+//! from flooding the output the same way.
+
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Emit {
+    Show,
+    Suppress,
+}
+
+#[derive(Debug, Clone)]
+struct Seen {
+    window_start: u64,
+    // Identical messages swallowed since window_start
+    repeats: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct EventLog {
+    // Seconds an identical message stays quiet after being shown; 0 shows everything
+    window: u64,
+    // Distinct messages shown per window before the rest are dropped
+    max_per_window: u32,
+    seen: HashMap<String, Seen>,
+    window_start: u64,
+    shown_in_window: u32,
+    // Distinct messages dropped by the cap this window
+    dropped: u32,
+}
+
+impl EventLog {
+    pub fn new(window: u64, max_per_window: u32) -> Self {
+        Self { window, max_per_window, seen: HashMap::new(), window_start: 0, shown_in_window: 0, dropped: 0 }
+    }
+
+    /// BOT_LOG_DEDUP_SECS (default 60) and BOT_LOG_MAX_PER_WINDOW (default 20).
+    pub fn from_env() -> Result<Self, String> {
+        let var = |name: &str, default: u64| match std::env::var(name) {
+            Ok(v) => v.parse::<u64>().map_err(|_| format!("Invalid {} '{}'", name, v)),
+            Err(_) => Ok(default),
+        };
+        let max = var("BOT_LOG_MAX_PER_WINDOW", 20)?;
+        Ok(Self::new(var("BOT_LOG_DEDUP_SECS", 60)?, u32::try_from(max).unwrap_or(u32::MAX)))
+    }
+
+    /// Whether to print `message` now. Call `flush` first so summaries of
+    /// expired windows come out before whatever is shown next.
+    pub fn check(&mut self, message: &str, now: u64) -> Emit {
+        if self.window == 0 {
+            return Emit::Show;
+        }
+        if let Some(seen) = self.seen.get_mut(message) {
+            if now < seen.window_start + self.window {
+                seen.repeats += 1;
+                return Emit::Suppress;
+            }
+        }
+        if now >= self.window_start + self.window {
+            self.window_start = now;
+            self.shown_in_window = 0;
+        }
+        if self.shown_in_window >= self.max_per_window {
+            self.dropped += 1;
+            return Emit::Suppress;
+        }
+        self.shown_in_window += 1;
+        self.seen.insert(message.to_string(), Seen { window_start: now, repeats: 0 });
+        Emit::Show
+    }
+
+    /// Summary lines for windows that ended by `now`, oldest first. Messages
+    /// that never repeated are simply forgotten.
+    pub fn flush(&mut self, now: u64) -> Vec<String> {
+        let window = self.window;
+        let mut expired: Vec<(u64, String, u32)> = Vec::new();
+        self.seen.retain(|message, seen| {
+            if now < seen.window_start + window {
+                return true;
+            }
+            if seen.repeats > 0 {
+                expired.push((seen.window_start, message.clone(), seen.repeats));
+            }
+            false
+        });
+        expired.sort();
+
+        let mut lines: Vec<String> = expired.into_iter()
+            .map(|(_, message, repeats)| format!("… \"{}\" repeated {} more time{}", message.trim(), repeats, if repeats == 1 { "" } else { "s" }))
+            .collect();
+        if self.dropped > 0 && now >= self.window_start + window {
+            lines.push(format!("… {} other warning{} suppressed", self.dropped, if self.dropped == 1 { "" } else { "s" }));
+            self.dropped = 0;
+        }
+        lines
+    }
+}
@@ -0,0 +1,131 @@
+//! Live bot events over Server-Sent Events (feature `event-stream`).
+//! Dashboards connect to `GET /events` on BOT_EVENTS_ADDR and receive one
+//! `data:` line of JSON per `BotEvent`, wrapped with a sequence number and
+//! timestamp:
+//!
+//!   id: 42
+//!   event: fill
+//!   data: {"seq":42,"ts":1760000400,"type":"fill",...}
+//!
+//! `GET /metrics` answers with the latest snapshot handed to `set_metrics`
+//! (performance statistics, as JSON) and closes.
+//!
+//! Plain std networking: an accept thread registers clients and `publish`
+//! writes to each from the bot's thread. A client that can't keep up within
+//! WRITE_TIMEOUT is dropped rather than allowed to stall trading.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::bot_event::BotEvent;
+
+const WRITE_TIMEOUT: Duration = Duration::from_millis(200);
+
+pub struct EventServer {
+    addr: SocketAddr,
+    clients: Arc<Mutex<Vec<TcpStream>>>,
+    seq: Mutex<u64>,
+    metrics: Arc<Mutex<Value>>,
+}
+
+impl EventServer {
+    /// Bind `addr` and start accepting subscribers in the background.
+    pub fn start(addr: &str) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+        let clients = Arc::new(Mutex::new(Vec::new()));
+        let metrics = Arc::new(Mutex::new(Value::Object(Default::default())));
+
+        let registry = Arc::clone(&clients);
+        let snapshot = Arc::clone(&metrics);
+        thread::Builder::new().name("event-stream".to_string()).spawn(move || {
+            for stream in listener.incoming().flatten() {
+                if let Ok(Some(stream)) = handshake(stream, &snapshot) {
+                    registry.lock().unwrap_or_else(|e| e.into_inner()).push(stream);
+                }
+            }
+        })?;
+
+        Ok(Self { addr, clients, seq: Mutex::new(0), metrics })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    pub fn subscribers(&self) -> usize {
+        self.clients.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// Replace what `GET /metrics` returns.
+    pub fn set_metrics(&self, metrics: Value) {
+        *self.metrics.lock().unwrap_or_else(|e| e.into_inner()) = metrics;
+    }
+
+    /// Send `event` to every subscriber, dropping the ones that fail.
+    pub fn publish(&self, ts: u64, event: &BotEvent) {
+        let mut clients = self.clients.lock().unwrap_or_else(|e| e.into_inner());
+        if clients.is_empty() {
+            return;
+        }
+        let seq = {
+            let mut seq = self.seq.lock().unwrap_or_else(|e| e.into_inner());
+            *seq += 1;
+            *seq
+        };
+        let frame = frame(seq, ts, event);
+        clients.retain_mut(|client| client.write_all(frame.as_bytes()).and_then(|_| client.flush()).is_ok());
+    }
+}
+
+/// One SSE message for `event`.
+pub fn frame(seq: u64, ts: u64, event: &BotEvent) -> String {
+    let mut data = serde_json::to_value(event).unwrap_or(Value::Null);
+    let kind = data["type"].as_str().unwrap_or("event").to_string();
+    if let Value::Object(fields) = &mut data {
+        fields.insert("seq".to_string(), seq.into());
+        fields.insert("ts".to_string(), ts.into());
+    }
+    format!("id: {}\nevent: {}\ndata: {}\n\n", seq, kind, data)
+}
+
+/// Read the request head and answer with the event-stream preamble for
+/// `GET /events` (returning the stream to subscribe), the metrics snapshot
+/// for `GET /metrics`, or a 404.
+fn handshake(mut stream: TcpStream, metrics: &Mutex<Value>) -> io::Result<Option<TcpStream>> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && header.trim() != "" {
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next();
+    let path = parts.next().and_then(|t| t.split('?').next());
+    match (method, path) {
+        (Some("GET"), Some("/events")) => {}
+        (Some("GET"), Some("/metrics")) => {
+            let body = metrics.lock().unwrap_or_else(|e| e.into_inner()).to_string();
+            write!(stream, "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\nAccess-Control-Allow-Origin: *\r\n\r\n{}", body.len(), body)?;
+            stream.flush()?;
+            return Ok(None);
+        }
+        _ => {
+            stream.write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")?;
+            return Err(io::Error::new(io::ErrorKind::NotFound, "not /events"));
+        }
+    }
+
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\nAccess-Control-Allow-Origin: *\r\n\r\n: connected\n\n")?;
+    stream.flush()?;
+    Ok(Some(stream))
+}
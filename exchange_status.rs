@@ -0,0 +1,44 @@
+use crate::chain::{self, RpcClient};
+use crate::network::NetworkProfile;
+
+/// Rejection texts the CLOB returns when trading is halted, as opposed to
+/// FOK kills or thin books.
+const HALT_MARKERS: &[&str] = &["paused", "trading is currently disabled", "closed only", "cancel-only", "cancel only"];
+
+#[derive(Debug, Clone, Default)]
+pub struct ExchangeStatus {
+    pub exchange_paused: bool,
+    pub neg_risk_paused: bool,
+    // None when the CLOB health endpoint couldn't be reached
+    pub clob_ok: Option<bool>,
+}
+
+impl ExchangeStatus {
+    pub fn is_halted(&self) -> bool {
+        self.exchange_paused || self.clob_ok == Some(false)
+    }
+
+    pub fn describe(&self) -> String {
+        let clob = match self.clob_ok {
+            Some(true) => "ok",
+            Some(false) => "DOWN",
+            None => "unknown",
+        };
+        format!("exchange paused: {} | neg-risk exchange paused: {} | CLOB: {}",
+            self.exchange_paused, self.neg_risk_paused, clob)
+    }
+}
+
+/// `paused()` on both CTF exchanges. The bot only trades binary markets on
+/// the standard exchange, so neg-risk is reported but doesn't halt.
+pub fn check_paused(rpc: &RpcClient, network: &NetworkProfile) -> Result<(bool, bool), Box<dyn std::error::Error>> {
+    let data = chain::encode_call("paused()", &[]);
+    let exchange = chain::decode_uint(&rpc.eth_call(network.exchange, &data)?)?;
+    let neg_risk = chain::decode_uint(&rpc.eth_call(network.neg_risk_exchange, &data)?)?;
+    Ok((!exchange.is_zero(), !neg_risk.is_zero()))
+}
+
+pub fn is_halt_rejection(error_text: &str) -> bool {
+    let text = error_text.to_lowercase();
+    HALT_MARKERS.iter().any(|marker| text.contains(marker))
+}
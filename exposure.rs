@@ -0,0 +1,100 @@
+//! Net exposure per market. A YES and a NO share of the same market always
+//! pay exactly $1 between them, so matched pairs (complete sets) carry no
+//! risk and can be merged back into USDC; only what's left over on one
+//! side is directional. Risk checks and marks go through this view rather
+//! than summing tokens one by one.
+
+use crate::strategy::Outcome;
+
+// Share counts below this are rounding
+const EPSILON: f64 = 1e-6;
+
+/// Shares held of one outcome token.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Leg {
+    // Condition id, or anything else both of a market's tokens share
+    pub market: String,
+    pub outcome: Outcome,
+    pub shares: f64,
+    // Mark for the token; 0 when unknown
+    pub price: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarketExposure {
+    pub market: String,
+    pub yes: f64,
+    pub no: f64,
+    pub yes_price: f64,
+    pub no_price: f64,
+}
+
+impl MarketExposure {
+    /// Matched YES + NO pairs.
+    pub fn complete_sets(&self) -> f64 {
+        self.yes.min(self.no).max(0.0)
+    }
+
+    /// The unmatched side and its share count, if any.
+    pub fn directional(&self) -> Option<(Outcome, f64)> {
+        let net = self.yes - self.no;
+        if net > EPSILON {
+            Some((Outcome::Yes, net))
+        } else if net < -EPSILON {
+            Some((Outcome::No, -net))
+        } else {
+            None
+        }
+    }
+
+    /// Dollar value of the unmatched side at its mark.
+    pub fn at_risk(&self) -> f64 {
+        match self.directional() {
+            Some((Outcome::Yes, shares)) => shares * self.yes_price,
+            Some((Outcome::No, shares)) => shares * self.no_price,
+            None => 0.0,
+        }
+    }
+
+    /// Complete sets at $1 each plus the unmatched side at its mark.
+    /// Marking both legs separately would understate sets whenever the two
+    /// marks sum to less than $1 (bids always do).
+    pub fn value(&self) -> f64 {
+        self.complete_sets() + self.at_risk()
+    }
+}
+
+/// Legs grouped by market, in the order markets first appear.
+pub fn net(legs: &[Leg]) -> Vec<MarketExposure> {
+    let mut markets: Vec<MarketExposure> = Vec::new();
+    for leg in legs {
+        let i = match markets.iter().position(|m| m.market == leg.market) {
+            Some(i) => i,
+            None => {
+                markets.push(MarketExposure { market: leg.market.clone(), yes: 0.0, no: 0.0, yes_price: 0.0, no_price: 0.0 });
+                markets.len() - 1
+            }
+        };
+        let m = &mut markets[i];
+        match leg.outcome {
+            Outcome::Yes => {
+                m.yes += leg.shares;
+                m.yes_price = leg.price;
+            }
+            Outcome::No => {
+                m.no += leg.shares;
+                m.no_price = leg.price;
+            }
+        }
+    }
+    markets
+}
+
+/// Markets holding at least `min_sets` complete sets, with the whole sets
+/// a merge would turn back into as many dollars.
+pub fn merge_suggestions(exposures: &[MarketExposure], min_sets: f64) -> Vec<(String, f64)> {
+    exposures.iter()
+        .map(|m| (m.market.clone(), (m.complete_sets() + EPSILON).floor()))
+        .filter(|(_, sets)| *sets >= min_sets.max(1.0))
+        .collect()
+}
@@ -0,0 +1,165 @@
+//! How simulated orders fill. Assuming every order fills in full at the
+//! displayed ask overstates results, so replays and paper runs go through a
+//! `FillModel`: only part of the displayed size is ours to take, the touch
+//! sometimes moves away before the order lands, and passive orders wait
+//! behind the queue that was already resting at their price.
+//!
+//! Pure and seeded, so a replay with the same model and ticks is repeatable.
+
+use serde::{Deserialize, Serialize};
+
+use crate::strategy::OrderBook;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FillModel {
+    // Share of the displayed ask size we can take; other takers race for the rest
+    pub displayed_share: f64,
+    // Chance the ask lifts before a taker order arrives
+    pub adverse_selection: f64,
+    // How far the ask lifts when it does, in dollars
+    pub adverse_move: f64,
+    // Share of the displayed size at our price assumed ahead of a new passive order
+    pub queue_ahead_share: f64,
+    pub seed: u64,
+}
+
+impl Default for FillModel {
+    /// Every taker order fills at the displayed ask; passive orders go to the
+    /// front of the queue. Matches the old always-fill simulation.
+    fn default() -> Self {
+        Self { displayed_share: 1.0, adverse_selection: 0.0, adverse_move: 0.0, queue_ahead_share: 0.0, seed: 1 }
+    }
+}
+
+/// Shares filled and their average price; `size == 0.0` means no fill.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SimFill {
+    pub size: f64,
+    pub price: f64,
+}
+
+impl SimFill {
+    pub const NONE: SimFill = SimFill { size: 0.0, price: 0.0 };
+}
+
+/// A simulated resting buy at `price`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PassiveOrder {
+    pub price: f64,
+    pub remaining: f64,
+    // Shares resting at our price ahead of us
+    pub queue_ahead: f64,
+    // Bid size at our price on the previous tick, to see how much traded
+    last_level_size: f64,
+}
+
+/// Simulation state: the model plus its random stream.
+#[derive(Debug, Clone)]
+pub struct FillSimulator {
+    model: FillModel,
+    rng: u64,
+}
+
+impl FillSimulator {
+    pub fn new(model: FillModel) -> Self {
+        let rng = model.seed;
+        Self { model, rng }
+    }
+
+    pub fn model(&self) -> &FillModel {
+        &self.model
+    }
+
+    /// splitmix64, mapped to [0, 1)
+    fn next_unit(&mut self) -> f64 {
+        self.rng = self.rng.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.rng;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Marketable buy of `size` shares limited at `limit`. FOK fills all or
+    /// nothing; FAK takes what is available and kills the rest.
+    pub fn take(&mut self, book: &OrderBook, size: f64, limit: f64, fill_or_kill: bool) -> SimFill {
+        self.cross(book.best_ask, book.ask_size, 1.0, size, limit, fill_or_kill)
+    }
+
+    /// Marketable sell into the bid, limited at `limit`; adverse selection
+    /// drops the bid instead of lifting the ask.
+    pub fn hit(&mut self, book: &OrderBook, size: f64, limit: f64, fill_or_kill: bool) -> SimFill {
+        self.cross(book.best_bid, book.bid_size, -1.0, size, limit, fill_or_kill)
+    }
+
+    // `direction` is +1 for buys and -1 for sells: the way a worse price moves
+    fn cross(&mut self, touch: Option<f64>, touch_size: f64, direction: f64, size: f64, limit: f64, fill_or_kill: bool) -> SimFill {
+        let Some(mut price) = touch else { return SimFill::NONE };
+        if self.model.adverse_selection > 0.0 && self.next_unit() < self.model.adverse_selection {
+            price += direction * self.model.adverse_move;
+        }
+        if direction * (price - limit) > 1e-9 {
+            return SimFill::NONE;
+        }
+
+        let available = (touch_size * self.model.displayed_share.clamp(0.0, 1.0)).floor();
+        let filled = size.min(available);
+        if filled <= 0.0 || (fill_or_kill && filled < size) {
+            return SimFill::NONE;
+        }
+        SimFill { size: filled, price }
+    }
+
+    /// Rest a buy at `price` behind the size already displayed there.
+    pub fn rest(&self, book: &OrderBook, price: f64, size: f64) -> PassiveOrder {
+        let level_size = if book.best_bid == Some(price) { book.bid_size } else { 0.0 };
+        PassiveOrder {
+            price,
+            remaining: size,
+            queue_ahead: level_size * self.model.queue_ahead_share.clamp(0.0, 1.0),
+            last_level_size: level_size,
+        }
+    }
+
+    /// Advance a resting buy by one tick. An ask at or through our price
+    /// fills the rest; otherwise size leaving our bid level works through
+    /// the queue ahead first. Cancels can't be told apart from trades in
+    /// book snapshots, so this leans optimistic on busy levels.
+    pub fn advance(&self, order: &mut PassiveOrder, book: &OrderBook) -> SimFill {
+        if order.remaining <= 0.0 {
+            return SimFill::NONE;
+        }
+        if book.best_ask.is_some_and(|ask| ask <= order.price) {
+            let fill = SimFill { size: order.remaining, price: order.price };
+            order.remaining = 0.0;
+            return fill;
+        }
+
+        let traded = match book.best_bid {
+            Some(bid) if bid == order.price => {
+                let traded = (order.last_level_size - book.bid_size).max(0.0);
+                order.last_level_size = book.bid_size;
+                traded
+            }
+            // Everyone else at our price left; without trade prints, assume
+            // they cancelled, which puts us at the front
+            Some(bid) if bid < order.price => {
+                order.queue_ahead = 0.0;
+                order.last_level_size = 0.0;
+                return SimFill::NONE;
+            }
+            // Someone improved on our price: our level isn't visible this tick
+            _ => return SimFill::NONE,
+        };
+
+        let through_queue = (traded - order.queue_ahead).max(0.0);
+        order.queue_ahead = (order.queue_ahead - traded).max(0.0);
+        let filled = through_queue.min(order.remaining).floor();
+        if filled <= 0.0 {
+            return SimFill::NONE;
+        }
+        order.remaining -= filled;
+        SimFill { size: filled, price: order.price }
+    }
+}
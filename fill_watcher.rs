@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+
+use ethers::types::{Address, Block, Log, H256, U256};
+use serde_json::json;
+use ethers::utils::keccak256;
+
+use crate::chain::{self, RpcClient};
+
+// Polygon returns at most ~10k logs / a few thousand blocks per query
+const MAX_BLOCK_RANGE: u64 = 2_000;
+// Depth after which a fill is treated as final; Polygon reorgs are rarely deeper
+pub const CONFIRMATIONS: u64 = 30;
+
+/// `OrderFilled` from the CTF exchange. Amounts are raw 6-decimal units;
+/// asset id 0 is USDC, anything else is a conditional token id.
+#[derive(Debug, Clone)]
+pub struct OrderFilledEvent {
+    pub order_hash: H256,
+    pub maker: Address,
+    pub taker: Address,
+    pub maker_asset_id: U256,
+    pub taker_asset_id: U256,
+    pub maker_amount_filled: U256,
+    pub taker_amount_filled: U256,
+    pub fee: U256,
+    pub block_number: u64,
+    pub block_hash: H256,
+    pub transaction_hash: H256,
+    pub log_index: U256,
+}
+
+impl OrderFilledEvent {
+    pub fn topic() -> H256 {
+        H256::from(keccak256(
+            "OrderFilled(bytes32,address,address,uint256,uint256,uint256,uint256,uint256)".as_bytes()
+        ))
+    }
+
+    pub fn decode(log: &Log) -> Option<Self> {
+        if log.removed == Some(true) || log.topics.len() != 4 || log.topics[0] != Self::topic() || log.data.len() != 5 * 32 {
+            return None;
+        }
+        let word = |i: usize| U256::from_big_endian(&log.data[i * 32..(i + 1) * 32]);
+
+        Some(Self {
+            order_hash: log.topics[1],
+            maker: Address::from_slice(&log.topics[2].as_bytes()[12..]),
+            taker: Address::from_slice(&log.topics[3].as_bytes()[12..]),
+            maker_asset_id: word(0),
+            taker_asset_id: word(1),
+            maker_amount_filled: word(2),
+            taker_amount_filled: word(3),
+            fee: word(4),
+            block_number: log.block_number.map(|b| b.as_u64()).unwrap_or(0),
+            block_hash: log.block_hash.unwrap_or_default(),
+            transaction_hash: log.transaction_hash.unwrap_or_default(),
+            log_index: log.log_index.unwrap_or_default(),
+        })
+    }
+
+    /// Shares of the conditional token that changed hands in this fill.
+    pub fn shares(&self) -> f64 {
+        let raw = if self.maker_asset_id.is_zero() { self.taker_amount_filled } else { self.maker_amount_filled };
+        raw.as_u128() as f64 / 1_000_000.0
+    }
+}
+
+/// Result of one watcher poll. `new` fills are provisional until they show
+/// up in `finalized`; anything in `orphaned` was reorged out and must be
+/// undone by whoever applied it.
+#[derive(Debug, Default)]
+pub struct FillUpdate {
+    pub new: Vec<OrderFilledEvent>,
+    pub finalized: Vec<OrderFilledEvent>,
+    pub orphaned: Vec<OrderFilledEvent>,
+}
+
+/// Polls `eth_getLogs` for fills of orders made by `maker`, resuming from
+/// the last scanned block each time, and re-verifies unconfirmed fills
+/// against the canonical chain until they are CONFIRMATIONS deep.
+pub struct FillWatcher {
+    exchange: Address,
+    maker: Address,
+    next_block: u64,
+    pending: Vec<OrderFilledEvent>,
+}
+
+impl FillWatcher {
+    pub fn new(exchange: Address, maker: Address, start_block: u64) -> Self {
+        Self { exchange, maker, next_block: start_block, pending: Vec::new() }
+    }
+
+    pub fn poll(&mut self, rpc: &RpcClient) -> Result<FillUpdate, Box<dyn std::error::Error>> {
+        let head = rpc.block_number()?;
+        let mut update = FillUpdate::default();
+
+        self.check_pending(rpc, head, &mut update)?;
+
+        while self.next_block <= head {
+            let to_block = (self.next_block + MAX_BLOCK_RANGE - 1).min(head);
+            let topics = [Some(OrderFilledEvent::topic()), None, Some(chain::address_topic(self.maker))];
+            let logs = rpc.get_logs(self.exchange, &topics, self.next_block, to_block)?;
+
+            for event in logs.iter().filter_map(OrderFilledEvent::decode) {
+                let seen = self.pending.iter().any(|p| p.transaction_hash == event.transaction_hash
+                    && p.log_index == event.log_index && p.block_hash == event.block_hash);
+                if !seen {
+                    self.pending.push(event.clone());
+                    update.new.push(event);
+                }
+            }
+            self.next_block = to_block + 1;
+        }
+
+        Ok(update)
+    }
+
+    /// Drop pending fills whose block is no longer canonical and promote the
+    /// ones that are deep enough. Rescans from the earliest orphaned block so
+    /// re-included transactions are picked up again.
+    fn check_pending(&mut self, rpc: &RpcClient, head: u64, update: &mut FillUpdate) -> Result<(), Box<dyn std::error::Error>> {
+        let mut canonical: HashMap<u64, H256> = HashMap::new();
+        for block_number in self.pending.iter().map(|e| e.block_number) {
+            if canonical.contains_key(&block_number) {
+                continue;
+            }
+            let block: Option<Block<H256>> = rpc.call_optional("eth_getBlockByNumber", json!([format!("0x{:x}", block_number), false]))?;
+            canonical.insert(block_number, block.and_then(|b| b.hash).unwrap_or_default());
+        }
+
+        let mut still_pending = Vec::new();
+        for event in self.pending.drain(..) {
+            if canonical.get(&event.block_number) != Some(&event.block_hash) {
+                self.next_block = self.next_block.min(event.block_number);
+                update.orphaned.push(event);
+            } else if head.saturating_sub(event.block_number) >= CONFIRMATIONS {
+                update.finalized.push(event);
+            } else {
+                still_pending.push(event);
+            }
+        }
+        self.pending = still_pending;
+        Ok(())
+    }
+}
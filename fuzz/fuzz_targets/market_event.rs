@@ -0,0 +1,10 @@
+#![no_main]
+
+use eth_no_trend_bot::responses;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(Some(market)) = responses::parse_market_event("eth-updown-15m-1700000000", data) {
+        assert!(market.link.ends_with(&market.slug));
+    }
+});
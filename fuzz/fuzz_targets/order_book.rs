@@ -0,0 +1,13 @@
+#![no_main]
+
+use eth_no_trend_bot::book_parser;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(top) = book_parser::parse_top_of_book(data) {
+        for (price, size) in top.best_ask.into_iter().chain(top.best_bid) {
+            assert!(book_parser::fixed_to_f64(price).is_finite());
+            assert!(book_parser::fixed_to_f64(size).is_finite());
+        }
+    }
+});
@@ -0,0 +1,14 @@
+#![no_main]
+
+use eth_no_trend_bot::responses;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(order) = responses::parse_order_status(data) {
+        let progress = order.progress();
+        assert!(progress.original_size.is_finite() && progress.filled_size.is_finite());
+        assert!(progress.remaining() >= 0.0);
+        assert!(order.reported_avg_price().is_finite());
+        let _ = (progress.is_filled(), progress.is_closed());
+    }
+});
@@ -0,0 +1,12 @@
+#![no_main]
+
+use eth_no_trend_bot::redemption;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(positions) = redemption::parse_positions(data) {
+        // Planning runs straight off the parsed page, so it must not panic either
+        let plans = redemption::plan_redemptions(&positions);
+        assert!(plans.len() <= positions.len());
+    }
+});
@@ -0,0 +1,196 @@
+//! gRPC front end for order execution (feature `grpc`). Other services send
+//! place/cancel/status/positions/book calls here instead of talking to the
+//! CLOB themselves, so signing, risk checks and submission tracking stay in
+//! one process. Wire format: proto/execution.proto.
+
+// tonic::Status is large, but it is what every tonic handler returns
+#![allow(clippy::result_large_err)]
+
+use std::net::SocketAddr;
+use std::sync::mpsc;
+use std::thread;
+
+use tokio::sync::oneshot;
+use tonic::{Request, Response, Status};
+
+include!(concat!(env!("OUT_DIR"), "/execution.Execution.rs"));
+
+pub use execution_client::ExecutionClient;
+pub use execution_server::{Execution, ExecutionServer};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum Side {
+    Buy = 0,
+    Sell = 1,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct PlaceOrderRequest {
+    #[prost(string, tag = "1")]
+    pub token_id: String,
+    #[prost(enumeration = "Side", tag = "2")]
+    pub side: i32,
+    #[prost(double, tag = "3")]
+    pub price: f64,
+    #[prost(uint32, tag = "4")]
+    pub size: u32,
+    #[prost(string, tag = "5")]
+    pub order_type: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct PlaceOrderReply {
+    #[prost(string, tag = "1")]
+    pub order_id: String,
+    #[prost(double, tag = "2")]
+    pub filled_size: f64,
+    #[prost(double, tag = "3")]
+    pub avg_price: f64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CancelOrderRequest {
+    #[prost(string, tag = "1")]
+    pub order_id: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CancelOrderReply {}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct OrderStatusRequest {
+    #[prost(string, tag = "1")]
+    pub order_id: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct OrderStatusReply {
+    #[prost(string, tag = "1")]
+    pub status: String,
+    #[prost(double, tag = "2")]
+    pub original_size: f64,
+    #[prost(double, tag = "3")]
+    pub filled_size: f64,
+    #[prost(double, tag = "4")]
+    pub avg_price: f64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct PositionsRequest {}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Position {
+    #[prost(string, tag = "1")]
+    pub token_id: String,
+    #[prost(double, tag = "2")]
+    pub size: f64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct PositionsReply {
+    #[prost(message, repeated, tag = "1")]
+    pub positions: Vec<Position>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BookRequest {
+    #[prost(string, tag = "1")]
+    pub token_id: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BookReply {
+    #[prost(double, optional, tag = "1")]
+    pub best_bid: Option<f64>,
+    #[prost(double, tag = "2")]
+    pub bid_size: f64,
+    #[prost(double, optional, tag = "3")]
+    pub best_ask: Option<f64>,
+    #[prost(double, tag = "4")]
+    pub ask_size: f64,
+}
+
+/// What the service executes against. The bot implements this; calls arrive
+/// one at a time on the thread that called `serve`, so implementations can
+/// keep their `RefCell`/blocking-client state.
+pub trait ExecutionBackend {
+    fn place_order(&self, request: PlaceOrderRequest) -> Result<PlaceOrderReply, Status>;
+    fn cancel_order(&self, order_id: &str) -> Result<(), Status>;
+    fn order_status(&self, order_id: &str) -> Result<OrderStatusReply, Status>;
+    fn positions(&self) -> Result<Vec<Position>, Status>;
+    fn book(&self, token_id: &str) -> Result<BookReply, Status>;
+}
+
+type Job = Box<dyn FnOnce(&dyn ExecutionBackend) + Send>;
+
+struct ExecutionService {
+    jobs: mpsc::Sender<Job>,
+}
+
+impl ExecutionService {
+    async fn call<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&dyn ExecutionBackend) -> Result<T, Status> + Send + 'static,
+    ) -> Result<Response<T>, Status> {
+        let (reply, result) = oneshot::channel();
+        self.jobs
+            .send(Box::new(move |backend| {
+                let _ = reply.send(f(backend));
+            }))
+            .map_err(|_| Status::unavailable("execution backend stopped"))?;
+        let result = result.await.map_err(|_| Status::internal("execution backend dropped the call"))?;
+        result.map(Response::new)
+    }
+}
+
+#[tonic::async_trait]
+impl Execution for ExecutionService {
+    async fn place_order(&self, request: Request<PlaceOrderRequest>) -> Result<Response<PlaceOrderReply>, Status> {
+        let request = request.into_inner();
+        self.call(move |backend| backend.place_order(request)).await
+    }
+
+    async fn cancel_order(&self, request: Request<CancelOrderRequest>) -> Result<Response<CancelOrderReply>, Status> {
+        let order_id = request.into_inner().order_id;
+        self.call(move |backend| backend.cancel_order(&order_id).map(|_| CancelOrderReply {})).await
+    }
+
+    async fn get_order_status(&self, request: Request<OrderStatusRequest>) -> Result<Response<OrderStatusReply>, Status> {
+        let order_id = request.into_inner().order_id;
+        self.call(move |backend| backend.order_status(&order_id)).await
+    }
+
+    async fn get_positions(&self, _request: Request<PositionsRequest>) -> Result<Response<PositionsReply>, Status> {
+        self.call(|backend| backend.positions().map(|positions| PositionsReply { positions })).await
+    }
+
+    async fn get_book(&self, request: Request<BookRequest>) -> Result<Response<BookReply>, Status> {
+        let token_id = request.into_inner().token_id;
+        self.call(move |backend| backend.book(&token_id)).await
+    }
+}
+
+/// Serve the Execution service on `addr` until the server fails. Requests
+/// are handled on the calling thread, in arrival order; the async transport
+/// runs on its own thread.
+pub fn serve(addr: SocketAddr, backend: &dyn ExecutionBackend) -> Result<(), Box<dyn std::error::Error>> {
+    let (jobs, queue) = mpsc::channel::<Job>();
+    let server = thread::spawn(move || -> Result<(), String> {
+        let runtime = tokio::runtime::Runtime::new().map_err(|e| e.to_string())?;
+        runtime
+            .block_on(
+                tonic::transport::Server::builder()
+                    .add_service(ExecutionServer::new(ExecutionService { jobs }))
+                    .serve(addr),
+            )
+            .map_err(|e| e.to_string())
+    });
+
+    // Ends once the server thread exits and drops the service's sender
+    for job in queue {
+        job(backend);
+    }
+    server.join().map_err(|_| "gRPC server thread panicked")??;
+    Ok(())
+}
@@ -0,0 +1,82 @@
+//! `--log-format json` (or `log_format = "json"` in the config): alongside
+//! the console output, one JSON object per event on stderr for Loki, ELK and
+//! other collectors. The console keeps stdout to itself, so
+//! `2>>bot.jsonl` separates the two.
+//!
+//!   {"event":"state","level":"info","market":"eth-updown-15m-1760000400","profile":"default","state":"monitoring","ts":"2025-10-09T09:16:00Z","unix":1760000760}
+//!
+//! Every line has `ts` (RFC3339), `unix`, `level`, `event` and `profile`.
+//! `event` is a `BotEvent` type (tick, fill, state, …), `notification` or
+//! `trade`; the rest of the object is that event's fields. Ticks are logged
+//! at `debug`, rejections at `warning`, notifications at their severity.
+
+use std::io::Write;
+
+use serde_json::{Map, Value};
+
+use crate::bot_event::BotEvent;
+use crate::notify::Notification;
+use crate::timestamps;
+use crate::trade_log::{Column, Field, LogFormat, TradeLog, TradeRecord};
+
+pub struct JsonLog {
+    profile: String,
+    // Renders trade rows with every journal field
+    trades: TradeLog,
+}
+
+impl JsonLog {
+    pub fn new(profile: &str) -> Self {
+        Self {
+            profile: profile.to_string(),
+            trades: TradeLog::new("", LogFormat::Jsonl, Field::all().map(Column::new).collect()),
+        }
+    }
+
+    /// One line: `fields` (an object) plus the keys every line has.
+    pub fn line(&self, unix: u64, level: &str, event: &str, fields: Value) -> String {
+        let mut object = match fields {
+            Value::Object(map) => map,
+            _ => Map::new(),
+        };
+        object.insert("ts".to_string(), timestamps::rfc3339(unix).into());
+        object.insert("unix".to_string(), unix.into());
+        object.insert("level".to_string(), level.into());
+        object.insert("event".to_string(), event.into());
+        object.insert("profile".to_string(), self.profile.clone().into());
+        Value::Object(object).to_string()
+    }
+
+    pub fn bot_event(&self, unix: u64, event: &BotEvent) -> String {
+        let mut fields = serde_json::to_value(event).unwrap_or(Value::Null);
+        let kind = fields.as_object_mut()
+            .and_then(|f| f.remove("type"))
+            .and_then(|t| t.as_str().map(str::to_string))
+            .unwrap_or_default();
+        let level = match event {
+            BotEvent::Tick { .. } => "debug",
+            BotEvent::OrderRejected { .. } => "warning",
+            _ => "info",
+        };
+        self.line(unix, level, &kind, fields)
+    }
+
+    pub fn notification(&self, notification: &Notification) -> String {
+        let fields = serde_json::json!({
+            "kind": notification.event,
+            "title": notification.title,
+            "body": notification.body,
+        });
+        self.line(notification.ts, notification.severity.as_str(), "notification", fields)
+    }
+
+    pub fn trade(&self, unix: u64, record: &TradeRecord) -> String {
+        let fields = serde_json::from_str(self.trades.render(record).trim_end()).unwrap_or(Value::Null);
+        self.line(unix, "info", "trade", fields)
+    }
+
+    /// Write `line` to stderr; a failed write is dropped like a console print.
+    pub fn write(&self, line: &str) {
+        let _ = writeln!(std::io::stderr().lock(), "{}", line);
+    }
+}
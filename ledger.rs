@@ -0,0 +1,333 @@
+//! Double-entry ledger of everything that moves money: fills, fees,
+//! redemptions and deposits/withdrawals. Every entry's postings sum to zero
+//! (debits positive, credits negative), so the books always balance and any
+//! gap against the wallet points at something the bot never saw.
+//!
+//! Accounts, all valued in USDC:
+//!   - `usdc`: collateral in the trading wallet
+//!   - `inventory:<token id>`: outcome shares at cost; postings also carry a
+//!     share count so holdings can be checked against the chain
+//!   - `fees`: trading fees paid
+//!   - `pnl`: realized profit and loss (a credit balance is a profit)
+//!   - `capital`: money put in or taken out from outside
+//!
+//! Entries are appended to a JSON-lines journal and replayed on load.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+// Amounts below this are rounding, not money
+const EPSILON: f64 = 1e-6;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub enum Account {
+    Usdc,
+    Inventory(String),
+    Fees,
+    Pnl,
+    Capital,
+}
+
+impl Account {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "usdc" => Some(Self::Usdc),
+            "fees" => Some(Self::Fees),
+            "pnl" => Some(Self::Pnl),
+            "capital" => Some(Self::Capital),
+            _ => s.strip_prefix("inventory:").filter(|t| !t.is_empty()).map(|t| Self::Inventory(t.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Account {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Usdc => f.write_str("usdc"),
+            Self::Inventory(token) => write!(f, "inventory:{}", token),
+            Self::Fees => f.write_str("fees"),
+            Self::Pnl => f.write_str("pnl"),
+            Self::Capital => f.write_str("capital"),
+        }
+    }
+}
+
+impl From<Account> for String {
+    fn from(account: Account) -> Self {
+        account.to_string()
+    }
+}
+
+impl TryFrom<String> for Account {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::parse(&s).ok_or_else(|| format!("unknown account '{}'", s))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryKind {
+    Fill,
+    Fee,
+    Redemption,
+    Deposit,
+    Withdrawal,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Posting {
+    pub account: Account,
+    // USDC; debits positive, credits negative
+    pub amount: f64,
+    // Share count for inventory postings, 0 elsewhere
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub shares: f64,
+}
+
+fn is_zero(v: &f64) -> bool {
+    *v == 0.0
+}
+
+impl Posting {
+    pub fn new(account: Account, amount: f64) -> Self {
+        Self { account, amount, shares: 0.0 }
+    }
+
+    pub fn inventory(token_id: &str, amount: f64, shares: f64) -> Self {
+        Self { account: Account::Inventory(token_id.to_string()), amount, shares }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub ts: u64,
+    pub kind: EntryKind,
+    // Order id, tx hash or whatever identifies the event
+    pub memo: String,
+    pub postings: Vec<Posting>,
+}
+
+impl Entry {
+    pub fn is_balanced(&self) -> bool {
+        self.postings.iter().map(|p| p.amount).sum::<f64>().abs() < EPSILON
+    }
+}
+
+/// One execution to book; negative `shares` reverses an earlier one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fill<'a> {
+    pub token_id: &'a str,
+    pub buy: bool,
+    pub shares: f64,
+    pub price: f64,
+    // USDC, on top of shares * price
+    pub fee: f64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Balance {
+    amount: f64,
+    shares: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Ledger {
+    entries: Vec<Entry>,
+    balances: HashMap<Account, Balance>,
+}
+
+impl Ledger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replay a journal; a missing file is an empty ledger.
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut ledger = Self::new();
+        if !Path::new(path).exists() {
+            return Ok(ledger);
+        }
+        for (n, line) in BufReader::new(File::open(path)?).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: Entry = serde_json::from_str(&line).map_err(|e| format!("{} line {}: {}", path, n + 1, e))?;
+            ledger.post(entry).map_err(|e| format!("{} line {}: {}", path, n + 1, e))?;
+        }
+        Ok(ledger)
+    }
+
+    /// Append one entry to the journal at `path`.
+    pub fn append(path: &str, entry: &Entry) -> Result<(), Box<dyn std::error::Error>> {
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+        Ok(())
+    }
+
+    /// Record `entry`, refusing one that doesn't balance.
+    pub fn post(&mut self, entry: Entry) -> Result<(), String> {
+        if entry.postings.len() < 2 || !entry.is_balanced() {
+            let sum: f64 = entry.postings.iter().map(|p| p.amount).sum();
+            return Err(format!("unbalanced {:?} entry '{}' (off by {:.6})", entry.kind, entry.memo, sum));
+        }
+        for p in &entry.postings {
+            let balance = self.balances.entry(p.account.clone()).or_default();
+            balance.amount += p.amount;
+            balance.shares += p.shares;
+        }
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn balance(&self, account: &Account) -> f64 {
+        self.balances.get(account).map(|b| b.amount).unwrap_or(0.0)
+    }
+
+    pub fn shares(&self, token_id: &str) -> f64 {
+        self.balances.get(&Account::Inventory(token_id.to_string())).map(|b| b.shares).unwrap_or(0.0)
+    }
+
+    pub fn usdc(&self) -> f64 {
+        self.balance(&Account::Usdc)
+    }
+
+    pub fn realized_pnl(&self) -> f64 {
+        -self.balance(&Account::Pnl)
+    }
+
+    pub fn fees(&self) -> f64 {
+        self.balance(&Account::Fees)
+    }
+
+    /// Every account with a balance, sorted by name.
+    pub fn accounts(&self) -> Vec<(Account, f64, f64)> {
+        let mut accounts: Vec<(Account, f64, f64)> = self.balances.iter()
+            .filter(|(_, b)| b.amount.abs() >= EPSILON || b.shares.abs() >= EPSILON)
+            .map(|(a, b)| (a.clone(), b.amount, b.shares))
+            .collect();
+        accounts.sort_by(|a, b| a.0.cmp(&b.0));
+        accounts
+    }
+
+    /// Tokens the ledger holds or has held, sorted.
+    pub fn tokens(&self) -> Vec<String> {
+        let mut tokens: Vec<String> = self.balances.keys()
+            .filter_map(|a| match a { Account::Inventory(t) => Some(t.clone()), _ => None })
+            .collect();
+        tokens.sort();
+        tokens
+    }
+
+    fn unit_cost(&self, token_id: &str) -> f64 {
+        let shares = self.shares(token_id);
+        if shares.abs() < EPSILON { 0.0 } else { self.balance(&Account::Inventory(token_id.to_string())) / shares }
+    }
+
+    /// Entries for a fill plus its fee. Buys add inventory at cost; sells
+    /// relieve it at average cost and book the difference to PnL.
+    pub fn fill_entries(&self, ts: u64, memo: &str, fill: &Fill) -> Vec<Entry> {
+        let Fill { token_id, shares, fee, .. } = *fill;
+        let notional = shares * fill.price;
+        let postings = if fill.buy {
+            vec![Posting::inventory(token_id, notional, shares), Posting::new(Account::Usdc, -notional)]
+        } else {
+            let basis = shares * self.unit_cost(token_id);
+            vec![
+                Posting::new(Account::Usdc, notional),
+                Posting::inventory(token_id, -basis, -shares),
+                Posting::new(Account::Pnl, basis - notional),
+            ]
+        };
+        let mut entries = vec![Entry { ts, kind: EntryKind::Fill, memo: memo.to_string(), postings }];
+        if fee.abs() >= EPSILON {
+            entries.push(Entry {
+                ts,
+                kind: EntryKind::Fee,
+                memo: memo.to_string(),
+                postings: vec![Posting::new(Account::Fees, fee), Posting::new(Account::Usdc, -fee)],
+            });
+        }
+        entries
+    }
+
+    /// Redeeming resolved tokens: all held shares of each leave inventory
+    /// for its USDC payout, with the difference to cost booked to PnL.
+    pub fn redemption_entry(&self, ts: u64, memo: &str, payouts: &[(String, f64)]) -> Entry {
+        let mut postings = Vec::new();
+        for (token_id, payout) in payouts {
+            let shares = self.shares(token_id);
+            let basis = self.balance(&Account::Inventory(token_id.clone()));
+            postings.push(Posting::new(Account::Usdc, *payout));
+            postings.push(Posting::inventory(token_id, -basis, -shares));
+            postings.push(Posting::new(Account::Pnl, basis - payout));
+        }
+        Entry { ts, kind: EntryKind::Redemption, memo: memo.to_string(), postings }
+    }
+
+    /// Money in (positive) or out (negative) of the wallet from outside.
+    pub fn transfer_entry(&self, ts: u64, memo: &str, amount: f64) -> Entry {
+        Entry {
+            ts,
+            kind: if amount >= 0.0 { EntryKind::Deposit } else { EntryKind::Withdrawal },
+            memo: memo.to_string(),
+            postings: vec![Posting::new(Account::Usdc, amount), Posting::new(Account::Capital, -amount)],
+        }
+    }
+
+    /// Compare against what the wallet actually holds. `wallet_shares`
+    /// should cover every token in `tokens()`; missing ones are skipped.
+    pub fn reconcile(&self, wallet_usdc: f64, wallet_shares: &HashMap<String, f64>, tolerance: f64) -> Reconciliation {
+        let token_diffs = self.tokens().into_iter()
+            .filter_map(|t| wallet_shares.get(&t).map(|w| (self.shares(&t), *w, t)))
+            .filter(|(ledger, wallet, _)| (ledger - wallet).abs() > tolerance)
+            .map(|(ledger, wallet, token_id)| TokenDiff { token_id, ledger, wallet })
+            .collect();
+        Reconciliation { ledger_usdc: self.usdc(), wallet_usdc, token_diffs, tolerance }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenDiff {
+    pub token_id: String,
+    pub ledger: f64,
+    pub wallet: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reconciliation {
+    pub ledger_usdc: f64,
+    pub wallet_usdc: f64,
+    // Share counts that disagree: fills the ledger never saw
+    pub token_diffs: Vec<TokenDiff>,
+    tolerance: f64,
+}
+
+impl Reconciliation {
+    pub fn usdc_diff(&self) -> f64 {
+        self.wallet_usdc - self.ledger_usdc
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.token_diffs.is_empty() && self.usdc_diff().abs() <= self.tolerance
+    }
+
+    /// A USDC gap with every share count agreeing can only be money moved
+    /// in or out from outside; with share gaps it is more likely a missed
+    /// fill, so nothing is suggested.
+    pub fn suggested_transfer(&self) -> Option<f64> {
+        (self.token_diffs.is_empty() && self.usdc_diff().abs() > self.tolerance).then(|| self.usdc_diff())
+    }
+}
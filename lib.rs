@@ -0,0 +1,100 @@
+// Reusable pieces of the bot: on-chain plumbing, signing and API response
+// parsing. main.rs holds the strategy loop; tests and benches link this.
+//
+// The first group is pure (serde + std only) and also builds for wasm32, so
+// a browser playground can replay recorded books through `strategy`. The
+// rest needs ethers/reqwest and a real network.
+
+pub mod alerts;
+pub mod attribution;
+pub mod book_parser;
+pub mod bot_event;
+pub mod cadence;
+pub mod chaos;
+pub mod clock;
+pub mod event_log;
+pub mod exposure;
+pub mod fill_model;
+pub mod ledger;
+pub mod market_cache;
+pub mod market_schedule;
+pub mod order_lifecycle;
+pub mod performance;
+pub mod pnl;
+pub mod position_limit;
+pub mod responses;
+pub mod rules;
+pub mod schedule;
+pub mod strategy;
+pub mod traded_markets;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub mod approvals;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod book_recorder;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod chain;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod collateral;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod config;
+#[cfg(all(feature = "event-stream", not(target_arch = "wasm32")))]
+pub mod event_stream;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod discord;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod discovery;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod exchange_status;
+#[cfg(all(feature = "fill-watch", not(target_arch = "wasm32")))]
+pub mod fill_watcher;
+#[cfg(all(feature = "grpc", not(target_arch = "wasm32")))]
+pub mod grpc;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod json_log;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod market_scanner;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod market_stream;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod network;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod nonce_manager;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod notify;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod positions;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod profiles;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod proxy_wallet;
+#[cfg(all(feature = "redeem", not(target_arch = "wasm32")))]
+pub mod redemption;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod report;
+#[cfg(all(feature = "resolution", not(target_arch = "wasm32")))]
+pub mod resolution;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod revert;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod rpc_pool;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod shutdown;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod signing;
+#[cfg(all(feature = "sqlite", not(target_arch = "wasm32")))]
+pub mod store;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod tax_lots;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod telegram;
+#[cfg(all(feature = "recording", not(target_arch = "wasm32")))]
+pub mod tick_log;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod timestamps;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod trade_log;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod tx_manager;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod webhook;
@@ -1,299 +1,406 @@
-use std::collections::{HashMap, HashSet};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::sync::Arc;
 use std::fs::{File, OpenOptions};
-use std::io::{self, Write};
-use std::thread;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use chrono::{Utc, TimeZone};
-use reqwest::blocking::Client;
+use std::io::{self, Read, Write};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use reqwest::blocking::{Client, RequestBuilder, Response};
 use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use ethers::signers::{LocalWallet, Signer};
-use ethers::types::{Address, Signature, U256, H256};
-use ethers::utils::keccak256;
+use ethers::types::{Address, U256};
+use clap::{Args, Parser, Subcommand};
 use std::str::FromStr;
-use hmac::{Hmac, Mac};
-use sha2::Sha256;
-use base64::{Engine as _, engine::general_purpose};
+
+use eth_no_trend_bot::{alerts, approvals, book_parser, book_recorder, bot_event, cadence, chain, chaos, clock, collateral, config, discord, discovery, event_log, exchange_status, exposure, json_log, ledger, market_cache, market_scanner, market_schedule, market_stream, network, order_lifecycle, notify, performance, pnl, position_limit, positions, profiles, proxy_wallet, report, responses, rules, schedule, shutdown, strategy, tax_lots, telegram, timestamps, trade_log, traded_markets, tx_manager, webhook};
+#[cfg(feature = "fill-watch")]
+use eth_no_trend_bot::fill_watcher::{self, FillWatcher, OrderFilledEvent};
+#[cfg(feature = "grpc")]
+use eth_no_trend_bot::grpc;
+#[cfg(feature = "event-stream")]
+use eth_no_trend_bot::event_stream::EventServer;
+#[cfg(feature = "recording")]
+use eth_no_trend_bot::{attribution, fill_model, tick_log};
+#[cfg(feature = "redeem")]
+use eth_no_trend_bot::redemption;
+#[cfg(feature = "sqlite")]
+use eth_no_trend_bot::store::{self, AbortRow, FillRow, Store};
+#[cfg(feature = "resolution")]
+use eth_no_trend_bot::resolution::{self, ResolutionState, ResolutionWatcher};
+use eth_no_trend_bot::fill_model::{FillModel, FillSimulator, SimFill};
+use eth_no_trend_bot::signing::{ApiCredentials, Eip712Signer, OrderAmounts, OrderSide, PolymarketOrder};
+use chain::RpcClient;
+use clock::Clock;
+use collateral::CollateralSwapHook;
+use network::NetworkProfile;
+use tx_manager::{TxConfig, TxManager, TxRequest};
+
+use market_cache::{MarketCache, MarketsPage};
+use market_scanner::MarketScanner;
+use market_stream::MarketStream;
+use book_recorder::{BookRecorder, Record};
+use timestamps::DisplayTz;
+use alerts::AlertWatcher;
+use cadence::Cadence;
+use config::{Config, LogOutput};
+use chaos::{Chaos, Fault};
+use rules::{Action, RuleEngine, Sample, Transition};
+use bot_event::BotEvent;
+use event_log::{EventLog, Emit};
+use exposure::{Leg, MarketExposure};
+use notify::{Event, JsonLinesNotifier, Router, Severity, StdoutNotifier};
+use performance::{PerformanceReport, TradeResult};
+use report::DailySummary;
+use pnl::PnlTracker;
+use tax_lots::FifoBook;
+use ledger::Ledger;
+use order_lifecycle::{OrderEvent, OrderJournal, OrderRecord, OrderSpec, OrderState};
+use trade_log::{TradeLog, TradeRecord};
+use profiles::Profile;
+use discord::{DiscordConfig, DiscordNotifier};
+use json_log::JsonLog;
+use telegram::{TelegramConfig, TelegramNotifier};
+use webhook::{WebhookConfig, WebhookNotifier};
+use schedule::Schedule;
+use market_schedule::MarketSchedule;
+use discovery::MarketFinder;
+use position_limit::PositionLimit;
+use traded_markets::TradedMarkets;
+use responses::{MarketData, OpenOrder, OrderProgress};
+use strategy::{EntryExecution, EntryMonitor, EntryStyle, StopLoss, Gate, OrderBook, Outcome, PartialFillAction, Signal, StrategyParams, TieBreak};
 
 // ==========================================
 // 📊 CONFIGURATION CONSTANTS
 // ==========================================
-const PRIVATE_KEY: &str = "0xbbd185bb356315b5f040a2af2fa28549177f3087559bb76885033e9cf8e8bf34";
-const POLYMARKET_ADDRESS: &str = "0xC47167d407A91965fAdc7aDAb96F0fF586566bF7";
-
-const TRADE_SIDE: &str = "BOTH";
-const ENTRY_PRICE: f64 = 0.96;
-const STOP_LOSS_PRICE: f64 = 0.89;
-const SUSTAIN_TIME: u64 = 3;
-const POSITION_SIZE: u32 = 5;
-const MARKET_WINDOW: u64 = 240;
-const POLLING_INTERVAL: u64 = 1;
-const ENTRY_TIMEOUT: u64 = 210;
-const ABORT_ASK_PRICE: f64 = 0.99;
-
-const HOST: &str = "https://clob.polymarket.com";
-const GAMMA_API_URL: &str = "https://gamma-api.polymarket.com";
-const CHAIN_ID: u64 = 137;
-const EXCHANGE_CONTRACT: &str = "0x4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E";
-const LOG_FILE: &str = "ETH_NO_trading_log.csv";
+// No signing key or trading address in the source: BOT_KEYSTORE or
+// PRIVATE_KEY (see wallet_from_env) and POLYMARKET_ADDRESS come from the env
+// Strategy parameters and timings live in config.toml; see config.rs
+// How long a passive entry bid rests before it's pulled and requoted
+const PASSIVE_REST_SECS: u64 = 20;
 
-// EIP-712 Constants
-const EIP712_DOMAIN_NAME: &str = "Polymarket CTF Exchange";
-const EIP712_DOMAIN_VERSION: &str = "1";
+const LOG_FILE: &str = "ETH_NO_trading_log.csv";
+// Strategy name written to the trade log
+const STRATEGY_NAME: &str = "eth_no_trend";
+const MARKET_CACHE_FILE: &str = "clob_markets_cache.json";
+const LEDGER_FILE: &str = "ledger.jsonl";
+const ORDERS_FILE: &str = "orders.jsonl";
+const TRADED_MARKETS_FILE: &str = "traded_markets.json";
+#[cfg(feature = "sqlite")]
+const DB_FILE: &str = "bot.db";
+const NOTIFICATIONS_FILE: &str = "notifications.jsonl";
+const DAILY_REPORT_FILE: &str = "daily_report.csv";
 
 // ==========================================
 // 📝 DATA STRUCTURES
 // ==========================================
 
+#[derive(Debug, Serialize)]
+struct OrderRequest {
+    order: PolymarketOrder,
+    #[serde(rename = "orderType")]
+    order_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    owner: Option<String>,
+    signature: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OrderResponse {
+    #[serde(rename = "orderID")]
+    order_id: Option<String>,
+    #[serde(rename = "errorMsg")]
+    error_msg: Option<String>,
+}
+
+/// Client-side record of an order we attempted to submit, keyed by its
+/// EIP-712 hash so an ambiguous POST can be reconciled before retrying.
+#[derive(Debug, Clone)]
+struct SubmittedOrder {
+    client_order_id: String,
+    token_id: String,
+    side: OrderSide,
+    price: f64,
+    size: u32,
+    submitted_at: u64,
+}
+
+/// A position the strategy opened, kept until it is sold so the close can
+/// be scored.
 #[derive(Debug, Clone)]
-struct MarketData {
+struct OpenEntry {
     slug: String,
     title: String,
     link: String,
-    yes_token: String,
-    no_token: String,
+    side: String,
+    price: f64,
+    // None for positions resumed after a restart
+    entered_at: Option<u64>,
+    // Set just before the bot sells for a reason of its own
+    exit: Option<ExitReason>,
+    // Sells that filled only partly so far, folded into the close
+    sold: f64,
+    proceeds: f64,
+    exit_fees: f64,
 }
 
-#[derive(Debug, Clone)]
-struct OrderBook {
-    best_ask: Option<f64>,
-    ask_size: f64,
-    best_bid: Option<f64>,
-    bid_size: f64,
+/// Why the bot sold a position, for the journal.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ExitReason {
+    // The bid held at or under the stop; its price then and when it fired
+    StopLoss { price: f64, at: u64 },
+    // exit_before_close came due; the seconds that were left to close
+    BeforeClose { left: u64, at: u64 },
+}
+
+/// When a new order expires, and whether `submit_order` waits on it.
+#[derive(Debug, Clone, Copy)]
+struct Placement {
+    // Signed expiration; None is an hour out
+    expires_at: Option<u64>,
+    // Hand a resting order back once accepted instead of waiting on fills
+    rest: bool,
+}
+
+/// What an order had done by the time `place_order` stopped waiting on it.
+#[derive(Debug, Clone, PartialEq)]
+struct Fill {
+    order_id: String,
+    filled_size: f64,
+    avg_price: f64,
+    // Shares of the order left unfilled, whether or not still working
+    remaining: f64,
 }
 
 #[derive(Debug, Clone)]
-struct TradeRecord {
-    title: String,
-    link: String,
-    status: String,
-    entry1_time: String,
-    entry_side: String,
-    entry_price: String,
-    position_size: String,
-    sl_time: String,
-    sl_price: String,
-    final_status: String,
-    notes: String,
-    is_sl_triggered: String,
-}
-
-impl Default for TradeRecord {
-    fn default() -> Self {
-        Self {
-            title: "-".to_string(),
-            link: "-".to_string(),
-            status: "-".to_string(),
-            entry1_time: "-".to_string(),
-            entry_side: "-".to_string(),
-            entry_price: "-".to_string(),
-            position_size: "-".to_string(),
-            sl_time: "-".to_string(),
-            sl_price: "-".to_string(),
-            final_status: "-".to_string(),
-            notes: "-".to_string(),
-            is_sl_triggered: "-".to_string(),
-        }
-    }
+struct TrackedOrder {
+    order_id: String,
+    token_id: String,
+    side: OrderSide,
+    progress: OrderProgress,
+    // Part of filled_size known only from unconfirmed chain logs; undone on reorg
+    chain_sourced_fill: f64,
 }
 
 #[derive(Debug, Deserialize)]
-struct OrderBookLevel {
+struct MakerOrderFill {
+    order_id: String,
+    matched_amount: String,
     price: String,
+    #[serde(default)]
+    fee_rate_bps: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClobTrade {
+    id: String,
+    taker_order_id: String,
     size: String,
+    price: String,
+    #[serde(default)]
+    fee_rate_bps: String,
+    #[serde(default)]
+    maker_orders: Vec<MakerOrderFill>,
 }
 
 #[derive(Debug, Deserialize)]
-struct OrderBookResponse {
+struct TradesPage {
     #[serde(default)]
-    asks: Vec<OrderBookLevel>,
+    data: Vec<ClobTrade>,
     #[serde(default)]
-    bids: Vec<OrderBookLevel>,
+    next_cursor: String,
 }
 
-#[derive(Debug, Clone, Serialize)]
-struct PolymarketOrder {
-    salt: String,
-    maker: String,
-    signer: String,
-    taker: String,
-    #[serde(rename = "tokenId")]
-    token_id: String,
-    #[serde(rename = "makerAmount")]
-    maker_amount: String,
-    #[serde(rename = "takerAmount")]
-    taker_amount: String,
-    expiration: String,
-    nonce: String,
-    #[serde(rename = "feeRateBps")]
-    fee_rate_bps: String,
-    side: String,
-    #[serde(rename = "signatureType")]
-    signature_type: u8,
+/// One execution of one of our orders, as reported by `/data/trades`.
+#[derive(Debug, Clone)]
+struct OrderFill {
+    trade_id: String,
+    price: f64,
+    size: f64,
+    fee: f64,
 }
 
-#[derive(Debug, Serialize)]
-struct OrderRequest {
-    order: PolymarketOrder,
-    #[serde(rename = "orderType")]
-    order_type: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    owner: Option<String>,
-    signature: String,
+impl OrderFill {
+    fn new(trade_id: &str, price: &str, size: &str, fee_rate_bps: &str) -> Self {
+        let price = price.parse::<f64>().unwrap_or(0.0);
+        let size = size.parse::<f64>().unwrap_or(0.0);
+        let fee_rate = fee_rate_bps.parse::<f64>().unwrap_or(0.0) / 10_000.0;
+        // CLOB fee is charged on the cheaper side of the binary outcome
+        let fee = fee_rate * price.min(1.0 - price) * size;
+        Self { trade_id: trade_id.to_string(), price, size, fee }
+    }
+}
+
+/// Size-weighted average price across fills, None when nothing filled.
+fn average_fill_price(fills: &[OrderFill]) -> Option<f64> {
+    let total_size: f64 = fills.iter().map(|f| f.size).sum();
+    if total_size <= 0.0 {
+        return None;
+    }
+    let notional: f64 = fills.iter().map(|f| f.price * f.size).sum();
+    Some(notional / total_size)
+}
+
+/// Whole shares still to buy of `target` once `bought` have filled. Rounds
+/// down, so a fractional fill can leave a buy short of a share but never
+/// over its target.
+fn shares_left(target: u32, bought: f64) -> u32 {
+    (target as f64 - bought + 1e-6).floor().max(0.0) as u32
+}
+
+/// ", down to $x" when a liquidation ladder reaches under the bid.
+fn ladder_floor(ladder: &[f64], bid: f64) -> String {
+    match ladder.last() {
+        Some(&worst) if worst < bid - 1e-9 => format!(", down to ${:.3}", worst),
+        _ => String::new(),
+    }
 }
 
 #[derive(Debug, Deserialize)]
-struct OrderResponse {
-    #[serde(rename = "orderID")]
-    order_id: Option<String>,
-    #[serde(rename = "errorMsg")]
-    error_msg: Option<String>,
+struct MidpointResponse {
+    mid: String,
 }
 
 #[derive(Debug, Deserialize)]
-struct OrderStatus {
-    status: Option<String>,
-    #[serde(rename = "avgFillPrice")]
-    avg_fill_price: Option<String>,
-    price: Option<String>,
+struct PriceResponse {
+    price: String,
 }
 
-#[derive(Debug, Clone)]
-struct ApiCredentials {
-    api_key: String,
-    secret: String,
-    passphrase: String,
+#[derive(Debug, Deserialize)]
+struct SpreadResponse {
+    spread: String,
 }
 
-// ==========================================
-// 🔐 EIP-712 SIGNING
-// ==========================================
+#[derive(Debug, Serialize)]
+struct BookParams<'a> {
+    token_id: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    side: Option<&'static str>,
+}
 
-struct Eip712Signer {
-    wallet: LocalWallet,
+#[derive(Debug, Deserialize)]
+struct OrderScoringResponse {
+    #[serde(default)]
+    scoring: bool,
 }
 
-impl Eip712Signer {
-    fn new(wallet: LocalWallet) -> Self {
-        Self { wallet }
-    }
+#[derive(Debug, Deserialize)]
+struct Notification {
+    id: u64,
+    #[serde(rename = "type")]
+    notification_type: u32,
+    #[serde(default)]
+    payload: Value,
+}
+
+/// Exchange-side events delivered through `/notifications`.
+#[derive(Debug, Clone, PartialEq)]
+enum ExchangeEvent {
+    OrderCancelled { order_id: String, market: String },
+    OrderFilled { order_id: String, market: String },
+    MarketResolved { market: String },
+    Other { notification_type: u32 },
+}
 
-    fn encode_type(type_name: &str) -> String {
-        format!(
-            "{}(uint256 salt,address maker,address signer,address taker,uint256 tokenId,uint256 makerAmount,uint256 takerAmount,uint256 expiration,uint256 nonce,uint256 feeRateBps,uint256 side,uint256 signatureType)",
-            type_name
-        )
+impl From<&Notification> for ExchangeEvent {
+    fn from(n: &Notification) -> Self {
+        let field = |key: &str| n.payload[key].as_str().unwrap_or_default().to_string();
+        match n.notification_type {
+            1 => ExchangeEvent::OrderCancelled { order_id: field("order_id"), market: field("market") },
+            2 => ExchangeEvent::OrderFilled { order_id: field("order_id"), market: field("market") },
+            4 => ExchangeEvent::MarketResolved { market: field("market") },
+            other => ExchangeEvent::Other { notification_type: other },
+        }
     }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum AssetType {
+    Collateral,
+    Conditional,
+}
 
-    fn hash_type(type_name: &str) -> H256 {
-        H256::from(keccak256(Self::encode_type(type_name).as_bytes()))
+impl AssetType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            AssetType::Collateral => "COLLATERAL",
+            AssetType::Conditional => "CONDITIONAL",
+        }
     }
+}
 
-    fn hash_domain() -> H256 {
-        let domain_separator = format!(
-            "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)"
-        );
-        let domain_type_hash = H256::from(keccak256(domain_separator.as_bytes()));
-        
-        let name_hash = H256::from(keccak256(EIP712_DOMAIN_NAME.as_bytes()));
-        let version_hash = H256::from(keccak256(EIP712_DOMAIN_VERSION.as_bytes()));
-        let chain_id = U256::from(CHAIN_ID);
-        let verifying_contract = Address::from_str(EXCHANGE_CONTRACT).unwrap();
-
-        let mut encoded = Vec::new();
-        encoded.extend_from_slice(domain_type_hash.as_bytes());
-        encoded.extend_from_slice(name_hash.as_bytes());
-        encoded.extend_from_slice(version_hash.as_bytes());
-        
-        let mut chain_id_bytes = [0u8; 32];
-        chain_id.to_big_endian(&mut chain_id_bytes);
-        encoded.extend_from_slice(&chain_id_bytes);
-        
-        let mut contract_bytes = [0u8; 32];
-        contract_bytes[12..].copy_from_slice(verifying_contract.as_bytes());
-        encoded.extend_from_slice(&contract_bytes);
+#[derive(Debug, Deserialize)]
+struct BalanceAllowanceResponse {
+    #[serde(default)]
+    balance: String,
+    #[serde(default)]
+    allowance: Option<String>,
+    // Newer responses report one allowance per exchange contract
+    #[serde(default)]
+    allowances: HashMap<String, String>,
+}
+
+/// Balances in whole units (USDC or shares), converted from 6-decimal raw values.
+#[derive(Debug, Clone, Copy)]
+struct BalanceAllowance {
+    balance: f64,
+    allowance: f64,
+}
 
-        H256::from(keccak256(&encoded))
+impl From<BalanceAllowanceResponse> for BalanceAllowance {
+    fn from(r: BalanceAllowanceResponse) -> Self {
+        let to_units = |raw: &str| raw.parse::<f64>().unwrap_or(0.0) / 1_000_000.0;
+        let allowance = match &r.allowance {
+            Some(a) => to_units(a),
+            None => r.allowances.values().map(|a| to_units(a)).fold(0.0, f64::max),
+        };
+        Self {
+            balance: to_units(&r.balance),
+            allowance,
+        }
     }
+}
 
-    fn hash_struct(&self, order: &PolymarketOrder) -> H256 {
-        let type_hash = Self::hash_type("Order");
-        
-        let salt = U256::from_dec_str(&order.salt).unwrap_or(U256::zero());
-        let maker = Address::from_str(&order.maker).unwrap_or(Address::zero());
-        let signer = Address::from_str(&order.signer).unwrap_or(Address::zero());
-        let taker = Address::from_str(&order.taker).unwrap_or(Address::zero());
-        let token_id = U256::from_dec_str(&order.token_id).unwrap_or(U256::zero());
-        let maker_amount = U256::from_dec_str(&order.maker_amount).unwrap_or(U256::zero());
-        let taker_amount = U256::from_dec_str(&order.taker_amount).unwrap_or(U256::zero());
-        let expiration = U256::from_dec_str(&order.expiration).unwrap_or(U256::zero());
-        let nonce = U256::from_dec_str(&order.nonce).unwrap_or(U256::zero());
-        let fee_rate = U256::from_dec_str(&order.fee_rate_bps).unwrap_or(U256::zero());
-        let side = if order.side == "BUY" { U256::zero() } else { U256::one() };
-        let sig_type = U256::from(order.signature_type);
-
-        let mut encoded = Vec::new();
-        encoded.extend_from_slice(type_hash.as_bytes());
-        
-        let mut temp = [0u8; 32];
-        
-        salt.to_big_endian(&mut temp);
-        encoded.extend_from_slice(&temp);
-        
-        temp = [0u8; 32];
-        temp[12..].copy_from_slice(maker.as_bytes());
-        encoded.extend_from_slice(&temp);
-        
-        temp = [0u8; 32];
-        temp[12..].copy_from_slice(signer.as_bytes());
-        encoded.extend_from_slice(&temp);
-        
-        temp = [0u8; 32];
-        temp[12..].copy_from_slice(taker.as_bytes());
-        encoded.extend_from_slice(&temp);
-        
-        token_id.to_big_endian(&mut temp);
-        encoded.extend_from_slice(&temp);
-        
-        maker_amount.to_big_endian(&mut temp);
-        encoded.extend_from_slice(&temp);
-        
-        taker_amount.to_big_endian(&mut temp);
-        encoded.extend_from_slice(&temp);
-        
-        expiration.to_big_endian(&mut temp);
-        encoded.extend_from_slice(&temp);
-        
-        nonce.to_big_endian(&mut temp);
-        encoded.extend_from_slice(&temp);
-        
-        fee_rate.to_big_endian(&mut temp);
-        encoded.extend_from_slice(&temp);
-        
-        side.to_big_endian(&mut temp);
-        encoded.extend_from_slice(&temp);
-        
-        sig_type.to_big_endian(&mut temp);
-        encoded.extend_from_slice(&temp);
+// ==========================================
+// ⏱️ SERVER CLOCK
+// ==========================================
+
+/// Server time anchored to a monotonic clock: `/time` gives the offset once,
+/// the clock's monotonic `elapsed` carries it forward so local wall-clock
+/// jumps can't skew auth.
+#[derive(Debug, Clone, Copy)]
+struct ServerClock {
+    server_time_at_sync: f64,
+    synced_at: Duration,
+    skew_secs: f64,
+}
 
-        H256::from(keccak256(&encoded))
+impl ServerClock {
+    /// Unsynced clock that simply follows local time.
+    fn local(clock: &dyn Clock) -> Self {
+        Self {
+            server_time_at_sync: clock.unix_secs_f64(),
+            synced_at: clock.elapsed(),
+            skew_secs: 0.0,
+        }
     }
 
-    fn sign_order(&self, order: &PolymarketOrder) -> Result<Signature, Box<dyn std::error::Error>> {
-        let domain_separator = Self::hash_domain();
-        let struct_hash = self.hash_struct(order);
+    fn synced(server_time: f64, round_trip: Duration, clock: &dyn Clock) -> Self {
+        // Assume the server stamped the response halfway through the round trip
+        let server_now = server_time + round_trip.as_secs_f64() / 2.0;
+        Self {
+            server_time_at_sync: server_now,
+            synced_at: clock.elapsed(),
+            skew_secs: clock.unix_secs_f64() - server_now,
+        }
+    }
 
-        let mut message = Vec::new();
-        message.push(0x19);
-        message.push(0x01);
-        message.extend_from_slice(domain_separator.as_bytes());
-        message.extend_from_slice(struct_hash.as_bytes());
+    fn now_secs(&self, clock: &dyn Clock) -> u64 {
+        (self.server_time_at_sync + clock.elapsed().saturating_sub(self.synced_at).as_secs_f64()) as u64
+    }
 
-        let message_hash = H256::from(keccak256(&message));
-        
-        let signature = self.wallet.sign_hash(message_hash)?;
-        Ok(signature)
+    fn needs_resync(&self, clock: &dyn Clock, interval: Duration) -> bool {
+        clock.elapsed().saturating_sub(self.synced_at) > interval
     }
 }
 
@@ -303,34 +410,171 @@ impl Eip712Signer {
 
 struct EthNoTrendBot {
     client: Client,
+    // BOT_CHAOS fault injection on API requests; off unless set
+    chaos: Option<Chaos>,
     wallet: LocalWallet,
     signer: Eip712Signer,
     trading_address: Address,
     use_proxy: bool,
     signature_type: u8,
     active_trade: bool,
-    traded_markets: HashSet<String>,
+    // Account this instance trades; its files live in profile.data_dir
+    profile: Profile,
+    traded_markets: TradedMarkets,
+    // Queryable copy of trades, fills, aborts and traded markets; BOT_DB="" turns it off
+    #[cfg(feature = "sqlite")]
+    store: Option<Store>,
+    // Which cycles run() may pick up; every cycle by default
+    schedule: Schedule,
+    // Cycle length and slug template of the traded markets
+    market_schedule: MarketSchedule,
+    // Looks each cycle's market up by slug, or discovers it on Gamma
+    finder: MarketFinder,
+    // risk.max_open_positions, shared with the other markets' threads
+    position_limit: Arc<PositionLimit>,
+    // Cancel every open order at startup; off for all but one of the
+    // threads sharing an account
+    cancel_on_startup: bool,
+    // Markets worked this run as (slug, condition id, close time); their
+    // leftover orders are canceled once they close
+    closing_markets: Vec<(String, String, u64)>,
+    // Sleep lengths: fast polls inside the window, boundary-exact outside
+    cadence: Cadence,
+    // Resolves the next cycles' markets while the current one plays out
+    scanner: MarketScanner,
+    // Live books over the CLOB WebSocket; None with POLY_WS_URL empty
+    stream: Option<MarketStream>,
+    // BOT_RECORD_DIR: books and trades seen while monitoring, as JSON lines
+    recorder: Option<RefCell<BookRecorder>>,
+    // --log-format json: events, notifications and trades as JSON lines on stderr
+    json_log: Option<JsonLog>,
+    // Last cycle reported as skipped, so the notice prints once
+    skipped_cycle: u64,
+    // UTC day the run loop last saw; its summary goes out once the day changes
+    report_day: String,
+    // Console only; files always get RFC3339 UTC
+    display_tz: DisplayTz,
+    // Collapses repeated warnings during API incidents
+    events: RefCell<EventLog>,
+    // BOT_ALERTS price triggers on the monitored market
+    alerts: AlertWatcher,
+    // BOT_RULES operational policies, fed with API and order outcomes
+    rules: RefCell<RuleEngine>,
+    // Outbound channels, each with its own minimum severity
+    notifiers: Router,
+    // SSE subscribers on BOT_EVENTS_ADDR
+    #[cfg(feature = "event-stream")]
+    event_stream: Option<EventServer>,
+    // Double-entry record of fills, fees, redemptions and transfers
+    ledger: RefCell<Ledger>,
+    // Journal of entries and exits, in the columns BOT_LOG_COLUMNS asks for
+    trade_log: TradeLog,
+    // Entries still held, by token id, and positions closed this session
+    open_entries: RefCell<HashMap<String, OpenEntry>>,
+    closed_trades: RefCell<Vec<TradeResult>>,
+    // Cost basis of what's held, marked to the bid, and PnL realized this session
+    pnl: RefCell<PnlTracker>,
     api_creds: ApiCredentials,
+    // --paper: fills simulated against the live book, by paper order id
+    paper_fills: RefCell<FillSimulator>,
+    paper_orders: RefCell<HashMap<String, OrderProgress>>,
+    // (asset type, token id) -> (fetched at, value)
+    balance_cache: RefCell<HashMap<(AssetType, String), (Duration, BalanceAllowance)>>,
+    // Reused across polls so deep books don't reallocate every tick
+    book_buffer: RefCell<Vec<u8>>,
+    clock: ServerClock,
+    // Real time, or simulated when BOT_SIM_START is set
+    time: Box<dyn Clock>,
+    // Simulation runs exit once the clock passes BOT_SIM_END
+    stop_at: Option<u64>,
+    // Orders whose POST outcome is unknown, by client order id
+    pending_submissions: RefCell<HashMap<String, SubmittedOrder>>,
+    // Persisted lifecycle of every order placed, created through filled
+    orders: RefCell<OrderJournal>,
+    // Accepted orders and how much of each has filled, by order id
+    tracked_orders: RefCell<HashMap<String, TrackedOrder>>,
+    // Net shares held per token, updated incrementally as fills arrive
+    positions: RefCell<HashMap<String, f64>>,
+    // Condition id and outcome of every token monitored, so YES and NO
+    // holdings of one market net against each other
+    token_markets: RefCell<HashMap<String, (String, Outcome)>>,
+    rpc: RpcClient,
+    // Independent fill channel from exchange OrderFilled logs
+    #[cfg(feature = "fill-watch")]
+    fill_watcher: RefCell<Option<FillWatcher>>,
+    // Shares per order id confirmed on-chain
+    #[cfg(feature = "fill-watch")]
+    chain_fills: RefCell<HashMap<String, f64>>,
+    // Approvals, merges and redemptions go through here
+    tx_manager: TxManager,
+    // Set by integrations that can convert native USDC into exchange collateral
+    collateral_swap_hook: Option<Box<dyn CollateralSwapHook>>,
+    // UMA lifecycle of markets we hold positions in
+    #[cfg(feature = "resolution")]
+    resolution_watcher: ResolutionWatcher,
+    #[cfg(feature = "resolution")]
+    last_resolution_poll: u64,
+    last_market_sync: u64,
+    // Lowered below the configured size while collateral is under risk.low_balance_threshold
+    max_position_size: Cell<u32>,
+    network: NetworkProfile,
+    // Set when the exchange is paused or the CLOB is down; no orders go out
+    exchange_halted: Cell<bool>,
+    last_status_check: Cell<u64>,
+    strategy: StrategyParams,
+    config: Config,
 }
 
 impl EthNoTrendBot {
-    fn new() -> Result<Self, Box<dyn std::error::Error>> {
+    fn new(mut profile: Profile, config: Config) -> Result<Self, Box<dyn std::error::Error>> {
         println!("🤖 ETH No Trend Bot Starting...");
-        println!("📊 Configuration:");
-        println!("   Trade Side: {}", TRADE_SIDE);
-        println!("   Entry Price: ${}", ENTRY_PRICE);
-        println!("   Stop Loss: ${}", STOP_LOSS_PRICE);
-        println!("   Position Size: {} shares", POSITION_SIZE);
-        println!("   Trading Window: Last {}s of market", MARKET_WINDOW);
-        println!("   🚨 ABORT Trigger: ASK > ${}\n", ABORT_ASK_PRICE);
-
-        if !["YES", "NO", "BOTH"].contains(&TRADE_SIDE) {
-            return Err(format!("❌ Invalid TRADE_SIDE: {}. Must be 'YES', 'NO', or 'BOTH'", TRADE_SIDE).into());
+        if !profile.is_default() {
+            println!("👤 Profile: {} ({})", profile.name, profile.data_dir.display());
+        }
+        let problems = config.validate();
+        if !problems.is_empty() {
+            return Err(format!("Invalid config {}: {}", config.source.as_deref().unwrap_or("defaults"), problems.join("; ")).into());
+        }
+        let strategy = strategy_params(&config)?;
+        let market_schedule = config.market.schedule()?;
+        let finder = config.market.finder()?;
+        println!("📊 Configuration{}:", config.source.as_ref().map(|p| format!(" ({})", p)).unwrap_or_default());
+        if finder.is_discovering() {
+            println!("   Market: discovered on Gamma (tag '{}', series '{}') every {}",
+                config.market.tag, config.market.series_id, market_schedule::cadence_label(market_schedule.cadence));
+        } else {
+            println!("   Market: {} every {}", market_schedule.pattern(), market_schedule::cadence_label(market_schedule.cadence));
+        }
+        println!("   Trade Side: {}", strategy.trade_side.as_str());
+        println!("   Entry Price: ${}", strategy.entry_price);
+        println!("   Stop Loss: ${}{}", config.strategy.stop_loss_price,
+            config.strategy.trailing_stop.map(|t| format!(", trailing {} once {} up", t.trail, t.activation)).unwrap_or_default());
+        if config.strategy.exit_before_close > 0 {
+            println!("   Forced Exit: {}s before close", config.strategy.exit_before_close);
+        }
+        println!("   Partial Fills: {}", config.strategy.partial_fill.as_str());
+        if !config.strategy.tranches.is_empty() {
+            println!("   Tranches: {}", config.strategy.tranches.iter()
+                .map(|t| format!("{}% @ ${}", t.fraction * 100.0, t.price)).collect::<Vec<_>>().join(", "));
+        }
+        match config.strategy.sizing {
+            Some(s) => println!("   Position Size: {}% of USDC balance, {}-{} shares", s.balance_pct, s.min_shares, s.max_shares),
+            None => println!("   Position Size: {} shares", strategy.position_size),
+        }
+        println!("   Trading Window: Last {}s of market", strategy.market_window);
+        println!("   🚨 ABORT Trigger: ASK > ${}\n", strategy.abort_ask_price);
+        if config.dry_run {
+            println!("🧪 DRY RUN: orders are printed, never submitted\n");
+        }
+        if config.paper {
+            // Paper fills must never mix with the real ledger and journals
+            profile.data_dir = profile.data_dir.join("paper");
+            println!("📄 PAPER TRADING: orders fill against the live book, never submitted; logs in {}\n", profile.path(""));
         }
 
-        let wallet = PRIVATE_KEY.parse::<LocalWallet>()?;
+        let wallet = wallet_from_env(&profile)?;
         let wallet_address = wallet.address();
-        let polymarket_addr = Address::from_str(POLYMARKET_ADDRESS)?;
+        let polymarket_addr = Address::from_str(&trading_address_from_env(&profile)?)?;
 
         let (use_proxy, signature_type, trading_address) = if wallet_address == polymarket_addr {
             (false, 0, wallet_address)
@@ -338,504 +582,4671 @@ impl EthNoTrendBot {
             (true, 1, polymarket_addr)
         };
 
-        init_csv_log()?;
+        std::fs::create_dir_all(&profile.data_dir)?;
         
-        let signer = Eip712Signer::new(wallet.clone());
+        let network = NetworkProfile::from_env()?;
+        println!("🌐 Network: {} (chain {})", network.name, network.chain_id);
+
+        let signer = Eip712Signer::new(wallet.clone(), network.chain_id, network.exchange);
         
-        // Get API credentials from environment (pre-generated from Python)
-        let api_creds = ApiCredentials {
-            api_key: std::env::var("POLY_API_KEY")
-                .expect("POLY_API_KEY not set"),
-            secret: std::env::var("POLY_API_SECRET")
-                .expect("POLY_API_SECRET not set"),
-            passphrase: std::env::var("POLY_API_PASSPHRASE")
-                .expect("POLY_API_PASSPHRASE not set"),
-        };
+        // Pre-generated L2 credentials, or None to derive them once the bot is up
+        let env_creds = api_creds_from_env(&profile)?;
         
-        println!("✅ Using API credentials from environment");
-        println!("✅ Client Ready. Trading as: {:?}\n", trading_address);
+        let rpc = RpcClient::new(&rpc_urls_from_env(&network))?;
+        for (url, head) in rpc.pool().health_check() {
+            match head {
+                Some(head) => println!("   🌐 RPC {} at block {}", url, head),
+                None => println!("   ⚠️ RPC {} unreachable", url),
+            }
+        }
+        #[cfg(feature = "fill-watch")]
+        let fill_watcher = match rpc.block_number() {
+            Ok(head) => Some(FillWatcher::new(network.exchange, trading_address, head)),
+            Err(e) => {
+                println!("⚠️ RPC unavailable ({}); on-chain fill confirmation disabled", e);
+                None
+            }
+        };
+
+        let tx_manager = TxManager::new(wallet.clone(), network.chain_id, TxConfig::from_env());
+
+        let time = clock::from_env()?;
+        let stop_at = match std::env::var("BOT_SIM_END") {
+            Ok(v) => Some(v.parse::<u64>().map_err(|_| format!("Invalid BOT_SIM_END '{}'", v))?),
+            Err(_) => None,
+        };
+        let display_tz = DisplayTz::from_env()?;
+        if time.is_simulated() {
+            println!("🧪 Simulated clock starting at {}", display_tz.datetime(time.now_secs()));
+        }
+
+        let traded_markets_file = profile.path(TRADED_MARKETS_FILE);
+        let mut traded_markets = TradedMarkets::load(&traded_markets_file)
+            .map_err(|e| format!("Cannot read {}: {}", traded_markets_file, e))?;
+        #[cfg(feature = "sqlite")]
+        let store = match profile.var("BOT_DB").unwrap_or_else(|| profile.path(DB_FILE)) {
+            path if path.is_empty() => None,
+            // A dry run leaves an out-of-date file alone and runs without it
+            path if config.dry_run && !store::pending(&path)?.is_empty() => {
+                println!("🧪 Dry run: {} not migrated; pending:", path);
+                store::pending(&path)?.iter().for_each(|m| println!("   v{} {}", m.version, m.name));
+                None
+            }
+            path => {
+                let store = Store::open(&path)?;
+                for m in store.migrated() {
+                    println!("🗄️ Migrated {} to schema v{} ({})", path, m.version, m.name);
+                }
+                // The database survives a lost or stale JSON file
+                let since = time.now_secs().saturating_sub(config.timing.traded_markets_ttl);
+                for (slug, market) in store.traded_markets(since)? {
+                    traded_markets.insert(&slug, market.marked_at, &market.reason);
+                }
+                println!("🗄️ Database: {}", path);
+                Some(store)
+            }
+        };
+        let pruned = traded_markets.prune(time.now_secs(), config.timing.traded_markets_ttl);
+        if !traded_markets.markets.is_empty() || pruned > 0 {
+            println!("📒 {} market(s) already traded (dropped {} stale)", traded_markets.markets.len(), pruned);
+        }
+
+        let events = EventLog::from_env()?;
+        let alerts = AlertWatcher::from_env()?;
+        if !alerts.is_empty() {
+            println!("🔔 {} price alert(s) armed", alerts.rules().len());
+        }
+        let rules = RuleEngine::from_env()?;
+        if !rules.is_empty() {
+            println!("📏 {} operational rule(s) active", rules.rules().len());
+        }
+        #[cfg(feature = "event-stream")]
+        let event_stream = match profile.var("BOT_EVENTS_ADDR") {
+            Some(addr) => {
+                let server = EventServer::start(&addr).map_err(|e| format!("Cannot serve events on {}: {}", addr, e))?;
+                println!("📡 Streaming events on http://{}/events", server.local_addr());
+                Some(server)
+            }
+            None => None,
+        };
+        let notifiers = notifiers_from_env(&profile)?;
+        for (name, min) in notifiers.channels() {
+            println!("📣 Notifications: {} ({} and above)", name, min);
+        }
+        let ledger = Ledger::load(&profile.path(LEDGER_FILE))?;
+        let orders = OrderJournal::load(&profile.path(ORDERS_FILE))?;
+        let trade_log = trade_log_from_env(&profile)?;
+        if let Some(moved) = trade_log.init(time.now_secs())? {
+            println!("📝 Trade log columns changed; previous log moved to {}", moved);
+        }
+        let schedule = Schedule::from_env(market_schedule.cadence)?;
+        let cadence = Cadence { cycle: market_schedule.cadence, ..Cadence::from_env()? };
+        let scanner = MarketScanner::spawn(http_client(config.timing.http_timeout)?, &network.gamma_url, finder.clone(), market_schedule.cadence, lookahead_from_env()?)?;
+        let stream = match network.ws_url.as_str() {
+            "" => None,
+            url => {
+                println!("📡 Streaming order books from {}", url);
+                Some(MarketStream::spawn(url)?)
+            }
+        };
+        let recorder = match profile.var("BOT_RECORD_DIR") {
+            Some(dir) => {
+                let recorder = BookRecorder::new(profile.path(&dir)).map_err(|e| format!("Cannot record to BOT_RECORD_DIR {}: {}", dir, e))?;
+                println!("🎞️ Recording books to {}", recorder.dir().display());
+                Some(RefCell::new(recorder))
+            }
+            None => None,
+        };
+        let chaos = chaos_from_env()?;
+        if let Some(chaos) = &chaos {
+            println!("🧪 Chaos mode: injecting faults into API requests ({})", chaos);
+        }
+        if !schedule.is_unrestricted() {
+            println!("📅 Trading schedule active (BOT_SCHEDULE/BOT_SKIP_DATES/BOT_SKIP_AFTER)");
+        }
 
-        Ok(Self {
-            client: Client::builder()
-                .timeout(Duration::from_secs(30))
-                .build()?,
+        let json_log = (config.log_format == LogOutput::Json).then(|| JsonLog::new(&profile.name));
+        let mut bot = Self {
+            client: http_client(config.timing.http_timeout)?,
+            chaos,
             wallet,
             signer,
             trading_address,
             use_proxy,
             signature_type,
             active_trade: false,
-            traded_markets: HashSet::new(),
-            api_creds,
-        })
+            profile,
+            traded_markets,
+            #[cfg(feature = "sqlite")]
+            store,
+            schedule,
+            market_schedule,
+            finder,
+            position_limit: Arc::new(PositionLimit::new(config.risk.max_open_positions)),
+            cancel_on_startup: true,
+            closing_markets: Vec::new(),
+            cadence,
+            scanner,
+            stream,
+            recorder,
+            json_log,
+            skipped_cycle: 0,
+            report_day: report::day_of(time.now_secs()),
+            display_tz,
+            events: RefCell::new(events),
+            alerts,
+            rules: RefCell::new(rules),
+            notifiers,
+            #[cfg(feature = "event-stream")]
+            event_stream,
+            ledger: RefCell::new(ledger),
+            trade_log,
+            open_entries: RefCell::new(HashMap::new()),
+            pnl: RefCell::new(PnlTracker::new()),
+            closed_trades: RefCell::new(Vec::new()),
+            paper_fills: RefCell::new(FillSimulator::new(FillModel::default())),
+            paper_orders: RefCell::new(HashMap::new()),
+            api_creds: env_creds.clone().unwrap_or(ApiCredentials { api_key: String::new(), secret: String::new(), passphrase: String::new() }),
+            balance_cache: RefCell::new(HashMap::new()),
+            book_buffer: RefCell::new(Vec::with_capacity(64 * 1024)),
+            clock: ServerClock::local(time.as_ref()),
+            time,
+            stop_at,
+            pending_submissions: RefCell::new(HashMap::new()),
+            orders: RefCell::new(orders),
+            tracked_orders: RefCell::new(HashMap::new()),
+            positions: RefCell::new(HashMap::new()),
+            token_markets: RefCell::new(HashMap::new()),
+            rpc,
+            #[cfg(feature = "fill-watch")]
+            fill_watcher: RefCell::new(fill_watcher),
+            #[cfg(feature = "fill-watch")]
+            chain_fills: RefCell::new(HashMap::new()),
+            tx_manager,
+            collateral_swap_hook: None,
+            #[cfg(feature = "resolution")]
+            resolution_watcher: ResolutionWatcher::default(),
+            #[cfg(feature = "resolution")]
+            last_resolution_poll: 0,
+            last_market_sync: 0,
+            max_position_size: Cell::new(config.strategy.sizing.map_or(strategy.position_size, |s| s.max_shares)),
+            network,
+            exchange_halted: Cell::new(false),
+            last_status_check: Cell::new(0),
+            strategy,
+            config,
+        };
+
+        if env_creds.is_some() {
+            println!("✅ Using API credentials from environment");
+        } else {
+            // L1 auth timestamps must be close to the server's
+            bot.sync_server_clock();
+            bot.api_creds = bot.create_or_derive_api_creds()?;
+        }
+        println!("✅ Client Ready. Trading as: {:?}\n", trading_address);
+        Ok(bot)
     }
 
-    fn create_auth_headers(&self, method: &str, request_path: &str, body: &str) -> Result<HeaderMap, Box<dyn std::error::Error>> {
-        let mut headers = HeaderMap::new();
-        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
-        
-        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs().to_string();
-        
-        // Create signature EXACTLY like Python: timestamp + method + requestPath + body
-        let message = format!("{}{}{}{}", timestamp, method.to_uppercase(), request_path, body);
-        
-        // HMAC-SHA256 signature
-        type HmacSha256 = Hmac<Sha256>;
-        let mut mac = HmacSha256::new_from_slice(self.api_creds.secret.as_bytes())
-            .map_err(|_| "Invalid HMAC key")?;
-        mac.update(message.as_bytes());
-        let signature = mac.finalize();
-        let sig_base64 = general_purpose::STANDARD.encode(signature.into_bytes());
-        
-        // Match Python headers EXACTLY
-        headers.insert("POLY-ADDRESS", HeaderValue::from_str(&format!("{:?}", self.wallet.address()).to_lowercase())?);
-        headers.insert("POLY-SIGNATURE", HeaderValue::from_str(&sig_base64)?);
-        headers.insert("POLY-TIMESTAMP", HeaderValue::from_str(&timestamp)?);
-        headers.insert("POLY-NONCE", HeaderValue::from_str(&timestamp)?);
-        headers.insert("POLY-API-KEY", HeaderValue::from_str(&self.api_creds.api_key)?);
-        headers.insert("POLY-PASSPHRASE", HeaderValue::from_str(&self.api_creds.passphrase)?);
-        
-        Ok(headers)
+    /// Remember that this market is done and persist it right away, so a
+    /// restart in the same window doesn't trade it again.
+    fn mark_traded(&mut self, slug: &str, reason: &str) {
+        self.traded_markets.insert(slug, self.time.now_secs(), reason);
+        #[cfg(feature = "sqlite")]
+        self.with_store(|store| store.mark_traded(slug, self.time.now_secs(), reason));
+        self.emit(BotEvent::State { market: slug.to_string(), state: reason.to_string() });
+        // Entries and aborts get their own, more detailed notification
+        if !matches!(reason, "entered" | "aborted") {
+            let severity = if matches!(reason, "entry_failed" | "halted") { Severity::Warning } else { Severity::Info };
+            self.notify(severity, Event::MarketDone, &format!("Market {}", reason), slug);
+        }
+        let path = self.profile.path(TRADED_MARKETS_FILE);
+        if let Err(e) = self.traded_markets.save(&path) {
+            println!("\n⚠️ Could not save {}: {}", path, e);
+        }
     }
 
-    fn get_order_book_depth(&self, token_id: &str) -> Option<OrderBook> {
-        for attempt in 1..=3 {
-            match self.fetch_order_book(token_id) {
-                Ok(book) => return Some(book),
-                Err(e) => {
-                    if attempt < 3 {
-                        thread::sleep(Duration::from_secs(1));
-                    }
-                }
-            }
+    /// Print a warning unless an identical one was shown recently; see
+    /// `event_log`. Summaries of quieted repeats come out first.
+    fn warn(&self, message: String) {
+        let now = self.time.now_secs();
+        let mut events = self.events.borrow_mut();
+        for summary in events.flush(now) {
+            println!("   {}", summary);
+        }
+        if events.check(&message, now) == Emit::Show {
+            println!("{}", message);
+            drop(events);
+            self.notify(Severity::Warning, Event::Error, "Warning", message.trim());
         }
-        None
     }
 
-    fn fetch_order_book(&self, token_id: &str) -> Result<OrderBook, Box<dyn std::error::Error>> {
-        let url = format!("{}/book?token_id={}", HOST, token_id);
-        let resp: OrderBookResponse = self.client.get(&url).send()?.json()?;
-
-        let (best_ask, ask_size) = if let Some(ask) = resp.asks.iter()
-            .min_by(|a, b| a.price.parse::<f64>().unwrap_or(f64::MAX)
-                .partial_cmp(&b.price.parse::<f64>().unwrap_or(f64::MAX))
-                .unwrap()) {
-            (Some(ask.price.parse::<f64>()?), ask.size.parse::<f64>()?)
-        } else {
-            (None, 0.0)
-        };
+    /// Send to the configured channels; delivery problems are only printed,
+    /// since they must never interrupt trading.
+    fn notify(&self, severity: Severity, event: Event, title: &str, body: &str) {
+        if self.notifiers.is_empty() && self.json_log.is_none() {
+            return;
+        }
+        let notification = notify::Notification::new(severity, title, body, self.time.now_secs()).with_event(event.as_str());
+        if let Some(log) = &self.json_log {
+            log.write(&log.notification(&notification));
+        }
+        for (channel, e) in self.notifiers.notify(&notification) {
+            println!("\n   ⚠️ {} notification failed: {}", channel, e);
+        }
+    }
 
-        let (best_bid, bid_size) = if let Some(bid) = resp.bids.iter()
-            .max_by(|a, b| a.price.parse::<f64>().unwrap_or(0.0)
-                .partial_cmp(&b.price.parse::<f64>().unwrap_or(0.0))
-                .unwrap()) {
-            (Some(bid.price.parse::<f64>()?), bid.size.parse::<f64>()?)
-        } else {
-            (None, 0.0)
-        };
+    fn sample(&self, sample: Sample) {
+        self.rules.borrow_mut().record(self.time.now_secs(), sample);
+    }
 
-        Ok(OrderBook {
-            best_ask,
-            ask_size,
-            best_bid,
-            bid_size,
-        })
+    /// Evaluate BOT_RULES and carry out the actions of any that just fired.
+    fn apply_rules(&self) {
+        // Complete sets are worth $1 whatever happens; only net exposure counts
+        let holding = self.exposure().iter().any(|m| m.directional().is_some());
+        let transitions = self.rules.borrow_mut().evaluate(self.time.now_secs(), holding);
+        for transition in transitions {
+            let rule = match &transition {
+                Transition::Fired(i, _) | Transition::Cleared(i) => self.rules.borrow().rules()[*i].clone(),
+            };
+            let Transition::Fired(_, value) = transition else {
+                println!("\n✅ Rule cleared: {}", rule.text);
+                if rule.has(Action::Pause) {
+                    self.notify(Severity::Info, Event::Resumed, &format!("Rule cleared: {}", rule.text), "Entries allowed again");
+                }
+                continue;
+            };
+
+            println!("\n📏 RULE [{}] fired at {:.3}", rule.text, value);
+            let body = format!("{} at {:.3}{}", rule.text, value, if rule.has(Action::Pause) { "; entries paused" } else { "" });
+            if rule.has(Action::Page) {
+                self.notify(Severity::Critical, Event::RuleFired, &format!("Rule fired: {}", rule.text), &body);
+            } else if rule.has(Action::Notify) {
+                self.notify(Severity::Warning, Event::RuleFired, &format!("Rule fired: {}", rule.text), &body);
+            }
+            if rule.has(Action::Pause) {
+                println!("   ⏸️ New entries paused until the rule clears");
+            }
+            if rule.has(Action::Liquidate) {
+                self.liquidate();
+            }
+        }
     }
 
-    fn get_market_from_slug(&self, slug: &str) -> Option<MarketData> {
-        for attempt in 1..=3 {
-            match self.fetch_market_data(slug) {
-                Ok(Some(market)) => return Some(market),
-                Ok(None) => return None,
-                Err(_) => {
-                    if attempt < 3 {
-                        thread::sleep(Duration::from_secs(3));
-                    }
+    /// Sell every held position into the bid.
+    fn liquidate(&self) {
+        let held: Vec<(String, f64)> = self.positions.borrow().iter()
+            .filter(|(_, shares)| **shares >= 1.0)
+            .map(|(token, shares)| (token.clone(), *shares))
+            .collect();
+        let mut unsold = Vec::new();
+        for (token_id, shares) in &held {
+            let Some(bid) = self.get_order_book_depth(token_id).and_then(|b| b.best_bid) else {
+                self.warn(format!("\n   ⚠️ Cannot liquidate {}: no bid", token_id));
+                unsold.push(token_id.clone());
+                continue;
+            };
+            if let Some(working) = self.working_order(token_id, OrderSide::Sell) {
+                self.warn(format!("\n   ⚠️ Not liquidating {}: sell {} already working", token_id, working.id));
+                unsold.push(token_id.clone());
+                continue;
+            }
+            let ladder = self.sell_ladder(bid);
+            println!("   🔻 Liquidating {:.2} shares of {} @ ${:.3}{}", shares, token_id, bid, ladder_floor(&ladder, bid));
+            self.pnl.borrow_mut().mark(token_id, bid);
+            match self.sell_down_ladder(token_id, &ladder, shares.floor() as u32) {
+                Ok(Some(fill)) if fill.remaining < 1.0 => {}
+                Ok(Some(fill)) => {
+                    self.warn(format!("\n   ⚠️ Liquidated only {:.2} of {} ({:.2} left)", fill.filled_size, token_id, fill.remaining));
+                    unsold.push(token_id.clone());
+                }
+                Ok(None) if self.config.dry_run => {}
+                Ok(None) => unsold.push(token_id.clone()),
+                Err(e) => {
+                    self.warn(format!("\n   ⚠️ Liquidation of {} failed: {}", token_id, e));
+                    unsold.push(token_id.clone());
                 }
             }
         }
-        None
+        self.notify_liquidation(held.len(), &unsold);
     }
 
-    fn fetch_market_data(&self, slug: &str) -> Result<Option<MarketData>, Box<dyn std::error::Error>> {
-        let url = format!("{}/events?slug={}", GAMMA_API_URL, slug);
-        let resp = self.client.get(&url).timeout(Duration::from_secs(10)).send()?;
+    /// The ladder exits and liquidations sell down under `bid`, from `[risk]`.
+    fn sell_ladder(&self, bid: f64) -> Vec<f64> {
+        strategy::sell_ladder(bid, self.config.risk.liquidation_slippage, self.config.risk.liquidation_rungs)
+    }
 
-        if resp.status() == 404 {
-            return Ok(None);
+    /// Sell `shares` with a FAK at each rung of `ladder` in turn until
+    /// they're gone. Every rung sweeps the bids at or above its price, so a
+    /// thin top of book only costs the levels actually taken. Returns what
+    /// all the rungs sold together, at their average price.
+    fn sell_down_ladder(&self, token_id: &str, ladder: &[f64], shares: u32) -> Result<Option<Fill>, Box<dyn std::error::Error>> {
+        let (mut sold, mut proceeds) = (0.0, 0.0);
+        let mut last_order = String::new();
+        for (rung, &price) in ladder.iter().enumerate() {
+            let left = (shares as f64 - sold).floor() as u32;
+            if left == 0 {
+                break;
+            }
+            if rung > 0 {
+                println!("   🪜 {} left; stepping down to ${:.3}", left, price);
+            }
+            match self.place_order(token_id, price, left, OrderSide::Sell, "FAK") {
+                Ok(Some(fill)) => {
+                    sold += fill.filled_size;
+                    proceeds += fill.filled_size * fill.avg_price;
+                    last_order = fill.order_id;
+                }
+                Ok(None) if self.config.dry_run => return Ok(None),
+                Ok(None) => {}
+                Err(e) if sold == 0.0 => return Err(e),
+                Err(e) => {
+                    self.warn(format!("   ⚠️ Sell at ${:.3} failed: {}", price, e));
+                    break;
+                }
+            }
         }
+        Ok((sold > 0.0).then(|| Fill {
+            order_id: last_order,
+            filled_size: sold,
+            avg_price: proceeds / sold,
+            remaining: (shares as f64 - sold).max(0.0),
+        }))
+    }
 
-        let data: Vec<Value> = resp.json()?;
-        
-        if data.is_empty() {
-            return Ok(None);
+    /// How a liquidation went; critical when something was left unsold.
+    fn notify_liquidation(&self, positions: usize, unsold: &[String]) {
+        if positions == 0 {
+            return;
         }
-
-        let event = &data[0];
-        let markets = event["markets"].as_array().ok_or("No markets found")?;
-        
-        if markets.is_empty() {
-            return Ok(None);
+        let pnl = self.pnl.borrow().summary();
+        if unsold.is_empty() {
+            self.notify(Severity::Info, Event::Liquidation, &format!("Liquidated {} position(s)", positions),
+                &format!("Everything sold into the bid; {}", pnl));
+        } else {
+            self.notify(Severity::Critical, Event::Liquidation, &format!("Liquidated {} of {} position(s)", positions - unsold.len(), positions),
+                &format!("Still held: {}; {}", unsold.join(", "), pnl));
         }
+    }
 
-        let market_data = &markets[0];
-        
-        let enable_orderbook = market_data["enableOrderBook"].as_bool().unwrap_or(false);
-        
-        if !enable_orderbook {
-            return Ok(None);
+    /// Score a sale against the entry that opened the position, and refresh
+    /// the session's performance figures. Returns the PnL it realized, net
+    /// of entry and exit fees.
+    fn record_close(&self, token_id: &str, order_id: &str, progress: &OrderProgress) -> f64 {
+        let pnl = self.pnl.borrow_mut().take_realized(token_id);
+        let Some(entry) = self.open_entries.borrow_mut().remove(token_id) else { return pnl };
+        // Earlier partial sells, e.g. the upper rungs of a ladder, count too
+        let size = entry.sold + progress.filled_size;
+        let price = (entry.proceeds + progress.filled_size * progress.avg_price) / size;
+        let (sl_time, sl_price, notes) = match entry.exit {
+            Some(ExitReason::StopLoss { price, at }) => (Some(at), Some(price), format!("Stop loss at ${:.3}", price)),
+            Some(ExitReason::BeforeClose { left, .. }) => (None, None, format!("Forced exit {}s before close", left)),
+            None => (None, None, String::new()),
+        };
+        self.log_trade(TradeRecord {
+            title: entry.title,
+            link: entry.link,
+            status: "EXITED".to_string(),
+            market: entry.slug.clone(),
+            side: entry.side.clone(),
+            token_id: token_id.to_string(),
+            order_id: order_id.to_string(),
+            entry_time: entry.entered_at,
+            entry_price: Some(entry.price),
+            size: Some(size),
+            sl_time,
+            sl_price,
+            sl_triggered: sl_time.map(|_| true),
+            exit_time: Some(self.time.now_secs()),
+            exit_price: Some(price),
+            fees: Some(entry.exit_fees + progress.fee),
+            pnl: Some(pnl),
+            notes,
+            ..Default::default()
+        });
+        let mut closed = self.closed_trades.borrow_mut();
+        closed.push(TradeResult {
+            strategy: entry.side,
+            asset: performance::asset_of(&entry.slug),
+            ts: self.time.now_secs(),
+            pnl,
+            cost: entry.price * size,
+        });
+        let report = PerformanceReport::new(&closed);
+        let o = &report.overall;
+        println!("   📈 Session: {} closed, win rate {:.0}%, PnL ${:+.2}, max drawdown ${:.2}", o.trades, o.win_rate * 100.0, o.total_pnl, o.max_drawdown);
+        #[cfg(feature = "event-stream")]
+        if let Some(stream) = &self.event_stream {
+            stream.set_metrics(serde_json::to_value(&report).unwrap_or(Value::Null));
         }
-        
-        let token_ids: Vec<String> = serde_json::from_str(
-            market_data["clobTokenIds"].as_str().ok_or("Invalid clobTokenIds")?
-        )?;
+        pnl
+    }
 
-        if token_ids.len() < 2 {
-            return Ok(None);
+    /// Hold on to a sell that filled only partly until the close is
+    /// recorded, so the EXITED row covers every share sold.
+    fn record_partial_exit(&self, token_id: &str, progress: &OrderProgress) {
+        if let Some(entry) = self.open_entries.borrow_mut().get_mut(token_id) {
+            entry.sold += progress.filled_size;
+            entry.proceeds += progress.filled_size * progress.avg_price;
+            entry.exit_fees += progress.fee;
         }
+    }
 
-        let title = event["title"].as_str().unwrap_or(slug).to_string();
-        println!("   ✅ Market found: {}", title);
-
-        Ok(Some(MarketData {
-            slug: slug.to_string(),
-            title,
-            link: format!("https://polymarket.com/event/{}", slug),
-            yes_token: token_ids[0].clone(),
-            no_token: token_ids[1].clone(),
-        }))
+    /// " | PnL …" for the status line, once there's anything to show.
+    fn pnl_status(&self) -> String {
+        let pnl = self.pnl.borrow();
+        if pnl.is_empty() { String::new() } else { format!(" | {}", pnl.summary()) }
     }
 
-    fn place_order(&self, token_id: &str, price: f64, size: u32, side: &str, order_type: &str) 
-        -> Result<(Option<String>, Option<f64>), Box<dyn std::error::Error>> {
-        
-        println!("📝 Placing {} {} order: {} shares @ ${:.3}", side, order_type, size, price);
-        
-        let rounded_price = (price * 100.0).round() / 100.0;
-        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
-        
-        let maker_amount = (size as u64) * 1_000_000;
-        let price_in_usdc = (rounded_price * 1_000_000.0) as u64;
-        let taker_amount = (size as u64) * price_in_usdc;
-        
-        let order = PolymarketOrder {
-            salt: timestamp.to_string(),
-            maker: format!("{:?}", self.trading_address).to_lowercase(),
-            signer: format!("{:?}", self.wallet.address()).to_lowercase(),
-            taker: "0x0000000000000000000000000000000000000000".to_string(),
-            token_id: token_id.to_string(),
-            maker_amount: maker_amount.to_string(),
-            taker_amount: taker_amount.to_string(),
-            expiration: (timestamp + 3600).to_string(),
-            nonce: timestamp.to_string(),
-            fee_rate_bps: "0".to_string(),
-            side: side.to_string(),
-            signature_type: self.signature_type,
-        };
+    /// Append to the trade journal; a failed write is reported, not fatal.
+    fn log_trade(&self, mut record: TradeRecord) {
+        if record.strategy.is_empty() {
+            record.strategy = STRATEGY_NAME.to_string();
+        }
+        if self.config.paper {
+            record.notes = if record.notes.is_empty() { "paper".to_string() } else { format!("paper; {}", record.notes) };
+        }
+        if let Err(e) = self.trade_log.append(&record) {
+            println!("   ⚠️ Failed to write trade log: {}", e);
+        }
+        if let Some(log) = &self.json_log {
+            log.write(&log.trade(self.time.now_secs(), &record));
+        }
+        #[cfg(feature = "sqlite")]
+        self.with_store(|store| store.insert_trade(&record));
+    }
 
-        let signature = self.signer.sign_order(&order)?;
-        let sig_hex = format!("0x{}", hex::encode(signature.to_vec()));
+    /// Write to the database, if there is one; a failure is reported, not fatal.
+    #[cfg(feature = "sqlite")]
+    fn with_store(&self, write: impl FnOnce(&Store) -> Result<(), String>) {
+        let Some(store) = &self.store else { return };
+        if let Err(e) = write(store) {
+            self.warn(format!("\n   ⚠️ Database write failed: {}", e));
+        }
+    }
 
-        let request = OrderRequest {
-            order,
-            order_type: order_type.to_string(),
-            owner: if self.use_proxy { 
-                Some(format!("{:?}", self.trading_address).to_lowercase()) 
-            } else { 
-                None 
-            },
-            signature: sig_hex,
-        };
+    /// Notify an abort and note it in the journal and database; the console
+    /// and traded markets already show it.
+    fn record_abort(&self, market: &str, stage: &str, ask: Option<f64>) {
+        let at = ask.map(|ask| format!(" with the ask at ${:.3}", ask)).unwrap_or_default();
+        self.notify(Severity::Warning, Event::Abort, &format!("Aborted {}", market), &format!("Gave up during {}{}", stage, at));
+        self.log_trade(TradeRecord {
+            status: "ABORTED".to_string(),
+            market: market.to_string(),
+            entry_time: Some(self.time.now_secs()),
+            notes: format!("{}: gave up during {}{}", market, stage, at),
+            ..Default::default()
+        });
+        #[cfg(feature = "sqlite")]
+        self.with_store(|store| store.insert_abort(&AbortRow { ts: self.time.now_secs(), market: market.to_string(), stage: stage.to_string(), ask }));
+    }
 
-        let body = serde_json::to_string(&request)?;
-        let headers = self.create_auth_headers("POST", "/order", &body)?;
+    /// Publish to event stream subscribers, if any.
+    fn emit(&self, event: BotEvent) {
+        if let Some(log) = &self.json_log {
+            log.write(&log.bot_event(self.time.now_secs(), &event));
+        }
+        #[cfg(feature = "event-stream")]
+        if let Some(stream) = &self.event_stream {
+            stream.publish(self.time.now_secs(), &event);
+        }
+    }
 
-        let url = format!("{}/order", HOST);
-        let response = self.client.post(&url).headers(headers).body(body).send()?;
+    /// Current exchange time, for auth timestamps and expirations.
+    fn now_secs(&self) -> u64 {
+        self.clock.now_secs(self.time.as_ref())
+    }
 
-        if !response.status().is_success() {
-            println!("   ❌ Order rejected: HTTP {}", response.status());
-            let error_text = response.text().unwrap_or_default();
-            println!("   Error details: {}", error_text);
-            return Ok((None, None));
+    /// Send an API request; under BOT_CHAOS it may be delayed, time out,
+    /// fail with a 503 before leaving, or come back with a mangled body.
+    fn send(&self, request: RequestBuilder) -> Result<Response, Box<dyn std::error::Error>> {
+        let Some(chaos) = &self.chaos else { return Ok(request.send()?) };
+        let request = request.build()?;
+        let injection = chaos.roll(request.url().path());
+        if let Some(delay) = injection.delay {
+            self.time.sleep(delay);
+        }
+        match injection.fault {
+            None => Ok(self.client.execute(request)?),
+            Some(Fault::Timeout) => {
+                self.time.sleep(Duration::from_secs(self.config.timing.http_timeout));
+                Err(format!("{} {}: timed out (injected)", request.method(), request.url().path()).into())
+            }
+            Some(Fault::ServerError) => {
+                Ok(http::Response::builder().status(503).body(r#"{"error":"service unavailable (injected)"}"#)?.into())
+            }
+            Some(Fault::Malformed) => {
+                let response = self.client.execute(request)?;
+                let status = response.status();
+                let body = response.bytes()?;
+                Ok(http::Response::builder().status(status).body(chaos::mangle(&body))?.into())
+            }
         }
+    }
 
-        let order_resp: OrderResponse = response.json()?;
+    /// Re-anchor the clock on the CLOB's `/time`, warning when the local
+    /// clock is far enough off to get auth headers rejected.
+    fn sync_server_clock(&mut self) {
+        if self.time.is_simulated() {
+            // The exchange's /time says nothing about a simulated timeline
+            return;
+        }
+        let url = format!("{}/time", self.network.clob_url);
+        let started = Instant::now();
+        let server_time = self.send(self.client.get(&url))
+            .and_then(|r| Ok(r.error_for_status()?.text()?));
 
-        if let Some(order_id) = order_resp.order_id {
-            println!("   🆔 Order Placed! ID: {}", order_id);
-            thread::sleep(Duration::from_secs(2));
-            
-            for attempt in 1..=10 {
-                match self.check_order_status(&order_id) {
-                    Ok((true, fill_price)) => {
-                        println!("🎊 EXECUTED: {} {} filled at ${:.2}", side, order_type, fill_price);
-                        return Ok((Some(order_id), Some(fill_price)));
-                    },
-                    Ok((false, _)) => {
-                        thread::sleep(Duration::from_secs(2));
-                    },
-                    Err(_) => {}
+        match server_time.map(|t| t.trim().parse::<f64>()) {
+            Ok(Ok(server_time)) => {
+                self.clock = ServerClock::synced(server_time, started.elapsed(), self.time.as_ref());
+                if self.clock.skew_secs.abs() > self.config.timing.max_clock_skew_secs {
+                    println!("\n🚨 CLOCK SKEW WARNING: local clock is {:+.1}s off server time.", self.clock.skew_secs);
+                    println!("   Using server time for auth and expirations; fix NTP on this host.");
                 }
             }
-            
-            println!("\n   ⚠️ Order not filled within timeout");
-            return Ok((None, None));
-            
-        } else if let Some(err) = order_resp.error_msg {
-            println!("   ⚠️ Order Rejected: {}", err);
+            Ok(Err(e)) => self.warn(format!("\n⚠️ Unparseable /time response: {}", e)),
+            Err(e) => self.warn(format!("\n⚠️ Failed to sync server clock: {}", e)),
         }
-        
-        Ok((None, None))
     }
 
-    fn check_order_status(&self, order_id: &str) -> Result<(bool, f64), Box<dyn std::error::Error>> {
-        let request_path = format!("/order/{}", order_id);
-        let url = format!("{}{}", HOST, request_path);
-        
-        let headers = self.create_auth_headers("GET", &request_path, "")?;
-        let resp = self.client.get(&url).headers(headers).send()?;
-        
-        if resp.status().is_success() {
-            let order: OrderStatus = resp.json()?;
-            if let Some(status) = order.status {
-                if status == "MATCHED" || status == "FILLED" || status == "COMPLETED" {
-                    let price = if let Some(avg) = order.avg_fill_price {
-                        avg.parse::<f64>().unwrap_or(0.0)
-                    } else if let Some(p) = order.price {
-                        p.parse::<f64>().unwrap_or(0.0)
-                    } else {
-                        0.0
-                    };
-                    return Ok((true, price));
-                }
-            }
-            return Ok((false, 0.0));
+    /// CLOB health endpoint; anything but a 2xx "OK" counts as down.
+    fn clob_ok(&self) -> Option<bool> {
+        match self.send(self.client.get(format!("{}/", self.network.clob_url))) {
+            Ok(resp) => Some(resp.status().is_success()),
+            Err(_) => None,
         }
-        
-        Ok((false, 0.0))
     }
 
-    fn monitor_market(&mut self, market: MarketData, market_start_ts: u64) {
-        println!("\n{}", "=".repeat(60));
-        println!("📊 MONITORING: {}", market.title);
-        println!("🔗 Link: {}", market.link);
-        println!("{}", "=".repeat(60));
+    /// Re-check the exchange's paused flag and the CLOB status, alerting on
+    /// every change. Returns true while trading is halted.
+    fn refresh_exchange_status(&self) -> bool {
+        self.last_status_check.set(self.now_secs());
 
-        let mut entry_window_start: Option<u64> = None;
-        
-        loop {
-            let current_time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-            let elapsed = current_time - market_start_ts;
-            let time_until_close = 900 - elapsed;
-
-            if time_until_close > MARKET_WINDOW {
-                print!("\r⏳ Waiting for trading window ({}s remaining)...    ", time_until_close - MARKET_WINDOW);
-                io::stdout().flush().unwrap();
-                entry_window_start = None;
-                thread::sleep(Duration::from_secs(1));
-                continue;
+        let mut status = exchange_status::ExchangeStatus { clob_ok: self.clob_ok(), ..Default::default() };
+        match exchange_status::check_paused(&self.rpc, &self.network) {
+            Ok((exchange, neg_risk)) => {
+                status.exchange_paused = exchange;
+                status.neg_risk_paused = neg_risk;
             }
+            // Unknown isn't halted; the CLOB check and order rejections still apply
+            Err(e) => println!("\n⚠️ Could not read exchange paused state: {}", e),
+        }
 
-            if entry_window_start.is_none() {
-                entry_window_start = Some(current_time);
-                println!("\n🔵 Entered trading window. Entry timeout starts now ({}s)", ENTRY_TIMEOUT);
-            }
+        let halted = status.is_halted();
+        if halted && !self.exchange_halted.get() {
+            println!("\n🚨 TRADING HALTED EXCHANGE-SIDE ({}). Pausing order placement.", status.describe());
+            self.notify(Severity::Critical, Event::Halted, "Trading halted", &status.describe());
+        } else if !halted && self.exchange_halted.get() {
+            println!("\n✅ Exchange operational again ({}). Resuming.", status.describe());
+            self.notify(Severity::Info, Event::Resumed, "Exchange operational again", &status.describe());
+        }
+        self.exchange_halted.set(halted);
+        halted
+    }
 
-            if time_until_close <= 0 {
-                println!("\n⏰ Market closed. Moving to next market.");
-                self.traded_markets.insert(market.slug.clone());
+    /// Report collateral balances and warn when funds sit in the USDC variant
+    /// the exchange doesn't settle in.
+    fn check_collateral(&self) {
+        let report = match collateral::check_collateral(&self.rpc, &self.network, self.trading_address) {
+            Ok(report) => report,
+            Err(e) => {
+                println!("⚠️ Collateral check failed: {}", e);
                 return;
             }
+        };
 
-            if let Some(window_start) = entry_window_start {
-                if current_time - window_start > ENTRY_TIMEOUT {
-                    println!("\n❌ Entry window timeout. Moving to next market.");
-                    self.traded_markets.insert(market.slug.clone());
-                    return;
+        println!("💵 Collateral: {:.2} {} (exchange token {:?})",
+            report.collateral_balance, collateral::token_label(report.collateral_token), report.collateral_token);
+
+        if report.has_misplaced_funds() {
+            println!("🚨 {:.2} {} is sitting in the wrong token — the exchange only accepts {}",
+                report.other_balance, collateral::token_label(report.other_token), collateral::token_label(report.collateral_token));
+
+            if let Some(hook) = &self.collateral_swap_hook {
+                if let Err(e) = hook.swap_to_collateral(report.other_token, report.collateral_token, report.other_balance_raw) {
+                    println!("   ⚠️ Collateral swap hook failed: {}", e);
                 }
             }
+        }
+    }
 
-            let yes_book = self.get_order_book_depth(&market.yes_token);
-            let no_book = self.get_order_book_depth(&market.no_token);
+    /// Send on-chain maintenance calls from whichever account holds the
+    /// assets: directly from the EOA, or batched through the proxy wallet.
+    fn send_onchain(&self, label: &str, requests: &[TxRequest]) -> Result<ethers::types::TransactionReceipt, Box<dyn std::error::Error>> {
+        if self.use_proxy {
+            let wrapped = proxy_wallet::wrap_for_proxy(&self.network, label, requests)?;
+            return self.tx_manager.send(&self.rpc, &wrapped);
+        }
 
-            if yes_book.is_none() || no_book.is_none() {
-                thread::sleep(Duration::from_secs(POLLING_INTERVAL));
-                continue;
-            }
+        // Consecutive nonces so the batch doesn't wait on each confirmation in turn
+        let receipts = self.tx_manager.send_all(&self.rpc, requests)?;
+        receipts.into_iter().last().ok_or_else(|| format!("{}: nothing to send", label).into())
+    }
 
-            let yes_book = yes_book.unwrap();
-            let no_book = no_book.unwrap();
-
-            let yes_bid = yes_book.best_bid.unwrap_or(0.0);
-            let no_bid = no_book.best_bid.unwrap_or(0.0);
-            
-            let yes_ask_opt = yes_book.best_ask;
-            let no_ask_opt = no_book.best_ask;
-            let yes_ask_size = yes_book.ask_size;
-            let no_ask_size = no_book.ask_size;
-
-            let should_abort = 
-                (yes_ask_opt.is_some() && yes_ask_opt.unwrap() > ABORT_ASK_PRICE) ||
-                (no_ask_opt.is_some() && no_ask_opt.unwrap() > ABORT_ASK_PRICE);
-            
-            if should_abort {
-                println!("\n🚨 ABORT TRIGGERED: ASK price exceeded ${}", ABORT_ASK_PRICE);
-                self.traded_markets.insert(market.slug.clone());
-                return;
+    fn approval_statuses(&self) -> Result<Vec<approvals::ApprovalStatus>, Box<dyn std::error::Error>> {
+        let collateral = collateral::exchange_collateral(&self.rpc, &self.network);
+        approvals::check_approvals(&self.rpc, &self.network, self.trading_address, collateral)
+    }
+
+    /// Report which exchange approvals are missing and, after confirmation
+    /// on stdin, send them. Returns true when everything is approved.
+    fn ensure_approvals(&self) -> bool {
+        let statuses = match self.approval_statuses() {
+            Ok(statuses) => statuses,
+            Err(e) => {
+                println!("⚠️ Approval check failed: {}", e);
+                return false;
             }
+        };
 
-            print!("\rMonitoring {} | YES: ${:.2}/${:.2} ({}) | NO: ${:.2}/${:.2} ({}) | Target: ${:.2}   ",
-                TRADE_SIDE, yes_bid, yes_ask_opt.unwrap_or(0.0), yes_ask_size as u32, 
-                no_bid, no_ask_opt.unwrap_or(0.0), no_ask_size as u32, ENTRY_PRICE);
-            io::stdout().flush().unwrap();
+        let missing: Vec<_> = statuses.iter().filter(|s| !s.approved).collect();
+        if missing.is_empty() {
+            println!("✅ All exchange approvals in place");
+            return true;
+        }
 
-            let mut triggered_side = None;
-            let mut triggered_token = None;
-            let mut triggered_ask = None;
+        println!("🚨 Missing approvals (sells/buys will fail until set):");
+        for status in &missing {
+            println!("   ❌ {} ({:?})", status.describe(), status.spender);
+        }
+
+        print!("Send {} approval transaction(s) now? [y/N] ", missing.len());
+        io::stdout().flush().ok();
+        let mut answer = String::new();
+        if io::stdin().read_line(&mut answer).is_err() || !answer.trim().eq_ignore_ascii_case("y") {
+            println!("   Skipping approvals.");
+            return false;
+        }
 
-            if (TRADE_SIDE == "YES" || TRADE_SIDE == "BOTH") && 
-               yes_bid >= ENTRY_PRICE && 
-               yes_ask_size >= POSITION_SIZE as f64 && 
-               yes_ask_opt.is_some() {
-                triggered_side = Some("YES");
-                triggered_token = Some(market.yes_token.clone());
-                triggered_ask = yes_ask_opt;
+        let requests: Vec<TxRequest> = missing.iter().map(|s| s.approval_tx()).collect();
+        match self.send_onchain("Exchange approvals", &requests) {
+            Ok(receipt) => {
+                println!("✅ Approvals confirmed in block {:?}", receipt.block_number.unwrap_or_default());
+                self.refresh_collateral();
+                true
+            }
+            Err(e) => {
+                println!("❌ Approval transaction failed: {}", e);
+                false
             }
+        }
+    }
+
+    fn create_auth_headers(&self, method: &str, request_path: &str, body: &str) -> Result<HeaderMap, Box<dyn std::error::Error>> {
+        let timestamp = self.now_secs().to_string();
+        self.api_creds.l2_headers(self.wallet.address(), &timestamp, method, request_path, body)
+    }
+
+    fn create_l1_headers(&self, nonce: u64) -> Result<HeaderMap, Box<dyn std::error::Error>> {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+        let timestamp = self.now_secs().to_string();
+
+        let signature = self.signer.sign_clob_auth(&timestamp, nonce)?;
+        let sig_hex = format!("0x{}", hex::encode(signature.to_vec()));
+
+        // L1 headers are always for the signing EOA, never the proxy
+        headers.insert("POLY_ADDRESS", HeaderValue::from_str(&format!("{:?}", self.wallet.address()))?);
+        headers.insert("POLY_SIGNATURE", HeaderValue::from_str(&sig_hex)?);
+        headers.insert("POLY_TIMESTAMP", HeaderValue::from_str(&timestamp)?);
+        headers.insert("POLY_NONCE", HeaderValue::from_str(&nonce.to_string())?);
 
-            if (TRADE_SIDE == "NO" || TRADE_SIDE == "BOTH") && 
-               no_bid >= ENTRY_PRICE && 
-               no_ask_size >= POSITION_SIZE as f64 && 
-               no_ask_opt.is_some() {
-                if triggered_side.is_none() || (TRADE_SIDE == "BOTH" && no_bid > yes_bid) {
-                    triggered_side = Some("NO");
-                    triggered_token = Some(market.no_token.clone());
-                    triggered_ask = no_ask_opt;
+        Ok(headers)
+    }
+
+    /// L2 credentials for this signer: the key it already has, from
+    /// `/auth/derive-api-key`, or a new one from `/auth/api-key` if it has none.
+    fn create_or_derive_api_creds(&self) -> Result<ApiCredentials, Box<dyn std::error::Error>> {
+        let nonce = match self.profile.var("POLY_API_NONCE") {
+            Some(v) => v.parse::<u64>().map_err(|_| format!("Invalid POLY_API_NONCE '{}'", v))?,
+            None => 0,
+        };
+
+        let url = format!("{}/auth/derive-api-key", self.network.clob_url);
+        let resp = self.send(self.client.get(&url).headers(self.create_l1_headers(nonce)?))?;
+        let derive_error = if resp.status().is_success() {
+            match parse_api_creds(&resp.json()?) {
+                Ok(creds) => {
+                    println!("🔑 Derived API credentials for {:?}", self.wallet.address());
+                    return Ok(creds);
                 }
+                Err(e) => e,
             }
+        } else {
+            format!("HTTP {}: {}", resp.status(), resp.text().unwrap_or_default())
+        };
 
-            if !self.active_trade && triggered_side.is_some() && triggered_ask.is_some() {
-                let side = triggered_side.unwrap();
-                let token = triggered_token.unwrap();
-                let ask = triggered_ask.unwrap();
-                
-                println!("\n🚀 ENTRY TRIGGERED: {} - Placing order...", side);
-                self.execute_trade(&market, side, &token, ask);
-                return;
+        // No key for this signer and nonce yet
+        let url = format!("{}/auth/api-key", self.network.clob_url);
+        let resp = self.send(self.client.post(&url).headers(self.create_l1_headers(nonce)?))?;
+        if !resp.status().is_success() {
+            return Err(format!(
+                "Cannot get API credentials: derive-api-key {}; api-key HTTP {}: {}",
+                derive_error, resp.status(), resp.text().unwrap_or_default(),
+            ).into());
+        }
+        let creds = parse_api_creds(&resp.json()?).map_err(|e| format!("Cannot get API credentials: api-key {}", e))?;
+        println!("🔑 Created API credentials for {:?}", self.wallet.address());
+        Ok(creds)
+    }
+
+    fn get_order_book_depth(&self, token_id: &str) -> Option<OrderBook> {
+        if let Some(book) = self.stream.as_ref().and_then(|s| s.book(token_id)) {
+            return Some(book);
+        }
+        for attempt in 1..=3 {
+            let result = self.fetch_order_book(token_id);
+            self.sample(Sample::Request { ok: result.is_ok() });
+            match result {
+                Ok(book) => return Some(book),
+                Err(e) => {
+                    self.warn(format!("\n   ⚠️ Order book fetch error for {}: {}", token_id, e));
+                    if attempt < 3 {
+                        self.time.sleep(Duration::from_secs(1));
+                    }
+                }
             }
+        }
+        None
+    }
+
+    fn fetch_order_book(&self, token_id: &str) -> Result<OrderBook, Box<dyn std::error::Error>> {
+        let url = format!("{}/book?token_id={}", self.network.clob_url, token_id);
+        let mut resp = self.send(self.client.get(&url))?.error_for_status()?;
+
+        let mut buffer = self.book_buffer.borrow_mut();
+        buffer.clear();
+        resp.read_to_end(&mut buffer)?;
+
+        let top = book_parser::parse_top_of_book(&buffer)?;
+
+        let (best_ask, ask_size) = match top.best_ask {
+            Some((price, size)) => (Some(book_parser::fixed_to_f64(price)), book_parser::fixed_to_f64(size)),
+            None => (None, 0.0),
+        };
+        let (best_bid, bid_size) = match top.best_bid {
+            Some((price, size)) => (Some(book_parser::fixed_to_f64(price)), book_parser::fixed_to_f64(size)),
+            None => (None, 0.0),
+        };
+
+        Ok(OrderBook {
+            best_ask,
+            ask_size,
+            best_bid,
+            bid_size,
+        })
+    }
+
+    fn get_midpoint(&self, token_id: &str) -> Result<f64, Box<dyn std::error::Error>> {
+        let url = format!("{}/midpoint?token_id={}", self.network.clob_url, token_id);
+        let resp: MidpointResponse = self.send(self.client.get(&url))?.error_for_status()?.json()?;
+        Ok(resp.mid.parse::<f64>()?)
+    }
 
-            thread::sleep(Duration::from_secs(POLLING_INTERVAL));
+    fn get_price(&self, token_id: &str, side: OrderSide) -> Result<f64, Box<dyn std::error::Error>> {
+        let url = format!("{}/price?token_id={}&side={}", self.network.clob_url, token_id, side.as_str());
+        let resp: PriceResponse = self.send(self.client.get(&url))?.error_for_status()?.json()?;
+        Ok(resp.price.parse::<f64>()?)
+    }
+
+    fn get_spread(&self, token_id: &str) -> Result<f64, Box<dyn std::error::Error>> {
+        let url = format!("{}/spread?token_id={}", self.network.clob_url, token_id);
+        let resp: SpreadResponse = self.send(self.client.get(&url))?.error_for_status()?.json()?;
+        Ok(resp.spread.parse::<f64>()?)
+    }
+
+    fn get_midpoints(&self, token_ids: &[&str]) -> Result<HashMap<String, f64>, Box<dyn std::error::Error>> {
+        let params: Vec<BookParams> = token_ids.iter()
+            .map(|t| BookParams { token_id: t, side: None })
+            .collect();
+        let url = format!("{}/midpoints", self.network.clob_url);
+        let resp: HashMap<String, String> = self.send(self.client.post(&url).json(&params))?.error_for_status()?.json()?;
+        parse_price_map(resp)
+    }
+
+    /// Returns token id -> side -> price.
+    fn get_prices(&self, requests: &[(&str, OrderSide)]) -> Result<HashMap<String, HashMap<String, f64>>, Box<dyn std::error::Error>> {
+        let params: Vec<BookParams> = requests.iter()
+            .map(|(t, side)| BookParams { token_id: t, side: Some(side.as_str()) })
+            .collect();
+        let url = format!("{}/prices", self.network.clob_url);
+        let resp: HashMap<String, HashMap<String, String>> = self.send(self.client.post(&url).json(&params))?.error_for_status()?.json()?;
+
+        let mut prices = HashMap::new();
+        for (token_id, sides) in resp {
+            prices.insert(token_id, parse_price_map(sides)?);
         }
+        Ok(prices)
     }
 
-    fn execute_trade(&mut self, market: &MarketData, side: &str, token_id: &str, entry_ask: f64) {
-        println!("\n🎯 Attempting {} entry at ${:.3}", side, entry_ask);
-        
-        let position_size = if side == "NO" { POSITION_SIZE } else { (POSITION_SIZE as f64 * 0.5) as u32 };
+    fn get_spreads(&self, token_ids: &[&str]) -> Result<HashMap<String, f64>, Box<dyn std::error::Error>> {
+        let params: Vec<BookParams> = token_ids.iter()
+            .map(|t| BookParams { token_id: t, side: None })
+            .collect();
+        let url = format!("{}/spreads", self.network.clob_url);
+        let resp: HashMap<String, String> = self.send(self.client.post(&url).json(&params))?.error_for_status()?.json()?;
+        parse_price_map(resp)
+    }
 
-        for attempt in 1..=20 {
-            if let Some(current_book) = self.get_order_book_depth(token_id) {
-                let current_bid = current_book.best_bid.unwrap_or(0.0);
-                
-                if let Some(current_ask) = current_book.best_ask {
-                    if current_ask > ABORT_ASK_PRICE {
-                        println!("\n🚨 ABORT during entry: ASK ${:.3} > ${}", current_ask, ABORT_ASK_PRICE);
-                        self.traded_markets.insert(market.slug.clone());
-                        return;
-                    }
-                } else {
-                    thread::sleep(Duration::from_secs(1));
-                    continue;
-                }
-                
-                let current_ask = current_book.best_ask.unwrap();
+    fn fetch_markets_page(&self, cursor: &str) -> Result<MarketsPage, Box<dyn std::error::Error>> {
+        let url = if cursor.is_empty() {
+            format!("{}/markets", self.network.clob_url)
+        } else {
+            format!("{}/markets?next_cursor={}", self.network.clob_url, cursor)
+        };
+        Ok(self.send(self.client.get(&url))?.error_for_status()?.json()?)
+    }
 
-                if current_bid < ENTRY_PRICE - 0.02 {
-                    thread::sleep(Duration::from_secs(1));
-                    continue;
-                }
+    /// Walk the paginated `/markets` listing into the on-disk cache.
+    /// Resumes from the stored cursor; pass `full = true` to start over.
+    fn sync_market_cache(&self, full: bool) -> Result<MarketCache, Box<dyn std::error::Error>> {
+        let cache_file = self.profile.path(MARKET_CACHE_FILE);
+        let mut cache = MarketCache::load(&cache_file)?;
+        let mut cursor = if full { String::new() } else { cache.next_cursor.clone() };
+        let mut last_cursor = cursor.clone();
+        let mut pages = 0;
 
-                if current_book.ask_size < position_size as f64 {
-                    thread::sleep(Duration::from_secs(1));
-                    continue;
-                }
+        while cursor != market_cache::END_CURSOR {
+            let page = self.fetch_markets_page(&cursor)?;
+            cache.upsert(page.data);
+            last_cursor = std::mem::replace(&mut cursor, page.next_cursor);
+            pages += 1;
 
-                println!("🔄 Entry Attempt {}/20: Placing FOK @ ${:.3}", attempt, current_ask);
-                
-                match self.place_order(token_id, current_ask, position_size, "BUY", "FOK") {
-                    Ok((Some(_order_id), Some(_fill_price))) => {
-                        self.active_trade = true;
-                        self.traded_markets.insert(market.slug.clone());
-                        return;
-                    },
-                    _ => {
-                        thread::sleep(Duration::from_millis(500));
+            // Persist progress periodically so an interrupted sync can resume
+            if pages % 20 == 0 {
+                cache.next_cursor = cursor.clone();
+                cache.save(&cache_file)?;
+            }
+            if cursor.is_empty() {
+                break;
+            }
+        }
+
+        // New markets are appended to the end of the listing, so the next
+        // incremental sync re-reads the last page instead of starting over
+        cache.next_cursor = last_cursor;
+        cache.save(&cache_file)?;
+        println!("✅ Market cache synced: {} markets ({} pages)", cache.markets.len(), pages);
+        Ok(cache)
+    }
+
+    /// Incremental market cache sync, every timing.market_cache_interval.
+    fn refresh_market_cache(&mut self) {
+        let interval = self.config.timing.market_cache_interval;
+        let now = self.now_secs();
+        if interval == 0 || now.saturating_sub(self.last_market_sync) < interval {
+            return;
+        }
+        self.last_market_sync = now;
+        if let Err(e) = self.sync_market_cache(false) {
+            self.warn(format!("\n⚠️ Market cache sync failed: {}", e));
+        }
+    }
+
+    fn get_market(&self, cycle_start: u64) -> Option<MarketData> {
+        for attempt in 1..=3 {
+            match self.fetch_market(cycle_start) {
+                Ok(Some(market)) => return Some(market),
+                Ok(None) => return None,
+                Err(_) => {
+                    if attempt < 3 {
+                        self.time.sleep(Duration::from_secs(3));
                     }
                 }
             }
         }
-
-        println!("\n⚠️ Failed to enter after 20 attempts.");
-        self.traded_markets.insert(market.slug.clone());
+        None
     }
 
-    fn run(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        println!("🚀 ETH No Trend Bot Running...\n");
+    /// The market of the cycle starting at `cycle_start`, by slug or by
+    /// discovery as configured.
+    fn fetch_market(&self, cycle_start: u64) -> Result<Option<MarketData>, Box<dyn std::error::Error>> {
+        let url = format!("{}{}", self.network.gamma_url, self.finder.query(cycle_start));
+        let resp = self.send(self.client.get(&url).timeout(Duration::from_secs(10)))?;
 
-        loop {
-            let current_time = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
-            let ts = (current_time / 900) * 900;
-            let slug = format!("eth-updown-15m-{}", ts);
+        if resp.status() == 404 {
+            return Ok(None);
+        }
 
-            let elapsed_since_open = current_time - ts;
-            let time_until_next = 900 - elapsed_since_open;
+        let market = self.finder.parse(cycle_start, &resp.bytes()?)?;
+        if let Some(market) = &market {
+            println!("   ✅ Market found: {}", market.title);
+        }
+        Ok(market)
+    }
 
-            let open_time = Utc.timestamp_opt(ts as i64, 0).unwrap().format("%H:%M:%S");
-            print!("\r⏰ Current Market: {} | Open Time: {} | Next in: {}s ", 
-                slug, open_time, time_until_next);
-            io::stdout().flush()?;
+    fn balance_allowance_path(&self, asset_type: AssetType, token_id: &str) -> String {
+        let mut query = format!("asset_type={}&signature_type={}", asset_type.as_str(), self.signature_type);
+        if asset_type == AssetType::Conditional {
+            query.push_str(&format!("&token_id={}", token_id));
+        }
+        query
+    }
 
-            if self.traded_markets.contains(&slug) {
-                thread::sleep(Duration::from_secs(60));
-                continue;
+    /// Cached for timing.balance_cache_ttl seconds; pass an empty token id for collateral.
+    fn get_balance_allowance(&self, asset_type: AssetType, token_id: &str) -> Result<BalanceAllowance, Box<dyn std::error::Error>> {
+        let key = (asset_type, token_id.to_string());
+        if let Some((fetched_at, cached)) = self.balance_cache.borrow().get(&key) {
+            if self.time.elapsed().saturating_sub(*fetched_at) < Duration::from_secs(self.config.timing.balance_cache_ttl) {
+                return Ok(*cached);
             }
+        }
 
-            if elapsed_since_open < 5 {
-                thread::sleep(Duration::from_secs(5));
-                continue;
-            }
+        let request_path = "/balance-allowance";
+        let url = format!("{}{}?{}", self.network.clob_url, request_path, self.balance_allowance_path(asset_type, token_id));
+        let headers = self.create_auth_headers("GET", request_path, "")?;
+        let resp = self.send(self.client.get(&url).headers(headers))?;
 
-            if let Some(market) = self.get_market_from_slug(&slug) {
-                self.monitor_market(market, ts);
-            } else {
-                thread::sleep(Duration::from_secs(2));
+        if !resp.status().is_success() {
+            return Err(format!("balance-allowance HTTP {}", resp.status()).into());
+        }
+
+        let value: BalanceAllowance = resp.json::<BalanceAllowanceResponse>()?.into();
+        self.balance_cache.borrow_mut().insert(key, (self.time.elapsed(), value));
+        Ok(value)
+    }
+
+    /// Ask the CLOB to re-read on-chain balances, then drop our cached copy.
+    fn update_balance_allowance(&self, asset_type: AssetType, token_id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let request_path = "/balance-allowance/update";
+        let url = format!("{}{}?{}", self.network.clob_url, request_path, self.balance_allowance_path(asset_type, token_id));
+        let headers = self.create_auth_headers("GET", request_path, "")?;
+        self.send(self.client.get(&url).headers(headers))?.error_for_status()?;
+
+        self.balance_cache.borrow_mut().remove(&(asset_type, token_id.to_string()));
+        Ok(())
+    }
+
+    /// Have the CLOB re-read our USDC balance and allowance, which it
+    /// otherwise only catches up on lazily after approvals and deposits.
+    fn refresh_collateral(&self) {
+        if let Err(e) = self.update_balance_allowance(AssetType::Collateral, "") {
+            self.warn(format!("   ⚠️ Could not refresh the exchange's balance view: {}", e));
+        }
+    }
+
+    /// Shares of `token_id` held by the trading address, read straight from the
+    /// CTF contract. Reflects fills as soon as they're mined, unlike the APIs.
+    fn onchain_token_balance(&self, token_id: &str) -> Result<f64, Box<dyn std::error::Error>> {
+        let token = U256::from_dec_str(token_id)?;
+        let raw = chain::erc1155_balance_of(&self.rpc, self.network.ctf, self.trading_address, token)?;
+        Ok(raw.as_u128() as f64 / 1_000_000.0)
+    }
+
+    /// Pre-trade check: enough collateral for a BUY, enough shares for a SELL.
+    /// Errors from the endpoint itself don't block trading.
+    fn has_sufficient_balance(&self, token_id: &str, price: f64, size: u32, side: OrderSide) -> bool {
+        // For sells the chain is the authoritative, lag-free source
+        if side == OrderSide::Sell {
+            if let Ok(held) = self.onchain_token_balance(token_id) {
+                if held + 1e-6 < size as f64 {
+                    self.warn(format!("   ❌ Insufficient shares on-chain: have {:.2}, need {}", held, size));
+                    return false;
+                }
             }
+        }
 
-            thread::sleep(Duration::from_secs(1));
+        let (asset_type, token, required) = match side {
+            OrderSide::Buy => (AssetType::Collateral, "", price * size as f64),
+            OrderSide::Sell => (AssetType::Conditional, token_id, size as f64),
+        };
+
+        match self.get_balance_allowance(asset_type, token) {
+            Ok(ba) => {
+                if ba.balance < required {
+                    self.warn(format!("   ❌ Insufficient {}: have {:.2}, need {:.2}", asset_type.as_str(), ba.balance, required));
+                    return false;
+                }
+                if ba.allowance < required {
+                    self.warn(format!("   ❌ Insufficient {} allowance: {:.2} < {:.2}", asset_type.as_str(), ba.allowance, required));
+                    return false;
+                }
+                true
+            }
+            Err(e) => {
+                self.warn(format!("   ⚠️ Balance check unavailable ({}), proceeding", e));
+                true
+            }
         }
     }
-}
 
-fn init_csv_log() -> Result<(), Box<dyn std::error::Error>> {
-    if !std::path::Path::new(LOG_FILE).exists() {
-        let mut file = File::create(LOG_FILE)?;
-        writeln!(
-            file,
-            "Market Title,Market Link,Status,entry1_Time,entry_Side,entry_Price,position_size,sl_Time,sl_Price,Final_Status,Notes,is_SL_Triggered"
-        )?;
+    fn place_order(&self, token_id: &str, price: f64, size: u32, side: OrderSide, order_type: &str) 
+        -> Result<Option<Fill>, Box<dyn std::error::Error>> {
+        self.place_order_until(token_id, price, size, side, order_type, None)
     }
-    Ok(())
-}
 
-fn main() {
-    println!("✅ COMPLETE Rust Trading Bot with REST API");
-    println!("✅ EIP-712 Signing Implemented");
+    /// `place_order` with the signed expiration set to `expires_at` rather
+    /// than an hour out. Only GTD orders are pulled by the exchange then.
+    fn place_order_until(&self, token_id: &str, price: f64, size: u32, side: OrderSide, order_type: &str, expires_at: Option<u64>)
+        -> Result<Option<Fill>, Box<dyn std::error::Error>> {
+        self.submit_order(token_id, price, size, side, order_type, Placement { expires_at, rest: false })
+    }
+
+    /// Place a GTC or GTD order to rest on the book and return its id as
+    /// soon as the exchange accepts it, without waiting on fills. The order
+    /// is tracked; the caller polls it and pulls what's left.
+    fn rest_order(&self, token_id: &str, price: f64, size: u32, side: OrderSide, order_type: &str, expires_at: Option<u64>)
+        -> Result<Option<String>, Box<dyn std::error::Error>> {
+        let placed = self.submit_order(token_id, price, size, side, order_type, Placement { expires_at, rest: true })?;
+        if let Some(fill) = &placed {
+            if !self.config.paper && self.is_order_scoring(&fill.order_id).unwrap_or(false) {
+                println!("   💰 Scoring for liquidity rewards");
+            }
+        }
+        Ok(placed.map(|fill| fill.order_id))
+    }
+
+    fn submit_order(&self, token_id: &str, price: f64, size: u32, side: OrderSide, order_type: &str, placement: Placement)
+        -> Result<Option<Fill>, Box<dyn std::error::Error>> {
+        let expires_at = placement.expires_at;
+        
+        println!("📝 Placing {} {} order: {} shares @ ${:.3}", side, order_type, size, price);
+        if self.config.dry_run {
+            println!("   🧪 Dry run: not submitted");
+            return Ok(None);
+        }
+        
+        let rounded_price = (price * 100.0).round() / 100.0;
+
+        if !self.config.paper && !self.has_sufficient_balance(token_id, rounded_price, size, side) {
+            return Ok(None);
+        }
+
+        let timestamp = self.now_secs();
+        
+        let amounts = OrderAmounts::new(side, rounded_price, size);
+        
+        let order = PolymarketOrder {
+            salt: unique_salt().to_string(),
+            maker: format!("{:?}", self.trading_address).to_lowercase(),
+            signer: format!("{:?}", self.wallet.address()).to_lowercase(),
+            taker: "0x0000000000000000000000000000000000000000".to_string(),
+            token_id: token_id.to_string(),
+            maker_amount: amounts.maker_amount.to_string(),
+            taker_amount: amounts.taker_amount.to_string(),
+            expiration: expires_at.unwrap_or(timestamp + 3600).to_string(),
+            nonce: timestamp.to_string(),
+            fee_rate_bps: "0".to_string(),
+            side: side.as_str().to_string(),
+            signature_type: self.signature_type,
+        };
+
+        if let Some(existing) = self.find_pending_submission(token_id, side) {
+            println!("   ⏸️ Unresolved submission {} for this token; reconciling first", existing.client_order_id);
+            match self.reconcile_submission(&existing) {
+                Ok(Some(order_id)) => return self.follow_order(order_id, &existing.token_id, existing.size, side, order_type, placement.rest),
+                Ok(None) => {}
+                Err(e) => {
+                    println!("   ⚠️ Still cannot reconcile ({}); not resubmitting", e);
+                    return Ok(None);
+                }
+            }
+        }
+
+        let client_order_id = format!("{:?}", self.signer.order_hash(&order));
+        self.order_event(OrderEvent::created(timestamp, &client_order_id, OrderSpec {
+            token_id: token_id.to_string(),
+            side: side.as_str().to_string(),
+            order_type: order_type.to_string(),
+            price: rounded_price,
+            size: size as f64,
+            salt: order.salt.clone(),
+        }));
+        let submission = SubmittedOrder {
+            client_order_id: client_order_id.clone(),
+            token_id: token_id.to_string(),
+            side,
+            price: rounded_price,
+            size,
+            submitted_at: timestamp,
+        };
+
+        let signature = match self.signer.sign_order(&order) {
+            Ok(signature) => signature,
+            Err(e) => {
+                self.order_moved(&client_order_id, OrderState::Rejected, &format!("signing failed: {}", e));
+                return Err(e);
+            }
+        };
+        self.order_moved(&client_order_id, OrderState::Signed, "");
+        if self.config.paper {
+            return self.paper_execute(&client_order_id, token_id, rounded_price, size, side, order_type);
+        }
+        let sig_hex = format!("0x{}", hex::encode(signature.to_vec()));
+
+        let request = OrderRequest {
+            order,
+            order_type: order_type.to_string(),
+            owner: if self.use_proxy { 
+                Some(format!("{:?}", self.trading_address).to_lowercase()) 
+            } else { 
+                None 
+            },
+            signature: sig_hex,
+        };
+
+        let body = serde_json::to_string(&request)?;
+        let headers = self.create_auth_headers("POST", "/order", &body)?;
+
+        let url = format!("{}/order", self.network.clob_url);
+        self.pending_submissions.borrow_mut().insert(client_order_id.clone(), submission.clone());
+        self.order_moved(&client_order_id, OrderState::Submitted, "");
+
+        // A transport error or 5xx means the order may or may not be live
+        let response = self.send(self.client.post(&url).headers(headers).body(body));
+        self.sample(Sample::Request { ok: response.as_ref().is_ok_and(|r| !r.status().is_server_error()) });
+        let response = match response {
+            Ok(resp) if !resp.status().is_server_error() => resp,
+            Ok(resp) => {
+                self.warn(format!("   ⚠️ Ambiguous order response: HTTP {}", resp.status()));
+                return self.resolve_ambiguous_submission(&submission, side, order_type);
+            }
+            Err(e) => {
+                self.warn(format!("   ⚠️ Order POST failed ({}); checking whether it was accepted", e));
+                return self.resolve_ambiguous_submission(&submission, side, order_type);
+            }
+        };
+        self.pending_submissions.borrow_mut().remove(&client_order_id);
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().unwrap_or_default();
+            self.warn(format!("   ❌ Order rejected: HTTP {}\n   Error details: {}", status, error_text));
+            self.order_moved(&client_order_id, OrderState::Rejected, &format!("HTTP {}: {}", status, error_text));
+            self.emit(BotEvent::OrderRejected { token_id: token_id.to_string(), side: side.to_string(), reason: format!("HTTP {}: {}", status, error_text) });
+            self.sample(Sample::Reject);
+            if exchange_status::is_halt_rejection(&error_text) {
+                println!("   🚨 Rejection looks like an exchange halt, not liquidity; pausing until status clears");
+                self.exchange_halted.set(true);
+            }
+            return Ok(None);
+        }
+
+        let order_resp: OrderResponse = response.json()?;
+
+        if let Some(order_id) = order_resp.order_id {
+            self.order_accepted(&client_order_id, &order_id);
+            self.emit(BotEvent::OrderAccepted {
+                order_id: order_id.clone(),
+                token_id: token_id.to_string(),
+                side: side.to_string(),
+                price: rounded_price,
+                size,
+                order_type: order_type.to_string(),
+            });
+            return self.follow_order(order_id, token_id, size, side, order_type, placement.rest);
+        } else if let Some(err) = order_resp.error_msg {
+            self.warn(format!("   ⚠️ Order Rejected: {}", err));
+            self.order_moved(&client_order_id, OrderState::Rejected, &err);
+            self.emit(BotEvent::OrderRejected { token_id: token_id.to_string(), side: side.to_string(), reason: err.clone() });
+            self.sample(Sample::Reject);
+            if exchange_status::is_halt_rejection(&err) {
+                println!("   🚨 Rejection looks like an exchange halt, not liquidity; pausing until status clears");
+                self.exchange_halted.set(true);
+            }
+        } else {
+            self.order_moved(&client_order_id, OrderState::Rejected, "no order id in response");
+        }
+        
+        Ok(None)
+    }
+
+    /// --paper: match the order against the live book instead of POSTing it.
+    /// A fill is reported the way the exchange would, so the rest of the
+    /// order handling runs unchanged. Resting is not simulated: whatever
+    /// doesn't cross right away is killed, GTC included.
+    fn paper_execute(&self, client_order_id: &str, token_id: &str, price: f64, size: u32, side: OrderSide, order_type: &str)
+        -> Result<Option<Fill>, Box<dyn std::error::Error>> {
+        self.order_moved(client_order_id, OrderState::Submitted, "paper");
+        let fill = match self.get_order_book_depth(token_id) {
+            Some(book) => {
+                let fill_or_kill = order_type == "FOK";
+                let mut sim = self.paper_fills.borrow_mut();
+                match side {
+                    OrderSide::Buy => sim.take(&book, size as f64, price, fill_or_kill),
+                    OrderSide::Sell => sim.hit(&book, size as f64, price, fill_or_kill),
+                }
+            }
+            None => SimFill::NONE,
+        };
+        if fill.size <= 0.0 {
+            let reason = format!("paper: no liquidity at ${:.3} for {} shares", price, size);
+            self.warn(format!("   ⚠️ Order Rejected: {}", reason));
+            self.order_moved(client_order_id, OrderState::Rejected, &reason);
+            self.emit(BotEvent::OrderRejected { token_id: token_id.to_string(), side: side.to_string(), reason });
+            return Ok(None);
+        }
+
+        // Client order ids are order hashes, so this is unique too
+        let order_id = format!("paper-{}", client_order_id.trim_start_matches("0x"));
+        self.order_accepted(client_order_id, &order_id);
+        self.emit(BotEvent::OrderAccepted {
+            order_id: order_id.clone(),
+            token_id: token_id.to_string(),
+            side: side.to_string(),
+            price,
+            size,
+            order_type: order_type.to_string(),
+        });
+        self.paper_orders.borrow_mut().insert(order_id.clone(), OrderProgress {
+            status: "MATCHED".to_string(),
+            original_size: size as f64,
+            filled_size: fill.size,
+            avg_price: fill.price,
+            fee: 0.0,
+        });
+        self.wait_for_fill(order_id, token_id, size, side, order_type)
+    }
+
+    /// An accepted order: waited on until it fills, or for a resting order
+    /// tracked and handed straight back, nothing filled yet.
+    fn follow_order(&self, order_id: String, token_id: &str, size: u32, side: OrderSide, order_type: &str, rest: bool)
+        -> Result<Option<Fill>, Box<dyn std::error::Error>> {
+        if !rest {
+            return self.wait_for_fill(order_id, token_id, size, side, order_type);
+        }
+        self.track_order(&order_id, token_id, size, side);
+        Ok(Some(Fill { order_id, filled_size: 0.0, avg_price: 0.0, remaining: size as f64 }))
+    }
+
+    fn track_order(&self, order_id: &str, token_id: &str, size: u32, side: OrderSide) {
+        println!("   🆔 Order Placed! ID: {}", order_id);
+        self.tracked_orders.borrow_mut().insert(order_id.to_string(), TrackedOrder {
+            order_id: order_id.to_string(),
+            token_id: token_id.to_string(),
+            side,
+            progress: OrderProgress { original_size: size as f64, ..Default::default() },
+            chain_sourced_fill: 0.0,
+        });
+    }
+
+    /// Poll an accepted order until it fills, dies or times out. Returns the
+    /// order id and average price whenever *any* size filled; the filled
+    /// amount is available from `filled_size`.
+    fn wait_for_fill(&self, order_id: String, token_id: &str, size: u32, side: OrderSide, order_type: &str)
+        -> Result<Option<Fill>, Box<dyn std::error::Error>> {
+        self.track_order(&order_id, token_id, size, side);
+        self.time.sleep(Duration::from_secs(2));
+        
+        for _ in 0..10 {
+            match self.check_order_status(&order_id) {
+                Ok(progress) => {
+                    self.record_fill_progress(&order_id, &progress);
+                    if progress.is_filled() {
+                        println!("🎊 EXECUTED: {} {} filled at ${:.2}", side, order_type, progress.avg_price);
+                        if side == OrderSide::Sell {
+                            let realized = self.record_close(token_id, &order_id, &progress);
+                            self.notify(Severity::Info, Event::Exit, &format!("Sold {}", token_id),
+                                &format!("{:.2} shares @ ${:.3} ({}), realized ${:+.2}; session {}",
+                                    progress.filled_size, progress.avg_price, order_id, realized, self.pnl.borrow().summary()));
+                        }
+                        self.balance_cache.borrow_mut().clear();
+                        return Ok(self.fill_of(&order_id));
+                    }
+                    if progress.is_closed() {
+                        break;
+                    }
+                    self.time.sleep(Duration::from_secs(2));
+                },
+                Err(_) => {
+                    // API status is lagging or erroring; fall back to chain logs
+                    self.reconcile_chain_fills();
+                    if self.filled_size(&order_id) >= size as f64 - 1e-6 {
+                        let avg_price = self.tracked_orders.borrow().get(&order_id).map(|o| o.progress.avg_price).unwrap_or(0.0);
+                        println!("⛓️ EXECUTED (on-chain): {} {} filled at ${:.2}", side, order_type, avg_price);
+                        self.balance_cache.borrow_mut().clear();
+                        return Ok(self.fill_of(&order_id));
+                    }
+                    self.time.sleep(Duration::from_secs(2));
+                }
+            }
+        }
+
+        let tracked = self.tracked_orders.borrow().get(&order_id).cloned();
+        if let Some(tracked) = tracked {
+            if tracked.progress.filled_size > 0.0 {
+                self.balance_cache.borrow_mut().clear();
+                println!("\n   🧩 Partial fill: {:.2}/{:.2} @ ${:.3}",
+                    tracked.progress.filled_size, tracked.progress.original_size, tracked.progress.avg_price);
+                if side == OrderSide::Sell {
+                    self.record_partial_exit(token_id, &tracked.progress);
+                }
+                self.handle_partial_fill(&tracked, self.config.strategy.partial_fill);
+                return Ok(self.fill_of(&order_id));
+            }
+        }
+        
+        println!("\n   ⚠️ Order not filled within timeout");
+        Ok(None)
+    }
+
+    fn adjust_position(&self, token_id: &str, side: OrderSide, shares: f64) {
+        let signed = match side {
+            OrderSide::Buy => shares,
+            OrderSide::Sell => -shares,
+        };
+        *self.positions.borrow_mut().entry(token_id.to_string()).or_insert(0.0) += signed;
+    }
+
+    /// Post a fill (negative `shares` reverses one) and its fee to the ledger.
+    fn book_fill(&self, order_id: &str, token_id: &str, side: OrderSide, shares: f64, price: f64, fee: f64) {
+        #[cfg(feature = "sqlite")]
+        self.with_store(|store| store.insert_fill(&FillRow {
+            ts: self.time.now_secs(),
+            order_id: order_id.to_string(),
+            token_id: token_id.to_string(),
+            side: side.as_str().to_string(),
+            size: shares,
+            price,
+            fee,
+        }));
+        self.pnl.borrow_mut().fill(token_id, side == OrderSide::Buy, shares, price, fee);
+        let fill = ledger::Fill { token_id, buy: side == OrderSide::Buy, shares, price, fee };
+        let entries = self.ledger.borrow().fill_entries(self.time.now_secs(), order_id, &fill);
+        self.book(entries);
+    }
+
+    /// Post to the in-memory ledger and append to its journal. A failure is
+    /// a bug in how the entry was built, so it's loud but not fatal.
+    fn book(&self, entries: Vec<ledger::Entry>) {
+        let path = self.profile.path(LEDGER_FILE);
+        for entry in entries {
+            if let Err(e) = self.ledger.borrow_mut().post(entry.clone()) {
+                println!("\n   🚨 Ledger rejected entry: {}", e);
+                continue;
+            }
+            if let Err(e) = Ledger::append(&path, &entry) {
+                self.warn(format!("\n   ⚠️ Could not write {}: {}", path, e));
+            }
+        }
+    }
+
+    /// Apply a lifecycle event and append it to the order journal. A refused
+    /// move is a bug in the caller, so it's loud but not fatal.
+    fn order_event(&self, event: OrderEvent) {
+        if let Err(e) = self.orders.borrow_mut().apply(event.clone()) {
+            println!("\n   🚨 Order journal rejected event: {}", e);
+            return;
+        }
+        let path = self.profile.path(ORDERS_FILE);
+        if let Err(e) = OrderJournal::append(&path, &event) {
+            self.warn(format!("\n   ⚠️ Could not write {}: {}", path, e));
+        }
+    }
+
+    /// Move a known order to `state`; unknown orders (placed by an earlier
+    /// version or elsewhere) and moves the lifecycle forbids are skipped.
+    fn order_moved(&self, id: &str, state: OrderState, note: &str) {
+        let event = self.orders.borrow().transition(self.time.now_secs(), id, state, note);
+        if let Some(event) = event {
+            self.order_event(event);
+        }
+    }
+
+    fn order_accepted(&self, key: &str, order_id: &str) {
+        let event = self.orders.borrow().transition(self.time.now_secs(), key, OrderState::Live, "");
+        if let Some(event) = event {
+            self.order_event(OrderEvent { order_id: Some(order_id.to_string()), ..event });
+        }
+    }
+
+    /// Record what the latest status or fill says about the order.
+    fn sync_order(&self, order_id: &str, progress: &OrderProgress) {
+        let event = self.orders.borrow().progress_event(self.time.now_secs(), order_id, progress);
+        if let Some(event) = event {
+            self.order_event(event);
+        }
+    }
+
+    /// Apply the fill delta since the last poll to the tracked order and position.
+    /// Fill size never goes backwards here: a lagging API can't undo a fill
+    /// the chain already showed us.
+    fn record_fill_progress(&self, order_id: &str, progress: &OrderProgress) {
+        let mut orders = self.tracked_orders.borrow_mut();
+        let Some(tracked) = orders.get_mut(order_id) else { return };
+
+        let filled_size = progress.filled_size.max(tracked.progress.filled_size);
+        let delta = filled_size - tracked.progress.filled_size;
+        let fee = progress.fee.max(tracked.progress.fee);
+        if delta > 0.0 {
+            self.adjust_position(&tracked.token_id, tracked.side, delta);
+            self.sample(Sample::Fill);
+            // Price of just the new shares, from the change in total notional
+            let before = tracked.progress.filled_size * tracked.progress.avg_price;
+            let price = if progress.avg_price > 0.0 { (filled_size * progress.avg_price - before) / delta } else { tracked.progress.avg_price };
+            self.book_fill(order_id, &tracked.token_id, tracked.side, delta, price, fee - tracked.progress.fee);
+            self.emit(BotEvent::Fill {
+                order_id: order_id.to_string(),
+                token_id: tracked.token_id.clone(),
+                side: tracked.side.to_string(),
+                size: delta,
+                filled: filled_size,
+                avg_price: if progress.avg_price > 0.0 { progress.avg_price } else { tracked.progress.avg_price },
+            });
+        }
+
+        // Whatever the API now reports is no longer chain-only
+        tracked.chain_sourced_fill = tracked.chain_sourced_fill.min((filled_size - progress.filled_size).max(0.0));
+
+        let original_size = if progress.original_size > 0.0 { progress.original_size } else { tracked.progress.original_size };
+        let avg_price = if progress.avg_price > 0.0 { progress.avg_price } else { tracked.progress.avg_price };
+        tracked.progress = OrderProgress { original_size, filled_size, avg_price, fee, ..progress.clone() };
+        self.sync_order(order_id, &tracked.progress);
+    }
+
+    /// Pull new OrderFilled logs for our orders and fold any fills the API
+    /// hasn't reported yet into the tracked orders; undo fills whose block
+    /// was reorged away.
+    #[cfg(feature = "fill-watch")]
+    fn reconcile_chain_fills(&self) {
+        let mut watcher_slot = self.fill_watcher.borrow_mut();
+        let Some(watcher) = watcher_slot.as_mut() else { return };
+
+        let update = match watcher.poll(&self.rpc) {
+            Ok(update) => update,
+            Err(e) => {
+                self.warn(format!("\n   ⚠️ On-chain fill poll failed: {}", e));
+                return;
+            }
+        };
+
+        drop(watcher_slot);
+
+        for event in &update.orphaned {
+            self.rollback_chain_fill(event);
+        }
+        for event in &update.new {
+            self.apply_chain_fill(event);
+        }
+        if !update.finalized.is_empty() {
+            println!("\n   ⛓️ {} fill(s) final after {} confirmations", update.finalized.len(), fill_watcher::CONFIRMATIONS);
+        }
+    }
+
+    #[cfg(not(feature = "fill-watch"))]
+    fn reconcile_chain_fills(&self) {}
+
+    #[cfg(feature = "fill-watch")]
+    fn apply_chain_fill(&self, event: &OrderFilledEvent) {
+        let order_id = format!("{:?}", event.order_hash);
+        let on_chain = {
+            let mut chain_fills = self.chain_fills.borrow_mut();
+            let total = chain_fills.entry(order_id.clone()).or_insert(0.0);
+            *total += event.shares();
+            *total
+        };
+
+        let mut orders = self.tracked_orders.borrow_mut();
+        let Some(tracked) = orders.get_mut(&order_id) else {
+            println!("\n   ⛓️ Untracked on-chain fill for order {} ({:.2} shares)", order_id, event.shares());
+            return;
+        };
+
+        let delta = on_chain - tracked.progress.filled_size;
+        if delta > 1e-6 {
+            let usdc = if event.maker_asset_id.is_zero() { event.maker_amount_filled } else { event.taker_amount_filled };
+            let price = if event.shares() > 0.0 { usdc.as_u128() as f64 / 1_000_000.0 / event.shares() } else { 0.0 };
+
+            println!("\n   ⛓️ Chain shows {:.2} filled on {} (API had {:.2})", on_chain, order_id, tracked.progress.filled_size);
+            self.adjust_position(&tracked.token_id, tracked.side, delta);
+            self.book_fill(&order_id, &tracked.token_id, tracked.side, delta, price, 0.0);
+            tracked.progress.filled_size = on_chain;
+            tracked.chain_sourced_fill += delta;
+            if tracked.progress.avg_price <= 0.0 {
+                tracked.progress.avg_price = price;
+            }
+            self.sync_order(&order_id, &tracked.progress);
+        }
+    }
+
+    #[cfg(feature = "fill-watch")]
+    fn rollback_chain_fill(&self, event: &OrderFilledEvent) {
+        let order_id = format!("{:?}", event.order_hash);
+        if let Some(total) = self.chain_fills.borrow_mut().get_mut(&order_id) {
+            *total = (*total - event.shares()).max(0.0);
+        }
+
+        let mut orders = self.tracked_orders.borrow_mut();
+        let Some(tracked) = orders.get_mut(&order_id) else { return };
+
+        let rollback = event.shares().min(tracked.chain_sourced_fill);
+        println!("\n   🔀 Reorg removed fill of {:.2} on {} (block {})", event.shares(), order_id, event.block_number);
+        if rollback <= 0.0 {
+            // The API independently reported this fill; it will settle again
+            return;
+        }
+
+        self.adjust_position(&tracked.token_id, tracked.side, -rollback);
+        self.book_fill(&order_id, &tracked.token_id, tracked.side, -rollback, tracked.progress.avg_price, 0.0);
+        tracked.progress.filled_size -= rollback;
+        tracked.chain_sourced_fill -= rollback;
+        self.sync_order(&order_id, &tracked.progress);
+
+        self.log_trade(TradeRecord {
+            status: "REORG".to_string(),
+            entry_time: Some(self.time.now_secs()),
+            side: tracked.side.as_str().to_string(),
+            token_id: tracked.token_id.clone(),
+            order_id: order_id.clone(),
+            size: Some(-rollback),
+            notes: format!("Fill on {} in orphaned block {} rolled back", order_id, event.block_number),
+            ..Default::default()
+        });
+
+        self.check_low_balance();
+    }
+
+    /// Alert when collateral drops under risk.low_balance_threshold and cap the
+    /// position size to what the remaining balance can actually pay for.
+    fn check_low_balance(&self) {
+        // Fresh read, not the pre-trade cached value
+        self.balance_cache.borrow_mut().clear();
+        self.refresh_collateral();
+        let balance = match self.get_balance_allowance(AssetType::Collateral, "") {
+            Ok(ba) => ba.balance,
+            Err(e) => {
+                println!("   ⚠️ Could not read collateral balance: {}", e);
+                return;
+            }
+        };
+
+        let threshold = self.config.risk.low_balance_threshold;
+        let full_size = self.config.strategy.sizing.map_or(self.strategy.position_size, |s| s.max_shares);
+        if balance < threshold {
+            // Worst case we pay up to the abort price per share
+            let affordable = (balance / self.strategy.abort_ask_price).floor() as u32;
+            let capped = affordable.min(full_size);
+            println!("\n🚨 LOW BALANCE: ${:.2} USDC (threshold ${:.2}). Max size now {} shares.",
+                balance, threshold, capped);
+            self.max_position_size.set(capped);
+        } else if self.max_position_size.get() < full_size {
+            println!("\n✅ Balance recovered: ${:.2} USDC. Max size restored to {} shares.", balance, full_size);
+            self.max_position_size.set(full_size);
+        }
+    }
+
+    /// Settle the remainder of a partly filled order per the strategy's
+    /// `partial_fill` policy.
+    fn handle_partial_fill(&self, tracked: &TrackedOrder, policy: PartialFillAction) {
+        if tracked.progress.is_closed() {
+            return;
+        }
+        match policy {
+            PartialFillAction::Accept => {
+                println!("   ↪️ Leaving remaining {:.2} working", tracked.progress.remaining());
+            }
+            PartialFillAction::Cancel | PartialFillAction::Requote => {
+                if let Err(e) = self.cancel_order(&tracked.order_id) {
+                    println!("   ⚠️ Failed to cancel remainder of {}: {}", tracked.order_id, e);
+                }
+            }
+        }
+    }
+
+    /// The tracked order as a `Fill`, if anything of it filled.
+    fn fill_of(&self, order_id: &str) -> Option<Fill> {
+        let orders = self.tracked_orders.borrow();
+        let progress = &orders.get(order_id)?.progress;
+        (progress.filled_size > 0.0).then(|| Fill {
+            order_id: order_id.to_string(),
+            filled_size: progress.filled_size,
+            avg_price: progress.avg_price,
+            remaining: progress.remaining(),
+        })
+    }
+
+    fn filled_size(&self, order_id: &str) -> f64 {
+        self.tracked_orders.borrow().get(order_id).map(|o| o.progress.filled_size).unwrap_or(0.0)
+    }
+
+    fn position(&self, token_id: &str) -> f64 {
+        self.positions.borrow().get(token_id).copied().unwrap_or(0.0)
+    }
+
+    /// Holdings netted per market. Tokens from markets never monitored
+    /// this run stand alone, since their other side isn't known.
+    fn exposure(&self) -> Vec<MarketExposure> {
+        let token_markets = self.token_markets.borrow();
+        let legs: Vec<Leg> = self.positions.borrow().iter()
+            .filter(|(_, shares)| **shares > 1e-6)
+            .map(|(token, shares)| {
+                let (market, outcome) = token_markets.get(token).cloned().unwrap_or_else(|| (token.clone(), Outcome::Yes));
+                Leg { market, outcome, shares: *shares, price: 0.0 }
+            })
+            .collect();
+        exposure::net(&legs)
+    }
+
+    /// Point out complete sets in `market` that could be merged into USDC.
+    fn suggest_merge(&self, market: &MarketData) {
+        let exposure = self.exposure();
+        let Some((_, sets)) = exposure::merge_suggestions(&exposure, 1.0).into_iter().find(|(m, _)| *m == market.condition_id) else { return };
+        println!("\n💡 Holding {:.0} complete YES+NO set(s) of {}; merging frees ${:.2}", sets, market.title, sets);
+        self.notify(Severity::Info, Event::Mergeable, &format!("Mergeable sets in {}", market.slug),
+            &format!("{:.0} YES+NO set(s) of {} are riskless; merge to free ${:.2}", sets, market.title, sets));
+    }
+
+    fn cancel_order(&self, order_id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let request_path = "/order";
+        let url = format!("{}{}", self.network.clob_url, request_path);
+        let body = json!({ "orderID": order_id }).to_string();
+
+        let headers = self.create_auth_headers("DELETE", request_path, &body)?;
+        let resp: Value = self.send(self.client.delete(&url).headers(headers).body(body))?.error_for_status()?.json()?;
+        // Unknown or already-closed orders still come back 200, listed under not_canceled
+        if let Some(reason) = resp["not_canceled"].get(order_id) {
+            return Err(format!("not canceled: {}", reason.as_str().unwrap_or("unknown reason")).into());
+        }
+        self.order_moved(order_id, OrderState::Canceled, "canceled by the bot");
+        Ok(())
+    }
+
+    /// Cancel/replace: pull `order_id` and put the same order back at
+    /// `price` for `size` shares, less whatever matched while the cancel was
+    /// in flight. The replacement is submitted only once the cancel is
+    /// confirmed, so both never work at once. Returns the new order's id, or
+    /// None when the old one was already done or nothing is left to buy.
+    fn amend_order(&self, order_id: &str, price: f64, size: u32, expires_at: Option<u64>)
+        -> Result<Option<String>, Box<dyn std::error::Error>> {
+        let Some(spec) = self.orders.borrow().get(order_id).map(|o| o.spec.clone()) else {
+            return Err(format!("order {} is not ours", order_id).into());
+        };
+        let side = if spec.side == OrderSide::Sell.as_str() { OrderSide::Sell } else { OrderSide::Buy };
+        let filled_before = self.filled_size(order_id);
+        let canceled = self.cancel_order(order_id);
+        if let Ok(progress) = self.check_order_status(order_id) {
+            self.record_fill_progress(order_id, &progress);
+        }
+        if let Err(e) = canceled {
+            println!("   ↕️ Not amending {}: {}", order_id, e);
+            return Ok(None);
+        }
+        let size = shares_left(size, self.filled_size(order_id) - filled_before);
+        if size == 0 {
+            return Ok(None);
+        }
+        println!("   ↕️ Amending {} {} {}: ${:.3} x {:.0} -> ${:.3} x {}",
+            side, spec.order_type, order_id, spec.price, spec.size, price, size);
+        self.rest_order(&spec.token_id, price, size, side, &spec.order_type, expires_at)
+    }
+
+    /// Cancel every open order on the account. Returns the ids canceled.
+    fn cancel_all_orders(&self) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        self.cancel_orders_at("/cancel-all", String::new())
+    }
+
+    /// Cancel our open orders in the market with `condition_id`, either
+    /// outcome. Returns the ids canceled.
+    fn cancel_market_orders(&self, condition_id: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        self.cancel_orders_at("/cancel-market-orders", json!({ "market": condition_id }).to_string())
+    }
+
+    fn cancel_orders_at(&self, request_path: &str, body: String) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let url = format!("{}{}", self.network.clob_url, request_path);
+        let headers = self.create_auth_headers("DELETE", request_path, &body)?;
+        let resp: Value = self.send(self.client.delete(&url).headers(headers).body(body))?.error_for_status()?.json()?;
+        let canceled: Vec<String> = resp["canceled"].as_array().into_iter().flatten()
+            .filter_map(|id| id.as_str().map(str::to_string))
+            .collect();
+        for order_id in &canceled {
+            self.order_moved(order_id, OrderState::Canceled, "canceled by the bot");
+        }
+        Ok(canceled)
+    }
+
+    /// Pull whatever a crashed run left resting, so it can't fill behind
+    /// the bot's back. Runs after `recover_orders` has taken in their fills.
+    fn cancel_stale_orders(&self) {
+        if self.config.dry_run || self.config.paper || !self.cancel_on_startup {
+            return;
+        }
+        match self.cancel_all_orders() {
+            Ok(canceled) if !canceled.is_empty() => println!("🧹 Canceled {} order(s) left open by the last run", canceled.len()),
+            Ok(_) => {}
+            Err(e) => self.warn(format!("⚠️ Could not cancel orders left open by the last run: {}", e)),
+        }
+    }
+
+    /// Cancel anything still resting in markets that have closed by `now`.
+    fn close_out_markets(&mut self, now: u64) {
+        let (closed, open) = std::mem::take(&mut self.closing_markets).into_iter().partition(|(_, _, closes_at)| *closes_at <= now);
+        self.closing_markets = open;
+        if self.config.dry_run || self.config.paper {
+            return;
+        }
+        for (slug, condition_id, _) in closed {
+            match self.cancel_market_orders(&condition_id) {
+                Ok(canceled) if !canceled.is_empty() => println!("\n🧹 Canceled {} order(s) still open on closed market {}", canceled.len(), slug),
+                Ok(_) => {}
+                Err(e) => self.warn(format!("\n⚠️ Could not cancel orders on closed market {}: {}", slug, e)),
+            }
+        }
+    }
+
+    fn find_pending_submission(&self, token_id: &str, side: OrderSide) -> Option<SubmittedOrder> {
+        self.pending_submissions.borrow().values()
+            .find(|s| s.token_id == token_id && s.side == side)
+            .cloned()
+    }
+
+    fn resolve_ambiguous_submission(&self, submission: &SubmittedOrder, side: OrderSide, order_type: &str)
+        -> Result<Option<Fill>, Box<dyn std::error::Error>> {
+        for attempt in 1..=3 {
+            match self.reconcile_submission(submission) {
+                Ok(Some(order_id)) => return self.wait_for_fill(order_id, &submission.token_id, submission.size, side, order_type),
+                Ok(None) => {
+                    println!("   ✅ Exchange has no record of {}; safe to retry", submission.client_order_id);
+                    return Ok(None);
+                }
+                Err(e) => {
+                    println!("   ⚠️ Reconcile attempt {}/3 failed: {}", attempt, e);
+                    self.time.sleep(Duration::from_secs(1));
+                }
+            }
+        }
+        // Stays in pending_submissions so the next place_order reconciles before sending
+        Ok(None)
+    }
+
+    /// Pick up orders the last run left in flight: ones never sent are
+    /// dropped, ambiguous submissions reconciled, and accepted ones re-read
+    /// from the exchange so fills made while the bot was down are counted.
+    fn recover_orders(&self) {
+        let open: Vec<OrderRecord> = self.orders.borrow().open().into_iter().cloned().collect();
+        if open.is_empty() {
+            return;
+        }
+        println!("♻️ Recovering {} order(s) left open by the last run", open.len());
+        for order in open {
+            let side = if order.spec.side == OrderSide::Sell.as_str() { OrderSide::Sell } else { OrderSide::Buy };
+            match order.state {
+                OrderState::Created | OrderState::Signed => {
+                    self.order_moved(&order.key, OrderState::Canceled, "never submitted before restart");
+                }
+                OrderState::Submitted => {
+                    let submission = SubmittedOrder {
+                        client_order_id: order.key.clone(),
+                        token_id: order.spec.token_id.clone(),
+                        side,
+                        price: order.spec.price,
+                        size: order.spec.size as u32,
+                        submitted_at: order.updated_at(),
+                    };
+                    match self.reconcile_submission(&submission) {
+                        Ok(Some(order_id)) => self.resume_order(&order, &order_id, side),
+                        Ok(None) => println!("   ✅ {} never reached the exchange", order.key),
+                        Err(e) => {
+                            self.warn(format!("   ⚠️ Cannot reconcile {} yet ({}); it will be checked before the next order", order.key, e));
+                            self.pending_submissions.borrow_mut().insert(order.key.clone(), submission);
+                        }
+                    }
+                }
+                _ => self.resume_order(&order, order.exchange_id(), side),
+            }
+        }
+    }
+
+    /// Pick up shares the last run bought in the current market: the Data
+    /// API's positions restore the held size and the open entry, and the
+    /// market is marked entered so the loop doesn't buy it a second time.
+    fn recover_positions(&mut self) {
+        if self.config.dry_run || self.config.paper {
+            return;
+        }
+        let live: Vec<positions::DataPosition> = match positions::fetch_positions(&self.client, &self.network.data_url, self.trading_address, false) {
+            Ok(positions) => positions.into_iter().filter(|p| !p.redeemable && p.size >= 1.0).collect(),
+            Err(e) => {
+                self.warn(format!("⚠️ Cannot fetch positions to recover: {}", e));
+                return;
+            }
+        };
+        if live.is_empty() {
+            return;
+        }
+        let cycle_start = self.market_schedule.cycle_start(self.time.now_secs());
+        let market = match self.fetch_market(cycle_start) {
+            Ok(Some(market)) => market,
+            Ok(None) => return,
+            Err(e) => {
+                self.warn(format!("⚠️ Cannot look up {} to recover positions: {}", self.finder.describe(cycle_start), e));
+                return;
+            }
+        };
+        let slug = market.slug.clone();
+        let held = live.into_iter().filter(|p| p.asset == market.yes_token || p.asset == market.no_token);
+
+        for position in held {
+            let (side, outcome) = if position.asset == market.yes_token { ("YES", Outcome::Yes) } else { ("NO", Outcome::No) };
+            let price = if position.avg_price > 0.0 { position.avg_price } else { position.cur_price };
+            println!("♻️ Resuming {:.2} {} shares of {} @ ${:.3} held before the restart", position.size, side, slug, price);
+            self.positions.borrow_mut().insert(position.asset.clone(), position.size);
+            self.pnl.borrow_mut().open(&position.asset, position.size, position.size * price);
+            self.token_markets.borrow_mut().insert(position.asset.clone(), (market.condition_id.clone(), outcome));
+            self.open_entries.borrow_mut().entry(position.asset.clone()).or_insert(OpenEntry {
+                slug: market.slug.clone(),
+                title: market.title.clone(),
+                link: market.link.clone(),
+                side: side.to_string(),
+                price,
+                entered_at: None,
+                exit: None,
+                sold: 0.0,
+                proceeds: 0.0,
+                exit_fees: 0.0,
+            });
+            self.active_trade = true;
+        }
+        if self.active_trade {
+            self.position_limit.hold(&slug, self.closes_at(&market, cycle_start));
+        }
+        if self.active_trade && !self.traded_markets.contains(&slug) {
+            self.mark_traded(&slug, "entered");
+            #[cfg(feature = "resolution")]
+            self.resolution_watcher.watch(&market.condition_id, &market.title);
+        }
+    }
+
+    /// Track an accepted order again from its journaled fills and bring it
+    /// up to date with the exchange.
+    fn resume_order(&self, order: &OrderRecord, order_id: &str, side: OrderSide) {
+        self.tracked_orders.borrow_mut().insert(order_id.to_string(), TrackedOrder {
+            order_id: order_id.to_string(),
+            token_id: order.spec.token_id.clone(),
+            side,
+            progress: OrderProgress {
+                original_size: order.spec.size,
+                filled_size: order.filled,
+                avg_price: order.avg_price,
+                fee: order.fee,
+                ..Default::default()
+            },
+            chain_sourced_fill: 0.0,
+        });
+        match self.check_order_status(order_id) {
+            Ok(progress) => {
+                self.record_fill_progress(order_id, &progress);
+                let state = self.orders.borrow().get(order_id).map(|o| o.state.as_str()).unwrap_or("unknown");
+                println!("   ♻️ {} {} {}: {} ({:.2}/{:.2} filled)", side, order.spec.token_id, order_id, state, self.filled_size(order_id), order.spec.size);
+            }
+            Err(e) => self.warn(format!("   ⚠️ Could not refresh order {} ({}); leaving it {}", order_id, e, order.state.as_str())),
+        }
+    }
+
+    /// Ask the exchange whether an order with this client id exists, either
+    /// as a live/finished order or as fills. Ok(None) means it never landed.
+    fn reconcile_submission(&self, submission: &SubmittedOrder) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        let order_id = &submission.client_order_id;
+        let request_path = format!("/data/order/{}", order_id);
+        let url = format!("{}{}", self.network.clob_url, request_path);
+
+        let headers = self.create_auth_headers("GET", &request_path, "")?;
+        let resp = self.send(self.client.get(&url).headers(headers))?;
+
+        if resp.status().is_server_error() {
+            return Err(format!("order lookup HTTP {}", resp.status()).into());
+        }
+
+        // Unknown hashes come back as 404 or an empty/null body
+        let known = if resp.status().is_success() {
+            let text = resp.text().unwrap_or_default();
+            let text = text.trim();
+            if let Ok(found) = responses::parse_order_status(text.as_bytes()) {
+                // The hash covers token and price, so a different order
+                // under it means the record or the lookup is wrong
+                let price = found.price.as_deref().and_then(|p| p.parse::<f64>().ok());
+                let token = found.asset_id.as_deref();
+                if price.is_some_and(|p| (p - submission.price).abs() > 1e-9) || token.is_some_and(|t| t != submission.token_id) {
+                    return Err(format!("exchange order {} is {} @ ${}, not the {} @ ${} submitted",
+                        order_id, token.unwrap_or("?"), price.unwrap_or(0.0), submission.token_id, submission.price).into());
+                }
+            }
+            !text.is_empty() && text != "null"
+        } else {
+            false
+        };
+        let landed = known || !self.get_order_fills(order_id, &submission.token_id, Some(submission.submitted_at))?.is_empty();
+
+        self.pending_submissions.borrow_mut().remove(order_id);
+        if landed {
+            self.order_accepted(order_id, order_id);
+            Ok(Some(order_id.clone()))
+        } else {
+            self.order_moved(order_id, OrderState::Rejected, "exchange has no record of it");
+            Ok(None)
+        }
+    }
+
+    fn check_order_status(&self, order_id: &str) -> Result<OrderProgress, Box<dyn std::error::Error>> {
+        if let Some(progress) = self.paper_orders.borrow().get(order_id) {
+            return Ok(progress.clone());
+        }
+        let request_path = format!("/order/{}", order_id);
+        let url = format!("{}{}", self.network.clob_url, request_path);
+        
+        let headers = self.create_auth_headers("GET", &request_path, "")?;
+        let resp = self.send(self.client.get(&url).headers(headers))?;
+        
+        if !resp.status().is_success() {
+            return Err(format!("order status HTTP {}", resp.status()).into());
+        }
+
+        let order = responses::parse_order_status(&resp.bytes()?)?;
+        let mut progress = order.progress();
+        if progress.filled_size <= 0.0 && !matches!(progress.status.as_str(), "MATCHED" | "FILLED" | "COMPLETED") {
+            return Ok(progress);
+        }
+
+        // The trades endpoint is the source of truth for what we paid
+        if let Some(asset_id) = &order.asset_id {
+            match self.get_order_fills(order_id, asset_id, order.created_at) {
+                Ok(fills) => {
+                    if let Some(avg) = average_fill_price(&fills) {
+                        progress.avg_price = avg;
+                        progress.fee = fills.iter().map(|f| f.fee).sum();
+                        let fill_total: f64 = fills.iter().map(|f| f.size).sum();
+                        progress.filled_size = progress.filled_size.max(fill_total);
+                        return Ok(progress);
+                    }
+                }
+                Err(e) => println!("   ⚠️ Could not load fills for {}: {}", order_id, e),
+            }
+        }
+
+        progress.avg_price = order.reported_avg_price();
+        Ok(progress)
+    }
+
+    /// All fills of `order_id`, whether it matched as taker or rested as maker.
+    fn get_order_fills(&self, order_id: &str, asset_id: &str, created_at: Option<u64>) -> Result<Vec<OrderFill>, Box<dyn std::error::Error>> {
+        let request_path = "/data/trades";
+        let mut query = format!("asset_id={}&maker_address={:?}", asset_id, self.trading_address);
+        if let Some(ts) = created_at {
+            query.push_str(&format!("&after={}", ts.saturating_sub(60)));
+        }
+
+        let mut fills = Vec::new();
+        let mut cursor = String::new();
+
+        loop {
+            let mut url = format!("{}{}?{}", self.network.clob_url, request_path, query);
+            if !cursor.is_empty() {
+                url.push_str(&format!("&next_cursor={}", cursor));
+            }
+
+            let headers = self.create_auth_headers("GET", request_path, "")?;
+            let page: TradesPage = self.send(self.client.get(&url).headers(headers))?.error_for_status()?.json()?;
+
+            for trade in &page.data {
+                if trade.taker_order_id == order_id {
+                    fills.push(OrderFill::new(&trade.id, &trade.price, &trade.size, &trade.fee_rate_bps));
+                }
+                for maker in trade.maker_orders.iter().filter(|m| m.order_id == order_id) {
+                    fills.push(OrderFill::new(&trade.id, &maker.price, &maker.matched_amount, &maker.fee_rate_bps));
+                }
+            }
+
+            if page.next_cursor.is_empty() || page.next_cursor == market_cache::END_CURSOR {
+                break;
+            }
+            cursor = page.next_cursor;
+        }
+
+        Ok(fills)
+    }
+
+    /// Every order of ours still resting on the book, across all markets.
+    fn get_open_orders(&self) -> Result<Vec<OpenOrder>, Box<dyn std::error::Error>> {
+        self.query_open_orders("")
+    }
+
+    /// Our orders resting on the book for one token.
+    fn get_open_orders_on(&self, token_id: &str) -> Result<Vec<OpenOrder>, Box<dyn std::error::Error>> {
+        self.query_open_orders(&format!("asset_id={}", token_id))
+    }
+
+    /// `/data/orders`, narrowed by `filter` (a query string, or empty).
+    fn query_open_orders(&self, filter: &str) -> Result<Vec<OpenOrder>, Box<dyn std::error::Error>> {
+        let request_path = "/data/orders";
+        let mut orders = Vec::new();
+        let mut cursor = String::new();
+
+        loop {
+            let mut params: Vec<String> = Vec::new();
+            if !filter.is_empty() {
+                params.push(filter.to_string());
+            }
+            if !cursor.is_empty() {
+                params.push(format!("next_cursor={}", cursor));
+            }
+            let mut url = format!("{}{}", self.network.clob_url, request_path);
+            if !params.is_empty() {
+                url.push_str(&format!("?{}", params.join("&")));
+            }
+
+            let headers = self.create_auth_headers("GET", request_path, "")?;
+            let page = responses::parse_open_orders(&self.send(self.client.get(&url).headers(headers))?.error_for_status()?.bytes()?)?;
+            orders.extend(page.data);
+
+            if page.next_cursor.is_empty() || page.next_cursor == market_cache::END_CURSOR {
+                break;
+            }
+            cursor = page.next_cursor;
+        }
+
+        Ok(orders)
+    }
+
+    /// An order of ours already working `side` on `token_id`, which a new
+    /// one would double up on. A failed lookup is warned about and let
+    /// through, as is everything in dry-run and paper mode.
+    fn working_order(&self, token_id: &str, side: OrderSide) -> Option<OpenOrder> {
+        if self.config.dry_run || self.config.paper {
+            return None;
+        }
+        match self.get_open_orders_on(token_id) {
+            Ok(orders) => orders.into_iter().find(|o| o.asset_id == token_id && o.side.eq_ignore_ascii_case(side.as_str())),
+            Err(e) => {
+                self.warn(format!("   ⚠️ Cannot list open orders ({}); submitting anyway", e));
+                None
+            }
+        }
+    }
+
+    /// Whether a resting order currently qualifies for liquidity rewards.
+    fn is_order_scoring(&self, order_id: &str) -> Result<bool, Box<dyn std::error::Error>> {
+        // Signed path excludes the query string, same as py_clob_client
+        let request_path = "/order-scoring";
+        let url = format!("{}{}?order_id={}", self.network.clob_url, request_path, order_id);
+
+        let headers = self.create_auth_headers("GET", request_path, "")?;
+        let resp = self.send(self.client.get(&url).headers(headers))?;
+
+        if !resp.status().is_success() {
+            return Err(format!("order-scoring HTTP {}", resp.status()).into());
+        }
+
+        let scoring: OrderScoringResponse = resp.json()?;
+        Ok(scoring.scoring)
+    }
+
+    /// Batch variant: returns order id -> scoring for every id the server knows.
+    fn are_orders_scoring(&self, order_ids: &[String]) -> Result<HashMap<String, bool>, Box<dyn std::error::Error>> {
+        if order_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let request_path = "/orders-scoring";
+        let url = format!("{}{}", self.network.clob_url, request_path);
+        let body = serde_json::to_string(order_ids)?;
+
+        let headers = self.create_auth_headers("POST", request_path, &body)?;
+        let resp = self.send(self.client.post(&url).headers(headers).body(body))?;
+
+        if !resp.status().is_success() {
+            return Err(format!("orders-scoring HTTP {}", resp.status()).into());
+        }
+
+        Ok(resp.json()?)
+    }
+
+    /// Fetch pending exchange notifications and acknowledge them so they
+    /// are not delivered again.
+    fn poll_notifications(&self) -> Result<Vec<ExchangeEvent>, Box<dyn std::error::Error>> {
+        let request_path = "/notifications";
+        let url = format!("{}{}?signature_type={}", self.network.clob_url, request_path, self.signature_type);
+
+        let headers = self.create_auth_headers("GET", request_path, "")?;
+        let resp = self.send(self.client.get(&url).headers(headers))?;
+
+        if !resp.status().is_success() {
+            return Err(format!("notifications HTTP {}", resp.status()).into());
+        }
+
+        let notifications: Vec<Notification> = resp.json()?;
+        if notifications.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let events = notifications.iter().map(ExchangeEvent::from).collect();
+
+        let ids: Vec<String> = notifications.iter().map(|n| n.id.to_string()).collect();
+        let drop_url = format!("{}{}?ids={}", self.network.clob_url, request_path, ids.join(","));
+        let headers = self.create_auth_headers("DELETE", request_path, "")?;
+        if let Err(e) = self.send(self.client.delete(&drop_url).headers(headers)) {
+            self.warn(format!("\n   ⚠️ Failed to acknowledge notifications: {}", e));
+        }
+
+        Ok(events)
+    }
+
+    /// Returns true when an event means this market can no longer be traded.
+    fn handle_exchange_events(&self, market: &MarketData, events: &[ExchangeEvent]) -> bool {
+        let mut halt = false;
+        for event in events {
+            match event {
+                ExchangeEvent::OrderCancelled { order_id, market: m } => {
+                    println!("\n⚠️ Exchange cancelled order {} (market {})", order_id, m);
+                    self.order_moved(order_id, OrderState::Canceled, "canceled by the exchange");
+                    // Take in anything that matched before the cancel
+                    self.refresh_order(order_id);
+                    if let Some(tracked) = self.tracked_orders.borrow_mut().get_mut(order_id) {
+                        if !tracked.progress.is_closed() {
+                            tracked.progress.status = "CANCELED".to_string();
+                        }
+                    }
+                }
+                ExchangeEvent::OrderFilled { order_id, .. } => {
+                    println!("\n📬 Exchange reported fill for order {}", order_id);
+                    self.refresh_order(order_id);
+                }
+                ExchangeEvent::MarketResolved { market: m } => {
+                    println!("\n🏁 Exchange reported market {} resolved", m);
+                    if *m == market.condition_id {
+                        halt = true;
+                    }
+                }
+                ExchangeEvent::Other { notification_type } => {
+                    println!("\nℹ️ Exchange notification type {}", notification_type);
+                }
+            }
+        }
+        halt
+    }
+
+    /// Re-read a tracked order's status after the exchange reported on it,
+    /// so its fills reach the position, ledger and order journal now.
+    fn refresh_order(&self, order_id: &str) {
+        if !self.tracked_orders.borrow().contains_key(order_id) {
+            return;
+        }
+        match self.check_order_status(order_id) {
+            Ok(progress) => self.record_fill_progress(order_id, &progress),
+            Err(e) => self.warn(format!("   ⚠️ Could not read status of {}: {}", order_id, e)),
+        }
+    }
+
+    /// Gamma's endDate when it has a sane one, so a late or early close
+    /// moves the trading window with it; the schedule's cycle end otherwise.
+    fn closes_at(&self, market: &MarketData, market_start_ts: u64) -> u64 {
+        market.end_ts.filter(|end| *end > market_start_ts)
+            .unwrap_or_else(|| self.market_schedule.cycle_end(market_start_ts))
+    }
+
+    fn check_alerts(&mut self, market: &MarketData, closes_at: u64, yes_book: &OrderBook, no_book: &OrderBook) {
+        let secs_left = closes_at.saturating_sub(self.time.now_secs());
+        for alert in self.alerts.on_books(&market.slug, secs_left, yes_book, no_book) {
+            println!("\n🔔 ALERT [{}] {} at ${:.3} with {}s left", alert.rule, market.title, alert.value, alert.secs_left);
+            self.notify(Severity::Info, Event::PriceAlert, &format!("Price alert: {}", alert.rule),
+                &format!("{} at ${:.3} with {}s left", market.title, alert.value, alert.secs_left));
+            self.emit(BotEvent::Alert { market: alert.market, rule: alert.rule, value: alert.value, secs_left: alert.secs_left });
+        }
+    }
+
+    fn monitor_market(&mut self, market: MarketData, market_start_ts: u64) {
+        println!("\n{}", "=".repeat(60));
+        println!("📊 MONITORING: {}", market.title);
+        println!("🔗 Link: {}", market.link);
+        let closes_at = self.closes_at(&market, market_start_ts);
+        println!("🕒 Closes: {}{}", self.display_tz.time(closes_at), if market.end_ts.is_some() { "" } else { " (by schedule)" });
+        println!("{}", "=".repeat(60));
+
+        let mut monitor = EntryMonitor::new(self.strategy.clone(), market_start_ts).closing_at(closes_at);
+        let mut token_markets = self.token_markets.borrow_mut();
+        token_markets.insert(market.yes_token.clone(), (market.condition_id.clone(), Outcome::Yes));
+        token_markets.insert(market.no_token.clone(), (market.condition_id.clone(), Outcome::No));
+        drop(token_markets);
+        if let Some(stream) = &self.stream {
+            stream.watch(&[market.yes_token.clone(), market.no_token.clone()]);
+        }
+        self.emit(BotEvent::State { market: market.slug.clone(), state: "monitoring".to_string() });
+        let mut last_notification_poll = 0;
+        
+        loop {
+            // The market stays unmarked; the run loop shuts down
+            if shutdown::requested().is_some() {
+                return;
+            }
+            let current_time = self.time.now_secs();
+
+            match monitor.on_clock(current_time) {
+                Gate::Waiting { opens_in } => {
+                    // Alerts watch the whole market, not just the trading window
+                    if !self.alerts.is_empty() {
+                        if let (Some(yes_book), Some(no_book)) = (self.get_order_book_depth(&market.yes_token), self.get_order_book_depth(&market.no_token)) {
+                            self.check_alerts(&market, closes_at, &yes_book, &no_book);
+                        }
+                    }
+                    print!("\r⏳ Waiting for trading window ({}s remaining)...    ", opens_in);
+                    io::stdout().flush().unwrap();
+                    let opens_at = current_time + opens_in;
+                    shutdown::sleep(self.time.as_ref(), self.cadence.before_window(self.time.unix_secs_f64(), opens_at, !self.alerts.is_empty()));
+                    continue;
+                }
+                Gate::Closed => {
+                    println!("\n⏰ Market closed. Moving to next market.");
+                    self.mark_traded(&market.slug, "closed");
+                    return;
+                }
+                Gate::TimedOut => {
+                    println!("\n❌ Entry window timeout. Moving to next market.");
+                    self.mark_traded(&market.slug, "timed_out");
+                    return;
+                }
+                Gate::Opened => {
+                    println!("\n🔵 Entered trading window. Entry timeout starts now ({}s)", self.strategy.entry_timeout);
+                    self.emit(BotEvent::State { market: market.slug.clone(), state: "window_open".to_string() });
+                    self.refresh_exchange_status();
+                }
+                Gate::Open => {}
+            }
+
+            if current_time - last_notification_poll >= self.config.timing.notification_poll_interval {
+                last_notification_poll = current_time;
+                self.reconcile_chain_fills();
+                if let Ok(events) = self.poll_notifications() {
+                    if self.handle_exchange_events(&market, &events) {
+                        println!("\n🛑 Market halted exchange-side. Moving to next market.");
+                        self.mark_traded(&market.slug, "halted");
+                        return;
+                    }
+                }
+            }
+
+            if self.exchange_halted.get() {
+                let halted = self.now_secs().saturating_sub(self.last_status_check.get()) < self.config.timing.exchange_status_interval
+                    || self.refresh_exchange_status();
+                if halted {
+                    print!("\r⏸️ Exchange halted; re-checking every {}s...    ", self.config.timing.exchange_status_interval);
+                    io::stdout().flush().unwrap();
+                    self.time.sleep(Duration::from_secs(1));
+                    continue;
+                }
+            }
+
+            // Before the book fetch, so rules on API errors run while it fails
+            self.apply_rules();
+            let (Some(yes_book), Some(no_book)) = (self.get_order_book_depth(&market.yes_token), self.get_order_book_depth(&market.no_token)) else {
+                self.time.sleep(self.cadence.in_window(self.streaming(&market)));
+                continue;
+            };
+
+            self.record_books(&market, &yes_book, &no_book);
+            for (token, book) in [(&market.yes_token, &yes_book), (&market.no_token, &no_book)] {
+                if let Some(bid) = book.best_bid {
+                    self.pnl.borrow_mut().mark(token, bid);
+                }
+            }
+            self.emit(BotEvent::Tick {
+                market: market.slug.clone(),
+                secs_left: closes_at.saturating_sub(current_time),
+                yes_bid: yes_book.best_bid,
+                yes_ask: yes_book.best_ask,
+                no_bid: no_book.best_bid,
+                no_ask: no_book.best_ask,
+            });
+            self.check_alerts(&market, closes_at, &yes_book, &no_book);
+            let signal = monitor.on_books(&yes_book, &no_book);
+            if let Signal::Abort { ask } = signal {
+                println!("\n🚨 ABORT TRIGGERED: ASK price exceeded ${}", self.strategy.abort_ask_price);
+                self.record_abort(&market.slug, "monitoring", Some(ask));
+                self.mark_traded(&market.slug, "aborted");
+                return;
+            }
+
+            print!("\rMonitoring {} | YES: ${:.2}/${:.2} ({}) | NO: ${:.2}/${:.2} ({}) | Target: ${:.2}{}   ",
+                self.strategy.trade_side.as_str(), yes_book.best_bid.unwrap_or(0.0), yes_book.best_ask.unwrap_or(0.0), yes_book.ask_size as u32,
+                no_book.best_bid.unwrap_or(0.0), no_book.best_ask.unwrap_or(0.0), no_book.ask_size as u32, self.strategy.entry_price, self.pnl_status());
+            io::stdout().flush().unwrap();
+
+            if let Signal::Enter { outcome, ask, tie } = signal {
+                if self.active_trade {
+                    // Already entered; a slot is only taken for an entry about to be tried
+                } else if self.rules.borrow().is_paused() {
+                    print!("\r⏸️ Entry signal on {} ignored: paused by BOT_RULES    ", outcome.as_str());
+                    io::stdout().flush().unwrap();
+                } else if let Err(open) = self.position_limit.try_open(&market.slug, closes_at, current_time) {
+                    print!("\r⏸️ Entry signal on {} held back: {} of {} positions open across markets    ", outcome.as_str(), open, self.position_limit.max());
+                    io::stdout().flush().unwrap();
+                } else {
+                    let token = match outcome {
+                        Outcome::Yes => market.yes_token.clone(),
+                        Outcome::No => market.no_token.clone(),
+                    };
+                    println!("\n🚀 ENTRY TRIGGERED: {} - Placing order...", outcome.as_str());
+                    if let Some(criterion) = tie {
+                        println!("⚖️ Both sides triggered; {} chosen by {} (tie-break {})",
+                            outcome.as_str(), criterion.as_str(), self.strategy.tie_break);
+                    }
+                    self.emit(BotEvent::State { market: market.slug.clone(), state: "entering".to_string() });
+                    let deadline = monitor.deadline().unwrap_or(current_time);
+                    self.execute_trade(&market, outcome, &token, ask, deadline);
+                    if !self.active_trade {
+                        self.position_limit.release(&market.slug);
+                    } else if self.position(&token) >= 1.0
+                        && (self.config.strategy.trailing_stop.is_some() || self.config.strategy.exit_before_close > 0) {
+                        self.manage_position(&market, &token, closes_at);
+                    }
+                    return;
+                }
+            }
+
+            self.time.sleep(self.cadence.in_window(self.streaming(&market)));
+        }
+    }
+
+    /// Append the books just read, and any trades the stream printed for
+    /// this market, to BOT_RECORD_DIR. A failed write is reported, not fatal.
+    fn record_books(&self, market: &MarketData, yes: &OrderBook, no: &OrderBook) {
+        let Some(recorder) = &self.recorder else { return };
+        let ts_ms = (self.time.unix_secs_f64() * 1000.0) as u64;
+        let mut records = vec![Record::Book { ts_ms, market: market.slug.clone(), yes: *yes, no: *no }];
+        for trade in self.stream.as_ref().map(|s| s.take_trades()).unwrap_or_default() {
+            if trade.token_id == market.yes_token || trade.token_id == market.no_token {
+                records.push(Record::Trade {
+                    ts_ms: if trade.ts_ms > 0 { trade.ts_ms } else { ts_ms },
+                    market: market.slug.clone(),
+                    token_id: trade.token_id,
+                    price: trade.price,
+                    size: trade.size,
+                    side: trade.side,
+                });
+            }
+        }
+        let mut recorder = recorder.borrow_mut();
+        for record in &records {
+            if let Err(e) = recorder.record(record) {
+                self.warn(format!("\n   ⚠️ Could not record books to {}: {}", recorder.dir().display(), e));
+                return;
+            }
+        }
+    }
+
+    /// Whether both of the market's books are coming off the stream.
+    fn streaming(&self, market: &MarketData) -> bool {
+        self.stream.as_ref().is_some_and(|s| s.book(&market.yes_token).is_some() && s.book(&market.no_token).is_some())
+    }
+
+    /// Work the entry until it fills, aborts or runs out of attempts. With
+    /// a passive entry style, bids rest inside the spread and get more
+    /// aggressive as `deadline` approaches; a limit entry rests at the
+    /// entry price until `deadline` and never crosses.
+    fn execute_trade(&mut self, market: &MarketData, outcome: Outcome, token_id: &str, entry_ask: f64, deadline: u64) {
+        let side = outcome.as_str();
+        println!("\n🎯 Attempting {} entry at ${:.3}", side, entry_ask);
+        
+        let Some(base_size) = self.base_position_size(entry_ask) else {
+            self.mark_traded(&market.slug, "skipped");
+            return;
+        };
+        let sized = StrategyParams { position_size: base_size, ..self.strategy.clone() };
+        let position_size = strategy::entry_size(&sized, outcome, self.max_position_size.get());
+        if position_size == 0 {
+            println!("\n🚨 Skipping entry: collateral too low for even 1 share");
+            self.mark_traded(&market.slug, "skipped");
+            return;
+        }
+        if self.config.dry_run {
+            println!("🧪 Dry run: would buy {} {} shares at up to ${:.3}", position_size, side, entry_ask);
+            self.mark_traded(&market.slug, "dry-run");
+            return;
+        }
+        if !self.config.strategy.tranches.is_empty() {
+            self.scale_in(market, side, token_id, position_size, entry_ask, deadline);
+            return;
+        }
+        // Shares bought so far, fractions included
+        let mut bought = 0.0;
+        let mut remaining_size = position_size;
+        let started = self.now_secs();
+
+        for attempt in 1..=20 {
+            self.apply_rules();
+            if self.rules.borrow().is_paused() {
+                println!("\n⏸️ Entry stopped: paused by BOT_RULES");
+                self.mark_traded(&market.slug, "paused");
+                self.finish_entry(market, side, token_id, position_size, entry_ask);
+                return;
+            }
+            if self.strategy.entry_exec.style == EntryStyle::Limit && self.now_secs() >= deadline {
+                println!("\n⌛ Limit bid unfilled by the entry deadline");
+                self.mark_traded(&market.slug, "entry_failed");
+                self.finish_entry(market, side, token_id, position_size, entry_ask);
+                return;
+            }
+            if let Some(current_book) = self.get_order_book_depth(token_id) {
+                let current_bid = current_book.best_bid.unwrap_or(0.0);
+                
+                if let Some(current_ask) = current_book.best_ask {
+                    if current_ask > self.strategy.abort_ask_price {
+                        println!("\n🚨 ABORT during entry: ASK ${:.3} > ${}", current_ask, self.strategy.abort_ask_price);
+                        self.record_abort(&market.slug, "entry", Some(current_ask));
+                        self.mark_traded(&market.slug, "aborted");
+                        self.finish_entry(market, side, token_id, position_size, entry_ask);
+                        return;
+                    }
+                } else {
+                    self.time.sleep(Duration::from_secs(1));
+                    continue;
+                }
+                
+                let current_ask = current_book.best_ask.unwrap();
+
+                if current_bid < self.strategy.entry_price - 0.02 {
+                    self.time.sleep(Duration::from_secs(1));
+                    continue;
+                }
+
+                if let Some(working) = self.working_order(token_id, OrderSide::Buy) {
+                    println!("⏸️ Entry Attempt {}/20: buy {} already working ({:.2} @ ${:.3}); not submitting another",
+                        attempt, working.id, working.remaining(), working.price());
+                    self.time.sleep(Duration::from_secs(1));
+                    continue;
+                }
+                let Some(quote) = self.strategy.entry_quote(&current_book, started, self.now_secs(), deadline) else { continue };
+                if !quote.cross {
+                    let limit = self.strategy.entry_exec.style == EntryStyle::Limit;
+                    println!("🔄 Entry Attempt {}/20: Resting {} @ ${:.3} (ask ${:.3})",
+                        attempt, if limit { "GTD" } else { "GTC" }, quote.price, current_ask);
+                    bought += if limit {
+                        self.work_limit_bid(token_id, quote.price, remaining_size, started, deadline)
+                    } else {
+                        self.rest_entry_bid(token_id, quote.price, remaining_size, deadline)
+                    };
+                    remaining_size = shares_left(position_size, bought);
+                    if remaining_size == 0 {
+                        self.finish_entry(market, side, token_id, position_size, entry_ask);
+                        return;
+                    }
+                    continue;
+                }
+
+                if current_book.ask_size < remaining_size as f64 {
+                    self.time.sleep(Duration::from_secs(1));
+                    continue;
+                }
+
+                println!("🔄 Entry Attempt {}/20: Placing FOK @ ${:.3}", attempt, current_ask);
+                
+                match self.place_order(token_id, current_ask, remaining_size, OrderSide::Buy, "FOK") {
+                    Ok(Some(fill)) => {
+                        bought += fill.filled_size;
+                        remaining_size = shares_left(position_size, bought);
+                        if remaining_size == 0 || self.config.strategy.partial_fill != PartialFillAction::Requote {
+                            self.finish_entry(market, side, token_id, position_size, entry_ask);
+                            return;
+                        }
+                        println!("   🔁 Re-quoting remaining {} shares", remaining_size);
+                    },
+                    _ => {
+                        self.time.sleep(Duration::from_millis(500));
+                    }
+                }
+            }
+        }
+
+        println!("\n⚠️ Failed to enter after 20 attempts.");
+        self.mark_traded(&market.slug, "entry_failed");
+        self.finish_entry(market, side, token_id, position_size, entry_ask);
+    }
+
+    /// Shares for an entry at `price` before the YES half and the low
+    /// balance cap: `[strategy] sizing`'s share of the USDC balance, or the
+    /// fixed position_size. None when sizing can't tell what the balance
+    /// affords, or it affords less than min_shares; the entry is skipped.
+    fn base_position_size(&self, price: f64) -> Option<u32> {
+        let Some(sizing) = self.config.strategy.sizing else { return Some(self.strategy.position_size) };
+        match self.get_balance_allowance(AssetType::Collateral, "") {
+            Ok(ba) => {
+                let shares = sizing.shares(ba.balance, price);
+                if shares == 0 && sizing.min_shares > 0 {
+                    self.warn(format!("   ⚠️ ${:.2} USDC buys fewer than min_shares {} at ${:.3}; skipping entry", ba.balance, sizing.min_shares, price));
+                    return None;
+                }
+                println!("💰 Sizing: {}% of ${:.2} USDC at ${:.3} is {} shares", sizing.balance_pct, ba.balance, price, shares);
+                Some(shares)
+            }
+            Err(e) => {
+                self.warn(format!("   ⚠️ Could not read collateral balance for sizing ({}); skipping entry", e));
+                None
+            }
+        }
+    }
+
+    /// Enter in `[strategy] tranches`: a GTD bid per tranche at its own
+    /// price, all left working until the deadline, then pulled. Whatever
+    /// they buy is one position, priced at the blend of their fills.
+    fn scale_in(&mut self, market: &MarketData, side: &str, token_id: &str, position_size: u32, entry_ask: f64, deadline: u64) {
+        let tranches = strategy::tranche_sizes(&self.config.strategy.tranches, position_size);
+        println!("🪜 Scaling in: {}", tranches.iter().map(|(price, size)| format!("{} @ ${:.3}", size, price)).collect::<Vec<_>>().join(", "));
+        // The exchange keeps a GTD order a minute past its expiration
+        let expires_at = deadline.max(self.now_secs()) + 60;
+        let mut bids: Vec<String> = Vec::new();
+        for (price, size) in tranches {
+            if self.now_secs() >= deadline || shutdown::requested().is_some() {
+                break;
+            }
+            match self.rest_order(token_id, price, size, OrderSide::Buy, "GTD", Some(expires_at)) {
+                Ok(placed) => bids.extend(placed),
+                Err(e) => self.warn(format!("   ⚠️ Tranche bid @ ${:.3} failed: {}", price, e)),
+            }
+        }
+
+        let abort = self.watch_entry_bids(token_id, &bids, deadline);
+        if let Some(ask) = abort {
+            println!("\n🚨 ABORT during entry: ASK ${:.3} > ${}", ask, self.strategy.abort_ask_price);
+            self.record_abort(&market.slug, "entry", Some(ask));
+        }
+        let bought: f64 = bids.iter().map(|order_id| self.pull_entry_bid(order_id)).sum();
+
+        if abort.is_some() {
+            self.mark_traded(&market.slug, "aborted");
+        } else if bought < 1.0 {
+            println!("\n⌛ Tranche bids unfilled by the entry deadline");
+            self.mark_traded(&market.slug, "entry_failed");
+        }
+        self.finish_entry(market, side, token_id, position_size, entry_ask);
+    }
+
+    /// Rest a GTC bid for PASSIVE_REST_SECS, or to the deadline if that's
+    /// sooner, then pull what's left of it. Returns the shares it bought,
+    /// counting any that matched while the cancel was in flight.
+    fn rest_entry_bid(&self, token_id: &str, price: f64, size: u32, deadline: u64) -> f64 {
+        let Ok(Some(order_id)) = self.rest_order(token_id, price, size, OrderSide::Buy, "GTC", None) else { return 0.0 };
+        let until = deadline.min(self.now_secs() + PASSIVE_REST_SECS);
+        // An abort is caught by the entry loop on its next pass
+        self.watch_entry_bids(token_id, std::slice::from_ref(&order_id), until);
+        self.pull_entry_bid(&order_id)
+    }
+
+    /// Poll resting entry bids until none is working, `until` passes or the
+    /// ask goes past the abort price. Returns that ask on an abort.
+    fn watch_entry_bids(&self, token_id: &str, order_ids: &[String], until: u64) -> Option<f64> {
+        loop {
+            let working: Vec<&String> = order_ids.iter()
+                .filter(|id| self.orders.borrow().get(id).is_some_and(|o| !o.state.is_terminal()))
+                .collect();
+            if working.is_empty() || self.now_secs() >= until || shutdown::requested().is_some() {
+                return None;
+            }
+            if let Some(ask) = self.get_order_book_depth(token_id).and_then(|b| b.best_ask) {
+                if ask > self.strategy.abort_ask_price {
+                    return Some(ask);
+                }
+            }
+            for order_id in working {
+                if let Ok(progress) = self.check_order_status(order_id) {
+                    self.record_fill_progress(order_id, &progress);
+                }
+            }
+            self.time.sleep(Duration::from_secs(2));
+        }
+    }
+
+    /// Rest a GTD bid at `price`, expiring at `deadline`, and keep it at
+    /// the quote: when the book moves, the bid is amended to the new price.
+    /// It comes down when it fills, the ask goes past the abort price or the
+    /// deadline passes. Returns the shares it bought.
+    fn work_limit_bid(&self, token_id: &str, mut price: f64, size: u32, started: u64, deadline: u64) -> f64 {
+        // The exchange keeps a GTD order a minute past its expiration
+        let expires_at = deadline.max(self.now_secs()) + 60;
+        // Bought by bids already replaced
+        let mut replaced_fills = 0.0;
+        let Ok(Some(mut order_id)) = self.rest_order(token_id, price, size, OrderSide::Buy, "GTD", Some(expires_at)) else { return 0.0 };
+        loop {
+            let open = self.orders.borrow().get(&order_id).is_some_and(|o| !o.state.is_terminal());
+            if !open || self.now_secs() >= deadline || shutdown::requested().is_some() {
+                break;
+            }
+            if let Some(book) = self.get_order_book_depth(token_id) {
+                if book.best_ask.is_some_and(|ask| ask > self.strategy.abort_ask_price) {
+                    break;
+                }
+                match self.strategy.entry_quote(&book, started, self.now_secs(), deadline) {
+                    Some(quote) if (quote.price - price).abs() < 1e-9 => {}
+                    Some(quote) => {
+                        println!("   ↕️ Book moved; limit bid ${:.3} -> ${:.3}", price, quote.price);
+                        let bought = replaced_fills + self.filled_size(&order_id);
+                        let left = shares_left(size, bought);
+                        match self.amend_order(&order_id, quote.price, left, Some(expires_at)) {
+                            Ok(Some(new_id)) => {
+                                replaced_fills += self.filled_size(&order_id);
+                                order_id = new_id;
+                                price = quote.price;
+                                continue;
+                            }
+                            Ok(None) => break,
+                            Err(e) => {
+                                self.warn(format!("   ⚠️ Failed to amend limit bid {}: {}", order_id, e));
+                                break;
+                            }
+                        }
+                    }
+                    None => break,
+                }
+            }
+            if let Ok(progress) = self.check_order_status(&order_id) {
+                self.record_fill_progress(&order_id, &progress);
+                if progress.is_filled() {
+                    println!("🎊 EXECUTED: BUY GTD filled at ${:.2}", progress.avg_price);
+                    self.balance_cache.borrow_mut().clear();
+                    break;
+                }
+            }
+            self.time.sleep(Duration::from_secs(2));
+        }
+        replaced_fills + self.pull_entry_bid(&order_id)
+    }
+
+    /// Cancel `order_id` if it's still working and take in its last fills.
+    /// Returns the shares it bought.
+    fn pull_entry_bid(&self, order_id: &str) -> f64 {
+        let open = self.orders.borrow().get(order_id).is_some_and(|o| !o.state.is_terminal());
+        if open {
+            if let Err(e) = self.cancel_order(order_id) {
+                self.warn(format!("   ⚠️ Failed to cancel resting bid {}: {}", order_id, e));
+            }
+            if let Ok(progress) = self.check_order_status(order_id) {
+                self.record_fill_progress(order_id, &progress);
+            }
+        }
+        self.filled_size(order_id)
+    }
+
+    /// Watch a fresh position until its market closes, selling it into the
+    /// bid if the trailing stop fires or exit_before_close comes due.
+    /// Otherwise it rides to resolution.
+    fn manage_position(&mut self, market: &MarketData, token_id: &str, closes_at: u64) {
+        let s = &self.config.strategy;
+        let entry_price = self.open_entries.borrow().get(token_id).map(|e| e.price).unwrap_or(self.strategy.entry_price);
+        let mut stop = s.trailing_stop.map(|t| StopLoss::new(entry_price, s.stop_loss_price, Some(t), s.sustain_time));
+        let exit_before_close = s.exit_before_close;
+        if let (Some(stop), Some(t)) = (&stop, s.trailing_stop) {
+            println!("🛡️ Stop at ${:.3}, trailing ${:.3} under the bid from ${:.3}", stop.price(), t.trail, entry_price + t.activation);
+        }
+        if exit_before_close > 0 {
+            println!("⏰ Selling {}s before close", exit_before_close);
+        }
+
+        loop {
+            // The run loop shuts down, liquidating if told to
+            if shutdown::requested().is_some() {
+                return;
+            }
+            let now = self.now_secs();
+            if now >= closes_at {
+                println!("\n🏁 Market closed{}; holding to resolution",
+                    stop.as_ref().map(|s| format!(" with the stop at ${:.3}", s.price())).unwrap_or_default());
+                return;
+            }
+            let held = self.position(token_id);
+            if held < 1.0 {
+                return;
+            }
+            let Some(bid) = self.get_order_book_depth(token_id).and_then(|b| b.best_bid) else {
+                self.time.sleep(self.cadence.in_window(self.streaming(market)));
+                continue;
+            };
+            self.pnl.borrow_mut().mark(token_id, bid);
+
+            let left = closes_at - now;
+            let exit = if exit_before_close > 0 && left <= exit_before_close {
+                println!("\n⏰ FORCED EXIT: {}s to close; selling {:.0} shares at ${:.3}{}", left, held.floor(), bid, ladder_floor(&self.sell_ladder(bid), bid));
+                Some(ExitReason::BeforeClose { left, at: now })
+            } else if let Some(stop) = stop.as_mut() {
+                let before = stop.price();
+                let fired = stop.on_bid(bid, now);
+                if stop.price() > before + 1e-9 {
+                    println!("\n   📈 Stop raised to ${:.3} (bid ${:.3})", stop.price(), bid);
+                }
+                fired.then(|| {
+                    println!("\n🛑 STOP LOSS: bid ${:.3} at or under the ${:.3} stop; selling {:.0} shares{}", bid, stop.price(), held.floor(), ladder_floor(&self.sell_ladder(bid), bid));
+                    ExitReason::StopLoss { price: stop.price(), at: now }
+                })
+            } else {
+                None
+            };
+            if let Some(reason) = exit {
+                // A forced exit keeps the reason it was first given
+                if let Some(entry) = self.open_entries.borrow_mut().get_mut(token_id) {
+                    entry.exit.get_or_insert(reason);
+                }
+                let ladder = self.sell_ladder(bid);
+                match self.sell_down_ladder(token_id, &ladder, held.floor() as u32) {
+                    Ok(Some(fill)) if fill.remaining < 1.0 => return,
+                    Ok(_) => println!("   ⚠️ Exit sell incomplete; {:.2} shares still held", self.position(token_id)),
+                    Err(e) => self.warn(format!("   ⚠️ Exit sell failed: {}", e)),
+                }
+            }
+            print!("\r🛡️ Holding {:.2} | bid ${:.2}{} | {}s to close{}    ", held, bid,
+                stop.as_ref().map(|s| format!(" | stop ${:.2}", s.price())).unwrap_or_default(), left, self.pnl_status());
+            io::stdout().flush().unwrap();
+            self.time.sleep(self.cadence.in_window(self.streaming(market)));
+        }
+    }
+
+    /// Record whatever was actually acquired, which may be less than targeted.
+    fn finish_entry(&mut self, market: &MarketData, side: &str, token_id: &str, target_size: u32, signal_ask: f64) {
+        let held = self.position(token_id);
+        if held <= 0.0 {
+            return;
+        }
+
+        self.active_trade = true;
+        self.mark_traded(&market.slug, "entered");
+        #[cfg(feature = "resolution")]
+        self.resolution_watcher.watch(&market.condition_id, &market.title);
+
+        let (avg_price, fees, order_ids) = {
+            let orders = self.tracked_orders.borrow();
+            let fills: Vec<OrderFill> = orders.values()
+                .filter(|o| o.token_id == token_id && o.side == OrderSide::Buy && o.progress.filled_size > 0.0)
+                .map(|o| OrderFill { trade_id: o.order_id.clone(), price: o.progress.avg_price, size: o.progress.filled_size, fee: o.progress.fee })
+                .collect();
+            let order_ids: Vec<&str> = fills.iter().map(|f| f.trade_id.as_str()).collect();
+            (average_fill_price(&fills).unwrap_or(0.0), fills.iter().map(|f| f.fee).sum::<f64>(), order_ids.join(" "))
+        };
+
+        self.open_entries.borrow_mut().insert(token_id.to_string(), OpenEntry {
+            slug: market.slug.clone(),
+            title: market.title.clone(),
+            link: market.link.clone(),
+            side: side.to_string(),
+            price: avg_price,
+            entered_at: Some(self.time.now_secs()),
+            exit: None,
+            sold: 0.0,
+            proceeds: 0.0,
+            exit_fees: 0.0,
+        });
+
+        let partial = held + 1e-6 < target_size as f64;
+        self.log_trade(TradeRecord {
+            title: market.title.clone(),
+            link: market.link.clone(),
+            status: if partial { "PARTIAL".to_string() } else { "ENTERED".to_string() },
+            market: market.slug.clone(),
+            side: side.to_string(),
+            token_id: token_id.to_string(),
+            order_id: order_ids,
+            entry_time: Some(self.time.now_secs()),
+            entry_price: Some(avg_price),
+            size: Some(held),
+            fees: Some(fees),
+            slippage: Some(avg_price - signal_ask),
+            notes: if partial { format!("Filled {:.2} of {} target", held, target_size) } else { String::new() },
+            ..Default::default()
+        });
+        self.notify(Severity::Info, Event::EntryFilled, &format!("Entered {} {}", side, market.slug),
+            &format!("{}: {:.2} shares @ ${:.3}{}", market.title, held, avg_price, if partial { " (partial)" } else { "" }));
+        self.suggest_merge(market);
+    }
+
+    #[cfg(feature = "resolution")]
+    fn poll_resolutions(&mut self) {
+        let alerts = match self.resolution_watcher.poll(&self.client, &self.network.gamma_url, &self.rpc, self.network.ctf) {
+            Ok(alerts) => alerts,
+            Err(e) => {
+                self.warn(format!("\n⚠️ Resolution poll failed: {}", e));
+                return;
+            }
+        };
+
+        for alert in alerts {
+            match alert.to {
+                ResolutionState::Disputed => {
+                    println!("\n🚨 DISPUTE: {} ({}) — redemption delayed until the dispute settles", alert.title, alert.condition_id);
+                }
+                ResolutionState::Proposed => {
+                    println!("\n⚖️ Outcome proposed for {} — challenge window running", alert.title);
+                }
+                ResolutionState::Resolved => {
+                    println!("\n🏁 {} resolved on-chain — ready to redeem", alert.title);
+                }
+                ResolutionState::AwaitingProposal => {
+                    println!("\n⏳ {} closed, awaiting oracle proposal", alert.title);
+                }
+                ResolutionState::Open => {}
+            }
+        }
+    }
+
+    /// Scan the trading address for resolved positions and redeem them all,
+    /// reporting how much USDC came back.
+    #[cfg(feature = "redeem")]
+    fn claim_winnings(&self) -> Result<(), Box<dyn std::error::Error>> {
+        println!("🔎 Scanning positions for redeemable winnings...");
+        let positions = redemption::fetch_positions(&self.client, &self.network.data_url, self.trading_address, true)?;
+        let ctf = self.network.ctf;
+        let collateral = collateral::exchange_collateral(&self.rpc, &self.network);
+
+        let mut plans = redemption::plan_redemptions(&positions);
+        // The data API can flag markets before payouts land; trust the chain
+        plans.retain(|plan| {
+            let condition_id = format!("{:?}", plan.condition_id);
+            match resolution::payouts_reported(&self.rpc, ctf, &condition_id) {
+                Ok(true) => true,
+                Ok(false) => {
+                    println!("   ⏳ {} not resolved on-chain yet, skipping", plan.title);
+                    false
+                }
+                Err(e) => {
+                    println!("   ⚠️ {}: resolution check failed ({}), skipping", plan.title, e);
+                    false
+                }
+            }
+        });
+
+        if plans.is_empty() {
+            println!("✅ Nothing to redeem.");
+            return Ok(());
+        }
+
+        for plan in &plans {
+            println!("   🏆 {} — expected ${:.2}", plan.title, plan.expected_value);
+        }
+
+        let requests = plans.iter()
+            .map(|plan| plan.tx(&self.network, collateral))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let before = chain::erc20_balance_of(&self.rpc, collateral, self.trading_address)?;
+        let receipt = self.send_onchain(&format!("Redeem {} market(s)", plans.len()), &requests)?;
+        let after = chain::erc20_balance_of(&self.rpc, collateral, self.trading_address)?;
+
+        let recovered = after.saturating_sub(before).as_u128() as f64 / 1_000_000.0;
+        println!("✅ Redeemed {} market(s) in tx {:?}", plans.len(), receipt.transaction_hash);
+        self.refresh_collateral();
+        let redeemed: Vec<String> = plans.iter().map(|p| format!("{:?}", p.condition_id)).collect();
+        let redeemed: Vec<&positions::DataPosition> = positions.iter()
+            .filter(|p| redeemed.contains(&p.condition_id.to_lowercase()))
+            .collect();
+        let payouts: Vec<(String, f64)> = redeemed.iter().map(|p| (p.asset.clone(), p.size * p.cur_price)).collect();
+        let entry = self.ledger.borrow().redemption_entry(self.time.now_secs(), &format!("{:?}", receipt.transaction_hash), &payouts);
+        self.book(vec![entry]);
+
+        // Resolution realizes what the data API says each position cost
+        for p in &redeemed {
+            let mut pnl = self.pnl.borrow_mut();
+            pnl.open(&p.asset, p.size, p.size * p.avg_price);
+            pnl.resolve(&p.asset, p.cur_price);
+            let realized = pnl.take_realized(&p.asset);
+            drop(pnl);
+            self.log_trade(TradeRecord {
+                title: p.title.clone(),
+                status: "REDEEMED".to_string(),
+                side: p.outcome.to_uppercase(),
+                token_id: p.asset.clone(),
+                order_id: format!("{:?}", receipt.transaction_hash),
+                entry_price: Some(p.avg_price),
+                size: Some(p.size),
+                exit_time: Some(self.time.now_secs()),
+                exit_price: Some(p.cur_price),
+                pnl: Some(realized),
+                ..Default::default()
+            });
+        }
+        println!("💰 Total USDC recovered: ${:.2}, {}", recovered, self.pnl.borrow().summary());
+        let titles: Vec<&str> = plans.iter().map(|p| p.title.as_str()).collect();
+        self.notify(Severity::Info, Event::Exit, &format!("Redeemed {} market(s) for ${:.2}", plans.len(), recovered),
+            &format!("{}; {}", titles.join(", "), self.pnl.borrow().summary()));
+        Ok(())
+    }
+
+    /// A UTC day just ended: its summary on the console, appended to
+    /// daily_report.csv and sent to the notification channels.
+    fn daily_report(&self, day: &str) {
+        let records = match self.trade_log.read() {
+            Ok(records) => records,
+            Err(e) => {
+                self.warn(format!("\n⚠️ No daily summary for {}: {}", day, e));
+                return;
+            }
+        };
+        let summary = report::daily(&records).into_iter()
+            .find(|s| s.day == day)
+            .unwrap_or_else(|| DailySummary { day: day.to_string(), ..Default::default() });
+        println!("\n📅 {}", summary.text());
+        let path = self.profile.path(DAILY_REPORT_FILE);
+        let header = !std::path::Path::new(&path).exists();
+        let written = OpenOptions::new().create(true).append(true).open(&path)
+            .map_err(|e| e.into())
+            .and_then(|file| report::write_csv(std::slice::from_ref(&summary), file, header));
+        if let Err(e) = written {
+            println!("   ⚠️ Could not write {}: {}", path, e);
+        }
+        self.notify(Severity::Info, Event::DailyReport, &format!("Daily summary {}", day), &summary.text());
+    }
+
+    /// Wind down after SIGINT/SIGTERM: cancel whatever rests on the book,
+    /// sell held positions if risk.liquidate_on_shutdown says so, and leave
+    /// a SHUTDOWN row in the journal saying what was done.
+    fn shut_down(&mut self, signal: &str) -> Result<(), Box<dyn std::error::Error>> {
+        println!("\n🛑 Shutting down ({})", signal);
+        let mut notes = vec![signal.to_string()];
+
+        if !self.config.dry_run && !self.config.paper {
+            match self.get_open_orders() {
+                Ok(orders) => {
+                    let canceled = orders.iter().filter(|o| match self.cancel_order(&o.id) {
+                        Ok(()) => {
+                            println!("   ✅ Canceled {} ({} {:.2} @ ${:.3})", o.id, o.side, o.remaining(), o.price());
+                            true
+                        }
+                        Err(e) => {
+                            println!("   ❌ {}: {}", o.id, e);
+                            false
+                        }
+                    }).count();
+                    notes.push(format!("canceled {} of {} open order(s)", canceled, orders.len()));
+                }
+                Err(e) => {
+                    println!("   ⚠️ Cannot list open orders to cancel: {}", e);
+                    notes.push("open orders not canceled".to_string());
+                }
+            }
+        }
+
+        let held = || self.positions.borrow().values().filter(|shares| **shares >= 1.0).count();
+        let before = held();
+        if before > 0 && self.config.risk.liquidate_on_shutdown {
+            self.liquidate();
+            notes.push(format!("liquidated {} of {} position(s)", before - held(), before));
+        } else if before > 0 {
+            println!("   📌 Leaving {} position(s) open; set risk.liquidate_on_shutdown to sell them", before);
+            notes.push(format!("left {} position(s) open", before));
+        }
+
+        let pnl = self.pnl.borrow().clone();
+        if !pnl.is_empty() {
+            notes.push(pnl.summary());
+        }
+        self.log_trade(TradeRecord {
+            status: "SHUTDOWN".to_string(),
+            exit_time: Some(self.time.now_secs()),
+            pnl: (!pnl.is_empty()).then(|| pnl.realized()),
+            notes: notes.join("; "),
+            ..Default::default()
+        });
+        self.notify(Severity::Warning, Event::Shutdown, "Bot shut down", &notes.join("; "));
+        println!("👋 Stopped");
+        io::stdout().flush()?;
+        Ok(())
+    }
+
+    fn run(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        println!("🚀 ETH No Trend Bot Running...\n");
+        shutdown::install();
+        self.sync_server_clock();
+        self.recover_orders();
+        self.cancel_stale_orders();
+        self.recover_positions();
+        self.check_collateral();
+        self.ensure_approvals();
+        self.check_low_balance();
+        self.refresh_exchange_status();
+
+        loop {
+            if let Some(signal) = shutdown::requested() {
+                return self.shut_down(signal);
+            }
+            if self.clock.needs_resync(self.time.as_ref(), Duration::from_secs(self.config.timing.clock_resync_interval)) {
+                self.sync_server_clock();
+                // Same cadence is fine for re-ranking RPC endpoints
+                self.rpc.pool().health_check();
+            }
+
+            #[cfg(feature = "resolution")]
+            let now = self.now_secs();
+            #[cfg(feature = "resolution")]
+            if !self.resolution_watcher.is_empty() && now - self.last_resolution_poll >= self.config.timing.resolution_poll_interval {
+                self.last_resolution_poll = now;
+                self.poll_resolutions();
+            }
+            self.refresh_market_cache();
+
+            let current_time = self.time.now_secs();
+            self.close_out_markets(current_time);
+            let today = report::day_of(current_time);
+            if today != self.report_day {
+                let day = std::mem::replace(&mut self.report_day, today);
+                self.daily_report(&day);
+            }
+            if self.stop_at.is_some_and(|stop_at| current_time >= stop_at) {
+                println!("\n🧪 Simulation reached BOT_SIM_END; stopping.");
+                return Ok(());
+            }
+            let ts = self.market_schedule.cycle_start(current_time);
+            let slug = self.finder.describe(ts);
+            self.scanner.look_ahead(ts);
+
+            let time_until_next = self.market_schedule.cycle_end(ts) - current_time;
+
+            let open_time = self.display_tz.time(ts);
+            print!("\r⏰ Current Market: {} | Open Time: {} | Next in: {}s{} ",
+                slug, open_time, time_until_next, self.pnl_status());
+            io::stdout().flush()?;
+
+            if self.traded_markets.contains(&slug) {
+                shutdown::sleep(self.time.as_ref(), self.cadence.until_next_cycle(self.time.unix_secs_f64()));
+                continue;
+            }
+
+            if let Some(reason) = self.schedule.skip_reason(ts) {
+                if self.skipped_cycle != ts {
+                    self.skipped_cycle = ts;
+                    println!("\n📅 Skipping {} ({})", slug, reason);
+                    self.notify(Severity::Info, Event::MarketSkipped, &format!("Skipping {}", slug), &reason);
+                }
+                shutdown::sleep(self.time.as_ref(), self.cadence.until_next_cycle(self.time.unix_secs_f64()));
+                continue;
+            }
+
+            let market = match self.scanner.take(ts, Duration::from_secs(10)) {
+                Some(market) => {
+                    println!("\n   ⚡ Market pre-resolved: {}", market.title);
+                    Some(market)
+                }
+                None => {
+                    let unlisted = self.cadence.until_listed(self.time.unix_secs_f64(), ts);
+                    if !unlisted.is_zero() {
+                        shutdown::sleep(self.time.as_ref(), unlisted);
+                        continue;
+                    }
+                    self.get_market(ts)
+                }
+            };
+
+            if let Some(market) = market {
+                // A discovered market's slug is only known now
+                if self.traded_markets.contains(&market.slug) {
+                    shutdown::sleep(self.time.as_ref(), self.cadence.until_next_cycle(self.time.unix_secs_f64()));
+                    continue;
+                }
+                let traded = market.slug.clone();
+                self.closing_markets.push((traded.clone(), market.condition_id.clone(), self.closes_at(&market, ts)));
+                self.monitor_market(market, ts);
+                self.alerts.forget(&traded);
+            } else {
+                self.time.sleep(Duration::from_secs(2));
+            }
+
+            self.time.sleep(Duration::from_secs(1));
+        }
+    }
+}
+
+// ==========================================
+// 🧰 ONE-SHOT COMMANDS
+// ==========================================
+
+/// Manual fixes from the shell: each command performs one authenticated
+/// action and returns, without starting the strategy loop.
+impl EthNoTrendBot {
+    /// `buy|sell <token_id> <price> <size> [FOK|FAK|GTC|GTD]`; a GTD is
+    /// good for an hour.
+    fn cli_order(&self, side: OrderSide, args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+        let usage = || format!("usage: {} <token_id> <price> <size> [FOK|FAK|GTC|GTD]", side.as_str().to_lowercase());
+        let [token_id, price, size, rest @ ..] = args else { return Err(usage().into()) };
+
+        let price: f64 = price.parse().map_err(|_| format!("Invalid price '{}'", price))?;
+        if !(price > 0.0 && price < 1.0) {
+            return Err(format!("Price {} outside (0, 1)", price).into());
+        }
+        let size: u32 = size.parse().ok().filter(|s| *s > 0).ok_or_else(|| format!("Invalid size '{}'", size))?;
+        let order_type = rest.first().map(|t| t.to_uppercase()).unwrap_or_else(|| "FOK".to_string());
+        if !matches!(order_type.as_str(), "FOK" | "FAK" | "GTC" | "GTD") || rest.len() > 1 {
+            return Err(usage().into());
+        }
+
+        if self.refresh_exchange_status() {
+            return Err("Exchange halted; not submitting".into());
+        }
+
+        match self.place_order(token_id, price, size, side, &order_type)? {
+            Some(fill) => {
+                println!("✅ {} {:.2}/{} shares @ ${:.3} (order {})", side, fill.filled_size, size, fill.avg_price, fill.order_id);
+                Ok(())
+            }
+            None => Err("Order was not filled; check `orders` for anything left resting".into()),
+        }
+    }
+
+    /// `cancel <order_id>`
+    fn cli_cancel(&self, args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+        let [order_id] = args else { return Err("usage: cancel <order_id>".into()) };
+        if self.config.dry_run {
+            println!("🧪 Dry run: would cancel {}", order_id);
+            return Ok(());
+        }
+        self.cancel_order(order_id)?;
+        println!("✅ Canceled {}", order_id);
+        Ok(())
+    }
+
+    /// `cancel-all`: every open order, one at a time. Fails if any is left.
+    fn cli_cancel_all(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let orders = self.get_open_orders()?;
+        if orders.is_empty() {
+            println!("📭 No open orders");
+            return Ok(());
+        }
+
+        let mut failed = 0;
+        for o in &orders {
+            if self.config.dry_run || self.config.paper {
+                println!("   🧪 {}: would cancel {} ({} {:.2} @ ${:.3})", if self.config.paper { "Paper" } else { "Dry run" }, o.id, o.side, o.remaining(), o.price());
+                continue;
+            }
+            match self.cancel_order(&o.id) {
+                Ok(()) => println!("   ✅ Canceled {} ({} {:.2} @ ${:.3})", o.id, o.side, o.remaining(), o.price()),
+                Err(e) => {
+                    println!("   ❌ {}: {}", o.id, e);
+                    failed += 1;
+                }
+            }
+        }
+        if failed > 0 {
+            return Err(format!("{} of {} order(s) not canceled", failed, orders.len()).into());
+        }
+        Ok(())
+    }
+
+    /// `liquidate [token_id]`: sell every held position, or just one, into
+    /// the bid. Resolved markets are left for `claim`.
+    fn cli_liquidate(&self, only: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+        let positions = positions::fetch_positions(&self.client, &self.network.data_url, self.trading_address, false)?;
+        let held: Vec<_> = positions.iter()
+            .filter(|p| !p.redeemable && p.size >= 1.0)
+            .filter(|p| only.is_none_or(|token| p.asset == token))
+            .collect();
+        if held.is_empty() {
+            println!("📭 Nothing to liquidate");
+            return Ok(());
+        }
+        if self.refresh_exchange_status() {
+            return Err("Exchange halted; not submitting".into());
+        }
+
+        let mut unsold = Vec::new();
+        for p in &held {
+            let Some(bid) = self.get_order_book_depth(&p.asset).and_then(|b| b.best_bid) else {
+                println!("   ⚠️ {} [{}]: no bid", p.title, p.outcome);
+                unsold.push(p.asset.clone());
+                continue;
+            };
+            if let Some(working) = self.working_order(&p.asset, OrderSide::Sell) {
+                println!("   ⏸️ {} [{}]: sell {} already working ({:.2} @ ${:.3}); cancel it first",
+                    p.title, p.outcome, working.id, working.remaining(), working.price());
+                unsold.push(p.asset.clone());
+                continue;
+            }
+            let ladder = self.sell_ladder(bid);
+            println!("🔻 {} [{}]: selling {:.0} shares @ ${:.3}{}", p.title, p.outcome, p.size.floor(), bid, ladder_floor(&ladder, bid));
+            self.pnl.borrow_mut().open(&p.asset, p.size, p.size * p.avg_price);
+            match self.sell_down_ladder(&p.asset, &ladder, p.size.floor() as u32)? {
+                Some(fill) if fill.remaining < 1.0 => println!("   ✅ Sold {:.2} @ ${:.3}", fill.filled_size, fill.avg_price),
+                Some(fill) => {
+                    println!("   ⚠️ Sold only {:.2} @ ${:.3}; {:.2} left", fill.filled_size, fill.avg_price, fill.remaining);
+                    unsold.push(p.asset.clone());
+                }
+                None if self.config.dry_run => {}
+                None => unsold.push(p.asset.clone()),
+            }
+        }
+        self.notify_liquidation(held.len(), &unsold);
+        if !unsold.is_empty() {
+            return Err(format!("{} position(s) not sold", unsold.len()).into());
+        }
+        Ok(())
+    }
+
+    /// `status`: where the bot stands right now, without trading.
+    fn cli_status(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let now = self.now_secs();
+        let cycle_start = self.market_schedule.cycle_start(now);
+        println!("📟 Status for {:?}{}", self.trading_address, if self.config.dry_run { " (dry run)" } else { "" });
+        println!("   market             {} ({}s left)", self.finder.describe(cycle_start), self.market_schedule.cycle_end(cycle_start) - now);
+        println!("   exchange           {}", if self.refresh_exchange_status() { "halted" } else { "open" });
+        match self.get_balance_allowance(AssetType::Collateral, "") {
+            Ok(ba) => println!("   collateral         ${:.2}", ba.balance),
+            Err(e) => println!("   collateral         unavailable ({})", e),
+        }
+        let orders = self.get_open_orders()?;
+        println!("   open orders        {}", orders.len());
+        let positions = positions::fetch_positions(&self.client, &self.network.data_url, self.trading_address, false)?;
+        let value: f64 = positions.iter().map(|p| p.current_value).sum();
+        println!("   positions          {} (${:.2})", positions.len(), value);
+        Ok(())
+    }
+
+    fn cli_positions(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let positions = positions::fetch_positions(&self.client, &self.network.data_url, self.trading_address, false)?;
+        if positions.is_empty() {
+            println!("📭 No open positions for {:?}", self.trading_address);
+            return Ok(());
+        }
+
+        println!("📦 {} position(s) for {:?}", positions.len(), self.trading_address);
+        for p in &positions {
+            println!("   {} [{}] {:.2} shares @ ${:.3} = ${:.2}{}",
+                p.title, p.outcome, p.size, p.cur_price, p.current_value,
+                if p.redeemable { " (redeemable)" } else { "" });
+            println!("      token {}", p.asset);
+        }
+
+        // Outcome index 0 is the market's first (YES/Up) token
+        let legs: Vec<Leg> = positions.iter()
+            .map(|p| Leg {
+                market: p.condition_id.clone(),
+                outcome: if p.outcome_index == 0 { Outcome::Yes } else { Outcome::No },
+                shares: p.size,
+                price: p.cur_price,
+            })
+            .collect();
+        let exposure = exposure::net(&legs);
+        println!("\n📐 Net exposure");
+        for m in &exposure {
+            let title = positions.iter().find(|p| p.condition_id == m.market).map(|p| p.title.as_str()).unwrap_or(&m.market);
+            let net = match m.directional() {
+                Some((outcome, shares)) => {
+                    let label = positions.iter()
+                        .find(|p| p.condition_id == m.market && (p.outcome_index == 0) == (outcome == Outcome::Yes))
+                        .map(|p| p.outcome.as_str())
+                        .unwrap_or(outcome.as_str());
+                    format!("net {:.2} {} (${:.2} at risk)", shares, label, m.at_risk())
+                }
+                None => "flat".to_string(),
+            };
+            println!("   {}: {:.2} sets (${:.2} riskless), {}, value ${:.2}", title, m.complete_sets(), m.complete_sets(), net, m.value());
+        }
+        for (market, sets) in exposure::merge_suggestions(&exposure, 1.0) {
+            println!("   💡 Merge {:.0} complete set(s) of {} to free ${:.2}", sets, market, sets);
+        }
+        Ok(())
+    }
+
+    /// `orders`: what's resting on the exchange. `orders history [ID]`: the
+    /// local lifecycle journal, every order or one in full.
+    fn cli_orders(&self, args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+        match args {
+            [] => {}
+            [sub] if sub == "history" => {
+                let journal = self.orders.borrow();
+                if journal.orders().is_empty() {
+                    println!("📭 No orders in {}", self.profile.path(ORDERS_FILE));
+                }
+                for o in journal.orders() {
+                    println!("   {} {} {} {:.2} @ ${:.3} {:<16} {:.2} filled  {}",
+                        timestamps::rfc3339(o.created_at()), o.spec.side, o.spec.order_type, o.spec.size, o.spec.price,
+                        o.state.as_str(), o.filled, o.exchange_id());
+                }
+                return Ok(());
+            }
+            [sub, id] if sub == "history" => {
+                let journal = self.orders.borrow();
+                let o = journal.get(id).ok_or_else(|| format!("no order {} in {}", id, self.profile.path(ORDERS_FILE)))?;
+                println!("📋 {} {} {} {:.2} @ ${:.3} token {}", o.exchange_id(), o.spec.side, o.spec.order_type, o.spec.size, o.spec.price, o.spec.token_id);
+                for step in &o.history {
+                    let note = if step.note.is_empty() { String::new() } else { format!("  ({})", step.note) };
+                    println!("   {} {}{}", timestamps::rfc3339(step.ts), step.state.as_str(), note);
+                }
+                println!("   {:.2} filled @ ${:.3}, fees ${:.4}", o.filled, o.avg_price, o.fee);
+                return Ok(());
+            }
+            _ => return Err("usage: orders | orders history [ORDER_ID]".into()),
+        }
+
+        let orders = self.get_open_orders()?;
+        if orders.is_empty() {
+            println!("📭 No open orders");
+            return Ok(());
+        }
+
+        // Rewards scoring is informational; the listing doesn't depend on it
+        let ids: Vec<String> = orders.iter().map(|o| o.id.clone()).collect();
+        let scoring = self.are_orders_scoring(&ids).unwrap_or_else(|e| {
+            self.warn(format!("⚠️ Could not check rewards scoring: {}", e));
+            HashMap::new()
+        });
+        println!("📋 {} open order(s)", orders.len());
+        for o in &orders {
+            let scoring = if scoring.get(&o.id).copied().unwrap_or(false) { " scoring" } else { "" };
+            println!("   {} {} {:.2} @ ${:.3} {} [{}] {}{}",
+                o.id, o.side, o.remaining(), o.price(), o.order_type, o.outcome, o.status, scoring);
+            println!("      token {}", o.asset_id);
+        }
+        Ok(())
+    }
+
+    /// `export positions [FILE]`: every data-API position with its on-chain
+    /// balance and current mark, as JSON when FILE ends in .json, else CSV.
+    /// `export lots [FILE]`: FIFO tax lots from the trade history.
+    fn cli_export(&self, args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+        let path = match args {
+            [what] if what == "positions" => "positions_export.csv",
+            [what, path] if what == "positions" => path.as_str(),
+            [what] if what == "lots" => return self.export_lots("tax_lots.csv"),
+            [what, path] if what == "lots" => return self.export_lots(path),
+            _ => return Err("usage: export positions [FILE.csv|FILE.json] | export lots [FILE.csv]".into()),
+        };
+
+        let positions = positions::fetch_positions(&self.client, &self.network.data_url, self.trading_address, false)?;
+        let token_ids: Vec<&str> = positions.iter().map(|p| p.asset.as_str()).collect();
+        // Resolved markets have no book and so no midpoint; they keep the data API's price
+        let midpoints = if token_ids.is_empty() {
+            HashMap::new()
+        } else {
+            self.get_midpoints(&token_ids).unwrap_or_else(|e| {
+                println!("⚠️ Midpoints unavailable ({}); marking at data API prices", e);
+                HashMap::new()
+            })
+        };
+
+        let rows: Vec<positions::PositionExport> = positions.iter().map(|p| {
+            let onchain = match self.onchain_token_balance(&p.asset) {
+                Ok(shares) => Some(shares),
+                Err(e) => {
+                    println!("⚠️ {}: on-chain balance unavailable ({})", p.asset, e);
+                    None
+                }
+            };
+            positions::PositionExport::new(p, onchain, midpoints.get(&p.asset).copied())
+        }).collect();
+
+        for row in rows.iter().filter(|r| r.is_mismatched()) {
+            println!("🚨 {} [{}]: data API shows {:.2} shares, chain shows {:.2}",
+                row.title, row.outcome, row.size, row.onchain_size.unwrap_or_default());
+        }
+
+        let file = File::create(path)?;
+        if path.ends_with(".json") {
+            serde_json::to_writer_pretty(file, &rows)?;
+        } else {
+            positions::write_csv(&rows, file)?;
+        }
+        let total: f64 = rows.iter().map(|r| r.value).sum();
+        println!("✅ Exported {} position(s), ${:.2} marked value, to {}", rows.len(), total, path);
+        Ok(())
+    }
+
+    /// `report [YYYY-MM-DD] [FILE.csv]`: the trade journal summed up per UTC
+    /// day, or for one day, on the console and as CSV.
+    fn cli_report(&self, args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+        let is_day = |s: &str| chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok();
+        let (day, path) = match args {
+            [] => (None, DAILY_REPORT_FILE),
+            [day] if is_day(day) => (Some(day.as_str()), DAILY_REPORT_FILE),
+            [path] => (None, path.as_str()),
+            [day, path] if is_day(day) => (Some(day.as_str()), path.as_str()),
+            _ => return Err("usage: report [YYYY-MM-DD] [FILE.csv]".into()),
+        };
+
+        let mut summaries = report::daily(&self.trade_log.read()?);
+        if let Some(day) = day {
+            summaries.retain(|s| s.day == day);
+        }
+        if summaries.is_empty() {
+            println!("📭 Nothing in {}{}", self.trade_log.path(), day.map(|d| format!(" for {}", d)).unwrap_or_default());
+            return Ok(());
+        }
+        println!("📅 Daily summary of {}", self.trade_log.path());
+        for summary in &summaries {
+            println!("   {}", summary.text());
+        }
+        report::write_csv(&summaries, File::create(path)?, true)?;
+        println!("✅ Wrote {} day(s) to {}", summaries.len(), path);
+        Ok(())
+    }
+
+    /// Closed FIFO lots from every fill and redemption on the account, as
+    /// CSV, with realized gains per market on the console.
+    fn export_lots(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let activity = positions::fetch_activity(&self.client, &self.network.data_url, self.trading_address)?;
+        let book = FifoBook::from_activity(&activity);
+        for (token_id, shares) in book.unmatched() {
+            println!("⚠️ {}: {:.2} share(s) disposed with no buy in the history; left out", token_id, shares);
+        }
+
+        tax_lots::write_csv(book.closed(), File::create(path)?)?;
+        let gains = book.gains_by_market();
+        for (condition_id, gain) in &gains {
+            let title = book.closed().iter().find(|l| l.condition_id == *condition_id).map(|l| l.title.as_str()).unwrap_or(condition_id);
+            println!("   {:<50} {:>+9.2}", title, gain);
+        }
+        let total: f64 = gains.iter().map(|(_, g)| g).sum();
+        println!("✅ Exported {} lot(s) across {} market(s), realized ${:+.2}, to {}", book.closed().len(), gains.len(), total, path);
+        if !book.open().is_empty() {
+            println!("   {} position(s) still open and not in the export", book.open().len());
+        }
+        Ok(())
+    }
+
+    /// `ledger` prints account balances; `ledger reconcile [--apply]`
+    /// compares them with the wallet on-chain and, with --apply, books an
+    /// unexplained USDC difference as a deposit or withdrawal.
+    fn cli_ledger(&self, args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+        let apply = match args {
+            [] => {
+                let ledger = self.ledger.borrow();
+                println!("📒 Ledger: {} entr{}", ledger.entries().len(), if ledger.entries().len() == 1 { "y" } else { "ies" });
+                for (account, amount, shares) in ledger.accounts() {
+                    let shares = if shares != 0.0 { format!(" ({:.2} shares)", shares) } else { String::new() };
+                    println!("   {:<40} {:>12.4}{}", account.to_string(), amount, shares);
+                }
+                println!("   Realized PnL ${:+.2}, fees ${:.2}", ledger.realized_pnl(), ledger.fees());
+                return Ok(());
+            }
+            [sub] if sub == "reconcile" => false,
+            [sub, flag] if sub == "reconcile" && flag == "--apply" => true,
+            _ => return Err("usage: ledger | ledger reconcile [--apply]".into()),
+        };
+
+        let collateral = collateral::exchange_collateral(&self.rpc, &self.network);
+        let wallet_usdc = chain::erc20_balance_of(&self.rpc, collateral, self.trading_address)?.as_u128() as f64 / 1_000_000.0;
+        let mut wallet_shares = HashMap::new();
+        for token_id in self.ledger.borrow().tokens() {
+            wallet_shares.insert(token_id.clone(), self.onchain_token_balance(&token_id)?);
+        }
+        let rec = self.ledger.borrow().reconcile(wallet_usdc, &wallet_shares, 0.01);
+
+        println!("📒 USDC: ledger ${:.2}, wallet ${:.2} ({:+.2})", rec.ledger_usdc, rec.wallet_usdc, rec.usdc_diff());
+        for diff in &rec.token_diffs {
+            println!("   🚨 {}: ledger {:.2} shares, chain {:.2} — missed fill?", diff.token_id, diff.ledger, diff.wallet);
+        }
+        if rec.is_clean() {
+            println!("✅ Ledger matches the wallet.");
+            return Ok(());
+        }
+        match rec.suggested_transfer() {
+            Some(amount) if apply => {
+                let entry = self.ledger.borrow().transfer_entry(self.time.now_secs(), "reconcile", amount);
+                self.book(vec![entry]);
+                println!("✅ Booked {} of ${:.2}", if amount >= 0.0 { "deposit" } else { "withdrawal" }, amount.abs());
+                Ok(())
+            }
+            Some(amount) => {
+                println!("   Shares agree, so ${:+.2} looks like a deposit/withdrawal; rerun with --apply to book it.", amount);
+                Err("ledger out of balance with the wallet".into())
+            }
+            None => Err("ledger out of balance with the wallet".into()),
+        }
+    }
+
+    /// `book <token_id>`
+    /// One token: its book's touch plus the CLOB's midpoint, spread and
+    /// quoted prices. Several: one line each from the batch endpoints.
+    fn cli_book(&self, args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+        match args {
+            [] => Err("usage: book <token_id>...".into()),
+            [token_id] => {
+                let book = self.fetch_order_book(token_id)?;
+                let level = |price: Option<f64>, size: f64| match price {
+                    Some(price) => format!("${:.3} x {:.2}", price, size),
+                    None => "empty".to_string(),
+                };
+                println!("📖 {}", token_id);
+                println!("   Best bid: {}", level(book.best_bid, book.bid_size));
+                println!("   Best ask: {}", level(book.best_ask, book.ask_size));
+                println!("   Midpoint: ${:.4}", self.get_midpoint(token_id)?);
+                println!("   Spread:   ${:.4}", self.get_spread(token_id)?);
+                println!("   Quoted:   buy ${:.3}, sell ${:.3}",
+                    self.get_price(token_id, OrderSide::Buy)?, self.get_price(token_id, OrderSide::Sell)?);
+                Ok(())
+            }
+            tokens => {
+                let tokens: Vec<&str> = tokens.iter().map(String::as_str).collect();
+                let sides: Vec<(&str, OrderSide)> = tokens.iter()
+                    .flat_map(|t| [(*t, OrderSide::Buy), (*t, OrderSide::Sell)])
+                    .collect();
+                let mids = self.get_midpoints(&tokens)?;
+                let spreads = self.get_spreads(&tokens)?;
+                let prices = self.get_prices(&sides)?;
+                let cell = |v: Option<f64>| v.map(|v| format!("${:.3}", v)).unwrap_or_else(|| "-".to_string());
+                println!("📖 {} book(s)", tokens.len());
+                println!("   {:<20} {:>8} {:>8} {:>8} {:>8}", "token", "buy", "sell", "mid", "spread");
+                for token in tokens {
+                    let quoted = |side: OrderSide| prices.get(token).and_then(|p| p.get(side.as_str())).copied();
+                    println!("   {:<20} {:>8} {:>8} {:>8} {:>8}", token,
+                        cell(quoted(OrderSide::Buy)), cell(quoted(OrderSide::Sell)),
+                        cell(mids.get(token).copied()), cell(spreads.get(token).copied()));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Pass/fail report on everything trading depends on, so setup problems
+    /// show up here instead of as rejections mid-market.
+    fn doctor(&self) -> Result<(), Box<dyn std::error::Error>> {
+        println!("🩺 Checking trading setup...\n");
+        let err = |e: Box<dyn std::error::Error>| e.to_string();
+        let mut checks: Vec<(&str, Result<String, String>)> = Vec::new();
+
+        checks.push(("Signing key", Ok(format!("loaded, signer {:?}", self.wallet.address()))));
+        checks.push(("Trading address", self.doctor_trading_address()));
+        checks.push(("Polygon RPC", self.rpc.block_number()
+            .map(|head| format!("{} at block {}", self.rpc.url(), head))
+            .map_err(err)));
+
+        let apis = [
+            ("CLOB API", format!("{}/", self.network.clob_url)),
+            ("Gamma API", format!("{}/events?limit=1", self.network.gamma_url)),
+            ("Data API", format!("{}/positions?user={:?}&limit=1", self.network.data_url, self.trading_address)),
+        ];
+        for (name, url) in apis {
+            checks.push((name, self.doctor_reachable(&url)));
+        }
+
+        checks.push(("Clock skew", self.doctor_clock_skew()));
+        checks.push(("API credentials", self.doctor_api_credentials()));
+        checks.push(("USDC balance", self.get_balance_allowance(AssetType::Collateral, "").map_err(err).and_then(|ba| {
+            let detail = format!("${:.2}, exchange allowance ${:.2}", ba.balance, ba.allowance);
+            if ba.balance > 0.0 && ba.allowance > 0.0 { Ok(detail) } else { Err(detail) }
+        })));
+        checks.push(("Exchange approvals", self.approval_statuses().map_err(err).and_then(|statuses| {
+            let missing: Vec<String> = statuses.iter().filter(|s| !s.approved).map(|s| s.describe()).collect();
+            if missing.is_empty() {
+                Ok(format!("{} in place", statuses.len()))
+            } else {
+                Err(format!("missing {}", missing.join(", ")))
+            }
+        })));
+
+        for (name, result) in &checks {
+            match result {
+                Ok(detail) => println!("   ✅ {}: {}", name, detail),
+                Err(detail) => println!("   ❌ {}: {}", name, detail),
+            }
+        }
+
+        let failed = checks.iter().filter(|(_, result)| result.is_err()).count();
+        if failed > 0 {
+            return Err(format!("{} of {} checks failed", failed, checks.len()).into());
+        }
+        println!("\n✅ All {} checks passed. Ready to trade.", checks.len());
+        Ok(())
+    }
+
+    /// A proxy trading address must be a deployed contract; an EOA trades as itself.
+    fn doctor_trading_address(&self) -> Result<String, String> {
+        if !self.use_proxy {
+            return Ok(format!("{:?} (EOA, signature type 0)", self.trading_address));
+        }
+        let code: String = self.rpc.call("eth_getCode", json!([self.trading_address, "latest"])).map_err(|e| e.to_string())?;
+        if code.trim_start_matches("0x").is_empty() {
+            return Err(format!("{:?} has no contract code; POLYMARKET_ADDRESS is not a proxy wallet", self.trading_address));
+        }
+        Ok(format!("{:?} (proxy wallet, signature type {})", self.trading_address, self.signature_type))
+    }
+
+    fn doctor_reachable(&self, url: &str) -> Result<String, String> {
+        let started = Instant::now();
+        let resp = self.send(self.client.get(url)).map_err(|e| e.to_string())?;
+        let detail = format!("HTTP {} in {}ms", resp.status().as_u16(), started.elapsed().as_millis());
+        if resp.status().is_success() { Ok(detail) } else { Err(detail) }
+    }
+
+    fn doctor_clock_skew(&self) -> Result<String, String> {
+        if self.time.is_simulated() {
+            return Ok("simulated clock, not checked".to_string());
+        }
+        let url = format!("{}/time", self.network.clob_url);
+        let text = self.send(self.client.get(&url))
+            .and_then(|r| Ok(r.error_for_status()?.text()?))
+            .map_err(|e| e.to_string())?;
+        let server_time: f64 = text.trim().trim_matches('"').parse().map_err(|_| format!("unparseable /time response {:?}", text))?;
+
+        let skew = self.time.unix_secs_f64() - server_time;
+        let detail = format!("{:+.1}s vs server time", skew);
+        if skew.abs() > self.config.timing.max_clock_skew_secs { Err(detail) } else { Ok(detail) }
+    }
+
+    /// The L1-derived key for this signer must be the one in POLY_API_KEY;
+    /// keys made for another wallet authenticate but can't place its orders.
+    fn doctor_api_credentials(&self) -> Result<String, String> {
+        let headers = self.create_l1_headers(0).map_err(|e| e.to_string())?;
+        let url = format!("{}/auth/derive-api-key", self.network.clob_url);
+        let resp = self.send(self.client.get(&url).headers(headers)).map_err(|e| e.to_string())?;
+        if !resp.status().is_success() {
+            return Err(format!("derive-api-key HTTP {}", resp.status()));
+        }
+        let derived: Value = resp.json().map_err(|e| e.to_string())?;
+        match derived["apiKey"].as_str() {
+            Some(key) if key == self.api_creds.api_key => Ok(format!("POLY_API_KEY matches the key derived for {:?}", self.wallet.address())),
+            Some(key) => Err(format!("POLY_API_KEY differs from the key derived for this signer ({})", key)),
+            None => Err("derive-api-key returned no apiKey".to_string()),
+        }
+    }
+}
+
+// ==========================================
+// 🛰️ gRPC EXECUTION BACKEND
+// ==========================================
+
+/// RPC calls go through the same checks and submission tracking as the
+/// strategy's own orders.
+#[cfg(feature = "grpc")]
+impl grpc::ExecutionBackend for EthNoTrendBot {
+    fn place_order(&self, request: grpc::PlaceOrderRequest) -> Result<grpc::PlaceOrderReply, tonic::Status> {
+        let side = match grpc::Side::try_from(request.side) {
+            Ok(grpc::Side::Buy) => OrderSide::Buy,
+            Ok(grpc::Side::Sell) => OrderSide::Sell,
+            Err(_) => return Err(tonic::Status::invalid_argument(format!("unknown side {}", request.side))),
+        };
+        if !matches!(request.order_type.as_str(), "FOK" | "FAK" | "GTC" | "GTD") {
+            return Err(tonic::Status::invalid_argument(format!("unsupported order type {:?}", request.order_type)));
+        }
+        if !(request.price > 0.0 && request.price < 1.0) {
+            return Err(tonic::Status::invalid_argument(format!("price {} outside (0, 1)", request.price)));
+        }
+
+        if self.exchange_halted.get() {
+            return Err(tonic::Status::unavailable("exchange halted; not submitting"));
+        }
+        if request.size == 0 || request.size > self.max_position_size.get() {
+            return Err(tonic::Status::failed_precondition(format!(
+                "size {} outside 1..={} shares", request.size, self.max_position_size.get())));
+        }
+        if side == OrderSide::Buy && request.price > self.strategy.abort_ask_price {
+            return Err(tonic::Status::failed_precondition(format!("buy above abort price ${}", self.strategy.abort_ask_price)));
+        }
+
+        match EthNoTrendBot::place_order(self, &request.token_id, request.price, request.size, side, &request.order_type) {
+            Ok(Some(fill)) => Ok(grpc::PlaceOrderReply {
+                filled_size: fill.filled_size,
+                avg_price: fill.avg_price,
+                order_id: fill.order_id,
+            }),
+            Ok(None) => Err(tonic::Status::aborted("order rejected or not filled")),
+            Err(e) => Err(tonic::Status::internal(e.to_string())),
+        }
+    }
+
+    fn cancel_order(&self, order_id: &str) -> Result<(), tonic::Status> {
+        EthNoTrendBot::cancel_order(self, order_id).map_err(|e| tonic::Status::internal(e.to_string()))
+    }
+
+    fn order_status(&self, order_id: &str) -> Result<grpc::OrderStatusReply, tonic::Status> {
+        let progress = self.check_order_status(order_id).map_err(|e| tonic::Status::internal(e.to_string()))?;
+        Ok(grpc::OrderStatusReply {
+            status: progress.status,
+            original_size: progress.original_size,
+            filled_size: progress.filled_size,
+            avg_price: progress.avg_price,
+        })
+    }
+
+    fn positions(&self) -> Result<Vec<grpc::Position>, tonic::Status> {
+        Ok(self.positions.borrow().iter()
+            .filter(|(_, size)| size.abs() > 1e-9)
+            .map(|(token_id, size)| grpc::Position { token_id: token_id.clone(), size: *size })
+            .collect())
+    }
+
+    fn book(&self, token_id: &str) -> Result<grpc::BookReply, tonic::Status> {
+        let book = self.fetch_order_book(token_id).map_err(|e| tonic::Status::unavailable(e.to_string()))?;
+        Ok(grpc::BookReply { best_bid: book.best_bid, bid_size: book.bid_size, best_ask: book.best_ask, ask_size: book.ask_size })
+    }
+}
+
+/// Run the execution gRPC server on GRPC_ADDR (default 127.0.0.1:50051)
+/// instead of the strategy loop.
+#[cfg(feature = "grpc")]
+fn serve_grpc(bot: &EthNoTrendBot) -> Result<(), Box<dyn std::error::Error>> {
+    let addr = std::env::var("GRPC_ADDR").unwrap_or_else(|_| "127.0.0.1:50051".to_string());
+    let addr = addr.parse().map_err(|_| format!("Invalid GRPC_ADDR '{}'", addr))?;
+    println!("🛰️ Execution gRPC service listening on {}", addr);
+    grpc::serve(addr, bot)
+}
+
+// ==========================================
+// 🧾 EFFECTIVE CONFIG
+// ==========================================
+
+/// The config file's strategy with BOT_TIE_BREAK and BOT_ENTRY_EXEC applied.
+fn strategy_params(config: &Config) -> Result<StrategyParams, Box<dyn std::error::Error>> {
+    let mut params = config.strategy.params();
+    if let Ok(v) = std::env::var("BOT_TIE_BREAK") {
+        params.tie_break = TieBreak::parse(&v).map_err(|e| format!("Invalid BOT_TIE_BREAK: {}", e))?;
+    }
+    if let Ok(v) = std::env::var("BOT_ENTRY_EXEC") {
+        params.entry_exec = EntryExecution::parse(&v).map_err(|e| format!("Invalid BOT_ENTRY_EXEC: {}", e))?;
+    }
+    Ok(params)
+}
+
+/// First few characters and the length; enough to tell two secrets apart.
+/// Short values show nothing, since a prefix would give most of them away.
+fn mask_secret(secret: &str) -> String {
+    let len = secret.chars().count();
+    let prefix: String = if len >= 16 { secret.chars().take(4).collect() } else { String::new() };
+    format!("{}… ({} chars)", prefix, len)
+}
+
+/// One profile's wallet and credentials, printed masked; returns problems
+/// prefixed with the profile name when there are several.
+fn check_account(profile: &Profile) -> Vec<String> {
+    let mut errors = Vec::new();
+    if profile.is_default() {
+        println!("\nAccount:");
+    } else {
+        println!("\nAccount {}:", profile.name);
+        println!("   data_dir           {}", profile.data_dir.display());
+    }
+    let polymarket_address = trading_address_from_env(profile).unwrap_or_else(|e| {
+        errors.push(e);
+        String::new()
+    });
+    match (profile.var("BOT_KEYSTORE"), profile.var("PRIVATE_KEY")) {
+        (Some(path), _) => println!("   keystore           {}", path),
+        (None, Some(key)) => println!("   private_key        {}", mask_secret(&key)),
+        (None, None) => println!("   private_key        (not set)"),
+    }
+    match (wallet_from_env(profile), Address::from_str(&polymarket_address)) {
+        (Ok(wallet), Ok(trading_address)) => {
+            println!("   signer             {:?}", wallet.address());
+            let mode = if wallet.address() == trading_address { "EOA, signature type 0" } else { "proxy wallet, signature type 1" };
+            println!("   trading_address    {:?} ({})", trading_address, mode);
+        }
+        (wallet, trading_address) => {
+            errors.extend(wallet.map(|_| ()).map_err(|e| e.to_string()).err());
+            if !polymarket_address.is_empty() {
+                errors.extend(trading_address.map(|_| ()).map_err(|_| format!("POLYMARKET_ADDRESS '{}' is not an address", polymarket_address)).err());
+            }
+        }
+    }
+    match api_creds_from_env(profile) {
+        Ok(Some(_)) => {
+            for var in ["POLY_API_KEY", "POLY_API_SECRET", "POLY_API_PASSPHRASE"] {
+                println!("   {:<18} {}", var.trim_start_matches("POLY_").to_lowercase(), mask_secret(&profile.var(var).unwrap_or_default()));
+            }
+        }
+        Ok(None) => println!("   {:<18} (derived from the signer at startup)", "api_key"),
+        Err(e) => errors.push(e),
+    }
+    if profile.is_default() {
+        errors
+    } else {
+        errors.into_iter().map(|e| format!("profile {}: {}", profile.name, e)).collect()
+    }
+}
+
+/// `config check`: resolve the configuration the way startup does, env
+/// overrides included, without touching the network. Prints the result with
+/// secrets masked and fails on anything startup would reject or trade badly on.
+fn config_check(overrides: &Overrides) -> Result<(), Box<dyn std::error::Error>> {
+    let mut errors: Vec<String> = Vec::new();
+
+    println!("🧾 Effective configuration\n");
+
+    let config = overrides.load().unwrap_or_else(|e| {
+        errors.push(e);
+        Config::default()
+    });
+    println!("Config file:");
+    println!("   path               {}", config.source.as_deref().unwrap_or("(none, built-in defaults)"));
+    println!("   dry_run            {}", config.dry_run);
+    println!("   paper              {}", config.paper);
+    println!("   log_format         {}", config.log_format.as_str());
+    if config.market.assets.len() > 1 && overrides.asset.is_none() {
+        println!("   assets             {} (shown: {})", config.market.assets.join(", "), config.market.asset);
+    }
+    println!("   asset              {}{}", config.market.asset,
+        if config.assets.contains_key(&config.market.asset) { " ([assets] overrides applied)" } else { "" });
+    println!("   market             {} every {}", config.market.slug, config.market.cadence);
+    if config.market.discover {
+        println!("   discover           tag '{}', series '{}'", config.market.tag, config.market.series_id);
+    }
+    errors.extend(config.validate());
+
+    println!("\nStrategy:");
+    match strategy_params(&config) {
+        Ok(params) => {
+            println!("   trade_side         {:?}", params.trade_side);
+            println!("   entry_price        ${}", params.entry_price);
+            println!("   stop_loss_price    ${}", config.strategy.stop_loss_price);
+            if let Some(t) = config.strategy.trailing_stop {
+                println!("   trailing_stop      {} under the high bid, from {} above entry", t.trail, t.activation);
+            }
+            if config.strategy.exit_before_close > 0 {
+                println!("   exit_before_close  {}s", config.strategy.exit_before_close);
+            }
+            println!("   partial_fill       {}", config.strategy.partial_fill.as_str());
+            for t in &config.strategy.tranches {
+                println!("   tranche            {} of the size @ ${}", t.fraction, t.price);
+            }
+            println!("   abort_ask_price    ${}", params.abort_ask_price);
+            println!("   position_size      {} shares", params.position_size);
+            if let Some(s) = config.strategy.sizing {
+                println!("   sizing             {}% of USDC balance, {}-{} shares (replaces position_size)", s.balance_pct, s.min_shares, s.max_shares);
+            }
+            println!("   market_window      {}s", params.market_window);
+            println!("   entry_timeout      {}s", params.entry_timeout);
+            println!("   tie_break          {}", params.tie_break);
+            println!("   entry_exec         {}", params.entry_exec);
+        }
+        Err(e) => errors.push(e.to_string()),
+    }
+
+    let t = &config.timing;
+    println!("\nTiming:");
+    println!("   notification_poll  {}s", t.notification_poll_interval);
+    println!("   exchange_status    {}s", t.exchange_status_interval);
+    println!("   resolution_poll    {}s", t.resolution_poll_interval);
+    println!("   market_cache       {}", if t.market_cache_interval == 0 { "off".to_string() } else { format!("{}s", t.market_cache_interval) });
+    println!("   clock_resync       {}s (warn over {}s skew)", t.clock_resync_interval, t.max_clock_skew_secs);
+    println!("   balance_cache_ttl  {}s", t.balance_cache_ttl);
+    println!("   http_timeout       {}s", t.http_timeout);
+    println!("   low_balance        ${}", config.risk.low_balance_threshold);
+    println!("   on_shutdown        {}", if config.risk.liquidate_on_shutdown { "cancel orders, liquidate" } else { "cancel orders" });
+    println!("   liquidation        down to ${} under the bid in {} rung(s)", config.risk.liquidation_slippage, config.risk.liquidation_rungs);
+    match config.risk.max_open_positions {
+        0 => println!("   max_open_positions unlimited"),
+        max => println!("   max_open_positions {} across all markets", max),
+    }
+
+    match profiles::from_env() {
+        Ok(profiles) => {
+            for profile in &profiles {
+                errors.extend(check_account(profile));
+            }
+        }
+        Err(e) => errors.push(e),
+    }
+
+    println!("\nNetwork:");
+    match NetworkProfile::from_env() {
+        Ok(network) => {
+            println!("   name               {} (chain {})", network.name, network.chain_id);
+            println!("   clob_url           {}", network.clob_url);
+            println!("   gamma_url          {}", network.gamma_url);
+            println!("   data_url           {}", network.data_url);
+            println!("   ws_url             {}", if network.ws_url.is_empty() { "off (polling /book)" } else { &network.ws_url });
+            println!("   exchange           {:?}", network.exchange);
+            println!("   neg_risk_exchange  {:?}", network.neg_risk_exchange);
+            println!("   neg_risk_adapter   {:?}", network.neg_risk_adapter);
+            println!("   ctf                {:?}", network.ctf);
+            println!("   collateral         {:?}", network.collateral);
+            let rpc_urls = rpc_urls_from_env(&network);
+            for url in &rpc_urls {
+                errors.extend(url.parse::<reqwest::Url>().map(|_| ()).map_err(|_| format!("RPC url '{}' is not a URL", url)).err());
+            }
+            println!("   rpc_urls           {}", rpc_urls.join(", "));
+        }
+        Err(e) => errors.push(e.to_string()),
+    }
+
+    let tx = TxConfig::from_env();
+    println!("\nTransactions:");
+    println!("   confirmations      {}", tx.confirmations);
+    println!("   stuck_after        {}s", tx.stuck_after.as_secs());
+    println!("   timeout            {}s", tx.timeout.as_secs());
+    println!("   max_replacements   {}", tx.max_replacements);
+    if tx.stuck_after >= tx.timeout {
+        errors.push(format!("TX_STUCK_SECS {} must be below TX_TIMEOUT_SECS {}", tx.stuck_after.as_secs(), tx.timeout.as_secs()));
+    }
+
+    println!("\nRuntime:");
+    match Cadence::from_env() {
+        Ok(cadence) => {
+            println!("   poll_active        {}ms", cadence.active.as_millis());
+            println!("   poll_streaming     {}ms", cadence.streaming.as_millis());
+            println!("   poll_watching      {}s", cadence.watching.as_secs());
+        }
+        Err(e) => errors.push(e),
+    }
+    match lookahead_from_env() {
+        Ok(n) => println!("   lookahead          {} cycle(s)", n),
+        Err(e) => errors.push(e.to_string()),
+    }
+    match chaos_from_env() {
+        Ok(Some(chaos)) => println!("   chaos              {}", chaos),
+        Ok(None) => println!("   chaos              off"),
+        Err(e) => errors.push(e.to_string()),
+    }
+    match clock::from_env() {
+        Ok(time) if time.is_simulated() => println!("   clock              simulated from {}", time.now_secs()),
+        Ok(_) => println!("   clock              system"),
+        Err(e) => errors.push(e.to_string()),
+    }
+    match DisplayTz::from_env() {
+        Ok(tz) => println!("   display_tz         {}", tz),
+        Err(e) => errors.push(e.to_string()),
+    }
+    match trade_log_from_env(&Profile::default()) {
+        Ok(log) => {
+            let columns: Vec<&str> = log.columns().iter().map(|c| c.field.name()).collect();
+            println!("   trade_log          {} ({:?}: {})", log.path(), log.format(), columns.join(","));
+        }
+        Err(e) => errors.push(e.to_string()),
+    }
+    match notifiers_from_env(&Profile::default()) {
+        Ok(router) if router.is_empty() => println!("   notifications      (none)"),
+        Ok(router) => {
+            for (name, min) in router.channels() {
+                println!("   notifications      {} ({} and above)", name, min);
+            }
+        }
+        Err(e) => errors.push(e.to_string()),
+    }
+    match AlertWatcher::from_env() {
+        Ok(alerts) if alerts.is_empty() => println!("   alerts             (none)"),
+        Ok(alerts) => {
+            for rule in alerts.rules() {
+                println!("   alert              {}", rule.text);
+            }
+        }
+        Err(e) => errors.push(e),
+    }
+    match RuleEngine::from_env() {
+        Ok(rules) if rules.is_empty() => println!("   rules              (none)"),
+        Ok(rules) => {
+            for rule in rules.rules() {
+                println!("   rule               {}", rule.text);
+            }
+        }
+        Err(e) => errors.push(e),
+    }
+    match EventLog::from_env() {
+        Ok(_) => {
+            let show = |name: &str, default: &str| std::env::var(name).unwrap_or_else(|_| default.to_string());
+            println!("   log_dedup          {}s, at most {} distinct warnings per window",
+                show("BOT_LOG_DEDUP_SECS", "60"), show("BOT_LOG_MAX_PER_WINDOW", "20"));
+        }
+        Err(e) => errors.push(e),
+    }
+    match Schedule::from_env(config.market.schedule().map(|m| m.cadence).unwrap_or(market_schedule::DEFAULT_CADENCE)) {
+        Ok(_) => {
+            let show = |name: &str| std::env::var(name).unwrap_or_else(|_| "(unset)".to_string());
+            println!("   schedule           {}", show("BOT_SCHEDULE"));
+            println!("   skip_dates         {}", show("BOT_SKIP_DATES"));
+            println!("   skip_after         {}", show("BOT_SKIP_AFTER"));
+        }
+        Err(e) => errors.push(e),
+    }
+    if let Ok(v) = std::env::var("BOT_SIM_END") {
+        println!("   sim_end            {}", v);
+        errors.extend(v.parse::<u64>().map(|_| ()).map_err(|_| format!("Invalid BOT_SIM_END '{}'", v)).err());
+    }
+    #[cfg(feature = "event-stream")]
+    if let Ok(addr) = std::env::var("BOT_EVENTS_ADDR") {
+        println!("   events_addr        {}", addr);
+        errors.extend(addr.parse::<std::net::SocketAddr>().map(|_| ()).map_err(|_| format!("Invalid BOT_EVENTS_ADDR '{}'", addr)).err());
+    }
+    #[cfg(feature = "grpc")]
+    {
+        let addr = std::env::var("GRPC_ADDR").unwrap_or_else(|_| "127.0.0.1:50051".to_string());
+        println!("   grpc_addr          {}", addr);
+        errors.extend(addr.parse::<std::net::SocketAddr>().map(|_| ()).map_err(|_| format!("Invalid GRPC_ADDR '{}'", addr)).err());
+    }
+    let features: Vec<&str> = [
+        ("compression", cfg!(feature = "compression")),
+        ("fill-watch", cfg!(feature = "fill-watch")),
+        ("resolution", cfg!(feature = "resolution")),
+        ("redeem", cfg!(feature = "redeem")),
+        ("grpc", cfg!(feature = "grpc")),
+        ("recording", cfg!(feature = "recording")),
+        ("event-stream", cfg!(feature = "event-stream")),
+    ].into_iter().filter(|(_, on)| *on).map(|(name, _)| name).collect();
+    println!("   features           {}", features.join(", "));
+
+    if errors.is_empty() {
+        println!("\n✅ Configuration is valid.");
+        return Ok(());
+    }
+    println!("\n❌ {} problem(s):", errors.len());
+    for e in &errors {
+        println!("   - {}", e);
+    }
+    Err(format!("{} configuration problem(s)", errors.len()).into())
+}
+
+/// `ticks info <file>` lists a recording's frames; `ticks csv <file> [out]`
+/// flattens it to CSV (default: the input name with .csv).
+#[cfg(feature = "recording")]
+fn ticks_command(args: &[String], config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    match args {
+        [sub, path] if sub == "info" => {
+            let reader = tick_log::TickReader::open(path)?;
+            let index = reader.index();
+            let ticks: u64 = index.iter().map(|f| f.ticks as u64).sum();
+            println!("📼 {}: {} tick(s) in {} frame(s)", path, ticks, index.len());
+            for market in reader.markets() {
+                let frames: Vec<_> = index.iter().filter(|f| f.market == market).collect();
+                let first = frames.iter().map(|f| f.first_ts).min().unwrap_or_default();
+                let last = frames.iter().map(|f| f.last_ts).max().unwrap_or_default();
+                let count: u64 = frames.iter().map(|f| f.ticks as u64).sum();
+                println!("   {} {} tick(s) {} → {}", market, count, timestamps::rfc3339(first), timestamps::rfc3339(last));
+            }
+            Ok(())
+        }
+        [sub, path, out @ ..] if sub == "csv" && out.len() <= 1 => {
+            let out_path = match out.first() {
+                Some(out) => out.clone(),
+                None => format!("{}.csv", path.trim_end_matches(".ticks")),
+            };
+            let mut reader = tick_log::TickReader::open(path)?;
+            let rows = tick_log::write_csv(&mut reader, io::BufWriter::new(File::create(&out_path)?))?;
+            println!("✅ Wrote {} row(s) to {}", rows, out_path);
+            Ok(())
+        }
+        [sub, path, model @ ..] if sub == "attribute" && model.len() <= 1 => {
+            let model: fill_model::FillModel = match model.first() {
+                Some(model) => serde_json::from_str(&std::fs::read_to_string(model)?)?,
+                None => Default::default(),
+            };
+            attribute_ticks(path, &model, config)
+        }
+        [sub, path, model @ ..] if sub == "analyze" && model.len() <= 1 => {
+            let model: fill_model::FillModel = match model.first() {
+                Some(model) => serde_json::from_str(&std::fs::read_to_string(model)?)?,
+                None => Default::default(),
+            };
+            analyze_ticks(path, &model, config)
+        }
+        [sub, inputs @ .., out] if sub == "import" && !inputs.is_empty() => {
+            let mut records = Vec::new();
+            for input in inputs {
+                records.extend(book_recorder::read(input)?);
+            }
+            let mut writer = tick_log::TickWriter::create(out)?;
+            let mut total = 0;
+            for market in book_recorder::markets(&records) {
+                let ticks = book_recorder::ticks(&records, market);
+                for tick in &ticks {
+                    writer.push(market, tick)?;
+                }
+                println!("   {} {} tick(s)", market, ticks.len());
+                total += ticks.len();
+            }
+            writer.finish()?;
+            println!("✅ Wrote {} tick(s) to {}", total, out);
+            Ok(())
+        }
+        _ => Err("usage: ticks info <file> | ticks csv <file> [out.csv] | ticks attribute|analyze <file> [fill_model.json] | ticks import <books.jsonl>... <out.ticks>".into()),
+    }
+}
+
+/// `ticks attribute`: per-market PnL split into entry timing, stop vs hold
+/// and abort avoidance, using the configured strategy and stop loss. The
+/// winner is read off each market's last recorded tick.
+#[cfg(feature = "recording")]
+fn attribute_ticks(path: &str, model: &fill_model::FillModel, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    use attribution::{AttributionTotals, Resolution};
+
+    let params = strategy_params(config)?;
+    let stop_loss = config.strategy.stop_loss_price;
+    let mut reader = tick_log::TickReader::open(path)?;
+    let markets: Vec<String> = reader.markets().into_iter().map(str::to_string).collect();
+    let signed = |v: Option<f64>| v.map(|v| format!("{:+.2}", v)).unwrap_or_else(|| "-".to_string());
+
+    println!("📐 Decision attribution for {} (stop loss ${})", path, stop_loss);
+    println!("   {:<28} {:<10} {:>9} {:>9} {:>9} {:>9}", "market", "decision", "realized", "timing", "stop", "abort");
+    let mut totals = AttributionTotals::default();
+    for market in &markets {
+        let ticks = reader.read_market(market, 0, u64::MAX)?;
+        let Some(first) = ticks.first() else { continue };
+        // Slugs end in the market's start time
+        let start = market.rsplit('-').next().and_then(|ts| ts.parse::<u64>().ok()).unwrap_or(first.ts / 900 * 900);
+        let a = attribution::attribute(&params, start, &ticks, stop_loss, Resolution::FromLastTick, model);
+        let decision = match &a.decision {
+            strategy::ReplayOutcome::Entered { outcome, .. } => format!("entered {}", outcome.as_str()),
+            strategy::ReplayOutcome::Aborted { .. } => "aborted".to_string(),
+            strategy::ReplayOutcome::TimedOut { .. } => "timed out".to_string(),
+            strategy::ReplayOutcome::Closed { .. } => "closed".to_string(),
+            strategy::ReplayOutcome::NoDecision => "undecided".to_string(),
+        };
+        println!("   {:<28} {:<10} {:>+9.2} {:>9} {:>9} {:>9}", market, decision, a.realized, signed(a.entry_timing), signed(a.stop_vs_hold), signed(a.abort_avoidance));
+        totals.add(&a);
+    }
+    println!("\n   {} market(s), {} entr{}: realized ${:+.2}", totals.markets, totals.entries, if totals.entries == 1 { "y" } else { "ies" }, totals.realized);
+    println!("   Entry timing vs entering at window open: ${:+.2}", totals.entry_timing);
+    println!("   Stop loss vs holding ({} stop(s)):        ${:+.2}", totals.stops, totals.stop_vs_hold);
+    println!("   Aborts vs trading through ({} abort(s)):  ${:+.2}", totals.aborts, totals.abort_avoidance);
+    Ok(())
+}
+
+/// `ticks analyze`: performance statistics over every trade the configured
+/// strategy and stop loss take in a recording, overall and by side and
+/// asset. `GET /metrics` serves the same report for live trading.
+#[cfg(feature = "recording")]
+fn analyze_ticks(path: &str, model: &fill_model::FillModel, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    let params = strategy_params(config)?;
+    let stop_loss = config.strategy.stop_loss_price;
+    let mut reader = tick_log::TickReader::open(path)?;
+    let markets: Vec<String> = reader.markets().into_iter().map(str::to_string).collect();
+
+    let mut trades = Vec::new();
+    for market in &markets {
+        let ticks = reader.read_market(market, 0, u64::MAX)?;
+        let Some(first) = ticks.first() else { continue };
+        let start = market.rsplit('-').next().and_then(|ts| ts.parse::<u64>().ok()).unwrap_or(first.ts / 900 * 900);
+        let a = attribution::attribute(&params, start, &ticks, stop_loss, attribution::Resolution::FromLastTick, model);
+        if let (strategy::ReplayOutcome::Entered { outcome, .. }, Some(position)) = (&a.decision, a.position) {
+            trades.push(TradeResult {
+                strategy: outcome.as_str().to_string(),
+                asset: performance::asset_of(market),
+                ts: position.exit_ts,
+                pnl: position.pnl,
+                cost: position.entry_price * position.size as f64,
+            });
+        }
+    }
+
+    println!("📈 Performance over {} ({} market(s), stop loss ${})", path, markets.len(), stop_loss);
+    print_performance(&PerformanceReport::new(&trades));
+    Ok(())
+}
+
+#[cfg(feature = "recording")]
+fn print_performance(report: &PerformanceReport) {
+    let ratio = |v: Option<f64>| v.map(|v| format!("{:.2}", v)).unwrap_or_else(|| "-".to_string());
+    println!("   {:<10} {:>6} {:>7} {:>9} {:>8} {:>8} {:>7} {:>9} {:>7}", "", "trades", "win %", "pnl", "avg win", "avg loss", "pf", "drawdown", "sharpe");
+    let row = |name: &str, s: &performance::PerformanceStats| {
+        println!("   {:<10} {:>6} {:>6.1}% {:>+9.2} {:>8.2} {:>8.2} {:>7} {:>9.2} {:>7}",
+            name, s.trades, s.win_rate * 100.0, s.total_pnl, s.avg_win, s.avg_loss, ratio(s.profit_factor), s.max_drawdown, ratio(s.sharpe));
+    };
+    row("all", &report.overall);
+    for (strategy, stats) in &report.by_strategy {
+        row(&format!("side {}", strategy), stats);
+    }
+    for (asset, stats) in &report.by_asset {
+        row(asset, stats);
+    }
+}
+
+/// BOT_LOOKAHEAD: upcoming cycles to resolve in advance (default 2, 0 = off).
+fn lookahead_from_env() -> Result<u64, Box<dyn std::error::Error>> {
+    match std::env::var("BOT_LOOKAHEAD") {
+        Ok(v) => Ok(v.trim().parse().map_err(|_| format!("Invalid BOT_LOOKAHEAD '{}': expected a number of cycles", v))?),
+        Err(_) => Ok(2),
+    }
+}
+
+fn chaos_from_env() -> Result<Option<Chaos>, Box<dyn std::error::Error>> {
+    match std::env::var("BOT_CHAOS") {
+        Ok(v) => Ok(Some(Chaos::parse(&v).map_err(|e| format!("Invalid BOT_CHAOS: {}", e))?)),
+        Err(_) => Ok(None),
+    }
+}
+
+fn http_client(timeout: u64) -> Result<Client, reqwest::Error> {
+    let builder = Client::builder().timeout(Duration::from_secs(timeout));
+    // Sends Accept-Encoding and transparently decompresses books/listings
+    #[cfg(feature = "compression")]
+    let builder = builder.gzip(true).brotli(true);
+    builder.build()
+}
+
+/// POLYGON_RPC_URLS (comma-separated, in preference order), else
+/// POLYGON_RPC_URL, else the network's public default.
+fn rpc_urls_from_env(network: &NetworkProfile) -> Vec<String> {
+    let urls: Vec<String> = std::env::var("POLYGON_RPC_URLS")
+        .or_else(|_| std::env::var("POLYGON_RPC_URL"))
+        .unwrap_or_default()
+        .split(',')
+        .map(|u| u.trim().to_string())
+        .filter(|u| !u.is_empty())
+        .collect();
+
+    if urls.is_empty() {
+        vec![network.default_rpc_url.clone()]
+    } else {
+        urls
+    }
+}
+
+/// Order salt unique per submission so identical retries get distinct hashes.
+fn unique_salt() -> u64 {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos() as u64).unwrap_or(0);
+    (nanos / 1_000) * 1_000 + COUNTER.fetch_add(1, Ordering::Relaxed) % 1_000
+}
+
+fn parse_price_map(raw: HashMap<String, String>) -> Result<HashMap<String, f64>, Box<dyn std::error::Error>> {
+    let mut parsed = HashMap::with_capacity(raw.len());
+    for (key, value) in raw {
+        parsed.insert(key, value.parse::<f64>()?);
+    }
+    Ok(parsed)
+}
+
+/// POLYMARKET_ADDRESS: the proxy wallet to trade from, or the signer's
+/// own address for EOA mode. There's no default.
+fn trading_address_from_env(profile: &Profile) -> Result<String, String> {
+    profile.var("POLYMARKET_ADDRESS")
+        .ok_or_else(|| "POLYMARKET_ADDRESS is not set; give the proxy wallet to trade from, or the signer's own address".to_string())
+}
+
+/// The signing key: BOT_KEYSTORE, an encrypted JSON keystore unlocked with
+/// BOT_KEYSTORE_PASSWORD or a password typed at the terminal, else a raw
+/// PRIVATE_KEY. There is no built-in key.
+fn wallet_from_env(profile: &Profile) -> Result<LocalWallet, Box<dyn std::error::Error>> {
+    if let Some(path) = profile.var("BOT_KEYSTORE") {
+        let password = match profile.var("BOT_KEYSTORE_PASSWORD") {
+            Some(password) => password,
+            None => rpassword::prompt_password(format!("🔐 Password for keystore {}: ", path))
+                .map_err(|e| format!("Keystore {} needs a password: set BOT_KEYSTORE_PASSWORD or run from a terminal ({})", path, e))?,
+        };
+        return Ok(LocalWallet::decrypt_keystore(&path, password).map_err(|e| format!("Cannot unlock keystore {}: {}", path, e))?);
+    }
+    match profile.var("PRIVATE_KEY") {
+        Some(key) => Ok(key.parse::<LocalWallet>().map_err(|e| format!("PRIVATE_KEY does not parse: {}", e))?),
+        None => Err("No signing key: set BOT_KEYSTORE to an encrypted keystore file, or PRIVATE_KEY".into()),
+    }
+}
+
+/// POLY_API_KEY, POLY_API_SECRET and POLY_API_PASSPHRASE, all or none;
+/// with none set the bot derives them from the signer at startup.
+fn api_creds_from_env(profile: &Profile) -> Result<Option<ApiCredentials>, String> {
+    match (profile.var("POLY_API_KEY"), profile.var("POLY_API_SECRET"), profile.var("POLY_API_PASSPHRASE")) {
+        (Some(api_key), Some(secret), Some(passphrase)) => Ok(Some(ApiCredentials { api_key, secret, passphrase })),
+        (None, None, None) => Ok(None),
+        (key, secret, _) => {
+            let missing = if key.is_none() { "POLY_API_KEY" } else if secret.is_none() { "POLY_API_SECRET" } else { "POLY_API_PASSPHRASE" };
+            Err(format!("{} not set (set all three POLY_API_* or none to derive them)", missing))
+        }
+    }
+}
+
+/// The `{apiKey, secret, passphrase}` body of derive-api-key and api-key.
+fn parse_api_creds(body: &Value) -> Result<ApiCredentials, String> {
+    let field = |key: &str| match body[key].as_str() {
+        Some(v) if !v.is_empty() => Ok(v.to_string()),
+        _ => Err(format!("response has no {}", key)),
+    };
+    Ok(ApiCredentials { api_key: field("apiKey")?, secret: field("secret")?, passphrase: field("passphrase")? })
+}
+
+/// BOT_LOG_COLUMNS and BOT_LOG_FORMAT; the original CSV when unset.
+fn trade_log_from_env(profile: &Profile) -> Result<TradeLog, Box<dyn std::error::Error>> {
+    Ok(TradeLog::configure(
+        &profile.path(LOG_FILE),
+        profile.var("BOT_LOG_COLUMNS").as_deref(),
+        profile.var("BOT_LOG_FORMAT").as_deref(),
+    )?)
+}
+
+/// BOT_WEBHOOK_URL plus optional BOT_WEBHOOK_TEMPLATE (inline, or @path to
+/// read it from a file), BOT_WEBHOOK_SECRET for signing and
+/// BOT_WEBHOOK_EVENTS (comma-separated, default entry,exit,error).
+fn webhook_from_env(profile: &Profile) -> Result<Option<WebhookConfig>, Box<dyn std::error::Error>> {
+    let Some(url) = profile.var("BOT_WEBHOOK_URL") else { return Ok(None) };
+    url.parse::<reqwest::Url>().map_err(|_| format!("Invalid BOT_WEBHOOK_URL '{}'", url))?;
+    let mut config = WebhookConfig::new(&url);
+    config.template = match profile.var("BOT_WEBHOOK_TEMPLATE") {
+        Some(path) if path.starts_with('@') => Some(std::fs::read_to_string(&path[1..])
+            .map_err(|e| format!("Cannot read BOT_WEBHOOK_TEMPLATE {}: {}", &path[1..], e))?),
+        template => template,
+    };
+    config.secret = profile.var("BOT_WEBHOOK_SECRET");
+    if let Some(events) = events_from_env(profile, "BOT_WEBHOOK_EVENTS")? {
+        config.events = events;
+    }
+    Ok(Some(config))
+}
+
+/// BOT_TELEGRAM_TOKEN and BOT_TELEGRAM_CHAT_ID (both or neither), plus
+/// optional BOT_TELEGRAM_EVENTS (comma-separated, default entry, exit,
+/// abort, liquidation, rule and fatal) and BOT_TELEGRAM_API_URL for a
+/// self-hosted Bot API server.
+fn telegram_from_env(profile: &Profile) -> Result<Option<TelegramConfig>, Box<dyn std::error::Error>> {
+    let (token, chat_id) = match (profile.var("BOT_TELEGRAM_TOKEN"), profile.var("BOT_TELEGRAM_CHAT_ID")) {
+        (Some(token), Some(chat_id)) => (token, chat_id),
+        (None, None) => return Ok(None),
+        (Some(_), None) => return Err("BOT_TELEGRAM_CHAT_ID not set (BOT_TELEGRAM_TOKEN needs a chat)".into()),
+        (None, Some(_)) => return Err("BOT_TELEGRAM_TOKEN not set (BOT_TELEGRAM_CHAT_ID needs a bot token)".into()),
+    };
+    let mut config = TelegramConfig::new(&token, &chat_id);
+    if let Some(url) = profile.var("BOT_TELEGRAM_API_URL") {
+        url.parse::<reqwest::Url>().map_err(|_| format!("Invalid BOT_TELEGRAM_API_URL '{}'", url))?;
+        config.api_url = url;
+    }
+    if let Some(events) = events_from_env(profile, "BOT_TELEGRAM_EVENTS")? {
+        config.events = events;
+    }
+    Ok(Some(config))
+}
+
+/// BOT_DISCORD_WEBHOOK_URL plus optional BOT_DISCORD_EVENTS (same list
+/// and defaults as Telegram's).
+fn discord_from_env(profile: &Profile) -> Result<Option<DiscordConfig>, Box<dyn std::error::Error>> {
+    let Some(url) = profile.var("BOT_DISCORD_WEBHOOK_URL") else { return Ok(None) };
+    // The URL is a credential; keep it out of the message
+    url.parse::<reqwest::Url>().map_err(|_| "Invalid BOT_DISCORD_WEBHOOK_URL")?;
+    let mut config = DiscordConfig::new(&url);
+    if let Some(events) = events_from_env(profile, "BOT_DISCORD_EVENTS")? {
+        config.events = events;
+    }
+    Ok(Some(config))
+}
+
+/// A comma-separated event subscription; see `notify::Event`.
+fn events_from_env(profile: &Profile, name: &str) -> Result<Option<Vec<String>>, String> {
+    profile.var(name).map(|list| notify::parse_events(&list).map_err(|e| format!("Invalid {}: {}", name, e))).transpose()
+}
+
+fn severity_from_env(profile: &Profile, name: &str) -> Result<Option<Severity>, String> {
+    profile.var(name)
+        .map(|level| Severity::parse(&level).ok_or_else(|| format!("Invalid {} '{}': use info, warning or critical", name, level)))
+        .transpose()
+}
+
+/// Channels from the environment. BOT_NOTIFY_STDOUT and BOT_NOTIFY_FILE
+/// (=<min severity>) print notifications or append them as JSON lines to
+/// the profile's notifications.jsonl; BOT_WEBHOOK_URL adds a webhook,
+/// BOT_TELEGRAM_TOKEN a Telegram chat and BOT_DISCORD_WEBHOOK_URL a Discord
+/// channel. Anything else implements `notify::Notifier` and goes on the
+/// router the same way.
+fn notifiers_from_env(profile: &Profile) -> Result<Router, Box<dyn std::error::Error>> {
+    let mut router = Router::new();
+    if let Some(min) = severity_from_env(profile, "BOT_NOTIFY_STDOUT")? {
+        router.add(min, Box::new(StdoutNotifier));
+    }
+    if let Some(min) = severity_from_env(profile, "BOT_NOTIFY_FILE")? {
+        router.add(min, Box::new(JsonLinesNotifier::new(profile.path(NOTIFICATIONS_FILE))));
+    }
+    if let Some(config) = webhook_from_env(profile)? {
+        router.add(Severity::Info, Box::new(WebhookNotifier::new(config)?));
+    }
+    if let Some(config) = telegram_from_env(profile)? {
+        router.add(Severity::Info, Box::new(TelegramNotifier::new(config)?));
+    }
+    if let Some(config) = discord_from_env(profile)? {
+        router.add(Severity::Info, Box::new(DiscordNotifier::new(config)?));
+    }
+    Ok(router)
+}
+
+/// Trade every profile and asset at once, one thread each. The bot keeps its
+/// state in RefCells, so each thread builds its own instance; all they share
+/// is the process and risk.max_open_positions. With several assets, each
+/// keeps its files in a directory named after it under the profile's.
+/// Exits non-zero if any of them failed.
+fn run_concurrently(profiles: Vec<Profile>, configs: &[Config]) {
+    let limit = Arc::new(PositionLimit::new(configs.first().map(|c| c.risk.max_open_positions).unwrap_or(0)));
+    if limit.max() > 0 {
+        println!("🧮 At most {} position(s) open at once across all markets", limit.max());
+    }
+    let instances: Vec<(String, Profile, Config)> = profiles.iter().flat_map(|profile| configs.iter().map(move |config| {
+        if configs.len() == 1 {
+            return (profile.name.clone(), profile.clone(), config.clone());
+        }
+        let mut profile = profile.clone();
+        profile.data_dir = profile.data_dir.join(&config.market.asset);
+        (format!("{}-{}", profile.name, config.market.asset), profile, config.clone())
+    })).collect();
+
+    let handles: Vec<_> = instances.into_iter().enumerate().map(|(i, (name, profile, config))| {
+        let limit = Arc::clone(&limit);
+        // A profile's assets share its account; one startup cancel-all will do
+        let cancel_on_startup = i.is_multiple_of(configs.len());
+        let handle = std::thread::Builder::new().name(name.clone()).spawn(move || -> Result<(), String> {
+            let mut bot = EthNoTrendBot::new(profile, config).map_err(|e| format!("failed to initialize: {}", e))?;
+            bot.position_limit = limit;
+            bot.cancel_on_startup = cancel_on_startup;
+            bot.run().map_err(|e| {
+                bot.notify(Severity::Critical, Event::Fatal, "Bot stopped on an error", &e.to_string());
+                e.to_string()
+            })
+        });
+        (name, handle)
+    }).collect();
+
+    let mut failed = 0;
+    for (name, handle) in handles {
+        let result = match handle {
+            Ok(handle) => handle.join().unwrap_or_else(|_| Err("thread panicked".to_string())),
+            Err(e) => Err(format!("could not start thread: {}", e)),
+        };
+        if let Err(e) = result {
+            eprintln!("\n❌ {}: {}", name, e);
+            failed += 1;
+        }
+    }
+    if failed > 0 {
+        std::process::exit(1);
+    }
+}
+
+// ==========================================
+// 🖥️ COMMAND LINE
+// ==========================================
+
+/// Trades Polymarket's 15-minute up/down markets. Without a command it runs
+/// the strategy loop, same as `run`.
+#[derive(Parser)]
+#[command(version)]
+struct Cli {
+    #[command(flatten)]
+    overrides: Overrides,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// Settings for this invocation that win over config.toml.
+#[derive(Args, Default)]
+struct Overrides {
+    /// Config file to use instead of BOT_CONFIG or ./config.toml
+    #[arg(long, global = true, value_name = "FILE")]
+    config: Option<String>,
+    /// Asset(s) whose up/down markets to trade (market.asset), e.g. btc or eth,btc
+    #[arg(long, global = true, value_name = "SYMBOL")]
+    asset: Option<String>,
+    /// Entry trigger price (strategy.entry_price)
+    #[arg(long, global = true, value_name = "PRICE")]
+    entry_price: Option<f64>,
+    /// Shares per entry (strategy.position_size)
+    #[arg(long, global = true, value_name = "SHARES")]
+    size: Option<u32>,
+    /// Print orders instead of submitting them
+    #[arg(long, global = true)]
+    dry_run: bool,
+    /// Fill orders against the live book instead of submitting them
+    #[arg(long, global = true)]
+    paper: bool,
+    /// `json` also writes every event as a JSON line on stderr
+    #[arg(long, global = true, value_name = "FORMAT", value_parser = ["text", "json"])]
+    log_format: Option<String>,
+}
+
+impl Overrides {
+    /// The configuration of the first traded asset.
+    fn load(&self) -> Result<Config, String> {
+        self.load_all()?.into_iter().next().ok_or_else(|| "--asset names no asset".to_string())
+    }
+
+    /// One configuration per traded asset, from `--asset` or the file's
+    /// `[market]`: each with its `[assets.*]` overrides, then the flags.
+    fn load_all(&self) -> Result<Vec<Config>, String> {
+        let mut base = match &self.config {
+            Some(path) => Config::load(path)?,
+            None => Config::from_env()?,
+        };
+        base.dry_run |= self.dry_run;
+        base.paper |= self.paper;
+        if let Some(format) = self.log_format.as_deref().and_then(LogOutput::parse) {
+            base.log_format = format;
+        }
+        let assets = match &self.asset {
+            Some(list) => list.split(',').map(str::trim).filter(|a| !a.is_empty()).map(str::to_string).collect(),
+            None => base.traded_assets(),
+        };
+        Ok(assets.iter().map(|asset| {
+            let mut config = base.clone();
+            config.select_asset(Some(asset));
+            if let Some(price) = self.entry_price {
+                config.strategy.entry_price = price;
+            }
+            if let Some(size) = self.size {
+                // A size given on the command line is meant literally
+                config.strategy.position_size = size;
+                config.strategy.sizing = None;
+            }
+            config
+        }).collect())
+    }
+}
+
+/// Arguments a command parses itself.
+#[derive(Args)]
+struct Rest {
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    args: Vec<String>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the strategy loop (the default)
+    Run,
+    /// Market, exchange, balance, open orders and positions at a glance
+    Status,
+    /// Cancel every open order
+    CancelAll,
+    /// Sell every held position into the bid
+    Liquidate {
+        /// Only this token
+        token_id: Option<String>,
+    },
+    /// buy <token_id> <price> <size> [FOK|FAK|GTC|GTD]
+    Buy(Rest),
+    /// sell <token_id> <price> <size> [FOK|FAK|GTC|GTD]
+    Sell(Rest),
+    /// cancel <order_id>
+    Cancel(Rest),
+    /// Positions and net exposure per market
+    Positions,
+    /// orders | orders history [ORDER_ID]
+    Orders(Rest),
+    /// book <token_id>...
+    Book(Rest),
+    /// export positions|lots [FILE]
+    Export(Rest),
+    /// report [YYYY-MM-DD] [FILE.csv]
+    Report(Rest),
+    /// Check connectivity, clock and credentials
+    Doctor,
+    /// ledger | ledger reconcile [--apply]
+    Ledger(Rest),
+    /// Redeem resolved positions
+    #[cfg(feature = "redeem")]
+    Claim,
+    /// Serve the execution gRPC API
+    #[cfg(feature = "grpc")]
+    ServeGrpc,
+    /// Print the effective configuration without starting
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// ticks info|csv|attribute|analyze|import <file> ...
+    #[cfg(feature = "recording")]
+    Ticks(Rest),
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Resolve and validate config.toml, env and flags
+    Check,
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    println!("✅ COMPLETE Rust Trading Bot with REST API");
+    println!("✅ EIP-712 Signing Implemented");
     println!("✅ All Trading Functions Operational\n");
-    
-    match EthNoTrendBot::new() {
+
+    // Runs before startup so a bad config is reported instead of acted on
+    if let Some(Command::Config { command: ConfigCommand::Check }) = cli.command {
+        if let Err(e) = config_check(&cli.overrides) {
+            eprintln!("\n❌ {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let configs = match cli.overrides.load_all() {
+        Ok(configs) if !configs.is_empty() => configs,
+        Ok(_) => {
+            eprintln!("❌ Failed to initialize bot: --asset names no asset");
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("❌ Failed to initialize bot: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    #[cfg(feature = "recording")]
+    if let Some(Command::Ticks(rest)) = &cli.command {
+        if let Err(e) = ticks_command(&rest.args, &configs[0]) {
+            eprintln!("\n❌ {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let profiles = match profiles::from_env() {
+        Ok(profiles) => profiles,
+        Err(e) => {
+            eprintln!("❌ Failed to initialize bot: {}", e);
+            std::process::exit(1);
+        }
+    };
+    if profiles.len() > 1 || configs.len() > 1 {
+        if !matches!(cli.command, None | Some(Command::Run)) {
+            if profiles.len() > 1 {
+                let names: Vec<&str> = profiles.iter().map(|p| p.name.as_str()).collect();
+                eprintln!("❌ Several profiles configured; set BOT_PROFILE to one of: {}", names.join(", "));
+            } else {
+                eprintln!("❌ Several assets configured; pick one with --asset");
+            }
+            std::process::exit(1);
+        }
+        run_concurrently(profiles, &configs);
+        return;
+    }
+    let profile = profiles.into_iter().next().unwrap_or_default();
+    let config = configs.into_iter().next().unwrap_or_default();
+
+    match EthNoTrendBot::new(profile, config) {
         Ok(mut bot) => {
-            if let Err(e) = bot.run() {
+            let trading = matches!(cli.command, None | Some(Command::Run));
+            let result = match cli.command {
+                None | Some(Command::Run) => bot.run(),
+                Some(Command::Status) => bot.cli_status(),
+                Some(Command::CancelAll) => bot.cli_cancel_all(),
+                Some(Command::Liquidate { token_id }) => bot.cli_liquidate(token_id.as_deref()),
+                Some(Command::Buy(rest)) => bot.cli_order(OrderSide::Buy, &rest.args),
+                Some(Command::Sell(rest)) => bot.cli_order(OrderSide::Sell, &rest.args),
+                Some(Command::Cancel(rest)) => bot.cli_cancel(&rest.args),
+                Some(Command::Positions) => bot.cli_positions(),
+                Some(Command::Orders(rest)) => bot.cli_orders(&rest.args),
+                Some(Command::Book(rest)) => bot.cli_book(&rest.args),
+                Some(Command::Export(rest)) => bot.cli_export(&rest.args),
+                Some(Command::Report(rest)) => bot.cli_report(&rest.args),
+                Some(Command::Doctor) => bot.doctor(),
+                Some(Command::Ledger(rest)) => bot.cli_ledger(&rest.args),
+                #[cfg(feature = "redeem")]
+                Some(Command::Claim) => bot.claim_winnings(),
+                #[cfg(feature = "grpc")]
+                Some(Command::ServeGrpc) => serve_grpc(&bot),
+                // Handled above, before the bot starts
+                Some(Command::Config { .. }) => Ok(()),
+                #[cfg(feature = "recording")]
+                Some(Command::Ticks(_)) => Ok(()),
+            };
+            if let Err(e) = result {
                 eprintln!("\n❌ Bot error: {}", e);
+                if trading {
+                    bot.notify(Severity::Critical, Event::Fatal, "Bot stopped on an error", &e.to_string());
+                }
+                // Lets queued notifications go out before the exit
+                drop(bot);
+                std::process::exit(1);
             }
         }
         Err(e) => {
@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+// The CLOB signals the last page with this cursor ("-1" base64-encoded)
+pub const END_CURSOR: &str = "LTE=";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClobToken {
+    pub token_id: String,
+    #[serde(default)]
+    pub outcome: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClobMarket {
+    pub condition_id: String,
+    #[serde(default)]
+    pub question: String,
+    #[serde(default)]
+    pub market_slug: String,
+    #[serde(default)]
+    pub minimum_tick_size: f64,
+    #[serde(default)]
+    pub minimum_order_size: f64,
+    #[serde(default)]
+    pub neg_risk: bool,
+    #[serde(default)]
+    pub active: bool,
+    #[serde(default)]
+    pub closed: bool,
+    #[serde(default)]
+    pub tokens: Vec<ClobToken>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MarketsPage {
+    #[serde(default)]
+    pub data: Vec<ClobMarket>,
+    #[serde(default)]
+    pub next_cursor: String,
+}
+
+/// On-disk cache of `/markets`, keyed by condition id.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct MarketCache {
+    pub markets: HashMap<String, ClobMarket>,
+    // Resume point for incremental syncs
+    #[serde(default)]
+    pub next_cursor: String,
+}
+
+impl MarketCache {
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        if !Path::new(path).exists() {
+            return Ok(Self::default());
+        }
+        let file = File::open(path)?;
+        Ok(serde_json::from_reader(BufReader::new(file))?)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        // Write to a temp file first so a crash never leaves a truncated cache
+        let tmp_path = format!("{}.tmp", path);
+        let file = File::create(&tmp_path)?;
+        serde_json::to_writer(BufWriter::new(file), self)?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    pub fn upsert(&mut self, markets: Vec<ClobMarket>) {
+        for market in markets {
+            self.markets.insert(market.condition_id.clone(), market);
+        }
+    }
+
+    pub fn get(&self, condition_id: &str) -> Option<&ClobMarket> {
+        self.markets.get(condition_id)
+    }
+
+    pub fn find_by_token(&self, token_id: &str) -> Option<&ClobMarket> {
+        self.markets.values()
+            .find(|m| m.tokens.iter().any(|t| t.token_id == token_id))
+    }
+}
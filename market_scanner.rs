@@ -0,0 +1,96 @@
+//! Looks up upcoming markets ahead of time. While the bot waits out the
+//! current cycle, `look_ahead` queues the next few cycles and a background
+//! thread resolves them on Gamma (event, condition id, token
+//! ids), so when a cycle opens its `MarketData` is already in hand instead
+//! of being fetched, with a listing delay and retries, right then.
+//!
+//! A cycle that isn't listed yet or fails to resolve is simply not ready;
+//! the next `look_ahead` queues it again and `take` falls through to the
+//! caller's own lookup.
+
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use reqwest::blocking::Client;
+
+use crate::discovery::MarketFinder;
+use crate::responses::MarketData;
+
+#[derive(Default)]
+struct ScanState {
+    // Queued or being fetched
+    pending: HashSet<u64>,
+    ready: HashMap<u64, MarketData>,
+}
+
+pub struct MarketScanner {
+    jobs: Sender<u64>,
+    state: Arc<(Mutex<ScanState>, Condvar)>,
+    cycle: u64,
+    // Cycles after the current one to resolve; 0 turns the scanner off
+    lookahead: u64,
+}
+
+impl MarketScanner {
+    /// Start the lookup thread. `finder` says how a cycle's market is found.
+    pub fn spawn(client: Client, gamma_url: &str, finder: MarketFinder, cycle: u64, lookahead: u64) -> io::Result<Self> {
+        let (jobs, queue) = mpsc::channel::<u64>();
+        let state = Arc::new((Mutex::new(ScanState::default()), Condvar::new()));
+
+        let shared = Arc::clone(&state);
+        let gamma_url = gamma_url.to_string();
+        thread::Builder::new().name("market-scanner".to_string()).spawn(move || {
+            for cycle_start in queue {
+                let market = resolve(&client, &gamma_url, &finder, cycle_start);
+                let (lock, resolved) = &*shared;
+                let mut state = lock.lock().unwrap_or_else(|e| e.into_inner());
+                state.pending.remove(&cycle_start);
+                if let Some(market) = market {
+                    state.ready.insert(cycle_start, market);
+                }
+                resolved.notify_all();
+            }
+        })?;
+
+        Ok(Self { jobs, state, cycle, lookahead })
+    }
+
+    /// Queue the cycles after `current_start` that aren't resolved or
+    /// already queued, and forget ones that have passed.
+    pub fn look_ahead(&self, current_start: u64) {
+        let mut state = self.state.0.lock().unwrap_or_else(|e| e.into_inner());
+        state.ready.retain(|start, _| *start >= current_start);
+        for n in 1..=self.lookahead {
+            let start = current_start + n * self.cycle;
+            if !state.ready.contains_key(&start) && state.pending.insert(start) {
+                // The thread only stops with the scanner
+                let _ = self.jobs.send(start);
+            }
+        }
+    }
+
+    /// The market for the cycle at `cycle_start`, waiting up to `wait` for
+    /// a lookup already under way. None if it was never queued or didn't
+    /// resolve.
+    pub fn take(&self, cycle_start: u64, wait: Duration) -> Option<MarketData> {
+        let (lock, resolved) = &*self.state;
+        let state = lock.lock().unwrap_or_else(|e| e.into_inner());
+        let (mut state, _) = resolved
+            .wait_timeout_while(state, wait, |s| s.pending.contains(&cycle_start))
+            .unwrap_or_else(|e| e.into_inner());
+        state.ready.remove(&cycle_start)
+    }
+}
+
+fn resolve(client: &Client, gamma_url: &str, finder: &MarketFinder, cycle_start: u64) -> Option<MarketData> {
+    let url = format!("{}{}", gamma_url, finder.query(cycle_start));
+    let resp = client.get(&url).timeout(Duration::from_secs(10)).send().ok()?;
+    if !resp.status().is_success() {
+        return None;
+    }
+    finder.parse(cycle_start, &resp.bytes().ok()?).ok().flatten()
+}
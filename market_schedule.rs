@@ -0,0 +1,128 @@
+//! Which market is live when. A schedule is a cadence (15m, 1h, 4h, 1d or
+//! any whole number of seconds) and a slug template. Cycles start on
+//! multiples of the cadence since the epoch, so hourly markets open on the
+//! UTC hour and daily ones at UTC midnight; a cycle is named by its start.
+//!
+//! Template placeholders:
+//!   {asset}    the traded asset (eth)
+//!   {cadence}  the cadence as slugs spell it (15m, 1h, 4h, 1d)
+//!   {ts}       cycle start, unix seconds
+//!   {date}     the start's UTC date, YYYY-MM-DD
+//!   {hour}     the start's UTC hour, 00-23
+//!
+//! The default, `{asset}-updown-{cadence}-{ts}`, gives
+//! `eth-updown-15m-1760000400`.
+
+use crate::schedule::civil_from_days;
+
+pub const DEFAULT_TEMPLATE: &str = "{asset}-updown-{cadence}-{ts}";
+pub const DEFAULT_CADENCE: u64 = 900;
+
+const PLACEHOLDERS: [&str; 5] = ["asset", "cadence", "ts", "date", "hour"];
+// Placeholders that differ from one cycle to the next
+const PER_CYCLE: [&str; 3] = ["ts", "date", "hour"];
+
+/// `15m`, `1h`, `4h`, `1d`, `90s` or bare seconds to seconds.
+pub fn parse_cadence(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let (number, unit) = match s.find(|c: char| !c.is_ascii_digit()) {
+        Some(i) => s.split_at(i),
+        None => (s, "s"),
+    };
+    let scale = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3_600,
+        "d" => 86_400,
+        _ => return Err(format!("cadence '{}' must be a number of s, m, h or d", s)),
+    };
+    match number.parse::<u64>() {
+        Ok(n) if n > 0 => Ok(n * scale),
+        _ => Err(format!("cadence '{}' must be a number of s, m, h or d", s)),
+    }
+}
+
+/// The largest whole unit: 900 -> `15m`, 14400 -> `4h`, 86400 -> `1d`.
+pub fn cadence_label(secs: u64) -> String {
+    for (unit, scale) in [("d", 86_400), ("h", 3_600), ("m", 60)] {
+        if secs.is_multiple_of(scale) {
+            return format!("{}{}", secs / scale, unit);
+        }
+    }
+    format!("{}s", secs)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarketSchedule {
+    pub asset: String,
+    pub template: String,
+    // Cycle length, seconds
+    pub cadence: u64,
+}
+
+impl Default for MarketSchedule {
+    fn default() -> Self {
+        Self { asset: "eth".to_string(), template: DEFAULT_TEMPLATE.to_string(), cadence: DEFAULT_CADENCE }
+    }
+}
+
+impl MarketSchedule {
+    /// Rejects unknown placeholders, unbalanced braces and templates that
+    /// would name every cycle the same.
+    pub fn new(asset: &str, template: &str, cadence: u64) -> Result<Self, String> {
+        if cadence == 0 {
+            return Err("cadence must be at least 1s".to_string());
+        }
+        let mut used = Vec::new();
+        let mut rest = template;
+        while let Some(open) = rest.find('{') {
+            let close = rest[open..].find('}').ok_or_else(|| format!("slug template '{}' has an unclosed '{{'", template))?;
+            let name = &rest[open + 1..open + close];
+            if !PLACEHOLDERS.contains(&name) {
+                return Err(format!("slug template '{}' has unknown placeholder {{{}}}; expected one of {{{}}}",
+                    template, name, PLACEHOLDERS.join("}, {")));
+            }
+            used.push(name);
+            rest = &rest[open + close + 1..];
+        }
+        if rest.contains('}') {
+            return Err(format!("slug template '{}' has a stray '}}'", template));
+        }
+        if !used.iter().any(|name| PER_CYCLE.contains(name)) {
+            return Err(format!("slug template '{}' needs {{ts}}, {{date}} or {{hour}} to tell cycles apart", template));
+        }
+        Ok(Self { asset: asset.to_string(), template: template.to_string(), cadence })
+    }
+
+    /// Start of the cycle `now` falls in.
+    pub fn cycle_start(&self, now: u64) -> u64 {
+        now / self.cadence * self.cadence
+    }
+
+    /// When the cycle starting at `start` closes.
+    pub fn cycle_end(&self, start: u64) -> u64 {
+        start + self.cadence
+    }
+
+    /// Start of the cycle after the one `now` falls in.
+    pub fn next_start(&self, now: u64) -> u64 {
+        self.cycle_end(self.cycle_start(now))
+    }
+
+    /// The slug of the cycle starting at `start`.
+    pub fn slug(&self, start: u64) -> String {
+        let (year, month, day) = civil_from_days((start / 86_400) as i64);
+        self.template
+            .replace("{asset}", &self.asset)
+            .replace("{cadence}", &cadence_label(self.cadence))
+            .replace("{ts}", &start.to_string())
+            .replace("{date}", &format!("{:04}-{:02}-{:02}", year, month, day))
+            .replace("{hour}", &format!("{:02}", start % 86_400 / 3_600))
+    }
+
+    /// The template with only the per-cycle placeholders left, for the
+    /// console: `eth-updown-15m-{ts}`.
+    pub fn pattern(&self) -> String {
+        self.template.replace("{asset}", &self.asset).replace("{cadence}", &cadence_label(self.cadence))
+    }
+}
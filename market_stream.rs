@@ -0,0 +1,307 @@
+//! Live order books from the CLOB WebSocket market channel. A background
+//! thread subscribes to the tokens being watched, keeps every level of
+//! their books from the `book` snapshots and `price_change` deltas, and
+//! serves the touch to the trading loop without a request per look.
+//! Trades printed on the channel are kept for the book recorder.
+//!
+//! A book is only served while the connection is up and its snapshot has
+//! arrived; otherwise `book` returns None and the caller polls `/book` as
+//! before. Dropped connections are retried with backoff, and a change of
+//! watched tokens reconnects with the new subscription.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::net::TcpStream;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket};
+
+use crate::book_parser::{fixed_to_f64, parse_fixed};
+use crate::strategy::OrderBook;
+
+// The server drops connections that stay quiet longer than this
+const PING_INTERVAL: Duration = Duration::from_secs(10);
+// How often the reader wakes to ping and notice a new subscription
+const READ_TIMEOUT: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+// Trades kept until someone takes them; the oldest go first
+const MAX_PENDING_TRADES: usize = 1024;
+
+/// Every level of one token's book, price -> size in fixed point.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Levels {
+    pub bids: BTreeMap<u64, u64>,
+    pub asks: BTreeMap<u64, u64>,
+}
+
+impl Levels {
+    pub fn top(&self) -> OrderBook {
+        let bid = self.bids.iter().next_back();
+        let ask = self.asks.iter().next();
+        OrderBook {
+            best_bid: bid.map(|(p, _)| fixed_to_f64(*p)),
+            bid_size: bid.map(|(_, s)| fixed_to_f64(*s)).unwrap_or(0.0),
+            best_ask: ask.map(|(p, _)| fixed_to_f64(*p)),
+            ask_size: ask.map(|(_, s)| fixed_to_f64(*s)).unwrap_or(0.0),
+        }
+    }
+}
+
+/// One `last_trade_price` print.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarketTrade {
+    pub token_id: String,
+    pub price: f64,
+    pub size: f64,
+    // Taker side, BUY or SELL
+    pub side: String,
+    pub ts_ms: u64,
+}
+
+impl MarketTrade {
+    fn parse(event: &Value) -> Option<Self> {
+        let number = |key: &str| event[key].as_str()?.parse::<f64>().ok();
+        Some(Self {
+            token_id: event["asset_id"].as_str()?.to_string(),
+            price: number("price")?,
+            size: number("size")?,
+            side: event["side"].as_str().unwrap_or_default().to_string(),
+            ts_ms: event["timestamp"].as_str().and_then(|t| t.parse().ok()).unwrap_or(0),
+        })
+    }
+}
+
+/// Books built from market channel messages.
+#[derive(Debug, Default)]
+pub struct LiveBooks {
+    books: HashMap<String, Levels>,
+    trades: Vec<MarketTrade>,
+}
+
+impl LiveBooks {
+    /// Apply one message (a single event or an array of them) and return
+    /// the tokens whose books changed. Events for other channels, and
+    /// deltas for a token whose snapshot hasn't arrived, are skipped.
+    pub fn apply(&mut self, text: &str) -> Result<Vec<String>, String> {
+        let value: Value = serde_json::from_str(text).map_err(|e| format!("bad market message: {}", e))?;
+        let events = match value {
+            Value::Array(events) => events,
+            event => vec![event],
+        };
+        let mut changed = Vec::new();
+        for event in &events {
+            match event["event_type"].as_str() {
+                Some("book") => {
+                    let asset = event["asset_id"].as_str().ok_or("book event without asset_id")?;
+                    let levels = Levels {
+                        bids: parse_levels(event.get("bids").or_else(|| event.get("buys")))?,
+                        asks: parse_levels(event.get("asks").or_else(|| event.get("sells")))?,
+                    };
+                    self.books.insert(asset.to_string(), levels);
+                    changed.push(asset.to_string());
+                }
+                Some("price_change") => {
+                    // One asset with `changes`, or `price_changes` each naming its asset
+                    let changes: Vec<(&str, &Value)> = match (event.get("price_changes"), event.get("changes")) {
+                        (Some(Value::Array(list)), _) => list.iter().filter_map(|c| Some((c["asset_id"].as_str()?, c))).collect(),
+                        (_, Some(Value::Array(list))) => match event["asset_id"].as_str() {
+                            Some(asset) => list.iter().map(|c| (asset, c)).collect(),
+                            None => Vec::new(),
+                        },
+                        _ => Vec::new(),
+                    };
+                    for (asset, change) in changes {
+                        let Some(book) = self.books.get_mut(asset) else { continue };
+                        let price = fixed_field(change, "price")?;
+                        let size = fixed_field(change, "size")?;
+                        let side = match change["side"].as_str() {
+                            Some("BUY") => &mut book.bids,
+                            Some("SELL") => &mut book.asks,
+                            other => return Err(format!("price change with side {:?}", other)),
+                        };
+                        if size == 0 {
+                            side.remove(&price);
+                        } else {
+                            side.insert(price, size);
+                        }
+                        if !changed.iter().any(|c| c == asset) {
+                            changed.push(asset.to_string());
+                        }
+                    }
+                }
+                Some("last_trade_price") => {
+                    if let Some(trade) = MarketTrade::parse(event) {
+                        if self.trades.len() >= MAX_PENDING_TRADES {
+                            self.trades.remove(0);
+                        }
+                        self.trades.push(trade);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(changed)
+    }
+
+    pub fn levels(&self, token_id: &str) -> Option<&Levels> {
+        self.books.get(token_id)
+    }
+
+    /// Trades seen since the last call.
+    pub fn take_trades(&mut self) -> Vec<MarketTrade> {
+        std::mem::take(&mut self.trades)
+    }
+
+    pub fn clear(&mut self) {
+        self.books.clear();
+    }
+}
+
+fn fixed_field(level: &Value, key: &str) -> Result<u64, String> {
+    let raw = level[key].as_str().ok_or_else(|| format!("level without {}", key))?;
+    parse_fixed(raw).ok_or_else(|| format!("bad {} '{}'", key, raw))
+}
+
+fn parse_levels(levels: Option<&Value>) -> Result<BTreeMap<u64, u64>, String> {
+    let mut parsed = BTreeMap::new();
+    for level in levels.and_then(Value::as_array).into_iter().flatten() {
+        let size = fixed_field(level, "size")?;
+        if size > 0 {
+            parsed.insert(fixed_field(level, "price")?, size);
+        }
+    }
+    Ok(parsed)
+}
+
+#[derive(Default)]
+struct StreamState {
+    watched: Vec<String>,
+    connected: bool,
+    books: LiveBooks,
+}
+
+pub struct MarketStream {
+    state: Arc<Mutex<StreamState>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl MarketStream {
+    /// Start the connection thread for the market channel at `url`. Nothing
+    /// connects until there are tokens to watch.
+    pub fn spawn(url: &str) -> io::Result<Self> {
+        let state = Arc::new(Mutex::new(StreamState::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let (shared, stopped, url) = (Arc::clone(&state), Arc::clone(&stop), url.to_string());
+        thread::Builder::new().name("market-stream".to_string()).spawn(move || run(&url, &shared, &stopped))?;
+        Ok(Self { state, stop })
+    }
+
+    /// Subscribe to exactly these tokens, reconnecting if they changed.
+    pub fn watch(&self, tokens: &[String]) {
+        lock(&self.state).watched = tokens.to_vec();
+    }
+
+    /// The live touch for `token_id`, or None while it isn't streaming.
+    pub fn book(&self, token_id: &str) -> Option<OrderBook> {
+        let state = lock(&self.state);
+        if !state.connected {
+            return None;
+        }
+        state.books.levels(token_id).map(Levels::top)
+    }
+
+    /// Trades printed since the last call.
+    pub fn take_trades(&self) -> Vec<MarketTrade> {
+        lock(&self.state).books.take_trades()
+    }
+}
+
+impl Drop for MarketStream {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+fn lock(state: &Mutex<StreamState>) -> std::sync::MutexGuard<'_, StreamState> {
+    state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn run(url: &str, state: &Mutex<StreamState>, stop: &AtomicBool) {
+    let mut backoff = Duration::from_secs(1);
+    while !stop.load(Ordering::Relaxed) {
+        let watched = lock(state).watched.clone();
+        if watched.is_empty() {
+            thread::sleep(READ_TIMEOUT);
+            continue;
+        }
+
+        let started = Instant::now();
+        let result = stream_books(url, &watched, state, stop);
+        {
+            let mut state = lock(state);
+            state.connected = false;
+            state.books.clear();
+        }
+        match result {
+            // Resubscribing; not a failure
+            Ok(()) => continue,
+            Err(e) => {
+                if started.elapsed() > MAX_BACKOFF {
+                    backoff = Duration::from_secs(1);
+                }
+                println!("\n⚠️ Market stream dropped ({}); polling /book, retrying in {}s", e, backoff.as_secs());
+                thread::sleep(backoff);
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+        }
+    }
+}
+
+/// One connection: subscribe and apply messages until the watched tokens
+/// change (Ok) or the connection fails.
+fn stream_books(url: &str, watched: &[String], state: &Mutex<StreamState>, stop: &AtomicBool) -> Result<(), String> {
+    let (mut ws, _) = tungstenite::connect(url).map_err(|e| e.to_string())?;
+    set_read_timeout(&mut ws).map_err(|e| e.to_string())?;
+    let subscribe = serde_json::json!({ "assets_ids": watched, "type": "market" });
+    ws.send(Message::Text(subscribe.to_string())).map_err(|e| e.to_string())?;
+    lock(state).connected = true;
+
+    let mut last_ping = Instant::now();
+    loop {
+        if stop.load(Ordering::Relaxed) || lock(state).watched != watched {
+            let _ = ws.close(None);
+            return Ok(());
+        }
+        if last_ping.elapsed() >= PING_INTERVAL {
+            ws.send(Message::Text("PING".to_string())).map_err(|e| e.to_string())?;
+            last_ping = Instant::now();
+        }
+        match ws.read() {
+            // PONG and other non-JSON keepalives
+            Ok(Message::Text(text)) if !text.starts_with(['{', '[']) => {}
+            Ok(Message::Text(text)) => {
+                if let Err(e) = lock(state).books.apply(&text) {
+                    println!("\n⚠️ Market stream: {}", e);
+                }
+            }
+            Ok(Message::Close(_)) => return Err("closed by server".to_string()),
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e)) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => {}
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+}
+
+fn set_read_timeout(ws: &mut WebSocket<MaybeTlsStream<TcpStream>>) -> io::Result<()> {
+    let tcp = match ws.get_mut() {
+        MaybeTlsStream::Plain(s) => s,
+        MaybeTlsStream::NativeTls(s) => s.get_mut(),
+        _ => return Ok(()),
+    };
+    tcp.set_read_timeout(Some(READ_TIMEOUT))
+}
@@ -0,0 +1,118 @@
+use std::str::FromStr;
+
+use ethers::types::Address;
+
+/// Chain id and contract addresses for one deployment of the exchange.
+/// Chosen with POLY_NETWORK (polygon | amoy); each address can also be
+/// overridden individually from the environment, as can the API base URLs.
+#[derive(Debug, Clone)]
+pub struct NetworkProfile {
+    pub name: String,
+    pub chain_id: u64,
+    pub exchange: Address,
+    pub neg_risk_exchange: Address,
+    pub neg_risk_adapter: Address,
+    pub ctf: Address,
+    pub collateral: Address,
+    // ProxyWalletFactory whose `proxy()` runs calls from the caller's proxy
+    // wallet; None where Polymarket hasn't deployed one
+    pub proxy_factory: Option<Address>,
+    pub default_rpc_url: String,
+    // REST endpoints; overridable so tests can point the bot at a mock server
+    pub clob_url: String,
+    pub gamma_url: String,
+    pub data_url: String,
+    // CLOB WebSocket market channel; empty turns streaming off
+    pub ws_url: String,
+}
+
+fn addr(s: &str) -> Address {
+    Address::from_str(s).expect("valid built-in address")
+}
+
+impl NetworkProfile {
+    pub fn polygon() -> Self {
+        Self {
+            name: "polygon".to_string(),
+            chain_id: 137,
+            exchange: addr("0x4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E"),
+            neg_risk_exchange: addr("0xC5d563A36AE78145C45a50134d48A1215220f80a"),
+            neg_risk_adapter: addr("0xd91E80cF2E7be2e162c6513ceD06f1dD0dA35296"),
+            ctf: addr("0x4D97DCd97eC945f40cF65F87097ACe5EA0476045"),
+            collateral: addr("0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174"),
+            proxy_factory: Some(addr("0xaB45c5A4B0c941a2F231C04C3f49182e1A254052")),
+            default_rpc_url: "https://polygon-rpc.com".to_string(),
+            clob_url: "https://clob.polymarket.com".to_string(),
+            gamma_url: "https://gamma-api.polymarket.com".to_string(),
+            data_url: "https://data-api.polymarket.com".to_string(),
+            ws_url: "wss://ws-subscriptions-clob.polymarket.com/ws/market".to_string(),
+        }
+    }
+
+    /// Polygon Amoy testnet deployment, for end-to-end runs without real funds.
+    pub fn amoy() -> Self {
+        Self {
+            name: "amoy".to_string(),
+            chain_id: 80002,
+            exchange: addr("0xdFE02Eb6733538f8Ea35D585af8DE5958AD99E40"),
+            neg_risk_exchange: addr("0xC5d563A36AE78145C45a50134d48A1215220f80a"),
+            neg_risk_adapter: addr("0xd91E80cF2E7be2e162c6513ceD06f1dD0dA35296"),
+            ctf: addr("0x69308FB512518e39F9b16112fA8d994F4e2Bf8bB"),
+            collateral: addr("0x9c4e1703476e875070ee25b56a58b008cfb8fa78"),
+            // No published factory on Amoy; POLY_PROXY_FACTORY_ADDRESS sets one
+            proxy_factory: None,
+            default_rpc_url: "https://rpc-amoy.polygon.technology".to_string(),
+            clob_url: "https://clob.polymarket.com".to_string(),
+            gamma_url: "https://gamma-api.polymarket.com".to_string(),
+            data_url: "https://data-api.polymarket.com".to_string(),
+            ws_url: "wss://ws-subscriptions-clob.polymarket.com/ws/market".to_string(),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        match name.to_lowercase().as_str() {
+            "polygon" | "mainnet" | "137" => Ok(Self::polygon()),
+            "amoy" | "testnet" | "80002" => Ok(Self::amoy()),
+            other => Err(format!("Unknown POLY_NETWORK '{}'. Use 'polygon' or 'amoy'", other).into()),
+        }
+    }
+
+    pub fn from_env() -> Result<Self, Box<dyn std::error::Error>> {
+        let mut profile = match std::env::var("POLY_NETWORK") {
+            Ok(name) => Self::from_name(&name)?,
+            Err(_) => Self::polygon(),
+        };
+
+        if let Ok(v) = std::env::var("POLY_CHAIN_ID") {
+            profile.chain_id = v.parse().map_err(|_| format!("Invalid POLY_CHAIN_ID '{}'", v))?;
+        }
+        let overrides: [(&str, &mut Address); 5] = [
+            ("POLY_EXCHANGE_ADDRESS", &mut profile.exchange),
+            ("POLY_NEG_RISK_EXCHANGE_ADDRESS", &mut profile.neg_risk_exchange),
+            ("POLY_NEG_RISK_ADAPTER_ADDRESS", &mut profile.neg_risk_adapter),
+            ("POLY_CTF_ADDRESS", &mut profile.ctf),
+            ("POLY_COLLATERAL_ADDRESS", &mut profile.collateral),
+        ];
+        for (var, slot) in overrides {
+            if let Ok(v) = std::env::var(var) {
+                *slot = Address::from_str(&v).map_err(|_| format!("Invalid {} '{}'", var, v))?;
+            }
+        }
+        if let Ok(v) = std::env::var("POLY_PROXY_FACTORY_ADDRESS") {
+            profile.proxy_factory = Some(Address::from_str(&v).map_err(|_| format!("Invalid POLY_PROXY_FACTORY_ADDRESS '{}'", v))?);
+        }
+        let url_overrides: [(&str, &mut String); 4] = [
+            ("POLY_CLOB_URL", &mut profile.clob_url),
+            ("POLY_GAMMA_URL", &mut profile.gamma_url),
+            ("POLY_DATA_URL", &mut profile.data_url),
+            ("POLY_WS_URL", &mut profile.ws_url),
+        ];
+        for (var, slot) in url_overrides {
+            if let Ok(v) = std::env::var(var) {
+                *slot = v.trim_end_matches('/').to_string();
+            }
+        }
+
+        Ok(profile)
+    }
+}
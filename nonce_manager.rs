@@ -0,0 +1,96 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use ethers::types::{Address, H256, U256};
+use serde_json::json;
+
+use crate::chain::RpcClient;
+
+#[derive(Debug, Clone)]
+pub struct PendingTx {
+    pub label: String,
+    // Every hash broadcast at this nonce (original plus replacements)
+    pub hashes: Vec<H256>,
+}
+
+#[derive(Default)]
+struct NonceState {
+    next: Option<U256>,
+    pending: BTreeMap<U256, PendingTx>,
+}
+
+/// Hands out nonces for one EOA so queued operations (approve + redeem +
+/// merge) get consecutive nonces instead of all reading the same "pending"
+/// count, and keeps track of which nonces are still in flight.
+pub struct NonceManager {
+    address: Address,
+    state: Mutex<NonceState>,
+}
+
+impl NonceManager {
+    pub fn new(address: Address) -> Self {
+        Self { address, state: Mutex::new(NonceState::default()) }
+    }
+
+    fn chain_pending_nonce(&self, rpc: &RpcClient) -> Result<U256, Box<dyn std::error::Error>> {
+        rpc.call("eth_getTransactionCount", json!([format!("{:?}", self.address), "pending"]))
+    }
+
+    /// Reserve the next nonce. Never goes below what the node reports, so
+    /// transactions sent from elsewhere (wallet UI) are accounted for.
+    pub fn reserve(&self, rpc: &RpcClient, label: &str) -> Result<U256, Box<dyn std::error::Error>> {
+        let on_chain = self.chain_pending_nonce(rpc)?;
+        let mut state = self.state.lock().map_err(|_| "nonce manager poisoned")?;
+
+        let nonce = state.next.map_or(on_chain, |local| local.max(on_chain));
+        state.next = Some(nonce + 1);
+        state.pending.insert(nonce, PendingTx { label: label.to_string(), hashes: Vec::new() });
+        Ok(nonce)
+    }
+
+    pub fn record_broadcast(&self, nonce: U256, tx_hash: H256) {
+        if let Ok(mut state) = self.state.lock() {
+            if let Some(pending) = state.pending.get_mut(&nonce) {
+                pending.hashes.push(tx_hash);
+            }
+        }
+    }
+
+    /// The transaction at `nonce` was mined (or replaced by one that was).
+    pub fn confirm(&self, nonce: U256) {
+        if let Ok(mut state) = self.state.lock() {
+            state.pending.remove(&nonce);
+        }
+    }
+
+    /// Nothing at `nonce` ever reached the mempool. If it was the most recent
+    /// reservation the nonce is handed back; otherwise later nonces would be
+    /// stuck behind the gap, so the local counter is dropped and re-read.
+    pub fn release(&self, nonce: U256) {
+        if let Ok(mut state) = self.state.lock() {
+            let never_broadcast = state.pending.get(&nonce).is_none_or(|p| p.hashes.is_empty());
+            if !never_broadcast {
+                return;
+            }
+            state.pending.remove(&nonce);
+            if state.next == Some(nonce + 1) {
+                state.next = Some(nonce);
+            } else {
+                state.next = None;
+            }
+        }
+    }
+
+    /// Forget local state after the node rejects a nonce as too low.
+    pub fn resync(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.next = None;
+        }
+    }
+
+    pub fn in_flight(&self) -> Vec<(U256, PendingTx)> {
+        self.state.lock()
+            .map(|state| state.pending.iter().map(|(n, p)| (*n, p.clone())).collect())
+            .unwrap_or_default()
+    }
+}
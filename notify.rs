@@ -0,0 +1,300 @@
+//! Outbound notifications. Every channel (stdout, file, Telegram, Discord,
+//! webhook, a custom one) implements `Notifier`; the `Router` hands each
+//! notification to the channels whose minimum severity it meets, so a phone
+//! only buzzes for halts while a log file gets everything. Channels that
+//! filter further do it on the notification's `Event`.
+
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::thread::{self, JoinHandle};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Critical,
+}
+
+impl Severity {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "info" => Some(Self::Info),
+            "warning" | "warn" => Some(Self::Warning),
+            "critical" | "crit" => Some(Self::Critical),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Critical => "critical",
+        }
+    }
+
+    /// Leads chat messages.
+    pub fn marker(&self) -> &'static str {
+        match self {
+            Self::Info => "ℹ️",
+            Self::Warning => "⚠️",
+            Self::Critical => "🚨",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// What a notification is about. On the wire, and in the event lists
+/// channels subscribe to, it is the snake_case name from `as_str`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Event {
+    EntryFilled,
+    Exit,
+    // Nothing raises this yet: there is no stop-loss loop, only rules
+    StopLossTriggered,
+    Abort,
+    Liquidation,
+    // Cycle left out by the schedule
+    MarketSkipped,
+    // Market finished some other way: closed, timed out, halted, failed
+    MarketDone,
+    RuleFired,
+    PriceAlert,
+    Halted,
+    Resumed,
+    Mergeable,
+    Error,
+    // The trading loop stopped on an error
+    Fatal,
+    Shutdown,
+    // End-of-day summary of the trade journal
+    DailyReport,
+}
+
+impl Event {
+    pub const ALL: [Event; 16] = [
+        Self::EntryFilled, Self::Exit, Self::StopLossTriggered, Self::Abort, Self::Liquidation,
+        Self::MarketSkipped, Self::MarketDone, Self::RuleFired, Self::PriceAlert, Self::Halted,
+        Self::Resumed, Self::Mergeable, Self::Error, Self::Fatal, Self::Shutdown, Self::DailyReport,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::EntryFilled => "entry",
+            Self::Exit => "exit",
+            Self::StopLossTriggered => "stop_loss",
+            Self::Abort => "abort",
+            Self::Liquidation => "liquidation",
+            Self::MarketSkipped => "skipped",
+            Self::MarketDone => "market",
+            Self::RuleFired => "rule",
+            Self::PriceAlert => "alert",
+            Self::Halted => "halt",
+            Self::Resumed => "resume",
+            Self::Mergeable => "merge",
+            Self::Error => "error",
+            Self::Fatal => "fatal",
+            Self::Shutdown => "shutdown",
+            Self::DailyReport => "report",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|e| e.as_str() == s.trim())
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A subscription list: event names from `Event::as_str`, or "*" for all.
+/// Unknown names are errors so a typo doesn't silently mute a channel.
+pub fn parse_events(list: &str) -> Result<Vec<String>, String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|e| !e.is_empty())
+        .map(|e| match e == "*" || Event::parse(e).is_some() {
+            true => Ok(e.to_string()),
+            false => Err(format!("unknown event '{}' (one of *, {})", e, Event::ALL.map(|e| e.as_str()).join(", "))),
+        })
+        .collect()
+}
+
+/// Whether `events` (as from `parse_events`) takes `notification`.
+pub fn subscribed(events: &[String], notification: &Notification) -> bool {
+    events.iter().any(|e| e == "*" || notification.event.as_deref() == Some(e.as_str()))
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Notification {
+    pub severity: Severity,
+    // What happened, for channels that filter on it; see `Event`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event: Option<String>,
+    pub title: String,
+    pub body: String,
+    // Unix seconds
+    pub ts: u64,
+}
+
+impl Notification {
+    pub fn new(severity: Severity, title: impl Into<String>, body: impl Into<String>, ts: u64) -> Self {
+        Self { severity, event: None, title: title.into(), body: body.into(), ts }
+    }
+
+    pub fn with_event(mut self, event: &str) -> Self {
+        self.event = Some(event.to_string());
+        self
+    }
+
+    /// Title and body on one line, for text channels.
+    pub fn line(&self) -> String {
+        if self.body.is_empty() {
+            self.title.clone()
+        } else {
+            format!("{}: {}", self.title, self.body)
+        }
+    }
+}
+
+/// One delivery channel. Called on the bot's thread, so keep `send` quick
+/// or hand off to a worker.
+pub trait Notifier {
+    fn name(&self) -> &str;
+    fn send(&self, notification: &Notification) -> Result<(), Box<dyn std::error::Error>>;
+}
+
+struct Route {
+    min: Severity,
+    notifier: Box<dyn Notifier>,
+}
+
+#[derive(Default)]
+pub struct Router {
+    routes: Vec<Route>,
+}
+
+impl Router {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Deliver notifications at `min` severity and above to `notifier`.
+    pub fn add(&mut self, min: Severity, notifier: Box<dyn Notifier>) {
+        self.routes.push(Route { min, notifier });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    /// Channel names and their minimum severity.
+    pub fn channels(&self) -> Vec<(String, Severity)> {
+        self.routes.iter().map(|r| (r.notifier.name().to_string(), r.min)).collect()
+    }
+
+    /// Send to every matching channel. A failing channel doesn't stop the
+    /// others; failures come back as (channel, error) for the caller to log.
+    pub fn notify(&self, notification: &Notification) -> Vec<(String, String)> {
+        self.routes.iter()
+            .filter(|r| notification.severity >= r.min)
+            .filter_map(|r| r.notifier.send(notification).err().map(|e| (r.notifier.name().to_string(), e.to_string())))
+            .collect()
+    }
+}
+
+/// Appends each notification as a JSON line; handy for tailing and as the
+/// reference channel.
+pub struct JsonLinesNotifier {
+    path: String,
+}
+
+impl JsonLinesNotifier {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl Notifier for JsonLinesNotifier {
+    fn name(&self) -> &str {
+        "file"
+    }
+
+    fn send(&self, notification: &Notification) -> Result<(), Box<dyn std::error::Error>> {
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(notification)?)?;
+        Ok(())
+    }
+}
+
+/// Prints each notification; for running under a supervisor that collects
+/// stdout, or to see what the other channels would get.
+pub struct StdoutNotifier;
+
+impl Notifier for StdoutNotifier {
+    fn name(&self) -> &str {
+        "stdout"
+    }
+
+    fn send(&self, notification: &Notification) -> Result<(), Box<dyn std::error::Error>> {
+        println!("\n🔔 [{}] {}", notification.severity, notification.line());
+        Ok(())
+    }
+}
+
+// Messages an outbox holds for its worker before new ones are dropped
+const OUTBOX_LEN: usize = 64;
+
+/// Delivery off the bot's thread for chat channels: `push` only queues, a
+/// worker sends, and a failure is printed rather than returned. A full
+/// queue drops the message. Dropping the outbox delivers what is queued.
+pub struct Outbox {
+    queue: Option<SyncSender<String>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl Outbox {
+    pub fn spawn(name: &str, deliver: impl Fn(&str) -> Result<(), String> + Send + 'static) -> io::Result<Self> {
+        let (queue, messages) = mpsc::sync_channel::<String>(OUTBOX_LEN);
+        let channel = name.to_string();
+        let worker = thread::Builder::new().name(name.to_string()).spawn(move || {
+            for message in messages {
+                if let Err(e) = deliver(&message) {
+                    println!("\n   ⚠️ {} notification failed: {}", channel, e);
+                }
+            }
+        })?;
+        Ok(Self { queue: Some(queue), worker: Some(worker) })
+    }
+
+    pub fn push(&self, message: String) -> Result<(), String> {
+        let Some(queue) = &self.queue else { return Err("stopped".to_string()) };
+        match queue.try_send(message) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(_)) => Err(format!("{} messages queued; dropped this one", OUTBOX_LEN)),
+            Err(TrySendError::Disconnected(_)) => Err("delivery thread stopped".to_string()),
+        }
+    }
+}
+
+impl Drop for Outbox {
+    fn drop(&mut self) {
+        self.queue = None;
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
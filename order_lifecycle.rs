@@ -0,0 +1,309 @@
+//! Each order's lifecycle as an explicit state machine:
+//!
+//!   created → signed → submitted → live → partially_filled → filled
+//!                          │         │            │
+//!                          │         └────────────┴──→ canceled / expired
+//!                          └──→ rejected (or straight to any later state
+//!                               once an ambiguous POST is reconciled)
+//!
+//! Every transition is appended to a JSON-lines journal with its timestamp
+//! and replayed on load, so orders still in flight when the bot stopped are
+//! known after a restart (`OrderJournal::open`) and can be reconciled
+//! against the exchange instead of forgotten.
+//!
+//! Orders are keyed by their client id (the EIP-712 order hash); the id the
+//! exchange assigns is recorded once it's accepted, and either finds the order.
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::responses::OrderProgress;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderState {
+    Created,
+    Signed,
+    Submitted,
+    Live,
+    PartiallyFilled,
+    Filled,
+    Canceled,
+    Expired,
+    Rejected,
+}
+
+impl OrderState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Created => "created",
+            Self::Signed => "signed",
+            Self::Submitted => "submitted",
+            Self::Live => "live",
+            Self::PartiallyFilled => "partially_filled",
+            Self::Filled => "filled",
+            Self::Canceled => "canceled",
+            Self::Expired => "expired",
+            Self::Rejected => "rejected",
+        }
+    }
+
+    /// Nothing more can happen to the order on the exchange.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, Self::Filled | Self::Canceled | Self::Expired | Self::Rejected)
+    }
+
+    /// Whether `self → to` is a legal move. Staying put is always allowed,
+    /// so fill and price updates (including reorg rollbacks) can be recorded.
+    pub fn can_move_to(&self, to: OrderState) -> bool {
+        use OrderState::*;
+        *self == to || matches!(
+            (self, to),
+            (Created, Signed | Rejected | Canceled)
+                | (Signed, Submitted | Rejected | Canceled)
+                | (Submitted, Live | PartiallyFilled | Filled | Canceled | Expired | Rejected)
+                | (Live, PartiallyFilled | Filled | Canceled | Expired)
+                | (PartiallyFilled, Filled | Canceled | Expired)
+        )
+    }
+
+    /// Where an accepted order stands given the exchange's latest report.
+    pub fn from_progress(progress: &OrderProgress) -> Self {
+        if progress.is_filled() {
+            return Self::Filled;
+        }
+        match progress.status.as_str() {
+            "EXPIRED" => Self::Expired,
+            // Killed or canceled, with or without a partial fill
+            _ if progress.is_closed() => Self::Canceled,
+            _ if progress.filled_size > 0.0 => Self::PartiallyFilled,
+            _ => Self::Live,
+        }
+    }
+}
+
+/// What was asked for; recorded with `created`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderSpec {
+    pub token_id: String,
+    // BUY or SELL
+    pub side: String,
+    pub order_type: String,
+    pub price: f64,
+    pub size: f64,
+    // Needed to rebuild the submission for reconciliation
+    #[serde(default)]
+    pub salt: String,
+}
+
+/// One line of the journal.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderEvent {
+    pub ts: u64,
+    pub key: String,
+    pub state: OrderState,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spec: Option<OrderSpec>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order_id: Option<String>,
+    #[serde(default)]
+    pub filled: f64,
+    #[serde(default)]
+    pub avg_price: f64,
+    #[serde(default)]
+    pub fee: f64,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub note: String,
+}
+
+impl OrderEvent {
+    pub fn created(ts: u64, key: &str, spec: OrderSpec) -> Self {
+        Self { spec: Some(spec), ..Self::moved(ts, key, OrderState::Created, "") }
+    }
+
+    /// A move with no fill information.
+    pub fn moved(ts: u64, key: &str, state: OrderState, note: &str) -> Self {
+        Self {
+            ts,
+            key: key.to_string(),
+            state,
+            spec: None,
+            order_id: None,
+            filled: 0.0,
+            avg_price: 0.0,
+            fee: 0.0,
+            note: note.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Step {
+    pub state: OrderState,
+    pub ts: u64,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub note: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OrderRecord {
+    pub key: String,
+    pub spec: OrderSpec,
+    pub order_id: Option<String>,
+    pub state: OrderState,
+    pub filled: f64,
+    pub avg_price: f64,
+    pub fee: f64,
+    // Every state entered, oldest first
+    pub history: Vec<Step>,
+}
+
+impl OrderRecord {
+    /// The id to query the exchange with.
+    pub fn exchange_id(&self) -> &str {
+        self.order_id.as_deref().unwrap_or(&self.key)
+    }
+
+    pub fn created_at(&self) -> u64 {
+        self.history.first().map(|s| s.ts).unwrap_or(0)
+    }
+
+    pub fn updated_at(&self) -> u64 {
+        self.history.last().map(|s| s.ts).unwrap_or(0)
+    }
+
+    /// When the order entered `state`, if it did.
+    pub fn entered(&self, state: OrderState) -> Option<u64> {
+        self.history.iter().find(|s| s.state == state).map(|s| s.ts)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct OrderJournal {
+    orders: Vec<OrderRecord>,
+    // Client key and exchange id → index into `orders`
+    index: HashMap<String, usize>,
+}
+
+impl OrderJournal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replay a journal; a missing file is an empty one.
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut journal = Self::new();
+        if !Path::new(path).exists() {
+            return Ok(journal);
+        }
+        for (n, line) in BufReader::new(File::open(path)?).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let event: OrderEvent = serde_json::from_str(&line).map_err(|e| format!("{} line {}: {}", path, n + 1, e))?;
+            journal.apply(event).map_err(|e| format!("{} line {}: {}", path, n + 1, e))?;
+        }
+        Ok(journal)
+    }
+
+    /// Append one event to the journal at `path`.
+    pub fn append(path: &str, event: &OrderEvent) -> Result<(), Box<dyn std::error::Error>> {
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", serde_json::to_string(event)?)?;
+        Ok(())
+    }
+
+    /// Record `event`, refusing moves the state machine doesn't allow.
+    pub fn apply(&mut self, event: OrderEvent) -> Result<(), String> {
+        if event.state == OrderState::Created {
+            if self.index.contains_key(&event.key) {
+                return Err(format!("order {} created twice", event.key));
+            }
+            let spec = event.spec.ok_or_else(|| format!("order {} created without its details", event.key))?;
+            self.index.insert(event.key.clone(), self.orders.len());
+            self.orders.push(OrderRecord {
+                key: event.key,
+                spec,
+                order_id: None,
+                state: OrderState::Created,
+                filled: 0.0,
+                avg_price: 0.0,
+                fee: 0.0,
+                history: vec![Step { state: OrderState::Created, ts: event.ts, note: event.note }],
+            });
+            return Ok(());
+        }
+
+        let i = *self.index.get(&event.key).ok_or_else(|| format!("unknown order {}", event.key))?;
+        let order = &mut self.orders[i];
+        if !order.state.can_move_to(event.state) {
+            return Err(format!("order {} cannot go from {} to {}", order.key, order.state.as_str(), event.state.as_str()));
+        }
+        if let Some(id) = event.order_id {
+            self.index.insert(id.clone(), i);
+            order.order_id = Some(id);
+        }
+        if event.state != order.state || !event.note.is_empty() {
+            order.history.push(Step { state: event.state, ts: event.ts, note: event.note });
+        }
+        order.state = event.state;
+        order.filled = event.filled;
+        order.avg_price = event.avg_price;
+        order.fee = event.fee;
+        Ok(())
+    }
+
+    /// By client key or exchange id.
+    pub fn get(&self, id: &str) -> Option<&OrderRecord> {
+        self.index.get(id).map(|i| &self.orders[*i])
+    }
+
+    /// Every order, oldest first.
+    pub fn orders(&self) -> &[OrderRecord] {
+        &self.orders
+    }
+
+    /// Orders that can still change on the exchange.
+    pub fn open(&self) -> Vec<&OrderRecord> {
+        self.orders.iter().filter(|o| !o.state.is_terminal()).collect()
+    }
+
+    /// A move of `id` to `state` keeping its fill figures, or None when the
+    /// order is unknown or the move isn't allowed.
+    pub fn transition(&self, ts: u64, id: &str, state: OrderState, note: &str) -> Option<OrderEvent> {
+        let order = self.get(id).filter(|o| o.state.can_move_to(state))?;
+        Some(OrderEvent {
+            filled: order.filled,
+            avg_price: order.avg_price,
+            fee: order.fee,
+            ..OrderEvent::moved(ts, &order.key, state, note)
+        })
+    }
+
+    /// The event that brings `id` in line with `progress`, or None when
+    /// nothing changed. Fill figures never go below what's recorded unless
+    /// `progress` says so explicitly (a reorg rollback).
+    pub fn progress_event(&self, ts: u64, id: &str, progress: &OrderProgress) -> Option<OrderEvent> {
+        let order = self.get(id)?;
+        // A report that lags a terminal state still carries fill figures
+        let state = Some(OrderState::from_progress(progress)).filter(|s| order.state.can_move_to(*s)).unwrap_or(order.state);
+        let unchanged = state == order.state
+            && (progress.filled_size - order.filled).abs() < 1e-9
+            && (progress.avg_price - order.avg_price).abs() < 1e-9
+            && (progress.fee - order.fee).abs() < 1e-9;
+        if unchanged {
+            return None;
+        }
+        Some(OrderEvent {
+            filled: progress.filled_size,
+            avg_price: progress.avg_price,
+            fee: progress.fee,
+            ..OrderEvent::moved(ts, &order.key, state, "")
+        })
+    }
+}
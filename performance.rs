@@ -0,0 +1,113 @@
+//! Performance statistics over closed trades, overall and broken down by
+//! strategy and asset, so a parameter change can be judged on more than
+//! total PnL:
+//!   - win rate, average win and average loss
+//!   - profit factor: gross wins over gross losses
+//!   - max drawdown: the worst peak-to-trough fall of cumulative PnL
+//!   - Sharpe-like ratio: mean over standard deviation of per-trade returns
+//!     (PnL / cost), not annualised; markets aren't evenly spaced in time
+//!
+//! Trades are ordered by close time before anything path-dependent is
+//! computed.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TradeResult {
+    pub strategy: String,
+    pub asset: String,
+    // Unix seconds the position was closed
+    pub ts: u64,
+    pub pnl: f64,
+    // USDC paid to open
+    pub cost: f64,
+}
+
+/// The asset a market slug trades: `eth-updown-15m-1760000400` → `eth`.
+pub fn asset_of(slug: &str) -> String {
+    slug.split('-').next().unwrap_or(slug).to_ascii_lowercase()
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PerformanceStats {
+    pub trades: usize,
+    pub wins: usize,
+    pub losses: usize,
+    pub win_rate: f64,
+    pub total_pnl: f64,
+    pub avg_win: f64,
+    // Mean of losing trades, so zero or negative
+    pub avg_loss: f64,
+    // None without any losses
+    pub profit_factor: Option<f64>,
+    pub max_drawdown: f64,
+    // None under two trades or with no variation in returns
+    pub sharpe: Option<f64>,
+}
+
+impl PerformanceStats {
+    pub fn compute(results: &[TradeResult]) -> Self {
+        let mut sorted: Vec<&TradeResult> = results.iter().collect();
+        sorted.sort_by_key(|r| r.ts);
+
+        let wins: Vec<f64> = sorted.iter().map(|r| r.pnl).filter(|p| *p > 0.0).collect();
+        let losses: Vec<f64> = sorted.iter().map(|r| r.pnl).filter(|p| *p < 0.0).collect();
+        let mean = |v: &[f64]| if v.is_empty() { 0.0 } else { v.iter().sum::<f64>() / v.len() as f64 };
+        let gross_loss = -losses.iter().sum::<f64>();
+
+        let mut cumulative = 0.0_f64;
+        let mut peak = 0.0_f64;
+        let mut max_drawdown = 0.0_f64;
+        for r in &sorted {
+            cumulative += r.pnl;
+            peak = peak.max(cumulative);
+            max_drawdown = max_drawdown.max(peak - cumulative);
+        }
+
+        let returns: Vec<f64> = sorted.iter().filter(|r| r.cost > 0.0).map(|r| r.pnl / r.cost).collect();
+        let sharpe = (returns.len() >= 2).then(|| {
+            let m = mean(&returns);
+            let variance = returns.iter().map(|r| (r - m).powi(2)).sum::<f64>() / (returns.len() - 1) as f64;
+            (m, variance.sqrt())
+        }).filter(|(_, sd)| *sd > 1e-12).map(|(m, sd)| m / sd);
+
+        Self {
+            trades: sorted.len(),
+            wins: wins.len(),
+            losses: losses.len(),
+            win_rate: if sorted.is_empty() { 0.0 } else { wins.len() as f64 / sorted.len() as f64 },
+            total_pnl: cumulative,
+            avg_win: mean(&wins),
+            avg_loss: mean(&losses),
+            profit_factor: (gross_loss > 0.0).then(|| wins.iter().sum::<f64>() / gross_loss),
+            max_drawdown,
+            sharpe,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PerformanceReport {
+    pub overall: PerformanceStats,
+    pub by_strategy: BTreeMap<String, PerformanceStats>,
+    pub by_asset: BTreeMap<String, PerformanceStats>,
+}
+
+impl PerformanceReport {
+    pub fn new(results: &[TradeResult]) -> Self {
+        let group = |key: fn(&TradeResult) -> &str| {
+            let mut groups: BTreeMap<String, Vec<TradeResult>> = BTreeMap::new();
+            for r in results {
+                groups.entry(key(r).to_string()).or_default().push(r.clone());
+            }
+            groups.into_iter().map(|(k, v)| (k, PerformanceStats::compute(&v))).collect()
+        };
+        Self {
+            overall: PerformanceStats::compute(results),
+            by_strategy: group(|r| &r.strategy),
+            by_asset: group(|r| &r.asset),
+        }
+    }
+}
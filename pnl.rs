@@ -0,0 +1,134 @@
+//! Live profit and loss of this session's positions. Fills build each
+//! token's cost basis (fees included, relieved at average cost), open
+//! shares are marked to the best bid, and profit is realized as shares are
+//! sold or resolve.
+//!
+//! The ledger keeps the books across restarts; this is the running figure
+//! for the console, the trade log and notifications.
+
+use std::collections::HashMap;
+
+// Share counts below this are rounding, not a position
+const EPSILON: f64 = 1e-6;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Holding {
+    pub shares: f64,
+    // USDC paid for `shares`, fees included
+    pub cost: f64,
+    // Best bid last seen, if any
+    pub mark: Option<f64>,
+}
+
+impl Holding {
+    pub fn unit_cost(&self) -> f64 {
+        if self.shares.abs() < EPSILON { 0.0 } else { self.cost / self.shares }
+    }
+
+    /// Value at the mark less cost; zero until a bid has been seen.
+    pub fn unrealized(&self) -> f64 {
+        self.mark.map(|bid| bid * self.shares - self.cost).unwrap_or(0.0)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PnlTracker {
+    holdings: HashMap<String, Holding>,
+    // Realized per token, until `take_realized` collects it for a trade row
+    pending: HashMap<String, f64>,
+    realized: f64,
+}
+
+impl PnlTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take over shares bought before this session at a known cost.
+    pub fn open(&mut self, token_id: &str, shares: f64, cost: f64) {
+        let holding = self.holdings.entry(token_id.to_string()).or_default();
+        holding.shares += shares;
+        holding.cost += cost;
+    }
+
+    /// Apply a fill (negative `shares` reverses one) and return the profit it
+    /// realized: buys add to the basis, sells relieve it at average cost.
+    pub fn fill(&mut self, token_id: &str, buy: bool, shares: f64, price: f64, fee: f64) -> f64 {
+        let holding = self.holdings.entry(token_id.to_string()).or_default();
+        if buy {
+            holding.shares += shares;
+            holding.cost += shares * price + fee;
+            self.settle(token_id);
+            return 0.0;
+        }
+        let sold = shares.min(holding.shares);
+        let basis = sold * holding.unit_cost();
+        holding.shares -= sold;
+        holding.cost -= basis;
+        let realized = sold * price - fee - basis;
+        self.realize(token_id, realized);
+        realized
+    }
+
+    /// The market resolved: every held share of `token_id` pays
+    /// `payout_per_share` ($1 or $0). Returns the profit realized.
+    pub fn resolve(&mut self, token_id: &str, payout_per_share: f64) -> f64 {
+        let Some(holding) = self.holdings.get_mut(token_id) else { return 0.0 };
+        let realized = holding.shares * payout_per_share - holding.cost;
+        holding.shares = 0.0;
+        holding.cost = 0.0;
+        self.realize(token_id, realized);
+        realized
+    }
+
+    /// Mark `token_id` to `bid`; ignored for tokens not held.
+    pub fn mark(&mut self, token_id: &str, bid: f64) {
+        if let Some(holding) = self.holdings.get_mut(token_id) {
+            holding.mark = Some(bid);
+        }
+    }
+
+    fn realize(&mut self, token_id: &str, amount: f64) {
+        self.realized += amount;
+        *self.pending.entry(token_id.to_string()).or_insert(0.0) += amount;
+        self.settle(token_id);
+    }
+
+    // A position sold down to nothing leaves no basis behind
+    fn settle(&mut self, token_id: &str) {
+        if self.holdings.get(token_id).is_some_and(|h| h.shares.abs() < EPSILON) {
+            self.holdings.remove(token_id);
+        }
+    }
+
+    pub fn holding(&self, token_id: &str) -> Option<&Holding> {
+        self.holdings.get(token_id)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.holdings.is_empty() && self.realized == 0.0
+    }
+
+    pub fn realized(&self) -> f64 {
+        self.realized
+    }
+
+    pub fn unrealized(&self) -> f64 {
+        self.holdings.values().map(Holding::unrealized).sum()
+    }
+
+    /// Profit realized on `token_id` since the last call, for the trade row
+    /// that closes it.
+    pub fn take_realized(&mut self, token_id: &str) -> f64 {
+        self.pending.remove(token_id).unwrap_or(0.0)
+    }
+
+    /// "PnL $+0.12 (unrealized $-0.05)" for the console and notifications.
+    pub fn summary(&self) -> String {
+        if self.holdings.is_empty() {
+            format!("PnL ${:+.2}", self.realized)
+        } else {
+            format!("PnL ${:+.2} (unrealized ${:+.2})", self.realized, self.unrealized())
+        }
+    }
+}
@@ -0,0 +1,57 @@
+//! Cap on positions held at once across every market the process trades.
+//! Each market thread runs its own bot; they share one `PositionLimit`, and
+//! an entry goes ahead only if it gets a slot. A slot is held until the
+//! market closes, since positions ride to resolution, or until released
+//! because the entry didn't fill.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+#[derive(Debug, Default)]
+pub struct PositionLimit {
+    // 0 means no limit
+    max: usize,
+    // Market slug to the time its slot frees up
+    open: Mutex<HashMap<String, u64>>,
+}
+
+impl PositionLimit {
+    pub fn new(max: usize) -> Self {
+        Self { max, open: Mutex::new(HashMap::new()) }
+    }
+
+    pub fn max(&self) -> usize {
+        self.max
+    }
+
+    /// Take a slot for `market` until `closes_at`. Err carries the number
+    /// of positions open when the limit is reached. A market that already
+    /// holds a slot keeps it.
+    pub fn try_open(&self, market: &str, closes_at: u64, now: u64) -> Result<(), usize> {
+        let mut open = self.lock();
+        open.retain(|_, until| *until > now);
+        if self.max > 0 && open.len() >= self.max && !open.contains_key(market) {
+            return Err(open.len());
+        }
+        open.insert(market.to_string(), closes_at);
+        Ok(())
+    }
+
+    /// Count a position the limit had no say in, such as one found at
+    /// startup, even past the limit.
+    pub fn hold(&self, market: &str, closes_at: u64) {
+        self.lock().insert(market.to_string(), closes_at);
+    }
+
+    pub fn release(&self, market: &str) {
+        self.lock().remove(market);
+    }
+
+    pub fn open(&self, now: u64) -> usize {
+        self.lock().values().filter(|until| **until > now).count()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, u64>> {
+        self.open.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -0,0 +1,141 @@
+//! Data API position queries, shared by redemption, the `positions`
+//! command and `export positions`, plus the activity history behind
+//! `export lots`.
+
+use std::io::Write;
+
+use ethers::types::Address;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::tax_lots::{self, Activity};
+
+/// A position as reported by the data API `/positions` endpoint.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DataPosition {
+    #[serde(default)]
+    pub asset: String,
+    #[serde(rename = "conditionId", default)]
+    pub condition_id: String,
+    #[serde(default)]
+    pub size: f64,
+    #[serde(default)]
+    pub redeemable: bool,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub outcome: String,
+    #[serde(rename = "outcomeIndex", default)]
+    pub outcome_index: u32,
+    #[serde(rename = "negativeRisk", default)]
+    pub negative_risk: bool,
+    #[serde(rename = "avgPrice", default)]
+    pub avg_price: f64,
+    #[serde(rename = "curPrice", default)]
+    pub cur_price: f64,
+    #[serde(rename = "currentValue", default)]
+    pub current_value: f64,
+}
+
+pub fn parse_positions(bytes: &[u8]) -> Result<Vec<DataPosition>, serde_json::Error> {
+    serde_json::from_slice(bytes)
+}
+
+pub fn fetch_positions(client: &Client, data_url: &str, user: Address, redeemable_only: bool) -> Result<Vec<DataPosition>, Box<dyn std::error::Error>> {
+    let mut positions = Vec::new();
+    let page_size = 500;
+    let mut offset = 0;
+
+    loop {
+        let mut url = format!("{}/positions?user={:?}&limit={}&offset={}&sizeThreshold=0", data_url, user, page_size, offset);
+        if redeemable_only {
+            url.push_str("&redeemable=true");
+        }
+        let page = parse_positions(&client.get(&url).send()?.error_for_status()?.bytes()?)?;
+        let done = page.len() < page_size;
+        positions.extend(page);
+        if done {
+            return Ok(positions);
+        }
+        offset += page_size;
+    }
+}
+
+/// Every trade and redemption of `user`, in API order; `FifoBook` sorts them.
+pub fn fetch_activity(client: &Client, data_url: &str, user: Address) -> Result<Vec<Activity>, Box<dyn std::error::Error>> {
+    let mut activity = Vec::new();
+    let page_size = 500;
+    let mut offset = 0;
+
+    loop {
+        let url = format!("{}/activity?user={:?}&limit={}&offset={}", data_url, user, page_size, offset);
+        let page = tax_lots::parse_activity(&client.get(&url).send()?.error_for_status()?.bytes()?)?;
+        let done = page.len() < page_size;
+        activity.extend(page);
+        if done {
+            return Ok(activity);
+        }
+        offset += page_size;
+    }
+}
+
+/// One line of `export positions`: the data API's view next to the chain's,
+/// marked at the CLOB midpoint where the market still has a book.
+#[derive(Debug, Clone, Serialize)]
+pub struct PositionExport {
+    pub token_id: String,
+    pub condition_id: String,
+    pub title: String,
+    pub outcome: String,
+    pub size: f64,
+    // None when the chain couldn't be read
+    pub onchain_size: Option<f64>,
+    pub mark: f64,
+    // "midpoint" or "data_api" (resolved/bookless markets)
+    pub mark_source: String,
+    pub value: f64,
+    pub redeemable: bool,
+}
+
+impl PositionExport {
+    pub fn new(position: &DataPosition, onchain_size: Option<f64>, midpoint: Option<f64>) -> Self {
+        let (mark, mark_source) = match midpoint {
+            Some(mid) => (mid, "midpoint"),
+            None => (position.cur_price, "data_api"),
+        };
+        // The chain is authoritative for how many shares we hold
+        let size = onchain_size.unwrap_or(position.size);
+        Self {
+            token_id: position.asset.clone(),
+            condition_id: position.condition_id.clone(),
+            title: position.title.clone(),
+            outcome: position.outcome.clone(),
+            size: position.size,
+            onchain_size,
+            mark,
+            mark_source: mark_source.to_string(),
+            value: size * mark,
+            redeemable: position.redeemable,
+        }
+    }
+
+    /// Data API and chain disagree by more than rounding.
+    pub fn is_mismatched(&self) -> bool {
+        self.onchain_size.is_some_and(|onchain| (onchain - self.size).abs() > 1e-6)
+    }
+}
+
+const EXPORT_COLUMNS: [&str; 10] = [
+    "token_id", "condition_id", "title", "outcome", "size", "onchain_size", "mark", "mark_source", "value", "redeemable",
+];
+
+/// CSV with a header row, also when there are no positions.
+pub fn write_csv<W: Write>(rows: &[PositionExport], out: W) -> Result<(), Box<dyn std::error::Error>> {
+    let mut writer = csv::WriterBuilder::new().has_headers(false).from_writer(out);
+    writer.write_record(EXPORT_COLUMNS)?;
+    for row in rows {
+        writer.serialize(row)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
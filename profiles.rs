@@ -0,0 +1,97 @@
+//! Account profiles: several wallets traded by one process. BOT_PROFILES
+//! lists the names; each profile reads its settings from `<NAME>_<VAR>`
+//! (e.g. ALICE_PRIVATE_KEY, ALICE_POLY_API_KEY), falling back to the plain
+//! variable for anything shared, and keeps its logs and state files in its
+//! own directory under BOT_PROFILE_DIR (default `profiles/`).
+//!
+//! Without BOT_PROFILES there is a single unnamed profile that reads the
+//! plain variables and writes to the working directory, as before.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    pub name: String,
+    // Logs, traded markets and caches for this account; empty means the
+    // working directory
+    pub data_dir: PathBuf,
+    // Prepended to variable names; empty for the default profile
+    prefix: String,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self { name: "default".to_string(), data_dir: PathBuf::new(), prefix: String::new() }
+    }
+}
+
+impl Profile {
+    pub fn named(name: &str, base_dir: &Path) -> Result<Self, String> {
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            return Err(format!("profile name '{}' must be letters, digits, '_' or '-'", name));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            data_dir: base_dir.join(name),
+            prefix: format!("{}_", name.to_ascii_uppercase().replace('-', "_")),
+        })
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.prefix.is_empty()
+    }
+
+    /// `<NAME>_<var>` if set, else `var`.
+    pub fn var(&self, var: &str) -> Option<String> {
+        self.var_with(var, |name| std::env::var(name).ok())
+    }
+
+    /// `var` against an arbitrary lookup, for callers that don't read the
+    /// process environment.
+    pub fn var_with(&self, var: &str, lookup: impl Fn(&str) -> Option<String>) -> Option<String> {
+        if !self.prefix.is_empty() {
+            if let Some(value) = lookup(&format!("{}{}", self.prefix, var)) {
+                return Some(value);
+            }
+        }
+        lookup(var)
+    }
+
+    /// Where this profile keeps `file`.
+    pub fn path(&self, file: &str) -> String {
+        self.data_dir.join(file).to_string_lossy().into_owned()
+    }
+}
+
+/// Comma-separated profile names; an empty list means the default profile.
+pub fn parse_profiles(list: &str, base_dir: &Path) -> Result<Vec<Profile>, String> {
+    let mut seen = HashSet::new();
+    let mut profiles = Vec::new();
+    for name in list.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+        let profile = Profile::named(name, base_dir)?;
+        // alice-1 and ALICE_1 would read the same variables
+        if !seen.insert(profile.prefix.clone()) {
+            return Err(format!("profile '{}' listed twice", name));
+        }
+        profiles.push(profile);
+    }
+    if profiles.is_empty() {
+        profiles.push(Profile::default());
+    }
+    Ok(profiles)
+}
+
+/// BOT_PROFILES and BOT_PROFILE_DIR, narrowed to BOT_PROFILE when set.
+pub fn from_env() -> Result<Vec<Profile>, String> {
+    let base_dir = std::env::var("BOT_PROFILE_DIR").unwrap_or_else(|_| "profiles".to_string());
+    let profiles = parse_profiles(&std::env::var("BOT_PROFILES").unwrap_or_default(), Path::new(&base_dir))
+        .map_err(|e| format!("Invalid BOT_PROFILES: {}", e))?;
+    match std::env::var("BOT_PROFILE") {
+        Ok(only) => match profiles.into_iter().find(|p| p.name == only) {
+            Some(profile) => Ok(vec![profile]),
+            None => Err(format!("BOT_PROFILE '{}' is not in BOT_PROFILES", only)),
+        },
+        Err(_) => Ok(profiles),
+    }
+}
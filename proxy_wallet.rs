@@ -0,0 +1,51 @@
+use ethers::abi::{self, Token};
+use ethers::types::{Address, Bytes, U256};
+
+use crate::chain;
+use crate::network::NetworkProfile;
+use crate::tx_manager::TxRequest;
+
+const CALL_TYPE_CALL: u8 = 1;
+
+/// One call to execute from the proxy wallet.
+#[derive(Debug, Clone)]
+pub struct ProxyCall {
+    pub to: Address,
+    pub value: U256,
+    pub data: Bytes,
+}
+
+/// ABI-encode `proxy((uint8,address,uint256,bytes)[])`.
+pub fn encode_proxy_calls(calls: &[ProxyCall]) -> Vec<u8> {
+    let tuples = calls.iter().map(|c| Token::Tuple(vec![
+        Token::Uint(U256::from(CALL_TYPE_CALL)),
+        Token::Address(c.to),
+        Token::Uint(c.value),
+        Token::Bytes(c.data.to_vec()),
+    ])).collect();
+
+    let mut data = chain::function_selector("proxy((uint8,address,uint256,bytes)[])").to_vec();
+    data.extend(abi::encode(&[Token::Array(tuples)]));
+    data
+}
+
+/// Re-target EOA transactions so they execute from the proxy wallet that
+/// actually holds the funds (signature type 1 accounts), through the
+/// network's ProxyWalletFactory, whose `proxy()` forwards each call via the
+/// wallet owned by msg.sender. The EOA still pays gas.
+pub fn wrap_for_proxy(network: &NetworkProfile, label: &str, requests: &[TxRequest]) -> Result<TxRequest, Box<dyn std::error::Error>> {
+    let factory = network.proxy_factory
+        .ok_or_else(|| format!("No proxy wallet factory on {}; set POLY_PROXY_FACTORY_ADDRESS", network.name))?;
+    let calls: Vec<ProxyCall> = requests.iter().map(|r| ProxyCall {
+        to: r.to,
+        value: r.value,
+        data: r.data.clone(),
+    }).collect();
+
+    Ok(TxRequest {
+        label: format!("{} (via proxy)", label),
+        to: factory,
+        data: encode_proxy_calls(&calls).into(),
+        value: U256::zero(),
+    })
+}
@@ -0,0 +1,81 @@
+use ethers::abi::{self, Token};
+use ethers::types::{Address, H256, U256};
+
+use crate::chain;
+use crate::network::NetworkProfile;
+use crate::tx_manager::TxRequest;
+
+// Positions used to live here; keep the old paths working
+pub use crate::positions::{fetch_positions, parse_positions, DataPosition};
+
+/// Winning (or losing) tokens of one resolved condition to redeem.
+#[derive(Debug, Clone)]
+pub struct Redemption {
+    pub condition_id: H256,
+    pub title: String,
+    pub neg_risk: bool,
+    // Raw 6-decimal amounts per outcome index (YES, NO)
+    pub amounts: [U256; 2],
+    pub expected_value: f64,
+}
+
+/// Collapse per-token positions into one redemption per condition.
+pub fn plan_redemptions(positions: &[DataPosition]) -> Vec<Redemption> {
+    let mut plans: Vec<Redemption> = Vec::new();
+
+    for p in positions.iter().filter(|p| p.redeemable && p.size > 0.0) {
+        let Ok(condition_id) = p.condition_id.parse::<H256>() else { continue };
+        let raw = U256::from((p.size * 1_000_000.0).round() as u64);
+        let index = (p.outcome_index as usize).min(1);
+
+        match plans.iter_mut().find(|r| r.condition_id == condition_id) {
+            Some(plan) => {
+                plan.amounts[index] += raw;
+                plan.expected_value += p.current_value;
+            }
+            None => {
+                let mut amounts = [U256::zero(); 2];
+                amounts[index] = raw;
+                plans.push(Redemption {
+                    condition_id,
+                    title: p.title.clone(),
+                    neg_risk: p.negative_risk,
+                    amounts,
+                    expected_value: p.current_value,
+                });
+            }
+        }
+    }
+    plans
+}
+
+impl Redemption {
+    /// Standard markets redeem on the CTF contract with both index sets;
+    /// neg-risk markets go through the adapter with explicit amounts.
+    pub fn tx(&self, network: &NetworkProfile, collateral: Address) -> Result<TxRequest, Box<dyn std::error::Error>> {
+        let (to, data) = if self.neg_risk {
+            let mut data = chain::function_selector("redeemPositions(bytes32,uint256[])").to_vec();
+            data.extend(abi::encode(&[
+                Token::FixedBytes(self.condition_id.as_bytes().to_vec()),
+                Token::Array(self.amounts.iter().map(|a| Token::Uint(*a)).collect()),
+            ]));
+            (network.neg_risk_adapter, data)
+        } else {
+            let mut data = chain::function_selector("redeemPositions(address,bytes32,bytes32,uint256[])").to_vec();
+            data.extend(abi::encode(&[
+                Token::Address(collateral),
+                Token::FixedBytes(H256::zero().as_bytes().to_vec()),
+                Token::FixedBytes(self.condition_id.as_bytes().to_vec()),
+                Token::Array(vec![Token::Uint(U256::from(1)), Token::Uint(U256::from(2))]),
+            ]));
+            (network.ctf, data)
+        };
+
+        Ok(TxRequest {
+            label: format!("Redeem {}", self.title),
+            to,
+            data: data.into(),
+            value: U256::zero(),
+        })
+    }
+}
@@ -0,0 +1,115 @@
+//! Daily summaries of the trade journal, one per UTC day: trades taken, win
+//! rate, average fill against the price the signal fired at, PnL,
+//! stop-losses and aborts. `report` prints them and writes CSV; the run
+//! loop appends each day's row to `daily_report.csv` once the day is over.
+//!
+//! Figures come from whatever columns the journal has. Without `pnl` there
+//! is no win rate or PnL, and without `slippage` no trigger price; those
+//! print as `-`.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use serde::Serialize;
+
+use crate::timestamps;
+use crate::trade_log::TradeRecord;
+
+pub const CSV_COLUMNS: [&str; 10] = [
+    "day", "trades", "closed", "wins", "win_rate", "avg_fill", "avg_trigger", "pnl", "stop_losses", "aborts",
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DailySummary {
+    // YYYY-MM-DD, UTC
+    pub day: String,
+    // Entries, partial ones included
+    pub trades: usize,
+    // Exits and redemptions with a PnL
+    pub closed: usize,
+    pub wins: usize,
+    pub win_rate: Option<f64>,
+    pub avg_fill: Option<f64>,
+    // Fill less slippage, over the entries that logged it
+    pub avg_trigger: Option<f64>,
+    pub pnl: Option<f64>,
+    pub stop_losses: usize,
+    pub aborts: usize,
+}
+
+impl DailySummary {
+    /// One console line.
+    pub fn text(&self) -> String {
+        let price = |p: Option<f64>| p.map(|p| format!("${:.3}", p)).unwrap_or_else(|| "-".to_string());
+        let win_rate = match self.win_rate {
+            Some(rate) => format!("{:.0}% ({}/{})", rate * 100.0, self.wins, self.closed),
+            None => "-".to_string(),
+        };
+        format!("{}: {} trade(s), win rate {}, fill {} vs trigger {}, PnL {}, {} stop-loss(es), {} abort(s)",
+            self.day, self.trades, win_rate, price(self.avg_fill), price(self.avg_trigger),
+            self.pnl.map(|p| format!("${:+.2}", p)).unwrap_or_else(|| "-".to_string()), self.stop_losses, self.aborts)
+    }
+}
+
+/// `2025-10-09` for unix seconds.
+pub fn day_of(unix_secs: u64) -> String {
+    timestamps::rfc3339(unix_secs)[..10].to_string()
+}
+
+// Exits date by when they closed, everything else by when it happened
+fn row_time(r: &TradeRecord) -> Option<u64> {
+    r.exit_time.or(r.entry_time).or(r.sl_time)
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() { None } else { Some(values.iter().sum::<f64>() / values.len() as f64) }
+}
+
+/// Summaries for every day with activity, oldest first. Rows with no time
+/// at all can't be placed and are left out.
+pub fn daily(records: &[TradeRecord]) -> Vec<DailySummary> {
+    let mut days: BTreeMap<String, Vec<&TradeRecord>> = BTreeMap::new();
+    for record in records {
+        if let Some(ts) = row_time(record) {
+            days.entry(day_of(ts)).or_default().push(record);
+        }
+    }
+    days.into_iter().map(|(day, rows)| summarize(day, &rows)).collect()
+}
+
+fn summarize(day: String, rows: &[&TradeRecord]) -> DailySummary {
+    let entries: Vec<&&TradeRecord> = rows.iter().filter(|r| matches!(r.status.as_str(), "ENTERED" | "PARTIAL")).collect();
+    let fills: Vec<f64> = entries.iter().filter_map(|r| r.entry_price).collect();
+    let triggers: Vec<f64> = entries.iter().filter_map(|r| Some(r.entry_price? - r.slippage?)).collect();
+    let closes: Vec<f64> = rows.iter()
+        .filter(|r| matches!(r.status.as_str(), "EXITED" | "REDEEMED"))
+        .filter_map(|r| r.pnl)
+        .collect();
+    let wins = closes.iter().filter(|p| **p > 0.0).count();
+    DailySummary {
+        day,
+        trades: entries.len(),
+        closed: closes.len(),
+        wins,
+        win_rate: (!closes.is_empty()).then(|| wins as f64 / closes.len() as f64),
+        avg_fill: mean(&fills),
+        avg_trigger: mean(&triggers),
+        pnl: (!closes.is_empty()).then(|| closes.iter().sum()),
+        stop_losses: rows.iter().filter(|r| r.sl_triggered == Some(true) || r.status.starts_with("STOP")).count(),
+        aborts: rows.iter().filter(|r| r.status == "ABORTED").count(),
+    }
+}
+
+/// Rows under `CSV_COLUMNS`, with the header unless `header` is false (for
+/// appending to an existing file).
+pub fn write_csv<W: Write>(summaries: &[DailySummary], out: W, header: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let mut writer = csv::WriterBuilder::new().has_headers(false).from_writer(out);
+    if header {
+        writer.write_record(CSV_COLUMNS)?;
+    }
+    for summary in summaries {
+        writer.serialize(summary)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
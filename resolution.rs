@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+
+use ethers::types::{Address, H256};
+use reqwest::blocking::Client;
+use serde::Deserialize;
+
+use crate::chain::{self, RpcClient};
+
+/// Where a held market is in the UMA optimistic-oracle lifecycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResolutionState {
+    Open,
+    // Trading closed, no price proposed yet
+    AwaitingProposal,
+    // Price proposed; the ~2h challenge window is running
+    Proposed,
+    // Proposal challenged; resolution escalates and can take days
+    Disputed,
+    // Payouts reported on the CTF contract, redeemable
+    Resolved,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResolutionAlert {
+    pub condition_id: String,
+    pub title: String,
+    pub from: ResolutionState,
+    pub to: ResolutionState,
+}
+
+#[derive(Debug, Deserialize)]
+struct GammaMarket {
+    #[serde(rename = "conditionId", default)]
+    condition_id: String,
+    #[serde(default)]
+    closed: bool,
+    #[serde(rename = "umaResolutionStatus", default)]
+    uma_resolution_status: Option<String>,
+}
+
+struct WatchedMarket {
+    title: String,
+    state: ResolutionState,
+}
+
+/// Tracks resolution of markets we hold positions in, so redemption waits
+/// for actual on-chain payouts and disputes are surfaced instead of silent.
+#[derive(Default)]
+pub struct ResolutionWatcher {
+    markets: HashMap<String, WatchedMarket>,
+}
+
+impl ResolutionWatcher {
+    pub fn watch(&mut self, condition_id: &str, title: &str) {
+        if condition_id.is_empty() {
+            return;
+        }
+        self.markets.entry(condition_id.to_string()).or_insert(WatchedMarket {
+            title: title.to_string(),
+            state: ResolutionState::Open,
+        });
+    }
+
+    pub fn unwatch(&mut self, condition_id: &str) {
+        self.markets.remove(condition_id);
+    }
+
+    pub fn state(&self, condition_id: &str) -> Option<ResolutionState> {
+        self.markets.get(condition_id).map(|m| m.state)
+    }
+
+    pub fn is_redeemable(&self, condition_id: &str) -> bool {
+        self.state(condition_id) == Some(ResolutionState::Resolved)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.markets.is_empty()
+    }
+
+    pub fn poll(&mut self, client: &Client, gamma_url: &str, rpc: &RpcClient, ctf: Address)
+        -> Result<Vec<ResolutionAlert>, Box<dyn std::error::Error>> {
+        let mut alerts = Vec::new();
+        if self.markets.is_empty() {
+            return Ok(alerts);
+        }
+
+        let ids: Vec<&str> = self.markets.keys().map(|k| k.as_str()).collect();
+        let url = format!("{}/markets?condition_ids={}", gamma_url, ids.join(","));
+        let gamma: Vec<GammaMarket> = client.get(&url).send()?.error_for_status()?.json()?;
+        let by_id: HashMap<&str, &GammaMarket> = gamma.iter().map(|m| (m.condition_id.as_str(), m)).collect();
+
+        for (condition_id, watched) in self.markets.iter_mut() {
+            // The CTF payout report is final; Gamma status only describes the path there
+            let new_state = if payouts_reported(rpc, ctf, condition_id)? {
+                ResolutionState::Resolved
+            } else {
+                match by_id.get(condition_id.as_str()) {
+                    Some(m) => state_from_gamma(m),
+                    None => watched.state,
+                }
+            };
+
+            if new_state != watched.state {
+                alerts.push(ResolutionAlert {
+                    condition_id: condition_id.clone(),
+                    title: watched.title.clone(),
+                    from: watched.state,
+                    to: new_state,
+                });
+                watched.state = new_state;
+            }
+        }
+        Ok(alerts)
+    }
+}
+
+fn state_from_gamma(market: &GammaMarket) -> ResolutionState {
+    match market.uma_resolution_status.as_deref().map(|s| s.to_lowercase()) {
+        Some(s) if s.contains("disput") => ResolutionState::Disputed,
+        Some(s) if s.contains("propos") => ResolutionState::Proposed,
+        // Gamma can flip to "resolved" before the payout tx lands; not redeemable yet
+        Some(s) if s.contains("resolv") => ResolutionState::Proposed,
+        _ if market.closed => ResolutionState::AwaitingProposal,
+        _ => ResolutionState::Open,
+    }
+}
+
+/// `payoutDenominator(conditionId) > 0` once the oracle has reported.
+pub fn payouts_reported(rpc: &RpcClient, ctf: Address, condition_id: &str) -> Result<bool, Box<dyn std::error::Error>> {
+    let condition: H256 = condition_id.parse()?;
+    let data = chain::encode_call("payoutDenominator(bytes32)", &[condition.0]);
+    Ok(!chain::decode_uint(&rpc.eth_call(ctf, &data)?)?.is_zero())
+}
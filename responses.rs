@@ -0,0 +1,169 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::schedule;
+
+/// The pair of outcome tokens the bot trades for one event.
+#[derive(Debug, Clone)]
+pub struct MarketData {
+    pub slug: String,
+    pub title: String,
+    pub link: String,
+    pub condition_id: String,
+    pub yes_token: String,
+    pub no_token: String,
+    // When Gamma says the market closes (its endDate), if it said
+    pub end_ts: Option<u64>,
+}
+
+/// Pull the first tradable market out of a gamma `/events?slug=` response.
+/// Ok(None) means the event exists but has nothing we can trade yet.
+pub fn parse_market_event(slug: &str, bytes: &[u8]) -> Result<Option<MarketData>, Box<dyn std::error::Error>> {
+    let data: Vec<Value> = serde_json::from_slice(bytes)?;
+    let Some(event) = data.first() else { return Ok(None) };
+    market_from_event(slug, event)
+}
+
+/// The first tradable market of one gamma event object.
+pub fn market_from_event(slug: &str, event: &Value) -> Result<Option<MarketData>, Box<dyn std::error::Error>> {
+    let markets = event["markets"].as_array().ok_or("No markets found")?;
+    let Some(market_data) = markets.first() else { return Ok(None) };
+
+    if !market_data["enableOrderBook"].as_bool().unwrap_or(false) {
+        return Ok(None);
+    }
+
+    let token_ids: Vec<String> = serde_json::from_str(
+        market_data["clobTokenIds"].as_str().ok_or("Invalid clobTokenIds")?
+    )?;
+    let [yes_token, no_token, ..] = token_ids.as_slice() else { return Ok(None) };
+
+    Ok(Some(MarketData {
+        slug: slug.to_string(),
+        title: event["title"].as_str().unwrap_or(slug).to_string(),
+        link: format!("https://polymarket.com/event/{}", slug),
+        condition_id: market_data["conditionId"].as_str().unwrap_or_default().to_string(),
+        yes_token: yes_token.clone(),
+        no_token: no_token.clone(),
+        end_ts: market_data["endDate"].as_str().or(event["endDate"].as_str()).and_then(schedule::parse_instant),
+    }))
+}
+
+/// `/order/{id}` response; every field is optional on older orders.
+#[derive(Debug, Deserialize)]
+pub struct OrderStatus {
+    pub status: Option<String>,
+    #[serde(rename = "avgFillPrice")]
+    pub avg_fill_price: Option<String>,
+    pub price: Option<String>,
+    #[serde(default)]
+    pub asset_id: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<u64>,
+    #[serde(default)]
+    pub original_size: Option<String>,
+    #[serde(default)]
+    pub size_matched: Option<String>,
+}
+
+pub fn parse_order_status(bytes: &[u8]) -> Result<OrderStatus, serde_json::Error> {
+    serde_json::from_slice(bytes)
+}
+
+/// Numeric decimal string, or 0 for anything missing, unparsable or non-finite.
+fn parse_amount(v: &Option<String>) -> f64 {
+    v.as_deref()
+        .and_then(|s| s.parse::<f64>().ok())
+        .filter(|x| x.is_finite())
+        .unwrap_or(0.0)
+}
+
+impl OrderStatus {
+    /// Fill state as reported by the order itself, before consulting trades.
+    pub fn progress(&self) -> OrderProgress {
+        let status = self.status.clone().unwrap_or_default();
+        let original_size = parse_amount(&self.original_size);
+        let mut filled_size = parse_amount(&self.size_matched);
+        // Older responses omit size_matched on fully matched orders
+        if filled_size == 0.0 && matches!(status.as_str(), "MATCHED" | "FILLED" | "COMPLETED") {
+            filled_size = original_size;
+        }
+        OrderProgress { status, original_size, filled_size, avg_price: 0.0, fee: 0.0 }
+    }
+
+    /// Average price from the order fields, when the trades endpoint has none.
+    pub fn reported_avg_price(&self) -> f64 {
+        let avg = parse_amount(&self.avg_fill_price);
+        if avg == 0.0 { parse_amount(&self.price) } else { avg }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct OrderProgress {
+    pub status: String,
+    pub original_size: f64,
+    pub filled_size: f64,
+    pub avg_price: f64,
+    // Fees charged on the filled size, in USDC
+    pub fee: f64,
+}
+
+impl OrderProgress {
+    pub fn remaining(&self) -> f64 {
+        (self.original_size - self.filled_size).max(0.0)
+    }
+
+    pub fn is_filled(&self) -> bool {
+        matches!(self.status.as_str(), "FILLED" | "COMPLETED")
+            || (self.filled_size > 0.0 && self.remaining() < 1e-6)
+    }
+
+    /// No further fills can happen (filled, killed, cancelled or expired).
+    pub fn is_closed(&self) -> bool {
+        matches!(self.status.as_str(), "MATCHED" | "FILLED" | "COMPLETED" | "CANCELED" | "CANCELLED" | "EXPIRED" | "UNMATCHED")
+    }
+}
+
+/// One resting order from `/data/orders`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OpenOrder {
+    pub id: String,
+    #[serde(default)]
+    pub status: String,
+    #[serde(default)]
+    pub side: String,
+    #[serde(default)]
+    pub asset_id: String,
+    #[serde(default)]
+    pub outcome: String,
+    #[serde(default)]
+    pub order_type: String,
+    #[serde(default)]
+    pub price: Option<String>,
+    #[serde(default)]
+    pub original_size: Option<String>,
+    #[serde(default)]
+    pub size_matched: Option<String>,
+}
+
+impl OpenOrder {
+    pub fn price(&self) -> f64 {
+        parse_amount(&self.price)
+    }
+
+    pub fn remaining(&self) -> f64 {
+        (parse_amount(&self.original_size) - parse_amount(&self.size_matched)).max(0.0)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OpenOrdersPage {
+    #[serde(default)]
+    pub data: Vec<OpenOrder>,
+    #[serde(default)]
+    pub next_cursor: String,
+}
+
+pub fn parse_open_orders(bytes: &[u8]) -> Result<OpenOrdersPage, serde_json::Error> {
+    serde_json::from_slice(bytes)
+}
@@ -0,0 +1,90 @@
+use ethers::abi::{decode, ParamType};
+use serde_json::Value;
+
+use crate::chain::RpcCallError;
+
+// Error(string) and Panic(uint256) selectors
+const ERROR_STRING_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Pull the revert reason out of an eth_call / eth_estimateGas failure.
+/// Nodes put the ABI-encoded reason in `error.data` (either a hex string or
+/// an object with a `data` field) and usually a readable copy in the message.
+pub fn revert_reason(err: &RpcCallError) -> Option<String> {
+    let raw = match &err.data {
+        Some(Value::String(s)) => Some(s.clone()),
+        Some(Value::Object(o)) => o.get("data").and_then(|d| d.as_str()).map(|s| s.to_string()),
+        _ => None,
+    };
+
+    if let Some(bytes) = raw.and_then(|s| hex::decode(s.trim_start_matches("0x")).ok()) {
+        if let Some(reason) = decode_revert_data(&bytes) {
+            return Some(reason);
+        }
+    }
+
+    err.message.strip_prefix("execution reverted")
+        .map(|rest| rest.trim_start_matches(':').trim().to_string())
+        .filter(|reason| !reason.is_empty())
+}
+
+pub fn decode_revert_data(data: &[u8]) -> Option<String> {
+    if data.len() < 4 {
+        return None;
+    }
+    let (selector, body) = data.split_at(4);
+
+    if selector == ERROR_STRING_SELECTOR {
+        let tokens = decode(&[ParamType::String], body).ok()?;
+        return tokens.into_iter().next()?.into_string();
+    }
+    if selector == PANIC_SELECTOR {
+        let tokens = decode(&[ParamType::Uint(256)], body).ok()?;
+        let code = tokens.into_iter().next()?.into_uint()?;
+        return Some(format!("panic 0x{:x}", code));
+    }
+    // Custom error: nothing to decode without the ABI, but the selector is
+    // enough to look up
+    Some(format!("custom error 0x{}", hex::encode(selector)))
+}
+
+/// Map the reverts we actually hit (missing approvals, unresolved markets,
+/// empty positions) to what the operator should do about them.
+pub fn explain(reason: &str) -> Option<&'static str> {
+    let reason = reason.to_lowercase();
+    let hints: &[(&[&str], &str)] = &[
+        (&["insufficient allowance", "exceeds allowance"],
+            "collateral is not approved for this spender; restart the bot to run the approval check"),
+        (&["not owner nor approved", "need operator approval", "caller is not token owner"],
+            "outcome tokens are not approved (setApprovalForAll) for this operator"),
+        (&["result for condition not received", "payout denominator", "not resolved"],
+            "market is not resolved on-chain yet; try again after the oracle reports"),
+        (&["condition not prepared"],
+            "condition id is unknown to the CTF contract; check the market's conditionId"),
+        (&["burn amount exceeds balance", "insufficient balance for transfer"],
+            "no outcome tokens to redeem/merge for this position"),
+        (&["transfer amount exceeds balance"],
+            "not enough collateral in the sending wallet"),
+        (&["paused"],
+            "contract is paused exchange-side"),
+    ];
+
+    hints.iter()
+        .find(|(needles, _)| needles.iter().any(|n| reason.contains(n)))
+        .map(|(_, hint)| *hint)
+}
+
+/// Turn a failed simulation into an actionable error message.
+pub fn describe_failure(label: &str, err: &(dyn std::error::Error + 'static)) -> String {
+    let Some(rpc_err) = err.downcast_ref::<RpcCallError>() else {
+        return format!("{} simulation failed: {}", label, err);
+    };
+
+    match revert_reason(rpc_err) {
+        Some(reason) => match explain(&reason) {
+            Some(hint) => format!("{} would revert ({}): {}", label, reason, hint),
+            None => format!("{} would revert: {}", label, reason),
+        },
+        None => format!("{} simulation failed: {}", label, rpc_err),
+    }
+}
@@ -0,0 +1,152 @@
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+use reqwest::blocking::Client;
+use serde_json::{json, Value};
+
+// Endpoints are benched after this many consecutive failures...
+const MAX_CONSECUTIVE_FAILURES: u32 = 3;
+// ...for this long before being tried again
+const BENCH_DURATION: Duration = Duration::from_secs(60);
+// An endpoint this many blocks behind the best head is treated as unhealthy
+const MAX_BLOCK_LAG: u64 = 10;
+
+struct Endpoint {
+    url: String,
+    // Exponentially weighted latency of successful requests
+    latency: Cell<Option<Duration>>,
+    consecutive_failures: Cell<u32>,
+    benched_until: Cell<Option<Instant>>,
+}
+
+impl Endpoint {
+    fn is_available(&self) -> bool {
+        match self.benched_until.get() {
+            Some(until) => Instant::now() >= until,
+            None => true,
+        }
+    }
+
+    fn record_success(&self, elapsed: Duration) {
+        let smoothed = match self.latency.get() {
+            Some(prev) => (prev * 7 + elapsed) / 8,
+            None => elapsed,
+        };
+        self.latency.set(Some(smoothed));
+        self.consecutive_failures.set(0);
+        self.benched_until.set(None);
+    }
+
+    fn record_failure(&self) {
+        let failures = self.consecutive_failures.get() + 1;
+        self.consecutive_failures.set(failures);
+        if failures >= MAX_CONSECUTIVE_FAILURES {
+            self.bench();
+        }
+    }
+
+    fn bench(&self) {
+        self.benched_until.set(Some(Instant::now() + BENCH_DURATION));
+    }
+}
+
+/// A set of interchangeable RPC endpoints. Requests go to the fastest healthy
+/// endpoint and fail over down the list on transport errors, 429s and 5xx.
+pub struct RpcPool {
+    endpoints: Vec<Endpoint>,
+    client: Client,
+}
+
+impl RpcPool {
+    pub fn new(urls: &[String]) -> Result<Self, Box<dyn std::error::Error>> {
+        if urls.is_empty() {
+            return Err("at least one RPC URL is required".into());
+        }
+        Ok(Self {
+            endpoints: urls.iter().map(|url| Endpoint {
+                url: url.clone(),
+                latency: Cell::new(None),
+                consecutive_failures: Cell::new(0),
+                benched_until: Cell::new(None),
+            }).collect(),
+            client: Client::builder()
+                .timeout(Duration::from_secs(15))
+                .build()?,
+        })
+    }
+
+    /// Preferred endpoint order: available before benched, then by latency,
+    /// with untested endpoints ahead of slow ones so they get measured.
+    fn ordered(&self) -> Vec<&Endpoint> {
+        let mut order: Vec<&Endpoint> = self.endpoints.iter().collect();
+        order.sort_by_key(|e| (!e.is_available(), e.latency.get().unwrap_or(Duration::ZERO)));
+        order
+    }
+
+    pub fn primary_url(&self) -> &str {
+        &self.ordered()[0].url
+    }
+
+    /// Send one JSON-RPC request body and return the decoded response envelope.
+    pub fn send(&self, request: &Value) -> Result<Value, Box<dyn std::error::Error>> {
+        let mut last_error = String::new();
+
+        for endpoint in self.ordered() {
+            let started = Instant::now();
+            let result = self.client.post(&endpoint.url)
+                .json(request)
+                .send()
+                .and_then(|r| r.error_for_status())
+                .and_then(|r| r.json::<Value>());
+
+            match result {
+                Ok(body) => {
+                    endpoint.record_success(started.elapsed());
+                    return Ok(body);
+                }
+                Err(e) => {
+                    endpoint.record_failure();
+                    last_error = format!("{}: {}", endpoint.url, e);
+                }
+            }
+        }
+
+        Err(format!("all RPC endpoints failed (last: {})", last_error).into())
+    }
+
+    /// Probe every endpoint, refresh latencies, and bench endpoints that are
+    /// down or lagging behind the best head.
+    pub fn health_check(&self) -> Vec<(String, Option<u64>)> {
+        let request = json!({ "jsonrpc": "2.0", "id": 0, "method": "eth_blockNumber", "params": [] });
+        let mut heads = Vec::new();
+
+        for endpoint in &self.endpoints {
+            let started = Instant::now();
+            let head = self.client.post(&endpoint.url)
+                .json(&request)
+                .send()
+                .and_then(|r| r.error_for_status())
+                .and_then(|r| r.json::<Value>())
+                .ok()
+                .and_then(|body| body["result"].as_str().and_then(|h| u64::from_str_radix(h.trim_start_matches("0x"), 16).ok()));
+
+            match head {
+                Some(_) => endpoint.record_success(started.elapsed()),
+                None => endpoint.record_failure(),
+            }
+            heads.push(head);
+        }
+
+        if let Some(best) = heads.iter().flatten().max().copied() {
+            for (endpoint, head) in self.endpoints.iter().zip(&heads) {
+                if let Some(h) = head {
+                    if best - h > MAX_BLOCK_LAG {
+                        endpoint.bench();
+                    }
+                }
+            }
+        }
+
+        self.endpoints.iter().map(|e| e.url.clone()).zip(heads).collect()
+    }
+}
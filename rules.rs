@@ -0,0 +1,225 @@
+//! Operational rules: a condition on what the bot has been seeing, and what
+//! to do when it starts to hold. Rules live in BOT_RULES, separated by ';':
+//!
+//!   BOT_RULES="api_error_rate>0.2 over 1m while holding -> page,pause;
+//!              rejects>=3 over 5m -> notify"
+//!
+//! Metrics, each over its window (default 1m):
+//!   - `api_error_rate`: failed share of API requests (no value without any)
+//!   - `api_errors`: failed API requests
+//!   - `rejects`: orders the exchange refused
+//!   - `fills`: fills received
+//!
+//! `while holding` / `while flat` limit a rule to when the bot does or
+//! doesn't hold a position. Actions: `notify` (warning), `page` (critical),
+//! `pause` (no new entries while the rule holds) and `liquidate` (sell what
+//! is held). Like price alerts, a rule fires once when it starts holding and
+//! re-arms when it stops.
+
+use std::collections::VecDeque;
+
+use crate::alerts::{self, Cmp};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    ApiErrorRate,
+    ApiErrors,
+    Rejects,
+    Fills,
+}
+
+impl Metric {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "api_error_rate" => Some(Self::ApiErrorRate),
+            "api_errors" => Some(Self::ApiErrors),
+            "rejects" => Some(Self::Rejects),
+            "fills" => Some(Self::Fills),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Notify,
+    Page,
+    Pause,
+    Liquidate,
+}
+
+impl Action {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "notify" => Some(Self::Notify),
+            "page" => Some(Self::Page),
+            "pause" => Some(Self::Pause),
+            "liquidate" => Some(Self::Liquidate),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Guard {
+    Holding,
+    Flat,
+}
+
+/// Something the bot observed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sample {
+    Request { ok: bool },
+    Reject,
+    Fill,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    // As written, used as its name in notifications
+    pub text: String,
+    pub metric: Metric,
+    pub cmp: Cmp,
+    pub threshold: f64,
+    // Seconds
+    pub window: u64,
+    pub guard: Option<Guard>,
+    pub actions: Vec<Action>,
+}
+
+/// `30s`, `5m`, `1h` or plain seconds.
+fn parse_window(s: &str) -> Option<u64> {
+    let (n, unit) = match s.char_indices().last()? {
+        (i, 's') => (&s[..i], 1),
+        (i, 'm') => (&s[..i], 60),
+        (i, 'h') => (&s[..i], 3600),
+        _ => (s, 1),
+    };
+    n.parse::<u64>().ok().filter(|n| *n > 0).map(|n| n * unit)
+}
+
+impl Rule {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let (condition, actions) = text.split_once("->").ok_or_else(|| format!("'{}': expected <condition> -> <actions>", text))?;
+        let actions = actions.split(',').map(str::trim).filter(|a| !a.is_empty())
+            .map(|a| Action::parse(a).ok_or_else(|| format!("unknown action '{}' (notify, page, pause, liquidate)", a)))
+            .collect::<Result<Vec<_>, _>>()?;
+        if actions.is_empty() {
+            return Err(format!("'{}': no actions", text));
+        }
+
+        let mut terms = condition.split_whitespace();
+        let first = terms.next().ok_or_else(|| format!("'{}': empty condition", text))?;
+        let (field, cmp, value) = alerts::split_comparison(first).ok_or_else(|| format!("'{}': expected e.g. api_error_rate>0.2", first))?;
+        let metric = Metric::parse(field).ok_or_else(|| format!("unknown metric '{}' (api_error_rate, api_errors, rejects, fills)", field))?;
+        let threshold: f64 = value.parse().map_err(|_| format!("'{}': bad number", first))?;
+
+        let mut rule = Self { text: text.to_string(), metric, cmp, threshold, window: 60, guard: None, actions };
+        while let Some(word) = terms.next() {
+            match (word, terms.next()) {
+                ("over", Some(w)) => rule.window = parse_window(w).ok_or_else(|| format!("'{}': bad window (e.g. 30s, 5m)", w))?,
+                ("while", Some("holding")) => rule.guard = Some(Guard::Holding),
+                ("while", Some("flat")) => rule.guard = Some(Guard::Flat),
+                (word, next) => return Err(format!("unexpected '{} {}' (over <window>, while holding|flat)", word, next.unwrap_or(""))),
+            }
+        }
+        Ok(rule)
+    }
+
+    pub fn has(&self, action: Action) -> bool {
+        self.actions.contains(&action)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Transition {
+    // Rule index and the metric value that tripped it
+    Fired(usize, f64),
+    Cleared(usize),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RuleEngine {
+    rules: Vec<Rule>,
+    firing: Vec<bool>,
+    samples: VecDeque<(u64, Sample)>,
+}
+
+impl RuleEngine {
+    pub fn new(rules: Vec<Rule>) -> Self {
+        Self { firing: vec![false; rules.len()], rules, samples: VecDeque::new() }
+    }
+
+    pub fn parse(list: &str) -> Result<Self, String> {
+        let rules = list.split(';').filter(|r| !r.trim().is_empty()).map(Rule::parse).collect::<Result<_, _>>()?;
+        Ok(Self::new(rules))
+    }
+
+    /// BOT_RULES; no rules when unset.
+    pub fn from_env() -> Result<Self, String> {
+        match std::env::var("BOT_RULES") {
+            Ok(v) => Self::parse(&v).map_err(|e| format!("Invalid BOT_RULES: {}", e)),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn record(&mut self, now: u64, sample: Sample) {
+        if self.rules.is_empty() {
+            return;
+        }
+        self.samples.push_back((now, sample));
+        let horizon = self.rules.iter().map(|r| r.window).max().unwrap_or(0);
+        while self.samples.front().is_some_and(|(ts, _)| now.saturating_sub(*ts) >= horizon) {
+            self.samples.pop_front();
+        }
+    }
+
+    /// `metric` over the `window` seconds before `now`.
+    pub fn value(&self, metric: Metric, window: u64, now: u64) -> Option<f64> {
+        let recent = self.samples.iter().filter(|(ts, _)| now.saturating_sub(*ts) < window).map(|(_, s)| *s);
+        let count = |f: fn(&Sample) -> bool| recent.clone().filter(f).count() as f64;
+        match metric {
+            Metric::ApiErrorRate => {
+                let requests = count(|s| matches!(s, Sample::Request { .. }));
+                (requests > 0.0).then(|| count(|s| *s == Sample::Request { ok: false }) / requests)
+            }
+            Metric::ApiErrors => Some(count(|s| *s == Sample::Request { ok: false })),
+            Metric::Rejects => Some(count(|s| *s == Sample::Reject)),
+            Metric::Fills => Some(count(|s| *s == Sample::Fill)),
+        }
+    }
+
+    /// Rules that started or stopped holding since the last call.
+    pub fn evaluate(&mut self, now: u64, holding: bool) -> Vec<Transition> {
+        let mut transitions = Vec::new();
+        for (i, rule) in self.rules.iter().enumerate() {
+            let guarded = match rule.guard {
+                Some(Guard::Holding) => holding,
+                Some(Guard::Flat) => !holding,
+                None => true,
+            };
+            let value = self.value(rule.metric, rule.window, now).filter(|v| guarded && rule.cmp.holds(*v, rule.threshold));
+            match (value, self.firing[i]) {
+                (Some(v), false) => transitions.push(Transition::Fired(i, v)),
+                (None, true) => transitions.push(Transition::Cleared(i)),
+                _ => {}
+            }
+            self.firing[i] = value.is_some();
+        }
+        transitions
+    }
+
+    /// Some firing rule asks for entries to stop.
+    pub fn is_paused(&self) -> bool {
+        self.rules.iter().zip(&self.firing).any(|(rule, firing)| *firing && rule.has(Action::Pause))
+    }
+}
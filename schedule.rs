@@ -0,0 +1,243 @@
+//! Which market cycles to trade. A cycle is named by its start time (the
+//! slug timestamp) and checked against, in order:
+//!   - skip dates: whole UTC days off (BOT_SKIP_DATES=2025-12-25,2026-01-01)
+//!   - skip-after instants: the first cycle starting at or after each one,
+//!     e.g. an options expiry (BOT_SKIP_AFTER=2025-10-31T08:00Z)
+//!   - a cron expression over the start time in UTC
+//!     (BOT_SCHEDULE="0,30 * * * *" trades only the :00 and :30 cycles)
+//!
+//! Pure calendar arithmetic, no chrono, so it builds with the other
+//! wasm32-safe modules.
+
+const DAY: u64 = 86_400;
+
+/// Days since 1970-01-01 to (year, month, day); Howard Hinnant's algorithm.
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// `YYYY-MM-DD` to the unix seconds of its UTC midnight.
+fn parse_date(s: &str) -> Option<u64> {
+    let mut parts = s.splitn(3, '-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: u32 = parts.next()?.parse().ok()?;
+    let day: u32 = parts.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let days = days_from_civil(year, month, day);
+    // Reject dates that roll over, like 02-30
+    (civil_from_days(days) == (year, month, day)).then(|| u64::try_from(days).ok()).flatten().map(|d| d * DAY)
+}
+
+/// `YYYY-MM-DDTHH:MM[:SS[.fff]]Z` (UTC only) to unix seconds; fractions
+/// are dropped.
+pub(crate) fn parse_instant(s: &str) -> Option<u64> {
+    let (date, time) = s.split_once('T')?;
+    let time = time.strip_suffix('Z')?;
+    let mut parts = time.split(':');
+    let hour: u64 = parts.next()?.parse().ok()?;
+    let minute: u64 = parts.next()?.parse().ok()?;
+    let second: u64 = match parts.next() {
+        Some(s) => {
+            let (whole, fraction) = s.split_once('.').unwrap_or((s, "0"));
+            if !fraction.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            whole.parse().ok()?
+        }
+        None => 0,
+    };
+    if parts.next().is_some() || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+    Some(parse_date(date)? + hour * 3_600 + minute * 60 + second)
+}
+
+/// One cron field as a bitmask of allowed values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Field(u64);
+
+impl Field {
+    fn parse(spec: &str, min: u32, max: u32) -> Result<Self, String> {
+        let mut mask = 0u64;
+        for part in spec.split(',') {
+            let (range, step) = match part.split_once('/') {
+                Some((range, step)) => (range, step.parse::<u32>().ok().filter(|s| *s > 0).ok_or_else(|| format!("bad step in '{}'", part))?),
+                None => (part, 1),
+            };
+            let (lo, hi) = match range {
+                "*" => (min, max),
+                _ => match range.split_once('-') {
+                    Some((lo, hi)) => (Self::value(lo, part)?, Self::value(hi, part)?),
+                    // `5/15` means from 5 to the end in steps of 15
+                    None if step > 1 => (Self::value(range, part)?, max),
+                    None => {
+                        let v = Self::value(range, part)?;
+                        (v, v)
+                    }
+                },
+            };
+            if lo < min || hi > max || lo > hi {
+                return Err(format!("'{}' outside {}-{}", part, min, max));
+            }
+            for v in (lo..=hi).step_by(step as usize) {
+                mask |= 1 << v;
+            }
+        }
+        Ok(Self(mask))
+    }
+
+    fn value(s: &str, part: &str) -> Result<u32, String> {
+        s.parse().map_err(|_| format!("bad value in '{}'", part))
+    }
+
+    fn matches(&self, v: u32) -> bool {
+        self.0 & (1 << v) != 0
+    }
+}
+
+/// Standard five-field cron: minute hour day-of-month month day-of-week
+/// (0 = Sunday; 7 is accepted as Sunday too).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronExpr {
+    minute: Field,
+    hour: Field,
+    day: Field,
+    month: Field,
+    weekday: Field,
+    // Cron ORs day-of-month and day-of-week when both are restricted
+    day_restricted: bool,
+    weekday_restricted: bool,
+}
+
+impl CronExpr {
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(format!("cron '{}' needs 5 fields: minute hour day month weekday", expr));
+        };
+        let mut weekdays = Field::parse(weekday, 0, 7)?;
+        if weekdays.matches(7) {
+            weekdays.0 |= 1;
+        }
+        Ok(Self {
+            minute: Field::parse(minute, 0, 59)?,
+            hour: Field::parse(hour, 0, 23)?,
+            day: Field::parse(day, 1, 31)?,
+            month: Field::parse(month, 1, 12)?,
+            weekday: weekdays,
+            day_restricted: day != "*",
+            weekday_restricted: weekday != "*",
+        })
+    }
+
+    pub fn matches(&self, unix_secs: u64) -> bool {
+        let days = (unix_secs / DAY) as i64;
+        let secs_of_day = unix_secs % DAY;
+        let (_, month, day) = civil_from_days(days);
+        // 1970-01-01 was a Thursday
+        let weekday = ((days + 4) % 7) as u32;
+
+        let day_ok = match (self.day_restricted, self.weekday_restricted) {
+            (true, true) => self.day.matches(day) || self.weekday.matches(weekday),
+            _ => self.day.matches(day) && self.weekday.matches(weekday),
+        };
+        self.minute.matches((secs_of_day / 60 % 60) as u32)
+            && self.hour.matches((secs_of_day / 3_600) as u32)
+            && self.month.matches(month)
+            && day_ok
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Schedule {
+    // None trades every cycle
+    pub cron: Option<CronExpr>,
+    // UTC midnights of days with no trading
+    pub skip_dates: Vec<u64>,
+    pub skip_after: Vec<u64>,
+    // Cycle length in seconds, to find "the first cycle after"
+    pub cycle: u64,
+}
+
+impl Schedule {
+    pub fn new(cycle: u64) -> Self {
+        Self { cycle, ..Default::default() }
+    }
+
+    pub fn with_cron(mut self, expr: &str) -> Result<Self, String> {
+        self.cron = Some(CronExpr::parse(expr)?);
+        Ok(self)
+    }
+
+    /// Comma-separated `YYYY-MM-DD` list.
+    pub fn with_skip_dates(mut self, list: &str) -> Result<Self, String> {
+        for date in list.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            self.skip_dates.push(parse_date(date).ok_or_else(|| format!("bad date '{}', want YYYY-MM-DD", date))?);
+        }
+        Ok(self)
+    }
+
+    /// Comma-separated `YYYY-MM-DDTHH:MM[:SS]Z` list.
+    pub fn with_skip_after(mut self, list: &str) -> Result<Self, String> {
+        for instant in list.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            self.skip_after.push(parse_instant(instant).ok_or_else(|| format!("bad instant '{}', want YYYY-MM-DDTHH:MMZ", instant))?);
+        }
+        Ok(self)
+    }
+
+    /// BOT_SCHEDULE, BOT_SKIP_DATES and BOT_SKIP_AFTER; every cycle when unset.
+    pub fn from_env(cycle: u64) -> Result<Self, String> {
+        let mut schedule = Self::new(cycle);
+        if let Ok(v) = std::env::var("BOT_SCHEDULE") {
+            schedule = schedule.with_cron(&v).map_err(|e| format!("Invalid BOT_SCHEDULE: {}", e))?;
+        }
+        if let Ok(v) = std::env::var("BOT_SKIP_DATES") {
+            schedule = schedule.with_skip_dates(&v).map_err(|e| format!("Invalid BOT_SKIP_DATES: {}", e))?;
+        }
+        if let Ok(v) = std::env::var("BOT_SKIP_AFTER") {
+            schedule = schedule.with_skip_after(&v).map_err(|e| format!("Invalid BOT_SKIP_AFTER: {}", e))?;
+        }
+        Ok(schedule)
+    }
+
+    /// Why the cycle starting at `start` shouldn't be traded, or None to trade it.
+    pub fn skip_reason(&self, start: u64) -> Option<String> {
+        let midnight = start - start % DAY;
+        if self.skip_dates.contains(&midnight) {
+            return Some("skip date".to_string());
+        }
+        // First cycle start at or after the instant
+        if self.skip_after.iter().any(|at| at.div_ceil(self.cycle.max(1)) * self.cycle.max(1) == start) {
+            return Some("first cycle after a skip-after instant".to_string());
+        }
+        match &self.cron {
+            Some(cron) if !cron.matches(start) => Some("outside BOT_SCHEDULE".to_string()),
+            _ => None,
+        }
+    }
+
+    pub fn is_unrestricted(&self) -> bool {
+        self.cron.is_none() && self.skip_dates.is_empty() && self.skip_after.is_empty()
+    }
+}
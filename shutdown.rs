@@ -0,0 +1,53 @@
+//! SIGINT/SIGTERM for the trading loop. The handler only records the
+//! signal; the loop notices it between steps, so an order already in flight
+//! is seen through before the bot cancels what rests, optionally flattens
+//! (risk.liquidate_on_shutdown) and exits. A second signal exits at once.
+
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::time::Duration;
+
+use crate::clock::Clock;
+
+static SIGNAL: AtomicI32 = AtomicI32::new(0);
+
+// Longest a shutdown-aware sleep goes without looking at SIGNAL
+const SLICE: Duration = Duration::from_millis(200);
+
+#[cfg(unix)]
+extern "C" fn on_signal(signal: libc::c_int) {
+    if SIGNAL.swap(signal, Ordering::SeqCst) != 0 {
+        // Only async-signal-safe calls in here
+        unsafe { libc::_exit(128 + signal) };
+    }
+}
+
+/// Route SIGINT and SIGTERM to `requested`; a no-op off unix.
+pub fn install() {
+    #[cfg(unix)]
+    unsafe {
+        libc::signal(libc::SIGINT, on_signal as *const () as libc::sighandler_t);
+        libc::signal(libc::SIGTERM, on_signal as *const () as libc::sighandler_t);
+    }
+}
+
+/// The signal that asked for a shutdown, if one has.
+pub fn requested() -> Option<&'static str> {
+    match SIGNAL.load(Ordering::SeqCst) {
+        0 => None,
+        #[cfg(unix)]
+        libc::SIGINT => Some("SIGINT"),
+        #[cfg(unix)]
+        libc::SIGTERM => Some("SIGTERM"),
+        _ => Some("signal"),
+    }
+}
+
+/// `clock.sleep(duration)`, cut short once a shutdown is requested.
+pub fn sleep(clock: &dyn Clock, duration: Duration) {
+    let mut left = duration;
+    while !left.is_zero() && requested().is_none() {
+        let step = left.min(SLICE);
+        clock.sleep(step);
+        left -= step;
+    }
+}
@@ -0,0 +1,329 @@
+use std::str::FromStr;
+
+use base64::{Engine as _, engine::general_purpose};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::{Address, Signature, H256, U256};
+use ethers::utils::keccak256;
+use hmac::{Hmac, Mac};
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+use serde::Serialize;
+use sha2::Sha256;
+
+// EIP-712 Constants
+pub const EIP712_DOMAIN_NAME: &str = "Polymarket CTF Exchange";
+pub const EIP712_DOMAIN_VERSION: &str = "1";
+pub const CLOB_AUTH_DOMAIN_NAME: &str = "ClobAuthDomain";
+pub const CLOB_AUTH_MESSAGE: &str = "This message attests that I control the given wallet";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderSide {
+    Buy,
+    Sell,
+}
+
+impl OrderSide {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderSide::Buy => "BUY",
+            OrderSide::Sell => "SELL",
+        }
+    }
+}
+
+impl std::fmt::Display for OrderSide {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Raw 6-decimal amounts as signed into the order struct.
+/// BUY:  maker gives USDC, taker gives shares.
+/// SELL: maker gives shares, taker gives USDC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrderAmounts {
+    pub maker_amount: u64,
+    pub taker_amount: u64,
+}
+
+impl OrderAmounts {
+    pub fn new(side: OrderSide, price: f64, size: u32) -> Self {
+        let shares = (size as u64) * 1_000_000;
+        let price_in_usdc = (price * 1_000_000.0).round() as u64;
+        let usdc = (size as u64) * price_in_usdc;
+
+        match side {
+            OrderSide::Buy => Self { maker_amount: usdc, taker_amount: shares },
+            OrderSide::Sell => Self { maker_amount: shares, taker_amount: usdc },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PolymarketOrder {
+    pub salt: String,
+    pub maker: String,
+    pub signer: String,
+    pub taker: String,
+    #[serde(rename = "tokenId")]
+    pub token_id: String,
+    #[serde(rename = "makerAmount")]
+    pub maker_amount: String,
+    #[serde(rename = "takerAmount")]
+    pub taker_amount: String,
+    pub expiration: String,
+    pub nonce: String,
+    #[serde(rename = "feeRateBps")]
+    pub fee_rate_bps: String,
+    pub side: String,
+    #[serde(rename = "signatureType")]
+    pub signature_type: u8,
+}
+
+// ==========================================
+// 🔐 EIP-712 SIGNING
+// ==========================================
+
+pub struct Eip712Signer {
+    pub wallet: LocalWallet,
+    pub chain_id: u64,
+    pub exchange: Address,
+}
+
+impl Eip712Signer {
+    pub fn new(wallet: LocalWallet, chain_id: u64, exchange: Address) -> Self {
+        Self { wallet, chain_id, exchange }
+    }
+
+    // Must match ORDER_TYPEHASH in the exchange's OrderStructs.sol, where
+    // side and signatureType are uint8 (encoded as full words all the same)
+    pub fn encode_type(type_name: &str) -> String {
+        format!(
+            "{}(uint256 salt,address maker,address signer,address taker,uint256 tokenId,uint256 makerAmount,uint256 takerAmount,uint256 expiration,uint256 nonce,uint256 feeRateBps,uint8 side,uint8 signatureType)",
+            type_name
+        )
+    }
+
+    pub fn hash_type(type_name: &str) -> H256 {
+        H256::from(keccak256(Self::encode_type(type_name).as_bytes()))
+    }
+
+    pub fn hash_domain(&self) -> H256 {
+        let domain_separator =
+            "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)";
+        let domain_type_hash = H256::from(keccak256(domain_separator.as_bytes()));
+        
+        let name_hash = H256::from(keccak256(EIP712_DOMAIN_NAME.as_bytes()));
+        let version_hash = H256::from(keccak256(EIP712_DOMAIN_VERSION.as_bytes()));
+        let chain_id = U256::from(self.chain_id);
+        let verifying_contract = self.exchange;
+
+        let mut encoded = Vec::new();
+        encoded.extend_from_slice(domain_type_hash.as_bytes());
+        encoded.extend_from_slice(name_hash.as_bytes());
+        encoded.extend_from_slice(version_hash.as_bytes());
+        
+        let mut chain_id_bytes = [0u8; 32];
+        chain_id.to_big_endian(&mut chain_id_bytes);
+        encoded.extend_from_slice(&chain_id_bytes);
+        
+        let mut contract_bytes = [0u8; 32];
+        contract_bytes[12..].copy_from_slice(verifying_contract.as_bytes());
+        encoded.extend_from_slice(&contract_bytes);
+
+        H256::from(keccak256(&encoded))
+    }
+
+    pub fn hash_struct(&self, order: &PolymarketOrder) -> H256 {
+        let type_hash = Self::hash_type("Order");
+        
+        let salt = U256::from_dec_str(&order.salt).unwrap_or(U256::zero());
+        let maker = Address::from_str(&order.maker).unwrap_or(Address::zero());
+        let signer = Address::from_str(&order.signer).unwrap_or(Address::zero());
+        let taker = Address::from_str(&order.taker).unwrap_or(Address::zero());
+        let token_id = U256::from_dec_str(&order.token_id).unwrap_or(U256::zero());
+        let maker_amount = U256::from_dec_str(&order.maker_amount).unwrap_or(U256::zero());
+        let taker_amount = U256::from_dec_str(&order.taker_amount).unwrap_or(U256::zero());
+        let expiration = U256::from_dec_str(&order.expiration).unwrap_or(U256::zero());
+        let nonce = U256::from_dec_str(&order.nonce).unwrap_or(U256::zero());
+        let fee_rate = U256::from_dec_str(&order.fee_rate_bps).unwrap_or(U256::zero());
+        let side = if order.side == "BUY" { U256::zero() } else { U256::one() };
+        let sig_type = U256::from(order.signature_type);
+
+        let mut encoded = Vec::new();
+        encoded.extend_from_slice(type_hash.as_bytes());
+        
+        let mut temp = [0u8; 32];
+        
+        salt.to_big_endian(&mut temp);
+        encoded.extend_from_slice(&temp);
+        
+        temp = [0u8; 32];
+        temp[12..].copy_from_slice(maker.as_bytes());
+        encoded.extend_from_slice(&temp);
+        
+        temp = [0u8; 32];
+        temp[12..].copy_from_slice(signer.as_bytes());
+        encoded.extend_from_slice(&temp);
+        
+        temp = [0u8; 32];
+        temp[12..].copy_from_slice(taker.as_bytes());
+        encoded.extend_from_slice(&temp);
+        
+        token_id.to_big_endian(&mut temp);
+        encoded.extend_from_slice(&temp);
+        
+        maker_amount.to_big_endian(&mut temp);
+        encoded.extend_from_slice(&temp);
+        
+        taker_amount.to_big_endian(&mut temp);
+        encoded.extend_from_slice(&temp);
+        
+        expiration.to_big_endian(&mut temp);
+        encoded.extend_from_slice(&temp);
+        
+        nonce.to_big_endian(&mut temp);
+        encoded.extend_from_slice(&temp);
+        
+        fee_rate.to_big_endian(&mut temp);
+        encoded.extend_from_slice(&temp);
+        
+        side.to_big_endian(&mut temp);
+        encoded.extend_from_slice(&temp);
+        
+        sig_type.to_big_endian(&mut temp);
+        encoded.extend_from_slice(&temp);
+
+        H256::from(keccak256(&encoded))
+    }
+
+    /// Full EIP-712 digest of the order. The CLOB uses this as the order ID,
+    /// so it doubles as our client-side order identity before submission.
+    pub fn order_hash(&self, order: &PolymarketOrder) -> H256 {
+        let domain_separator = self.hash_domain();
+        let struct_hash = self.hash_struct(order);
+
+        let mut message = Vec::new();
+        message.push(0x19);
+        message.push(0x01);
+        message.extend_from_slice(domain_separator.as_bytes());
+        message.extend_from_slice(struct_hash.as_bytes());
+
+        H256::from(keccak256(&message))
+    }
+
+    pub fn sign_order(&self, order: &PolymarketOrder) -> Result<Signature, Box<dyn std::error::Error>> {
+        let message_hash = self.order_hash(order);
+        
+        let signature = self.wallet.sign_hash(message_hash)?;
+        Ok(signature)
+    }
+
+    // ClobAuth domain has no verifyingContract: EIP712Domain(name, version, chainId)
+    pub fn hash_clob_auth_domain(&self) -> H256 {
+        let domain_type_hash = H256::from(keccak256(
+            "EIP712Domain(string name,string version,uint256 chainId)".as_bytes()
+        ));
+        let name_hash = H256::from(keccak256(CLOB_AUTH_DOMAIN_NAME.as_bytes()));
+        let version_hash = H256::from(keccak256(EIP712_DOMAIN_VERSION.as_bytes()));
+
+        let mut encoded = Vec::new();
+        encoded.extend_from_slice(domain_type_hash.as_bytes());
+        encoded.extend_from_slice(name_hash.as_bytes());
+        encoded.extend_from_slice(version_hash.as_bytes());
+
+        let mut chain_id_bytes = [0u8; 32];
+        U256::from(self.chain_id).to_big_endian(&mut chain_id_bytes);
+        encoded.extend_from_slice(&chain_id_bytes);
+
+        H256::from(keccak256(&encoded))
+    }
+
+    pub fn hash_clob_auth(address: Address, timestamp: &str, nonce: u64) -> H256 {
+        let type_hash = H256::from(keccak256(
+            "ClobAuth(address address,string timestamp,uint256 nonce,string message)".as_bytes()
+        ));
+
+        let mut encoded = Vec::new();
+        encoded.extend_from_slice(type_hash.as_bytes());
+
+        let mut temp = [0u8; 32];
+        temp[12..].copy_from_slice(address.as_bytes());
+        encoded.extend_from_slice(&temp);
+
+        encoded.extend_from_slice(&keccak256(timestamp.as_bytes()));
+
+        temp = [0u8; 32];
+        U256::from(nonce).to_big_endian(&mut temp);
+        encoded.extend_from_slice(&temp);
+
+        encoded.extend_from_slice(&keccak256(CLOB_AUTH_MESSAGE.as_bytes()));
+
+        H256::from(keccak256(&encoded))
+    }
+
+    /// L1 auth: sign the ClobAuth typed-data message with the wallet itself.
+    /// Used by the key-management endpoints (create/derive API keys).
+    pub fn sign_clob_auth(&self, timestamp: &str, nonce: u64) -> Result<Signature, Box<dyn std::error::Error>> {
+        let domain_separator = self.hash_clob_auth_domain();
+        let struct_hash = Self::hash_clob_auth(self.wallet.address(), timestamp, nonce);
+
+        let mut message = Vec::new();
+        message.push(0x19);
+        message.push(0x01);
+        message.extend_from_slice(domain_separator.as_bytes());
+        message.extend_from_slice(struct_hash.as_bytes());
+
+        let message_hash = H256::from(keccak256(&message));
+
+        let signature = self.wallet.sign_hash(message_hash)?;
+        Ok(signature)
+    }
+}
+
+/// L2 API key triple returned by create/derive-api-key.
+#[derive(Debug, Clone)]
+pub struct ApiCredentials {
+    pub api_key: String,
+    pub secret: String,
+    pub passphrase: String,
+}
+
+impl ApiCredentials {
+    /// Headers for an HMAC-authenticated (L2) CLOB request.
+    pub fn l2_headers(&self, address: Address, timestamp: &str, method: &str, request_path: &str, body: &str)
+        -> Result<HeaderMap, Box<dyn std::error::Error>> {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+        let sig_base64 = build_hmac_signature(&self.secret, timestamp, method, request_path, body)?;
+
+        // Match Python headers EXACTLY
+        headers.insert("POLY_ADDRESS", HeaderValue::from_str(&format!("{:?}", address).to_lowercase())?);
+        headers.insert("POLY_SIGNATURE", HeaderValue::from_str(&sig_base64)?);
+        headers.insert("POLY_TIMESTAMP", HeaderValue::from_str(timestamp)?);
+        headers.insert("POLY_API_KEY", HeaderValue::from_str(&self.api_key)?);
+        headers.insert("POLY_PASSPHRASE", HeaderValue::from_str(&self.passphrase)?);
+
+        Ok(headers)
+    }
+}
+
+/// L2 HMAC signature, same scheme as py_clob_client's `build_hmac_signature`:
+/// the secret is base64url-decoded to raw key bytes, the message is
+/// timestamp + method + requestPath + body, and the digest is base64url-encoded.
+pub fn build_hmac_signature(secret: &str, timestamp: &str, method: &str, request_path: &str, body: &str)
+    -> Result<String, Box<dyn std::error::Error>> {
+    let key = general_purpose::URL_SAFE.decode(secret)
+        .map_err(|e| format!("Invalid API secret (expected base64url): {}", e))?;
+
+    // Sign the exact bytes that go on the wire; callers pass upper-case methods
+    let message = format!("{}{}{}{}", timestamp, method, request_path, body);
+
+    type HmacSha256 = Hmac<Sha256>;
+    let mut mac = HmacSha256::new_from_slice(&key)
+        .map_err(|_| "Invalid HMAC key")?;
+    mac.update(message.as_bytes());
+    let signature = mac.finalize();
+
+    Ok(general_purpose::URL_SAFE.encode(signature.into_bytes()))
+}
@@ -0,0 +1,306 @@
+//! SQLite store (feature `sqlite`): trades, fills, aborts and traded markets
+//! in one queryable file, `bot.db` in the profile's data directory unless
+//! BOT_DB names another. The CSV/JSONL journal and traded_markets.json are
+//! still written; this is the copy to query.
+//!
+//! WAL mode and a busy timeout let several processes (profiles sharing a
+//! BOT_DB, or a reader in another shell) use the file at once, and every
+//! insert commits on its own, so a crash loses nothing already written.
+//!
+//! The schema is versioned with `PRAGMA user_version`: `open` applies the
+//! migrations the file hasn't had, in order and in one transaction, and
+//! refuses a file from a newer build instead of writing to a schema it
+//! doesn't know. `pending` lists what `open` would apply without touching
+//! the file, for `--dry-run`.
+
+use std::path::Path;
+use std::time::Duration;
+
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension, TransactionBehavior};
+
+use crate::trade_log::TradeRecord;
+use crate::traded_markets::TradedMarket;
+
+// Files from before versioning already have these tables, hence IF NOT EXISTS
+const SCHEMA_V1: &str = "
+CREATE TABLE IF NOT EXISTS trades (
+    id INTEGER PRIMARY KEY,
+    title TEXT NOT NULL,
+    link TEXT NOT NULL,
+    status TEXT NOT NULL,
+    market TEXT NOT NULL,
+    strategy TEXT NOT NULL,
+    side TEXT NOT NULL,
+    token_id TEXT NOT NULL,
+    order_id TEXT NOT NULL,
+    entry_time INTEGER,
+    entry_price REAL,
+    size REAL,
+    sl_time INTEGER,
+    sl_price REAL,
+    sl_triggered INTEGER,
+    exit_time INTEGER,
+    exit_price REAL,
+    fees REAL,
+    slippage REAL,
+    pnl REAL,
+    final_status TEXT NOT NULL,
+    notes TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS trades_market ON trades (market);
+CREATE TABLE IF NOT EXISTS fills (
+    id INTEGER PRIMARY KEY,
+    ts INTEGER NOT NULL,
+    order_id TEXT NOT NULL,
+    token_id TEXT NOT NULL,
+    side TEXT NOT NULL,
+    -- Negative when a fill is rolled back
+    size REAL NOT NULL,
+    price REAL NOT NULL,
+    fee REAL NOT NULL
+);
+CREATE INDEX IF NOT EXISTS fills_order ON fills (order_id);
+CREATE TABLE IF NOT EXISTS aborts (
+    id INTEGER PRIMARY KEY,
+    ts INTEGER NOT NULL,
+    market TEXT NOT NULL,
+    -- monitoring or entry
+    stage TEXT NOT NULL,
+    ask REAL
+);
+CREATE TABLE IF NOT EXISTS traded_markets (
+    slug TEXT PRIMARY KEY,
+    marked_at INTEGER NOT NULL,
+    reason TEXT NOT NULL
+);
+";
+
+/// One schema change. Append new ones to MIGRATIONS; never edit one that
+/// has shipped.
+#[derive(Debug, PartialEq)]
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    sql: &'static str,
+}
+
+pub const MIGRATIONS: &[Migration] = &[
+    Migration { version: 1, name: "trades, fills, aborts and traded markets", sql: SCHEMA_V1 },
+];
+
+/// The schema version this build writes.
+pub fn latest_version() -> u32 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
+
+fn user_version(conn: &Connection) -> Result<u32, String> {
+    conn.pragma_query_value(None, "user_version", |row| row.get(0)).map_err(err)
+}
+
+fn newer_than_known(path: &str, version: u32) -> String {
+    format!("Database {} is at schema v{}, newer than this build's v{}; upgrade the bot", path, version, latest_version())
+}
+
+/// The migrations `Store::open` would apply to `path`, read without
+/// creating or changing the file.
+pub fn pending(path: &str) -> Result<Vec<&'static Migration>, String> {
+    if !Path::new(path).exists() {
+        return Ok(MIGRATIONS.iter().collect());
+    }
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| format!("Cannot open database {}: {}", path, e))?;
+    let version = user_version(&conn)?;
+    if version > latest_version() {
+        return Err(newer_than_known(path, version));
+    }
+    Ok(MIGRATIONS.iter().filter(|m| m.version > version).collect())
+}
+
+/// One fill as stored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FillRow {
+    pub ts: u64,
+    pub order_id: String,
+    pub token_id: String,
+    pub side: String,
+    pub size: f64,
+    pub price: f64,
+    pub fee: f64,
+}
+
+/// One abort as stored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AbortRow {
+    pub ts: u64,
+    pub market: String,
+    pub stage: String,
+    pub ask: Option<f64>,
+}
+
+pub struct Store {
+    conn: Connection,
+    migrated: Vec<&'static Migration>,
+}
+
+fn err(e: rusqlite::Error) -> String {
+    e.to_string()
+}
+
+impl Store {
+    pub fn open(path: &str) -> Result<Self, String> {
+        let mut conn = Connection::open(path).map_err(|e| format!("Cannot open database {}: {}", path, e))?;
+        conn.busy_timeout(Duration::from_secs(5)).map_err(err)?;
+        conn.pragma_update(None, "journal_mode", "WAL").map_err(err)?;
+        let migrated = migrate(&mut conn, path)?;
+        Ok(Self { conn, migrated })
+    }
+
+    /// What `open` applied, oldest first; empty when the file was current.
+    pub fn migrated(&self) -> &[&'static Migration] {
+        &self.migrated
+    }
+
+    pub fn schema_version(&self) -> Result<u32, String> {
+        user_version(&self.conn)
+    }
+
+    pub fn insert_trade(&self, r: &TradeRecord) -> Result<(), String> {
+        self.conn.execute(
+            "INSERT INTO trades (title, link, status, market, strategy, side, token_id, order_id,
+                entry_time, entry_price, size, sl_time, sl_price, sl_triggered, exit_time, exit_price,
+                fees, slippage, pnl, final_status, notes)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21)",
+            params![
+                r.title, r.link, r.status, r.market, r.strategy, r.side, r.token_id, r.order_id,
+                r.entry_time, r.entry_price, r.size, r.sl_time, r.sl_price, r.sl_triggered, r.exit_time, r.exit_price,
+                r.fees, r.slippage, r.pnl, r.final_status, r.notes,
+            ],
+        ).map_err(err)?;
+        Ok(())
+    }
+
+    /// Every trade row of `market`, oldest first.
+    pub fn trades(&self, market: &str) -> Result<Vec<TradeRecord>, String> {
+        let mut stmt = self.conn.prepare(
+            "SELECT title, link, status, market, strategy, side, token_id, order_id,
+                entry_time, entry_price, size, sl_time, sl_price, sl_triggered, exit_time, exit_price,
+                fees, slippage, pnl, final_status, notes
+             FROM trades WHERE market = ?1 ORDER BY id",
+        ).map_err(err)?;
+        let rows = stmt.query_map([market], |row| Ok(TradeRecord {
+            title: row.get(0)?,
+            link: row.get(1)?,
+            status: row.get(2)?,
+            market: row.get(3)?,
+            strategy: row.get(4)?,
+            side: row.get(5)?,
+            token_id: row.get(6)?,
+            order_id: row.get(7)?,
+            entry_time: row.get(8)?,
+            entry_price: row.get(9)?,
+            size: row.get(10)?,
+            sl_time: row.get(11)?,
+            sl_price: row.get(12)?,
+            sl_triggered: row.get(13)?,
+            exit_time: row.get(14)?,
+            exit_price: row.get(15)?,
+            fees: row.get(16)?,
+            slippage: row.get(17)?,
+            pnl: row.get(18)?,
+            final_status: row.get(19)?,
+            notes: row.get(20)?,
+        })).map_err(err)?;
+        rows.collect::<Result<_, _>>().map_err(err)
+    }
+
+    pub fn insert_fill(&self, fill: &FillRow) -> Result<(), String> {
+        self.conn.execute(
+            "INSERT INTO fills (ts, order_id, token_id, side, size, price, fee) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![fill.ts, fill.order_id, fill.token_id, fill.side, fill.size, fill.price, fill.fee],
+        ).map_err(err)?;
+        Ok(())
+    }
+
+    /// Fills of `order_id`, oldest first.
+    pub fn fills(&self, order_id: &str) -> Result<Vec<FillRow>, String> {
+        let mut stmt = self.conn.prepare(
+            "SELECT ts, order_id, token_id, side, size, price, fee FROM fills WHERE order_id = ?1 ORDER BY id",
+        ).map_err(err)?;
+        let rows = stmt.query_map([order_id], |row| Ok(FillRow {
+            ts: row.get(0)?,
+            order_id: row.get(1)?,
+            token_id: row.get(2)?,
+            side: row.get(3)?,
+            size: row.get(4)?,
+            price: row.get(5)?,
+            fee: row.get(6)?,
+        })).map_err(err)?;
+        rows.collect::<Result<_, _>>().map_err(err)
+    }
+
+    pub fn insert_abort(&self, abort: &AbortRow) -> Result<(), String> {
+        self.conn.execute(
+            "INSERT INTO aborts (ts, market, stage, ask) VALUES (?1, ?2, ?3, ?4)",
+            params![abort.ts, abort.market, abort.stage, abort.ask],
+        ).map_err(err)?;
+        Ok(())
+    }
+
+    pub fn aborts(&self) -> Result<Vec<AbortRow>, String> {
+        let mut stmt = self.conn.prepare("SELECT ts, market, stage, ask FROM aborts ORDER BY id").map_err(err)?;
+        let rows = stmt.query_map([], |row| Ok(AbortRow {
+            ts: row.get(0)?,
+            market: row.get(1)?,
+            stage: row.get(2)?,
+            ask: row.get(3)?,
+        })).map_err(err)?;
+        rows.collect::<Result<_, _>>().map_err(err)
+    }
+
+    /// Keeps the first reason when a market is marked twice, like
+    /// `TradedMarkets::insert`.
+    pub fn mark_traded(&self, slug: &str, now: u64, reason: &str) -> Result<(), String> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO traded_markets (slug, marked_at, reason) VALUES (?1, ?2, ?3)",
+            params![slug, now, reason],
+        ).map_err(err)?;
+        Ok(())
+    }
+
+    pub fn traded_market(&self, slug: &str) -> Result<Option<TradedMarket>, String> {
+        self.conn.query_row(
+            "SELECT marked_at, reason FROM traded_markets WHERE slug = ?1",
+            [slug],
+            |row| Ok(TradedMarket { marked_at: row.get(0)?, reason: row.get(1)? }),
+        ).optional().map_err(err)
+    }
+
+    /// Markets marked at or after `since`.
+    pub fn traded_markets(&self, since: u64) -> Result<Vec<(String, TradedMarket)>, String> {
+        let mut stmt = self.conn.prepare(
+            "SELECT slug, marked_at, reason FROM traded_markets WHERE marked_at >= ?1 ORDER BY marked_at",
+        ).map_err(err)?;
+        let rows = stmt.query_map([since], |row| Ok((row.get(0)?, TradedMarket { marked_at: row.get(1)?, reason: row.get(2)? })))
+            .map_err(err)?;
+        rows.collect::<Result<_, _>>().map_err(err)
+    }
+}
+
+/// Apply the migrations past the file's version. The version is read
+/// inside the write transaction, so two processes opening the same file
+/// can't both apply a migration.
+fn migrate(conn: &mut Connection, path: &str) -> Result<Vec<&'static Migration>, String> {
+    let failed = |e: rusqlite::Error| format!("Cannot migrate database {}: {}", path, e);
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate).map_err(failed)?;
+    let version = user_version(&tx)?;
+    if version > latest_version() {
+        return Err(newer_than_known(path, version));
+    }
+    let pending: Vec<&'static Migration> = MIGRATIONS.iter().filter(|m| m.version > version).collect();
+    for migration in &pending {
+        tx.execute_batch(migration.sql).map_err(failed)?;
+        tx.pragma_update(None, "user_version", migration.version).map_err(failed)?;
+    }
+    tx.commit().map_err(failed)?;
+    Ok(pending)
+}
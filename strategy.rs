@@ -0,0 +1,646 @@
+//! Entry decisions for one up/down market. Nothing here touches the network,
+//! the filesystem or the wall clock, so the live loop, scenario tests and a
+//! wasm32 replay build all run the same code.
+
+use serde::{Deserialize, Serialize};
+
+use crate::fill_model::{FillModel, FillSimulator};
+
+// Every up/down market runs for 15 minutes from its slug timestamp
+pub const MARKET_DURATION: u64 = 900;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum TradeSide {
+    Yes,
+    No,
+    Both,
+}
+
+impl TradeSide {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "YES" => Some(Self::Yes),
+            "NO" => Some(Self::No),
+            "BOTH" => Some(Self::Both),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TradeSide::Yes => "YES",
+            TradeSide::No => "NO",
+            TradeSide::Both => "BOTH",
+        }
+    }
+
+    fn allows(self, outcome: Outcome) -> bool {
+        matches!((self, outcome), (Self::Both, _) | (Self::Yes, Outcome::Yes) | (Self::No, Outcome::No))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Outcome {
+    Yes,
+    No,
+}
+
+impl Outcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Yes => "YES",
+            Self::No => "NO",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyParams {
+    pub trade_side: TradeSide,
+    // Best bid at or above this triggers an entry
+    pub entry_price: f64,
+    // Any ask above this means the market is decided; stop trading it
+    pub abort_ask_price: f64,
+    pub position_size: u32,
+    // Trading starts this many seconds before close
+    pub market_window: u64,
+    // Give up this long after the window opens without an entry
+    pub entry_timeout: u64,
+    // How to pick a side when YES and NO trigger on the same tick
+    #[serde(default)]
+    pub tie_break: TieBreak,
+    // How the entry order is worked once the signal fires
+    #[serde(default)]
+    pub entry_exec: EntryExecution,
+}
+
+impl StrategyParams {
+    /// Cross-field problems that would make the monitor misbehave, one
+    /// message per problem; empty when the parameters are usable.
+    pub fn validate(&self) -> Vec<String> {
+        self.validate_within(MARKET_DURATION)
+    }
+
+    /// `validate` for markets that last `duration` seconds.
+    pub fn validate_within(&self, duration: u64) -> Vec<String> {
+        let mut errors = Vec::new();
+        if !(self.entry_price > 0.0 && self.entry_price < 1.0) {
+            errors.push(format!("entry_price {} must be between 0 and 1", self.entry_price));
+        }
+        if self.abort_ask_price <= self.entry_price || self.abort_ask_price > 1.0 {
+            errors.push(format!("abort_ask_price {} must be above entry_price {} and at most 1",
+                self.abort_ask_price, self.entry_price));
+        }
+        if self.position_size == 0 {
+            errors.push("position_size must be at least 1 share".to_string());
+        }
+        if self.market_window == 0 || self.market_window > duration {
+            errors.push(format!("market_window {}s must be within the {}s market", self.market_window, duration));
+        }
+        if self.entry_timeout >= self.market_window {
+            errors.push(format!("entry_timeout {}s must be shorter than market_window {}s", self.entry_timeout, self.market_window));
+        }
+        errors
+    }
+
+    /// `entry_exec.quote`, with a limit bid held at or under `entry_price`.
+    pub fn entry_quote(&self, book: &OrderBook, started: u64, now: u64, deadline: u64) -> Option<Quote> {
+        let quote = self.entry_exec.quote(book, started, now, deadline)?;
+        if self.entry_exec.style != EntryStyle::Limit {
+            return Some(quote);
+        }
+        Some(Quote { price: quote.price.min(floor_to_tick(self.entry_price)), ..quote })
+    }
+}
+
+/// One way to choose between YES and NO when both trigger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TieCriterion {
+    // Higher best bid: the side the market leans to harder
+    Bid,
+    // Tighter ask - bid spread
+    Spread,
+    // More shares bid, to sell into on the way out
+    Depth,
+    // Fixed preferences, which always decide
+    Yes,
+    No,
+}
+
+impl TieCriterion {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "bid" => Some(Self::Bid),
+            "spread" => Some(Self::Spread),
+            "depth" => Some(Self::Depth),
+            "yes" => Some(Self::Yes),
+            "no" => Some(Self::No),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Bid => "bid",
+            Self::Spread => "spread",
+            Self::Depth => "depth",
+            Self::Yes => "yes",
+            Self::No => "no",
+        }
+    }
+
+    // Score for one side; higher wins
+    fn score(&self, outcome: Outcome, book: &OrderBook) -> f64 {
+        let bid = book.best_bid.unwrap_or(0.0);
+        match self {
+            Self::Bid => bid,
+            Self::Spread => -(book.best_ask.unwrap_or(1.0) - bid),
+            Self::Depth => book.bid_size,
+            Self::Yes => if outcome == Outcome::Yes { 1.0 } else { 0.0 },
+            Self::No => if outcome == Outcome::No { 1.0 } else { 0.0 },
+        }
+    }
+}
+
+/// Criteria tried in order until one separates the sides. If none does,
+/// YES wins, reported as decided by `yes`. The default (higher bid, then
+/// YES) is the original hardcoded rule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TieBreak(pub Vec<TieCriterion>);
+
+impl Default for TieBreak {
+    fn default() -> Self {
+        Self(vec![TieCriterion::Bid])
+    }
+}
+
+impl TieBreak {
+    /// Comma-separated criteria, e.g. `spread,depth,bid`.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let criteria = s.split(',')
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .map(|c| TieCriterion::parse(c)
+                .ok_or_else(|| format!("unknown tie-break criterion '{}' (expected bid, spread, depth, yes or no)", c)))
+            .collect::<Result<Vec<_>, _>>()?;
+        if criteria.is_empty() {
+            return Err("tie-break needs at least one criterion".to_string());
+        }
+        Ok(Self(criteria))
+    }
+
+    /// The side to enter when both trigger, and the criterion that decided.
+    pub fn decide(&self, yes: &OrderBook, no: &OrderBook) -> (Outcome, TieCriterion) {
+        for criterion in &self.0 {
+            let diff = criterion.score(Outcome::Yes, yes) - criterion.score(Outcome::No, no);
+            if diff.abs() > 1e-9 {
+                return (if diff > 0.0 { Outcome::Yes } else { Outcome::No }, *criterion);
+            }
+        }
+        (Outcome::Yes, TieCriterion::Yes)
+    }
+}
+
+impl std::fmt::Display for TieBreak {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names: Vec<&str> = self.0.iter().map(|c| c.as_str()).collect();
+        f.write_str(&names.join(","))
+    }
+}
+
+/// Touch of one outcome's book, in dollars and shares.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct OrderBook {
+    pub best_ask: Option<f64>,
+    pub ask_size: f64,
+    pub best_bid: Option<f64>,
+    pub bid_size: f64,
+}
+
+/// Where a tick falls relative to the trading window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gate {
+    Waiting { opens_in: u64 },
+    // First tick inside the window; the entry timeout starts here
+    Opened,
+    Open,
+    Closed,
+    TimedOut,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Signal {
+    Hold,
+    Abort { ask: f64 },
+    // `tie` is the criterion that picked this side when both triggered
+    Enter { outcome: Outcome, ask: f64, tie: Option<TieCriterion> },
+}
+
+/// Per-market decision state, advanced once per polling tick.
+#[derive(Debug, Clone)]
+pub struct EntryMonitor {
+    params: StrategyParams,
+    market_start_ts: u64,
+    // Seconds from market open to close
+    duration: u64,
+    window_start: Option<u64>,
+}
+
+impl EntryMonitor {
+    pub fn new(params: StrategyParams, market_start_ts: u64) -> Self {
+        Self { params, market_start_ts, duration: MARKET_DURATION, window_start: None }
+    }
+
+    /// For markets that run `duration` seconds instead of 15 minutes.
+    pub fn with_duration(mut self, duration: u64) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// For a market known to close at `close_ts`.
+    pub fn closing_at(self, close_ts: u64) -> Self {
+        let duration = close_ts.saturating_sub(self.market_start_ts);
+        self.with_duration(duration)
+    }
+
+    pub fn params(&self) -> &StrategyParams {
+        &self.params
+    }
+
+    /// When the entry timeout runs out, once the window has opened.
+    pub fn deadline(&self) -> Option<u64> {
+        self.window_start.map(|start| start + self.params.entry_timeout)
+    }
+
+    /// Timing checks that come before any book is fetched.
+    pub fn on_clock(&mut self, now: u64) -> Gate {
+        let until_close = self.duration.saturating_sub(now.saturating_sub(self.market_start_ts));
+        if until_close > self.params.market_window {
+            self.window_start = None;
+            return Gate::Waiting { opens_in: until_close - self.params.market_window };
+        }
+        if until_close == 0 {
+            return Gate::Closed;
+        }
+
+        match self.window_start {
+            None => {
+                self.window_start = Some(now);
+                Gate::Opened
+            }
+            Some(start) if now - start > self.params.entry_timeout => Gate::TimedOut,
+            Some(_) => Gate::Open,
+        }
+    }
+
+    /// Abort and entry triggers for the current YES/NO books. When both
+    /// sides trigger, the tie-break policy picks one.
+    pub fn on_books(&self, yes: &OrderBook, no: &OrderBook) -> Signal {
+        let p = &self.params;
+        for book in [yes, no] {
+            if let Some(ask) = book.best_ask.filter(|ask| *ask > p.abort_ask_price) {
+                return Signal::Abort { ask };
+            }
+        }
+
+        let triggered = |outcome: Outcome, book: &OrderBook| {
+            let bid = book.best_bid.unwrap_or(0.0);
+            let ask = book.best_ask?;
+            (p.trade_side.allows(outcome) && bid >= p.entry_price && book.ask_size >= p.position_size as f64)
+                .then_some(ask)
+        };
+
+        match (triggered(Outcome::Yes, yes), triggered(Outcome::No, no)) {
+            (Some(yes_ask), Some(no_ask)) => {
+                let (outcome, criterion) = p.tie_break.decide(yes, no);
+                let ask = if outcome == Outcome::Yes { yes_ask } else { no_ask };
+                Signal::Enter { outcome, ask, tie: Some(criterion) }
+            }
+            (Some(ask), None) => Signal::Enter { outcome: Outcome::Yes, ask, tie: None },
+            (None, Some(ask)) => Signal::Enter { outcome: Outcome::No, ask, tie: None },
+            (None, None) => Signal::Hold,
+        }
+    }
+}
+
+/// Shares to buy for `outcome`: the full size on NO, half on YES, never
+/// more than `max_size` (lowered while collateral is short).
+pub fn entry_size(params: &StrategyParams, outcome: Outcome, max_size: u32) -> u32 {
+    let size = match outcome {
+        Outcome::No => params.position_size,
+        Outcome::Yes => params.position_size / 2,
+    };
+    size.min(max_size)
+}
+
+// Exchange price increment; order prices are rounded to it
+pub const TICK: f64 = 0.01;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryStyle {
+    // FOK at the ask on every attempt
+    Fok,
+    // Rest a GTC bid inside the spread, raise it toward the ask as the
+    // deadline nears, and cross with a FOK for the last stretch
+    Passive,
+    // Rest a GTD bid at the entry price (a tick under the ask if that's
+    // lower) until the deadline, moving it when the book does; never cross
+    Limit,
+}
+
+/// What to do with the unfilled remainder of an order that filled only
+/// partly by the end of its fill wait.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PartialFillAction {
+    // Keep the filled shares and leave the remainder working
+    Accept,
+    // Keep the filled shares, cancel the remainder
+    Cancel,
+    // Cancel the remainder and let the caller place a fresh order for it
+    #[default]
+    Requote,
+}
+
+impl PartialFillAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PartialFillAction::Accept => "accept",
+            PartialFillAction::Cancel => "cancel",
+            PartialFillAction::Requote => "requote",
+        }
+    }
+}
+
+/// How an entry is worked between the signal and the entry deadline.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EntryExecution {
+    pub style: EntryStyle,
+    // Fraction of the time to the deadline after which a passive entry
+    // stops resting and crosses the spread
+    pub cross_at: f64,
+}
+
+impl Default for EntryExecution {
+    fn default() -> Self {
+        Self { style: EntryStyle::Fok, cross_at: 0.75 }
+    }
+}
+
+/// The order to send on one entry attempt.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quote {
+    pub price: f64,
+    // Take liquidity with a FOK; otherwise rest a bid at `price`
+    pub cross: bool,
+}
+
+impl EntryExecution {
+    /// `fok`, `limit`, `passive` or `passive:<cross_at>`, e.g. `passive:0.6`.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let (style, cross_at) = match s.trim().split_once(':') {
+            Some((style, cross_at)) => (style, Some(cross_at)),
+            None => (s.trim(), None),
+        };
+        let style = match style {
+            "fok" => EntryStyle::Fok,
+            "passive" => EntryStyle::Passive,
+            "limit" => EntryStyle::Limit,
+            _ => return Err(format!("unknown entry style '{}' (expected fok, passive or limit)", style)),
+        };
+        let mut exec = Self { style, ..Self::default() };
+        if let Some(v) = cross_at {
+            exec.cross_at = v.trim().parse::<f64>().ok().filter(|f| (0.0..=1.0).contains(f))
+                .ok_or_else(|| format!("invalid cross_at '{}': expected a fraction from 0 to 1", v))?;
+        }
+        Ok(exec)
+    }
+
+    /// The quote for an attempt at `now` on an entry that started at
+    /// `started` and must be done by `deadline`, or None without an ask.
+    /// A passive bid starts a tick above the best bid (or on it when the
+    /// spread is one tick) and climbs linearly to a tick under the ask by
+    /// `cross_at`; from there on it crosses at the ask. A limit bid sits a
+    /// tick under the ask; `StrategyParams::entry_quote` caps it at the
+    /// entry price.
+    pub fn quote(&self, book: &OrderBook, started: u64, now: u64, deadline: u64) -> Option<Quote> {
+        let ask = book.best_ask?;
+        let cross = Quote { price: ask, cross: true };
+        match self.style {
+            EntryStyle::Fok => return Some(cross),
+            EntryStyle::Limit => return Some(Quote { price: floor_to_tick(ask - TICK), cross: false }),
+            EntryStyle::Passive => {}
+        }
+        let progress = if deadline <= started { 1.0 } else { now.saturating_sub(started) as f64 / (deadline - started) as f64 };
+        let bid = book.best_bid.unwrap_or(0.0);
+        let top = ask - TICK;
+        if progress >= self.cross_at || bid > top + 1e-9 {
+            return Some(cross);
+        }
+        let start = (bid + TICK).min(top);
+        let price = start + (top - start) * progress / self.cross_at;
+        // Down to the tick, so resting never touches the ask
+        Some(Quote { price: floor_to_tick(price), cross: false })
+    }
+}
+
+fn floor_to_tick(price: f64) -> f64 {
+    ((price + 1e-9) / TICK).floor() / (1.0 / TICK)
+}
+
+/// Prices for selling into the bid a rung at a time: `rungs` even steps
+/// from `bid` down to `slippage` under it, on the tick and never under the
+/// last rung. One rung is just the worst price, a single sweep.
+pub fn sell_ladder(bid: f64, slippage: f64, rungs: u32) -> Vec<f64> {
+    let worst = (((bid - slippage - 1e-9) / TICK).ceil() / (1.0 / TICK)).max(TICK);
+    if rungs <= 1 {
+        return vec![worst];
+    }
+    let mut ladder: Vec<f64> = Vec::new();
+    for i in 0..rungs {
+        let price = floor_to_tick(bid - slippage * i as f64 / (rungs - 1) as f64).max(worst);
+        if ladder.last().is_none_or(|last| (last - price).abs() > 1e-9) {
+            ladder.push(price);
+        }
+    }
+    ladder
+}
+
+impl std::fmt::Display for EntryExecution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.style {
+            EntryStyle::Fok => f.write_str("fok"),
+            EntryStyle::Passive => write!(f, "passive:{}", self.cross_at),
+            EntryStyle::Limit => f.write_str("limit"),
+        }
+    }
+}
+
+/// One slice of a scaled-in entry: `fraction` of the position, bid for
+/// at `price`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Tranche {
+    pub price: f64,
+    pub fraction: f64,
+}
+
+/// Split `size` shares across `tranches` as (price, shares), rounding on
+/// the running total so the shares always add up to `size`. Tranches that
+/// round to nothing are left out.
+pub fn tranche_sizes(tranches: &[Tranche], size: u32) -> Vec<(f64, u32)> {
+    let total: f64 = tranches.iter().map(|t| t.fraction).sum();
+    let mut cumulative = 0.0;
+    let mut assigned = 0;
+    let mut sizes = Vec::new();
+    for (i, tranche) in tranches.iter().enumerate() {
+        cumulative += tranche.fraction;
+        let upto = if i + 1 == tranches.len() { size } else { (size as f64 * cumulative / total).round() as u32 };
+        let shares = upto.saturating_sub(assigned);
+        assigned += shares;
+        if shares > 0 {
+            sizes.push((tranche.price, shares));
+        }
+    }
+    sizes
+}
+
+/// `[strategy] sizing`: stake `balance_pct` percent of the USDC balance on
+/// each entry instead of a fixed `position_size`, in whole shares kept
+/// between `min_shares` and `max_shares` and never more than the balance
+/// buys outright.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BalanceSizing {
+    pub balance_pct: f64,
+    #[serde(default)]
+    pub min_shares: u32,
+    pub max_shares: u32,
+}
+
+impl BalanceSizing {
+    /// Shares `balance_pct` of `balance` buys at `price`, clamped. 0 when
+    /// the whole balance can't buy `min_shares`.
+    pub fn shares(&self, balance: f64, price: f64) -> u32 {
+        let buys = |usdc: f64| if price > 0.0 { (usdc.max(0.0) / price + 1e-9).floor() as u32 } else { 0 };
+        let affordable = buys(balance);
+        if affordable < self.min_shares {
+            return 0;
+        }
+        buys(balance * self.balance_pct / 100.0)
+            .clamp(self.min_shares, self.max_shares.max(self.min_shares))
+            .min(affordable)
+    }
+}
+
+/// `[strategy] trailing_stop`: once the bid has risen `activation` above
+/// the entry, the stop follows `trail` under the highest bid seen.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TrailingStop {
+    pub trail: f64,
+    #[serde(default)]
+    pub activation: f64,
+}
+
+/// The stop on a held position. It starts at the fixed stop price; a
+/// trailing stop ratchets it up with the bid and never lets it back down.
+/// The bid has to stay at or under the stop for `sustain` seconds before
+/// the stop fires, so one thin print doesn't sell the position.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StopLoss {
+    entry_price: f64,
+    stop: f64,
+    trailing: Option<TrailingStop>,
+    sustain: u64,
+    // When the bid went to or under the stop, while it stays there
+    breached_since: Option<u64>,
+}
+
+impl StopLoss {
+    pub fn new(entry_price: f64, stop_price: f64, trailing: Option<TrailingStop>, sustain: u64) -> Self {
+        Self { entry_price, stop: stop_price, trailing, sustain, breached_since: None }
+    }
+
+    pub fn price(&self) -> f64 {
+        self.stop
+    }
+
+    /// Take the best bid at `now`; true once the stop has fired.
+    pub fn on_bid(&mut self, bid: f64, now: u64) -> bool {
+        if let Some(t) = self.trailing {
+            if bid + 1e-9 >= self.entry_price + t.activation {
+                self.stop = self.stop.max(floor_to_tick(bid - t.trail));
+            }
+        }
+        if bid > self.stop + 1e-9 {
+            self.breached_since = None;
+            return false;
+        }
+        let since = *self.breached_since.get_or_insert(now);
+        now.saturating_sub(since) >= self.sustain
+    }
+}
+
+/// One recorded polling tick for replay.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tick {
+    pub ts: u64,
+    pub yes: OrderBook,
+    pub no: OrderBook,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+pub enum ReplayOutcome {
+    Entered { ts: u64, outcome: Outcome, ask: f64, size: u32 },
+    Aborted { ts: u64, ask: f64 },
+    TimedOut { ts: u64 },
+    Closed { ts: u64 },
+    // Recording ended before the monitor reached a decision
+    NoDecision,
+}
+
+/// Run recorded ticks for one market through the live decision path and
+/// report how the bot would have left it. Entries fill in full at the
+/// displayed ask; see `replay_with` for a realistic fill model.
+pub fn replay(params: &StrategyParams, market_start_ts: u64, ticks: &[Tick]) -> ReplayOutcome {
+    replay_with(params, market_start_ts, ticks, &mut FillSimulator::new(FillModel::default()))
+}
+
+/// `replay` with entries sent as FOK orders through `fills`. A missed fill
+/// retries on the next tick, the way the live entry loop does. Passive
+/// entries are replayed as FOK too: recorded touches say nothing about
+/// queue position.
+pub fn replay_with(params: &StrategyParams, market_start_ts: u64, ticks: &[Tick], fills: &mut FillSimulator) -> ReplayOutcome {
+    let mut monitor = EntryMonitor::new(params.clone(), market_start_ts);
+    for tick in ticks {
+        match monitor.on_clock(tick.ts) {
+            Gate::Waiting { .. } => continue,
+            Gate::Closed => return ReplayOutcome::Closed { ts: tick.ts },
+            Gate::TimedOut => return ReplayOutcome::TimedOut { ts: tick.ts },
+            Gate::Opened | Gate::Open => {}
+        }
+        match monitor.on_books(&tick.yes, &tick.no) {
+            Signal::Hold => {}
+            Signal::Abort { ask } => return ReplayOutcome::Aborted { ts: tick.ts, ask },
+            Signal::Enter { outcome, ask, .. } => {
+                let size = entry_size(params, outcome, params.position_size);
+                let book = match outcome {
+                    Outcome::Yes => &tick.yes,
+                    Outcome::No => &tick.no,
+                };
+                let fill = fills.take(book, size as f64, ask, true);
+                if fill.size > 0.0 {
+                    return ReplayOutcome::Entered { ts: tick.ts, outcome, ask: fill.price, size: fill.size as u32 };
+                }
+            }
+        }
+    }
+    ReplayOutcome::NoDecision
+}
@@ -0,0 +1,266 @@
+//! FIFO tax lots from the account's trade and redemption history (the data
+//! API `/activity` feed). Every buy opens a lot; sells and redemptions close
+//! the oldest open lots of that token first, splitting a lot when only part
+//! of it goes. Each closed piece carries its own cost basis, proceeds and
+//! gain, so the export lines up with Form 8949 style imports.
+//!
+//! Redemptions name the market, not the token: the payout is matched to the
+//! outcome whose open shares it equals ($1 a share, the rest expire at $0);
+//! a payout that matches neither is spread evenly over every open share.
+
+use std::collections::{HashMap, VecDeque};
+use std::io::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::timestamps::{self, DisplayTz};
+
+/// One row of the data API `/activity` feed. Other types (splits, merges,
+/// rewards) are parsed but ignored by `FifoBook`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Activity {
+    #[serde(default)]
+    pub timestamp: u64,
+    #[serde(rename = "type", default)]
+    pub kind: String,
+    #[serde(rename = "conditionId", default)]
+    pub condition_id: String,
+    #[serde(default)]
+    pub asset: String,
+    // BUY or SELL for trades
+    #[serde(default)]
+    pub side: String,
+    #[serde(default)]
+    pub size: f64,
+    #[serde(rename = "usdcSize", default)]
+    pub usdc_size: f64,
+    #[serde(default)]
+    pub price: f64,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub outcome: String,
+    #[serde(rename = "transactionHash", default)]
+    pub transaction_hash: String,
+}
+
+pub fn parse_activity(bytes: &[u8]) -> Result<Vec<Activity>, serde_json::Error> {
+    serde_json::from_slice(bytes)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct OpenLot {
+    acquired: u64,
+    quantity: f64,
+    // Per share, fees included
+    unit_cost: f64,
+}
+
+/// Token metadata carried onto each closed lot.
+#[derive(Debug, Clone, Default)]
+struct Token {
+    condition_id: String,
+    title: String,
+    outcome: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ClosedLot {
+    pub token_id: String,
+    pub condition_id: String,
+    pub title: String,
+    pub outcome: String,
+    pub quantity: f64,
+    pub acquired: u64,
+    pub disposed: u64,
+    pub cost_basis: f64,
+    pub proceeds: f64,
+    pub gain: f64,
+    // "sell" or "redeem"
+    pub disposal: String,
+}
+
+#[derive(Debug, Default)]
+pub struct FifoBook {
+    lots: HashMap<String, VecDeque<OpenLot>>,
+    tokens: HashMap<String, Token>,
+    closed: Vec<ClosedLot>,
+    // Shares sold or redeemed beyond what the history shows being bought
+    unmatched: Vec<(String, f64)>,
+}
+
+impl FifoBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply activity in time order, whatever order it arrives in.
+    pub fn from_activity(activity: &[Activity]) -> Self {
+        let mut sorted: Vec<&Activity> = activity.iter().collect();
+        sorted.sort_by_key(|a| a.timestamp);
+        let mut book = Self::new();
+        for a in sorted {
+            book.apply(a);
+        }
+        book
+    }
+
+    pub fn apply(&mut self, a: &Activity) {
+        match (a.kind.as_str(), a.side.as_str()) {
+            ("TRADE", "BUY") => {
+                self.note_token(a);
+                // usdcSize is what was actually paid; price * size as a fallback
+                let cost = if a.usdc_size > 0.0 { a.usdc_size } else { a.price * a.size };
+                if a.size > 0.0 {
+                    self.lots.entry(a.asset.clone()).or_default()
+                        .push_back(OpenLot { acquired: a.timestamp, quantity: a.size, unit_cost: cost / a.size });
+                }
+            }
+            ("TRADE", "SELL") => {
+                self.note_token(a);
+                let proceeds = if a.usdc_size > 0.0 { a.usdc_size } else { a.price * a.size };
+                self.dispose(&a.asset, a.size, proceeds, a.timestamp, "sell");
+            }
+            ("REDEEM", _) => self.redeem(&a.condition_id, a.usdc_size, a.timestamp),
+            _ => {}
+        }
+    }
+
+    fn note_token(&mut self, a: &Activity) {
+        self.tokens.entry(a.asset.clone()).or_insert_with(|| Token {
+            condition_id: a.condition_id.clone(),
+            title: a.title.clone(),
+            outcome: a.outcome.clone(),
+        });
+    }
+
+    fn open_quantity(&self, token_id: &str) -> f64 {
+        self.lots.get(token_id).map(|lots| lots.iter().map(|l| l.quantity).sum()).unwrap_or(0.0)
+    }
+
+    fn redeem(&mut self, condition_id: &str, payout: f64, ts: u64) {
+        let mut held: Vec<(String, f64)> = self.tokens.iter()
+            .filter(|(_, t)| t.condition_id == condition_id)
+            .map(|(id, _)| (id.clone(), self.open_quantity(id)))
+            .filter(|(_, qty)| *qty > 1e-9)
+            .collect();
+        held.sort_by(|a, b| a.0.cmp(&b.0));
+        let total: f64 = held.iter().map(|(_, qty)| qty).sum();
+        if total <= 0.0 {
+            return;
+        }
+
+        let winner = held.iter().find(|(_, qty)| (qty - payout).abs() < 0.01).map(|(id, _)| id.clone());
+        for (token_id, qty) in held {
+            let proceeds = match &winner {
+                Some(w) if *w == token_id => payout,
+                Some(_) => 0.0,
+                None => payout * qty / total,
+            };
+            self.dispose(&token_id, qty, proceeds, ts, "redeem");
+        }
+    }
+
+    /// Close `quantity` of `token_id` oldest-first for `proceeds` in total.
+    fn dispose(&mut self, token_id: &str, quantity: f64, proceeds: f64, ts: u64, disposal: &str) {
+        if quantity <= 0.0 {
+            return;
+        }
+        let unit_proceeds = proceeds / quantity;
+        let token = self.tokens.get(token_id).cloned().unwrap_or_default();
+        let lots = self.lots.entry(token_id.to_string()).or_default();
+        let mut remaining = quantity;
+
+        while remaining > 1e-9 {
+            let Some(lot) = lots.front_mut() else { break };
+            let take = remaining.min(lot.quantity);
+            let cost_basis = take * lot.unit_cost;
+            let proceeds = take * unit_proceeds;
+            self.closed.push(ClosedLot {
+                token_id: token_id.to_string(),
+                condition_id: token.condition_id.clone(),
+                title: token.title.clone(),
+                outcome: token.outcome.clone(),
+                quantity: take,
+                acquired: lot.acquired,
+                disposed: ts,
+                cost_basis,
+                proceeds,
+                gain: proceeds - cost_basis,
+                disposal: disposal.to_string(),
+            });
+            lot.quantity -= take;
+            remaining -= take;
+            if lot.quantity <= 1e-9 {
+                lots.pop_front();
+            }
+        }
+        if remaining > 1e-9 {
+            self.unmatched.push((token_id.to_string(), remaining));
+        }
+    }
+
+    pub fn closed(&self) -> &[ClosedLot] {
+        &self.closed
+    }
+
+    /// Disposals with no matching buy in the history, by token.
+    pub fn unmatched(&self) -> &[(String, f64)] {
+        &self.unmatched
+    }
+
+    /// Shares still held, by token.
+    pub fn open(&self) -> Vec<(String, f64)> {
+        let mut open: Vec<(String, f64)> = self.lots.keys()
+            .map(|id| (id.clone(), self.open_quantity(id)))
+            .filter(|(_, qty)| *qty > 1e-9)
+            .collect();
+        open.sort_by(|a, b| a.0.cmp(&b.0));
+        open
+    }
+
+    /// Realized gain per market (condition id), in order of first disposal.
+    pub fn gains_by_market(&self) -> Vec<(String, f64)> {
+        let mut gains: Vec<(String, f64)> = Vec::new();
+        for lot in &self.closed {
+            match gains.iter_mut().find(|(id, _)| *id == lot.condition_id) {
+                Some((_, gain)) => *gain += lot.gain,
+                None => gains.push((lot.condition_id.clone(), lot.gain)),
+            }
+        }
+        gains
+    }
+}
+
+const LOT_COLUMNS: [&str; 14] = [
+    "Description", "Date Acquired", "Date Sold", "Proceeds", "Cost Basis", "Gain or Loss",
+    "Quantity", "Market", "Outcome", "Condition ID", "Token ID", "Disposal", "Acquired At", "Sold At",
+];
+
+/// CSV in Form 8949 column order (dates MM/DD/YYYY, UTC), followed by the
+/// market details and RFC3339 timestamps.
+pub fn write_csv<W: Write>(lots: &[ClosedLot], out: W) -> Result<(), Box<dyn std::error::Error>> {
+    let mut writer = csv::WriterBuilder::new().has_headers(false).from_writer(out);
+    writer.write_record(LOT_COLUMNS)?;
+    let date = |ts: u64| DisplayTz::Utc.format(ts, "%m/%d/%Y");
+    for lot in lots {
+        writer.write_record([
+            format!("{:.2} {} ({})", lot.quantity, lot.outcome, lot.title),
+            date(lot.acquired),
+            date(lot.disposed),
+            format!("{:.2}", lot.proceeds),
+            format!("{:.2}", lot.cost_basis),
+            format!("{:.2}", lot.gain),
+            format!("{:.6}", lot.quantity),
+            lot.title.clone(),
+            lot.outcome.clone(),
+            lot.condition_id.clone(),
+            lot.token_id.clone(),
+            lot.disposal.clone(),
+            timestamps::rfc3339(lot.acquired),
+            timestamps::rfc3339(lot.disposed),
+        ])?;
+    }
+    writer.flush()?;
+    Ok(())
+}
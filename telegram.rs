@@ -0,0 +1,91 @@
+//! Telegram channel for `notify`: each notification whose event is
+//! subscribed becomes a plain-text `sendMessage` to one chat, delivered
+//! through an `Outbox` so a slow or unreachable api.telegram.org never holds
+//! up the trading loop.
+//!
+//! Errors never include the request URL, which carries the bot token.
+
+use std::time::Duration;
+
+use reqwest::blocking::Client;
+use serde_json::{json, Value};
+
+use crate::notify::{self, Notification, Notifier, Outbox};
+
+pub const DEFAULT_API_URL: &str = "https://api.telegram.org";
+pub const DEFAULT_EVENTS: &[&str] = &["entry", "exit", "abort", "liquidation", "rule", "fatal"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TelegramConfig {
+    pub token: String,
+    pub chat_id: String,
+    pub api_url: String,
+    // Notification events to deliver; "*" for all
+    pub events: Vec<String>,
+}
+
+impl TelegramConfig {
+    pub fn new(token: &str, chat_id: &str) -> Self {
+        Self {
+            token: token.to_string(),
+            chat_id: chat_id.to_string(),
+            api_url: DEFAULT_API_URL.to_string(),
+            events: DEFAULT_EVENTS.iter().map(|e| e.to_string()).collect(),
+        }
+    }
+
+    pub fn wants(&self, notification: &Notification) -> bool {
+        notify::subscribed(&self.events, notification)
+    }
+}
+
+/// The message text: severity marker and title, then the body.
+pub fn text(notification: &Notification) -> String {
+    let marker = notification.severity.marker();
+    if notification.body.is_empty() {
+        format!("{} {}", marker, notification.title)
+    } else {
+        format!("{} {}\n{}", marker, notification.title, notification.body)
+    }
+}
+
+pub struct TelegramNotifier {
+    config: TelegramConfig,
+    outbox: Outbox,
+}
+
+impl TelegramNotifier {
+    pub fn new(config: TelegramConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        let client = Client::builder().timeout(Duration::from_secs(10)).build()?;
+        let url = format!("{}/bot{}/sendMessage", config.api_url.trim_end_matches('/'), config.token);
+        let chat_id = config.chat_id.clone();
+        let outbox = Outbox::spawn("telegram", move |message| deliver(&client, &url, &chat_id, message))?;
+        Ok(Self { config, outbox })
+    }
+}
+
+fn deliver(client: &Client, url: &str, chat_id: &str, message: &str) -> Result<(), String> {
+    let body = json!({ "chat_id": chat_id, "text": message, "disable_web_page_preview": true });
+    let resp = client.post(url).json(&body).send().map_err(|e| e.without_url().to_string())?;
+    if resp.status().is_success() {
+        return Ok(());
+    }
+    let status = resp.status();
+    let description = resp.json::<Value>().ok()
+        .and_then(|v| v["description"].as_str().map(str::to_string))
+        .unwrap_or_default();
+    Err(format!("HTTP {} {}", status.as_u16(), description).trim_end().to_string())
+}
+
+impl Notifier for TelegramNotifier {
+    fn name(&self) -> &str {
+        "telegram"
+    }
+
+    fn send(&self, notification: &Notification) -> Result<(), Box<dyn std::error::Error>> {
+        if !self.config.wants(notification) {
+            return Ok(());
+        }
+        Ok(self.outbox.push(text(notification))?)
+    }
+}
@@ -0,0 +1,48 @@
+//! Price alert rules: parsing, time-left bounds and fire-once-per-crossing.
+
+use eth_no_trend_bot::alerts::{AlertRule, AlertWatcher, Cmp, Quote};
+use eth_no_trend_bot::strategy::{OrderBook, Outcome};
+
+fn book(bid: f64, ask: f64) -> OrderBook {
+    OrderBook { best_bid: Some(bid), bid_size: 100.0, best_ask: Some(ask), ask_size: 100.0 }
+}
+
+#[test]
+fn rules_parse_fields_thresholds_and_time_left() {
+    let rule = AlertRule::parse(" yes_bid>=0.90 left>=180 ").unwrap();
+    assert_eq!((rule.outcome, rule.quote, rule.cmp, rule.threshold), (Outcome::Yes, Quote::Bid, Cmp::AtLeast, 0.90));
+    assert_eq!((rule.min_left, rule.max_left), (Some(180), None));
+    assert_eq!(rule.text, "yes_bid>=0.90 left>=180");
+
+    let rule = AlertRule::parse("NO_ASK<0.05 left<60").unwrap();
+    assert_eq!((rule.outcome, rule.quote, rule.cmp), (Outcome::No, Quote::Ask, Cmp::Below));
+    assert_eq!(rule.max_left, Some(59));
+
+    for bad in ["", "yes_mid>=0.5", "yes_bid=0.5", "yes_bid>=1.5", "yes_bid>=0.5 spread<0.02", "yes_bid>=0.5 left>=soon"] {
+        assert!(AlertRule::parse(bad).is_err(), "{}", bad);
+    }
+    assert_eq!(AlertWatcher::parse("yes_bid>=0.9; ;no_bid>=0.9").unwrap().rules().len(), 2);
+}
+
+#[test]
+fn fires_once_per_crossing_within_the_time_bounds() {
+    let mut watcher = AlertWatcher::parse("yes_bid>=0.90 left>=180").unwrap();
+    let no = book(0.05, 0.06);
+
+    assert!(watcher.on_books("m1", 300, &book(0.85, 0.86), &no).is_empty());
+    let alerts = watcher.on_books("m1", 299, &book(0.91, 0.92), &no);
+    assert_eq!(alerts.len(), 1);
+    assert_eq!((alerts[0].market.as_str(), alerts[0].value, alerts[0].secs_left), ("m1", 0.91, 299));
+    // Still above: no repeat
+    assert!(watcher.on_books("m1", 298, &book(0.93, 0.94), &no).is_empty());
+    // Dips and comes back: fires again
+    assert!(watcher.on_books("m1", 297, &book(0.80, 0.81), &no).is_empty());
+    assert_eq!(watcher.on_books("m1", 296, &book(0.90, 0.91), &no).len(), 1);
+
+    // Too late in the market
+    assert!(watcher.on_books("m2", 120, &book(0.95, 0.96), &no).is_empty());
+    // Each market is tracked separately, and forgotten when done
+    assert_eq!(watcher.on_books("m3", 200, &book(0.95, 0.96), &no).len(), 1);
+    watcher.forget("m3");
+    assert_eq!(watcher.on_books("m3", 199, &book(0.95, 0.96), &no).len(), 1);
+}
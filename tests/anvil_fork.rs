@@ -0,0 +1,269 @@
+//! Integration tests against an anvil fork of Polygon: order signatures are
+//! checked by the deployed exchange itself, and approve / split / merge /
+//! redeem go through `TxManager` exactly as the bot sends them.
+//!
+//! Needs `anvil` (foundry) on PATH and POLYGON_FORK_URL set to an RPC that
+//! serves historical state. Without POLYGON_FORK_URL every test is a no-op:
+//!
+//!     POLYGON_FORK_URL=https://... cargo test --test anvil_fork
+
+use std::time::Duration;
+
+use ethers::abi::{self, Token};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::{Address, H256, U256};
+use ethers::utils::{keccak256, Anvil, AnvilInstance};
+use serde_json::json;
+
+use eth_no_trend_bot::approvals;
+use eth_no_trend_bot::chain::{self, RpcClient};
+use eth_no_trend_bot::network::NetworkProfile;
+use eth_no_trend_bot::redemption::Redemption;
+use eth_no_trend_bot::signing::{Eip712Signer, OrderAmounts, OrderSide, PolymarketOrder};
+use eth_no_trend_bot::tx_manager::{TxConfig, TxManager, TxRequest};
+
+const USDC: u64 = 1_000_000;
+// Any token id works for hashing/signature checks; this is a live ETH up/down token
+const TEST_TOKEN_ID: &str = "71321045679252212594626385532706912750332728571942532289631379312455583992563";
+
+struct Fork {
+    // Kept alive for the duration of the test; anvil is killed on drop
+    _anvil: AnvilInstance,
+    rpc: RpcClient,
+    network: NetworkProfile,
+    wallet: LocalWallet,
+}
+
+fn fork() -> Option<Fork> {
+    let Ok(fork_url) = std::env::var("POLYGON_FORK_URL") else {
+        println!("POLYGON_FORK_URL not set; skipping anvil fork test");
+        return None;
+    };
+
+    let anvil = Anvil::new().fork(fork_url).timeout(60_000u64).spawn();
+    let rpc = RpcClient::new(&[anvil.endpoint()]).expect("anvil rpc client");
+    let network = NetworkProfile::polygon();
+    let wallet = LocalWallet::from(anvil.keys()[0].clone()).with_chain_id(network.chain_id);
+
+    Some(Fork { _anvil: anvil, rpc, network, wallet })
+}
+
+fn tx_manager(fork: &Fork) -> TxManager {
+    // Anvil mines one block per transaction, so deeper confirmations never arrive
+    let config = TxConfig { confirmations: 1, timeout: Duration::from_secs(60), ..TxConfig::default() };
+    TxManager::new(fork.wallet.clone(), fork.network.chain_id, config)
+}
+
+fn call_data(signature: &str, tokens: &[Token]) -> Vec<u8> {
+    let mut data = chain::function_selector(signature).to_vec();
+    data.extend(abi::encode(tokens));
+    data
+}
+
+fn request(label: &str, to: Address, data: Vec<u8>) -> TxRequest {
+    TxRequest { label: label.to_string(), to, data: data.into(), value: U256::zero() }
+}
+
+fn sample_order(maker: Address, side: OrderSide) -> PolymarketOrder {
+    let amounts = OrderAmounts::new(side, 0.96, 5);
+    PolymarketOrder {
+        salt: "123456789".to_string(),
+        maker: format!("{:?}", maker),
+        signer: format!("{:?}", maker),
+        taker: format!("{:?}", Address::zero()),
+        token_id: TEST_TOKEN_ID.to_string(),
+        maker_amount: amounts.maker_amount.to_string(),
+        taker_amount: amounts.taker_amount.to_string(),
+        expiration: "0".to_string(),
+        nonce: "0".to_string(),
+        fee_rate_bps: "0".to_string(),
+        side: side.as_str().to_string(),
+        signature_type: 0,
+    }
+}
+
+/// The exchange's `Order` struct as an ABI tuple.
+fn order_token(order: &PolymarketOrder, signature: Vec<u8>) -> Token {
+    let uint = |s: &str| Token::Uint(U256::from_dec_str(s).unwrap());
+    let address = |s: &str| Token::Address(s.parse().unwrap());
+    Token::Tuple(vec![
+        uint(&order.salt),
+        address(&order.maker),
+        address(&order.signer),
+        address(&order.taker),
+        uint(&order.token_id),
+        uint(&order.maker_amount),
+        uint(&order.taker_amount),
+        uint(&order.expiration),
+        uint(&order.nonce),
+        uint(&order.fee_rate_bps),
+        Token::Uint(U256::from(if order.side == "BUY" { 0 } else { 1 })),
+        Token::Uint(U256::from(order.signature_type)),
+        Token::Bytes(signature),
+    ])
+}
+
+const ORDER_TUPLE: &str = "(uint256,address,address,address,uint256,uint256,uint256,uint256,uint256,uint256,uint8,uint8,bytes)";
+
+/// Move collateral to `to` by impersonating the CTF contract, which holds
+/// plenty of locked USDC.e on mainnet.
+fn fund_usdc(fork: &Fork, to: Address, amount: U256) {
+    let whale = format!("{:?}", fork.network.ctf);
+    fork.rpc.call_optional::<serde_json::Value>("anvil_impersonateAccount", json!([whale])).unwrap();
+    fork.rpc.call_optional::<serde_json::Value>("anvil_setBalance", json!([whale, U256::exp10(20)])).unwrap();
+
+    let data = chain::encode_call("transfer(address,uint256)", &[chain::address_word(to), chain::uint_word(amount)]);
+    let tx = json!({
+        "from": whale,
+        "to": format!("{:?}", fork.network.collateral),
+        "data": format!("0x{}", hex::encode(data)),
+    });
+    let _: H256 = fork.rpc.call("eth_sendTransaction", json!([tx])).unwrap();
+    fork.rpc.call_optional::<serde_json::Value>("anvil_stopImpersonatingAccount", json!([whale])).unwrap();
+}
+
+fn position_id(fork: &Fork, condition_id: H256, index_set: u64) -> U256 {
+    let collection = fork.rpc.eth_call(fork.network.ctf, &call_data("getCollectionId(bytes32,bytes32,uint256)", &[
+        Token::FixedBytes(H256::zero().as_bytes().to_vec()),
+        Token::FixedBytes(condition_id.as_bytes().to_vec()),
+        Token::Uint(U256::from(index_set)),
+    ])).unwrap();
+    let position = fork.rpc.eth_call(fork.network.ctf, &call_data("getPositionId(address,bytes32)", &[
+        Token::Address(fork.network.collateral),
+        Token::FixedBytes(collection[..32].to_vec()),
+    ])).unwrap();
+    chain::decode_uint(&position).unwrap()
+}
+
+#[test]
+fn exchange_hash_matches_local_order_hash() {
+    let Some(fork) = fork() else { return };
+    let signer = Eip712Signer::new(fork.wallet.clone(), fork.network.chain_id, fork.network.exchange);
+
+    for side in [OrderSide::Buy, OrderSide::Sell] {
+        let order = sample_order(fork.wallet.address(), side);
+        let local = signer.order_hash(&order);
+
+        let data = call_data(&format!("hashOrder({})", ORDER_TUPLE), &[order_token(&order, Vec::new())]);
+        let onchain = fork.rpc.eth_call(fork.network.exchange, &data).unwrap();
+
+        assert_eq!(H256::from_slice(&onchain[..32]), local, "{} order hash differs from the exchange's", side);
+    }
+}
+
+#[test]
+fn exchange_accepts_local_signature() {
+    let Some(fork) = fork() else { return };
+    let signer = Eip712Signer::new(fork.wallet.clone(), fork.network.chain_id, fork.network.exchange);
+
+    let order = sample_order(fork.wallet.address(), OrderSide::Buy);
+    let hash = signer.order_hash(&order);
+    let signature = signer.sign_order(&order).unwrap();
+    assert_eq!(signature.recover(hash).unwrap(), fork.wallet.address());
+
+    // Reverts on a bad signature, returns nothing otherwise
+    let data = call_data(&format!("validateOrderSignature(bytes32,{})", ORDER_TUPLE), &[
+        Token::FixedBytes(hash.as_bytes().to_vec()),
+        order_token(&order, signature.to_vec()),
+    ]);
+    fork.rpc.eth_call(fork.network.exchange, &data).expect("exchange rejected our signature");
+
+    // And a tampered order must not validate with the same signature
+    let mut tampered = order.clone();
+    tampered.maker_amount = "1".to_string();
+    let data = call_data(&format!("validateOrderSignature(bytes32,{})", ORDER_TUPLE), &[
+        Token::FixedBytes(signer.order_hash(&tampered).as_bytes().to_vec()),
+        order_token(&tampered, signature.to_vec()),
+    ]);
+    assert!(fork.rpc.eth_call(fork.network.exchange, &data).is_err());
+}
+
+#[test]
+fn approvals_are_sent_and_detected() {
+    let Some(fork) = fork() else { return };
+    let owner = fork.wallet.address();
+    let collateral = fork.network.collateral;
+
+    let before = approvals::check_approvals(&fork.rpc, &fork.network, owner, collateral).unwrap();
+    assert!(before.iter().all(|s| !s.approved), "fresh anvil account should have no approvals");
+
+    let requests: Vec<TxRequest> = before.iter().map(|s| s.approval_tx()).collect();
+    tx_manager(&fork).send_all(&fork.rpc, &requests).unwrap();
+
+    let after = approvals::check_approvals(&fork.rpc, &fork.network, owner, collateral).unwrap();
+    for status in &after {
+        assert!(status.approved, "{} still missing", status.describe());
+    }
+}
+
+#[test]
+fn split_merge_and_redeem_round_trip() {
+    let Some(fork) = fork() else { return };
+    let owner = fork.wallet.address();
+    let (ctf, collateral) = (fork.network.ctf, fork.network.collateral);
+    let tx = tx_manager(&fork);
+
+    let funded = U256::from(100 * USDC);
+    fund_usdc(&fork, owner, funded);
+    let start_balance = chain::erc20_balance_of(&fork.rpc, collateral, owner).unwrap();
+    assert!(start_balance >= funded);
+
+    // Our own binary condition with the test wallet as oracle, so it can resolve it
+    let question_id = H256::from(keccak256(b"anvil fork round trip"));
+    let mut packed = owner.as_bytes().to_vec();
+    packed.extend_from_slice(question_id.as_bytes());
+    packed.extend_from_slice(&chain::uint_word(U256::from(2)));
+    let condition_id = H256::from(keccak256(&packed));
+
+    let partition = Token::Array(vec![Token::Uint(U256::from(1)), Token::Uint(U256::from(2))]);
+    let position_call = |signature: &str, amount: u64| call_data(signature, &[
+        Token::Address(collateral),
+        Token::FixedBytes(H256::zero().as_bytes().to_vec()),
+        Token::FixedBytes(condition_id.as_bytes().to_vec()),
+        partition.clone(),
+        Token::Uint(U256::from(amount)),
+    ]);
+
+    tx.send_all(&fork.rpc, &[
+        request("Approve CTF", collateral, chain::encode_call("approve(address,uint256)",
+            &[chain::address_word(ctf), chain::uint_word(U256::MAX)])),
+        request("Prepare condition", ctf, call_data("prepareCondition(address,bytes32,uint256)", &[
+            Token::Address(owner),
+            Token::FixedBytes(question_id.as_bytes().to_vec()),
+            Token::Uint(U256::from(2)),
+        ])),
+        request("Split", ctf, position_call("splitPosition(address,bytes32,bytes32,uint256[],uint256)", 10 * USDC)),
+    ]).unwrap();
+
+    let yes = position_id(&fork, condition_id, 1);
+    let no = position_id(&fork, condition_id, 2);
+    assert_eq!(chain::erc1155_balance_of(&fork.rpc, ctf, owner, yes).unwrap(), U256::from(10 * USDC));
+    assert_eq!(chain::erc1155_balance_of(&fork.rpc, ctf, owner, no).unwrap(), U256::from(10 * USDC));
+
+    tx.send(&fork.rpc, &request("Merge", ctf,
+        position_call("mergePositions(address,bytes32,bytes32,uint256[],uint256)", 4 * USDC))).unwrap();
+    assert_eq!(chain::erc1155_balance_of(&fork.rpc, ctf, owner, yes).unwrap(), U256::from(6 * USDC));
+    assert_eq!(chain::erc20_balance_of(&fork.rpc, collateral, owner).unwrap(), start_balance - U256::from(6 * USDC));
+
+    // Redeeming before resolution must be caught by simulation, not mined
+    let redemption = Redemption {
+        condition_id,
+        title: "anvil round trip".to_string(),
+        neg_risk: false,
+        amounts: [U256::from(6 * USDC), U256::from(6 * USDC)],
+        expected_value: 6.0,
+    };
+    let early = tx.send(&fork.rpc, &redemption.tx(&fork.network, collateral).unwrap());
+    assert!(early.unwrap_err().to_string().contains("would revert"));
+
+    tx.send(&fork.rpc, &request("Report payouts", ctf, call_data("reportPayouts(bytes32,uint256[])", &[
+        Token::FixedBytes(question_id.as_bytes().to_vec()),
+        Token::Array(vec![Token::Uint(U256::one()), Token::Uint(U256::zero())]),
+    ]))).unwrap();
+    tx.send(&fork.rpc, &redemption.tx(&fork.network, collateral).unwrap()).unwrap();
+
+    // YES won: 6 YES shares pay out 6 USDC, NO shares are burned for nothing
+    assert_eq!(chain::erc1155_balance_of(&fork.rpc, ctf, owner, yes).unwrap(), U256::zero());
+    assert_eq!(chain::erc1155_balance_of(&fork.rpc, ctf, owner, no).unwrap(), U256::zero());
+    assert_eq!(chain::erc20_balance_of(&fork.rpc, collateral, owner).unwrap(), start_balance);
+}
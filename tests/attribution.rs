@@ -0,0 +1,100 @@
+//! Decision attribution over synthetic journals: each counterfactual is
+//! replayed through the same decision and fill code as the strategy.
+
+use eth_no_trend_bot::attribution::{self, AttributionTotals, Exit, Resolution};
+use eth_no_trend_bot::fill_model::FillModel;
+use eth_no_trend_bot::strategy::{EntryExecution, OrderBook, Outcome, ReplayOutcome, StrategyParams, TieBreak, Tick, TradeSide};
+
+const START: u64 = 1_760_000_400;
+const STOP: f64 = 0.89;
+
+fn params() -> StrategyParams {
+    StrategyParams {
+        trade_side: TradeSide::Both,
+        entry_price: 0.96,
+        abort_ask_price: 0.99,
+        position_size: 5,
+        market_window: 240,
+        entry_timeout: 210,
+        tie_break: TieBreak::default(),
+        entry_exec: EntryExecution::default(),
+    }
+}
+
+fn book(bid: f64, ask: f64) -> OrderBook {
+    OrderBook { best_bid: Some(bid), best_ask: Some(ask), ask_size: 100.0, bid_size: 100.0 }
+}
+
+/// NO books per second from the window open (START + 660) to close, taken
+/// from `(until_second, bid, ask)` segments; YES mirrors NO.
+fn journal(segments: &[(u64, f64, f64)]) -> Vec<Tick> {
+    (660..900).map(|s| {
+        let &(_, bid, ask) = segments.iter().find(|(until, _, _)| s < *until).unwrap();
+        Tick { ts: START + s, yes: book(1.0 - ask, 1.0 - bid), no: book(bid, ask) }
+    }).collect()
+}
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
+
+fn run(ticks: &[Tick]) -> attribution::Attribution {
+    attribution::attribute(&params(), START, ticks, STOP, Resolution::FromLastTick, &FillModel::default())
+}
+
+#[test]
+fn waiting_for_the_trigger_is_compared_with_entering_at_open() {
+    // Enters NO at 0.97 on second 700 and holds to a NO win
+    let ticks = journal(&[(700, 0.90, 0.92), (900, 0.96, 0.97)]);
+    let a = run(&ticks);
+    assert_eq!(a.decision, ReplayOutcome::Entered { ts: START + 700, outcome: Outcome::No, ask: 0.97, size: 5 });
+    assert!(close(a.realized, 0.15), "{}", a.realized);
+    assert_eq!(a.position.unwrap().exit, Exit::Resolution);
+    // Buying at 0.92 on open would have made 0.40
+    assert!(close(a.entry_timing.unwrap(), -0.25), "{:?}", a.entry_timing);
+    assert_eq!((a.stop_vs_hold, a.abort_avoidance), (None, None));
+}
+
+#[test]
+fn stop_that_sold_a_winner_costs_money() {
+    let ticks = journal(&[(700, 0.90, 0.92), (750, 0.96, 0.97), (760, 0.85, 0.86), (900, 0.97, 0.98)]);
+    let a = run(&ticks);
+    let position = a.position.unwrap();
+    assert_eq!((position.exit, position.exit_ts, position.exit_price), (Exit::StopLoss, START + 750, 0.85));
+    assert!(close(a.realized, -0.60), "{}", a.realized);
+    // Holding would have collected 1.00 on the win: +0.15
+    assert!(close(a.stop_vs_hold.unwrap(), -0.75), "{:?}", a.stop_vs_hold);
+}
+
+#[test]
+fn stop_ahead_of_a_loss_saves_money() {
+    let ticks = journal(&[(700, 0.90, 0.92), (750, 0.96, 0.97), (900, 0.20, 0.22)]);
+    let a = run(&ticks);
+    // Stopped at 0.20 for -3.85 instead of expiring worthless for -4.85
+    assert!(close(a.realized, -3.85), "{}", a.realized);
+    assert!(close(a.stop_vs_hold.unwrap(), 1.0), "{:?}", a.stop_vs_hold);
+}
+
+#[test]
+fn abort_is_credited_with_the_entry_it_prevented() {
+    // NO looks decided at 0.995, then YES wins anyway
+    let ticks = journal(&[(680, 0.90, 0.92), (700, 0.98, 0.995), (900, 0.30, 0.32)]);
+    let a = run(&ticks);
+    assert_eq!(a.decision, ReplayOutcome::Aborted { ts: START + 680, ask: 0.995 });
+    assert_eq!(a.realized, 0.0);
+    // Trading through would have bought at 0.995 and stopped out at 0.30
+    assert!(close(a.abort_avoidance.unwrap(), (0.995 - 0.30) * 5.0), "{:?}", a.abort_avoidance);
+
+    let mut totals = AttributionTotals::default();
+    totals.add(&a);
+    totals.add(&run(&journal(&[(700, 0.90, 0.92), (900, 0.96, 0.97)])));
+    assert_eq!((totals.markets, totals.entries, totals.aborts, totals.stops), (2, 1, 1, 0));
+    assert!(close(totals.realized, 0.15));
+}
+
+#[test]
+fn explicit_resolution_overrides_the_last_tick() {
+    let ticks = journal(&[(700, 0.90, 0.92), (900, 0.96, 0.97)]);
+    let a = attribution::attribute(&params(), START, &ticks, STOP, Resolution::Won(Outcome::Yes), &FillModel::default());
+    assert!(close(a.realized, -0.97 * 5.0), "{}", a.realized);
+}
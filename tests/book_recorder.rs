@@ -0,0 +1,84 @@
+use eth_no_trend_bot::book_recorder::{self, BookRecorder, Record};
+use eth_no_trend_bot::strategy::OrderBook;
+
+// 2025-10-09T09:00:00Z
+const START_MS: u64 = 1_760_000_400_000;
+
+fn book(bid: f64, ask: f64) -> OrderBook {
+    OrderBook { best_bid: Some(bid), bid_size: 40.0, best_ask: Some(ask), ask_size: 25.0 }
+}
+
+fn book_record(ts_ms: u64, market: &str, no_ask: f64) -> Record {
+    Record::Book { ts_ms, market: market.to_string(), yes: book(0.02, 0.03), no: book(no_ask - 0.01, no_ask) }
+}
+
+fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("book_recorder_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn records_round_trip_and_split_by_utc_day() {
+    let dir = temp_dir("days");
+    let mut recorder = BookRecorder::new(&dir).unwrap();
+    let trade = Record::Trade {
+        ts_ms: START_MS + 1_500,
+        market: "m1".to_string(),
+        token_id: "1002".to_string(),
+        price: 0.97,
+        size: 12.0,
+        side: "BUY".to_string(),
+    };
+    let next_day = START_MS + 15 * 3_600_000;
+    for record in [&book_record(START_MS, "m1", 0.98), &trade, &book_record(next_day, "m2", 0.99)] {
+        recorder.record(record).unwrap();
+    }
+
+    let first = recorder.path_for(START_MS);
+    assert!(first.ends_with("books-2025-10-09.jsonl"), "{}", first.display());
+    assert!(recorder.path_for(next_day).ends_with("books-2025-10-10.jsonl"));
+
+    let records = book_recorder::read(first.to_str().unwrap()).unwrap();
+    assert_eq!(records, [book_record(START_MS, "m1", 0.98), trade]);
+    let text = std::fs::read_to_string(&first).unwrap();
+    assert!(text.starts_with(r#"{"type":"book","ts_ms":1760000400000,"market":"m1","#), "{}", text);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn torn_last_line_is_skipped_but_corruption_elsewhere_is_not() {
+    let dir = temp_dir("torn");
+    let mut recorder = BookRecorder::new(&dir).unwrap();
+    recorder.record(&book_record(START_MS, "m1", 0.98)).unwrap();
+    let path = recorder.path_for(START_MS);
+    let mut text = std::fs::read_to_string(&path).unwrap();
+    text.push_str(r#"{"type":"book","ts_ms":17600"#);
+    std::fs::write(&path, &text).unwrap();
+
+    assert_eq!(book_recorder::read(path.to_str().unwrap()).unwrap().len(), 1);
+
+    text.push_str("\n{}\n");
+    std::fs::write(&path, &text).unwrap();
+    let err = book_recorder::read(path.to_str().unwrap()).unwrap_err();
+    assert!(err.contains("line 2"), "{}", err);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn book_records_become_replay_ticks_per_market() {
+    let records = vec![
+        book_record(START_MS + 2_000, "m1", 0.97),
+        book_record(START_MS + 2_000, "m2", 0.60),
+        book_record(START_MS + 1_000, "m1", 0.98),
+        Record::Trade { ts_ms: START_MS, market: "m1".to_string(), token_id: "1".to_string(), price: 0.5, size: 1.0, side: "SELL".to_string() },
+    ];
+    assert_eq!(book_recorder::markets(&records), ["m1", "m2"]);
+
+    let ticks = book_recorder::ticks(&records, "m1");
+    assert_eq!(ticks.iter().map(|t| t.ts).collect::<Vec<_>>(), [1_760_000_401, 1_760_000_402]);
+    assert_eq!(ticks[0].no.best_ask, Some(0.98));
+    assert_eq!(ticks[1].yes, book(0.02, 0.03));
+}
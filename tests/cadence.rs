@@ -0,0 +1,40 @@
+use std::time::Duration;
+
+use eth_no_trend_bot::cadence::Cadence;
+
+const OPEN: u64 = 1_760_000_400;
+
+#[test]
+fn waits_straight_to_the_window_unless_alerts_need_books() {
+    let cadence = Cadence::default();
+    let opens_at = OPEN + 660;
+    assert_eq!(cadence.before_window((OPEN + 620) as f64 + 0.25, opens_at, false), Duration::from_secs_f64(39.75));
+    // Far away: capped, so the outer loop still runs
+    assert_eq!(cadence.before_window((OPEN + 10) as f64, opens_at, false), Duration::from_secs(60));
+    assert_eq!(cadence.before_window((OPEN + 620) as f64, opens_at, true), Duration::from_secs(5));
+    assert_eq!(cadence.before_window((OPEN + 658) as f64, opens_at, true), Duration::from_secs(2));
+    assert_eq!(cadence.in_window(false), Duration::from_secs(1));
+    assert_eq!(cadence.in_window(true), Duration::from_millis(100));
+}
+
+#[test]
+fn sleeps_to_the_next_cycle_plus_the_listing_delay() {
+    let cadence = Cadence::default();
+    // 30s before the next open: lands 5s after it, not up to 60s later
+    assert_eq!(cadence.until_next_cycle((OPEN + 870) as f64), Duration::from_secs(35));
+    assert_eq!(cadence.until_next_cycle((OPEN + 100) as f64), Duration::from_secs(60));
+    // Hourly markets: OPEN is on the hour, so 09:59:30 waits for 10:00
+    let hourly = Cadence { cycle: 3_600, ..Cadence::default() };
+    assert_eq!(hourly.until_next_cycle((OPEN + 870) as f64), Duration::from_secs(60));
+    assert_eq!(hourly.until_next_cycle((OPEN + 3_570) as f64), Duration::from_secs(35));
+
+    assert_eq!(cadence.until_listed((OPEN + 2) as f64, OPEN), Duration::from_secs(3));
+    assert_eq!(cadence.until_listed((OPEN + 5) as f64, OPEN), Duration::ZERO);
+}
+
+#[test]
+fn computed_sleeps_never_round_to_nothing() {
+    let cadence = Cadence::default();
+    let almost = (OPEN + 660) as f64 - 1e-9;
+    assert_eq!(cadence.before_window(almost, OPEN + 660, false), Duration::from_millis(1));
+}
@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+use eth_no_trend_bot::chaos::{self, Chaos, Fault, Injection};
+
+#[test]
+fn parses_rates_and_rejects_nonsense() {
+    let chaos = Chaos::parse("latency=0.3, latency_ms=800,timeout=0.05,5xx=0.1,malformed=0.05,seed=7,paths=/book|/order").unwrap();
+    assert_eq!(chaos.latency, 0.3);
+    assert_eq!(chaos.latency_by, Duration::from_millis(800));
+    assert_eq!((chaos.timeout, chaos.server_error, chaos.malformed), (0.05, 0.1, 0.05));
+    assert_eq!(chaos.seed, 7);
+    assert_eq!(chaos.paths, ["/book", "/order"]);
+    assert_eq!(chaos.to_string(), "latency=0.3 latency_ms=800 timeout=0.05 5xx=0.1 malformed=0.05 seed=7 paths=/book|/order");
+
+    assert!(Chaos::parse("5xx=2").unwrap_err().contains("between 0 and 1"));
+    assert!(Chaos::parse("jitter=0.1").unwrap_err().contains("'jitter'"));
+    assert!(Chaos::parse("timeout").unwrap_err().contains("key=value"));
+    assert!(Chaos::parse("timeout=0.5,5xx=0.4,malformed=0.2").unwrap_err().contains("more than 1"));
+}
+
+#[test]
+fn rates_hold_and_seeds_replay() {
+    let chaos = Chaos::parse("latency=0.5,timeout=0.1,5xx=0.2,malformed=0.3,seed=42").unwrap();
+    let rolls: Vec<Injection> = (0..10_000).map(|_| chaos.roll("/book")).collect();
+    let share = |f: &dyn Fn(&Injection) -> bool| rolls.iter().filter(|i| f(i)).count() as f64 / rolls.len() as f64;
+    assert!((share(&|i| i.delay.is_some()) - 0.5).abs() < 0.02);
+    assert!((share(&|i| i.fault == Some(Fault::Timeout)) - 0.1).abs() < 0.02);
+    assert!((share(&|i| i.fault == Some(Fault::ServerError)) - 0.2).abs() < 0.02);
+    assert!((share(&|i| i.fault == Some(Fault::Malformed)) - 0.3).abs() < 0.02);
+
+    // Same seed, same faults
+    let again = Chaos::parse("latency=0.5,timeout=0.1,5xx=0.2,malformed=0.3,seed=42").unwrap();
+    assert!(rolls.iter().take(100).all(|i| *i == again.roll("/book")));
+}
+
+#[test]
+fn only_listed_paths_are_touched() {
+    let chaos = Chaos::parse("5xx=1,paths=/order").unwrap();
+    assert_eq!(chaos.roll("/order").fault, Some(Fault::ServerError));
+    assert_eq!(chaos.roll("/book"), Injection::default());
+    // Off by default
+    assert_eq!(Chaos::parse("").unwrap().roll("/order"), Injection::default());
+}
+
+#[test]
+fn mangled_bodies_do_not_parse() {
+    let body = br#"{"market":"0xabc","bids":[{"price":"0.5","size":"10"}],"asks":[]}"#;
+    let mangled = chaos::mangle(body);
+    assert!(mangled.starts_with(&body[..body.len() / 2]));
+    assert!(serde_json::from_slice::<serde_json::Value>(&mangled).is_err());
+}
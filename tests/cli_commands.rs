@@ -0,0 +1,663 @@
+//! One-shot commands (`buy`, `sell`, `cancel`, `cancel-all`, `liquidate`, `status`,
+//! `positions`, `orders`, `orders history`, `book`, `export positions`, `export lots`,
+//! `report`, `doctor`, `config check`) run the binary once against the mock API and exit.
+
+mod common;
+
+use std::process::Output;
+
+use serde_json::json;
+
+use common::mock_api::{MockApi, OrderOutcome};
+
+const TOKEN: &str = "1001";
+
+fn run(mock: &MockApi, test_name: &str, args: &[&str]) -> (Output, String) {
+    let (mut command, workdir) = common::bot_command(&mock.url, test_name);
+    // Simulated clock so fill polling doesn't sleep in real time
+    let output = command.env("BOT_SIM_START", "1760000400").args(args).output().unwrap();
+    let _ = std::fs::remove_dir_all(&workdir);
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    (output, stdout)
+}
+
+#[test]
+fn buy_then_sell_each_place_one_order_and_exit() {
+    let mock = MockApi::start();
+    mock.script_orders([OrderOutcome::Fill { price: 0.38 }, OrderOutcome::Fill { price: 0.42 }]);
+
+    let (output, stdout) = run(&mock, "cli_buy", &["buy", TOKEN, "0.40", "7"]);
+    assert!(output.status.success(), "bot failed:\n{}\n{}", stdout, String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains("BUY 7.00/7 shares @ $0.380"), "{}", stdout);
+
+    // Sell is checked against the shares the buy left us
+    let (output, stdout) = run(&mock, "cli_sell", &["sell", TOKEN, "0.40", "7", "fak"]);
+    assert!(output.status.success(), "bot failed:\n{}\n{}", stdout, String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains("SELL 7.00/7 shares @ $0.420"), "{}", stdout);
+
+    let posts = mock.requests_to("POST", "/order");
+    assert_eq!(posts.len(), 2);
+    assert!(posts[1].authenticated);
+    let body: serde_json::Value = serde_json::from_str(&posts[1].body).unwrap();
+    assert_eq!((body["orderType"].as_str(), body["order"]["side"].as_str()), (Some("FAK"), Some("SELL")));
+    // Never falls through to the strategy loop
+    assert!(mock.requests_to("GET", "/events").is_empty());
+}
+
+#[test]
+fn bad_order_arguments_fail_without_submitting() {
+    let mock = MockApi::start();
+    for args in [&["buy", TOKEN, "1.2", "5"][..], &["buy", TOKEN, "0.5", "0"], &["buy", TOKEN, "0.5"], &["sell", TOKEN, "0.5", "5", "IOC"]] {
+        let (output, _) = run(&mock, "cli_bad_args", args);
+        assert!(!output.status.success(), "{:?} should fail", args);
+    }
+    assert!(mock.requests_to("POST", "/order").is_empty());
+}
+
+#[test]
+fn resting_order_is_listed_then_canceled() {
+    let mock = MockApi::start();
+    mock.script_orders([OrderOutcome::Rest]);
+
+    let (output, _) = run(&mock, "cli_rest", &["buy", TOKEN, "0.30", "10", "GTC"]);
+    assert!(!output.status.success(), "a GTC that never fills is reported as unfilled");
+    let order_id = mock.state().orders[0].id.clone();
+
+    let (output, stdout) = run(&mock, "cli_orders", &["orders"]);
+    assert!(output.status.success());
+    assert!(stdout.contains(&format!("{} BUY 10.00 @ $0.300 GTC", order_id)), "{}", stdout);
+    assert!(stdout.contains("LIVE scoring"), "{}", stdout);
+    assert!(mock.requests_to("GET", "/data/orders")[0].authenticated);
+    assert!(mock.requests_to("POST", "/orders-scoring")[0].authenticated);
+
+    let (output, stdout) = run(&mock, "cli_cancel", &["cancel", &order_id]);
+    assert!(output.status.success(), "{}", stdout);
+    assert_eq!(mock.state().orders[0].status, "CANCELED");
+
+    // Second cancel is refused by the exchange and reported as a failure
+    let (output, _) = run(&mock, "cli_cancel_again", &["cancel", &order_id]);
+    assert!(!output.status.success());
+
+    let (_, stdout) = run(&mock, "cli_orders_empty", &["orders"]);
+    assert!(stdout.contains("No open orders"), "{}", stdout);
+}
+
+#[test]
+fn cancel_all_cancels_every_open_order() {
+    let mock = MockApi::start();
+    mock.script_orders([OrderOutcome::Rest, OrderOutcome::Rest]);
+    run(&mock, "cli_rest_a", &["buy", TOKEN, "0.30", "10", "GTC"]);
+    run(&mock, "cli_rest_b", &["buy", TOKEN, "0.31", "5", "GTC"]);
+
+    let (output, stdout) = run(&mock, "cli_cancel_all_dry", &["--dry-run", "cancel-all"]);
+    assert!(output.status.success(), "{}", stdout);
+    assert_eq!(stdout.matches("Dry run: would cancel").count(), 2, "{}", stdout);
+    // A dry run doesn't create or migrate the database either
+    assert!(stdout.contains("🧪 Dry run: bot.db not migrated; pending:\n   v1 trades, fills, aborts and traded markets"), "{}", stdout);
+    assert!(mock.requests_to("DELETE", "/order").is_empty());
+
+    let (output, stdout) = run(&mock, "cli_cancel_all", &["cancel-all"]);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(mock.state().orders.iter().all(|o| o.status == "CANCELED"));
+    assert_eq!(mock.requests_to("DELETE", "/order").len(), 2);
+
+    let (_, stdout) = run(&mock, "cli_cancel_all_empty", &["cancel-all"]);
+    assert!(stdout.contains("No open orders"), "{}", stdout);
+}
+
+#[test]
+fn liquidate_sells_held_positions_into_the_bid() {
+    let mock = MockApi::start();
+    for (asset, title, redeemable) in [(TOKEN, "Live market", false), ("2002", "Resolved market", true)] {
+        mock.state().positions.push(json!({
+            "asset": asset, "conditionId": "0x11", "size": 6.0, "title": title,
+            "outcome": "Down", "curPrice": 0.9, "currentValue": 5.4, "redeemable": redeemable,
+        }));
+    }
+    mock.push_book(TOKEN, &[(0.91, 20.0)], &[(0.93, 20.0)]);
+
+    let (output, stdout) = run(&mock, "cli_liquidate_dry", &["liquidate", "--dry-run"]);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("Live market [Down]: selling 6 shares @ $0.910"), "{}", stdout);
+    assert!(stdout.contains("Dry run: not submitted"), "{}", stdout);
+    assert!(mock.requests_to("POST", "/order").is_empty());
+
+    // The exchange's share balance comes from fills
+    mock.script_orders([OrderOutcome::Fill { price: 0.90 }, OrderOutcome::Fill { price: 0.91 }]);
+    run(&mock, "cli_liquidate_buy", &["buy", TOKEN, "0.90", "6"]);
+    let (output, stdout) = run(&mock, "cli_liquidate", &["liquidate"]);
+    assert!(output.status.success(), "{}\n{}", stdout, String::from_utf8_lossy(&output.stderr));
+    // Resolved markets are claimed, not sold
+    assert!(!stdout.contains("Resolved market"), "{}", stdout);
+    assert!(stdout.contains("Sold 6.00 @ $0.910"), "{}", stdout);
+    assert_eq!(mock.state().orders.len(), 2);
+    assert_eq!(mock.state().orders[1].order_type, "FAK");
+
+    let (_, stdout) = run(&mock, "cli_liquidate_other", &["liquidate", "9999"]);
+    assert!(stdout.contains("Nothing to liquidate"), "{}", stdout);
+}
+
+#[test]
+fn liquidate_reports_a_partly_sold_position_as_unsold() {
+    let mock = MockApi::start();
+    mock.state().positions.push(json!({
+        "asset": TOKEN, "conditionId": "0x11", "size": 6.0, "title": "Live market",
+        "outcome": "Down", "curPrice": 0.9, "currentValue": 5.4, "redeemable": false,
+    }));
+    mock.push_book(TOKEN, &[(0.91, 3.0)], &[(0.93, 20.0)]);
+    // Nothing bids under the top level
+    mock.script_orders([
+        OrderOutcome::Fill { price: 0.90 },
+        OrderOutcome::PartialFill { price: 0.91, fraction: 0.5 },
+        OrderOutcome::Reject("no match".to_string()),
+        OrderOutcome::Reject("no match".to_string()),
+    ]);
+    run(&mock, "cli_partial_buy", &["buy", TOKEN, "0.90", "6"]);
+
+    let (output, stdout) = run(&mock, "cli_partial_liquidate", &["liquidate"]);
+    assert!(!output.status.success(), "{}", stdout);
+    assert!(stdout.contains("Sold only 3.00 @ $0.910; 3.00 left"), "{}", stdout);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("1 position(s) not sold"));
+    // Two rungs under the bid tried for the rest, then it gives up
+    assert!(stdout.contains("stepping down to $0.890") && stdout.contains("stepping down to $0.880"), "{}", stdout);
+    let sells = mock.requests_to("POST", "/order").into_iter().filter(|r| r.body.contains("\"SELL\"")).count();
+    assert_eq!(sells, 3, "{}", stdout);
+}
+
+#[test]
+fn liquidate_steps_down_the_bids_until_the_position_is_sold() {
+    let mock = MockApi::start();
+    mock.state().positions.push(json!({
+        "asset": TOKEN, "conditionId": "0x11", "size": 6.0, "title": "Live market",
+        "outcome": "Down", "curPrice": 0.9, "currentValue": 5.4, "redeemable": false,
+    }));
+    mock.push_book(TOKEN, &[(0.91, 3.0), (0.89, 10.0)], &[(0.93, 20.0)]);
+    mock.script_orders([
+        OrderOutcome::Fill { price: 0.90 },
+        OrderOutcome::PartialFill { price: 0.91, fraction: 0.5 },
+        OrderOutcome::Fill { price: 0.89 },
+    ]);
+    run(&mock, "cli_ladder_buy", &["buy", TOKEN, "0.90", "6"]);
+
+    let (output, stdout) = run(&mock, "cli_ladder_liquidate", &["liquidate"]);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("selling 6 shares @ $0.910, down to $0.880"), "{}", stdout);
+    assert!(stdout.contains("3 left; stepping down to $0.890"), "{}", stdout);
+    assert!(stdout.contains("Sold 6.00 @ $0.900"), "{}", stdout);
+    let sells: Vec<_> = mock.state().orders.iter().filter(|o| o.side == "SELL").map(|o| (o.order_type.clone(), o.price, o.size)).collect();
+    assert_eq!(sells, [("FAK".to_string(), 0.91, 6.0), ("FAK".to_string(), 0.89, 3.0)], "{}", stdout);
+}
+
+#[test]
+fn liquidate_skips_a_position_with_a_sell_already_working() {
+    let mock = MockApi::start();
+    mock.state().positions.push(json!({
+        "asset": TOKEN, "conditionId": "0x11", "size": 6.0, "title": "Live market",
+        "outcome": "Down", "curPrice": 0.9, "currentValue": 5.4, "redeemable": false,
+    }));
+    mock.push_book(TOKEN, &[(0.91, 20.0)], &[(0.93, 20.0)]);
+    mock.script_orders([OrderOutcome::Fill { price: 0.90 }, OrderOutcome::Rest, OrderOutcome::Rest]);
+    run(&mock, "cli_working_buy", &["buy", TOKEN, "0.90", "6"]);
+    run(&mock, "cli_working_sell", &["sell", TOKEN, "0.99", "6", "GTC"]);
+    // Another token's order doesn't count
+    run(&mock, "cli_working_other", &["buy", "2002", "0.10", "5", "GTC"]);
+
+    let (output, stdout) = run(&mock, "cli_working_liquidate", &["liquidate"]);
+    assert!(!output.status.success(), "{}", stdout);
+    let sell_id = mock.state().orders[1].id.clone();
+    assert!(stdout.contains(&format!("Live market [Down]: sell {} already working (6.00 @ $0.990)", sell_id)), "{}", stdout);
+    assert_eq!(mock.state().orders.len(), 3);
+    assert_eq!(mock.requests_to("GET", "/data/orders")[0].query, format!("asset_id={}", TOKEN));
+}
+
+#[test]
+fn status_summarizes_market_orders_and_positions() {
+    let mock = MockApi::start();
+    mock.state().positions.push(json!({
+        "asset": TOKEN, "conditionId": "0x11", "size": 4.0, "title": "ETH Up or Down",
+        "outcome": "Up", "curPrice": 0.5, "currentValue": 2.0,
+    }));
+
+    let (output, stdout) = run(&mock, "cli_status", &["status"]);
+    assert!(output.status.success(), "{}\n{}", stdout, String::from_utf8_lossy(&output.stderr));
+    // BOT_SIM_START is 1760000400, a cycle boundary
+    assert!(stdout.contains("market             eth-updown-15m-1760000400 (900s left)"), "{}", stdout);
+    assert!(stdout.contains("open orders        0"), "{}", stdout);
+    assert!(stdout.contains("positions          1 ($2.00)"), "{}", stdout);
+}
+
+#[test]
+fn flags_override_the_config_file_and_bad_commands_are_rejected() {
+    let mock = MockApi::start();
+
+    let (output, stdout) = run(&mock, "cli_flags", &["--entry-price", "0.94", "config", "check", "--size", "3", "--dry-run"]);
+    assert!(output.status.success(), "{}\n{}", stdout, String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains("entry_price        $0.94"), "{}", stdout);
+    assert!(stdout.contains("position_size      3 shares"), "{}", stdout);
+    assert!(stdout.contains("dry_run            true"), "{}", stdout);
+
+    let (output, stdout) = run(&mock, "cli_flags_bad", &["--entry-price", "1.5", "config", "check"]);
+    assert!(!output.status.success());
+    assert!(stdout.contains("entry_price"), "{}", stdout);
+
+    let (output, _) = run(&mock, "cli_unknown", &["sel", TOKEN]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unrecognized subcommand 'sel'"));
+    assert!(mock.state().requests.is_empty());
+}
+
+#[test]
+fn positions_and_book_print_what_the_apis_return() {
+    let mock = MockApi::start();
+    mock.state().positions.push(json!({
+        "asset": TOKEN,
+        "conditionId": "0x11",
+        "size": 12.5,
+        "title": "ETH Up or Down",
+        "outcome": "Down",
+        "outcomeIndex": 1,
+        "curPrice": 0.97,
+        "currentValue": 12.125,
+    }));
+    mock.state().positions.push(json!({
+        "asset": "1000",
+        "conditionId": "0x11",
+        "size": 2.5,
+        "title": "ETH Up or Down",
+        "outcome": "Up",
+        "outcomeIndex": 0,
+        "curPrice": 0.02,
+        "currentValue": 0.05,
+    }));
+    mock.push_book(TOKEN, &[(0.95, 40.0), (0.96, 12.0)], &[(0.98, 30.0)]);
+
+    let (output, stdout) = run(&mock, "cli_positions", &["positions"]);
+    assert!(output.status.success());
+    assert!(stdout.contains("ETH Up or Down [Down] 12.50 shares @ $0.970 = $12.12"), "{}", stdout);
+    // Up and Down net against each other; the matched pairs are worth $1 each
+    assert!(stdout.contains("ETH Up or Down: 2.50 sets ($2.50 riskless), net 10.00 Down ($9.70 at risk), value $12.20"), "{}", stdout);
+    assert!(stdout.contains("Merge 2 complete set(s) of 0x11 to free $2.00"), "{}", stdout);
+    // All positions, not only redeemable ones
+    assert!(!mock.requests_to("GET", "/positions")[0].query.contains("redeemable"));
+
+    let (output, stdout) = run(&mock, "cli_book", &["book", TOKEN]);
+    assert!(output.status.success());
+    assert!(stdout.contains("Best bid: $0.960 x 12.00"), "{}", stdout);
+    assert!(stdout.contains("Best ask: $0.980 x 30.00"), "{}", stdout);
+    assert!(stdout.contains("Midpoint: $0.9700"), "{}", stdout);
+    assert!(stdout.contains("Spread:   $0.0200"), "{}", stdout);
+    assert!(stdout.contains("Quoted:   buy $0.980, sell $0.960"), "{}", stdout);
+}
+
+#[test]
+fn book_for_several_tokens_uses_the_batch_endpoints() {
+    let mock = MockApi::start();
+    mock.push_book("111", &[(0.40, 10.0)], &[(0.44, 10.0)]);
+    mock.push_book("222", &[(0.55, 10.0)], &[(0.56, 10.0)]);
+
+    let (output, stdout) = run(&mock, "cli_book_batch", &["book", "111", "222"]);
+    assert!(output.status.success(), "{}", stdout);
+    let row = |token: &str| stdout.lines().find(|l| l.trim_start().starts_with(token)).unwrap_or_default().split_whitespace().skip(1).collect::<Vec<_>>();
+    assert_eq!(row("111"), ["$0.440", "$0.400", "$0.420", "$0.040"], "{}", stdout);
+    assert_eq!(row("222"), ["$0.560", "$0.550", "$0.555", "$0.010"], "{}", stdout);
+    for path in ["/midpoints", "/spreads", "/prices"] {
+        assert_eq!(mock.requests_to("POST", path).len(), 1, "{}", path);
+    }
+    assert!(mock.requests_to("GET", "/book").is_empty());
+}
+
+#[test]
+fn doctor_reports_each_check_and_fails_without_a_chain() {
+    let mock = MockApi::start();
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "cli_doctor");
+    let output = command.arg("doctor").output().unwrap();
+    let _ = std::fs::remove_dir_all(&workdir);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    for passed in ["Signing key", "CLOB API", "Gamma API", "Data API", "Clock skew", "API credentials", "USDC balance: $1000.00"] {
+        assert!(stdout.contains(&format!("✅ {}", passed)), "{} should pass:\n{}", passed, stdout);
+    }
+    // The mock serves no JSON-RPC, so everything read from the chain fails
+    for failed in ["Trading address", "Polygon RPC", "Exchange approvals"] {
+        assert!(stdout.contains(&format!("❌ {}", failed)), "{} should fail:\n{}", failed, stdout);
+    }
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("3 of 10 checks failed"));
+    assert!(mock.requests_to("POST", "/order").is_empty());
+}
+
+#[test]
+fn doctor_flags_credentials_for_another_signer() {
+    let mock = MockApi::start();
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "cli_doctor_creds");
+    let output = command.env("POLY_API_KEY", "someone-elses-key").arg("doctor").output().unwrap();
+    let _ = std::fs::remove_dir_all(&workdir);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(stdout.contains("❌ API credentials: POLY_API_KEY differs"), "{}", stdout);
+}
+
+#[test]
+fn api_credentials_are_created_then_derived_when_not_configured() {
+    let mock = MockApi::start();
+    mock.state().no_api_key = true;
+
+    let run_orders = || {
+        let (mut command, workdir) = common::bot_command(&mock.url, "cli_derive_creds");
+        let output = command
+            .env_remove("POLY_API_KEY")
+            .env_remove("POLY_API_SECRET")
+            .env_remove("POLY_API_PASSPHRASE")
+            .env("BOT_SIM_START", "1760000400")
+            .arg("orders")
+            .output()
+            .unwrap();
+        let _ = std::fs::remove_dir_all(&workdir);
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        assert!(output.status.success(), "{}\n{}", stdout, String::from_utf8_lossy(&output.stderr));
+        stdout
+    };
+
+    // A new signer has nothing to derive, so a key is created
+    let stdout = run_orders();
+    assert!(stdout.contains("🔑 Created API credentials for"), "{}", stdout);
+    let creates = mock.requests_to("POST", "/auth/api-key");
+    assert_eq!(creates.len(), 1);
+    assert!(creates[0].l1_signed && !creates[0].authenticated);
+    assert!(mock.requests_to("GET", "/auth/derive-api-key")[0].l1_signed);
+    // The credentials sign the L2 request that follows
+    assert!(mock.requests_to("GET", "/data/orders")[0].authenticated);
+
+    // From then on the same key is derived
+    let stdout = run_orders();
+    assert!(stdout.contains("🔑 Derived API credentials for"), "{}", stdout);
+    assert_eq!(mock.requests_to("POST", "/auth/api-key").len(), 1);
+}
+
+#[test]
+fn config_check_prints_the_resolved_config_with_secrets_masked() {
+    let mock = MockApi::start();
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "cli_config");
+    let output = command.args(["config", "check"]).env("TX_CONFIRMATIONS", "12").output().unwrap();
+    let _ = std::fs::remove_dir_all(&workdir);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "{}\n{}", stdout, String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains(&format!("clob_url           {}", mock.url)), "{}", stdout);
+    assert!(stdout.contains("confirmations      12"), "{}", stdout);
+    assert!(!stdout.contains("bW9jay1zZWNyZXQ="), "secret leaked:\n{}", stdout);
+    assert!(stdout.contains("api_secret         bW9j… (16 chars)"), "{}", stdout);
+    // Never starts the bot, so nothing goes over the wire
+    assert!(mock.state().requests.is_empty());
+}
+
+#[test]
+fn config_check_fails_on_bad_overrides() {
+    let mock = MockApi::start();
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "cli_config_bad");
+    let output = command.args(["config", "check"])
+        .env("POLY_CTF_ADDRESS", "0x12")
+        .env("TX_STUCK_SECS", "900")
+        .env_remove("POLY_API_PASSPHRASE")
+        .output()
+        .unwrap();
+    let _ = std::fs::remove_dir_all(&workdir);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(!output.status.success());
+    for problem in ["Invalid POLY_CTF_ADDRESS '0x12'", "TX_STUCK_SECS 900 must be below TX_TIMEOUT_SECS 600", "POLY_API_PASSPHRASE not set"] {
+        assert!(stdout.contains(problem), "missing {:?}:\n{}", problem, stdout);
+    }
+}
+
+#[test]
+fn config_check_reads_config_toml_from_the_working_directory() {
+    let mock = MockApi::start();
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "cli_config_file");
+    std::fs::write(workdir.join("config.toml"), "[strategy]\nposition_size = 8\ntie_break = [\"spread\", \"bid\"]\n\n[timing]\nhttp_timeout = 12\n").unwrap();
+    let output = command.args(["config", "check"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "{}\n{}", stdout, String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains("path               config.toml"), "{}", stdout);
+    assert!(stdout.contains("position_size      8 shares"), "{}", stdout);
+    assert!(stdout.contains("tie_break          spread,bid"), "{}", stdout);
+    assert!(stdout.contains("http_timeout       12s"), "{}", stdout);
+
+    // BOT__ variables win over the file, and flags over both
+    let (mut command, _) = common::bot_command_in(&mock.url, &workdir);
+    let output = command.args(["config", "check"]).env("BOT__STRATEGY__POSITION_SIZE", "6").env("BOT__TIMING__HTTP_TIMEOUT", "20").output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}\n{}", stdout, String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains("position_size      6 shares"), "{}", stdout);
+    assert!(stdout.contains("http_timeout       20s"), "{}", stdout);
+    let (mut command, _) = common::bot_command_in(&mock.url, &workdir);
+    let output = command.args(["config", "check", "--size", "4"]).env("BOT__STRATEGY__POSITION_SIZE", "6").output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("position_size      4 shares"), "{}", stdout);
+
+    // BOT_CONFIG wins over the working directory's file
+    let bad = workdir.join("bad.toml");
+    std::fs::write(&bad, "[strategy]\nentry_price = 0.96\nstop_loss_price = 0.97\nsustain = 3\n").unwrap();
+    let (mut command, _) = common::bot_command_in(&mock.url, &workdir);
+    let output = command.args(["config", "check"]).env("BOT_CONFIG", &bad).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success());
+    assert!(stdout.contains("unknown field `sustain`"), "{}", stdout);
+
+    std::fs::write(&bad, "[strategy]\nentry_price = 0.96\nstop_loss_price = 0.97\n").unwrap();
+    let (mut command, _) = common::bot_command_in(&mock.url, &workdir);
+    let output = command.args(["config", "check"]).env("BOT_CONFIG", &bad).output().unwrap();
+    let _ = std::fs::remove_dir_all(&workdir);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success());
+    assert!(stdout.contains("stop_loss_price 0.97 must be above 0 and below entry_price 0.96"), "{}", stdout);
+}
+
+#[test]
+fn signer_comes_from_an_encrypted_keystore() {
+    use ethers::signers::{LocalWallet, Signer};
+
+    let mock = MockApi::start();
+    let (mut command, workdir) = common::bot_command(&mock.url, "cli_keystore");
+    let key = [0x42u8; 32];
+    let (wallet, _) = LocalWallet::encrypt_keystore(&workdir, &mut ethers::core::rand::thread_rng(), key, "hunter2", Some("key.json")).unwrap();
+    let keystore = workdir.join("key.json");
+
+    // The keystore wins over PRIVATE_KEY
+    let output = command.args(["config", "check"])
+        .env("BOT_KEYSTORE", &keystore)
+        .env("BOT_KEYSTORE_PASSWORD", "hunter2")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}\n{}", stdout, String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains(&format!("keystore           {}", keystore.display())), "{}", stdout);
+    assert!(stdout.contains(&format!("signer             {:?}", wallet.address())), "{}", stdout);
+
+    let (mut command, _) = common::bot_command_in(&mock.url, &workdir);
+    let output = command.args(["positions"])
+        .env("BOT_KEYSTORE", &keystore)
+        .env("BOT_KEYSTORE_PASSWORD", "hunter3")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Cannot unlock keystore"));
+
+    // No key anywhere: refuse to start
+    let (mut command, _) = common::bot_command_in(&mock.url, &workdir);
+    let output = command.args(["positions"]).env_remove("PRIVATE_KEY").output().unwrap();
+    let _ = std::fs::remove_dir_all(&workdir);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No signing key"));
+    assert!(mock.state().requests.is_empty());
+}
+
+#[test]
+fn config_file_holding_a_key_is_refused() {
+    let mock = MockApi::start();
+    let (mut command, workdir) = common::bot_command(&mock.url, "cli_config_key");
+    std::fs::write(workdir.join("config.toml"), format!("[strategy]\nentry_price = 0.95\n# {}\n", common::test_private_key())).unwrap();
+    let output = command.args(["positions"]).output().unwrap();
+    let _ = std::fs::remove_dir_all(&workdir);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("config.toml line 3 looks like a private key"));
+    assert!(mock.state().requests.is_empty());
+}
+
+#[test]
+fn trading_address_must_be_given() {
+    let mock = MockApi::start();
+    let (mut command, workdir) = common::bot_command(&mock.url, "cli_no_address");
+    let output = command.args(["positions"]).env_remove("POLYMARKET_ADDRESS").output().unwrap();
+    let _ = std::fs::remove_dir_all(&workdir);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("POLYMARKET_ADDRESS is not set"));
+    assert!(mock.state().requests.is_empty());
+}
+
+#[test]
+fn export_positions_marks_at_midpoint_and_writes_csv_or_json() {
+    let mock = MockApi::start();
+    for (asset, title, cur_price) in [(TOKEN, "Live market", 0.5), ("2002", "Resolved market", 1.0)] {
+        mock.state().positions.push(json!({
+            "asset": asset, "conditionId": "0x11", "size": 10.0, "title": title,
+            "outcome": "Yes", "curPrice": cur_price, "currentValue": 10.0 * cur_price,
+        }));
+    }
+    mock.push_book(TOKEN, &[(0.60, 5.0)], &[(0.64, 5.0)]);
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "cli_export");
+    let output = command.args(["export", "positions", "out.json"]).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stdout));
+    let rows: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(workdir.join("out.json")).unwrap()).unwrap();
+    assert_eq!((rows[0]["mark"].as_f64(), rows[0]["mark_source"].as_str()), (Some(0.62), Some("midpoint")));
+    assert_eq!((rows[1]["mark"].as_f64(), rows[1]["mark_source"].as_str()), (Some(1.0), Some("data_api")));
+    // No chain behind the mock: on-chain balance is unknown, not zero
+    assert!(rows[1]["onchain_size"].is_null());
+
+    let _ = std::fs::remove_dir_all(&workdir);
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "cli_export_csv");
+    let output = command.args(["export", "positions"]).output().unwrap();
+    assert!(output.status.success());
+    let csv = std::fs::read_to_string(workdir.join("positions_export.csv")).unwrap();
+    let _ = std::fs::remove_dir_all(&workdir);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "token_id,condition_id,title,outcome,size,onchain_size,mark,mark_source,value,redeemable");
+    assert_eq!(lines[2], "2002,0x11,Resolved market,Yes,10.0,,1.0,data_api,10.0,false");
+}
+
+#[test]
+fn export_lots_writes_fifo_lots_per_market() {
+    let mock = MockApi::start();
+    // Newest first, as the data API returns them
+    mock.state().activity.extend([
+        json!({"timestamp": 1_760_001_000, "type": "REDEEM", "conditionId": "0x11", "size": 4.0, "usdcSize": 4.0}),
+        json!({"timestamp": 1_760_000_800, "type": "TRADE", "side": "SELL", "conditionId": "0x11", "asset": TOKEN,
+            "size": 6.0, "usdcSize": 5.7, "price": 0.95, "title": "ETH up?", "outcome": "No"}),
+        json!({"timestamp": 1_760_000_700, "type": "TRADE", "side": "BUY", "conditionId": "0x11", "asset": TOKEN,
+            "size": 5.0, "usdcSize": 4.85, "price": 0.97, "title": "ETH up?", "outcome": "No"}),
+        json!({"timestamp": 1_760_000_600, "type": "TRADE", "side": "BUY", "conditionId": "0x11", "asset": TOKEN,
+            "size": 5.0, "usdcSize": 4.5, "price": 0.90, "title": "ETH up?", "outcome": "No"}),
+    ]);
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "cli_export_lots");
+    let output = command.args(["export", "lots"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    assert!(output.status.success(), "{}", stdout);
+    let csv = std::fs::read_to_string(workdir.join("tax_lots.csv")).unwrap();
+    let _ = std::fs::remove_dir_all(&workdir);
+
+    let lines: Vec<&str> = csv.lines().collect();
+    assert!(lines[0].starts_with("Description,Date Acquired,Date Sold,Proceeds,Cost Basis,Gain or Loss,"), "{}", lines[0]);
+    // The sale takes all of the 0.90 lot and one share of the 0.97 lot;
+    // the redemption pays out the other four at $1
+    let cells = |i: usize| lines[i].split(',').map(str::to_string).collect::<Vec<_>>();
+    assert_eq!(lines.len(), 4, "{}", csv);
+    assert_eq!(cells(1)[3..6], ["4.75", "4.50", "0.25"]);
+    assert_eq!(cells(2)[3..6], ["0.95", "0.97", "-0.02"]);
+    assert_eq!(cells(3)[3..6], ["4.00", "3.88", "0.12"]);
+    assert_eq!(cells(3)[11], "redeem");
+    assert_eq!(cells(1)[1..3], ["10/09/2025", "10/09/2025"]);
+    assert!(stdout.contains("realized $+0.35"), "{}", stdout);
+}
+
+#[test]
+fn orders_left_open_are_recovered_after_restart() {
+    let mock = MockApi::start();
+    mock.script_orders([OrderOutcome::Rest]);
+    let (mut command, workdir) = common::bot_command(&mock.url, "cli_order_recovery");
+    let output = command.env("BOT_SIM_START", "1760000400").args(["buy", TOKEN, "0.30", "10", "GTC"]).output().unwrap();
+    assert!(!output.status.success());
+    let order_id = mock.state().orders[0].id.clone();
+
+    let history = |args: &[&str]| {
+        let (mut command, _) = common::bot_command_in(&mock.url, &workdir);
+        let output = command.env("BOT_SIM_START", "1760000400").args(["orders", "history"]).args(args).output().unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+    let listed = history(&[]);
+    assert!(listed.contains(&format!("BUY GTC 10.00 @ $0.300 live             0.00 filled  {}", order_id)), "{}", listed);
+
+    // Fills while the bot is down
+    {
+        let mut state = mock.state();
+        state.orders[0].matched = 10.0;
+        state.orders[0].status = "MATCHED".to_string();
+    }
+    let (mut command, _) = common::bot_command_in(&mock.url, &workdir);
+    let output = command
+        .env("BOT_SIM_START", "1760000400")
+        .env("BOT_SIM_END", "1760000460")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("Recovering 1 order(s) left open by the last run"), "{}", stdout);
+    assert!(stdout.contains(&format!("BUY {} {}: filled (10.00/10.00 filled)", TOKEN, order_id)), "{}", stdout);
+
+    let detail = history(&[&order_id]);
+    let states: Vec<&str> = detail.lines().skip_while(|l| !l.starts_with("📋"))
+        .filter(|l| l.starts_with("   2025-"))
+        .filter_map(|l| l.split_whitespace().nth(1))
+        .collect();
+    assert_eq!(states, ["created", "signed", "submitted", "live", "filled"], "{}", detail);
+    assert!(detail.contains("10.00 filled @ $0.300"), "{}", detail);
+    let _ = std::fs::remove_dir_all(&workdir);
+}
+
+#[test]
+fn report_sums_up_the_journal_per_day() {
+    let mock = MockApi::start();
+    let (mut command, workdir) = common::bot_command(&mock.url, "cli_report");
+    std::fs::write(workdir.join("ETH_NO_trading_log.jsonl"), concat!(
+        "{\"status\":\"ENTERED\",\"entry_time\":\"2025-10-09T09:11:00Z\",\"entry_price\":0.975,\"slippage\":-0.005}\n",
+        "{\"status\":\"EXITED\",\"exit_time\":\"2025-10-09T09:20:00Z\",\"pnl\":-0.05}\n",
+        "{\"status\":\"ABORTED\",\"entry_time\":\"2025-10-10T10:56:00Z\"}\n",
+    )).unwrap();
+    let output = command
+        .env("BOT_LOG_FORMAT", "jsonl")
+        .env("BOT_LOG_COLUMNS", "status,entry_time,entry_price,exit_time,slippage,pnl")
+        .args(["report", "2025-10-09", "day.csv"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let csv = std::fs::read_to_string(workdir.join("day.csv")).unwrap_or_default();
+    let _ = std::fs::remove_dir_all(&workdir);
+
+    assert!(output.status.success(), "{}\n{}", stdout, String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains("2025-10-09: 1 trade(s), win rate 0% (0/1), fill $0.975 vs trigger $0.980, PnL $-0.05, 0 stop-loss(es), 0 abort(s)"), "{}", stdout);
+    assert!(!stdout.contains("2025-10-10"), "{}", stdout);
+    assert_eq!(csv.lines().count(), 2, "{}", csv);
+    assert!(csv.starts_with("day,trades,closed,wins,win_rate,avg_fill,avg_trigger,pnl,stop_losses,aborts\n2025-10-09,1,1,0,0.0,0.975,"), "{}", csv);
+    assert!(mock.requests_to("POST", "/order").is_empty());
+}
@@ -0,0 +1,565 @@
+//! In-process stand-in for the CLOB, Gamma and Data APIs. Serves every
+//! endpoint the bot calls from a shared, scriptable state: books advance
+//! through a queue of snapshots, order POSTs follow a queue of outcomes
+//! (fill, partial fill, rest, reject, 5xx) and every request is recorded so
+//! tests can assert on what the bot actually sent.
+//!
+//! Point the bot at it with POLY_CLOB_URL / POLY_GAMMA_URL / POLY_DATA_URL
+//! (all three can share the one server).
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+#[derive(Debug, Clone)]
+pub enum OrderOutcome {
+    // Matched in full at this price
+    Fill { price: f64 },
+    // Matched `fraction` of the size, remainder killed (FAK) or resting (GTC, GTD)
+    PartialFill { price: f64, fraction: f64 },
+    // Like PartialFill, matching exactly this many shares, fractions included
+    PartialShares { price: f64, shares: f64 },
+    // Accepted and resting, nothing matched yet
+    Rest,
+    // 200 with an errorMsg, like a FOK that couldn't match
+    Reject(String),
+    // 503 after the order was NOT recorded
+    ServerError,
+}
+
+/// Something the exchange does to a resting order on its own. Applied the
+/// next time the bot reads /notifications, which reports it.
+#[derive(Debug, Clone)]
+pub enum ExchangeAction {
+    Fill { order_id: String, price: f64 },
+    Cancel { order_id: String },
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub query: String,
+    pub body: String,
+    pub authenticated: bool,
+    // Carried the L1 (wallet-signed) auth headers
+    pub l1_signed: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct MockOrder {
+    pub id: String,
+    pub token_id: String,
+    pub side: String,
+    pub price: f64,
+    pub size: f64,
+    pub matched: f64,
+    pub fill_price: f64,
+    pub status: String,
+    pub order_type: String,
+}
+
+#[derive(Default)]
+pub struct MockState {
+    // token id -> successive /book payloads; the last one keeps being served
+    pub books: HashMap<String, VecDeque<Value>>,
+    // Gamma event per slug
+    pub events: HashMap<String, Value>,
+    pub positions: Vec<Value>,
+    // Data API /activity rows (trades and redemptions)
+    pub activity: Vec<Value>,
+    pub order_script: VecDeque<OrderOutcome>,
+    pub orders: Vec<MockOrder>,
+    pub notifications: Vec<Value>,
+    pub exchange_actions: Vec<ExchangeAction>,
+    // Collateral balance in USDC
+    pub balance: f64,
+    pub exchange_down: bool,
+    // The signer has no API key until it POSTs /auth/api-key
+    pub no_api_key: bool,
+    pub requests: Vec<RecordedRequest>,
+}
+
+pub struct MockApi {
+    pub url: String,
+    state: Arc<Mutex<MockState>>,
+    server: Arc<Server>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MockApi {
+    pub fn start() -> Self {
+        let server = Arc::new(Server::http("127.0.0.1:0").expect("bind mock api"));
+        let url = format!("http://{}", server.server_addr().to_ip().expect("tcp listener"));
+        let state = Arc::new(Mutex::new(MockState { balance: 1_000.0, ..Default::default() }));
+
+        let handle = {
+            let server = Arc::clone(&server);
+            let state = Arc::clone(&state);
+            thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    handle(request, &state);
+                }
+            })
+        };
+
+        Self { url, state, server, handle: Some(handle) }
+    }
+
+    pub fn state(&self) -> MutexGuard<'_, MockState> {
+        self.state.lock().unwrap()
+    }
+
+    /// Register a two-outcome market under `slug` the way Gamma returns it.
+    pub fn add_market(&self, slug: &str, yes_token: &str, no_token: &str) {
+        let event = json!({
+            "slug": slug,
+            "title": format!("Mock market {}", slug),
+            "markets": [{
+                "enableOrderBook": true,
+                "conditionId": format!("0x{:0>64}", hex::encode(slug.as_bytes()).chars().take(64).collect::<String>()),
+                "clobTokenIds": serde_json::to_string(&[yes_token, no_token]).unwrap(),
+                "umaResolutionStatus": "",
+            }],
+        });
+        self.state().events.insert(slug.to_string(), event);
+    }
+
+    /// `add_market` with what discovery filters on: an RFC3339 `endDate` and
+    /// one tag.
+    pub fn add_market_closing(&self, slug: &str, yes_token: &str, no_token: &str, end_date: &str, tag: &str) {
+        self.add_market(slug, yes_token, no_token);
+        let mut state = self.state();
+        let event = state.events.get_mut(slug).unwrap();
+        event["endDate"] = json!(end_date);
+        event["tags"] = json!([{ "slug": tag }]);
+    }
+
+    /// Queue a book snapshot for `token_id`; levels are (price, size).
+    pub fn push_book(&self, token_id: &str, bids: &[(f64, f64)], asks: &[(f64, f64)]) {
+        self.state().books.entry(token_id.to_string()).or_default().push_back(book_json(token_id, bids, asks));
+    }
+
+    pub fn script_orders(&self, outcomes: impl IntoIterator<Item = OrderOutcome>) {
+        self.state().order_script.extend(outcomes);
+    }
+
+    pub fn requests_to(&self, method: &str, path: &str) -> Vec<RecordedRequest> {
+        self.state().requests.iter()
+            .filter(|r| r.method == method && r.path == path)
+            .cloned()
+            .collect()
+    }
+}
+
+impl Drop for MockApi {
+    fn drop(&mut self) {
+        self.server.unblock();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+pub fn book_json(token_id: &str, bids: &[(f64, f64)], asks: &[(f64, f64)]) -> Value {
+    let levels = |levels: &[(f64, f64)]| -> Vec<Value> {
+        levels.iter().map(|(p, s)| json!({ "price": format!("{}", p), "size": format!("{}", s) })).collect()
+    };
+    // The CLOB lists bids ascending and asks descending; best levels come last
+    let mut bids = bids.to_vec();
+    bids.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut asks = asks.to_vec();
+    asks.sort_by(|a, b| b.0.total_cmp(&a.0));
+    json!({
+        "market": "",
+        "asset_id": token_id,
+        "hash": "mock",
+        "timestamp": "0",
+        "bids": levels(&bids),
+        "asks": levels(&asks),
+    })
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+}
+
+fn query_param<'a>(query: &'a str, key: &str) -> Option<&'a str> {
+    query.split('&').find_map(|pair| pair.strip_prefix(key)?.strip_prefix('='))
+}
+
+fn touch(book: &Value) -> (Option<f64>, Option<f64>) {
+    let best = |side: &str| book[side].as_array()?.last()?["price"].as_str()?.parse::<f64>().ok();
+    (best("bids"), best("asks"))
+}
+
+/// Cancel every live order `matches` picks, answering the way the bulk
+/// cancel endpoints do.
+fn cancel_live(state: &mut MockState, matches: impl Fn(&MockOrder) -> bool) -> Value {
+    let mut canceled = Vec::new();
+    for order in state.orders.iter_mut().filter(|o| o.status == "LIVE" && matches(o)) {
+        order.status = "CANCELED".to_string();
+        canceled.push(order.id.clone());
+    }
+    json!({ "canceled": canceled, "not_canceled": {} })
+}
+
+fn current_book(state: &mut MockState, token_id: &str) -> Option<Value> {
+    let queue = state.books.get_mut(token_id)?;
+    if queue.len() > 1 {
+        queue.pop_front()
+    } else {
+        queue.front().cloned()
+    }
+}
+
+fn handle(mut request: Request, state: &Mutex<MockState>) {
+    let mut body = String::new();
+    let _ = request.as_reader().read_to_string(&mut body);
+
+    let (path, query) = match request.url().split_once('?') {
+        Some((p, q)) => (p.to_string(), q.to_string()),
+        None => (request.url().to_string(), String::new()),
+    };
+    let method = request.method().to_string();
+    let authenticated = request.headers().iter().any(|h| h.field.equiv("POLY_API_KEY"));
+    let l1_signed = ["POLY_ADDRESS", "POLY_SIGNATURE", "POLY_TIMESTAMP", "POLY_NONCE"]
+        .iter()
+        .all(|name| request.headers().iter().any(|h| h.field.equiv(name)));
+
+    let mut state = state.lock().unwrap();
+    state.requests.push(RecordedRequest {
+        method: method.clone(),
+        path: path.clone(),
+        query: query.clone(),
+        body: body.clone(),
+        authenticated,
+        l1_signed,
+    });
+
+    let (status, payload) = route(&mut state, request.method(), &path, &query, &body);
+    drop(state);
+
+    let text = match payload {
+        Value::String(s) => s,
+        other => other.to_string(),
+    };
+    let response = Response::from_string(text)
+        .with_status_code(status)
+        .with_header(Header::from_bytes("Content-Type", "application/json").unwrap());
+    let _ = request.respond(response);
+}
+
+fn route(state: &mut MockState, method: &Method, path: &str, query: &str, body: &str) -> (u16, Value) {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    match (method, segments.as_slice()) {
+        // ---- CLOB: public ----
+        (Method::Get, [""]) => {
+            if state.exchange_down { (503, json!("down")) } else { (200, json!("\"OK\"")) }
+        }
+        (Method::Get, ["time"]) => (200, json!(now_secs().to_string())),
+        (Method::Get, ["book"]) => {
+            let token = query_param(query, "token_id").unwrap_or_default();
+            match current_book(state, token) {
+                Some(book) => (200, book),
+                None => (404, json!({ "error": "No orderbook exists for the requested token id" })),
+            }
+        }
+        (Method::Get, ["midpoint"]) | (Method::Get, ["price"]) | (Method::Get, ["spread"]) => {
+            let token = query_param(query, "token_id").unwrap_or_default();
+            let Some(book) = state.books.get(token).and_then(|q| q.front()).cloned() else {
+                return (404, json!({ "error": "no book" }));
+            };
+            let (bid, ask) = touch(&book);
+            let (bid, ask) = (bid.unwrap_or(0.0), ask.unwrap_or(1.0));
+            match segments[0] {
+                "midpoint" => (200, json!({ "mid": format!("{}", (bid + ask) / 2.0) })),
+                "spread" => (200, json!({ "spread": format!("{}", ask - bid) })),
+                _ => {
+                    let price = if query_param(query, "side") == Some("BUY") { ask } else { bid };
+                    (200, json!({ "price": format!("{}", price) }))
+                }
+            }
+        }
+        (Method::Post, ["midpoints"]) => {
+            let requested: Vec<Value> = serde_json::from_str(body).unwrap_or_default();
+            let mut mids = serde_json::Map::new();
+            for token in requested.iter().filter_map(|r| r["token_id"].as_str()) {
+                if let Some(book) = state.books.get(token).and_then(|q| q.front()) {
+                    let (bid, ask) = touch(book);
+                    mids.insert(token.to_string(), json!(format!("{}", (bid.unwrap_or(0.0) + ask.unwrap_or(1.0)) / 2.0)));
+                }
+            }
+            (200, Value::Object(mids))
+        }
+        (Method::Post, ["spreads"]) | (Method::Post, ["prices"]) => {
+            let requested: Vec<Value> = serde_json::from_str(body).unwrap_or_default();
+            let mut quoted = serde_json::Map::new();
+            for request in &requested {
+                let Some(token) = request["token_id"].as_str() else { continue };
+                let Some(book) = state.books.get(token).and_then(|q| q.front()) else { continue };
+                let (bid, ask) = touch(book);
+                let (bid, ask) = (bid.unwrap_or(0.0), ask.unwrap_or(1.0));
+                if segments[0] == "spreads" {
+                    quoted.insert(token.to_string(), json!(format!("{}", ask - bid)));
+                } else {
+                    let side = request["side"].as_str().unwrap_or("BUY");
+                    let price = if side == "BUY" { ask } else { bid };
+                    let sides = quoted.entry(token.to_string()).or_insert_with(|| json!({}));
+                    sides[side] = json!(format!("{}", price));
+                }
+            }
+            (200, Value::Object(quoted))
+        }
+        (Method::Get, ["markets"]) => (200, json!({ "data": [], "next_cursor": "LTE=", "limit": 0, "count": 0 })),
+
+        // ---- CLOB: authenticated ----
+        (Method::Get, ["auth", "derive-api-key"]) if state.no_api_key => (400, json!({ "error": "Could not derive api key!" })),
+        (Method::Get, ["auth", "derive-api-key"]) | (Method::Post, ["auth", "api-key"]) => {
+            state.no_api_key = false;
+            (200, json!({ "apiKey": "mock-key", "secret": "bW9jay1zZWNyZXQ=", "passphrase": "mock-passphrase" }))
+        }
+        (Method::Get, ["balance-allowance"]) => {
+            let raw = |usdc: f64| format!("{}", (usdc * 1_000_000.0).round() as u64);
+            let balance = if query_param(query, "asset_type") == Some("CONDITIONAL") {
+                let token = query_param(query, "token_id").unwrap_or_default();
+                held_shares(state, token)
+            } else {
+                state.balance
+            };
+            (200, json!({ "balance": raw(balance), "allowances": { "exchange": raw(1e12) } }))
+        }
+        (Method::Get, ["balance-allowance", "update"]) => (200, json!({})),
+        (Method::Post, ["order"]) => place_order(state, body),
+        (Method::Delete, ["order"]) => {
+            let id = serde_json::from_str::<Value>(body).ok()
+                .and_then(|v| v["orderID"].as_str().map(|s| s.to_string()))
+                .unwrap_or_default();
+            match state.orders.iter_mut().find(|o| o.id == id) {
+                Some(order) if order.status == "LIVE" => {
+                    order.status = "CANCELED".to_string();
+                    (200, json!({ "canceled": [id], "not_canceled": {} }))
+                }
+                _ => (200, json!({ "canceled": [], "not_canceled": { id: "order not live" } })),
+            }
+        }
+        (Method::Delete, ["cancel-all"]) => (200, cancel_live(state, |_| true)),
+        (Method::Delete, ["cancel-market-orders"]) => {
+            let market = serde_json::from_str::<Value>(body).ok()
+                .and_then(|v| v["market"].as_str().map(|s| s.to_string()))
+                .unwrap_or_default();
+            let tokens: Vec<String> = state.events.values()
+                .flat_map(|e| e["markets"].as_array().cloned().unwrap_or_default())
+                .filter(|m| m["conditionId"] == market.as_str())
+                .flat_map(|m| serde_json::from_str::<Vec<String>>(m["clobTokenIds"].as_str().unwrap_or("[]")).unwrap_or_default())
+                .collect();
+            (200, cancel_live(state, |o| tokens.contains(&o.token_id)))
+        }
+        (Method::Get, ["order", id]) | (Method::Get, ["data", "order", id]) => {
+            match state.orders.iter().find(|o| o.id == *id) {
+                Some(order) => (200, json!({
+                    "id": order.id,
+                    "status": order.status,
+                    "asset_id": order.token_id,
+                    "side": order.side,
+                    "price": format!("{}", order.price),
+                    "original_size": format!("{}", order.size),
+                    "size_matched": format!("{}", order.matched),
+                    "order_type": order.order_type,
+                    "created_at": now_secs(),
+                })),
+                None => (404, json!({ "error": "order not found" })),
+            }
+        }
+        (Method::Get, ["data", "orders"]) => {
+            let asset = query_param(query, "asset_id");
+            let live: Vec<Value> = state.orders.iter()
+                .filter(|o| o.status == "LIVE" && asset.is_none_or(|a| o.token_id == a))
+                .map(|o| json!({
+                    "id": o.id,
+                    "status": o.status,
+                    "asset_id": o.token_id,
+                    "side": o.side,
+                    "price": format!("{}", o.price),
+                    "original_size": format!("{}", o.size),
+                    "size_matched": format!("{}", o.matched),
+                    "order_type": o.order_type,
+                    "outcome": "",
+                }))
+                .collect();
+            let count = live.len();
+            (200, json!({ "data": live, "next_cursor": "LTE=", "limit": 100, "count": count }))
+        }
+        (Method::Get, ["data", "trades"]) => {
+            let asset = query_param(query, "asset_id").unwrap_or_default();
+            let trades: Vec<Value> = state.orders.iter()
+                .filter(|o| o.matched > 0.0 && (asset.is_empty() || o.token_id == asset))
+                .map(|o| json!({
+                    "id": format!("trade-{}", o.id),
+                    "taker_order_id": o.id,
+                    "asset_id": o.token_id,
+                    "side": o.side,
+                    "size": format!("{}", o.matched),
+                    "price": format!("{}", o.fill_price),
+                    "fee_rate_bps": "0",
+                    "maker_orders": [],
+                }))
+                .collect();
+            (200, json!({ "data": trades, "next_cursor": "LTE=" }))
+        }
+        // Every order resting on the book scores
+        (Method::Get, ["order-scoring"]) => {
+            let id = query_param(query, "order_id").unwrap_or_default();
+            (200, json!({ "scoring": state.orders.iter().any(|o| o.id == id && o.status == "LIVE") }))
+        }
+        (Method::Post, ["orders-scoring"]) => {
+            let requested: Vec<String> = serde_json::from_str(body).unwrap_or_default();
+            let scoring: serde_json::Map<String, Value> = requested.into_iter()
+                .filter_map(|id| {
+                    let order = state.orders.iter().find(|o| o.id == id)?;
+                    Some((id, json!(order.status == "LIVE")))
+                })
+                .collect();
+            (200, Value::Object(scoring))
+        }
+        (Method::Get, ["notifications"]) => {
+            for action in std::mem::take(&mut state.exchange_actions) {
+                let (order_id, notification_type) = match &action {
+                    ExchangeAction::Fill { order_id, .. } => (order_id.clone(), 2),
+                    ExchangeAction::Cancel { order_id } => (order_id.clone(), 1),
+                };
+                let Some(order) = state.orders.iter_mut().find(|o| o.id == order_id) else { continue };
+                match action {
+                    ExchangeAction::Fill { price, .. } => {
+                        order.matched = order.size;
+                        order.fill_price = price;
+                        order.status = "MATCHED".to_string();
+                    }
+                    ExchangeAction::Cancel { .. } => order.status = "CANCELED".to_string(),
+                }
+                let id = state.notifications.len() + 1;
+                state.notifications.push(json!({ "id": id, "type": notification_type, "payload": { "order_id": order_id, "market": "" } }));
+            }
+            (200, Value::Array(state.notifications.clone()))
+        }
+        (Method::Delete, ["notifications"]) => {
+            state.notifications.clear();
+            (200, json!(null))
+        }
+
+        // ---- Gamma ----
+        (Method::Get, ["events"]) => {
+            // Without a slug, a discovery search by end date and tag
+            let Some(slug) = query_param(query, "slug") else {
+                let (min, max) = (query_param(query, "end_date_min").unwrap_or(""), query_param(query, "end_date_max").unwrap_or("~"));
+                let tag = query_param(query, "tag_slug");
+                let found: Vec<Value> = state.events.values()
+                    .filter(|e| e["endDate"].as_str().is_some_and(|end| end >= min && end <= max))
+                    .filter(|e| tag.is_none_or(|tag| e["tags"].as_array().is_some_and(|tags| tags.iter().any(|t| t["slug"] == tag))))
+                    .cloned()
+                    .collect();
+                return (200, Value::Array(found));
+            };
+            match state.events.get(slug) {
+                Some(event) => (200, json!([event])),
+                None => (200, json!([])),
+            }
+        }
+
+        // ---- Data API ----
+        (Method::Get, ["positions"]) => {
+            let redeemable_only = query_param(query, "redeemable") == Some("true");
+            let offset: usize = query_param(query, "offset").and_then(|o| o.parse().ok()).unwrap_or(0);
+            let limit: usize = query_param(query, "limit").and_then(|l| l.parse().ok()).unwrap_or(100);
+            let page: Vec<Value> = state.positions.iter()
+                .filter(|p| !redeemable_only || p["redeemable"].as_bool().unwrap_or(false))
+                .skip(offset)
+                .take(limit)
+                .cloned()
+                .collect();
+            (200, Value::Array(page))
+        }
+        (Method::Get, ["activity"]) => {
+            let offset: usize = query_param(query, "offset").and_then(|o| o.parse().ok()).unwrap_or(0);
+            let limit: usize = query_param(query, "limit").and_then(|l| l.parse().ok()).unwrap_or(100);
+            (200, Value::Array(state.activity.iter().skip(offset).take(limit).cloned().collect()))
+        }
+
+        _ => (404, json!({ "error": format!("mock has no route for {} /{}", method, segments.join("/")) })),
+    }
+}
+
+fn held_shares(state: &MockState, token_id: &str) -> f64 {
+    state.orders.iter()
+        .filter(|o| o.token_id == token_id)
+        .map(|o| if o.side == "BUY" { o.matched } else { -o.matched })
+        .sum::<f64>()
+        .max(0.0)
+}
+
+fn place_order(state: &mut MockState, body: &str) -> (u16, Value) {
+    let Ok(request) = serde_json::from_str::<Value>(body) else {
+        return (400, json!({ "error": "invalid order payload" }));
+    };
+    let order = &request["order"];
+    let amount = |key: &str| order[key].as_str().and_then(|s| s.parse::<f64>().ok()).unwrap_or(0.0) / 1_000_000.0;
+    let side = order["side"].as_str().unwrap_or("BUY").to_string();
+    let (maker, taker) = (amount("makerAmount"), amount("takerAmount"));
+    let (size, notional) = if side == "BUY" { (taker, maker) } else { (maker, taker) };
+    if size <= 0.0 {
+        return (400, json!({ "error": "invalid order size" }));
+    }
+    let price = notional / size;
+    let order_type = request["orderType"].as_str().unwrap_or("GTC").to_string();
+
+    let outcome = state.order_script.pop_front().unwrap_or(OrderOutcome::Fill { price });
+    let id = format!("0x{:064x}", state.orders.len() + 1);
+    let mut record = MockOrder {
+        id: id.clone(),
+        token_id: order["tokenId"].as_str().unwrap_or_default().to_string(),
+        side,
+        price,
+        size,
+        matched: 0.0,
+        fill_price: price,
+        status: "LIVE".to_string(),
+        order_type: order_type.clone(),
+    };
+
+    match outcome {
+        OrderOutcome::Fill { price } => {
+            record.matched = size;
+            record.fill_price = price;
+            record.status = "MATCHED".to_string();
+        }
+        OrderOutcome::PartialFill { price, fraction } => {
+            record.matched = (size * fraction).floor();
+            record.fill_price = price;
+            // Only GTC and GTD remainders rest on the book
+            record.status = if matches!(order_type.as_str(), "GTC" | "GTD") { "LIVE" } else { "CANCELED" }.to_string();
+        }
+        OrderOutcome::PartialShares { price, shares } => {
+            record.matched = shares.min(size);
+            record.fill_price = price;
+            record.status = if matches!(order_type.as_str(), "GTC" | "GTD") { "LIVE" } else { "CANCELED" }.to_string();
+        }
+        OrderOutcome::Rest => {}
+        OrderOutcome::Reject(message) => {
+            return (200, json!({ "success": false, "errorMsg": message, "orderID": null }));
+        }
+        OrderOutcome::ServerError => return (503, json!({ "error": "upstream unavailable" })),
+    }
+
+    // Matched notional moves collateral the same way settlement would
+    let notional = record.matched * record.fill_price;
+    state.balance += if record.side == "BUY" { -notional } else { notional };
+
+    let status = record.status.to_lowercase();
+    state.orders.push(record);
+    (200, json!({ "success": true, "errorMsg": "", "orderID": id, "status": status }))
+}
@@ -0,0 +1,50 @@
+// Shared by the integration tests; each test crate uses a different subset
+#![allow(dead_code)]
+
+pub mod mock_api;
+
+use std::path::PathBuf;
+use std::process::Command;
+
+// Trading address for every test run; it isn't the signer's, so the bot
+// trades in proxy-wallet mode
+pub const TEST_POLYMARKET_ADDRESS: &str = "0x0000000000000000000000000000000000c0ffee";
+
+/// Signer for every test run: a throwaway key, built at runtime so the
+/// source scan in tests/config.rs has nothing to find.
+pub fn test_private_key() -> String {
+    format!("0x{}", "22".repeat(32))
+}
+
+/// The bot binary wired to `mock_url` for every API, with dummy credentials
+/// and no reachable chain. Runs in a fresh working directory so CSV logs and
+/// caches don't leak between tests.
+pub fn bot_command(mock_url: &str, test_name: &str) -> (Command, PathBuf) {
+    let workdir = std::env::temp_dir().join(format!("{}_{}", test_name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&workdir);
+    std::fs::create_dir_all(&workdir).unwrap();
+    bot_command_in(mock_url, &workdir)
+}
+
+/// `bot_command` in an existing working directory, to run the bot again
+/// over what an earlier run left behind.
+pub fn bot_command_in(mock_url: &str, workdir: &std::path::Path) -> (Command, PathBuf) {
+    let workdir = workdir.to_path_buf();
+    let mut command = Command::new(env!("CARGO_BIN_EXE_eth_no_trend_bot"));
+    command
+        .current_dir(&workdir)
+        .env("POLY_CLOB_URL", mock_url)
+        .env("POLY_GAMMA_URL", mock_url)
+        .env("POLY_DATA_URL", mock_url)
+        // Books over HTTP unless a test streams them
+        .env("POLY_WS_URL", "")
+        // No chain behind the mock: every RPC call fails fast
+        .env("POLYGON_RPC_URL", mock_url)
+        // Throwaway key and address; the bot has neither built in
+        .env("PRIVATE_KEY", test_private_key())
+        .env("POLYMARKET_ADDRESS", TEST_POLYMARKET_ADDRESS)
+        .env("POLY_API_KEY", "mock-key")
+        .env("POLY_API_SECRET", "bW9jay1zZWNyZXQ=")
+        .env("POLY_API_PASSPHRASE", "mock-passphrase");
+    (command, workdir)
+}
@@ -0,0 +1,340 @@
+use eth_no_trend_bot::config::{self, Config, LogOutput};
+use eth_no_trend_bot::strategy::{BalanceSizing, EntryStyle, PartialFillAction, TradeSide, TrailingStop, Tranche};
+
+#[test]
+fn empty_file_is_the_shipped_defaults() {
+    let config = Config::parse("").unwrap();
+    assert_eq!(config, Config::default());
+    assert_eq!(config.strategy.trade_side, TradeSide::Both);
+    assert_eq!(config.strategy.entry_price, 0.96);
+    assert_eq!(config.timing.http_timeout, 30);
+    assert_eq!(config.risk.low_balance_threshold, 25.0);
+    assert!(config.validate().is_empty());
+}
+
+#[test]
+fn partial_file_keeps_defaults_for_the_rest() {
+    let config = Config::parse(r#"
+        [strategy]
+        trade_side = "NO"
+        entry_price = 0.94
+        entry_exec = { style = "passive", cross_at = 0.5 }
+
+        [timing]
+        exchange_status_interval = 30
+    "#).unwrap();
+
+    assert_eq!(config.strategy.trade_side, TradeSide::No);
+    assert_eq!(config.strategy.entry_price, 0.94);
+    assert_eq!(config.strategy.stop_loss_price, 0.89);
+    assert_eq!(config.strategy.entry_exec.style, EntryStyle::Passive);
+    assert_eq!(config.strategy.partial_fill, PartialFillAction::Requote);
+    assert_eq!(config.timing.exchange_status_interval, 30);
+    assert_eq!(config.timing.notification_poll_interval, 10);
+
+    let params = config.strategy.params();
+    assert_eq!(params.entry_price, 0.94);
+    assert_eq!(params.position_size, 5);
+}
+
+#[test]
+fn typos_and_bad_values_are_rejected_with_the_key() {
+    let err = Config::parse("[strategy]\nentry_prise = 0.95\n").unwrap_err();
+    assert!(err.contains("entry_prise"), "{}", err);
+
+    let err = Config::parse("[stratgy]\n").unwrap_err();
+    assert!(err.contains("stratgy"), "{}", err);
+
+    let err = Config::parse("[strategy]\ntrade_side = \"UP\"\n").unwrap_err();
+    assert!(err.contains("UP"), "{}", err);
+
+    let err = Config::parse("[timing]\nhttp_timeout = \"soon\"\n").unwrap_err();
+    assert!(err.contains("http_timeout"), "{}", err);
+
+    assert_eq!(Config::parse("log_format = \"json\"\n").unwrap().log_format, LogOutput::Json);
+    let err = Config::parse("log_format = \"yaml\"\n").unwrap_err();
+    assert!(err.contains("yaml"), "{}", err);
+}
+
+#[test]
+fn trailing_stop_is_off_unless_configured() {
+    assert_eq!(Config::default().strategy.trailing_stop, None);
+    let config = Config::parse("[strategy]\ntrailing_stop = { trail = 0.02 }\n").unwrap();
+    assert_eq!(config.strategy.trailing_stop, Some(TrailingStop { trail: 0.02, activation: 0.0 }));
+    assert!(config.validate().is_empty());
+
+    let config = Config::parse("[strategy]\ntrailing_stop = { trail = 1.5, activation = -0.1 }\n").unwrap();
+    let errors = config.validate();
+    assert!(errors.iter().any(|e| e.contains("trailing_stop.trail 1.5")), "{:?}", errors);
+    assert!(errors.iter().any(|e| e.contains("trailing_stop.activation -0.1")), "{:?}", errors);
+    assert!(Config::parse("[strategy]\ntrailing_stop = { trial = 0.02 }\n").unwrap_err().contains("trial"));
+}
+
+#[test]
+fn partial_fill_policy_is_a_strategy_setting() {
+    let config = Config::parse("[strategy]\npartial_fill = \"accept\"\n").unwrap();
+    assert_eq!(config.strategy.partial_fill, PartialFillAction::Accept);
+    let err = Config::parse("[strategy]\npartial_fill = \"hold\"\n").unwrap_err();
+    assert!(err.contains("hold"), "{}", err);
+}
+
+#[test]
+fn exit_before_close_must_leave_time_to_trade() {
+    assert_eq!(Config::default().strategy.exit_before_close, 0);
+    let config = Config::parse("[strategy]\nexit_before_close = 30\n").unwrap();
+    assert_eq!(config.strategy.exit_before_close, 30);
+    assert!(config.validate().is_empty());
+
+    let errors = Config::parse("[strategy]\nmarket_window = 60\nexit_before_close = 60\n").unwrap().validate();
+    assert!(errors.iter().any(|e| e.contains("exit_before_close 60s must be under market_window 60s")), "{:?}", errors);
+}
+
+#[test]
+fn tranches_must_split_the_whole_position() {
+    assert!(Config::default().strategy.tranches.is_empty());
+    let config = Config::parse("[strategy]\ntranches = [{ price = 0.95, fraction = 0.5 }, { price = 0.97, fraction = 0.5 }]\n").unwrap();
+    assert_eq!(config.strategy.tranches, [Tranche { price: 0.95, fraction: 0.5 }, Tranche { price: 0.97, fraction: 0.5 }]);
+    assert!(config.validate().is_empty());
+
+    let config = Config::parse("[strategy]\ntranches = [{ price = 0.995, fraction = 0.5 }, { price = 0.97, fraction = 0.0 }]\n").unwrap();
+    let errors = config.validate();
+    assert!(errors.iter().any(|e| e.contains("tranches[0].price 0.995")), "{:?}", errors);
+    assert!(errors.iter().any(|e| e.contains("tranches[1].fraction 0")), "{:?}", errors);
+    assert!(errors.iter().any(|e| e.contains("add up to 0.5, not 1")), "{:?}", errors);
+}
+
+#[test]
+fn liquidation_ladder_is_bounded() {
+    let config = Config::default();
+    assert_eq!((config.risk.liquidation_slippage, config.risk.liquidation_rungs), (0.03, 3));
+    let errors = Config::parse("[risk]\nliquidation_slippage = 1.0\nliquidation_rungs = 0\n").unwrap().validate();
+    assert!(errors.iter().any(|e| e.contains("risk.liquidation_slippage 1")), "{:?}", errors);
+    assert!(errors.iter().any(|e| e.contains("risk.liquidation_rungs must be at least 1")), "{:?}", errors);
+}
+
+#[test]
+fn balance_sizing_replaces_the_fixed_size_when_set() {
+    assert_eq!(Config::default().strategy.sizing, None);
+    let config = Config::parse("[strategy]\nsizing = { balance_pct = 5.0, max_shares = 50 }\n").unwrap();
+    assert_eq!(config.strategy.sizing, Some(BalanceSizing { balance_pct: 5.0, min_shares: 0, max_shares: 50 }));
+    assert!(config.validate().is_empty());
+
+    let errors = Config::parse("[strategy]\nsizing = { balance_pct = 150.0, min_shares = 10, max_shares = 5 }\n").unwrap().validate();
+    assert!(errors.iter().any(|e| e.contains("sizing.balance_pct 150")), "{:?}", errors);
+    assert!(errors.iter().any(|e| e.contains("sizing.max_shares 5 must be at least 1 and at least min_shares 10")), "{:?}", errors);
+    assert!(Config::parse("[strategy]\nsizing = { balance_pct = 5.0 }\n").unwrap_err().contains("max_shares"));
+}
+
+#[test]
+fn asset_overrides_apply_only_to_their_asset() {
+    let text = r#"
+        [strategy]
+        entry_price = 0.96
+        position_size = 5
+
+        [assets.btc]
+        entry_price = 0.95
+        stop_loss_price = 0.85
+    "#;
+
+    let mut eth = Config::parse(text).unwrap();
+    eth.select_asset(None);
+    assert_eq!(eth.market.asset, "eth");
+    assert_eq!(eth.market.schedule().unwrap().slug(1_760_000_400), "eth-updown-15m-1760000400");
+    assert_eq!(eth.strategy.entry_price, 0.96);
+
+    let mut btc = Config::parse(text).unwrap();
+    btc.select_asset(Some("BTC"));
+    assert_eq!(btc.market.schedule().unwrap().slug(1_760_000_400), "btc-updown-15m-1760000400");
+    assert_eq!(btc.strategy.entry_price, 0.95);
+    assert_eq!(btc.strategy.stop_loss_price, 0.85);
+    assert_eq!(btc.strategy.position_size, 5);
+    assert!(btc.validate().is_empty());
+
+    assert_eq!(Config::parse(text).unwrap().traded_assets(), ["eth"]);
+    let both = Config::parse("[market]\nassets = [\"eth\", \"btc\"]\n[risk]\nmax_open_positions = 1\n").unwrap();
+    assert_eq!(both.traded_assets(), ["eth", "btc"]);
+    assert_eq!(both.risk.max_open_positions, 1);
+
+    let mut from_file = Config::parse("[market]\nasset = \"btc\"\n[assets.btc]\nposition_size = 2\n").unwrap();
+    from_file.select_asset(None);
+    assert_eq!(from_file.strategy.position_size, 2);
+
+    let err = Config::parse("[assets.btc]\nentry_prise = 0.95\n").unwrap_err();
+    assert!(err.contains("entry_prise"), "{}", err);
+    let errors = Config::parse("[market]\nasset = \"btc-usd\"\n").unwrap().validate();
+    assert!(errors.iter().any(|e| e.contains("btc-usd")), "{:?}", errors);
+}
+
+#[test]
+fn market_cadence_sets_the_slug_and_the_longest_window() {
+    let config = Config::parse(r#"
+        [strategy]
+        market_window = 1200
+        entry_timeout = 60
+
+        [market]
+        cadence = "1h"
+        slug = "{asset}-up-or-down-{date}-{hour}"
+    "#).unwrap();
+    let schedule = config.market.schedule().unwrap();
+    assert_eq!(schedule.cadence, 3_600);
+    assert_eq!(schedule.slug(1_760_000_400), "eth-up-or-down-2025-10-09-09");
+    assert!(config.validate().is_empty(), "{:?}", config.validate());
+
+    // A 20-minute window doesn't fit a 15-minute market
+    let errors = Config::parse("[strategy]\nmarket_window = 1200\nentry_timeout = 60\n").unwrap().validate();
+    assert!(errors.iter().any(|e| e.contains("market_window 1200s")), "{:?}", errors);
+
+    let errors = Config::parse("[market]\ncadence = \"fortnightly\"\nslug = \"{asset}-{day}\"\n").unwrap().validate();
+    assert!(errors.iter().any(|e| e.contains("fortnightly")), "{:?}", errors);
+}
+
+#[test]
+fn discovery_needs_a_filter() {
+    let errors = Config::parse("[market]\ndiscover = true\n").unwrap().validate();
+    assert!(errors.iter().any(|e| e.contains("tag or series_id")), "{:?}", errors);
+
+    let config = Config::parse("[market]\ndiscover = true\ntag = \"crypto\"\n").unwrap();
+    assert!(config.validate().is_empty(), "{:?}", config.validate());
+    assert!(config.market.finder().unwrap().is_discovering());
+    assert!(!Config::default().market.finder().unwrap().is_discovering());
+}
+
+#[test]
+fn validate_reports_every_problem() {
+    let config = Config::parse(r#"
+        dry_run = true
+        paper = true
+
+        [strategy]
+        entry_price = 0.90
+        stop_loss_price = 0.92
+
+        [timing]
+        http_timeout = 0
+
+        [risk]
+        low_balance_threshold = -1.0
+    "#).unwrap();
+
+    let errors = config.validate();
+    for problem in ["stop_loss_price 0.92", "timing.http_timeout", "risk.low_balance_threshold -1", "dry_run and paper"] {
+        assert!(errors.iter().any(|e| e.contains(problem)), "missing {:?} in {:?}", problem, errors);
+    }
+}
+
+#[test]
+fn load_names_the_file_it_read() {
+    let path = std::env::temp_dir().join(format!("config_load_{}.toml", std::process::id()));
+    std::fs::write(&path, "[strategy]\nposition_size = 8\n").unwrap();
+    let config = Config::load(path.to_str().unwrap()).unwrap();
+    assert_eq!(config.strategy.position_size, 8);
+    assert_eq!(config.source.as_deref(), path.to_str());
+
+    std::fs::write(&path, "[strategy\n").unwrap();
+    let err = Config::load(path.to_str().unwrap()).unwrap_err();
+    assert!(err.starts_with(&format!("Invalid config {}", path.display())), "{}", err);
+    let _ = std::fs::remove_file(&path);
+
+    let err = Config::load("/nonexistent/config.toml").unwrap_err();
+    assert!(err.contains("Cannot read config /nonexistent/config.toml"), "{}", err);
+}
+
+#[test]
+fn env_variables_override_the_file() {
+    let vars = |pairs: &[(&str, &str)]| pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect::<Vec<_>>();
+    let text = "[strategy]\nentry_price = 0.95\nposition_size = 8\n[market]\nasset = \"btc\"\n";
+    let config = Config::parse_with_env(text, vars(&[
+        ("BOT__STRATEGY__ENTRY_PRICE", "0.94"),
+        ("BOT__RISK__LIQUIDATION_RUNGS", "2"),
+        ("BOT__MARKET__ASSET", "eth"),
+        ("BOT__ASSETS__BTC__POSITION_SIZE", "3"),
+        ("BOT__DRY_RUN", "true"),
+        ("BOT_TIE_BREAK", "bid"),
+        ("PATH", "/usr/bin"),
+    ])).unwrap();
+    assert_eq!(config.strategy.entry_price, 0.94);
+    assert_eq!(config.strategy.position_size, 8);
+    assert_eq!(config.risk.liquidation_rungs, 2);
+    assert_eq!(config.market.asset, "eth");
+    assert_eq!(config.assets["btc"].position_size, Some(3));
+    assert!(config.dry_run);
+    assert_eq!(Config::parse_with_env(text, vars(&[("PATH", "/usr/bin")])).unwrap(), Config::parse(text).unwrap());
+
+    // A typo or a bad value names the variable instead of being ignored
+    let err = Config::parse_with_env(text, vars(&[("BOT__STRATEGY__ENTRY_PRISE", "0.94")])).unwrap_err();
+    assert!(err.starts_with("BOT__STRATEGY__ENTRY_PRISE: ") && err.contains("entry_prise"), "{}", err);
+    let err = Config::parse_with_env(text, vars(&[("BOT__STRATEGY__POSITION_SIZE", "lots")])).unwrap_err();
+    assert!(err.starts_with("BOT__STRATEGY__POSITION_SIZE: "), "{}", err);
+    let err = Config::parse_with_env(text, vars(&[("BOT__STRATEGY__ENTRY_PRICE__X", "1")])).unwrap_err();
+    assert!(err.contains("entry_price is a key, not a section"), "{}", err);
+    assert!(Config::parse_with_env(text, vars(&[("BOT__", "1")])).unwrap_err().contains("BOT__<SECTION>__<KEY>"));
+
+    // File errors keep their line numbers
+    let err = Config::parse_with_env("[strategy]\nentry_prise = 0.95\n", vars(&[("BOT__DRY_RUN", "true")])).unwrap_err();
+    assert!(err.contains("line 2"), "{}", err);
+}
+
+#[test]
+fn private_keys_are_spotted_with_or_without_0x() {
+    let key = "4c0883a69102937d6231471b5dbb6204".repeat(2);
+    assert_eq!(config::find_private_key(&format!("a = 1\nkey = \"0x{}\"\n", key)), Some(2));
+    assert_eq!(config::find_private_key(&format!("# {}", key)), Some(1));
+    // Addresses and shorter hex are fine
+    assert_eq!(config::find_private_key("trading = \"0x00000000000000000000000000000000000a11ce\""), None);
+    assert_eq!(config::find_private_key(&format!("x = \"{}ab\"", key)), None);
+}
+
+// 32-byte hex in the tree that is public by design: Foundry's anvil dev
+// key, the webhook signature vector and a condition id in the book bench
+const PUBLIC_HEX: [&str; 3] = [
+    "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+    "44627cdfe19800e025ef3ab8cd37635322d9ceacdb01bf5c7eca89f8dd3b79b0",
+    "5f65177b394277fd294cd75650044e32ba009a95022d88a0c1d565897d72f8f1",
+];
+
+fn rust_sources(dir: &std::path::Path, out: &mut Vec<std::path::PathBuf>) {
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            if path.file_name().is_some_and(|name| name != "target") {
+                rust_sources(&path, out);
+            }
+        } else if path.extension().is_some_and(|e| e == "rs") {
+            out.push(path);
+        }
+    }
+}
+
+#[test]
+fn no_private_key_in_the_source() {
+    let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(root).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_some_and(|e| e == "rs") {
+            files.push(path);
+        }
+    }
+    for dir in ["tests", "benches", "fuzz"] {
+        rust_sources(&root.join(dir), &mut files);
+    }
+    assert!(files.iter().any(|f| f.ends_with("tests/common/mod.rs")), "{:?}", files);
+
+    for path in &files {
+        let text = std::fs::read_to_string(path).unwrap();
+        for (i, line) in text.lines().enumerate() {
+            // Placeholders like 0x1111... or 0x00...01 are no one's key
+            let public = |word: &str| PUBLIC_HEX.contains(&word.to_ascii_lowercase().as_str())
+                || word.chars().collect::<std::collections::HashSet<_>>().len() <= 2;
+            let masked: String = line.split(|c: char| !c.is_ascii_alphanumeric())
+                .map(|word| word.strip_prefix("0x").unwrap_or(word))
+                .filter(|word| !public(word))
+                .collect::<Vec<_>>()
+                .join(" ");
+            assert_eq!(config::find_private_key(&masked), None,
+                "{}:{} holds something shaped like a private key", path.display(), i + 1);
+        }
+    }
+}
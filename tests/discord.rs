@@ -0,0 +1,47 @@
+//! Discord channel: message content and delivery to the channel webhook.
+
+use std::sync::mpsc;
+use std::time::Duration;
+
+use eth_no_trend_bot::discord::{self, DiscordConfig, DiscordNotifier};
+use eth_no_trend_bot::notify::{Notification, Notifier, Severity};
+
+fn abort() -> Notification {
+    Notification::new(Severity::Warning, "Aborted eth-updown-15m-1760000400", "Gave up during monitoring with the ask at $0.995", 1_760_000_400)
+        .with_event("abort")
+}
+
+#[test]
+fn content_is_bold_title_then_body_within_the_limit() {
+    assert_eq!(discord::content(&abort()), "⚠️ **Aborted eth-updown-15m-1760000400**\nGave up during monitoring with the ask at $0.995");
+    let long = Notification::new(Severity::Info, "Long", "é".repeat(3000), 0);
+    assert_eq!(discord::content(&long).chars().count(), discord::MAX_CONTENT);
+}
+
+#[test]
+fn subscribed_events_are_posted_to_the_webhook() {
+    let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/api/webhooks/1/secret", server.server_addr().to_ip().unwrap());
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for mut request in server.incoming_requests() {
+            let mut body = String::new();
+            request.as_reader().read_to_string(&mut body).unwrap();
+            tx.send((request.url().to_string(), body)).unwrap();
+            request.respond(tiny_http::Response::empty(204)).unwrap();
+        }
+    });
+
+    let channel = DiscordNotifier::new(DiscordConfig::new(&url)).unwrap();
+    channel.send(&Notification::new(Severity::Info, "Skipping", "", 1).with_event("skipped")).unwrap();
+    channel.send(&abort()).unwrap();
+    drop(channel);
+
+    let (path, body) = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(path, "/api/webhooks/1/secret");
+    let message: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(message["content"], discord::content(&abort()));
+    // Titles quoting user text can't ping anyone
+    assert_eq!(message["allowed_mentions"]["parse"], serde_json::json!([]));
+    assert!(rx.try_recv().is_err());
+}
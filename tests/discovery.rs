@@ -0,0 +1,69 @@
+use eth_no_trend_bot::discovery::{GammaFilters, MarketFinder};
+use eth_no_trend_bot::market_schedule::MarketSchedule;
+use serde_json::json;
+
+// 2025-10-09T09:00:00Z; its 15-minute market closes at 09:15
+const START: u64 = 1_760_000_400;
+
+fn event(slug: &str, end_date: &str, order_book: bool) -> serde_json::Value {
+    json!({
+        "slug": slug,
+        "title": format!("Event {}", slug),
+        "endDate": end_date,
+        "markets": [{
+            "enableOrderBook": order_book,
+            "conditionId": "0xabc",
+            "clobTokenIds": "[\"1001\", \"1002\"]",
+        }],
+    })
+}
+
+#[test]
+fn slug_mode_asks_for_the_templated_slug() {
+    let finder = MarketFinder::by_slug(MarketSchedule::default());
+    assert!(!finder.is_discovering());
+    assert_eq!(finder.query(START), "/events?slug=eth-updown-15m-1760000400");
+    assert_eq!(finder.describe(START), "eth-updown-15m-1760000400");
+
+    let body = json!([event("eth-updown-15m-1760000400", "2025-10-09T09:15:00Z", true)]).to_string();
+    let market = finder.parse(START, body.as_bytes()).unwrap().unwrap();
+    assert_eq!(market.slug, "eth-updown-15m-1760000400");
+    assert_eq!(market.end_ts, Some(START + 900));
+}
+
+#[test]
+fn discovery_searches_by_close_time_and_filters() {
+    let filters = GammaFilters { tag_slug: "crypto".to_string(), series_id: "10192".to_string() };
+    let finder = MarketFinder::discover(MarketSchedule::default(), filters);
+    assert!(finder.is_discovering());
+    let query = finder.query(START);
+    assert!(query.starts_with("/events?closed=false&"), "{}", query);
+    assert!(query.contains("end_date_min=2025-10-09T09:14:00Z&end_date_max=2025-10-09T09:16:00Z"), "{}", query);
+    assert!(query.contains("&tag_slug=crypto&series_id=10192"), "{}", query);
+    assert_eq!(finder.describe(START), "eth market closing 2025-10-09T09:15:00Z");
+
+    let only_tag = MarketFinder::discover(MarketSchedule::default(), GammaFilters { tag_slug: "crypto".to_string(), ..Default::default() });
+    assert!(!only_tag.query(START).contains("series_id"));
+}
+
+#[test]
+fn discovery_takes_the_first_tradable_event_closing_with_the_cycle() {
+    let finder = MarketFinder::discover(MarketSchedule::default(), GammaFilters { tag_slug: "crypto".to_string(), ..Default::default() });
+    let body = json!([
+        // Closes with the next cycle
+        event("eth-next", "2025-10-09T09:30:00Z", true),
+        // Right time, but no order book yet
+        event("eth-unlisted", "2025-10-09T09:15:00Z", false),
+        event("ethereum-up-or-down-oct-9-915am", "2025-10-09T09:15:00.000Z", true),
+    ]).to_string();
+
+    let market = finder.parse(START, body.as_bytes()).unwrap().unwrap();
+    assert_eq!(market.slug, "ethereum-up-or-down-oct-9-915am");
+    assert_eq!(market.link, "https://polymarket.com/event/ethereum-up-or-down-oct-9-915am");
+    assert_eq!((market.yes_token.as_str(), market.no_token.as_str()), ("1001", "1002"));
+    assert_eq!(market.end_ts, Some(START + 900));
+
+    assert!(finder.parse(START, b"[]").unwrap().is_none());
+    let late = json!([event("eth-late", "2025-10-09T09:17:00Z", true)]).to_string();
+    assert!(finder.parse(START, late.as_bytes()).unwrap().is_none());
+}
@@ -0,0 +1,41 @@
+//! Warning deduplication: identical messages are shown once per window and
+//! summarized afterwards; a per-window cap limits distinct messages.
+
+use eth_no_trend_bot::event_log::{Emit, EventLog};
+
+#[test]
+fn identical_messages_collapse_into_a_summary() {
+    let mut log = EventLog::new(60, 20);
+    assert_eq!(log.check("book fetch error", 100), Emit::Show);
+    for t in 101..=110 {
+        assert_eq!(log.check("book fetch error", t), Emit::Suppress);
+    }
+    assert_eq!(log.check("order rejected", 105), Emit::Show);
+    assert!(log.flush(159).is_empty());
+
+    assert_eq!(log.flush(160), ["… \"book fetch error\" repeated 10 more times"]);
+    // The window is over, so the next one shows again
+    assert_eq!(log.check("book fetch error", 161), Emit::Show);
+    // "order rejected" never repeated; it expires without a summary
+    assert!(log.flush(170).is_empty());
+}
+
+#[test]
+fn distinct_messages_are_capped_per_window() {
+    let mut log = EventLog::new(60, 2);
+    assert_eq!(log.check("order 1 failed", 0), Emit::Show);
+    assert_eq!(log.check("order 2 failed", 1), Emit::Show);
+    assert_eq!(log.check("order 3 failed", 2), Emit::Suppress);
+    assert_eq!(log.check("order 4 failed", 3), Emit::Suppress);
+    assert_eq!(log.flush(60), ["… 2 other warnings suppressed"]);
+    assert_eq!(log.check("order 5 failed", 61), Emit::Show);
+}
+
+#[test]
+fn zero_window_shows_everything() {
+    let mut log = EventLog::new(0, 1);
+    for t in 0..5 {
+        assert_eq!(log.check("same", t), Emit::Show);
+    }
+    assert!(log.flush(10).is_empty());
+}
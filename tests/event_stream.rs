@@ -0,0 +1,95 @@
+//! SSE event feed: subscribers get each published event as a framed JSON
+//! message; /metrics serves the latest snapshot; other paths are refused.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+
+use eth_no_trend_bot::bot_event::BotEvent;
+use eth_no_trend_bot::event_stream::{self, EventServer};
+
+fn subscribe(server: &EventServer, path: &str) -> BufReader<TcpStream> {
+    let mut stream = TcpStream::connect(server.local_addr()).unwrap();
+    stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+    write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\nAccept: text/event-stream\r\n\r\n", path).unwrap();
+    BufReader::new(stream)
+}
+
+fn read_until_blank(reader: &mut BufReader<TcpStream>) -> Vec<String> {
+    let mut lines = Vec::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        if line.trim().is_empty() {
+            return lines;
+        }
+        lines.push(line.trim_end().to_string());
+    }
+}
+
+fn fill() -> BotEvent {
+    BotEvent::Fill { order_id: "0x01".to_string(), token_id: "1002".to_string(), side: "BUY".to_string(), size: 5.0, filled: 5.0, avg_price: 0.97 }
+}
+
+#[test]
+fn frames_carry_sequence_timestamp_and_type() {
+    let frame = event_stream::frame(7, 1_760_000_400, &fill());
+    let lines: Vec<&str> = frame.lines().collect();
+    assert_eq!(&lines[..2], ["id: 7", "event: fill"]);
+    let data: serde_json::Value = serde_json::from_str(lines[2].strip_prefix("data: ").unwrap()).unwrap();
+    assert_eq!(data["seq"], 7);
+    assert_eq!(data["ts"], 1_760_000_400u64);
+    assert_eq!(data["type"], "fill");
+    assert_eq!(data["avg_price"], 0.97);
+    assert!(frame.ends_with("\n\n"));
+}
+
+#[test]
+fn subscribers_receive_published_events() {
+    let server = EventServer::start("127.0.0.1:0").unwrap();
+    let mut reader = subscribe(&server, "/events");
+
+    let head = read_until_blank(&mut reader);
+    assert_eq!(head[0], "HTTP/1.1 200 OK");
+    assert!(head.contains(&"Content-Type: text/event-stream".to_string()));
+    assert_eq!(read_until_blank(&mut reader), [": connected"]);
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while server.subscribers() == 0 {
+        assert!(Instant::now() < deadline, "subscriber never registered");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    server.publish(100, &BotEvent::State { market: "eth-updown-15m-1".to_string(), state: "monitoring".to_string() });
+    server.publish(101, &fill());
+
+    let first = read_until_blank(&mut reader);
+    assert_eq!(&first[..2], ["id: 1", "event: state"]);
+    assert!(first[2].contains("\"state\":\"monitoring\""), "{:?}", first);
+    let second = read_until_blank(&mut reader);
+    assert_eq!(&second[..2], ["id: 2", "event: fill"]);
+}
+
+#[test]
+fn other_paths_get_404_and_are_not_subscribed() {
+    let server = EventServer::start("127.0.0.1:0").unwrap();
+    let mut reader = subscribe(&server, "/");
+    let mut response = String::new();
+    reader.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 404"), "{}", response);
+    assert_eq!(server.subscribers(), 0);
+}
+
+#[test]
+fn metrics_serve_the_latest_snapshot() {
+    let server = EventServer::start("127.0.0.1:0").unwrap();
+    server.set_metrics(serde_json::json!({"overall": {"trades": 3}}));
+    let mut reader = subscribe(&server, "/metrics");
+    let head = read_until_blank(&mut reader);
+    assert_eq!(head[0], "HTTP/1.1 200 OK");
+    assert!(head.contains(&"Content-Type: application/json".to_string()));
+    let mut body = String::new();
+    reader.read_to_string(&mut body).unwrap();
+    assert_eq!(serde_json::from_str::<serde_json::Value>(&body).unwrap()["overall"]["trades"], 3);
+    assert_eq!(server.subscribers(), 0);
+}
@@ -0,0 +1,51 @@
+use eth_no_trend_bot::exposure::{self, Leg, MarketExposure};
+use eth_no_trend_bot::strategy::Outcome;
+
+fn leg(market: &str, outcome: Outcome, shares: f64, price: f64) -> Leg {
+    Leg { market: market.to_string(), outcome, shares, price }
+}
+
+#[test]
+fn yes_and_no_of_one_market_offset() {
+    let legs = [
+        leg("0xaa", Outcome::Yes, 10.0, 0.40),
+        leg("0xbb", Outcome::No, 5.0, 0.97),
+        leg("0xaa", Outcome::No, 4.0, 0.55),
+    ];
+    let markets = exposure::net(&legs);
+    assert_eq!(markets.len(), 2);
+
+    let aa = &markets[0];
+    assert_eq!((aa.market.as_str(), aa.yes, aa.no), ("0xaa", 10.0, 4.0));
+    assert_eq!(aa.complete_sets(), 4.0);
+    assert_eq!(aa.directional(), Some((Outcome::Yes, 6.0)));
+    assert!((aa.at_risk() - 2.4).abs() < 1e-9);
+    // Sets at $1, not at 0.40 + 0.55
+    assert!((aa.value() - 6.4).abs() < 1e-9);
+
+    let bb = &markets[1];
+    assert_eq!(bb.complete_sets(), 0.0);
+    assert_eq!(bb.directional(), Some((Outcome::No, 5.0)));
+    assert!((bb.value() - 4.85).abs() < 1e-9);
+}
+
+#[test]
+fn a_balanced_market_is_flat() {
+    let flat = MarketExposure { market: "0xaa".to_string(), yes: 3.0, no: 3.0, yes_price: 0.5, no_price: 0.49 };
+    assert_eq!(flat.directional(), None);
+    assert_eq!(flat.at_risk(), 0.0);
+    assert_eq!(flat.value(), 3.0);
+}
+
+#[test]
+fn merges_are_suggested_for_whole_sets() {
+    let markets = exposure::net(&[
+        leg("0xaa", Outcome::Yes, 7.5, 0.0),
+        leg("0xaa", Outcome::No, 2.9, 0.0),
+        leg("0xbb", Outcome::Yes, 0.6, 0.0),
+        leg("0xbb", Outcome::No, 0.6, 0.0),
+        leg("0xcc", Outcome::Yes, 9.0, 0.0),
+    ]);
+    assert_eq!(exposure::merge_suggestions(&markets, 1.0), [("0xaa".to_string(), 2.0)]);
+    assert!(exposure::merge_suggestions(&markets, 3.0).is_empty());
+}
@@ -0,0 +1,106 @@
+//! Simulated fills: displayed-size share, adverse selection and queue
+//! position, and their effect on strategy replays.
+
+use eth_no_trend_bot::fill_model::{FillModel, FillSimulator, SimFill};
+use eth_no_trend_bot::strategy::{self, EntryExecution, OrderBook, Outcome, ReplayOutcome, StrategyParams, TieBreak, Tick, TradeSide};
+
+const START: u64 = 1_760_000_400;
+
+fn book(bid: f64, bid_size: f64, ask: f64, ask_size: f64) -> OrderBook {
+    OrderBook { best_bid: Some(bid), bid_size, best_ask: Some(ask), ask_size }
+}
+
+fn model(displayed_share: f64, adverse_selection: f64) -> FillModel {
+    FillModel { displayed_share, adverse_selection, adverse_move: 0.01, ..FillModel::default() }
+}
+
+#[test]
+fn default_model_fills_at_the_displayed_ask() {
+    let mut sim = FillSimulator::new(FillModel::default());
+    assert_eq!(sim.take(&book(0.96, 10.0, 0.97, 5.0), 5.0, 0.97, true), SimFill { size: 5.0, price: 0.97 });
+    // More than is displayed never fills FOK
+    assert_eq!(sim.take(&book(0.96, 10.0, 0.97, 4.0), 5.0, 0.97, true), SimFill::NONE);
+}
+
+#[test]
+fn only_a_share_of_displayed_size_is_available() {
+    let mut sim = FillSimulator::new(model(0.5, 0.0));
+    let thin = book(0.96, 10.0, 0.97, 9.0);
+    assert_eq!(sim.take(&thin, 5.0, 0.97, true), SimFill::NONE);
+    assert_eq!(sim.take(&thin, 5.0, 0.97, false), SimFill { size: 4.0, price: 0.97 });
+}
+
+#[test]
+fn adverse_selection_lifts_the_ask_and_is_repeatable() {
+    let mut always = FillSimulator::new(model(1.0, 1.0));
+    let touch = book(0.96, 10.0, 0.97, 50.0);
+    assert_eq!(always.take(&touch, 5.0, 0.97, true), SimFill::NONE);
+    let fill = always.take(&touch, 5.0, 0.99, true);
+    assert!((fill.price - 0.98).abs() < 1e-9 && fill.size == 5.0, "{:?}", fill);
+
+    let run = |seed| {
+        let mut sim = FillSimulator::new(FillModel { seed, ..model(1.0, 0.5) });
+        (0..64).map(|_| sim.take(&touch, 5.0, 0.97, true).size > 0.0).collect::<Vec<_>>()
+    };
+    assert_eq!(run(7), run(7));
+    let hits = run(7).iter().filter(|filled| **filled).count();
+    assert!((16..=48).contains(&hits), "{} of 64 filled", hits);
+}
+
+#[test]
+fn sells_hit_the_bid_and_adverse_selection_drops_it() {
+    let mut sim = FillSimulator::new(FillModel::default());
+    let touch = book(0.90, 6.0, 0.92, 50.0);
+    assert_eq!(sim.hit(&touch, 5.0, 0.89, true), SimFill { size: 5.0, price: 0.90 });
+    // A bid under the limit doesn't fill; FAK takes what's displayed
+    assert_eq!(sim.hit(&touch, 5.0, 0.91, false), SimFill::NONE);
+    assert_eq!(sim.hit(&touch, 8.0, 0.90, false), SimFill { size: 6.0, price: 0.90 });
+    assert_eq!(sim.hit(&touch, 8.0, 0.90, true), SimFill::NONE);
+
+    let mut always = FillSimulator::new(model(1.0, 1.0));
+    assert_eq!(always.hit(&touch, 5.0, 0.90, true), SimFill::NONE);
+    let fill = always.hit(&touch, 5.0, 0.85, true);
+    assert!((fill.price - 0.89).abs() < 1e-9 && fill.size == 5.0, "{:?}", fill);
+}
+
+#[test]
+fn passive_orders_wait_behind_the_queue() {
+    let sim = FillSimulator::new(FillModel { queue_ahead_share: 0.5, ..FillModel::default() });
+    let mut order = sim.rest(&book(0.95, 100.0, 0.97, 50.0), 0.95, 10.0);
+    assert_eq!(order.queue_ahead, 50.0);
+
+    // 30 of the 50 ahead traded or left
+    assert_eq!(sim.advance(&mut order, &book(0.95, 70.0, 0.97, 50.0)), SimFill::NONE);
+    // 25 more: the last 20 ahead, then 5 of ours
+    assert_eq!(sim.advance(&mut order, &book(0.95, 45.0, 0.97, 50.0)), SimFill { size: 5.0, price: 0.95 });
+    // A better bid hides our level; nothing to learn
+    assert_eq!(sim.advance(&mut order, &book(0.96, 30.0, 0.97, 50.0)), SimFill::NONE);
+    // Ask trades down through our price: the rest fills
+    assert_eq!(sim.advance(&mut order, &book(0.94, 10.0, 0.95, 20.0)), SimFill { size: 5.0, price: 0.95 });
+    assert_eq!(order.remaining, 0.0);
+}
+
+#[test]
+fn replay_retries_missed_entries_until_timeout() {
+    let params = StrategyParams {
+        trade_side: TradeSide::Both,
+        entry_price: 0.96,
+        abort_ask_price: 0.99,
+        position_size: 5,
+        market_window: 240,
+        entry_timeout: 210,
+        tie_break: TieBreak::default(),
+        entry_exec: EntryExecution::default(),
+    };
+    let ticks: Vec<Tick> = (670..900)
+        .map(|s| Tick { ts: START + s, yes: book(0.02, 100.0, 0.03, 100.0), no: book(0.97, 100.0, 0.98, 6.0) })
+        .collect();
+
+    assert_eq!(
+        strategy::replay(&params, START, &ticks),
+        ReplayOutcome::Entered { ts: START + 670, outcome: Outcome::No, ask: 0.98, size: 5 }
+    );
+    // Half of the 6 displayed shares is never enough for a 5-share FOK
+    let mut thin = FillSimulator::new(model(0.5, 0.0));
+    assert_eq!(strategy::replay_with(&params, START, &ticks, &mut thin), ReplayOutcome::TimedOut { ts: START + 881 });
+}
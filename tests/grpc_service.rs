@@ -0,0 +1,130 @@
+//! Execution gRPC service against an in-memory backend: every RPC reaches
+//! the backend on the serving thread and backend errors keep their status.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::net::{SocketAddr, TcpListener};
+use std::time::Duration;
+
+use tonic::{Code, Status};
+
+use eth_no_trend_bot::grpc::{self, BookReply, BookRequest, CancelOrderRequest, ExecutionBackend, ExecutionClient, OrderStatusReply,
+    OrderStatusRequest, PlaceOrderReply, PlaceOrderRequest, Position, PositionsRequest, Side};
+
+/// Fills every valid order in full at its limit price. Uses RefCell on
+/// purpose: the backend never leaves the serving thread.
+#[derive(Default)]
+struct FakeBackend {
+    orders: RefCell<HashMap<String, OrderStatusReply>>,
+    positions: RefCell<HashMap<String, f64>>,
+}
+
+impl ExecutionBackend for FakeBackend {
+    fn place_order(&self, request: PlaceOrderRequest) -> Result<PlaceOrderReply, Status> {
+        if request.size == 0 {
+            return Err(Status::invalid_argument("size must be positive"));
+        }
+        let order_id = format!("0x{:02}", self.orders.borrow().len() + 1);
+        let signed = if request.side == Side::Sell as i32 { -1.0 } else { 1.0 } * request.size as f64;
+        *self.positions.borrow_mut().entry(request.token_id).or_insert(0.0) += signed;
+        self.orders.borrow_mut().insert(order_id.clone(), OrderStatusReply {
+            status: "MATCHED".to_string(),
+            original_size: request.size as f64,
+            filled_size: request.size as f64,
+            avg_price: request.price,
+        });
+        Ok(PlaceOrderReply { order_id, filled_size: request.size as f64, avg_price: request.price })
+    }
+
+    fn cancel_order(&self, order_id: &str) -> Result<(), Status> {
+        match self.orders.borrow().get(order_id) {
+            Some(_) => Err(Status::failed_precondition("order already matched")),
+            None => Err(Status::not_found(order_id)),
+        }
+    }
+
+    fn order_status(&self, order_id: &str) -> Result<OrderStatusReply, Status> {
+        self.orders.borrow().get(order_id).cloned().ok_or_else(|| Status::not_found(order_id))
+    }
+
+    fn positions(&self) -> Result<Vec<Position>, Status> {
+        let mut positions: Vec<Position> = self.positions.borrow().iter()
+            .map(|(token_id, size)| Position { token_id: token_id.clone(), size: *size })
+            .collect();
+        positions.sort_by(|a, b| a.token_id.cmp(&b.token_id));
+        Ok(positions)
+    }
+
+    fn book(&self, token_id: &str) -> Result<BookReply, Status> {
+        match token_id {
+            "1001" => Ok(BookReply { best_bid: Some(0.97), bid_size: 120.0, best_ask: Some(0.98), ask_size: 40.0 }),
+            _ => Ok(BookReply { best_bid: None, bid_size: 0.0, best_ask: None, ask_size: 0.0 }),
+        }
+    }
+}
+
+fn start_server() -> SocketAddr {
+    let addr = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+    std::thread::spawn(move || grpc::serve(addr, &FakeBackend::default()).unwrap());
+    addr
+}
+
+async fn connect(addr: SocketAddr) -> ExecutionClient<tonic::transport::Channel> {
+    for _ in 0..50 {
+        if let Ok(client) = ExecutionClient::connect(format!("http://{}", addr)).await {
+            return client;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("gRPC server did not come up on {}", addr);
+}
+
+fn order(token_id: &str, side: Side, size: u32) -> PlaceOrderRequest {
+    PlaceOrderRequest { token_id: token_id.to_string(), side: side as i32, price: 0.97, size, order_type: "FOK".to_string() }
+}
+
+#[tokio::test]
+async fn orders_round_trip_through_the_backend() {
+    let mut client = connect(start_server()).await;
+
+    let placed = client.place_order(order("1001", Side::Buy, 5)).await.unwrap().into_inner();
+    assert_eq!((placed.order_id.as_str(), placed.filled_size, placed.avg_price), ("0x01", 5.0, 0.97));
+    client.place_order(order("1001", Side::Sell, 2)).await.unwrap();
+    client.place_order(order("1002", Side::Buy, 3)).await.unwrap();
+
+    let status = client.get_order_status(OrderStatusRequest { order_id: "0x01".to_string() }).await.unwrap().into_inner();
+    assert_eq!(status.status, "MATCHED");
+    assert_eq!(status.filled_size, 5.0);
+
+    let positions = client.get_positions(PositionsRequest {}).await.unwrap().into_inner().positions;
+    assert_eq!(positions, vec![
+        Position { token_id: "1001".to_string(), size: 3.0 },
+        Position { token_id: "1002".to_string(), size: 3.0 },
+    ]);
+}
+
+#[tokio::test]
+async fn backend_errors_keep_their_status_code() {
+    let mut client = connect(start_server()).await;
+
+    let err = client.place_order(order("1001", Side::Buy, 0)).await.unwrap_err();
+    assert_eq!(err.code(), Code::InvalidArgument);
+
+    let err = client.cancel_order(CancelOrderRequest { order_id: "0xff".to_string() }).await.unwrap_err();
+    assert_eq!(err.code(), Code::NotFound);
+
+    client.place_order(order("1001", Side::Buy, 1)).await.unwrap();
+    let err = client.cancel_order(CancelOrderRequest { order_id: "0x01".to_string() }).await.unwrap_err();
+    assert_eq!(err.code(), Code::FailedPrecondition);
+}
+
+#[tokio::test]
+async fn empty_book_sides_come_back_unset() {
+    let mut client = connect(start_server()).await;
+
+    let book = client.get_book(BookRequest { token_id: "1001".to_string() }).await.unwrap().into_inner();
+    assert_eq!((book.best_bid, book.best_ask), (Some(0.97), Some(0.98)));
+
+    let book = client.get_book(BookRequest { token_id: "9999".to_string() }).await.unwrap().into_inner();
+    assert_eq!((book.best_bid, book.best_ask), (None, None));
+}
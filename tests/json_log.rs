@@ -0,0 +1,57 @@
+//! JSON log lines: the common keys, and how bot events, notifications and
+//! trade rows are laid out.
+
+use serde_json::Value;
+
+use eth_no_trend_bot::bot_event::BotEvent;
+use eth_no_trend_bot::json_log::JsonLog;
+use eth_no_trend_bot::notify::{Event, Notification, Severity};
+use eth_no_trend_bot::trade_log::TradeRecord;
+
+fn parse(line: &str) -> Value {
+    assert!(!line.contains('\n'), "{}", line);
+    serde_json::from_str(line).unwrap()
+}
+
+#[test]
+fn bot_events_keep_their_fields_under_the_common_keys() {
+    let log = JsonLog::new("main");
+    let state = parse(&log.bot_event(1_760_000_760, &BotEvent::State { market: "eth-updown-15m-1760000400".to_string(), state: "monitoring".to_string() }));
+    assert_eq!(state["ts"], "2025-10-09T09:06:00Z");
+    assert_eq!(state["unix"], 1_760_000_760u64);
+    assert_eq!((state["level"].as_str(), state["event"].as_str(), state["profile"].as_str()), (Some("info"), Some("state"), Some("main")));
+    assert_eq!(state["state"], "monitoring");
+    assert!(state.get("type").is_none());
+
+    let tick = parse(&log.bot_event(1, &BotEvent::Tick { market: "m".to_string(), secs_left: 200, yes_bid: Some(0.02), yes_ask: None, no_bid: Some(0.97), no_ask: Some(0.98) }));
+    assert_eq!((tick["level"].as_str(), tick["event"].as_str()), (Some("debug"), Some("tick")));
+    assert_eq!(tick["yes_ask"], Value::Null);
+    let rejected = parse(&log.bot_event(1, &BotEvent::OrderRejected { token_id: "1002".to_string(), side: "BUY".to_string(), reason: "not enough balance".to_string() }));
+    assert_eq!(rejected["level"], "warning");
+}
+
+#[test]
+fn notifications_and_trades_are_their_own_events() {
+    let log = JsonLog::new("default");
+    let abort = Notification::new(Severity::Warning, "Aborted eth-updown-15m-1760000400", "Gave up during monitoring", 1_760_000_400)
+        .with_event(Event::Abort.as_str());
+    let line = parse(&log.notification(&abort));
+    assert_eq!((line["level"].as_str(), line["event"].as_str(), line["kind"].as_str()), (Some("warning"), Some("notification"), Some("abort")));
+    assert_eq!(line["title"], "Aborted eth-updown-15m-1760000400");
+
+    let record = TradeRecord {
+        status: "ENTERED".to_string(),
+        market: "eth-updown-15m-1760000400".to_string(),
+        side: "NO".to_string(),
+        entry_time: Some(1_760_000_700),
+        entry_price: Some(0.975),
+        size: Some(5.0),
+        ..Default::default()
+    };
+    let trade = parse(&log.trade(1_760_000_700, &record));
+    assert_eq!(trade["event"], "trade");
+    assert_eq!(trade["status"], "ENTERED");
+    assert_eq!(trade["entry_price"], 0.975);
+    assert_eq!(trade["entry_time"], "2025-10-09T09:05:00Z");
+    assert_eq!(trade["pnl"], Value::Null);
+}
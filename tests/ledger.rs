@@ -0,0 +1,125 @@
+//! Double-entry ledger: entries balance, inventory is relieved at average
+//! cost, and reconciliation tells missed fills from outside transfers.
+
+use std::collections::HashMap;
+
+use eth_no_trend_bot::ledger::{Account, Entry, EntryKind, Fill, Ledger, Posting};
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
+
+fn post_all(ledger: &mut Ledger, entries: Vec<Entry>) {
+    for entry in entries {
+        ledger.post(entry).unwrap();
+    }
+}
+
+fn funded() -> Ledger {
+    let mut ledger = Ledger::new();
+    let deposit = ledger.transfer_entry(0, "opening", 100.0);
+    ledger.post(deposit).unwrap();
+    ledger
+}
+
+#[test]
+fn buys_and_sells_move_inventory_at_average_cost() {
+    let mut ledger = funded();
+    let e = ledger.fill_entries(1, "o1", &Fill { token_id: "no", buy: true, shares: 4.0, price: 0.90, fee: 0.02 });
+    post_all(&mut ledger, e);
+    let e = ledger.fill_entries(2, "o2", &Fill { token_id: "no", buy: true, shares: 4.0, price: 0.95, fee: 0.0 });
+    post_all(&mut ledger, e);
+    assert!(close(ledger.shares("no"), 8.0));
+    assert!(close(ledger.balance(&Account::Inventory("no".to_string())), 7.4));
+    assert!(close(ledger.fees(), 0.02));
+
+    // Average cost 0.925: selling 4 at 0.97 realizes 0.18
+    let e = ledger.fill_entries(3, "o3", &Fill { token_id: "no", buy: false, shares: 4.0, price: 0.97, fee: 0.0 });
+    post_all(&mut ledger, e);
+    assert!(close(ledger.realized_pnl(), 0.18), "{}", ledger.realized_pnl());
+    assert!(close(ledger.shares("no"), 4.0));
+    assert!(close(ledger.usdc(), 100.0 - 3.6 - 0.02 - 3.8 + 3.88));
+    assert!(ledger.entries().iter().all(Entry::is_balanced));
+    assert_eq!(ledger.entries().iter().filter(|e| e.kind == EntryKind::Fee).count(), 1);
+}
+
+#[test]
+fn reversing_a_fill_restores_the_books() {
+    let mut ledger = funded();
+    let e = ledger.fill_entries(1, "o1", &Fill { token_id: "no", buy: true, shares: 5.0, price: 0.97, fee: 0.0 });
+    post_all(&mut ledger, e);
+    let e = ledger.fill_entries(2, "o1", &Fill { token_id: "no", buy: true, shares: -2.0, price: 0.97, fee: 0.0 });
+    post_all(&mut ledger, e);
+    assert!(close(ledger.shares("no"), 3.0));
+    assert!(close(ledger.usdc(), 100.0 - 2.91));
+    assert!(close(ledger.realized_pnl(), 0.0));
+}
+
+#[test]
+fn redemption_settles_inventory_against_the_payout() {
+    let mut ledger = funded();
+    for (token, price) in [("no", 0.96), ("yes", 0.05)] {
+        let e = ledger.fill_entries(1, "o", &Fill { token_id: token, buy: true, shares: 5.0, price, fee: 0.0 });
+        post_all(&mut ledger, e);
+    }
+    let entry = ledger.redemption_entry(9, "0xtx", &[("no".to_string(), 5.0), ("yes".to_string(), 0.0)]);
+    ledger.post(entry).unwrap();
+    assert!(close(ledger.shares("no"), 0.0) && close(ledger.shares("yes"), 0.0));
+    assert!(close(ledger.realized_pnl(), 0.20 - 0.25));
+    assert!(close(ledger.usdc(), 100.0 - 4.8 - 0.25 + 5.0));
+}
+
+#[test]
+fn unbalanced_entries_are_refused() {
+    let mut ledger = Ledger::new();
+    let entry = Entry { ts: 0, kind: EntryKind::Deposit, memo: "bad".to_string(), postings: vec![
+        Posting::new(Account::Usdc, 10.0),
+        Posting::new(Account::Capital, -9.0),
+    ] };
+    assert!(ledger.post(entry).unwrap_err().contains("off by 1.000000"));
+    assert!(ledger.entries().is_empty());
+}
+
+#[test]
+fn reconciliation_separates_missed_fills_from_transfers() {
+    let mut ledger = funded();
+    let e = ledger.fill_entries(1, "o1", &Fill { token_id: "no", buy: true, shares: 5.0, price: 0.96, fee: 0.0 });
+    post_all(&mut ledger, e);
+    let chain = |shares: f64| HashMap::from([("no".to_string(), shares)]);
+
+    assert!(ledger.reconcile(95.2, &chain(5.0), 0.01).is_clean());
+
+    // Shares agree, USDC is up 20: a deposit
+    let rec = ledger.reconcile(115.2, &chain(5.0), 0.01);
+    assert!(!rec.is_clean());
+    assert!(close(rec.suggested_transfer().unwrap(), 20.0));
+
+    // Chain holds more shares than the ledger: a fill it never saw
+    let rec = ledger.reconcile(90.4, &chain(10.0), 0.01);
+    assert_eq!(rec.token_diffs.len(), 1);
+    assert!(close(rec.token_diffs[0].ledger, 5.0) && close(rec.token_diffs[0].wallet, 10.0));
+    assert_eq!(rec.suggested_transfer(), None);
+}
+
+#[test]
+fn journal_round_trips() {
+    let path = std::env::temp_dir().join(format!("ledger_{}.jsonl", std::process::id()));
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let mut ledger = funded();
+    let mut entries = vec![ledger.entries()[0].clone()];
+    entries.extend(ledger.fill_entries(1, "o1", &Fill { token_id: "1002", buy: true, shares: 5.0, price: 0.97, fee: 0.01 }));
+    for entry in &entries {
+        Ledger::append(path, entry).unwrap();
+    }
+    post_all(&mut ledger, entries[1..].to_vec());
+
+    let loaded = Ledger::load(path).unwrap();
+    let _ = std::fs::remove_file(path);
+    assert_eq!(loaded.entries(), ledger.entries());
+    assert_eq!(loaded.accounts(), ledger.accounts());
+    let line = serde_json::to_string(&loaded.entries()[1]).unwrap();
+    assert!(line.contains(r#""account":"inventory:1002""#), "{}", line);
+    assert_eq!(Account::parse("inventory:"), None);
+}
@@ -0,0 +1,92 @@
+//! Adversarial API payloads the fuzz targets turned up or are seeded with.
+//! Each must come back as an error or a harmless value, never a panic.
+
+use eth_no_trend_bot::{book_parser, responses};
+#[cfg(feature = "redeem")]
+use eth_no_trend_bot::redemption;
+
+const SLUG: &str = "eth-updown-15m-1760000400";
+
+#[test]
+fn order_book_rejects_garbage_levels() {
+    let cases: &[&[u8]] = &[
+        b"",
+        b"[]",
+        b"{\"asks\":[{\"price\":\"\",\"size\":\"1\"}]}",
+        b"{\"asks\":[{\"price\":\".\",\"size\":\"1\"}]}",
+        b"{\"bids\":[{\"price\":\"NaN\",\"size\":\"1\"}]}",
+        b"{\"bids\":[{\"price\":\"99999999999999999999\",\"size\":\"1\"}]}",
+        b"{\"asks\":[{\"price\":0.5,\"size\":\"1\"}]}",
+        b"{\"asks\":{}}",
+    ];
+    for case in cases {
+        assert!(book_parser::parse_top_of_book(case).is_err(), "{:?}", String::from_utf8_lossy(case));
+    }
+
+    let top = book_parser::parse_top_of_book(b"{\"asks\":null,\"bids\":[]}").unwrap();
+    assert_eq!(top, book_parser::TopOfBook::default());
+}
+
+#[test]
+fn market_event_tolerates_missing_and_mistyped_fields() {
+    let none_cases: &[&[u8]] = &[
+        b"[]",
+        b"[{\"markets\":[]}]",
+        b"[{\"markets\":[{\"enableOrderBook\":\"yes\"}]}]",
+        b"[{\"markets\":[{\"enableOrderBook\":true,\"clobTokenIds\":\"[\\\"1\\\"]\"}]}]",
+    ];
+    for case in none_cases {
+        assert!(responses::parse_market_event(SLUG, case).unwrap().is_none());
+    }
+
+    let err_cases: &[&[u8]] = &[
+        b"{}",
+        b"[1]",
+        b"[{\"markets\":{}}]",
+        b"[{\"markets\":[{\"enableOrderBook\":true}]}]",
+        b"[{\"markets\":[{\"enableOrderBook\":true,\"clobTokenIds\":\"[1,2]\"}]}]",
+    ];
+    for case in err_cases {
+        assert!(responses::parse_market_event(SLUG, case).is_err());
+    }
+
+    let market = responses::parse_market_event(
+        SLUG,
+        b"[{\"title\":7,\"markets\":[{\"enableOrderBook\":true,\"clobTokenIds\":\"[\\\"1\\\",\\\"2\\\",\\\"3\\\"]\"}]}]",
+    ).unwrap().unwrap();
+    assert_eq!((market.title.as_str(), market.yes_token.as_str(), market.no_token.as_str()), (SLUG, "1", "2"));
+}
+
+#[test]
+fn order_status_ignores_non_finite_amounts() {
+    let order = responses::parse_order_status(
+        br#"{"status":"LIVE","original_size":"inf","size_matched":"NaN","avgFillPrice":"-inf","price":"0.5"}"#,
+    ).unwrap();
+    let progress = order.progress();
+    assert_eq!((progress.original_size, progress.filled_size), (0.0, 0.0));
+    assert!(!progress.is_filled());
+    assert_eq!(order.reported_avg_price(), 0.5);
+
+    assert!(responses::parse_order_status(br#"{"status":5}"#).is_err());
+    assert!(responses::parse_order_status(br#"{"created_at":-1}"#).is_err());
+}
+
+#[cfg(feature = "redeem")]
+#[test]
+fn positions_with_extreme_sizes_still_plan() {
+    let positions = redemption::parse_positions(
+        br#"[{"conditionId":"0x01","size":1e300,"redeemable":true,"outcomeIndex":4000000000},
+             {"conditionId":"not hex","size":5,"redeemable":true},
+             {"size":-3,"redeemable":true}]"#,
+    ).unwrap();
+    assert_eq!(redemption::plan_redemptions(&positions).len(), 0);
+
+    let positions = redemption::parse_positions(
+        br#"[{"conditionId":"0x0000000000000000000000000000000000000000000000000000000000000001","size":1e300,"redeemable":true,"outcomeIndex":4000000000}]"#,
+    ).unwrap();
+    let plans = redemption::plan_redemptions(&positions);
+    assert_eq!(plans.len(), 1);
+    assert_eq!(plans[0].amounts[1].as_u64(), u64::MAX);
+
+    assert!(redemption::parse_positions(br#"{"size":1}"#).is_err());
+}
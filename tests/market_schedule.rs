@@ -0,0 +1,48 @@
+use eth_no_trend_bot::market_schedule::{self, MarketSchedule};
+
+// 2025-10-09T09:00:00Z
+const NINE_AM: u64 = 1_760_000_400;
+
+#[test]
+fn cadences_read_and_print_in_slug_units() {
+    for (text, secs, label) in [("15m", 900, "15m"), ("1h", 3_600, "1h"), ("4h", 14_400, "4h"), ("1d", 86_400, "1d"), ("90", 90, "90s")] {
+        assert_eq!(market_schedule::parse_cadence(text), Ok(secs), "{}", text);
+        assert_eq!(market_schedule::cadence_label(secs), label);
+    }
+    assert_eq!(market_schedule::cadence_label(5_400), "90m");
+    for bad in ["", "0m", "15x", "h", "-1h"] {
+        assert!(market_schedule::parse_cadence(bad).is_err(), "{:?}", bad);
+    }
+}
+
+#[test]
+fn cycles_line_up_with_the_cadence() {
+    let quarter = MarketSchedule::default();
+    assert_eq!(quarter.cycle_start(NINE_AM + 899), NINE_AM);
+    assert_eq!(quarter.next_start(NINE_AM + 899), NINE_AM + 900);
+    assert_eq!(quarter.slug(NINE_AM), "eth-updown-15m-1760000400");
+
+    let four_hourly = MarketSchedule::new("btc", market_schedule::DEFAULT_TEMPLATE, 14_400).unwrap();
+    // 09:00 is in the 08:00-12:00 cycle
+    assert_eq!(four_hourly.cycle_start(NINE_AM), NINE_AM - 3_600);
+    assert_eq!(four_hourly.cycle_end(NINE_AM - 3_600), NINE_AM + 10_800);
+    assert_eq!(four_hourly.slug(NINE_AM - 3_600), "btc-updown-4h-1759996800");
+
+    let daily = MarketSchedule::new("sol", "{asset}-up-or-down-{date}", 86_400).unwrap();
+    assert_eq!(daily.slug(daily.cycle_start(NINE_AM)), "sol-up-or-down-2025-10-09");
+    assert_eq!(daily.pattern(), "sol-up-or-down-{date}");
+
+    let hourly = MarketSchedule::new("eth", "{asset}-{date}-{hour}h", 3_600).unwrap();
+    assert_eq!(hourly.slug(NINE_AM), "eth-2025-10-09-09h");
+}
+
+#[test]
+fn templates_are_checked_up_front() {
+    let err = MarketSchedule::new("eth", "{asset}-updown-{cadence}-{tss}", 900).unwrap_err();
+    assert!(err.contains("{tss}"), "{}", err);
+    let err = MarketSchedule::new("eth", "{asset}-updown-{cadence}", 900).unwrap_err();
+    assert!(err.contains("tell cycles apart"), "{}", err);
+    assert!(MarketSchedule::new("eth", "{asset}-{ts", 900).is_err());
+    assert!(MarketSchedule::new("eth", "{asset}-ts}", 900).is_err());
+    assert!(MarketSchedule::new("eth", "{ts}", 0).is_err());
+}
@@ -0,0 +1,113 @@
+use std::net::TcpListener;
+use std::time::{Duration, Instant};
+
+use tungstenite::Message;
+
+use eth_no_trend_bot::market_stream::{LiveBooks, MarketStream};
+use eth_no_trend_bot::strategy::OrderBook;
+
+const SNAPSHOT: &str = r#"[
+    {"event_type": "book", "asset_id": "1001", "market": "0x11",
+     "bids": [{"price": "0.95", "size": "40"}, {"price": "0.96", "size": "12"}],
+     "asks": [{"price": "0.98", "size": "30"}, {"price": "0.99", "size": "100"}],
+     "timestamp": "1760000400000", "hash": "0xabc"},
+    {"event_type": "book", "asset_id": "1002", "market": "0x11",
+     "bids": [{"price": "0.02", "size": "30"}], "asks": [{"price": "0.04", "size": "12"}],
+     "timestamp": "1760000400000", "hash": "0xdef"}
+]"#;
+
+fn top(books: &LiveBooks, token: &str) -> OrderBook {
+    books.levels(token).unwrap().top()
+}
+
+#[test]
+fn snapshot_then_deltas_keep_the_touch() {
+    let mut books = LiveBooks::default();
+    assert_eq!(books.apply(SNAPSHOT).unwrap(), ["1001", "1002"]);
+    assert_eq!(top(&books, "1001"), OrderBook { best_bid: Some(0.96), bid_size: 12.0, best_ask: Some(0.98), ask_size: 30.0 });
+
+    // Per-asset price_changes: the best ask is taken out, a better bid added
+    let changed = books.apply(r#"{"event_type": "price_change", "market": "0x11", "price_changes": [
+        {"asset_id": "1001", "price": "0.98", "size": "0", "side": "SELL"},
+        {"asset_id": "1001", "price": "0.97", "size": "5", "side": "BUY"}
+    ]}"#).unwrap();
+    assert_eq!(changed, ["1001"]);
+    assert_eq!(top(&books, "1001"), OrderBook { best_bid: Some(0.97), bid_size: 5.0, best_ask: Some(0.99), ask_size: 100.0 });
+
+    // Older shape: one asset, `changes`
+    books.apply(r#"{"event_type": "price_change", "asset_id": "1002", "changes": [{"price": "0.03", "size": "7", "side": "SELL"}]}"#).unwrap();
+    assert_eq!(top(&books, "1002").best_ask, Some(0.03));
+    assert_eq!(top(&books, "1002").ask_size, 7.0);
+
+    // Trades are kept for the recorder until taken
+    books.apply(r#"{"event_type": "last_trade_price", "asset_id": "1002", "price": "0.03", "size": "20", "side": "BUY", "timestamp": "1760000401500"}"#).unwrap();
+    let trades = books.take_trades();
+    assert_eq!(trades.len(), 1);
+    assert_eq!((trades[0].token_id.as_str(), trades[0].price, trades[0].size, trades[0].ts_ms), ("1002", 0.03, 20.0, 1_760_000_401_500));
+    assert!(books.take_trades().is_empty());
+}
+
+#[test]
+fn deltas_before_a_snapshot_and_other_events_are_ignored() {
+    let mut books = LiveBooks::default();
+    let changed = books.apply(r#"[
+        {"event_type": "price_change", "asset_id": "1001", "changes": [{"price": "0.5", "size": "1", "side": "BUY"}]},
+        {"event_type": "last_trade_price", "asset_id": "1001", "price": "0.5"},
+        {"event_type": "tick_size_change", "asset_id": "1001"}
+    ]"#).unwrap();
+    assert!(changed.is_empty());
+    assert!(books.levels("1001").is_none());
+
+    // Empty side: no touch rather than a zero price
+    books.apply(r#"{"event_type": "book", "asset_id": "1001", "buys": [], "sells": [{"price": "0.5", "size": "3"}]}"#).unwrap();
+    assert_eq!(top(&books, "1001"), OrderBook { best_bid: None, bid_size: 0.0, best_ask: Some(0.5), ask_size: 3.0 });
+
+    assert!(books.apply("not json").is_err());
+    assert!(books.apply(r#"{"event_type": "price_change", "asset_id": "1001", "changes": [{"price": "0.5", "size": "1", "side": "HOLD"}]}"#).is_err());
+}
+
+fn wait_for(what: &str, mut done: impl FnMut() -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !done() {
+        assert!(Instant::now() < deadline, "timed out waiting for {}", what);
+        std::thread::sleep(Duration::from_millis(20));
+    }
+}
+
+#[test]
+fn streams_books_for_the_watched_tokens_and_resubscribes() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let (subscriptions, received) = std::sync::mpsc::channel::<String>();
+
+    let server = std::thread::spawn(move || {
+        for _ in 0..2 {
+            let (tcp, _) = listener.accept().unwrap();
+            let mut ws = tungstenite::accept(tcp).unwrap();
+            let Message::Text(subscribe) = ws.read().unwrap() else { panic!("expected a subscription") };
+            subscriptions.send(subscribe).unwrap();
+            ws.send(Message::Text(SNAPSHOT.to_string())).unwrap();
+            ws.send(Message::Text(r#"{"event_type": "price_change", "asset_id": "1001", "changes": [{"price": "0.97", "size": "8", "side": "BUY"}]}"#.to_string())).unwrap();
+            // Hold the connection until the client moves on
+            while ws.read().is_ok() {}
+        }
+    });
+
+    let stream = MarketStream::spawn(&url).unwrap();
+    assert_eq!(stream.book("1001"), None);
+
+    stream.watch(&["1001".to_string(), "1002".to_string()]);
+    let subscribe: serde_json::Value = serde_json::from_str(&received.recv_timeout(Duration::from_secs(5)).unwrap()).unwrap();
+    assert_eq!(subscribe["type"], "market");
+    assert_eq!(subscribe["assets_ids"], serde_json::json!(["1001", "1002"]));
+    wait_for("the delta", || stream.book("1001").and_then(|b| b.best_bid) == Some(0.97));
+    assert_eq!(stream.book("1002").unwrap().best_ask, Some(0.04));
+
+    // A new market's tokens reconnect with the new subscription
+    stream.watch(&["2001".to_string()]);
+    let subscribe: serde_json::Value = serde_json::from_str(&received.recv_timeout(Duration::from_secs(5)).unwrap()).unwrap();
+    assert_eq!(subscribe["assets_ids"], serde_json::json!(["2001"]));
+
+    drop(stream);
+    server.join().unwrap();
+}
@@ -0,0 +1,96 @@
+//! Checks the mock API against the real clients and parsers, and drives the
+//! bot binary against it end to end.
+
+mod common;
+
+use ethers::types::Address;
+use reqwest::blocking::Client;
+use serde_json::json;
+
+use common::mock_api::MockApi;
+use eth_no_trend_bot::book_parser::{self, parse_fixed};
+use eth_no_trend_bot::redemption;
+
+const CONDITION_A: &str = "0x1111111111111111111111111111111111111111111111111111111111111111";
+const CONDITION_B: &str = "0x2222222222222222222222222222222222222222222222222222222222222222";
+
+fn position(condition_id: &str, outcome_index: u32, size: f64, redeemable: bool) -> serde_json::Value {
+    json!({
+        "asset": format!("{}{}", &condition_id[2..10], outcome_index),
+        "conditionId": condition_id,
+        "size": size,
+        "redeemable": redeemable,
+        "title": format!("Market {}", &condition_id[2..6]),
+        "outcome": if outcome_index == 0 { "Yes" } else { "No" },
+        "outcomeIndex": outcome_index,
+        "curPrice": 1.0,
+        "currentValue": size,
+    })
+}
+
+#[test]
+fn book_snapshots_parse_to_best_levels() {
+    let mock = MockApi::start();
+    mock.push_book("yes", &[(0.95, 40.0), (0.96, 12.0)], &[(0.98, 30.0), (0.97, 7.5)]);
+    mock.push_book("yes", &[(0.88, 5.0)], &[]);
+
+    let client = Client::new();
+    let fetch = || client.get(format!("{}/book?token_id=yes", mock.url)).send().unwrap().bytes().unwrap();
+
+    let first = book_parser::parse_top_of_book(&fetch()).unwrap();
+    assert_eq!(first.best_bid, Some((parse_fixed("0.96").unwrap(), parse_fixed("12").unwrap())));
+    assert_eq!(first.best_ask, Some((parse_fixed("0.97").unwrap(), parse_fixed("7.5").unwrap())));
+
+    // Later snapshot sticks once the queue is drained
+    for _ in 0..2 {
+        let next = book_parser::parse_top_of_book(&fetch()).unwrap();
+        assert_eq!(next.best_bid, Some((parse_fixed("0.88").unwrap(), parse_fixed("5").unwrap())));
+        assert_eq!(next.best_ask, None);
+    }
+}
+
+#[test]
+fn redeemable_positions_are_paged_and_grouped_by_condition() {
+    let mock = MockApi::start();
+    {
+        let mut state = mock.state();
+        // More than one data-API page, with non-redeemable noise mixed in
+        for _ in 0..300 {
+            state.positions.push(position(CONDITION_B, 0, 1.0, false));
+        }
+        for _ in 0..600 {
+            state.positions.push(position(CONDITION_A, 0, 1.0, true));
+        }
+        state.positions.push(position(CONDITION_A, 1, 3.0, true));
+    }
+
+    let positions = redemption::fetch_positions(&Client::new(), &mock.url, Address::zero(), true).unwrap();
+    assert_eq!(positions.len(), 601);
+    assert_eq!(mock.requests_to("GET", "/positions").len(), 2);
+
+    let plans = redemption::plan_redemptions(&positions);
+    assert_eq!(plans.len(), 1);
+    assert_eq!(plans[0].amounts[0].as_u64(), 600 * 1_000_000);
+    assert_eq!(plans[0].amounts[1].as_u64(), 3 * 1_000_000);
+}
+
+#[test]
+fn claim_command_runs_against_the_mock() {
+    let mock = MockApi::start();
+    mock.state().positions.push(position(CONDITION_A, 0, 5.0, true));
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "mock_api_claim");
+    let output = command.arg("claim").output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "bot failed:\n{}\n{}", stdout, String::from_utf8_lossy(&output.stderr));
+    // Redeemable per the data API, but the chain can't confirm payouts: skip
+    assert!(stdout.contains("resolution check failed"), "{}", stdout);
+    assert!(stdout.contains("Nothing to redeem"), "{}", stdout);
+
+    let queries: Vec<String> = mock.requests_to("GET", "/positions").into_iter().map(|r| r.query).collect();
+    assert_eq!(queries.len(), 1);
+    assert!(queries[0].contains("redeemable=true"));
+
+    let _ = std::fs::remove_dir_all(&workdir);
+}
@@ -0,0 +1,99 @@
+//! Notification routing: each channel gets what meets its minimum severity,
+//! and one failing channel doesn't keep the others from delivering; event
+//! subscriptions name real events.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use eth_no_trend_bot::notify::{self, Event, JsonLinesNotifier, Notification, Notifier, Router, Severity};
+
+struct Recorder {
+    name: &'static str,
+    seen: Rc<RefCell<Vec<String>>>,
+}
+
+impl Notifier for Recorder {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn send(&self, notification: &Notification) -> Result<(), Box<dyn std::error::Error>> {
+        self.seen.borrow_mut().push(notification.title.clone());
+        Ok(())
+    }
+}
+
+struct Broken;
+
+impl Notifier for Broken {
+    fn name(&self) -> &str {
+        "broken"
+    }
+
+    fn send(&self, _: &Notification) -> Result<(), Box<dyn std::error::Error>> {
+        Err("connection refused".into())
+    }
+}
+
+#[test]
+fn routes_by_minimum_severity() {
+    let everything = Rc::new(RefCell::new(Vec::new()));
+    let urgent = Rc::new(RefCell::new(Vec::new()));
+    let mut router = Router::new();
+    router.add(Severity::Info, Box::new(Recorder { name: "log", seen: everything.clone() }));
+    router.add(Severity::Critical, Box::new(Recorder { name: "pager", seen: urgent.clone() }));
+    assert_eq!(router.channels(), [("log".to_string(), Severity::Info), ("pager".to_string(), Severity::Critical)]);
+
+    for (severity, title) in [(Severity::Info, "entered"), (Severity::Warning, "book errors"), (Severity::Critical, "halted")] {
+        assert!(router.notify(&Notification::new(severity, title, "", 0)).is_empty());
+    }
+    assert_eq!(*everything.borrow(), ["entered", "book errors", "halted"]);
+    assert_eq!(*urgent.borrow(), ["halted"]);
+}
+
+#[test]
+fn failing_channels_are_reported_not_fatal() {
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let mut router = Router::new();
+    router.add(Severity::Info, Box::new(Broken));
+    router.add(Severity::Info, Box::new(Recorder { name: "log", seen: seen.clone() }));
+
+    let failures = router.notify(&Notification::new(Severity::Warning, "x", "", 0));
+    assert_eq!(failures, [("broken".to_string(), "connection refused".to_string())]);
+    assert_eq!(seen.borrow().len(), 1);
+}
+
+#[test]
+fn json_lines_channel_appends_records() {
+    let path = std::env::temp_dir().join(format!("notify_jsonl_{}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let channel = JsonLinesNotifier::new(path.to_string_lossy());
+    channel.send(&Notification::new(Severity::Critical, "Trading halted", "exchange paused", 1_760_000_400)).unwrap();
+    channel.send(&Notification::new(Severity::Info, "Market entered", "eth-updown-15m-1", 1_760_000_500)).unwrap();
+
+    let text = std::fs::read_to_string(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    let records: Vec<Notification> = text.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].severity, Severity::Critical);
+    assert!(text.starts_with("{\"severity\":\"critical\""), "{}", text);
+
+    assert_eq!(Severity::parse("WARN"), Some(Severity::Warning));
+    assert_eq!(Severity::parse("debug"), None);
+}
+
+#[test]
+fn event_subscriptions_reject_unknown_names() {
+    assert_eq!(notify::parse_events(" entry, stop_loss ,,skipped").unwrap(), ["entry", "stop_loss", "skipped"]);
+    assert_eq!(notify::parse_events("*").unwrap(), ["*"]);
+    let err = notify::parse_events("entry,entries").unwrap_err();
+    assert!(err.starts_with("unknown event 'entries' (one of *, entry, exit, stop_loss,"), "{}", err);
+    for event in Event::ALL {
+        assert_eq!(Event::parse(event.as_str()), Some(event));
+    }
+
+    let events = notify::parse_events("abort,fatal").unwrap();
+    let abort = Notification::new(Severity::Warning, "Aborted", "", 0).with_event(Event::Abort.as_str());
+    assert!(notify::subscribed(&events, &abort));
+    assert!(!notify::subscribed(&events, &Notification::new(Severity::Warning, "Untagged", "", 0)));
+}
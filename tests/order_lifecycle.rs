@@ -0,0 +1,137 @@
+use eth_no_trend_bot::order_lifecycle::{OrderEvent, OrderJournal, OrderSpec, OrderState};
+use eth_no_trend_bot::responses::OrderProgress;
+
+fn spec() -> OrderSpec {
+    OrderSpec {
+        token_id: "1002".to_string(),
+        side: "BUY".to_string(),
+        order_type: "FOK".to_string(),
+        price: 0.98,
+        size: 5.0,
+        salt: "42".to_string(),
+    }
+}
+
+fn progress(status: &str, filled: f64) -> OrderProgress {
+    OrderProgress { status: status.to_string(), original_size: 5.0, filled_size: filled, avg_price: 0.975, fee: 0.0 }
+}
+
+/// An order taken through created → signed → submitted → live.
+fn live_journal() -> OrderJournal {
+    let mut journal = OrderJournal::new();
+    journal.apply(OrderEvent::created(100, "0xabc", spec())).unwrap();
+    journal.apply(OrderEvent::moved(100, "0xabc", OrderState::Signed, "")).unwrap();
+    journal.apply(OrderEvent::moved(101, "0xabc", OrderState::Submitted, "")).unwrap();
+    let live = journal.transition(102, "0xabc", OrderState::Live, "").unwrap();
+    journal.apply(OrderEvent { order_id: Some("7".to_string()), ..live }).unwrap();
+    journal
+}
+
+#[test]
+fn transitions_follow_the_lifecycle() {
+    use OrderState::*;
+    assert!(Created.can_move_to(Signed));
+    assert!(Submitted.can_move_to(Filled), "a reconciled submission can land already filled");
+    assert!(PartiallyFilled.can_move_to(Canceled));
+    assert!(Filled.can_move_to(Filled), "fill updates stay put");
+    assert!(!Created.can_move_to(Submitted), "must be signed first");
+    assert!(!Live.can_move_to(Rejected));
+    assert!(!Filled.can_move_to(Canceled));
+    assert!(!Canceled.can_move_to(Live));
+    assert!([Filled, Canceled, Expired, Rejected].iter().all(|s| s.is_terminal()));
+    assert!(![Created, Signed, Submitted, Live, PartiallyFilled].iter().any(|s| s.is_terminal()));
+}
+
+#[test]
+fn exchange_reports_map_to_states() {
+    assert_eq!(OrderState::from_progress(&progress("LIVE", 0.0)), OrderState::Live);
+    assert_eq!(OrderState::from_progress(&progress("LIVE", 2.0)), OrderState::PartiallyFilled);
+    assert_eq!(OrderState::from_progress(&progress("MATCHED", 5.0)), OrderState::Filled);
+    assert_eq!(OrderState::from_progress(&progress("CANCELED", 2.0)), OrderState::Canceled);
+    assert_eq!(OrderState::from_progress(&progress("UNMATCHED", 0.0)), OrderState::Canceled);
+    assert_eq!(OrderState::from_progress(&progress("EXPIRED", 0.0)), OrderState::Expired);
+}
+
+#[test]
+fn records_keep_timestamps_and_are_found_by_either_id() {
+    let journal = live_journal();
+    let order = journal.get("7").unwrap();
+    assert_eq!(order.key, "0xabc");
+    assert_eq!(order.exchange_id(), "7");
+    assert_eq!(order.state, OrderState::Live);
+    assert_eq!((order.created_at(), order.entered(OrderState::Submitted), order.updated_at()), (100, Some(101), 102));
+    assert_eq!(journal.get("0xabc"), Some(order));
+    assert_eq!(journal.open().len(), 1);
+}
+
+#[test]
+fn illegal_moves_and_unknown_orders_are_refused() {
+    let mut journal = live_journal();
+    let err = journal.apply(OrderEvent::moved(103, "0xabc", OrderState::Rejected, "late")).unwrap_err();
+    assert!(err.contains("cannot go from live to rejected"), "{}", err);
+    assert!(journal.apply(OrderEvent::moved(103, "0xdef", OrderState::Live, "")).is_err());
+    assert!(journal.apply(OrderEvent::created(103, "0xabc", spec())).unwrap_err().contains("created twice"));
+    assert_eq!(journal.transition(103, "0xabc", OrderState::Signed, ""), None);
+    assert_eq!(journal.get("0xabc").unwrap().state, OrderState::Live);
+}
+
+#[test]
+fn progress_events_record_fills_and_skip_repeats() {
+    let mut journal = live_journal();
+    let event = journal.progress_event(110, "7", &progress("LIVE", 2.0)).unwrap();
+    assert_eq!((event.key.as_str(), event.state, event.filled), ("0xabc", OrderState::PartiallyFilled, 2.0));
+    journal.apply(event).unwrap();
+    assert_eq!(journal.progress_event(111, "7", &progress("LIVE", 2.0)), None);
+
+    journal.apply(journal.transition(112, "7", OrderState::Canceled, "canceled by the bot").unwrap()).unwrap();
+    let canceled = journal.get("7").unwrap();
+    assert_eq!((canceled.state, canceled.filled), (OrderState::Canceled, 2.0), "cancel keeps the fills");
+
+    // A lagging report can't reopen the order, but a reorg can still move its fills
+    let event = journal.progress_event(113, "7", &progress("LIVE", 1.0)).unwrap();
+    assert_eq!((event.state, event.filled), (OrderState::Canceled, 1.0));
+    journal.apply(event).unwrap();
+    assert!(journal.open().is_empty());
+
+    let states: Vec<&str> = journal.get("7").unwrap().history.iter().map(|s| s.state.as_str()).collect();
+    assert_eq!(states, ["created", "signed", "submitted", "live", "partially_filled", "canceled"]);
+}
+
+#[test]
+fn journal_replays_after_restart() {
+    let dir = std::env::temp_dir().join(format!("order_journal_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("orders.jsonl").to_string_lossy().into_owned();
+
+    let mut events = vec![
+        OrderEvent::created(100, "0xabc", spec()),
+        OrderEvent::moved(100, "0xabc", OrderState::Signed, ""),
+        OrderEvent::moved(101, "0xabc", OrderState::Submitted, ""),
+        OrderEvent { order_id: Some("7".to_string()), ..OrderEvent::moved(102, "0xabc", OrderState::Live, "") },
+        OrderEvent::created(200, "0xdef", spec()),
+        OrderEvent::moved(200, "0xdef", OrderState::Rejected, "signing failed"),
+    ];
+    events.push(OrderEvent { filled: 5.0, avg_price: 0.975, ..OrderEvent::moved(103, "0xabc", OrderState::Filled, "") });
+    for event in &events[..4] {
+        OrderJournal::append(&path, event).unwrap();
+    }
+
+    let journal = OrderJournal::load(&path).unwrap();
+    let open = journal.open();
+    assert_eq!(open.len(), 1);
+    assert_eq!((open[0].exchange_id(), open[0].state, open[0].spec.salt.as_str()), ("7", OrderState::Live, "42"));
+
+    for event in &events[4..] {
+        OrderJournal::append(&path, event).unwrap();
+    }
+    let journal = OrderJournal::load(&path).unwrap();
+    assert!(journal.open().is_empty());
+    assert_eq!(journal.get("0xdef").unwrap().history[1].note, "signing failed");
+    assert_eq!(journal.get("7").unwrap().avg_price, 0.975);
+
+    std::fs::write(&path, "{\"ts\":1,\"key\":\"0x1\",\"state\":\"live\"}\n").unwrap();
+    let err = OrderJournal::load(&path).unwrap_err().to_string();
+    assert!(err.contains("line 1: unknown order 0x1"), "{}", err);
+    let _ = std::fs::remove_dir_all(&dir);
+}
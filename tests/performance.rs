@@ -0,0 +1,80 @@
+//! Performance statistics over hand-computed trade histories.
+
+use eth_no_trend_bot::performance::{self, PerformanceReport, PerformanceStats, TradeResult};
+
+fn trade(strategy: &str, asset: &str, ts: u64, pnl: f64, cost: f64) -> TradeResult {
+    TradeResult { strategy: strategy.to_string(), asset: asset.to_string(), ts, pnl, cost }
+}
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
+
+#[test]
+fn win_rate_averages_and_profit_factor() {
+    let trades = [
+        trade("NO", "eth", 1, 0.20, 4.8),
+        trade("NO", "eth", 2, 0.10, 4.9),
+        trade("NO", "eth", 3, -0.60, 4.8),
+        trade("YES", "eth", 4, 0.0, 2.4),
+    ];
+    let s = PerformanceStats::compute(&trades);
+    assert_eq!((s.trades, s.wins, s.losses), (4, 2, 1));
+    assert!(close(s.win_rate, 0.5));
+    assert!(close(s.total_pnl, -0.30), "{}", s.total_pnl);
+    assert!(close(s.avg_win, 0.15));
+    assert!(close(s.avg_loss, -0.60));
+    assert!(close(s.profit_factor.unwrap(), 0.5));
+}
+
+#[test]
+fn drawdown_follows_close_order_not_input_order() {
+    // Cumulative by time: +1, +3, +0.5, +1.5, -1.0 → worst fall 3 → -1
+    let trades = [
+        trade("NO", "eth", 50, -2.5, 5.0),
+        trade("NO", "eth", 10, 1.0, 5.0),
+        trade("NO", "eth", 40, 1.0, 5.0),
+        trade("NO", "eth", 20, 2.0, 5.0),
+        trade("NO", "eth", 30, -2.5, 5.0),
+    ];
+    assert!(close(PerformanceStats::compute(&trades).max_drawdown, 4.0));
+    // Only gains: no drawdown, no losses to divide by
+    let s = PerformanceStats::compute(&trades[1..2]);
+    assert_eq!((s.max_drawdown, s.profit_factor, s.sharpe), (0.0, None, None));
+}
+
+#[test]
+fn sharpe_uses_per_trade_returns() {
+    // Returns 0.1 and 0.3: mean 0.2, sample sd 0.1414…
+    let trades = [trade("NO", "eth", 1, 0.5, 5.0), trade("NO", "eth", 2, 1.5, 5.0)];
+    let sharpe = PerformanceStats::compute(&trades).sharpe.unwrap();
+    assert!(close(sharpe, 0.2 / 0.02_f64.sqrt()), "{}", sharpe);
+    // Identical returns have no spread to scale by
+    let flat = [trade("NO", "eth", 1, 0.5, 5.0), trade("NO", "eth", 2, 0.5, 5.0)];
+    assert_eq!(PerformanceStats::compute(&flat).sharpe, None);
+}
+
+#[test]
+fn report_breaks_down_by_strategy_and_asset() {
+    let trades = [
+        trade("NO", "eth", 1, 0.2, 4.8),
+        trade("YES", "eth", 2, -1.0, 2.4),
+        trade("NO", "btc", 3, 0.1, 4.9),
+    ];
+    let report = PerformanceReport::new(&trades);
+    assert_eq!(report.overall.trades, 3);
+    assert_eq!(report.by_strategy.keys().collect::<Vec<_>>(), ["NO", "YES"]);
+    assert_eq!(report.by_strategy["NO"].wins, 2);
+    assert_eq!(report.by_asset["eth"].trades, 2);
+    assert!(close(report.by_asset["btc"].total_pnl, 0.1));
+
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["by_strategy"]["YES"]["losses"], 1);
+    assert_eq!(PerformanceReport::new(&[]).overall, PerformanceStats::default());
+}
+
+#[test]
+fn asset_comes_from_the_slug() {
+    assert_eq!(performance::asset_of("eth-updown-15m-1760000400"), "eth");
+    assert_eq!(performance::asset_of("BTC"), "btc");
+}
@@ -0,0 +1,66 @@
+//! Session PnL: fees go into the basis, sells realize at average cost,
+//! resolution settles what's left, and open shares are marked to the bid.
+
+use eth_no_trend_bot::pnl::PnlTracker;
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
+
+#[test]
+fn sells_realize_against_the_average_cost_with_fees() {
+    let mut pnl = PnlTracker::new();
+    assert!(pnl.is_empty());
+    assert_eq!(pnl.fill("no", true, 4.0, 0.90, 0.02), 0.0);
+    pnl.fill("no", true, 4.0, 0.95, 0.0);
+    let holding = *pnl.holding("no").unwrap();
+    assert!(close(holding.shares, 8.0));
+    assert!(close(holding.cost, 7.42));
+
+    // Unmarked shares show no unrealized PnL; at a 0.97 bid they're up 0.34
+    assert!(close(pnl.unrealized(), 0.0));
+    pnl.mark("no", 0.97);
+    assert!(close(pnl.unrealized(), 8.0 * 0.97 - 7.42));
+
+    // Average cost 0.9275: selling 4 at 0.97 less a 0.01 fee realizes 0.16
+    let realized = pnl.fill("no", false, 4.0, 0.97, 0.01);
+    assert!(close(realized, 0.16), "{}", realized);
+    assert!(close(pnl.realized(), 0.16));
+    assert!(close(pnl.holding("no").unwrap().cost, 3.71));
+    assert_eq!(pnl.summary(), "PnL $+0.16 (unrealized $+0.17)");
+
+    pnl.fill("no", false, 4.0, 0.90, 0.0);
+    assert!(pnl.holding("no").is_none());
+    assert!(close(pnl.realized(), 0.16 + 3.6 - 3.71));
+    assert_eq!(pnl.summary(), "PnL $+0.05");
+    assert!(close(pnl.take_realized("no"), 0.05));
+    assert_eq!(pnl.take_realized("no"), 0.0);
+}
+
+#[test]
+fn resolution_pays_out_every_held_share() {
+    let mut pnl = PnlTracker::new();
+    pnl.open("no", 5.0, 4.875);
+    pnl.open("yes", 2.0, 0.06);
+    assert!(close(pnl.resolve("no", 1.0), 0.125));
+    assert!(close(pnl.resolve("yes", 0.0), -0.06));
+    assert_eq!(pnl.resolve("other", 1.0), 0.0);
+    assert!(pnl.holding("no").is_none() && pnl.holding("yes").is_none());
+    assert!(close(pnl.realized(), 0.065));
+    assert!(close(pnl.take_realized("no"), 0.125));
+    assert!(!pnl.is_empty());
+}
+
+#[test]
+fn reversed_fills_undo_the_basis_and_marks_need_a_holding() {
+    let mut pnl = PnlTracker::new();
+    pnl.mark("no", 0.5);
+    assert!(pnl.holding("no").is_none());
+    pnl.fill("no", true, 5.0, 0.97, 0.0);
+    pnl.fill("no", true, -2.0, 0.97, 0.0);
+    let holding = *pnl.holding("no").unwrap();
+    assert!(close(holding.shares, 3.0) && close(holding.cost, 2.91));
+    pnl.fill("no", true, -3.0, 0.97, 0.0);
+    assert!(pnl.holding("no").is_none());
+    assert!(pnl.is_empty());
+}
@@ -0,0 +1,41 @@
+use eth_no_trend_bot::position_limit::PositionLimit;
+
+const NOW: u64 = 1_760_000_700;
+const CLOSE: u64 = 1_760_001_300;
+
+#[test]
+fn entries_past_the_cap_wait_for_a_slot() {
+    let limit = PositionLimit::new(2);
+    assert_eq!(limit.try_open("eth-updown-15m-1", CLOSE, NOW), Ok(()));
+    assert_eq!(limit.try_open("btc-updown-15m-1", CLOSE, NOW), Ok(()));
+    assert_eq!(limit.try_open("sol-updown-15m-1", CLOSE, NOW), Err(2));
+    // A market keeps the slot it has
+    assert_eq!(limit.try_open("eth-updown-15m-1", CLOSE, NOW + 1), Ok(()));
+
+    limit.release("btc-updown-15m-1");
+    assert_eq!(limit.open(NOW), 1);
+    assert_eq!(limit.try_open("sol-updown-15m-1", CLOSE, NOW), Ok(()));
+}
+
+#[test]
+fn slots_free_up_when_their_market_closes() {
+    let limit = PositionLimit::new(1);
+    limit.try_open("eth-updown-15m-1", CLOSE, NOW).unwrap();
+    assert_eq!(limit.try_open("btc-updown-15m-1", CLOSE, CLOSE - 1), Err(1));
+    assert_eq!(limit.open(CLOSE), 0);
+    assert_eq!(limit.try_open("eth-updown-15m-2", CLOSE + 900, CLOSE), Ok(()));
+}
+
+#[test]
+fn recovered_positions_count_even_past_the_cap() {
+    let limit = PositionLimit::new(1);
+    limit.hold("eth-updown-15m-1", CLOSE);
+    limit.hold("btc-updown-15m-1", CLOSE);
+    assert_eq!(limit.open(NOW), 2);
+    assert_eq!(limit.try_open("sol-updown-15m-1", CLOSE, NOW), Err(2));
+
+    let unlimited = PositionLimit::new(0);
+    for market in ["a", "b", "c"] {
+        assert_eq!(unlimited.try_open(market, CLOSE, NOW), Ok(()));
+    }
+}
@@ -0,0 +1,43 @@
+//! Account profiles: name validation, per-profile variable lookup with
+//! fallback to the shared variable, and separate data directories.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use eth_no_trend_bot::profiles::{parse_profiles, Profile};
+
+#[test]
+fn empty_list_is_the_default_profile() {
+    let profiles = parse_profiles(" , ", Path::new("profiles")).unwrap();
+    assert_eq!(profiles, vec![Profile::default()]);
+    assert!(profiles[0].is_default());
+    assert_eq!(profiles[0].path("traded_markets.json"), "traded_markets.json");
+}
+
+#[test]
+fn named_profiles_get_their_own_directory_and_prefix() {
+    let profiles = parse_profiles("alice, desk-2", Path::new("/var/bot")).unwrap();
+    let names: Vec<&str> = profiles.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["alice", "desk-2"]);
+    assert_eq!(profiles[1].path("ETH_NO_trading_log.csv"), "/var/bot/desk-2/ETH_NO_trading_log.csv");
+
+    let env: HashMap<&str, &str> = [
+        ("ALICE_POLY_API_KEY", "alice-key"),
+        ("DESK_2_POLY_API_KEY", "desk-key"),
+        ("POLY_API_KEY", "shared-key"),
+        ("POLY_NETWORK", "amoy"),
+    ].into_iter().collect();
+    let lookup = |name: &str| env.get(name).map(|v| v.to_string());
+    assert_eq!(profiles[0].var_with("POLY_API_KEY", lookup).as_deref(), Some("alice-key"));
+    assert_eq!(profiles[1].var_with("POLY_API_KEY", lookup).as_deref(), Some("desk-key"));
+    assert_eq!(profiles[0].var_with("POLY_NETWORK", lookup).as_deref(), Some("amoy"));
+    assert_eq!(Profile::default().var_with("POLY_API_KEY", lookup).as_deref(), Some("shared-key"));
+    assert_eq!(profiles[0].var_with("PRIVATE_KEY", lookup), None);
+}
+
+#[test]
+fn bad_or_clashing_names_are_rejected() {
+    assert!(parse_profiles("alice,../bob", Path::new("p")).is_err());
+    assert!(parse_profiles("alice,ALICE", Path::new("p")).is_err());
+    assert!(parse_profiles("desk-1,desk_1", Path::new("p")).is_err());
+}
@@ -0,0 +1,31 @@
+use eth_no_trend_bot::network::NetworkProfile;
+use eth_no_trend_bot::proxy_wallet;
+use eth_no_trend_bot::tx_manager::TxRequest;
+use ethers::types::{Address, U256};
+
+fn approve() -> TxRequest {
+    TxRequest {
+        label: "approve".to_string(),
+        to: NetworkProfile::polygon().collateral,
+        data: vec![0x09, 0x5e, 0xa7, 0xb3].into(),
+        value: U256::zero(),
+    }
+}
+
+#[test]
+fn calls_go_through_the_networks_proxy_factory() {
+    let polygon = NetworkProfile::polygon();
+    let wrapped = proxy_wallet::wrap_for_proxy(&polygon, "approve", &[approve()]).unwrap();
+    assert_eq!(Some(wrapped.to), polygon.proxy_factory);
+    assert_eq!(wrapped.label, "approve (via proxy)");
+
+    let factory: Address = "0x00000000000000000000000000000000000fac70".parse().unwrap();
+    let amoy = NetworkProfile { proxy_factory: Some(factory), ..NetworkProfile::amoy() };
+    assert_eq!(proxy_wallet::wrap_for_proxy(&amoy, "approve", &[approve()]).unwrap().to, factory);
+}
+
+#[test]
+fn a_network_without_a_factory_refuses_to_wrap() {
+    let err = proxy_wallet::wrap_for_proxy(&NetworkProfile::amoy(), "approve", &[approve()]).unwrap_err();
+    assert_eq!(err.to_string(), "No proxy wallet factory on amoy; set POLY_PROXY_FACTORY_ADDRESS");
+}
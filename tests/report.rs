@@ -0,0 +1,58 @@
+//! Daily summaries: rows grouped by UTC day, entries against their trigger
+//! price, closes for the win rate and PnL, and the CSV layout.
+
+use eth_no_trend_bot::report::{self, DailySummary};
+use eth_no_trend_bot::trade_log::TradeRecord;
+
+// 2025-10-09T09:00:00Z
+const DAY1: u64 = 1_760_000_400;
+const DAY2: u64 = DAY1 + 86_400;
+
+fn row(status: &str, ts: u64) -> TradeRecord {
+    TradeRecord { status: status.to_string(), entry_time: Some(ts), ..Default::default() }
+}
+
+#[test]
+fn each_day_counts_its_own_rows() {
+    let records = vec![
+        TradeRecord { entry_price: Some(0.97), slippage: Some(-0.01), ..row("ENTERED", DAY1) },
+        TradeRecord { exit_time: Some(DAY1 + 600), pnl: Some(0.15), ..row("EXITED", DAY1) },
+        TradeRecord { entry_price: Some(0.98), ..row("PARTIAL", DAY1 + 900) },
+        TradeRecord { exit_time: Some(DAY1 + 1500), pnl: Some(-0.40), sl_triggered: Some(true), ..row("EXITED", DAY1 + 900) },
+        row("ABORTED", DAY1 + 1800),
+        // A close the next day counts there, whenever it was entered
+        TradeRecord { exit_time: Some(DAY2), pnl: Some(0.10), ..row("REDEEMED", DAY1 + 2700) },
+        TradeRecord { pnl: Some(-0.15), ..row("SHUTDOWN", DAY2 + 60) },
+        // No time: can't be placed
+        TradeRecord { status: "ENTERED".to_string(), ..Default::default() },
+    ];
+
+    let days = report::daily(&records);
+    assert_eq!(days.len(), 2);
+    let day1 = &days[0];
+    assert_eq!(day1.day, "2025-10-09");
+    assert_eq!((day1.trades, day1.closed, day1.wins, day1.stop_losses, day1.aborts), (2, 2, 1, 1, 1));
+    assert_eq!(day1.win_rate, Some(0.5));
+    assert!((day1.avg_fill.unwrap() - 0.975).abs() < 1e-9);
+    assert!((day1.avg_trigger.unwrap() - 0.98).abs() < 1e-9);
+    assert!((day1.pnl.unwrap() + 0.25).abs() < 1e-9);
+    assert_eq!(day1.text(), "2025-10-09: 2 trade(s), win rate 50% (1/2), fill $0.975 vs trigger $0.980, PnL $-0.25, 1 stop-loss(es), 1 abort(s)");
+
+    // The shutdown row's session total isn't counted again
+    assert_eq!(days[1], DailySummary { day: "2025-10-10".to_string(), closed: 1, wins: 1, win_rate: Some(1.0), pnl: Some(0.10), ..Default::default() });
+}
+
+#[test]
+fn missing_columns_print_as_dashes_and_csv_leaves_them_empty() {
+    let days = report::daily(&[row("ENTERED", DAY1)]);
+    assert_eq!(days[0].text(), "2025-10-09: 1 trade(s), win rate -, fill - vs trigger -, PnL -, 0 stop-loss(es), 0 abort(s)");
+
+    let mut out = Vec::new();
+    report::write_csv(&days, &mut out, true).unwrap();
+    report::write_csv(&days, &mut out, false).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "day,trades,closed,wins,win_rate,avg_fill,avg_trigger,pnl,stop_losses,aborts\n2025-10-09,1,0,0,,,,,0,0\n2025-10-09,1,0,0,,,,,0,0\n"
+    );
+    assert_eq!(report::day_of(DAY2 - 1), "2025-10-10");
+}
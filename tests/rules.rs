@@ -0,0 +1,68 @@
+//! BOT_RULES parsing and evaluation over recorded samples.
+
+use eth_no_trend_bot::rules::{Action, Guard, Metric, Rule, RuleEngine, Sample, Transition};
+
+#[test]
+fn parses_conditions_windows_guards_and_actions() {
+    let rule = Rule::parse("api_error_rate>0.2 over 1m while holding -> page, pause").unwrap();
+    assert_eq!((rule.metric, rule.threshold, rule.window, rule.guard), (Metric::ApiErrorRate, 0.2, 60, Some(Guard::Holding)));
+    assert_eq!(rule.actions, [Action::Page, Action::Pause]);
+
+    let rule = Rule::parse("rejects>=3 -> notify").unwrap();
+    assert_eq!((rule.window, rule.guard), (60, None));
+    assert_eq!(Rule::parse("fills<1 over 90 while flat -> liquidate").unwrap().window, 90);
+
+    for (bad, error) in [
+        ("rejects>=3", "expected <condition> -> <actions>"),
+        ("latency>3 -> notify", "unknown metric 'latency'"),
+        ("rejects>=3 -> reboot", "unknown action 'reboot'"),
+        ("rejects>=3 over soon -> notify", "bad window"),
+        ("rejects>=3 while busy -> notify", "unexpected 'while busy'"),
+        ("rejects>=3 ->", "no actions"),
+    ] {
+        let e = Rule::parse(bad).unwrap_err();
+        assert!(e.contains(error), "{}: {}", bad, e);
+    }
+}
+
+#[test]
+fn error_rate_fires_once_and_clears_when_the_window_passes() {
+    let mut engine = RuleEngine::parse("api_error_rate>0.2 over 1m -> notify,pause").unwrap();
+    // No requests yet: no rate to compare
+    assert!(engine.evaluate(0, false).is_empty());
+
+    for (ts, ok) in [(0, true), (1, true), (2, false), (3, true)] {
+        engine.record(ts, Sample::Request { ok });
+    }
+    assert_eq!(engine.evaluate(3, false), [Transition::Fired(0, 0.25)]);
+    assert!(engine.is_paused());
+    // Still holding: no repeat
+    assert!(engine.evaluate(10, false).is_empty());
+
+    // The failure ages out of the window
+    engine.record(62, Sample::Request { ok: true });
+    assert_eq!(engine.evaluate(62, false), [Transition::Cleared(0)]);
+    assert!(!engine.is_paused());
+}
+
+#[test]
+fn guards_limit_rules_to_holding_or_flat() {
+    let mut engine = RuleEngine::parse("api_errors>=1 while holding -> page; rejects>=1 while flat -> notify").unwrap();
+    engine.record(0, Sample::Request { ok: false });
+    engine.record(0, Sample::Reject);
+    assert_eq!(engine.evaluate(1, false), [Transition::Fired(1, 1.0)]);
+    assert_eq!(engine.evaluate(2, true), [Transition::Fired(0, 1.0), Transition::Cleared(1)]);
+    // Notify-only rules never pause
+    assert!(!engine.is_paused());
+}
+
+#[test]
+fn counts_only_samples_inside_each_window() {
+    let mut engine = RuleEngine::parse("fills>=2 over 30s -> notify; rejects>=1 over 5m -> notify").unwrap();
+    engine.record(0, Sample::Reject);
+    engine.record(4, Sample::Fill);
+    engine.record(35, Sample::Fill);
+    assert_eq!(engine.value(Metric::Fills, 30, 35), Some(1.0));
+    assert_eq!(engine.value(Metric::Rejects, 300, 35), Some(1.0));
+    assert_eq!(engine.value(Metric::ApiErrorRate, 60, 35), None);
+}
@@ -0,0 +1,58 @@
+//! Cycle schedules: cron over the UTC start time, skip dates and
+//! skip-after instants.
+
+use eth_no_trend_bot::schedule::{CronExpr, Schedule};
+
+// 2025-10-09 09:00:00 UTC, a Thursday
+const TS: u64 = 1_760_000_400;
+
+#[test]
+fn cron_fields_match_the_cycle_start() {
+    let half_hours = CronExpr::parse("0,30 * * * *").unwrap();
+    assert!(half_hours.matches(TS));
+    assert!(!half_hours.matches(TS + 900));
+    assert!(half_hours.matches(TS + 1_800));
+
+    let business_hours = CronExpr::parse("*/15 13-20 * * 1-5").unwrap();
+    assert!(!business_hours.matches(TS));
+    assert!(business_hours.matches(TS + 4 * 3_600 + 900));
+    // Saturday
+    assert!(!business_hours.matches(TS + 2 * 86_400 + 4 * 3_600));
+
+    // 7 is Sunday as well as 0
+    assert!(CronExpr::parse("* * * * 7").unwrap().matches(TS + 3 * 86_400));
+    // Day-of-month and day-of-week are ORed when both are set
+    let either = CronExpr::parse("* * 1 * 4").unwrap();
+    assert!(either.matches(TS));
+    assert!(either.matches(1_761_955_200)); // 2025-11-01, a Saturday
+    assert!(!either.matches(TS + 86_400));
+}
+
+#[test]
+fn bad_cron_expressions_are_rejected() {
+    for expr in ["* * * *", "60 * * * *", "*/0 * * * *", "5-1 * * * *", "x * * * *", "* * 0 * *"] {
+        assert!(CronExpr::parse(expr).is_err(), "{}", expr);
+    }
+}
+
+#[test]
+fn skip_dates_and_first_cycle_after_an_instant() {
+    let schedule = Schedule::new(900)
+        .with_skip_dates("2025-10-10, 2025-12-25").unwrap()
+        .with_skip_after("2025-10-09T08:00Z,2025-10-09T10:05:30Z").unwrap();
+
+    assert!(schedule.skip_reason(TS + 86_400 + 3_600).is_some());
+    assert_eq!(schedule.skip_reason(TS - 3_600).as_deref(), Some("first cycle after a skip-after instant"));
+    assert!(schedule.skip_reason(TS - 2_700).is_none());
+    // 10:05:30 rounds up to the 10:15 cycle
+    assert!(schedule.skip_reason(TS + 3_600).is_none());
+    assert!(schedule.skip_reason(TS + 4_500).is_some());
+    assert!(schedule.skip_reason(TS).is_none());
+
+    assert!(Schedule::new(900).is_unrestricted());
+    assert!(Schedule::new(900).with_skip_dates("2025-02-30").is_err());
+    assert!(Schedule::new(900).with_skip_after("2025-10-09T08:00").is_err());
+    // Gamma's endDate style, milliseconds and all
+    assert!(Schedule::new(900).with_skip_after("2025-10-09T08:00:00.000Z").is_ok());
+    assert!(Schedule::new(900).with_skip_after("2025-10-09T08:00:00.x0Z").is_err());
+}
@@ -0,0 +1,1355 @@
+//! Full trading-loop scenarios: the bot binary runs on a simulated clock
+//! (BOT_SIM_START / BOT_SIM_END) against the mock API, so a whole market
+//! window plays out in well under a second of sleeping.
+
+mod common;
+
+use common::mock_api::{ExchangeAction, MockApi, OrderOutcome};
+
+// A 15-minute boundary; the bot derives the slug from it
+const MARKET_TS: u64 = 1_760_000_400;
+const YES_TOKEN: &str = "1001";
+const NO_TOKEN: &str = "1002";
+
+fn run_market(mock: &MockApi, test_name: &str) -> (String, String) {
+    let (stdout, log, _) = run_market_with_state(mock, test_name, None);
+    (stdout, log)
+}
+
+/// Like `run_market`, starting from `traded_markets` (the persisted
+/// traded-markets file) when given; also returns that file after the run.
+fn run_market_with_state(mock: &MockApi, test_name: &str, traded_markets: Option<&str>) -> (String, String, String) {
+    mock.add_market(&format!("eth-updown-15m-{}", MARKET_TS), YES_TOKEN, NO_TOKEN);
+
+    let (mut command, workdir) = common::bot_command(&mock.url, test_name);
+    if let Some(contents) = traded_markets {
+        std::fs::write(workdir.join("traded_markets.json"), contents).unwrap();
+    }
+    let output = command
+        // Start right at the opening of the trading window (last 240s)
+        .env("BOT_SIM_START", (MARKET_TS + 900 - 240).to_string())
+        .env("BOT_SIM_END", (MARKET_TS + 900 + 60).to_string())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    assert!(output.status.success(), "bot failed:\n{}\n{}", stdout, String::from_utf8_lossy(&output.stderr));
+
+    let log = std::fs::read_to_string(workdir.join("ETH_NO_trading_log.csv")).unwrap_or_default();
+    let traded = std::fs::read_to_string(workdir.join("traded_markets.json")).unwrap_or_default();
+    let _ = std::fs::remove_dir_all(&workdir);
+    (stdout, log, traded)
+}
+
+#[test]
+fn enters_no_side_when_bid_reaches_entry_price() {
+    let mock = MockApi::start();
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.97, 100.0)], &[(0.98, 100.0)]);
+    mock.script_orders([OrderOutcome::Fill { price: 0.975 }]);
+
+    let (stdout, log) = run_market(&mock, "sim_entry");
+
+    let orders = mock.requests_to("POST", "/order");
+    assert_eq!(orders.len(), 1, "{}", stdout);
+    let body: serde_json::Value = serde_json::from_str(&orders[0].body).unwrap();
+    assert_eq!(body["order"]["tokenId"], NO_TOKEN);
+    assert_eq!(body["order"]["side"], "BUY");
+    assert_eq!(body["orderType"], "FOK");
+    assert!(orders[0].authenticated);
+    // The market cache syncs once at startup, then hourly
+    assert!(stdout.contains("✅ Market cache synced: 0 markets (1 pages)"), "{}", stdout);
+    // Gamma's /markets lookups carry a query; the CLOB listing starts without one
+    assert_eq!(mock.requests_to("GET", "/markets").iter().filter(|r| r.query.is_empty()).count(), 1, "{}", stdout);
+    // Low-balance checks have the exchange re-read the balance first
+    assert!(!mock.requests_to("GET", "/balance-allowance/update").is_empty(), "{}", stdout);
+
+    let entry = log.lines().find(|l| l.contains("ENTERED")).unwrap_or_else(|| panic!("no entry logged:\n{}", log));
+    assert!(entry.contains(",NO,0.975,5.00,"), "{}", entry);
+    // Full UTC timestamp, not a bare time of day
+    assert!(entry.contains(",2025-10-09T09:1"), "{}", entry);
+    assert!(entry.contains("Z,NO,"), "{}", entry);
+}
+
+#[test]
+fn requotes_remainder_after_partial_fill() {
+    let mock = MockApi::start();
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.97, 100.0)], &[(0.98, 100.0)]);
+    mock.script_orders([
+        OrderOutcome::PartialFill { price: 0.98, fraction: 0.6 },
+        OrderOutcome::Fill { price: 0.98 },
+    ]);
+
+    let (stdout, log) = run_market(&mock, "sim_partial");
+
+    let sizes: Vec<String> = mock.state().orders.iter().map(|o| format!("{}", o.size)).collect();
+    assert_eq!(sizes, ["5", "2"], "{}", stdout);
+    assert!(log.lines().any(|l| l.contains("ENTERED") && l.contains(",5.00,")), "{}", log);
+}
+
+#[test]
+fn fractional_fill_is_counted_so_the_requote_never_overbuys() {
+    let mock = MockApi::start();
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.97, 100.0)], &[(0.98, 100.0)]);
+    mock.script_orders([
+        OrderOutcome::PartialShares { price: 0.98, shares: 0.4 },
+        OrderOutcome::Fill { price: 0.98 },
+    ]);
+
+    let (stdout, _) = run_market(&mock, "sim_fractional_fill");
+
+    // 0.4 bought leaves 4.6 to go; only whole shares are ordered, so 4
+    let sizes: Vec<String> = mock.state().orders.iter().map(|o| format!("{}", o.size)).collect();
+    assert_eq!(sizes, ["5", "4"], "{}", stdout);
+    assert!(stdout.contains("Re-quoting remaining 4 shares"), "{}", stdout);
+}
+
+#[test]
+fn cancel_policy_keeps_a_partial_fill_without_requoting() {
+    let mock = MockApi::start();
+    mock.add_market(&format!("eth-updown-15m-{}", MARKET_TS), YES_TOKEN, NO_TOKEN);
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.97, 100.0)], &[(0.98, 100.0)]);
+    mock.script_orders([OrderOutcome::PartialFill { price: 0.98, fraction: 0.6 }]);
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_partial_cancel");
+    std::fs::write(workdir.join("config.toml"), "[strategy]\npartial_fill = \"cancel\"\n").unwrap();
+    let output = command
+        .env("BOT_SIM_START", (MARKET_TS + 900 - 240).to_string())
+        .env("BOT_SIM_END", (MARKET_TS + 900 + 60).to_string())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let log = std::fs::read_to_string(workdir.join("ETH_NO_trading_log.csv")).unwrap_or_default();
+    let _ = std::fs::remove_dir_all(&workdir);
+    assert!(output.status.success(), "bot failed:\n{}", stdout);
+
+    assert!(stdout.contains("Partial Fills: cancel"), "{}", stdout);
+    let sizes: Vec<String> = mock.state().orders.iter().map(|o| format!("{}", o.size)).collect();
+    assert_eq!(sizes, ["5"], "{}", stdout);
+    assert!(!stdout.contains("Re-quoting"), "{}", stdout);
+    let entry = log.lines().find(|l| l.contains(",PARTIAL,")).unwrap_or_else(|| panic!("no entry logged:\n{}", log));
+    assert!(entry.contains("Filled 3.00 of 5 target"), "{}", entry);
+}
+
+#[test]
+fn trailing_stop_follows_the_bid_up_then_sells_on_the_way_down() {
+    let mock = MockApi::start();
+    mock.add_market(&format!("eth-updown-15m-{}", MARKET_TS), YES_TOKEN, NO_TOKEN);
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    for _ in 0..3 {
+        mock.push_book(NO_TOKEN, &[(0.97, 100.0)], &[(0.98, 100.0)]);
+    }
+    for _ in 0..5 {
+        mock.push_book(NO_TOKEN, &[(0.99, 100.0)], &[(0.99, 100.0)]);
+    }
+    mock.push_book(NO_TOKEN, &[(0.95, 100.0)], &[(0.96, 100.0)]);
+    mock.script_orders([OrderOutcome::Fill { price: 0.975 }, OrderOutcome::Fill { price: 0.95 }]);
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_trailing_stop");
+    std::fs::write(workdir.join("config.toml"), "[strategy]\ntrailing_stop = { trail = 0.03, activation = 0.01 }\n").unwrap();
+    let output = command
+        .env("BOT_SIM_START", (MARKET_TS + 900 - 240).to_string())
+        .env("BOT_SIM_END", (MARKET_TS + 900 + 60).to_string())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let log = std::fs::read_to_string(workdir.join("ETH_NO_trading_log.csv")).unwrap_or_default();
+    let _ = std::fs::remove_dir_all(&workdir);
+    assert!(output.status.success(), "bot failed:\n{}", stdout);
+
+    // From the fixed 0.89 up to 0.03 under the 0.99 high, once 0.01 above the 0.975 fill
+    assert!(stdout.contains("🛡️ Stop at $0.890, trailing $0.030 under the bid from $0.985"), "{}", stdout);
+    assert!(stdout.contains("Stop raised to $0.960 (bid $0.990)"), "{}", stdout);
+    assert!(stdout.contains("STOP LOSS: bid $0.950 at or under the $0.960 stop"), "{}", stdout);
+    let sells: Vec<_> = mock.state().orders.iter().filter(|o| o.side == "SELL").map(|o| (o.order_type.clone(), o.size)).collect();
+    assert_eq!(sells, [("FAK".to_string(), 5.0)], "{}", stdout);
+    let exit = log.lines().find(|l| l.contains(",EXITED,")).unwrap_or_else(|| panic!("no exit logged:\n{}", log));
+    assert!(exit.contains("Stop loss at $0.960"), "{}", exit);
+}
+
+#[test]
+fn without_a_trailing_stop_positions_ride_to_resolution() {
+    let mock = MockApi::start();
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    for _ in 0..3 {
+        mock.push_book(NO_TOKEN, &[(0.97, 100.0)], &[(0.98, 100.0)]);
+    }
+    // Far under the stop price, which only a trailing stop setup acts on
+    mock.push_book(NO_TOKEN, &[(0.50, 100.0)], &[(0.60, 100.0)]);
+    mock.script_orders([OrderOutcome::Fill { price: 0.975 }]);
+
+    let (stdout, log) = run_market(&mock, "sim_no_stop");
+    assert!(log.contains("ENTERED"), "{}", log);
+    assert!(!stdout.contains("STOP LOSS"), "{}", stdout);
+    assert_eq!(mock.state().orders.len(), 1);
+}
+
+#[test]
+fn exit_before_close_sells_the_position_and_logs_why() {
+    let mock = MockApi::start();
+    mock.add_market(&format!("eth-updown-15m-{}", MARKET_TS), YES_TOKEN, NO_TOKEN);
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    for _ in 0..3 {
+        mock.push_book(NO_TOKEN, &[(0.97, 100.0)], &[(0.98, 100.0)]);
+    }
+    mock.push_book(NO_TOKEN, &[(0.98, 100.0)], &[(0.99, 100.0)]);
+    mock.script_orders([
+        OrderOutcome::Fill { price: 0.975 },
+        OrderOutcome::PartialFill { price: 0.98, fraction: 0.6 },
+        OrderOutcome::Fill { price: 0.96 },
+    ]);
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_exit_before_close");
+    std::fs::write(workdir.join("config.toml"), "[strategy]\nexit_before_close = 30\n").unwrap();
+    let output = command
+        .env("BOT_SIM_START", (MARKET_TS + 900 - 240).to_string())
+        .env("BOT_SIM_END", (MARKET_TS + 900 + 60).to_string())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let log = std::fs::read_to_string(workdir.join("ETH_NO_trading_log.csv")).unwrap_or_default();
+    let _ = std::fs::remove_dir_all(&workdir);
+    assert!(output.status.success(), "bot failed:\n{}", stdout);
+
+    // Held through the window, with no stop configured, until 30s were left
+    assert!(stdout.contains("⏰ Selling 30s before close"), "{}", stdout);
+    assert!(stdout.contains("FORCED EXIT: 30s to close; selling 5 shares at $0.980, down to $0.950"), "{}", stdout);
+    assert!(!stdout.contains("STOP LOSS"), "{}", stdout);
+    // The exit steps down the liquidation ladder for what the bid didn't take
+    let sells: Vec<_> = mock.state().orders.iter().filter(|o| o.side == "SELL").map(|o| (o.order_type.clone(), format!("{:.2}", o.price), o.size)).collect();
+    assert_eq!(sells, [("FAK".to_string(), "0.98".to_string(), 5.0), ("FAK".to_string(), "0.96".to_string(), 2.0)], "{}", stdout);
+    // One exit for both rungs: 3 @ 0.98 and 2 @ 0.96
+    let exits: Vec<&str> = log.lines().filter(|l| l.contains(",EXITED,")).collect();
+    assert_eq!(exits.len(), 1, "{}", log);
+    assert!(exits[0].contains("Forced exit 30s before close"), "{}", exits[0]);
+    assert!(exits[0].contains(",5.00,"), "{}", exits[0]);
+}
+
+#[test]
+fn tranches_scale_in_as_one_position_at_the_blended_price() {
+    let mock = MockApi::start();
+    mock.add_market(&format!("eth-updown-15m-{}", MARKET_TS), YES_TOKEN, NO_TOKEN);
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.97, 100.0)], &[(0.98, 100.0)]);
+    mock.script_orders([OrderOutcome::Fill { price: 0.98 }, OrderOutcome::PartialFill { price: 0.96, fraction: 0.5 }]);
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_tranches");
+    std::fs::write(workdir.join("config.toml"),
+        "[strategy]\ntranches = [{ price = 0.98, fraction = 0.6 }, { price = 0.96, fraction = 0.4 }]\n").unwrap();
+    let output = command
+        .env("BOT_SIM_START", (MARKET_TS + 900 - 240).to_string())
+        .env("BOT_SIM_END", (MARKET_TS + 900 + 60).to_string())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let log = std::fs::read_to_string(workdir.join("ETH_NO_trading_log.csv")).unwrap_or_default();
+    let _ = std::fs::remove_dir_all(&workdir);
+    assert!(output.status.success(), "bot failed:\n{}", stdout);
+
+    assert!(stdout.contains("🪜 Scaling in: 3 @ $0.980, 2 @ $0.960"), "{}", stdout);
+    let buys: Vec<_> = mock.state().orders.iter().filter(|o| o.side == "BUY").map(|o| (o.order_type.clone(), o.price, o.size)).collect();
+    assert_eq!(buys, [("GTD".to_string(), 0.98, 3.0), ("GTD".to_string(), 0.96, 2.0)], "{}", stdout);
+    // Both tranches rest at once; the part-filled one is pulled only at the
+    // deadline, not canceled after a fill wait
+    assert!(!stdout.contains("Partial fill"), "{}", stdout);
+    assert_eq!(mock.requests_to("DELETE", "/order").len(), 1, "{}", stdout);
+    // 3 @ 0.98 and 1 @ 0.96 make one 4-share position
+    let entry = log.lines().find(|l| l.contains(",PARTIAL,")).unwrap_or_else(|| panic!("no entry logged:\n{}", log));
+    assert!(entry.contains(",0.975,") && entry.contains("Filled 4.00 of 5 target"), "{}", entry);
+    assert_eq!(log.lines().filter(|l| l.contains(",ENTERED,") || l.contains(",PARTIAL,")).count(), 1, "{}", log);
+}
+
+#[test]
+fn balance_sizing_buys_a_percentage_of_the_bankroll() {
+    let mock = MockApi::start();
+    mock.add_market(&format!("eth-updown-15m-{}", MARKET_TS), YES_TOKEN, NO_TOKEN);
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.97, 100.0)], &[(0.98, 100.0)]);
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_balance_sizing");
+    std::fs::write(workdir.join("config.toml"), "[strategy]\nsizing = { balance_pct = 1.0, min_shares = 1, max_shares = 50 }\n").unwrap();
+    let output = command
+        .env("BOT_SIM_START", (MARKET_TS + 900 - 240).to_string())
+        .env("BOT_SIM_END", (MARKET_TS + 900 + 60).to_string())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let _ = std::fs::remove_dir_all(&workdir);
+    assert!(output.status.success(), "bot failed:\n{}", stdout);
+
+    // 1% of the mock's $1000 is $10, 10 shares at the $0.98 ask
+    assert!(stdout.contains("Position Size: 1% of USDC balance, 1-50 shares"), "{}", stdout);
+    assert!(stdout.contains("Sizing: 1% of $1000.00 USDC at $0.980 is 10 shares"), "{}", stdout);
+    let buys: Vec<_> = mock.state().orders.iter().filter(|o| o.side == "BUY").map(|o| o.size).collect();
+    assert_eq!(buys, [10.0], "{}", stdout);
+}
+
+#[test]
+fn balance_sizing_skips_an_entry_the_balance_cannot_cover() {
+    let mock = MockApi::start();
+    mock.add_market(&format!("eth-updown-15m-{}", MARKET_TS), YES_TOKEN, NO_TOKEN);
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.97, 100.0)], &[(0.98, 100.0)]);
+    mock.state().balance = 3.0;
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_balance_sizing_short");
+    std::fs::write(workdir.join("config.toml"), "[strategy]\nsizing = { balance_pct = 50.0, min_shares = 5, max_shares = 50 }\n").unwrap();
+    let output = command
+        .env("BOT_SIM_START", (MARKET_TS + 900 - 240).to_string())
+        .env("BOT_SIM_END", (MARKET_TS + 900 + 60).to_string())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let _ = std::fs::remove_dir_all(&workdir);
+    assert!(output.status.success(), "bot failed:\n{}", stdout);
+
+    assert!(stdout.contains("$3.00 USDC buys fewer than min_shares 5 at $0.980; skipping entry"), "{}", stdout);
+    assert!(mock.requests_to("POST", "/order").is_empty(), "{}", stdout);
+}
+
+#[test]
+fn aborts_without_ordering_when_ask_is_too_high() {
+    let mock = MockApi::start();
+    mock.push_book(YES_TOKEN, &[(0.01, 100.0)], &[(0.02, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.98, 100.0)], &[(0.995, 100.0)]);
+
+    let (stdout, log) = run_market(&mock, "sim_abort");
+
+    assert!(stdout.contains("ABORT TRIGGERED"), "{}", stdout);
+    assert!(mock.requests_to("POST", "/order").is_empty());
+    assert!(!log.contains("ENTERED"));
+}
+
+#[test]
+fn dry_run_signals_without_ordering() {
+    let mock = MockApi::start();
+    mock.add_market(&format!("eth-updown-15m-{}", MARKET_TS), YES_TOKEN, NO_TOKEN);
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.97, 100.0)], &[(0.98, 100.0)]);
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_dry_run");
+    std::fs::write(workdir.join("config.toml"), "dry_run = true\n").unwrap();
+    let output = command
+        .args(["run", "--size", "3"])
+        .env("BOT_SIM_START", (MARKET_TS + 900 - 240).to_string())
+        .env("BOT_SIM_END", (MARKET_TS + 900 + 60).to_string())
+        .output()
+        .unwrap();
+    let _ = std::fs::remove_dir_all(&workdir);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("DRY RUN"), "{}", stdout);
+    assert!(stdout.contains("Dry run: would buy 3 NO shares at up to $0.980"), "{}", stdout);
+    assert!(mock.requests_to("POST", "/order").is_empty());
+}
+
+#[test]
+fn trades_the_configured_asset_with_its_overrides() {
+    let mock = MockApi::start();
+    mock.add_market(&format!("btc-updown-15m-{}", MARKET_TS), YES_TOKEN, NO_TOKEN);
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.97, 100.0)], &[(0.98, 100.0)]);
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_btc");
+    std::fs::write(workdir.join("config.toml"), "dry_run = true\n[assets.btc]\nposition_size = 2\n").unwrap();
+    let output = command
+        .args(["run", "--asset", "btc"])
+        .env("BOT_SIM_START", (MARKET_TS + 900 - 240).to_string())
+        .env("BOT_SIM_END", (MARKET_TS + 900 + 60).to_string())
+        .output()
+        .unwrap();
+    let _ = std::fs::remove_dir_all(&workdir);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("Market: btc-updown-15m"), "{}", stdout);
+    assert!(stdout.contains(&format!("btc-updown-15m-{}", MARKET_TS)), "{}", stdout);
+    assert!(stdout.contains("Dry run: would buy 2 NO shares"), "{}", stdout);
+}
+
+#[test]
+fn hourly_markets_follow_the_slug_template() {
+    let mock = MockApi::start();
+    // MARKET_TS is on the hour: 2025-10-09T09:00Z
+    mock.add_market("eth-up-or-down-2025-10-09-09", YES_TOKEN, NO_TOKEN);
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.97, 100.0)], &[(0.98, 100.0)]);
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_hourly");
+    std::fs::write(workdir.join("config.toml"),
+        "dry_run = true\n[market]\ncadence = \"1h\"\nslug = \"{asset}-up-or-down-{date}-{hour}\"\n").unwrap();
+    let output = command
+        // The window is the hour's last 240s, not the quarter's
+        .env("BOT_SIM_START", (MARKET_TS + 3_600 - 240).to_string())
+        .env("BOT_SIM_END", (MARKET_TS + 3_600 + 60).to_string())
+        .output()
+        .unwrap();
+    let _ = std::fs::remove_dir_all(&workdir);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("Market: eth-up-or-down-{date}-{hour} every 1h"), "{}", stdout);
+    assert!(stdout.contains("Dry run: would buy 5 NO shares"), "{}", stdout);
+}
+
+#[test]
+fn discovery_finds_a_market_whatever_its_slug() {
+    let mock = MockApi::start();
+    // Neither slug is what the template would guess; only the close time
+    // and tag say which one is this cycle's
+    mock.add_market_closing("ethereum-up-or-down-oct-9-930am-et", "2001", "2002", "2025-10-09T09:30:00Z", "crypto");
+    mock.add_market_closing("ethereum-up-or-down-oct-9-915am-et", YES_TOKEN, NO_TOKEN, "2025-10-09T09:15:00Z", "crypto");
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.97, 100.0)], &[(0.98, 100.0)]);
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_discovery");
+    std::fs::write(workdir.join("config.toml"), "dry_run = true\n[market]\ndiscover = true\ntag = \"crypto\"\n").unwrap();
+    let output = command
+        .env("BOT_LOOKAHEAD", "0")
+        .env("BOT_SIM_START", (MARKET_TS + 900 - 240).to_string())
+        .env("BOT_SIM_END", (MARKET_TS + 900 + 60).to_string())
+        .output()
+        .unwrap();
+    let _ = std::fs::remove_dir_all(&workdir);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("Market: discovered on Gamma (tag 'crypto'"), "{}", stdout);
+    assert!(stdout.contains("MONITORING: Mock market ethereum-up-or-down-oct-9-915am-et"), "{}", stdout);
+    assert!(stdout.contains("Dry run: would buy 5 NO shares"), "{}", stdout);
+    assert!(mock.requests_to("GET", "/events").iter().any(|r| r.query.contains("tag_slug=crypto")));
+}
+
+#[test]
+fn markets_trade_side_by_side_under_one_position_cap() {
+    let mock = MockApi::start();
+    mock.add_market(&format!("eth-updown-15m-{}", MARKET_TS), YES_TOKEN, NO_TOKEN);
+    mock.add_market(&format!("btc-updown-15m-{}", MARKET_TS), "2001", "2002");
+    for (yes, no) in [(YES_TOKEN, NO_TOKEN), ("2001", "2002")] {
+        mock.push_book(yes, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+        mock.push_book(no, &[(0.97, 100.0)], &[(0.98, 100.0)]);
+    }
+    mock.script_orders([OrderOutcome::Fill { price: 0.975 }]);
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_multi_market");
+    std::fs::write(workdir.join("config.toml"), "[market]\nassets = [\"eth\", \"btc\"]\n[risk]\nmax_open_positions = 1\n").unwrap();
+    let output = command
+        .env("BOT_SIM_START", (MARKET_TS + 900 - 240).to_string())
+        .env("BOT_SIM_END", (MARKET_TS + 900 + 60).to_string())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let logs: Vec<String> = ["eth", "btc"].iter()
+        .map(|asset| std::fs::read_to_string(workdir.join(asset).join("ETH_NO_trading_log.csv")).unwrap_or_default())
+        .collect();
+    let _ = std::fs::remove_dir_all(&workdir);
+
+    assert!(output.status.success(), "{}\n{}", stdout, String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains("At most 1 position(s) open at once"), "{}", stdout);
+    for asset in ["eth", "btc"] {
+        assert!(stdout.contains(&format!("MONITORING: Mock market {}-updown-15m-{}", asset, MARKET_TS)), "{}", stdout);
+    }
+    // Whichever market signalled first took the one slot
+    assert_eq!(mock.requests_to("POST", "/order").len(), 1, "{}", stdout);
+    assert!(stdout.contains("held back: 1 of 1 positions open across markets"), "{}", stdout);
+    assert_eq!(logs.iter().filter(|log| log.contains("ENTERED")).count(), 1, "{:?}", logs);
+}
+
+#[test]
+fn a_market_already_entered_leaves_the_slot_to_the_others() {
+    let mock = MockApi::start();
+    // eth fills in the first cycle and keeps signalling in the next one,
+    // where btc, listed only then, still needs the one slot
+    mock.add_market(&format!("eth-updown-15m-{}", MARKET_TS), YES_TOKEN, NO_TOKEN);
+    mock.add_market(&format!("eth-updown-15m-{}", MARKET_TS + 900), "1003", "1004");
+    mock.add_market(&format!("btc-updown-15m-{}", MARKET_TS + 900), "2001", "2002");
+    for (yes, no) in [(YES_TOKEN, NO_TOKEN), ("1003", "1004"), ("2001", "2002")] {
+        mock.push_book(yes, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+        mock.push_book(no, &[(0.97, 100.0)], &[(0.98, 100.0)]);
+    }
+    mock.script_orders([OrderOutcome::Fill { price: 0.975 }, OrderOutcome::Fill { price: 0.975 }]);
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_slot_after_fill");
+    std::fs::write(workdir.join("config.toml"), "[market]\nassets = [\"eth\", \"btc\"]\n[risk]\nmax_open_positions = 1\n").unwrap();
+    let output = command
+        .env("BOT_SIM_START", (MARKET_TS + 900 - 240).to_string())
+        .env("BOT_SIM_END", (MARKET_TS + 1800 + 60).to_string())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let btc_log = std::fs::read_to_string(workdir.join("btc").join("ETH_NO_trading_log.csv")).unwrap_or_default();
+    let _ = std::fs::remove_dir_all(&workdir);
+
+    assert!(output.status.success(), "{}\n{}", stdout, String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains(&format!("MONITORING: Mock market eth-updown-15m-{}", MARKET_TS + 900)), "{}", stdout);
+    assert!(!stdout.contains("held back"), "{}", stdout);
+    assert_eq!(mock.requests_to("POST", "/order").len(), 2, "{}", stdout);
+    assert!(btc_log.contains("ENTERED"), "{}", btc_log);
+}
+
+#[test]
+fn trading_window_follows_the_close_gamma_reports() {
+    let mock = MockApi::start();
+    // Closes a minute before the cycle does
+    mock.add_market_closing(&format!("eth-updown-15m-{}", MARKET_TS), YES_TOKEN, NO_TOKEN, "2025-10-09T09:14:00Z", "crypto");
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.97, 100.0)], &[(0.98, 100.0)]);
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_gamma_close");
+    std::fs::write(workdir.join("config.toml"), "dry_run = true\n").unwrap();
+    let output = command
+        // 4m50s before the cycle ends: outside a window counted from the
+        // cycle, inside the last 240s before Gamma's close
+        .env("BOT_SIM_START", (MARKET_TS + 900 - 290).to_string())
+        .env("BOT_SIM_END", (MARKET_TS + 900 + 60).to_string())
+        .output()
+        .unwrap();
+    let _ = std::fs::remove_dir_all(&workdir);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("Closes: ") && !stdout.contains("(by schedule)"), "{}", stdout);
+    assert!(!stdout.contains("Waiting for trading window"), "{}", stdout);
+    assert!(stdout.contains("Dry run: would buy 5 NO shares"), "{}", stdout);
+}
+
+#[test]
+fn paper_trading_fills_against_the_book_and_logs_apart() {
+    let mock = MockApi::start();
+    mock.add_market(&format!("eth-updown-15m-{}", MARKET_TS), YES_TOKEN, NO_TOKEN);
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.97, 100.0)], &[(0.98, 100.0)]);
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_paper");
+    let output = command
+        .args(["run", "--paper"])
+        .env("BOT_SIM_START", (MARKET_TS + 900 - 240).to_string())
+        .env("BOT_SIM_END", (MARKET_TS + 900 + 60).to_string())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let paper_log = std::fs::read_to_string(workdir.join("paper").join("ETH_NO_trading_log.csv")).unwrap_or_default();
+    let paper_orders = std::fs::read_to_string(workdir.join("paper").join("orders.jsonl")).unwrap_or_default();
+    let real_log_written = workdir.join("ETH_NO_trading_log.csv").exists();
+    let _ = std::fs::remove_dir_all(&workdir);
+
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("PAPER TRADING"), "{}", stdout);
+    assert!(mock.requests_to("POST", "/order").is_empty());
+    // Filled at the displayed ask, and recorded like a real entry
+    let entry = paper_log.lines().find(|l| l.contains("ENTERED")).unwrap_or_else(|| panic!("no entry logged:\n{}\n{}", paper_log, stdout));
+    assert!(entry.contains(",NO,0.980,5.00,") && entry.contains(",paper,"), "{}", entry);
+    assert!(paper_orders.contains("\"paper-"), "{}", paper_orders);
+    assert!(!real_log_written);
+}
+
+#[test]
+fn records_the_books_it_trades_on() {
+    let mock = MockApi::start();
+    mock.add_market(&format!("eth-updown-15m-{}", MARKET_TS), YES_TOKEN, NO_TOKEN);
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.97, 100.0)], &[(0.995, 100.0)]);
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_record");
+    let output = command
+        .env("BOT_RECORD_DIR", "recordings")
+        .env("BOT_SIM_START", (MARKET_TS + 900 - 240).to_string())
+        .env("BOT_SIM_END", (MARKET_TS + 900 + 60).to_string())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let recording = workdir.join("recordings").join("books-2025-10-09.jsonl");
+    let records = eth_no_trend_bot::book_recorder::read(recording.to_str().unwrap());
+    let _ = std::fs::remove_dir_all(&workdir);
+
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("Recording books to recordings"), "{}", stdout);
+    // The tick that tripped the abort is on disk, ready for a replay
+    let ticks = eth_no_trend_bot::book_recorder::ticks(&records.unwrap(), &format!("eth-updown-15m-{}", MARKET_TS));
+    assert_eq!(ticks.len(), 1);
+    assert_eq!(ticks[0].ts, MARKET_TS + 900 - 240);
+    assert_eq!(ticks[0].no.best_ask, Some(0.995));
+}
+
+#[test]
+fn waits_out_an_exchange_halt() {
+    let mock = MockApi::start();
+    mock.state().exchange_down = true;
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.97, 100.0)], &[(0.98, 100.0)]);
+
+    let (stdout, _) = run_market(&mock, "sim_halt");
+
+    assert!(stdout.contains("TRADING HALTED"), "{}", stdout);
+    assert!(mock.requests_to("POST", "/order").is_empty());
+}
+
+#[test]
+fn remembers_traded_markets_across_restarts() {
+    let slug = format!("eth-updown-15m-{}", MARKET_TS);
+    let mock = MockApi::start();
+    mock.push_book(YES_TOKEN, &[(0.01, 100.0)], &[(0.02, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.98, 100.0)], &[(0.995, 100.0)]);
+
+    let (_, _, traded) = run_market_with_state(&mock, "sim_traded_saved", None);
+    let traded: serde_json::Value = serde_json::from_str(&traded).unwrap();
+    assert_eq!(traded["markets"][&slug]["reason"], "aborted");
+
+    // A restart inside the same window finds the market already done
+    let mock = MockApi::start();
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.97, 100.0)], &[(0.98, 100.0)]);
+    let (stdout, _, _) = run_market_with_state(&mock, "sim_traded_restart", Some(&traded.to_string()));
+    assert!(stdout.contains("1 market(s) already traded"), "{}", stdout);
+    assert!(mock.requests_to("POST", "/order").is_empty(), "{}", stdout);
+
+    // Entries older than a day are dropped on load and the market trades again
+    let stale = serde_json::json!({ "markets": { slug: { "marked_at": MARKET_TS - 2 * 86_400, "reason": "entered" } } });
+    let (stdout, _, _) = run_market_with_state(&mock, "sim_traded_stale", Some(&stale.to_string()));
+    assert!(stdout.contains("dropped 1 stale"), "{}", stdout);
+    assert_eq!(mock.requests_to("POST", "/order").len(), 1, "{}", stdout);
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn database_keeps_aborts_and_traded_markets_across_restarts() {
+    let slug = format!("eth-updown-15m-{}", MARKET_TS);
+    let mock = MockApi::start();
+    mock.add_market(&slug, YES_TOKEN, NO_TOKEN);
+    mock.push_book(YES_TOKEN, &[(0.01, 100.0)], &[(0.02, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.98, 100.0)], &[(0.995, 100.0)]);
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_database");
+    let run = |command: &mut std::process::Command| {
+        let output = command
+            .env("BOT_SIM_START", (MARKET_TS + 900 - 240).to_string())
+            .env("BOT_SIM_END", (MARKET_TS + 900 + 60).to_string())
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        assert!(output.status.success(), "{}", stdout);
+        stdout
+    };
+    let stdout = run(&mut command);
+    assert!(stdout.contains("🗄️ Migrated bot.db to schema v1"), "{}", stdout);
+    assert!(stdout.contains("🗄️ Database: bot.db"), "{}", stdout);
+
+    let store = eth_no_trend_bot::store::Store::open(workdir.join("bot.db").to_str().unwrap()).unwrap();
+    let aborts = store.aborts().unwrap();
+    assert_eq!(aborts.len(), 1);
+    assert_eq!((aborts[0].market.as_str(), aborts[0].stage.as_str(), aborts[0].ask), (slug.as_str(), "monitoring", Some(0.995)));
+    assert_eq!(store.traded_market(&slug).unwrap().unwrap().reason, "aborted");
+    drop(store);
+
+    // Without the JSON file the database still knows the market is done
+    std::fs::remove_file(workdir.join("traded_markets.json")).unwrap();
+    mock.push_book(NO_TOKEN, &[(0.97, 100.0)], &[(0.98, 100.0)]);
+    let (mut command, _) = common::bot_command_in(&mock.url, &workdir);
+    let stdout = run(&mut command);
+    let _ = std::fs::remove_dir_all(&workdir);
+    assert!(stdout.contains("1 market(s) already traded"), "{}", stdout);
+    assert!(mock.requests_to("POST", "/order").is_empty(), "{}", stdout);
+}
+
+#[test]
+fn resumes_a_position_held_before_a_restart() {
+    let mock = MockApi::start();
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.97, 100.0)], &[(0.98, 100.0)]);
+    // Bought by the run that crashed, plus an old position that isn't ours to resume
+    for (asset, size, redeemable) in [(NO_TOKEN, 5.0, false), ("2002", 3.0, true)] {
+        mock.state().positions.push(serde_json::json!({
+            "asset": asset, "conditionId": "0x11", "size": size, "avgPrice": 0.975,
+            "curPrice": 0.97, "outcome": "Down", "redeemable": redeemable,
+        }));
+    }
+
+    let (stdout, _, traded) = run_market_with_state(&mock, "sim_resume", None);
+    assert!(stdout.contains("♻️ Resuming 5.00 NO shares of eth-updown-15m-1760000400 @ $0.975"), "{}", stdout);
+    assert!(!stdout.contains("Resuming 3.00"), "{}", stdout);
+    assert!(mock.requests_to("POST", "/order").is_empty(), "{}", stdout);
+    assert!(traded.contains("entered"), "{}", traded);
+}
+
+#[test]
+fn exchange_notifications_update_orders_left_resting() {
+    let mock = MockApi::start();
+    mock.add_market(&format!("eth-updown-15m-{}", MARKET_TS), YES_TOKEN, NO_TOKEN);
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    // Never reaches the entry price
+    mock.push_book(NO_TOKEN, &[(0.90, 100.0)], &[(0.99, 100.0)]);
+    mock.script_orders([OrderOutcome::Rest, OrderOutcome::Rest]);
+    let (_, workdir) = common::bot_command(&mock.url, "sim_exchange_events");
+    for price in ["0.90", "0.91"] {
+        let (mut command, _) = common::bot_command_in(&mock.url, &workdir);
+        command.env("BOT_SIM_START", MARKET_TS.to_string()).args(["buy", NO_TOKEN, price, "5", "GTC"]).output().unwrap();
+    }
+    let ids: Vec<String> = mock.state().orders.iter().map(|o| o.id.clone()).collect();
+    assert_eq!(ids.len(), 2);
+    // Once the restarted bot is watching the market, one fills and the other is pulled
+    mock.state().exchange_actions = vec![
+        ExchangeAction::Fill { order_id: ids[0].clone(), price: 0.90 },
+        ExchangeAction::Cancel { order_id: ids[1].clone() },
+    ];
+
+    // A dry run leaves resting orders alone at startup
+    let (mut command, _) = common::bot_command_in(&mock.url, &workdir);
+    let output = command
+        .args(["run", "--dry-run"])
+        .env("BOT_SIM_START", (MARKET_TS + 900 - 240).to_string())
+        .env("BOT_SIM_END", (MARKET_TS + 900 + 60).to_string())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let journal = std::fs::read_to_string(workdir.join("orders.jsonl")).unwrap_or_default();
+    let ledger = eth_no_trend_bot::ledger::Ledger::load(workdir.join("ledger.jsonl").to_str().unwrap()).unwrap();
+    let _ = std::fs::remove_dir_all(&workdir);
+    assert!(output.status.success(), "bot failed:\n{}", stdout);
+
+    assert!(stdout.contains(&format!("📬 Exchange reported fill for order {}", ids[0])), "{}", stdout);
+    assert!(stdout.contains(&format!("⚠️ Exchange cancelled order {}", ids[1])), "{}", stdout);
+    let events: Vec<serde_json::Value> = journal.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+    let filled = events.iter().rev().find(|e| e["state"] == "filled").unwrap_or_else(|| panic!("no fill journaled:\n{}", journal));
+    assert_eq!(filled["filled"], 5.0, "{}", journal);
+    let canceled: Vec<&serde_json::Value> = events.iter().filter(|e| e["state"] == "canceled").collect();
+    assert_eq!(canceled.len(), 1, "{}", journal);
+    assert_eq!(canceled[0]["note"], "canceled by the exchange", "{}", journal);
+    let canceled = canceled[0];
+    assert_ne!(filled["key"], canceled["key"], "{}", journal);
+    // The fill reached the position's books too
+    assert!((ledger.shares(NO_TOKEN) - 5.0).abs() < 1e-9, "{}", stdout);
+}
+
+#[test]
+fn stale_orders_are_canceled_at_startup_and_when_their_market_closes() {
+    let slug = format!("eth-updown-15m-{}", MARKET_TS);
+    let mock = MockApi::start();
+    mock.add_market(&slug, YES_TOKEN, NO_TOKEN);
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    // Never reaches the entry price
+    mock.push_book(NO_TOKEN, &[(0.90, 100.0)], &[(0.99, 100.0)]);
+    mock.script_orders([OrderOutcome::Rest]);
+    // Left resting by a run that died
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_stale_setup");
+    command.env("BOT_SIM_START", MARKET_TS.to_string()).args(["buy", YES_TOKEN, "0.01", "5", "GTC"]).output().unwrap();
+    let _ = std::fs::remove_dir_all(&workdir);
+    assert_eq!(mock.state().orders[0].status, "LIVE");
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_stale_orders");
+    let output = command
+        .env("BOT_SIM_START", (MARKET_TS + 900 - 240).to_string())
+        .env("BOT_SIM_END", (MARKET_TS + 900 + 60).to_string())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let _ = std::fs::remove_dir_all(&workdir);
+    assert!(output.status.success(), "bot failed:\n{}", stdout);
+
+    assert!(stdout.contains("🧹 Canceled 1 order(s) left open by the last run"), "{}", stdout);
+    assert_eq!(mock.requests_to("DELETE", "/cancel-all").len(), 1, "{}", stdout);
+    assert_eq!(mock.state().orders[0].status, "CANCELED");
+
+    // Once the market closes, whatever is left in it goes too
+    let condition_id = mock.state().events[&slug]["markets"][0]["conditionId"].as_str().unwrap().to_string();
+    let closeouts = mock.requests_to("DELETE", "/cancel-market-orders");
+    assert_eq!(closeouts.len(), 1, "{}", stdout);
+    assert!(closeouts[0].body.contains(&condition_id), "{:?}", closeouts[0]);
+}
+
+#[test]
+fn sigterm_cancels_orders_and_flattens_positions() {
+    use std::io::{BufRead, BufReader, Read};
+    use std::process::Stdio;
+
+    let slug = format!("eth-updown-15m-{}", MARKET_TS);
+    let mock = MockApi::start();
+    mock.add_market(&slug, YES_TOKEN, NO_TOKEN);
+    mock.push_book(NO_TOKEN, &[(0.97, 100.0)], &[(0.98, 100.0)]);
+    mock.script_orders([OrderOutcome::Fill { price: 0.975 }, OrderOutcome::Rest, OrderOutcome::Fill { price: 0.965 }]);
+    let place = |args: [&str; 4]| {
+        let (mut command, workdir) = common::bot_command(&mock.url, "sim_shutdown_setup");
+        command.env("BOT_SIM_START", MARKET_TS.to_string()).args(args).args(["GTC"]).output().unwrap();
+        let _ = std::fs::remove_dir_all(&workdir);
+    };
+    // A position bought before this run
+    place(["buy", NO_TOKEN, "0.98", "5"]);
+    mock.state().positions.push(serde_json::json!({
+        "asset": NO_TOKEN, "conditionId": "0x11", "size": 5.0, "avgPrice": 0.975, "curPrice": 0.97, "outcome": "Down",
+    }));
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_shutdown");
+    std::fs::write(workdir.join("config.toml"), "[risk]\nliquidate_on_shutdown = true\n").unwrap();
+    let mut child = command
+        .env("BOT_SIM_START", (MARKET_TS + 900 - 240).to_string())
+        // Runs until signaled
+        .env("BOT_SIM_END", (MARKET_TS + 10 * 365 * 86_400).to_string())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut seen = String::new();
+    for line in stdout.by_ref().lines() {
+        let line = line.unwrap();
+        seen.push_str(&line);
+        seen.push('\n');
+        if line.contains("Resuming") {
+            break;
+        }
+    }
+    // An order resting while it runs, past the startup cancel
+    place(["buy", YES_TOKEN, "0.01", "5"]);
+    std::process::Command::new("kill").args(["-TERM", &child.id().to_string()]).status().unwrap();
+    stdout.read_to_string(&mut seen).unwrap();
+    let status = child.wait().unwrap();
+    let log = std::fs::read_to_string(workdir.join("ETH_NO_trading_log.csv")).unwrap_or_default();
+    let _ = std::fs::remove_dir_all(&workdir);
+    assert!(status.success(), "{}", seen);
+
+    assert!(seen.contains("🛑 Shutting down (SIGTERM)"), "{}", seen);
+    assert_eq!(mock.requests_to("DELETE", "/order").len(), 1, "{}", seen);
+    assert!(mock.state().orders.iter().all(|o| o.status != "LIVE"));
+    let sells: Vec<_> = mock.requests_to("POST", "/order").into_iter().filter(|r| r.body.contains("\"SELL\"")).collect();
+    assert_eq!(sells.len(), 1, "{}", seen);
+    assert!(log.contains(",EXITED,"), "{}", log);
+    let record = log.lines().find(|l| l.contains("SHUTDOWN")).unwrap_or_else(|| panic!("no shutdown record:\n{}", log));
+    assert!(record.contains("SIGTERM; canceled 1 of 1 open order(s); liquidated 1 of 1 position(s); PnL $-0.05"), "{}", record);
+}
+
+#[test]
+fn json_log_format_adds_event_lines_on_stderr() {
+    let mock = MockApi::start();
+    mock.add_market(&format!("eth-updown-15m-{}", MARKET_TS), YES_TOKEN, NO_TOKEN);
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.97, 100.0)], &[(0.98, 100.0)]);
+    mock.script_orders([OrderOutcome::Fill { price: 0.975 }]);
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_json_log");
+    let output = command
+        .args(["--log-format", "json"])
+        .env("BOT_SIM_START", (MARKET_TS + 900 - 240).to_string())
+        .env("BOT_SIM_END", (MARKET_TS + 900 + 60).to_string())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    let _ = std::fs::remove_dir_all(&workdir);
+    assert!(output.status.success(), "{}\n{}", stdout, stderr);
+
+    // The console is unchanged
+    assert!(stdout.contains("🚀 ENTRY TRIGGERED: NO"), "{}", stdout);
+    let lines: Vec<serde_json::Value> = stderr.lines()
+        .filter(|l| l.starts_with('{'))
+        .map(|l| serde_json::from_str(l).unwrap_or_else(|e| panic!("{}: {}", e, l)))
+        .collect();
+    let find = |event: &str, key: &str, value: &str| lines.iter().any(|l| l["event"] == event && l[key] == value);
+    assert!(find("state", "state", "monitoring"), "{}", stderr);
+    assert!(find("fill", "token_id", NO_TOKEN), "{}", stderr);
+    assert!(find("trade", "status", "ENTERED"), "{}", stderr);
+    assert!(lines.iter().all(|l| l["profile"] == "default" && l["ts"].is_string()), "{}", stderr);
+    assert!(!stdout.contains("\"event\""), "{}", stdout);
+}
+
+#[test]
+fn day_summary_goes_out_after_midnight() {
+    // The last market of 2025-10-09 UTC
+    let market_ts = 1_760_054_400 - 900;
+    let mock = MockApi::start();
+    mock.add_market(&format!("eth-updown-15m-{}", market_ts), YES_TOKEN, NO_TOKEN);
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.97, 100.0)], &[(0.98, 100.0)]);
+    mock.script_orders([OrderOutcome::Fill { price: 0.975 }]);
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_daily_report");
+    let output = command
+        .env("BOT_SIM_START", (market_ts + 900 - 240).to_string())
+        .env("BOT_SIM_END", (market_ts + 900 + 60).to_string())
+        .env("BOT_NOTIFY_STDOUT", "info")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let csv = std::fs::read_to_string(workdir.join("daily_report.csv")).unwrap_or_default();
+    let _ = std::fs::remove_dir_all(&workdir);
+    assert!(output.status.success(), "{}", stdout);
+
+    let summary = "2025-10-09: 1 trade(s), win rate -, fill $0.975 vs trigger -, PnL -, 0 stop-loss(es), 0 abort(s)";
+    assert!(stdout.contains(&format!("📅 {}", summary)), "{}", stdout);
+    assert!(stdout.contains("🔔 [info] Daily summary 2025-10-09"), "{}", stdout);
+    assert_eq!(csv, "day,trades,closed,wins,win_rate,avg_fill,avg_trigger,pnl,stop_losses,aborts\n2025-10-09,1,0,0,,0.975,,,0,0\n");
+}
+
+#[test]
+fn skips_cycles_outside_the_schedule() {
+    let mock = MockApi::start();
+    mock.add_market(&format!("eth-updown-15m-{}", MARKET_TS), YES_TOKEN, NO_TOKEN);
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.97, 100.0)], &[(0.98, 100.0)]);
+
+    // MARKET_TS is a :00 cycle; only :30 cycles are allowed
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_schedule");
+    let output = command
+        .env("BOT_SCHEDULE", "30 * * * *")
+        .env("BOT_NOTIFY_STDOUT", "info")
+        .env("BOT_SIM_START", (MARKET_TS + 900 - 240).to_string())
+        .env("BOT_SIM_END", (MARKET_TS + 900 - 60).to_string())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let _ = std::fs::remove_dir_all(&workdir);
+    assert!(output.status.success(), "{}", stdout);
+
+    assert_eq!(stdout.matches("📅 Skipping").count(), 1, "{}", stdout);
+    assert!(stdout.contains("outside BOT_SCHEDULE"), "{}", stdout);
+    assert!(stdout.contains("🔔 [info] Skipping eth-updown-15m-1760000400: outside BOT_SCHEDULE"), "{}", stdout);
+    assert!(mock.requests_to("POST", "/order").is_empty(), "{}", stdout);
+}
+
+#[test]
+fn profiles_trade_side_by_side_with_separate_state() {
+    let mock = MockApi::start();
+    mock.add_market(&format!("eth-updown-15m-{}", MARKET_TS), YES_TOKEN, NO_TOKEN);
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.97, 100.0)], &[(0.98, 100.0)]);
+    mock.script_orders([OrderOutcome::Fill { price: 0.975 }, OrderOutcome::Fill { price: 0.975 }]);
+
+    let alice_address = "0x00000000000000000000000000000000000a11ce";
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_profiles");
+    let output = command
+        .env("BOT_PROFILES", "alice,bob")
+        .env("ALICE_PRIVATE_KEY", format!("0x{}", "11".repeat(32)))
+        .env("ALICE_POLYMARKET_ADDRESS", alice_address)
+        .env("BOB_POLY_API_KEY", "bob-key")
+        .env("BOT_SIM_START", (MARKET_TS + 900 - 240).to_string())
+        .env("BOT_SIM_END", (MARKET_TS + 900 + 60).to_string())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    assert!(output.status.success(), "bot failed:\n{}\n{}", stdout, String::from_utf8_lossy(&output.stderr));
+
+    let makers: Vec<String> = mock.requests_to("POST", "/order").iter()
+        .map(|r| serde_json::from_str::<serde_json::Value>(&r.body).unwrap()["order"]["maker"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(makers.len(), 2, "{}", stdout);
+    assert_eq!(makers.iter().filter(|m| m.as_str() == alice_address).count(), 1, "{:?}", makers);
+
+    for name in ["alice", "bob"] {
+        let dir = workdir.join("profiles").join(name);
+        let log = std::fs::read_to_string(dir.join("ETH_NO_trading_log.csv")).unwrap();
+        assert_eq!(log.lines().filter(|l| l.contains("ENTERED")).count(), 1, "{}: {}", name, log);
+        assert!(dir.join("traded_markets.json").exists(), "{}", name);
+    }
+    assert!(!workdir.join("ETH_NO_trading_log.csv").exists());
+    let _ = std::fs::remove_dir_all(&workdir);
+}
+
+#[test]
+fn repeated_book_errors_are_collapsed() {
+    let mock = MockApi::start();
+    // Market exists but neither token has a book: every fetch 404s
+    let (stdout, _) = run_market(&mock, "sim_book_errors");
+
+    let fetches = mock.requests_to("GET", "/book").len();
+    let shown = stdout.lines().filter(|l| l.contains("Order book fetch error") && !l.contains("repeated")).count();
+    assert!(fetches > 100, "{} fetches\n{}", fetches, stdout);
+    // Once per token per minute of the five-minute run
+    assert!(shown <= 2 * 6, "{} of {} shown\n{}", shown, fetches, stdout);
+    assert!(stdout.contains("more times"), "{}", stdout);
+}
+
+#[test]
+fn price_alerts_fire_without_trading() {
+    let mock = MockApi::start();
+    mock.add_market(&format!("eth-updown-15m-{}", MARKET_TS), YES_TOKEN, NO_TOKEN);
+    mock.push_book(YES_TOKEN, &[(0.01, 100.0)], &[(0.02, 100.0)]);
+    // Ask above the abort price: the strategy stays out
+    mock.push_book(NO_TOKEN, &[(0.98, 100.0)], &[(0.995, 100.0)]);
+
+    // Telegram stand-in; alerts aren't among its default events
+    let telegram = tiny_http::Server::http("127.0.0.1:0").unwrap();
+    let telegram_url = format!("http://{}", telegram.server_addr().to_ip().unwrap());
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_alerts");
+    let output = command
+        .env("BOT_ALERTS", "no_bid>=0.95 left>=180; yes_bid>=0.5")
+        .env("BOT_NOTIFY_FILE", "info")
+        .env("BOT_TELEGRAM_TOKEN", "123:abc")
+        .env("BOT_TELEGRAM_CHAT_ID", "42")
+        .env("BOT_TELEGRAM_API_URL", &telegram_url)
+        .env("BOT_SIM_START", (MARKET_TS + 900 - 240).to_string())
+        .env("BOT_SIM_END", (MARKET_TS + 900 + 60).to_string())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let notifications = std::fs::read_to_string(workdir.join("notifications.jsonl")).unwrap_or_default();
+    let _ = std::fs::remove_dir_all(&workdir);
+    assert!(output.status.success(), "{}", stdout);
+
+    assert!(stdout.contains("🔔 2 price alert(s) armed"), "{}", stdout);
+    assert_eq!(stdout.matches("🔔 ALERT [no_bid>=0.95 left>=180]").count(), 1, "{}", stdout);
+    assert!(!stdout.contains("ALERT [yes_bid"), "{}", stdout);
+    assert!(stdout.contains("ABORT TRIGGERED"), "{}", stdout);
+    assert!(mock.requests_to("POST", "/order").is_empty());
+
+    // Both reach the notification channel too
+    let titles: Vec<String> = notifications.lines()
+        .map(|l| serde_json::from_str::<serde_json::Value>(l).unwrap()["title"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(titles, ["Price alert: no_bid>=0.95 left>=180", "Aborted eth-updown-15m-1760000400"], "{}", notifications);
+
+    let mut request = telegram.recv_timeout(std::time::Duration::from_secs(5)).unwrap().expect("no Telegram message");
+    let mut body = String::new();
+    request.as_reader().read_to_string(&mut body).unwrap();
+    assert_eq!(request.url(), "/bot123:abc/sendMessage");
+    let message: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(message["chat_id"], "42");
+    assert_eq!(message["text"], "⚠️ Aborted eth-updown-15m-1760000400\nGave up during monitoring with the ask at $0.995");
+    assert!(telegram.try_recv().unwrap().is_none());
+}
+
+#[test]
+fn fills_are_booked_to_the_ledger() {
+    let mock = MockApi::start();
+    mock.add_market(&format!("eth-updown-15m-{}", MARKET_TS), YES_TOKEN, NO_TOKEN);
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.97, 100.0)], &[(0.98, 100.0)]);
+    mock.script_orders([
+        OrderOutcome::PartialFill { price: 0.98, fraction: 0.6 },
+        OrderOutcome::Fill { price: 0.98 },
+    ]);
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_ledger");
+    let output = command
+        .env("BOT_SIM_START", (MARKET_TS + 900 - 240).to_string())
+        .env("BOT_SIM_END", (MARKET_TS + 900 + 60).to_string())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    assert!(output.status.success(), "{}", stdout);
+
+    let ledger = eth_no_trend_bot::ledger::Ledger::load(workdir.join("ledger.jsonl").to_str().unwrap()).unwrap();
+    #[cfg(feature = "sqlite")]
+    {
+        let store = eth_no_trend_bot::store::Store::open(workdir.join("bot.db").to_str().unwrap()).unwrap();
+        let trades = store.trades(&format!("eth-updown-15m-{}", MARKET_TS)).unwrap();
+        assert!(trades.iter().any(|t| t.status == "ENTERED" && t.size == Some(5.0)), "{:?}", trades);
+        let fills: Vec<f64> = mock.state().orders.iter().flat_map(|o| store.fills(&o.id).unwrap()).map(|f| f.size).collect();
+        assert_eq!(fills, [3.0, 2.0]);
+    }
+    let _ = std::fs::remove_dir_all(&workdir);
+    // One entry per fill: three shares, then the re-quoted two
+    let shares: Vec<f64> = ledger.entries().iter().map(|e| e.postings[0].shares).collect();
+    assert_eq!(shares, [3.0, 2.0], "{}", stdout);
+    assert!((ledger.shares(NO_TOKEN) - 5.0).abs() < 1e-9);
+    assert!((ledger.usdc() + 4.90).abs() < 1e-9, "{}", ledger.usdc());
+}
+
+#[test]
+fn rule_pages_and_pauses_entries_after_a_rejection() {
+    let mock = MockApi::start();
+    mock.add_market(&format!("eth-updown-15m-{}", MARKET_TS), YES_TOKEN, NO_TOKEN);
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.97, 100.0)], &[(0.98, 100.0)]);
+    mock.script_orders([OrderOutcome::Reject("not enough liquidity".to_string())]);
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_rules");
+    let output = command
+        .env("BOT_RULES", "rejects>=1 over 5m -> page,pause")
+        .env("BOT_NOTIFY_FILE", "critical")
+        .env("BOT_SIM_START", (MARKET_TS + 900 - 240).to_string())
+        .env("BOT_SIM_END", (MARKET_TS + 900 + 60).to_string())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let notifications = std::fs::read_to_string(workdir.join("notifications.jsonl")).unwrap_or_default();
+    let _ = std::fs::remove_dir_all(&workdir);
+    assert!(output.status.success(), "{}", stdout);
+
+    // Without the rule the entry loop would have retried
+    assert_eq!(mock.requests_to("POST", "/order").len(), 1, "{}", stdout);
+    assert!(stdout.contains("📏 RULE [rejects>=1 over 5m -> page,pause] fired at 1.000"), "{}", stdout);
+    assert!(stdout.contains("Entry stopped: paused by BOT_RULES"), "{}", stdout);
+    let paged: Vec<serde_json::Value> = notifications.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+    assert_eq!(paged.len(), 1, "{}", notifications);
+    assert_eq!((paged[0]["severity"].as_str(), paged[0]["event"].as_str()), (Some("critical"), Some("rule")));
+}
+
+#[test]
+fn trade_log_uses_configured_columns_and_format() {
+    let mock = MockApi::start();
+    mock.add_market(&format!("eth-updown-15m-{}", MARKET_TS), YES_TOKEN, NO_TOKEN);
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.97, 100.0)], &[(0.98, 100.0)]);
+    mock.script_orders([OrderOutcome::Fill { price: 0.975 }]);
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_trade_log");
+    let output = command
+        .env("BOT_LOG_COLUMNS", "entry_time:unix,status,market,strategy,side,entry_price:4,size:0,fees,slippage:3")
+        .env("BOT_LOG_FORMAT", "jsonl")
+        .env("BOT_SIM_START", (MARKET_TS + 900 - 240).to_string())
+        .env("BOT_SIM_END", (MARKET_TS + 900 + 60).to_string())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let log = std::fs::read_to_string(workdir.join("ETH_NO_trading_log.jsonl")).unwrap_or_default();
+    let csv_written = workdir.join("ETH_NO_trading_log.csv").exists();
+    let _ = std::fs::remove_dir_all(&workdir);
+    assert!(output.status.success(), "{}", stdout);
+
+    assert!(!csv_written);
+    let line = log.lines().next().unwrap_or_else(|| panic!("nothing logged:\n{}", stdout));
+    assert!(line.starts_with("{\"entry_time\":17600"), "{}", line);
+    let row: serde_json::Value = serde_json::from_str(line).unwrap();
+    assert_eq!(row["status"], "ENTERED");
+    assert_eq!(row["market"], format!("eth-updown-15m-{}", MARKET_TS));
+    assert_eq!(row["strategy"], "eth_no_trend");
+    assert_eq!(row["side"], "NO");
+    assert_eq!(row["entry_price"], 0.975);
+    assert_eq!(row["size"], 5.0);
+    assert!(row["fees"].is_number(), "{}", line);
+    // Filled at 0.975 against a 0.98 ask
+    assert_eq!(row["slippage"], -0.005);
+    assert!(row.get("title").is_none());
+}
+
+#[test]
+fn traded_market_sleeps_to_the_next_open_without_overshooting() {
+    let mock = MockApi::start();
+    let next = MARKET_TS + 900;
+    mock.add_market(&format!("eth-updown-15m-{}", MARKET_TS), YES_TOKEN, NO_TOKEN);
+    mock.add_market(&format!("eth-updown-15m-{}", next), YES_TOKEN, NO_TOKEN);
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_cadence");
+    let traded = serde_json::json!({ "markets": { format!("eth-updown-15m-{}", MARKET_TS): { "marked_at": MARKET_TS + 700, "reason": "entered" } } });
+    std::fs::write(workdir.join("traded_markets.json"), traded.to_string()).unwrap();
+    let output = command
+        .env("BOT_SIM_START", (MARKET_TS + 870).to_string())
+        .env("BOT_SIM_END", (next + 30).to_string())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let _ = std::fs::remove_dir_all(&workdir);
+    assert!(output.status.success(), "{}", stdout);
+
+    // Picked up 5s after the open, so the window is 900 - 240 - 5 away
+    assert!(stdout.contains("(655s remaining)"), "{}", stdout);
+    // Without alerts the wait is a few long sleeps, not a poll per second
+    assert_eq!(stdout.matches("Waiting for trading window").count(), 11, "{}", stdout);
+    assert!(stdout.contains("(55s remaining)"), "{}", stdout);
+}
+
+#[test]
+fn passive_entry_rests_inside_the_spread_then_crosses_near_the_deadline() {
+    let mock = MockApi::start();
+    mock.add_market(&format!("eth-updown-15m-{}", MARKET_TS), YES_TOKEN, NO_TOKEN);
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.96, 100.0)], &[(0.99, 100.0)]);
+    mock.script_orders([OrderOutcome::Rest, OrderOutcome::Fill { price: 0.99 }]);
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_passive_entry");
+    let output = command
+        // Crosses once a twentieth of the 210s entry timeout has gone by,
+        // which is before the first bid's 20s rest is up
+        .env("BOT_ENTRY_EXEC", "passive:0.05")
+        .env("BOT_SIM_START", (MARKET_TS + 900 - 240).to_string())
+        .env("BOT_SIM_END", (MARKET_TS + 900 + 60).to_string())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    assert!(output.status.success(), "bot failed:\n{}", stdout);
+
+    let orders: Vec<(String, f64, String)> = mock.state().orders.iter()
+        .map(|o| (o.order_type.clone(), o.price, o.status.clone()))
+        .collect();
+    assert_eq!(orders.len(), 2, "{}", stdout);
+    // First a bid a tick inside the spread, pulled when it didn't fill
+    assert_eq!(orders[0].0, "GTC");
+    assert!((orders[0].1 - 0.97).abs() < 1e-9, "{:?}", orders);
+    assert_eq!(orders[0].2, "CANCELED");
+    assert_eq!(orders[1].0, "FOK");
+    assert!((orders[1].1 - 0.99).abs() < 1e-9, "{:?}", orders);
+    assert!(stdout.contains("Resting GTC @ $0.970"), "{}", stdout);
+
+    let log = std::fs::read_to_string(workdir.join("ETH_NO_trading_log.csv")).unwrap_or_default();
+    assert!(log.lines().any(|l| l.contains("ENTERED") && l.contains(",5.00,")), "{}", log);
+    let _ = std::fs::remove_dir_all(&workdir);
+}
+
+#[test]
+fn limit_entry_rests_at_the_entry_price_and_follows_the_book() {
+    let mock = MockApi::start();
+    mock.add_market(&format!("eth-updown-15m-{}", MARKET_TS), YES_TOKEN, NO_TOKEN);
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    // A wide book for a while, then the ask drops onto the resting bid
+    for _ in 0..15 {
+        mock.push_book(NO_TOKEN, &[(0.96, 100.0)], &[(0.99, 100.0)]);
+    }
+    mock.push_book(NO_TOKEN, &[(0.95, 100.0)], &[(0.96, 100.0)]);
+    mock.script_orders([OrderOutcome::Rest, OrderOutcome::Fill { price: 0.95 }]);
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_limit_entry");
+    let output = command
+        .env("BOT_ENTRY_EXEC", "limit")
+        .env("BOT_SIM_START", (MARKET_TS + 900 - 240).to_string())
+        .env("BOT_SIM_END", (MARKET_TS + 900 + 60).to_string())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    assert!(output.status.success(), "bot failed:\n{}", stdout);
+
+    let orders: Vec<(String, f64, String)> = mock.state().orders.iter()
+        .map(|o| (o.order_type.clone(), o.price, o.status.clone()))
+        .collect();
+    assert_eq!(orders.len(), 2, "{}", stdout);
+    // Rests at the entry price rather than lifting the 0.99 ask...
+    assert_eq!(orders[0].0, "GTD");
+    assert!((orders[0].1 - 0.96).abs() < 1e-9, "{:?}", orders);
+    assert_eq!(orders[0].2, "CANCELED");
+    // ...and steps a tick under the ask once it comes down to meet the bid
+    assert_eq!(orders[1].0, "GTD");
+    assert!((orders[1].1 - 0.95).abs() < 1e-9, "{:?}", orders);
+    assert!(stdout.contains("Resting GTD @ $0.960"), "{}", stdout);
+    assert!(stdout.contains("limit bid $0.960 -> $0.950"), "{}", stdout);
+    assert!(stdout.contains(&format!("Amending BUY GTD {}: $0.960 x 5 -> $0.950 x 5", mock.state().orders[0].id)), "{}", stdout);
+    // One entry attempt, the bid moved in place
+    assert!(!stdout.contains("Entry Attempt 2/20"), "{}", stdout);
+
+    let log = std::fs::read_to_string(workdir.join("ETH_NO_trading_log.csv")).unwrap_or_default();
+    assert!(log.lines().any(|l| l.contains("ENTERED") && l.contains(",5.00,")), "{}", log);
+    let _ = std::fs::remove_dir_all(&workdir);
+}
+
+#[test]
+fn limit_entry_gives_up_at_the_deadline_without_crossing() {
+    let mock = MockApi::start();
+    mock.add_market(&format!("eth-updown-15m-{}", MARKET_TS), YES_TOKEN, NO_TOKEN);
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.96, 100.0)], &[(0.99, 100.0)]);
+    mock.script_orders([OrderOutcome::Rest]);
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_limit_deadline");
+    let output = command
+        .env("BOT_ENTRY_EXEC", "limit")
+        .env("BOT_SIM_START", (MARKET_TS + 900 - 240).to_string())
+        .env("BOT_SIM_END", (MARKET_TS + 900 + 60).to_string())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let _ = std::fs::remove_dir_all(&workdir);
+    assert!(output.status.success(), "bot failed:\n{}", stdout);
+
+    // One bid for the whole entry window, pulled at the deadline
+    let orders: Vec<(String, String)> = mock.state().orders.iter().map(|o| (o.order_type.clone(), o.status.clone())).collect();
+    assert_eq!(orders, vec![("GTD".to_string(), "CANCELED".to_string())], "{}", stdout);
+    assert!(stdout.contains("Limit bid unfilled by the entry deadline"), "{}", stdout);
+}
+
+#[test]
+fn partly_filled_limit_bid_keeps_resting_until_the_deadline() {
+    let mock = MockApi::start();
+    mock.add_market(&format!("eth-updown-15m-{}", MARKET_TS), YES_TOKEN, NO_TOKEN);
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.96, 100.0)], &[(0.99, 100.0)]);
+    mock.script_orders([OrderOutcome::PartialFill { price: 0.96, fraction: 0.4 }]);
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_limit_partial");
+    let output = command
+        .env("BOT_ENTRY_EXEC", "limit")
+        .env("BOT_SIM_START", (MARKET_TS + 900 - 240).to_string())
+        .env("BOT_SIM_END", (MARKET_TS + 900 + 60).to_string())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let log = std::fs::read_to_string(workdir.join("ETH_NO_trading_log.csv")).unwrap_or_default();
+    let _ = std::fs::remove_dir_all(&workdir);
+    assert!(output.status.success(), "bot failed:\n{}", stdout);
+
+    // The remainder isn't canceled and requoted after a fill wait; the one
+    // bid works the whole window and comes down at the deadline
+    let orders: Vec<(String, String)> = mock.state().orders.iter().map(|o| (o.order_type.clone(), o.status.clone())).collect();
+    assert_eq!(orders, vec![("GTD".to_string(), "CANCELED".to_string())], "{}", stdout);
+    assert_eq!(mock.requests_to("DELETE", "/order").len(), 1, "{}", stdout);
+    assert!(!stdout.contains("Partial fill"), "{}", stdout);
+    // A resting bid is checked once for rewards scoring
+    assert!(stdout.contains("💰 Scoring for liquidity rewards"), "{}", stdout);
+    assert_eq!(mock.requests_to("GET", "/order-scoring").len(), 1, "{}", stdout);
+    let entry = log.lines().find(|l| l.contains(",PARTIAL,")).unwrap_or_else(|| panic!("no entry logged:\n{}", log));
+    assert!(entry.contains("Filled 2.00 of 5 target"), "{}", entry);
+}
+
+#[test]
+fn chaos_mode_503s_never_reach_the_exchange() {
+    let mock = MockApi::start();
+    mock.add_market(&format!("eth-updown-15m-{}", MARKET_TS), YES_TOKEN, NO_TOKEN);
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.97, 100.0)], &[(0.98, 100.0)]);
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_chaos_orders");
+    let output = command
+        .env("BOT_CHAOS", "5xx=1,paths=/order")
+        .env("BOT_SIM_START", (MARKET_TS + 900 - 240).to_string())
+        .env("BOT_SIM_END", (MARKET_TS + 900 + 60).to_string())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let _ = std::fs::remove_dir_all(&workdir);
+    assert!(output.status.success(), "bot failed:\n{}", stdout);
+
+    assert!(stdout.contains("🧪 Chaos mode"), "{}", stdout);
+    assert!(stdout.contains("Ambiguous order response: HTTP 503"), "{}", stdout);
+    // Every attempt was treated as possibly live and reconciled, never double-sent
+    assert!(mock.state().orders.is_empty());
+    assert!(mock.requests_to("POST", "/order").is_empty());
+}
+
+#[test]
+fn chaos_mode_survives_malformed_books() {
+    let mock = MockApi::start();
+    mock.add_market(&format!("eth-updown-15m-{}", MARKET_TS), YES_TOKEN, NO_TOKEN);
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.97, 100.0)], &[(0.98, 100.0)]);
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_chaos_books");
+    let output = command
+        .env("BOT_CHAOS", "malformed=1,paths=/book")
+        .env("BOT_SIM_START", (MARKET_TS + 900 - 240).to_string())
+        .env("BOT_SIM_END", (MARKET_TS + 900 + 60).to_string())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let _ = std::fs::remove_dir_all(&workdir);
+    assert!(output.status.success(), "bot failed:\n{}", stdout);
+
+    // Books were served but never readable, so nothing was traded
+    assert!(!mock.requests_to("GET", "/book").is_empty());
+    assert!(mock.state().orders.is_empty(), "{}", stdout);
+    assert!(stdout.contains("Order book fetch error"), "{}", stdout);
+    assert!(stdout.contains("Entry window timeout"), "{}", stdout);
+}
+
+#[test]
+fn upcoming_market_is_resolved_before_its_cycle_opens() {
+    let mock = MockApi::start();
+    let slug = format!("eth-updown-15m-{}", MARKET_TS);
+    mock.add_market(&slug, YES_TOKEN, NO_TOKEN);
+    mock.push_book(YES_TOKEN, &[(0.02, 100.0)], &[(0.03, 100.0)]);
+    mock.push_book(NO_TOKEN, &[(0.50, 100.0)], &[(0.51, 100.0)]);
+
+    let (mut command, workdir) = common::bot_command(&mock.url, "sim_lookahead");
+    let output = command
+        // Late in the cycle before, whose market was never listed
+        .env("BOT_SIM_START", (MARKET_TS - 100).to_string())
+        .env("BOT_SIM_END", (MARKET_TS + 30).to_string())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let _ = std::fs::remove_dir_all(&workdir);
+    assert!(output.status.success(), "bot failed:\n{}", stdout);
+
+    assert!(stdout.contains("⚡ Market pre-resolved: Mock market"), "{}", stdout);
+    assert!(stdout.contains(&format!("MONITORING: Mock market {}", slug)), "{}", stdout);
+    // Looked up once, ahead of time, not again when the cycle opened
+    let lookups = mock.requests_to("GET", "/events").into_iter().filter(|r| r.query.contains(&slug)).count();
+    assert_eq!(lookups, 1, "{}", stdout);
+}
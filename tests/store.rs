@@ -0,0 +1,149 @@
+//! The SQLite store: rows round-trip, traded markets keep their first
+//! reason, separate connections can write the same file at once, and the
+//! schema is migrated by version.
+
+use eth_no_trend_bot::store::{self, AbortRow, FillRow, Store};
+use eth_no_trend_bot::trade_log::TradeRecord;
+
+fn temp_db(name: &str) -> String {
+    let path = std::env::temp_dir().join(format!("store_{}_{}.db", name, std::process::id()));
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+    }
+    path.to_string_lossy().into_owned()
+}
+
+fn fill(order_id: &str, size: f64) -> FillRow {
+    FillRow {
+        ts: 1_760_000_700,
+        order_id: order_id.to_string(),
+        token_id: "1002".to_string(),
+        side: "BUY".to_string(),
+        size,
+        price: 0.975,
+        fee: 0.01,
+    }
+}
+
+#[test]
+fn trades_fills_and_aborts_round_trip() {
+    let path = temp_db("round_trip");
+    let store = Store::open(&path).unwrap();
+
+    let entry = TradeRecord {
+        title: "Mock market".to_string(),
+        status: "ENTERED".to_string(),
+        market: "eth-updown-15m-1760000400".to_string(),
+        strategy: "eth_no_trend".to_string(),
+        side: "NO".to_string(),
+        token_id: "1002".to_string(),
+        order_id: "0xabc".to_string(),
+        entry_time: Some(1_760_000_700),
+        entry_price: Some(0.975),
+        size: Some(5.0),
+        sl_triggered: Some(false),
+        notes: "paper".to_string(),
+        ..Default::default()
+    };
+    let exit = TradeRecord { status: "EXITED".to_string(), exit_price: Some(0.99), pnl: Some(0.075), ..entry.clone() };
+    store.insert_trade(&entry).unwrap();
+    store.insert_trade(&exit).unwrap();
+    assert_eq!(store.trades("eth-updown-15m-1760000400").unwrap(), [entry, exit]);
+    assert!(store.trades("other").unwrap().is_empty());
+
+    store.insert_fill(&fill("0xabc", 3.0)).unwrap();
+    store.insert_fill(&fill("0xabc", -1.0)).unwrap();
+    store.insert_fill(&fill("0xdef", 2.0)).unwrap();
+    assert_eq!(store.fills("0xabc").unwrap(), [fill("0xabc", 3.0), fill("0xabc", -1.0)]);
+
+    let abort = AbortRow { ts: 1_760_000_800, market: "m".to_string(), stage: "entry".to_string(), ask: Some(0.995) };
+    store.insert_abort(&abort).unwrap();
+    assert_eq!(store.aborts().unwrap(), [abort]);
+
+    // Reopening finds everything still there
+    drop(store);
+    let store = Store::open(&path).unwrap();
+    assert_eq!(store.fills("0xdef").unwrap().len(), 1);
+}
+
+#[test]
+fn traded_markets_keep_the_first_reason() {
+    let store = Store::open(&temp_db("traded")).unwrap();
+    store.mark_traded("m1", 100, "entered").unwrap();
+    store.mark_traded("m1", 200, "closed").unwrap();
+    store.mark_traded("m2", 50, "aborted").unwrap();
+
+    let m1 = store.traded_market("m1").unwrap().unwrap();
+    assert_eq!((m1.marked_at, m1.reason.as_str()), (100, "entered"));
+    assert!(store.traded_market("m3").unwrap().is_none());
+    let recent: Vec<String> = store.traded_markets(60).unwrap().into_iter().map(|(slug, _)| slug).collect();
+    assert_eq!(recent, ["m1"]);
+}
+
+#[test]
+fn concurrent_writers_share_the_file() {
+    let path = temp_db("concurrent");
+    Store::open(&path).unwrap();
+
+    let writers: Vec<_> = (0..4).map(|w| {
+        let path = path.clone();
+        std::thread::spawn(move || {
+            let store = Store::open(&path).unwrap();
+            for i in 0..50 {
+                store.insert_fill(&fill(&format!("w{}", w), i as f64)).unwrap();
+            }
+        })
+    }).collect();
+    for writer in writers {
+        writer.join().unwrap();
+    }
+
+    let store = Store::open(&path).unwrap();
+    for w in 0..4 {
+        assert_eq!(store.fills(&format!("w{}", w)).unwrap().len(), 50);
+    }
+}
+
+#[test]
+fn new_files_are_migrated_to_the_latest_schema() {
+    let path = temp_db("migrate_new");
+    // Listing what's pending doesn't create the file
+    assert_eq!(store::pending(&path).unwrap().len(), store::MIGRATIONS.len());
+    assert!(!std::path::Path::new(&path).exists());
+
+    let store = Store::open(&path).unwrap();
+    assert_eq!(store.migrated().len(), store::MIGRATIONS.len());
+    assert_eq!(store.schema_version().unwrap(), store::latest_version());
+    drop(store);
+
+    assert!(store::pending(&path).unwrap().is_empty());
+    assert!(Store::open(&path).unwrap().migrated().is_empty());
+}
+
+#[test]
+fn files_from_before_versioning_keep_their_rows() {
+    let path = temp_db("migrate_unversioned");
+    {
+        let store = Store::open(&path).unwrap();
+        store.insert_fill(&fill("0xold", 2.0)).unwrap();
+    }
+    // What the unversioned build left behind: the tables at user_version 0
+    rusqlite::Connection::open(&path).unwrap().pragma_update(None, "user_version", 0).unwrap();
+    assert_eq!(store::pending(&path).unwrap()[0].version, 1);
+
+    let store = Store::open(&path).unwrap();
+    assert_eq!(store.migrated()[0].version, 1);
+    assert_eq!(store.fills("0xold").unwrap().len(), 1);
+}
+
+#[test]
+fn files_from_a_newer_build_are_refused() {
+    let path = temp_db("migrate_newer");
+    Store::open(&path).unwrap();
+    let newer = store::latest_version() + 1;
+    rusqlite::Connection::open(&path).unwrap().pragma_update(None, "user_version", newer).unwrap();
+
+    for err in [Store::open(&path).err().unwrap(), store::pending(&path).unwrap_err()] {
+        assert!(err.contains(&format!("schema v{}, newer than this build", newer)), "{}", err);
+    }
+}
@@ -0,0 +1,271 @@
+//! Recorded-tick replays through the pure decision code, the same path the
+//! live loop and a wasm32 build use.
+
+use eth_no_trend_bot::strategy::{
+    self, BalanceSizing, EntryExecution, EntryMonitor, EntryStyle, OrderBook, Outcome, Quote, ReplayOutcome, Signal, StopLoss, StrategyParams,
+    TieBreak, TieCriterion, Tick, TradeSide, TrailingStop, Tranche,
+};
+
+const START: u64 = 1_760_000_400;
+
+fn params(trade_side: TradeSide) -> StrategyParams {
+    StrategyParams {
+        trade_side,
+        entry_price: 0.96,
+        abort_ask_price: 0.99,
+        position_size: 5,
+        market_window: 240,
+        entry_timeout: 210,
+        tie_break: TieBreak::default(),
+        entry_exec: EntryExecution::default(),
+    }
+}
+
+fn book(bid: f64, ask: f64, ask_size: f64) -> OrderBook {
+    OrderBook { best_bid: Some(bid), best_ask: Some(ask), ask_size, bid_size: 100.0 }
+}
+
+/// One tick per second from `from` to `to` (seconds after START) with fixed books.
+fn ticks(from: u64, to: u64, yes: OrderBook, no: OrderBook) -> Vec<Tick> {
+    (from..to).map(|s| Tick { ts: START + s, yes, no }).collect()
+}
+
+#[test]
+fn ignores_triggers_before_the_window_opens() {
+    let hot = ticks(0, 660, book(0.02, 0.03, 100.0), book(0.97, 0.98, 100.0));
+    assert_eq!(strategy::replay(&params(TradeSide::Both), START, &hot[..660]), ReplayOutcome::NoDecision);
+
+    let mut recorded = hot;
+    recorded.extend(ticks(660, 670, book(0.02, 0.03, 100.0), book(0.97, 0.98, 100.0)));
+    assert_eq!(
+        strategy::replay(&params(TradeSide::Both), START, &recorded),
+        ReplayOutcome::Entered { ts: START + 660, outcome: Outcome::No, ask: 0.98, size: 5 }
+    );
+}
+
+#[test]
+fn higher_bid_wins_when_both_sides_trigger() {
+    let recorded = ticks(700, 701, book(0.965, 0.97, 50.0), book(0.97, 0.98, 50.0));
+    let ReplayOutcome::Entered { outcome, .. } = strategy::replay(&params(TradeSide::Both), START, &recorded) else { panic!() };
+    assert_eq!(outcome, Outcome::No);
+
+    // YES-only never takes the NO side, and sizes YES at half
+    assert_eq!(
+        strategy::replay(&params(TradeSide::Yes), START, &recorded),
+        ReplayOutcome::Entered { ts: START + 700, outcome: Outcome::Yes, ask: 0.97, size: 2 }
+    );
+}
+
+#[test]
+fn aborts_on_a_decided_market_and_needs_ask_depth() {
+    let recorded = ticks(700, 701, book(0.005, 0.995, 100.0), book(0.97, 0.98, 100.0));
+    assert_eq!(strategy::replay(&params(TradeSide::Both), START, &recorded), ReplayOutcome::Aborted { ts: START + 700, ask: 0.995 });
+
+    // Bid is there but fewer than position_size shares on the ask
+    let thin = ticks(670, 900, book(0.02, 0.03, 100.0), book(0.97, 0.98, 4.0));
+    assert_eq!(strategy::replay(&params(TradeSide::Both), START, &thin), ReplayOutcome::TimedOut { ts: START + 670 + 211 });
+}
+
+#[test]
+fn closes_when_the_recording_starts_after_close() {
+    let late = ticks(950, 951, book(0.5, 0.51, 100.0), book(0.97, 0.98, 100.0));
+    assert_eq!(strategy::replay(&params(TradeSide::Both), START, &late), ReplayOutcome::Closed { ts: START + 950 });
+}
+
+#[test]
+fn replay_round_trips_through_json() {
+    let recorded = ticks(700, 701, book(0.02, 0.03, 100.0), book(0.97, 0.98, 100.0));
+    let json = serde_json::to_string(&recorded).unwrap();
+    let parsed: Vec<Tick> = serde_json::from_str(&json).unwrap();
+    let outcome = strategy::replay(&params(TradeSide::Both), START, &parsed);
+    assert_eq!(
+        serde_json::to_value(&outcome).unwrap(),
+        serde_json::json!({ "result": "entered", "ts": START + 700, "outcome": "NO", "ask": 0.98, "size": 5 })
+    );
+}
+
+#[test]
+fn validation_catches_inconsistent_parameters() {
+    assert!(params(TradeSide::Both).validate().is_empty());
+
+    let bad = StrategyParams { entry_price: 0.99, abort_ask_price: 0.98, position_size: 0, entry_timeout: 300, ..params(TradeSide::No) };
+    let errors = bad.validate();
+    assert_eq!(errors.len(), 3, "{:?}", errors);
+    assert!(errors[0].starts_with("abort_ask_price 0.98 must be above entry_price 0.99"));
+    assert!(errors[2].starts_with("entry_timeout 300s must be shorter than market_window 240s"));
+}
+
+#[test]
+fn tie_break_policy_picks_the_side_and_names_the_criterion() {
+    let yes = OrderBook { best_bid: Some(0.97), best_ask: Some(0.98), ask_size: 50.0, bid_size: 20.0 };
+    let no = OrderBook { best_bid: Some(0.965), best_ask: Some(0.97), ask_size: 50.0, bid_size: 300.0 };
+    let signal = |policy: &str| {
+        let params = StrategyParams { tie_break: TieBreak::parse(policy).unwrap(), ..params(TradeSide::Both) };
+        EntryMonitor::new(params, START).on_books(&yes, &no)
+    };
+
+    assert_eq!(signal("bid"), Signal::Enter { outcome: Outcome::Yes, ask: 0.98, tie: Some(TieCriterion::Bid) });
+    // NO's spread is 0.005 against YES's 0.01
+    assert_eq!(signal("spread,bid"), Signal::Enter { outcome: Outcome::No, ask: 0.97, tie: Some(TieCriterion::Spread) });
+    assert_eq!(signal("depth"), Signal::Enter { outcome: Outcome::No, ask: 0.97, tie: Some(TieCriterion::Depth) });
+    assert_eq!(signal("no,bid"), Signal::Enter { outcome: Outcome::No, ask: 0.97, tie: Some(TieCriterion::No) });
+
+    // Equal on every listed criterion falls back to YES
+    let same = EntryMonitor::new(params(TradeSide::Both), START).on_books(&yes, &yes);
+    assert_eq!(same, Signal::Enter { outcome: Outcome::Yes, ask: 0.98, tie: Some(TieCriterion::Yes) });
+
+    // Only one side triggering isn't a tie
+    let cold = OrderBook { best_bid: Some(0.02), ..no };
+    assert_eq!(EntryMonitor::new(params(TradeSide::Both), START).on_books(&yes, &cold),
+        Signal::Enter { outcome: Outcome::Yes, ask: 0.98, tie: None });
+}
+
+#[test]
+fn tie_break_parses_and_round_trips() {
+    let policy = TieBreak::parse(" spread, depth ,bid").unwrap();
+    assert_eq!(policy, TieBreak(vec![TieCriterion::Spread, TieCriterion::Depth, TieCriterion::Bid]));
+    assert_eq!(policy.to_string(), "spread,depth,bid");
+    assert_eq!(TieBreak::default().to_string(), "bid");
+    assert!(TieBreak::parse("bid,spot").unwrap_err().contains("'spot'"));
+    assert!(TieBreak::parse(" , ").is_err());
+
+    // Parameters saved before the policy existed still load
+    let mut json = serde_json::to_value(params(TradeSide::Both)).unwrap();
+    assert_eq!(json["tie_break"], serde_json::json!(["bid"]));
+    json.as_object_mut().unwrap().remove("tie_break");
+    let loaded: StrategyParams = serde_json::from_value(json).unwrap();
+    assert_eq!(loaded.tie_break, TieBreak::default());
+}
+
+#[test]
+fn passive_entry_climbs_toward_the_ask_then_crosses() {
+    let passive = EntryExecution::parse("passive:0.5").unwrap();
+    let wide = book(0.90, 0.96, 100.0);
+    let quote = |book: &OrderBook, now: u64| passive.quote(book, 0, now, 100).unwrap();
+
+    // A tick inside the bid, rising linearly to a tick under the ask by the halfway mark
+    assert_eq!(quote(&wide, 0), Quote { price: 0.91, cross: false });
+    assert!((quote(&wide, 25).price - 0.93).abs() < 1e-9);
+    assert!((quote(&wide, 49).price - 0.94).abs() < 1e-9);
+    assert_eq!(quote(&wide, 50), Quote { price: 0.96, cross: true });
+
+    // One-tick spread joins the bid; a locked book crosses straight away
+    assert_eq!(quote(&book(0.95, 0.96, 100.0), 0), Quote { price: 0.95, cross: false });
+    assert!(quote(&book(0.96, 0.96, 100.0), 0).cross);
+    // No time left at all
+    assert!(passive.quote(&wide, 100, 100, 100).unwrap().cross);
+
+    // The default always crosses, and nothing is quoted without an ask
+    assert_eq!(EntryExecution::default().quote(&wide, 0, 0, 100), Some(Quote { price: 0.96, cross: true }));
+    assert_eq!(passive.quote(&OrderBook { best_ask: None, ..wide }, 0, 0, 100), None);
+}
+
+#[test]
+fn limit_entry_rests_at_the_entry_price_and_never_crosses() {
+    let limit = StrategyParams { entry_exec: EntryExecution::parse("limit").unwrap(), ..params(TradeSide::No) };
+    let quote = |book: &OrderBook, now: u64| limit.entry_quote(book, 0, now, 100).unwrap();
+
+    // Held at the 0.96 entry price however wide the spread or late the hour
+    assert_eq!(quote(&book(0.90, 0.99, 100.0), 0), Quote { price: 0.96, cross: false });
+    assert_eq!(quote(&book(0.90, 0.99, 100.0), 100), Quote { price: 0.96, cross: false });
+    // Dropped to a tick under a lower ask, so it stays a maker order
+    assert_eq!(quote(&book(0.93, 0.95, 100.0), 0), Quote { price: 0.94, cross: false });
+    assert_eq!(quote(&book(0.95, 0.95, 100.0), 50), Quote { price: 0.94, cross: false });
+
+    // Other styles pass through untouched
+    let fok = params(TradeSide::No);
+    assert_eq!(fok.entry_quote(&book(0.90, 0.99, 100.0), 0, 0, 100), Some(Quote { price: 0.99, cross: true }));
+}
+
+#[test]
+fn stop_fires_only_once_the_bid_holds_under_it() {
+    let mut stop = StopLoss::new(0.96, 0.89, None, 3);
+    assert!(!stop.on_bid(0.99, 0));
+    // A dip that recovers inside the sustain time is forgiven
+    assert!(!stop.on_bid(0.88, 10));
+    assert!(!stop.on_bid(0.90, 12));
+    assert!(!stop.on_bid(0.89, 20));
+    assert!(!stop.on_bid(0.85, 22));
+    assert!(stop.on_bid(0.87, 23));
+    // A fixed stop never moves
+    assert_eq!(stop.price(), 0.89);
+}
+
+#[test]
+fn trailing_stop_ratchets_up_after_activation_and_never_down() {
+    let mut stop = StopLoss::new(0.96, 0.89, Some(TrailingStop { trail: 0.03, activation: 0.02 }), 0);
+    // Below entry + activation the fixed stop holds
+    assert!(!stop.on_bid(0.97, 0));
+    assert_eq!(stop.price(), 0.89);
+    assert!(!stop.on_bid(0.98, 1));
+    assert!((stop.price() - 0.95).abs() < 1e-9);
+    assert!(!stop.on_bid(0.99, 2));
+    assert!((stop.price() - 0.96).abs() < 1e-9);
+    // The bid easing back doesn't lower it
+    assert!(!stop.on_bid(0.97, 3));
+    assert!((stop.price() - 0.96).abs() < 1e-9);
+    assert!(stop.on_bid(0.96, 4));
+}
+
+#[test]
+fn entry_execution_parses_and_round_trips() {
+    assert_eq!(EntryExecution::parse("fok").unwrap(), EntryExecution::default());
+    let passive = EntryExecution::parse("passive").unwrap();
+    assert_eq!(passive, EntryExecution { style: EntryStyle::Passive, cross_at: 0.75 });
+    assert_eq!(passive.to_string(), "passive:0.75");
+    assert_eq!(EntryExecution::parse(" passive:0.6 ").unwrap().cross_at, 0.6);
+    assert!(EntryExecution::parse("passive:1.5").unwrap_err().contains("'1.5'"));
+    let limit = EntryExecution::parse("limit").unwrap();
+    assert_eq!(limit.style, EntryStyle::Limit);
+    assert_eq!(limit.to_string(), "limit");
+    assert!(EntryExecution::parse("iceberg").unwrap_err().contains("'iceberg'"));
+}
+
+#[test]
+fn tranche_sizes_add_up_to_the_position() {
+    let halves = [Tranche { price: 0.95, fraction: 0.5 }, Tranche { price: 0.97, fraction: 0.5 }];
+    assert_eq!(strategy::tranche_sizes(&halves, 5), [(0.95, 3), (0.97, 2)]);
+    assert_eq!(strategy::tranche_sizes(&halves, 2), [(0.95, 1), (0.97, 1)]);
+    // Half of one share goes to the first tranche; the second gets none
+    assert_eq!(strategy::tranche_sizes(&halves, 1), [(0.95, 1)]);
+
+    let thirds = [Tranche { price: 0.94, fraction: 0.2 }, Tranche { price: 0.95, fraction: 0.3 }, Tranche { price: 0.96, fraction: 0.5 }];
+    let sizes = strategy::tranche_sizes(&thirds, 7);
+    assert_eq!(sizes, [(0.94, 1), (0.95, 3), (0.96, 3)]);
+    assert_eq!(sizes.iter().map(|(_, n)| n).sum::<u32>(), 7);
+}
+
+#[test]
+fn sell_ladder_steps_down_to_the_slippage_limit() {
+    assert_eq!(strategy::sell_ladder(0.91, 0.03, 3), [0.91, 0.89, 0.88]);
+    assert_eq!(strategy::sell_ladder(0.91, 0.03, 4), [0.91, 0.90, 0.89, 0.88]);
+    // More rungs than ticks collapse onto the tick
+    assert_eq!(strategy::sell_ladder(0.50, 0.02, 5), [0.50, 0.49, 0.48]);
+    // One rung sweeps straight to the worst price
+    assert_eq!(strategy::sell_ladder(0.91, 0.03, 1), [0.88]);
+    assert_eq!(strategy::sell_ladder(0.91, 0.0, 3), [0.91]);
+    assert_eq!(strategy::sell_ladder(0.02, 0.05, 2), [0.02, 0.01]);
+}
+
+#[test]
+fn balance_sizing_stakes_a_share_of_the_bankroll_within_its_clamps() {
+    let sizing = BalanceSizing { balance_pct: 5.0, min_shares: 2, max_shares: 40 };
+    // 5% of $200 is $10, or 10 shares at $0.98
+    assert_eq!(sizing.shares(200.0, 0.98), 10);
+    assert_eq!(sizing.shares(196.0, 0.98), 10);
+    assert_eq!(sizing.shares(20.0, 0.98), 2);
+    assert_eq!(sizing.shares(10_000.0, 0.98), 40);
+    assert_eq!(BalanceSizing { min_shares: 0, ..sizing }.shares(10.0, 0.98), 0);
+}
+
+#[test]
+fn balance_sizing_never_buys_more_than_the_balance_affords() {
+    let sizing = BalanceSizing { balance_pct: 5.0, min_shares: 5, max_shares: 40 };
+    // $3 buys 3 shares at $0.98, under min_shares: no entry rather than 5
+    assert_eq!(sizing.shares(3.0, 0.98), 0);
+    assert_eq!(sizing.shares(-5.0, 0.98), 0);
+    // $5 buys exactly min_shares, which the floor then asks for
+    assert_eq!(sizing.shares(4.90, 0.98), 5);
+    // Over 100% of the balance is still capped at what it buys
+    assert_eq!(BalanceSizing { balance_pct: 150.0, ..sizing }.shares(9.80, 0.98), 10);
+}
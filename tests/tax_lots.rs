@@ -0,0 +1,96 @@
+//! FIFO lot matching over synthetic activity histories.
+
+use eth_no_trend_bot::tax_lots::{self, Activity, FifoBook};
+
+fn trade(ts: u64, side: &str, asset: &str, size: f64, usdc: f64) -> Activity {
+    Activity {
+        timestamp: ts,
+        kind: "TRADE".to_string(),
+        condition_id: "0xc1".to_string(),
+        asset: asset.to_string(),
+        side: side.to_string(),
+        size,
+        usdc_size: usdc,
+        price: usdc / size,
+        title: "ETH up or down".to_string(),
+        outcome: if asset == "yes" { "Up" } else { "Down" }.to_string(),
+        transaction_hash: String::new(),
+    }
+}
+
+fn redeem(ts: u64, payout: f64) -> Activity {
+    Activity { kind: "REDEEM".to_string(), asset: String::new(), side: String::new(), usdc_size: payout, size: payout, ..trade(ts, "", "", 1.0, 0.0) }
+}
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
+
+#[test]
+fn sells_close_the_oldest_lots_first_and_split_them() {
+    let book = FifoBook::from_activity(&[
+        trade(1, "BUY", "no", 4.0, 2.0),
+        trade(2, "BUY", "no", 4.0, 3.0),
+        trade(3, "SELL", "no", 6.0, 4.8),
+    ]);
+    let closed = book.closed();
+    assert_eq!(closed.len(), 2);
+    assert_eq!((closed[0].acquired, closed[0].quantity), (1, 4.0));
+    assert!(close(closed[0].cost_basis, 2.0) && close(closed[0].proceeds, 3.2) && close(closed[0].gain, 1.2));
+    assert_eq!((closed[1].acquired, closed[1].quantity), (2, 2.0));
+    assert!(close(closed[1].cost_basis, 1.5) && close(closed[1].proceeds, 1.6));
+    assert_eq!(book.open(), [("no".to_string(), 2.0)]);
+}
+
+#[test]
+fn redemption_pays_the_side_whose_shares_match_and_expires_the_other() {
+    let book = FifoBook::from_activity(&[
+        // Arrives out of order, as the API lists newest first
+        redeem(9, 5.0),
+        trade(1, "BUY", "no", 5.0, 4.8),
+        trade(2, "BUY", "yes", 2.0, 0.1),
+    ]);
+    let closed = book.closed();
+    assert_eq!(closed.len(), 2);
+    let no = closed.iter().find(|l| l.token_id == "no").unwrap();
+    let yes = closed.iter().find(|l| l.token_id == "yes").unwrap();
+    assert!(close(no.proceeds, 5.0) && close(no.gain, 0.2));
+    assert!(close(yes.proceeds, 0.0) && close(yes.gain, -0.1));
+    assert!(closed.iter().all(|l| l.disposal == "redeem" && l.disposed == 9));
+    let gains = book.gains_by_market();
+    assert_eq!(gains.len(), 1);
+    assert!(close(gains[0].1, 0.1));
+    assert!(book.open().is_empty());
+}
+
+#[test]
+fn unexplained_payout_is_spread_evenly_and_orphan_sells_are_reported() {
+    let book = FifoBook::from_activity(&[
+        trade(1, "BUY", "no", 4.0, 2.0),
+        trade(2, "BUY", "yes", 4.0, 2.0),
+        redeem(3, 3.0),
+        trade(4, "SELL", "other", 3.0, 1.0),
+    ]);
+    assert!(book.closed().iter().all(|l| close(l.proceeds, 1.5)));
+    assert_eq!(book.unmatched(), [("other".to_string(), 3.0)]);
+}
+
+#[test]
+fn csv_follows_form_8949_columns() {
+    let book = FifoBook::from_activity(&[trade(1_760_000_400, "BUY", "no", 5.0, 4.8), trade(1_760_086_800, "SELL", "no", 5.0, 4.95)]);
+    let mut out = Vec::new();
+    tax_lots::write_csv(book.closed(), &mut out).unwrap();
+    let csv = String::from_utf8(out).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "Description,Date Acquired,Date Sold,Proceeds,Cost Basis,Gain or Loss,Quantity,Market,Outcome,Condition ID,Token ID,Disposal,Acquired At,Sold At");
+    assert_eq!(lines[1], "5.00 Down (ETH up or down),10/09/2025,10/10/2025,4.95,4.80,0.15,5.000000,ETH up or down,Down,0xc1,no,sell,2025-10-09T09:00:00Z,2025-10-10T09:00:00Z");
+}
+
+#[test]
+fn parses_activity_rows() {
+    let rows = tax_lots::parse_activity(br#"[{"timestamp":1,"type":"TRADE","side":"BUY","asset":"1","conditionId":"0xc1","size":5,"usdcSize":4.8,"price":0.96,"proxyWallet":"0x0"},{"type":"REWARD","usdcSize":0.1}]"#).unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!((rows[0].kind.as_str(), rows[0].side.as_str(), rows[0].size), ("TRADE", "BUY", 5.0));
+    // Rewards aren't disposals
+    assert!(FifoBook::from_activity(&rows).closed().is_empty());
+}
@@ -0,0 +1,74 @@
+//! Telegram channel: message text, event filtering, and delivery off the
+//! caller's thread.
+
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use eth_no_trend_bot::notify::{Notification, Notifier, Severity};
+use eth_no_trend_bot::telegram::{self, TelegramConfig, TelegramNotifier};
+
+fn entry() -> Notification {
+    Notification::new(Severity::Info, "Entered NO eth-updown-15m-1760000400", "5.00 shares @ $0.975", 1_760_000_400)
+        .with_event("entry")
+}
+
+#[test]
+fn text_leads_with_severity_and_title() {
+    assert_eq!(telegram::text(&entry()), "ℹ️ Entered NO eth-updown-15m-1760000400\n5.00 shares @ $0.975");
+    let fatal = Notification::new(Severity::Critical, "Bot stopped on an error", "", 1).with_event("fatal");
+    assert_eq!(telegram::text(&fatal), "🚨 Bot stopped on an error");
+}
+
+#[test]
+fn subscribed_events_are_sent_to_the_chat() {
+    let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+    let mut config = TelegramConfig::new("123:abc", "-1001");
+    config.api_url = format!("http://{}/", server.server_addr().to_ip().unwrap());
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for mut request in server.incoming_requests() {
+            let mut body = String::new();
+            request.as_reader().read_to_string(&mut body).unwrap();
+            tx.send((request.url().to_string(), body)).unwrap();
+            request.respond(tiny_http::Response::from_string(r#"{"ok":true}"#)).unwrap();
+        }
+    });
+
+    let channel = TelegramNotifier::new(config).unwrap();
+    channel.send(&Notification::new(Severity::Info, "Price alert", "", 1).with_event("alert")).unwrap();
+    channel.send(&entry()).unwrap();
+
+    let (path, body) = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(path, "/bot123:abc/sendMessage");
+    let message: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(message["chat_id"], "-1001");
+    assert_eq!(message["text"], telegram::text(&entry()));
+    drop(channel);
+    assert!(rx.try_recv().is_err());
+}
+
+#[test]
+fn a_slow_api_never_blocks_the_sender() {
+    let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+    let mut config = TelegramConfig::new("123:abc", "42");
+    config.api_url = format!("http://{}", server.server_addr().to_ip().unwrap());
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for request in server.incoming_requests() {
+            std::thread::sleep(Duration::from_millis(300));
+            tx.send(()).unwrap();
+            request.respond(tiny_http::Response::from_string(r#"{"ok":false,"description":"Bad Request: chat not found"}"#).with_status_code(400)).unwrap();
+        }
+    });
+
+    let channel = TelegramNotifier::new(config).unwrap();
+    let started = Instant::now();
+    for _ in 0..3 {
+        channel.send(&entry()).unwrap();
+    }
+    assert!(started.elapsed() < Duration::from_millis(300), "send waited on delivery");
+
+    // Dropping delivers what was queued, failures and all
+    drop(channel);
+    assert_eq!(rx.try_iter().count(), 3);
+}
@@ -0,0 +1,74 @@
+//! Binary tick recordings: round trip, index lookups, recovery of a file
+//! cut short by a crash, and the CSV converter.
+
+use std::io::Cursor;
+
+use eth_no_trend_bot::strategy::{OrderBook, Tick};
+use eth_no_trend_bot::tick_log::{self, TickReader, TickWriter};
+
+const START: u64 = 1_760_000_400;
+
+fn tick(ts: u64, no_bid: f64) -> Tick {
+    Tick {
+        ts,
+        yes: OrderBook { best_bid: Some(1.0 - no_bid - 0.01), bid_size: 120.0, best_ask: Some(1.0 - no_bid), ask_size: 80.0 },
+        no: OrderBook { best_bid: Some(no_bid), bid_size: 40.0, best_ask: None, ask_size: 0.0 },
+    }
+}
+
+/// Two markets interleaved, 25 ticks each, frames of 10.
+fn record() -> Vec<u8> {
+    let mut writer = TickWriter::new(Cursor::new(Vec::new())).unwrap().with_frame_ticks(10);
+    for s in 0..25 {
+        writer.push("eth-updown-15m-a", &tick(START + s, 0.5 + s as f64 / 100.0)).unwrap();
+        writer.push("eth-updown-15m-b", &tick(START + 900 + s, 0.2)).unwrap();
+    }
+    writer.finish().unwrap().into_inner()
+}
+
+#[test]
+fn round_trips_ticks_through_the_index() {
+    let bytes = record();
+    let mut reader = TickReader::new(Cursor::new(bytes.clone())).unwrap();
+    assert_eq!(reader.markets(), ["eth-updown-15m-a", "eth-updown-15m-b"]);
+    assert_eq!(reader.index().len(), 6);
+
+    let a = reader.read_market("eth-updown-15m-a", 0, u64::MAX).unwrap();
+    assert_eq!(a.len(), 25);
+    assert_eq!(a[24].ts, START + 24);
+    assert_eq!(a[24].no.best_ask, None);
+    assert_eq!(serde_json::to_value(&a[7]).unwrap(), serde_json::to_value(tick(START + 7, 0.5 + 7.0 / 100.0)).unwrap());
+
+    let window = reader.read_market("eth-updown-15m-a", START + 12, START + 14).unwrap();
+    assert_eq!(window.iter().map(|t| t.ts - START).collect::<Vec<_>>(), [12, 13, 14]);
+
+    // Well under the JSON size for the same ticks
+    let json = serde_json::to_vec(&a).unwrap().len() * 2;
+    assert!(bytes.len() * 4 < json, "{} bytes vs {} as JSON", bytes.len(), json);
+}
+
+#[test]
+fn rebuilds_the_index_when_the_footer_is_missing() {
+    let bytes = record();
+    let index = TickReader::new(Cursor::new(bytes.clone())).unwrap().index().to_vec();
+
+    // Crash after the fourth frame: no index, no footer
+    let cut = index[4].offset as usize;
+    let mut reader = TickReader::new(Cursor::new(bytes[..cut + 3].to_vec())).unwrap();
+    assert_eq!(reader.index(), &index[..4]);
+    assert_eq!(reader.read_market("eth-updown-15m-b", 0, u64::MAX).unwrap().len(), 20);
+
+    assert!(TickReader::new(Cursor::new(b"{\"ts\":1}".to_vec())).is_err());
+}
+
+#[test]
+fn converts_to_csv() {
+    let mut reader = TickReader::new(Cursor::new(record())).unwrap();
+    let mut out = Vec::new();
+    assert_eq!(tick_log::write_csv(&mut reader, &mut out).unwrap(), 50);
+
+    let csv = String::from_utf8(out).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "market,ts,yes_bid,yes_bid_size,yes_ask,yes_ask_size,no_bid,no_bid_size,no_ask,no_ask_size");
+    assert_eq!(lines[1], format!("eth-updown-15m-a,{},0.49,120,0.5,80,0.5,40,,0", START));
+}
@@ -0,0 +1,24 @@
+//! Record timestamps are RFC3339 UTC; console zones are configurable.
+
+use eth_no_trend_bot::timestamps::{self, DisplayTz};
+
+// 2025-10-09 09:00:00 UTC
+const TS: u64 = 1_760_000_400;
+
+#[test]
+fn records_use_rfc3339_utc() {
+    assert_eq!(timestamps::rfc3339(TS), "2025-10-09T09:00:00Z");
+    assert_eq!(timestamps::rfc3339(0), "1970-01-01T00:00:00Z");
+}
+
+#[test]
+fn display_zone_parses_and_shifts_console_times() {
+    assert_eq!(DisplayTz::parse("utc"), Some(DisplayTz::Utc));
+    assert_eq!(DisplayTz::parse("Local"), Some(DisplayTz::Local));
+    assert_eq!(DisplayTz::parse("Europe/Berlin"), None);
+
+    assert_eq!(DisplayTz::Utc.time(TS), "09:00:00 UTC");
+    let ist = DisplayTz::parse("+05:30").unwrap();
+    assert_eq!(ist.time(TS), "14:30:00 +05:30");
+    assert_eq!(DisplayTz::parse("-04:00").unwrap().datetime(TS + 3_600), "2025-10-09 06:00:00 -04:00");
+}
@@ -0,0 +1,119 @@
+use eth_no_trend_bot::trade_log::{self, Column, Field, Format, LogFormat, TradeLog, TradeRecord};
+
+fn entry() -> TradeRecord {
+    TradeRecord {
+        title: "Ethereum Up or Down - October 9, 9AM ET".to_string(),
+        link: "https://polymarket.com/event/eth-updown-15m-1760000400".to_string(),
+        status: "ENTERED".to_string(),
+        market: "eth-updown-15m-1760000400".to_string(),
+        strategy: "eth_no_trend".to_string(),
+        side: "NO".to_string(),
+        token_id: "1002".to_string(),
+        entry_time: Some(1_760_001_060),
+        entry_price: Some(0.975),
+        size: Some(5.0),
+        fees: Some(0.0125),
+        slippage: Some(-0.005),
+        ..Default::default()
+    }
+}
+
+fn temp_path(name: &str) -> String {
+    let dir = std::env::temp_dir().join(format!("trade_log_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.join("ETH_NO_trading_log.csv").to_string_lossy().into_owned()
+}
+
+#[test]
+fn default_layout_is_the_original_csv() {
+    let log = TradeLog::configure("log.csv", None, None).unwrap();
+    assert_eq!(log.format(), LogFormat::Csv);
+    assert_eq!(
+        log.header().join(","),
+        "Market Title,Market Link,Status,entry1_Time,entry_Side,entry_Price,position_size,sl_Time,sl_Price,Final_Status,Notes,is_SL_Triggered"
+    );
+    assert_eq!(
+        log.render(&entry()),
+        "\"Ethereum Up or Down - October 9, 9AM ET\",https://polymarket.com/event/eth-updown-15m-1760000400,ENTERED,2025-10-09T09:11:00Z,NO,0.975,5.00,-,-,-,-,-\n"
+    );
+}
+
+#[test]
+fn columns_pick_fields_order_and_format() {
+    let log = TradeLog::configure("log.csv", Some("entry_time:unix, market,entry_price:4,size:0,fees,pnl,entry_time:%d/%m/%Y %H:%M"), None).unwrap();
+    assert_eq!(log.header(), ["entry1_Time", "market", "entry_Price", "position_size", "fees", "pnl", "entry1_Time"]);
+    assert_eq!(log.render(&entry()), "1760001060,eth-updown-15m-1760000400,0.9750,5,0.0125,-,09/10/2025 09:11\n");
+    assert_eq!(log.columns()[0], Column { field: Field::EntryTime, format: Format::Unix });
+}
+
+#[test]
+fn jsonl_keeps_column_order_and_writes_missing_values_as_null() {
+    let log = TradeLog::configure("/data/ETH_NO_trading_log.csv", Some("status,entry_time,entry_price:2,pnl,slippage"), Some("jsonl")).unwrap();
+    assert_eq!(log.path(), "/data/ETH_NO_trading_log.jsonl");
+    assert_eq!(
+        log.render(&entry()),
+        "{\"status\":\"ENTERED\",\"entry_time\":\"2025-10-09T09:11:00Z\",\"entry_price\":0.98,\"pnl\":null,\"slippage\":-0.005}\n"
+    );
+}
+
+#[test]
+fn bad_configuration_is_rejected() {
+    for (columns, format, expected) in [
+        (Some("title,profit"), None, "unknown column 'profit'"),
+        (Some("pnl:two"), None, "not a number of decimals"),
+        (Some("entry_time:iso"), None, "must be rfc3339, unix, date"),
+        (Some("notes:3"), None, "takes no format"),
+        (Some(" , "), None, "no columns"),
+        (None, Some("xml"), "Invalid BOT_LOG_FORMAT 'xml'"),
+    ] {
+        let err = TradeLog::configure("log.csv", columns, format).unwrap_err();
+        assert!(err.contains(expected), "{:?}: {}", columns, err);
+    }
+    assert!(trade_log::parse_columns("date_acquired").is_err());
+}
+
+#[test]
+fn changed_columns_move_the_old_csv_aside() {
+    let path = temp_path("rotate");
+    let original = TradeLog::configure(&path, None, None).unwrap();
+    assert_eq!(original.init(100).unwrap(), None);
+    original.append(&entry()).unwrap();
+    // Same columns: appended to, not moved
+    assert_eq!(original.init(200).unwrap(), None);
+    assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 2);
+
+    let wider = TradeLog::configure(&path, Some("market,side,pnl"), None).unwrap();
+    let moved = wider.init(300).unwrap().expect("old log moved");
+    assert!(moved.ends_with("ETH_NO_trading_log.300.csv"), "{}", moved);
+    assert_eq!(std::fs::read_to_string(&moved).unwrap().lines().count(), 2);
+    wider.append(&TradeRecord { pnl: Some(0.126), ..entry() }).unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "market,entry_Side,pnl\neth-updown-15m-1760000400,NO,0.13\n");
+
+    let _ = std::fs::remove_dir_all(std::path::Path::new(&path).parent().unwrap());
+}
+
+#[test]
+fn read_gives_back_what_the_columns_hold() {
+    let path = temp_path("read");
+    let exit = TradeRecord { status: "EXITED".to_string(), exit_time: Some(1_760_001_900), exit_price: Some(0.99), pnl: Some(0.076), ..entry() };
+
+    let csv = TradeLog::configure(&path, Some("status,market,entry_time:%d/%m/%Y %H:%M,entry_price:4,exit_time:unix,pnl,notes"), None).unwrap();
+    csv.init(0).unwrap();
+    csv.append(&entry()).unwrap();
+    csv.append(&TradeRecord { notes: "Filled 2.00, of 5".to_string(), ..exit.clone() }).unwrap();
+    let rows = csv.read().unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!((rows[0].status.as_str(), rows[0].entry_time, rows[0].entry_price), ("ENTERED", Some(1_760_001_060), Some(0.975)));
+    assert_eq!((rows[1].exit_time, rows[1].pnl, rows[1].notes.as_str()), (Some(1_760_001_900), Some(0.08), "Filled 2.00, of 5"));
+    // Not a column, so not read back
+    assert_eq!((rows[0].side.as_str(), rows[0].size), ("", None));
+
+    let jsonl = TradeLog::configure(&path, Some("status,entry_time,size,sl_triggered,pnl"), Some("jsonl")).unwrap();
+    jsonl.append(&TradeRecord { sl_triggered: Some(true), ..exit }).unwrap();
+    let rows = jsonl.read().unwrap();
+    assert_eq!(rows, [TradeRecord { status: "EXITED".to_string(), entry_time: Some(1_760_001_060), size: Some(5.0), sl_triggered: Some(true), pnl: Some(0.08), ..Default::default() }]);
+
+    let _ = std::fs::remove_dir_all(std::path::Path::new(&path).parent().unwrap());
+    assert!(csv.read().unwrap().is_empty());
+}
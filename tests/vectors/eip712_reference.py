@@ -0,0 +1,369 @@
+#!/usr/bin/env python3
+"""Produces tests/vectors/eip712_orders.json, the order and ClobAuth vectors.
+
+Builds and signs every order with py_order_utils' OrderBuilder, the builder
+py_clob_client signs its orders with, and every ClobAuth with
+py_clob_client's own signer. The fixture records the client versions:
+
+    pip install py-clob-client
+    python3 tests/vectors/eip712_reference.py > tests/vectors/eip712_orders.json
+
+Without py_clob_client installed it falls back to the pure-Python encoder
+below (keccak-256 + secp256k1 with RFC 6979 nonces, checked against the
+"Mail" example from the EIP-712 specification), and the "generator" field
+says so; regenerate with the client before trusting such a fixture as
+external.
+"""
+
+import hashlib
+import hmac
+import json
+import sys
+
+# ---------------------------------------------------------------- keccak-256
+
+_RC = [
+    0x0000000000000001, 0x0000000000008082, 0x800000000000808A, 0x8000000080008000,
+    0x000000000000808B, 0x0000000080000001, 0x8000000080008081, 0x8000000000008009,
+    0x000000000000008A, 0x0000000000000088, 0x0000000080008009, 0x000000008000000A,
+    0x000000008000808B, 0x800000000000008B, 0x8000000000008089, 0x8000000000008003,
+    0x8000000000008002, 0x8000000000000080, 0x000000000000800A, 0x800000008000000A,
+    0x8000000080008081, 0x8000000000008080, 0x0000000080000001, 0x8000000080008008,
+]
+_ROT = [
+    [0, 36, 3, 41, 18], [1, 44, 10, 45, 2], [62, 6, 43, 15, 61],
+    [28, 55, 25, 21, 56], [27, 20, 39, 8, 14],
+]
+_MASK = (1 << 64) - 1
+
+
+def _rol(x, n):
+    return ((x << n) | (x >> (64 - n))) & _MASK if n else x
+
+
+def _keccak_f(a):
+    for rc in _RC:
+        c = [a[x][0] ^ a[x][1] ^ a[x][2] ^ a[x][3] ^ a[x][4] for x in range(5)]
+        d = [c[(x - 1) % 5] ^ _rol(c[(x + 1) % 5], 1) for x in range(5)]
+        a = [[a[x][y] ^ d[x] for y in range(5)] for x in range(5)]
+        b = [[0] * 5 for _ in range(5)]
+        for x in range(5):
+            for y in range(5):
+                b[y][(2 * x + 3 * y) % 5] = _rol(a[x][y], _ROT[x][y])
+        a = [[b[x][y] ^ ((~b[(x + 1) % 5][y]) & b[(x + 2) % 5][y]) for y in range(5)] for x in range(5)]
+        a[0][0] ^= rc
+    return a
+
+
+def keccak256(data: bytes) -> bytes:
+    rate = 136
+    padded = bytearray(data) + b"\x01"
+    padded += b"\x00" * (-len(padded) % rate)
+    padded[-1] |= 0x80
+    state = [[0] * 5 for _ in range(5)]
+    for block in range(0, len(padded), rate):
+        for i in range(rate // 8):
+            lane = int.from_bytes(padded[block + 8 * i: block + 8 * i + 8], "little")
+            state[i % 5][i // 5] ^= lane
+        state = _keccak_f(state)
+    out = b"".join(state[i % 5][i // 5].to_bytes(8, "little") for i in range(4))
+    return out
+
+
+# ---------------------------------------------------------------- secp256k1
+
+P = 2**256 - 2**32 - 977
+N = 0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141
+G = (0x79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798,
+     0x483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8)
+
+
+def _add(p, q):
+    if p is None:
+        return q
+    if q is None:
+        return p
+    if p[0] == q[0] and (p[1] + q[1]) % P == 0:
+        return None
+    if p == q:
+        lam = 3 * p[0] * p[0] * pow(2 * p[1], -1, P) % P
+    else:
+        lam = (q[1] - p[1]) * pow(q[0] - p[0], -1, P) % P
+    x = (lam * lam - p[0] - q[0]) % P
+    return (x, (lam * (p[0] - x) - p[1]) % P)
+
+
+def _mul(k, point=G):
+    result = None
+    while k:
+        if k & 1:
+            result = _add(result, point)
+        point = _add(point, point)
+        k >>= 1
+    return result
+
+
+def address_of(private_key: int) -> str:
+    x, y = _mul(private_key)
+    return "0x" + keccak256(x.to_bytes(32, "big") + y.to_bytes(32, "big"))[-20:].hex()
+
+
+def _rfc6979_k(private_key: int, digest: bytes) -> int:
+    x = private_key.to_bytes(32, "big")
+    h = (int.from_bytes(digest, "big") % N).to_bytes(32, "big")
+    v, k = b"\x01" * 32, b"\x00" * 32
+    k = hmac.new(k, v + b"\x00" + x + h, hashlib.sha256).digest()
+    v = hmac.new(k, v, hashlib.sha256).digest()
+    k = hmac.new(k, v + b"\x01" + x + h, hashlib.sha256).digest()
+    v = hmac.new(k, v, hashlib.sha256).digest()
+    while True:
+        v = hmac.new(k, v, hashlib.sha256).digest()
+        candidate = int.from_bytes(v, "big")
+        if 1 <= candidate < N:
+            return candidate
+        k = hmac.new(k, v + b"\x00", hashlib.sha256).digest()
+        v = hmac.new(k, v, hashlib.sha256).digest()
+
+
+def sign_hash(private_key: int, digest: bytes) -> str:
+    """65-byte r || s || v signature with low s and v in {27, 28}."""
+    z = int.from_bytes(digest, "big")
+    k = _rfc6979_k(private_key, digest)
+    rx, ry = _mul(k)
+    r = rx % N
+    s = pow(k, -1, N) * (z + r * private_key) % N
+    recovery = ry & 1
+    if s > N // 2:
+        s = N - s
+        recovery ^= 1
+    return "0x" + r.to_bytes(32, "big").hex() + s.to_bytes(32, "big").hex() + bytes([27 + recovery]).hex()
+
+
+# ---------------------------------------------------------------- EIP-712
+
+def _word_uint(v: int) -> bytes:
+    return v.to_bytes(32, "big")
+
+
+def _word_address(a: str) -> bytes:
+    return bytes.fromhex(a[2:].rjust(64, "0"))
+
+
+def typed_digest(domain_separator: bytes, struct_hash: bytes) -> bytes:
+    return keccak256(b"\x19\x01" + domain_separator + struct_hash)
+
+
+ORDER_TYPE = (
+    "Order(uint256 salt,address maker,address signer,address taker,uint256 tokenId,"
+    "uint256 makerAmount,uint256 takerAmount,uint256 expiration,uint256 nonce,"
+    "uint256 feeRateBps,uint8 side,uint8 signatureType)"
+)
+
+
+def exchange_domain(chain_id: int, exchange: str) -> bytes:
+    type_hash = keccak256(b"EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)")
+    return keccak256(type_hash + keccak256(b"Polymarket CTF Exchange") + keccak256(b"1")
+                     + _word_uint(chain_id) + _word_address(exchange))
+
+
+def order_struct_hash(order: dict) -> bytes:
+    side = 0 if order["side"] == "BUY" else 1
+    return keccak256(
+        keccak256(ORDER_TYPE.encode())
+        + _word_uint(int(order["salt"]))
+        + _word_address(order["maker"])
+        + _word_address(order["signer"])
+        + _word_address(order["taker"])
+        + _word_uint(int(order["tokenId"]))
+        + _word_uint(int(order["makerAmount"]))
+        + _word_uint(int(order["takerAmount"]))
+        + _word_uint(int(order["expiration"]))
+        + _word_uint(int(order["nonce"]))
+        + _word_uint(int(order["feeRateBps"]))
+        + _word_uint(side)
+        + _word_uint(int(order["signatureType"]))
+    )
+
+
+def clob_auth_domain(chain_id: int) -> bytes:
+    type_hash = keccak256(b"EIP712Domain(string name,string version,uint256 chainId)")
+    return keccak256(type_hash + keccak256(b"ClobAuthDomain") + keccak256(b"1") + _word_uint(chain_id))
+
+
+def clob_auth_struct_hash(address: str, timestamp: str, nonce: int) -> bytes:
+    type_hash = keccak256(b"ClobAuth(address address,string timestamp,uint256 nonce,string message)")
+    message = b"This message attests that I control the given wallet"
+    return keccak256(type_hash + _word_address(address) + keccak256(timestamp.encode())
+                     + _word_uint(nonce) + keccak256(message))
+
+
+# ---------------------------------------------------------------- self-check
+
+def _check_spec_mail_example():
+    """EIP-712 specification example: Cow mails Bob."""
+    domain_type = keccak256(b"EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)")
+    domain = keccak256(domain_type + keccak256(b"Ether Mail") + keccak256(b"1") + _word_uint(1)
+                       + _word_address("0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"))
+    assert domain.hex() == "f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f", domain.hex()
+
+    person = keccak256(b"Person(string name,address wallet)")
+    mail = keccak256(b"Mail(Person from,Person to,string contents)Person(string name,address wallet)")
+    cow = keccak256(person + keccak256(b"Cow") + _word_address("0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"))
+    bob = keccak256(person + keccak256(b"Bob") + _word_address("0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"))
+    digest = typed_digest(domain, keccak256(mail + cow + bob + keccak256(b"Hello, Bob!")))
+    assert digest.hex() == "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2", digest.hex()
+
+    cow_key = int.from_bytes(keccak256(b"cow"), "big")
+    assert address_of(cow_key) == "0xcd2a3d9f938e13cd947ec05abc7fe734df8dd826"
+    signature = sign_hash(cow_key, digest)
+    assert signature == ("0x4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d"
+                         "07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b91562" "1c"), signature
+
+
+# ---------------------------------------------------------------- vectors
+
+# Hardhat/anvil account #0; public test key, never funded on mainnet
+PRIVATE_KEY = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+# Made up; stands in for a proxy wallet the key signs for
+PROXY = "0x0000000000000000000000000000000000c0ffee"
+ZERO = "0x0000000000000000000000000000000000000000"
+POLYGON = (137, "0x4bfb41d5b3570defd03c39a9a4d8de6bd8b8982e")
+NEG_RISK = (137, "0xc5d563a36ae78145c45a50134d48a1215220f80a")
+AMOY = (80002, "0xdfe02eb6733538f8ea35d585af8de5958ad99e40")
+TOKEN = "71321045679252212594626385532706912750332728571942532289631379312455583992563"
+CLOB_AUTH_MESSAGE = "This message attests that I control the given wallet"
+
+
+def _hex(value):
+    if isinstance(value, str):
+        return "0x" + value.removeprefix("0x")
+    return "0x" + bytes(value).hex()
+
+
+class _Reference:
+    """The pure-Python encoder above."""
+
+    def __init__(self):
+        _check_spec_mail_example()
+        self.key = int(PRIVATE_KEY, 16)
+        self.address = address_of(self.key)
+
+    def order(self, chain_id, exchange, order):
+        domain = exchange_domain(chain_id, exchange)
+        struct_hash = order_struct_hash(order)
+        digest = typed_digest(domain, struct_hash)
+        return domain, struct_hash, digest, sign_hash(self.key, digest)
+
+    def clob_auth(self, chain_id, timestamp, nonce):
+        domain = clob_auth_domain(chain_id)
+        struct_hash = clob_auth_struct_hash(self.address, timestamp, nonce)
+        digest = typed_digest(domain, struct_hash)
+        return domain, struct_hash, digest, sign_hash(self.key, digest)
+
+
+class _Client:
+    """py_order_utils' OrderBuilder for orders, py_clob_client for ClobAuth."""
+
+    def __init__(self):
+        from eth_utils import keccak
+        from py_clob_client.signer import Signer as ClobSigner
+        from py_clob_client.signing.eip712 import get_clob_auth_domain, sign_clob_auth_message
+        from py_clob_client.signing.model import ClobAuth
+        from py_order_utils.builders import OrderBuilder
+        from py_order_utils.model import BUY, SELL, OrderData
+        from py_order_utils.signer import Signer as OrderSigner
+
+        self.keccak = keccak
+        self.clob_signer, self.get_clob_auth_domain, self.sign_clob_auth_message = ClobSigner, get_clob_auth_domain, sign_clob_auth_message
+        self.clob_auth_model = ClobAuth
+        self.order_builder, self.order_data, self.order_signer = OrderBuilder, OrderData, OrderSigner
+        self.sides = {"BUY": BUY, "SELL": SELL}
+        self.address = ClobSigner(PRIVATE_KEY, 137).address().lower()
+
+    def order(self, chain_id, exchange, order):
+        salt = int(order["salt"])
+        builder = self.order_builder(exchange, chain_id, self.order_signer(PRIVATE_KEY), salt_generator=lambda: salt)
+        built = builder.build_order(self.order_data(
+            maker=order["maker"], taker=order["taker"], tokenId=order["tokenId"],
+            makerAmount=order["makerAmount"], takerAmount=order["takerAmount"],
+            side=self.sides[order["side"]], feeRateBps=order["feeRateBps"], nonce=order["nonce"],
+            signer=order["signer"], expiration=order["expiration"], signatureType=order["signatureType"],
+        ))
+        return (builder.domain_separator.hash_struct(), built.hash_struct(),
+                builder._create_struct_hash(built), builder.build_order_signature(built))
+
+    def clob_auth(self, chain_id, timestamp, nonce):
+        signer = self.clob_signer(PRIVATE_KEY, chain_id)
+        domain = self.get_clob_auth_domain(chain_id)
+        auth = self.clob_auth_model(address=signer.address(), timestamp=timestamp, nonce=nonce, message=CLOB_AUTH_MESSAGE)
+        return (domain.hash_struct(), auth.hash_struct(), self.keccak(auth.signable_bytes(domain)),
+                self.sign_clob_auth_message(signer, int(timestamp), nonce))
+
+
+def _encoder():
+    from importlib.metadata import version
+    from importlib.util import find_spec
+
+    # An installed client that fails to import is an error, not a fallback
+    if find_spec("py_clob_client") is None:
+        return _Reference(), "pure-Python reference encoder (py_clob_client not installed)"
+    return _Client(), "py_clob_client {} (orders via py_order_utils {})".format(
+        version("py-clob-client"), version("py-order-utils"))
+
+
+def main():
+    encoder, generator = _encoder()
+    eoa = encoder.address
+
+    cases = [
+        ("eoa_buy_polygon", POLYGON, dict(salt="479249096354", maker=eoa, signer=eoa, side="BUY",
+                                          makerAmount="4800000", takerAmount="5000000", signatureType=0)),
+        ("eoa_sell_polygon", POLYGON, dict(salt="1", maker=eoa, signer=eoa, side="SELL",
+                                           makerAmount="5000000", takerAmount="4450000", signatureType=0)),
+        ("proxy_buy_polygon", POLYGON, dict(salt="1718000000123", maker=PROXY, signer=eoa, side="BUY",
+                                            makerAmount="2450000", takerAmount="2500000", signatureType=1)),
+        ("proxy_sell_expiring_fee", POLYGON, dict(salt="987654321987654321", maker=PROXY, signer=eoa, side="SELL",
+                                                  makerAmount="10000000", takerAmount="9900000", signatureType=1,
+                                                  expiration="1760000999", nonce="7", feeRateBps="100")),
+        ("eoa_buy_neg_risk", NEG_RISK, dict(salt="42", maker=eoa, signer=eoa, side="BUY",
+                                            makerAmount="960000", takerAmount="1000000", signatureType=0)),
+        ("eoa_buy_amoy", AMOY, dict(salt="42", maker=eoa, signer=eoa, side="BUY",
+                                    makerAmount="960000", takerAmount="1000000", signatureType=0)),
+    ]
+
+    orders = []
+    for name, (chain_id, exchange), fields in cases:
+        order = dict(taker=ZERO, tokenId=TOKEN, expiration="0", nonce="0", feeRateBps="0")
+        order.update(fields)
+        domain, struct_hash, digest, signature = encoder.order(chain_id, exchange, order)
+        orders.append({
+            "name": name,
+            "chain_id": chain_id,
+            "exchange": exchange,
+            "order": order,
+            "domain_separator": _hex(domain),
+            "struct_hash": _hex(struct_hash),
+            "order_hash": _hex(digest),
+            "signature": _hex(signature),
+        })
+
+    auth = []
+    for chain_id, timestamp, nonce in [(137, "1760000000", 0), (137, "1760000123", 5), (80002, "1760000000", 0)]:
+        domain, struct_hash, digest, signature = encoder.clob_auth(chain_id, timestamp, nonce)
+        auth.append({
+            "chain_id": chain_id,
+            "timestamp": timestamp,
+            "nonce": nonce,
+            "domain_separator": _hex(domain),
+            "struct_hash": _hex(struct_hash),
+            "digest": _hex(digest),
+            "signature": _hex(signature),
+        })
+
+    vectors = {"generator": generator, "private_key": PRIVATE_KEY, "address": eoa, "orders": orders, "clob_auth": auth}
+    json.dump(vectors, sys.stdout, indent=2)
+    print()
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,81 @@
+#!/usr/bin/env python3
+"""Produces tests/vectors/hmac_signatures.json, the L2 HMAC vectors.
+
+Signs each input with py_clob_client's own `build_hmac_signature` and
+records the client version in the fixture:
+
+    pip install py-clob-client
+    python3 tests/vectors/hmac_signatures.py > tests/vectors/hmac_signatures.json
+
+Without py_clob_client installed it falls back to a line-for-line copy of
+that function on the standard library, and the "generator" field says so;
+regenerate with the client before trusting such a fixture as external.
+
+py_clob_client signs str(body) with ' replaced by ", which only matches
+the JSON on the wire when the body has no apostrophes, so none of the
+inputs below do.
+"""
+
+import base64
+import hashlib
+import hmac
+import json
+import sys
+
+SECRETS = [
+    # 32 bytes with '-' and '_' in the encoding, as base64url secrets have
+    base64.urlsafe_b64encode(bytes(range(0xE0, 0x100))).decode(),
+    base64.urlsafe_b64encode(b"throwaway-l2-secret-for-vectors!").decode(),
+]
+
+INPUTS = [
+    ("get_no_body", 0, "1760000400", "GET", "/data/orders", ""),
+    ("get_balance_update", 1, "1760000401", "GET", "/balance-allowance/update", ""),
+    ("delete_order", 0, "1760000402", "DELETE", "/order",
+     '{"orderID":"0x5b0b4bd6e2bd8e5b5f3a6cb0a5c0c2e4a1a7f3a9d51cfb1e0e1b7c3a2f4d6e80"}'),
+    ("post_order", 1, "1760000403", "POST", "/order",
+     '{"order":{"salt":"479249096354","maker":"0x0000000000000000000000000000000000c0ffee",'
+     '"side":"BUY","makerAmount":"4800000","takerAmount":"5000000"},"owner":"key","orderType":"FOK"}'),
+    ("post_scoring", 0, "1760000404", "POST", "/orders-scoring", '["0xabc","0xdef"]'),
+]
+
+
+def _fallback(secret, timestamp, method, request_path, body=None):
+    # py_clob_client/signing/hmac.py, build_hmac_signature
+    base64_secret = base64.urlsafe_b64decode(secret)
+    message = str(timestamp) + str(method) + str(request_path)
+    if body:
+        message += str(body).replace("'", '"')
+    h = hmac.new(base64_secret, bytes(message, "utf-8"), hashlib.sha256)
+    return (base64.urlsafe_b64encode(h.digest())).decode("utf-8")
+
+
+def _signer():
+    try:
+        from importlib.metadata import version
+        from py_clob_client.signing.hmac import build_hmac_signature
+    except ImportError:
+        return _fallback, "stdlib copy of py_clob_client build_hmac_signature (client not installed)"
+    return build_hmac_signature, "py_clob_client " + version("py-clob-client")
+
+
+def main():
+    sign, generator = _signer()
+    vectors = []
+    for name, secret, timestamp, method, path, body in INPUTS:
+        assert "'" not in body, name
+        vectors.append({
+            "name": name,
+            "secret": SECRETS[secret],
+            "timestamp": timestamp,
+            "method": method,
+            "request_path": path,
+            "body": body,
+            "signature": sign(SECRETS[secret], timestamp, method, path, body or None),
+        })
+    json.dump({"generator": generator, "vectors": vectors}, sys.stdout, indent=2)
+    sys.stdout.write("\n")
+
+
+if __name__ == "__main__":
+    main()
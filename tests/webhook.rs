@@ -0,0 +1,78 @@
+//! Webhook channel: template rendering, event filtering, and a signed POST
+//! that a receiver can verify.
+
+use std::sync::mpsc;
+
+use eth_no_trend_bot::notify::{Notification, Notifier, Severity};
+use eth_no_trend_bot::webhook::{self, WebhookConfig, WebhookNotifier};
+
+fn entry() -> Notification {
+    Notification::new(Severity::Info, "Entered NO eth-updown-15m-1760000400", "ETH \"Up or Down\": 5.00 shares @ $0.975", 1_760_000_400)
+        .with_event("entry")
+}
+
+#[test]
+fn templates_get_json_escaped_fields() {
+    let template = r#"{"text":"{{title}}: {{body}}","at":"{{time}}","kind":"{{event}}/{{severity}}","ts":{{ts}}}"#;
+    let rendered = webhook::render(Some(template), &entry());
+    let value: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+    assert_eq!(value["text"], "Entered NO eth-updown-15m-1760000400: ETH \"Up or Down\": 5.00 shares @ $0.975");
+    assert_eq!(value["at"], "2025-10-09T09:00:00Z");
+    assert_eq!(value["kind"], "entry/info");
+    assert_eq!(value["ts"], 1_760_000_400u64);
+
+    // No template: the notification as JSON
+    let plain: Notification = serde_json::from_str(&webhook::render(None, &entry())).unwrap();
+    assert_eq!(plain, entry());
+}
+
+#[test]
+fn signature_is_hmac_of_timestamp_and_body() {
+    // python: hmac.new(b"whsec", b'1760000400.{"a":1}', hashlib.sha256).hexdigest()
+    assert_eq!(webhook::sign("whsec", 1_760_000_400, r#"{"a":1}"#), "44627cdfe19800e025ef3ab8cd37635322d9ceacdb01bf5c7eca89f8dd3b79b0");
+}
+
+#[test]
+fn subscribed_events_are_posted_signed() {
+    let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hook", server.server_addr().to_ip().unwrap());
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for mut request in server.incoming_requests() {
+            let mut body = String::new();
+            request.as_reader().read_to_string(&mut body).unwrap();
+            let header = |name: &'static str| request.headers().iter().find(|h| h.field.equiv(name)).map(|h| h.value.to_string());
+            tx.send((request.url().to_string(), header("X-Bot-Timestamp"), header("X-Bot-Signature"), body)).unwrap();
+            request.respond(tiny_http::Response::empty(204)).unwrap();
+        }
+    });
+
+    let mut config = WebhookConfig::new(&url);
+    config.secret = Some("whsec".to_string());
+    let channel = WebhookNotifier::new(config).unwrap();
+
+    // Not subscribed by default: nothing is sent
+    channel.send(&Notification::new(Severity::Info, "Price alert", "", 1).with_event("alert")).unwrap();
+    channel.send(&entry()).unwrap();
+
+    let (path, timestamp, signature, body) = rx.recv_timeout(std::time::Duration::from_secs(5)).unwrap();
+    assert_eq!(path, "/hook");
+    assert_eq!(timestamp.as_deref(), Some("1760000400"));
+    assert_eq!(signature.unwrap(), format!("sha256={}", webhook::sign("whsec", 1_760_000_400, &body)));
+    assert!(body.contains("\"event\":\"entry\""), "{}", body);
+    assert!(rx.try_recv().is_err());
+}
+
+#[test]
+fn receiver_errors_surface_as_failures() {
+    let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/", server.server_addr().to_ip().unwrap());
+    std::thread::spawn(move || {
+        for request in server.incoming_requests() {
+            request.respond(tiny_http::Response::empty(500)).unwrap();
+        }
+    });
+    let mut config = WebhookConfig::new(&url);
+    config.events = vec!["*".to_string()];
+    assert!(WebhookNotifier::new(config).unwrap().send(&entry()).is_err());
+}
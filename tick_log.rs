@@ -0,0 +1,323 @@
+//! Compact on-disk format for recorded ticks (feature `recording`). Per-second
+//! JSON books for many markets grow by gigabytes a week; this keeps the same
+//! `strategy::Tick`s in zstd-compressed frames of fixed-width records.
+//!
+//! Layout:
+//!   header   MAGIC, u8 version
+//!   frame*   u16 market_len, market, u32 ticks, u32 compressed_len, zstd(records)
+//!   index    u32 frames, then per frame: u16 market_len, market, u64 first_ts,
+//!            u64 last_ts, u64 offset, u32 ticks
+//!   footer   u64 index_offset, INDEX_MAGIC
+//!
+//! All integers little-endian. A record is the timestamp plus YES and NO
+//! books as four f64 each, NaN for an empty side. A file cut short by a crash
+//! has no footer; `TickReader` then rebuilds the index by scanning frames.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+
+use crate::strategy::{OrderBook, Tick};
+
+const MAGIC: &[u8; 8] = b"ETHTICK\0";
+const INDEX_MAGIC: &[u8; 8] = b"TICKIDX\0";
+const VERSION: u8 = 1;
+const HEADER_LEN: u64 = 9;
+const FOOTER_LEN: i64 = 16;
+const RECORD_LEN: usize = 8 + 2 * 32;
+// Ticks per frame per market: five minutes at one tick a second
+const DEFAULT_FRAME_TICKS: usize = 300;
+const ZSTD_LEVEL: i32 = 9;
+
+/// Where one frame lives and what it covers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameInfo {
+    pub market: String,
+    pub first_ts: u64,
+    pub last_ts: u64,
+    pub offset: u64,
+    pub ticks: u32,
+}
+
+fn encode_book(out: &mut Vec<u8>, book: &OrderBook) {
+    for v in [book.best_ask.unwrap_or(f64::NAN), book.ask_size, book.best_bid.unwrap_or(f64::NAN), book.bid_size] {
+        out.extend_from_slice(&v.to_le_bytes());
+    }
+}
+
+fn decode_book(bytes: &[u8]) -> OrderBook {
+    let f = |i: usize| f64::from_le_bytes(bytes[i * 8..i * 8 + 8].try_into().unwrap());
+    let price = |v: f64| if v.is_nan() { None } else { Some(v) };
+    OrderBook { best_ask: price(f(0)), ask_size: f(1), best_bid: price(f(2)), bid_size: f(3) }
+}
+
+fn read_u16<R: Read>(r: &mut R) -> io::Result<u16> {
+    let mut b = [0u8; 2];
+    r.read_exact(&mut b)?;
+    Ok(u16::from_le_bytes(b))
+}
+
+fn read_u32<R: Read>(r: &mut R) -> io::Result<u32> {
+    let mut b = [0u8; 4];
+    r.read_exact(&mut b)?;
+    Ok(u32::from_le_bytes(b))
+}
+
+fn read_u64<R: Read>(r: &mut R) -> io::Result<u64> {
+    let mut b = [0u8; 8];
+    r.read_exact(&mut b)?;
+    Ok(u64::from_le_bytes(b))
+}
+
+fn read_market<R: Read>(r: &mut R) -> Result<String, Box<dyn std::error::Error>> {
+    let mut market = vec![0u8; read_u16(r)? as usize];
+    r.read_exact(&mut market)?;
+    Ok(String::from_utf8(market)?)
+}
+
+fn write_market<W: Write>(w: &mut W, market: &str) -> io::Result<()> {
+    let len = u16::try_from(market.len()).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "market name too long"))?;
+    w.write_all(&len.to_le_bytes())?;
+    w.write_all(market.as_bytes())
+}
+
+/// Buffers ticks per market and writes a frame whenever one fills up.
+/// Call `finish` to flush the rest and write the index.
+pub struct TickWriter<W: Write + Seek> {
+    out: W,
+    pending: BTreeMap<String, Vec<Tick>>,
+    index: Vec<FrameInfo>,
+    frame_ticks: usize,
+}
+
+impl TickWriter<BufWriter<File>> {
+    pub fn create(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self::new(BufWriter::new(File::create(path)?))?)
+    }
+}
+
+impl<W: Write + Seek> TickWriter<W> {
+    pub fn new(mut out: W) -> io::Result<Self> {
+        out.write_all(MAGIC)?;
+        out.write_all(&[VERSION])?;
+        Ok(Self { out, pending: BTreeMap::new(), index: Vec::new(), frame_ticks: DEFAULT_FRAME_TICKS })
+    }
+
+    pub fn with_frame_ticks(mut self, frame_ticks: usize) -> Self {
+        self.frame_ticks = frame_ticks.max(1);
+        self
+    }
+
+    pub fn push(&mut self, market: &str, tick: &Tick) -> io::Result<()> {
+        let ticks = self.pending.entry(market.to_string()).or_default();
+        ticks.push(tick.clone());
+        if ticks.len() >= self.frame_ticks {
+            let ticks = self.pending.remove(market).unwrap_or_default();
+            self.write_frame(market, &ticks)?;
+        }
+        Ok(())
+    }
+
+    fn write_frame(&mut self, market: &str, ticks: &[Tick]) -> io::Result<()> {
+        let (Some(first), Some(last)) = (ticks.first(), ticks.last()) else { return Ok(()) };
+        let mut raw = Vec::with_capacity(ticks.len() * RECORD_LEN);
+        for tick in ticks {
+            raw.extend_from_slice(&tick.ts.to_le_bytes());
+            encode_book(&mut raw, &tick.yes);
+            encode_book(&mut raw, &tick.no);
+        }
+        let compressed = zstd::stream::encode_all(raw.as_slice(), ZSTD_LEVEL)?;
+
+        let offset = self.out.stream_position()?;
+        write_market(&mut self.out, market)?;
+        self.out.write_all(&(ticks.len() as u32).to_le_bytes())?;
+        self.out.write_all(&(compressed.len() as u32).to_le_bytes())?;
+        self.out.write_all(&compressed)?;
+
+        self.index.push(FrameInfo {
+            market: market.to_string(),
+            first_ts: first.ts,
+            last_ts: last.ts,
+            offset,
+            ticks: ticks.len() as u32,
+        });
+        Ok(())
+    }
+
+    /// Flush partial frames, append the index and footer, and hand back the
+    /// underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        for (market, ticks) in std::mem::take(&mut self.pending) {
+            self.write_frame(&market, &ticks)?;
+        }
+
+        let index_offset = self.out.stream_position()?;
+        self.out.write_all(&(self.index.len() as u32).to_le_bytes())?;
+        for frame in &self.index {
+            write_market(&mut self.out, &frame.market)?;
+            self.out.write_all(&frame.first_ts.to_le_bytes())?;
+            self.out.write_all(&frame.last_ts.to_le_bytes())?;
+            self.out.write_all(&frame.offset.to_le_bytes())?;
+            self.out.write_all(&frame.ticks.to_le_bytes())?;
+        }
+        self.out.write_all(&index_offset.to_le_bytes())?;
+        self.out.write_all(INDEX_MAGIC)?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+pub struct TickReader<R: Read + Seek> {
+    inner: R,
+    index: Vec<FrameInfo>,
+}
+
+impl TickReader<BufReader<File>> {
+    pub fn open(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read + Seek> TickReader<R> {
+    pub fn new(mut inner: R) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut header = [0u8; 9];
+        inner.read_exact(&mut header).map_err(|_| "not a tick file: too short")?;
+        if &header[..8] != MAGIC {
+            return Err("not a tick file: bad magic".into());
+        }
+        if header[8] != VERSION {
+            return Err(format!("unsupported tick file version {}", header[8]).into());
+        }
+
+        let index = match Self::read_index(&mut inner)? {
+            Some(index) => index,
+            None => Self::scan_frames(&mut inner)?,
+        };
+        Ok(Self { inner, index })
+    }
+
+    /// The index written by `finish`, or None when the footer is missing.
+    fn read_index(inner: &mut R) -> Result<Option<Vec<FrameInfo>>, Box<dyn std::error::Error>> {
+        let end = inner.seek(SeekFrom::End(0))?;
+        if end < HEADER_LEN + FOOTER_LEN as u64 {
+            return Ok(None);
+        }
+        inner.seek(SeekFrom::End(-FOOTER_LEN))?;
+        let index_offset = read_u64(inner)?;
+        let mut magic = [0u8; 8];
+        inner.read_exact(&mut magic)?;
+        if &magic != INDEX_MAGIC || index_offset < HEADER_LEN || index_offset > end {
+            return Ok(None);
+        }
+
+        inner.seek(SeekFrom::Start(index_offset))?;
+        let frames = read_u32(inner)?;
+        let mut index = Vec::with_capacity(frames.min(1 << 20) as usize);
+        for _ in 0..frames {
+            index.push(FrameInfo {
+                market: read_market(inner)?,
+                first_ts: read_u64(inner)?,
+                last_ts: read_u64(inner)?,
+                offset: read_u64(inner)?,
+                ticks: read_u32(inner)?,
+            });
+        }
+        Ok(Some(index))
+    }
+
+    /// Walk frames from the header, keeping every complete one. Timestamps
+    /// come from decoding each frame, so this is the slow path.
+    fn scan_frames(inner: &mut R) -> Result<Vec<FrameInfo>, Box<dyn std::error::Error>> {
+        let mut index = Vec::new();
+        let mut offset = HEADER_LEN;
+        loop {
+            inner.seek(SeekFrom::Start(offset))?;
+            let Ok((market, ticks)) = Self::read_frame_at(inner) else { break };
+            let (Some(first), Some(last)) = (ticks.first(), ticks.last()) else { break };
+            index.push(FrameInfo { market, first_ts: first.ts, last_ts: last.ts, offset, ticks: ticks.len() as u32 });
+            offset = inner.stream_position()?;
+        }
+        Ok(index)
+    }
+
+    fn read_frame_at(inner: &mut R) -> Result<(String, Vec<Tick>), Box<dyn std::error::Error>> {
+        let market = read_market(inner)?;
+        let count = read_u32(inner)? as usize;
+        let mut compressed = vec![0u8; read_u32(inner)? as usize];
+        inner.read_exact(&mut compressed)?;
+
+        let raw = zstd::stream::decode_all(compressed.as_slice())?;
+        if raw.len() != count * RECORD_LEN {
+            return Err(format!("frame for {} holds {} bytes, expected {} ticks", market, raw.len(), count).into());
+        }
+        let ticks = raw.chunks_exact(RECORD_LEN)
+            .map(|r| Tick {
+                ts: u64::from_le_bytes(r[..8].try_into().unwrap()),
+                yes: decode_book(&r[8..40]),
+                no: decode_book(&r[40..72]),
+            })
+            .collect();
+        Ok((market, ticks))
+    }
+
+    pub fn index(&self) -> &[FrameInfo] {
+        &self.index
+    }
+
+    /// Distinct markets in the file, sorted.
+    pub fn markets(&self) -> Vec<&str> {
+        let mut markets: Vec<&str> = self.index.iter().map(|f| f.market.as_str()).collect();
+        markets.sort_unstable();
+        markets.dedup();
+        markets
+    }
+
+    pub fn read_frame(&mut self, frame: &FrameInfo) -> Result<Vec<Tick>, Box<dyn std::error::Error>> {
+        self.inner.seek(SeekFrom::Start(frame.offset))?;
+        Ok(Self::read_frame_at(&mut self.inner)?.1)
+    }
+
+    /// Every tick of `market` with `from <= ts <= to`, in time order. Only
+    /// frames overlapping the range are decompressed.
+    pub fn read_market(&mut self, market: &str, from: u64, to: u64) -> Result<Vec<Tick>, Box<dyn std::error::Error>> {
+        let frames: Vec<FrameInfo> = self.index.iter()
+            .filter(|f| f.market == market && f.last_ts >= from && f.first_ts <= to)
+            .cloned()
+            .collect();
+        let mut ticks = Vec::new();
+        for frame in &frames {
+            ticks.extend(self.read_frame(frame)?.into_iter().filter(|t| t.ts >= from && t.ts <= to));
+        }
+        ticks.sort_by_key(|t| t.ts);
+        Ok(ticks)
+    }
+}
+
+const CSV_COLUMNS: [&str; 10] = [
+    "market", "ts", "yes_bid", "yes_bid_size", "yes_ask", "yes_ask_size", "no_bid", "no_bid_size", "no_ask", "no_ask_size",
+];
+
+/// Flatten a tick file to CSV, one row per tick, frames in file order.
+/// Empty book sides are empty cells. Returns the number of rows.
+pub fn write_csv<R: Read + Seek, W: Write>(reader: &mut TickReader<R>, out: W) -> Result<usize, Box<dyn std::error::Error>> {
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record(CSV_COLUMNS)?;
+
+    let price = |p: Option<f64>| p.map(|p| p.to_string()).unwrap_or_default();
+    let mut rows = 0;
+    for frame in reader.index().to_vec() {
+        for tick in reader.read_frame(&frame)? {
+            writer.write_record([
+                frame.market.clone(),
+                tick.ts.to_string(),
+                price(tick.yes.best_bid), tick.yes.bid_size.to_string(),
+                price(tick.yes.best_ask), tick.yes.ask_size.to_string(),
+                price(tick.no.best_bid), tick.no.bid_size.to_string(),
+                price(tick.no.best_ask), tick.no.ask_size.to_string(),
+            ])?;
+            rows += 1;
+        }
+    }
+    writer.flush()?;
+    Ok(rows)
+}
@@ -0,0 +1,80 @@
+//! Timestamp formatting. Anything written to disk is RFC3339 UTC so records
+//! from different days and hosts sort and compare; the console can show a
+//! chosen zone instead (BOT_DISPLAY_TZ).
+
+use std::str::FromStr;
+
+use chrono::{DateTime, FixedOffset, Local, SecondsFormat, TimeZone, Utc};
+
+/// `2025-10-09T08:00:00Z` for unix seconds.
+pub fn rfc3339(unix_secs: u64) -> String {
+    utc(unix_secs).to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn utc(unix_secs: u64) -> DateTime<Utc> {
+    Utc.timestamp_opt(unix_secs as i64, 0).single().unwrap_or_default()
+}
+
+/// Zone for console timestamps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayTz {
+    Utc,
+    // Whatever the host is set to
+    Local,
+    Fixed(FixedOffset),
+}
+
+impl DisplayTz {
+    /// `UTC`, `local`, or a fixed offset such as `+05:30` / `-04:00`.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim() {
+            v if v.eq_ignore_ascii_case("utc") || v == "Z" => Some(Self::Utc),
+            v if v.eq_ignore_ascii_case("local") => Some(Self::Local),
+            v => FixedOffset::from_str(v).ok().map(Self::Fixed),
+        }
+    }
+
+    /// BOT_DISPLAY_TZ, UTC when unset.
+    pub fn from_env() -> Result<Self, Box<dyn std::error::Error>> {
+        match std::env::var("BOT_DISPLAY_TZ") {
+            Ok(v) => Self::parse(&v)
+                .ok_or_else(|| format!("Invalid BOT_DISPLAY_TZ '{}': use UTC, local or an offset like +05:30", v).into()),
+            Err(_) => Ok(Self::Utc),
+        }
+    }
+
+    /// Format with a chrono pattern; `%Z`/`%:z` show the zone.
+    pub fn format(&self, unix_secs: u64, pattern: &str) -> String {
+        let t = utc(unix_secs);
+        match self {
+            Self::Utc => t.format(pattern).to_string(),
+            Self::Local => t.with_timezone(&Local).format(pattern).to_string(),
+            Self::Fixed(offset) => t.with_timezone(offset).format(pattern).to_string(),
+        }
+    }
+
+    /// Short console time with its zone, e.g. `08:00:00 UTC` or `13:30:00 +05:30`.
+    pub fn time(&self, unix_secs: u64) -> String {
+        match self {
+            Self::Utc => self.format(unix_secs, "%H:%M:%S UTC"),
+            _ => self.format(unix_secs, "%H:%M:%S %:z"),
+        }
+    }
+
+    pub fn datetime(&self, unix_secs: u64) -> String {
+        match self {
+            Self::Utc => self.format(unix_secs, "%Y-%m-%d %H:%M:%S UTC"),
+            _ => self.format(unix_secs, "%Y-%m-%d %H:%M:%S %:z"),
+        }
+    }
+}
+
+impl std::fmt::Display for DisplayTz {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Utc => write!(f, "UTC"),
+            Self::Local => write!(f, "local"),
+            Self::Fixed(offset) => write!(f, "{}", offset),
+        }
+    }
+}
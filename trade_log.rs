@@ -0,0 +1,497 @@
+//! The trade journal: one row per entry, exit or correction, as CSV or JSON
+//! lines. BOT_LOG_COLUMNS picks the fields and their order, each with an
+//! optional format after a colon:
+//!
+//!   BOT_LOG_COLUMNS=entry_time:unix,market,side,entry_price:4,size,fees,pnl:2
+//!
+//! Numbers take a count of decimals; times take `rfc3339` (the default),
+//! `unix`, `date`, or a chrono pattern such as `%d/%m/%Y %H:%M` (UTC).
+//! BOT_LOG_FORMAT is `csv` (default) or `jsonl`, the latter written next to
+//! the CSV with a `.jsonl` extension. With neither set the journal is the
+//! original twelve-column CSV, byte for byte.
+//!
+//! A CSV whose header no longer matches the configured columns is moved
+//! aside (`<name>.<unix secs>.csv`) instead of appended to, so a spreadsheet
+//! pointed at the old file never sees rows of a different shape.
+//!
+//! `read` turns the journal back into records for reports. Rows keep only
+//! what their columns hold; times in a %-pattern are read back with the
+//! pattern configured for that column.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+use serde_json::Value;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+
+use crate::timestamps::{self, DisplayTz};
+
+/// Everything the journal knows about one event. Empty strings and `None`
+/// are written as `-` in CSV and `null` in JSON lines.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TradeRecord {
+    pub title: String,
+    pub link: String,
+    // ENTERED, PARTIAL, EXITED, REDEEMED, ABORTED, REORG, SHUTDOWN
+    pub status: String,
+    pub market: String,
+    pub strategy: String,
+    pub side: String,
+    pub token_id: String,
+    pub order_id: String,
+    pub entry_time: Option<u64>,
+    pub entry_price: Option<f64>,
+    pub size: Option<f64>,
+    pub sl_time: Option<u64>,
+    pub sl_price: Option<f64>,
+    pub sl_triggered: Option<bool>,
+    pub exit_time: Option<u64>,
+    pub exit_price: Option<f64>,
+    // USDC paid in fees on the fills this row covers
+    pub fees: Option<f64>,
+    // Average fill price minus the price the signal fired at
+    pub slippage: Option<f64>,
+    pub pnl: Option<f64>,
+    pub final_status: String,
+    pub notes: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Text,
+    Number,
+    Time,
+    Flag,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Title,
+    Link,
+    Status,
+    Market,
+    Strategy,
+    Side,
+    TokenId,
+    OrderId,
+    EntryTime,
+    EntryPrice,
+    Size,
+    SlTime,
+    SlPrice,
+    SlTriggered,
+    ExitTime,
+    ExitPrice,
+    Fees,
+    Slippage,
+    Pnl,
+    FinalStatus,
+    Notes,
+}
+
+// Config name, CSV header, kind and default decimals for numbers. The
+// original twelve columns keep their original headers.
+const FIELDS: [(Field, &str, &str, Kind, usize); 21] = [
+    (Field::Title, "title", "Market Title", Kind::Text, 0),
+    (Field::Link, "link", "Market Link", Kind::Text, 0),
+    (Field::Status, "status", "Status", Kind::Text, 0),
+    (Field::Market, "market", "market", Kind::Text, 0),
+    (Field::Strategy, "strategy", "strategy", Kind::Text, 0),
+    (Field::Side, "side", "entry_Side", Kind::Text, 0),
+    (Field::TokenId, "token_id", "token_id", Kind::Text, 0),
+    (Field::OrderId, "order_id", "order_id", Kind::Text, 0),
+    (Field::EntryTime, "entry_time", "entry1_Time", Kind::Time, 0),
+    (Field::EntryPrice, "entry_price", "entry_Price", Kind::Number, 3),
+    (Field::Size, "size", "position_size", Kind::Number, 2),
+    (Field::SlTime, "sl_time", "sl_Time", Kind::Time, 0),
+    (Field::SlPrice, "sl_price", "sl_Price", Kind::Number, 3),
+    (Field::SlTriggered, "sl_triggered", "is_SL_Triggered", Kind::Flag, 0),
+    (Field::ExitTime, "exit_time", "exit_time", Kind::Time, 0),
+    (Field::ExitPrice, "exit_price", "exit_price", Kind::Number, 3),
+    (Field::Fees, "fees", "fees", Kind::Number, 4),
+    (Field::Slippage, "slippage", "slippage", Kind::Number, 4),
+    (Field::Pnl, "pnl", "pnl", Kind::Number, 2),
+    (Field::FinalStatus, "final_status", "Final_Status", Kind::Text, 0),
+    (Field::Notes, "notes", "Notes", Kind::Text, 0),
+];
+
+impl Field {
+    pub fn parse(name: &str) -> Option<Self> {
+        FIELDS.iter().find(|f| f.1 == name).map(|f| f.0)
+    }
+
+    fn spec(self) -> (Field, &'static str, &'static str, Kind, usize) {
+        FIELDS.iter().copied().find(|f| f.0 == self).expect("every field is listed")
+    }
+
+    /// Name used in BOT_LOG_COLUMNS and as the JSON key.
+    pub fn name(self) -> &'static str {
+        self.spec().1
+    }
+
+    pub fn header(self) -> &'static str {
+        self.spec().2
+    }
+
+    fn kind(self) -> Kind {
+        self.spec().3
+    }
+
+    pub fn all() -> impl Iterator<Item = Field> {
+        FIELDS.iter().map(|f| f.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Format {
+    // Text and flags as they are
+    Plain,
+    Decimals(usize),
+    Rfc3339,
+    Unix,
+    // Chrono pattern, UTC
+    Pattern(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Column {
+    pub field: Field,
+    pub format: Format,
+}
+
+impl Column {
+    pub fn new(field: Field) -> Self {
+        let format = match field.kind() {
+            Kind::Text | Kind::Flag => Format::Plain,
+            Kind::Number => Format::Decimals(field.spec().4),
+            Kind::Time => Format::Rfc3339,
+        };
+        Self { field, format }
+    }
+
+    /// `name` or `name:format`.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let (name, format) = match s.split_once(':') {
+            Some((name, format)) => (name.trim(), Some(format.trim())),
+            None => (s.trim(), None),
+        };
+        let field = Field::parse(name).ok_or_else(|| {
+            let known: Vec<&str> = Field::all().map(Field::name).collect();
+            format!("unknown column '{}' (known: {})", name, known.join(", "))
+        })?;
+        let mut column = Self::new(field);
+        let Some(format) = format else { return Ok(column) };
+        column.format = match (field.kind(), format) {
+            (Kind::Number, digits) => digits.parse::<usize>().ok().filter(|d| *d <= 10).map(Format::Decimals)
+                .ok_or_else(|| format!("column '{}': '{}' is not a number of decimals (0-10)", name, digits))?,
+            (Kind::Time, "rfc3339") => Format::Rfc3339,
+            (Kind::Time, "unix") => Format::Unix,
+            (Kind::Time, "date") => Format::Pattern("%Y-%m-%d".to_string()),
+            (Kind::Time, pattern) if pattern.contains('%') => Format::Pattern(pattern.to_string()),
+            (Kind::Time, other) => return Err(format!("column '{}': time format '{}' must be rfc3339, unix, date or a %-pattern", name, other)),
+            (_, other) => return Err(format!("column '{}' takes no format (got '{}')", name, other)),
+        };
+        Ok(column)
+    }
+
+    fn text(&self, record: &TradeRecord) -> String {
+        let text = match (value(record, self.field), &self.format) {
+            (Cell::Text(s), _) => s.to_string(),
+            (Cell::Number(Some(v)), Format::Decimals(d)) => format!("{:.*}", *d, v),
+            (Cell::Time(Some(t)), Format::Unix) => t.to_string(),
+            (Cell::Time(Some(t)), Format::Pattern(p)) => DisplayTz::Utc.format(t, p),
+            (Cell::Time(Some(t)), _) => timestamps::rfc3339(t),
+            (Cell::Flag(Some(b)), _) => b.to_string(),
+            _ => String::new(),
+        };
+        if text.is_empty() { "-".to_string() } else { text }
+    }
+
+    fn json(&self, record: &TradeRecord) -> Value {
+        match (value(record, self.field), &self.format) {
+            (Cell::Text(s), _) if !s.is_empty() => s.into(),
+            (Cell::Number(Some(v)), Format::Decimals(d)) => {
+                let scale = 10f64.powi(*d as i32);
+                ((v * scale).round() / scale).into()
+            }
+            (Cell::Time(Some(t)), Format::Unix) => t.into(),
+            (Cell::Time(Some(_)), _) => self.text(record).into(),
+            (Cell::Flag(Some(b)), _) => b.into(),
+            _ => Value::Null,
+        }
+    }
+}
+
+enum Cell<'a> {
+    Text(&'a str),
+    Number(Option<f64>),
+    Time(Option<u64>),
+    Flag(Option<bool>),
+}
+
+fn value(r: &TradeRecord, field: Field) -> Cell<'_> {
+    match field {
+        Field::Title => Cell::Text(&r.title),
+        Field::Link => Cell::Text(&r.link),
+        Field::Status => Cell::Text(&r.status),
+        Field::Market => Cell::Text(&r.market),
+        Field::Strategy => Cell::Text(&r.strategy),
+        Field::Side => Cell::Text(&r.side),
+        Field::TokenId => Cell::Text(&r.token_id),
+        Field::OrderId => Cell::Text(&r.order_id),
+        Field::EntryTime => Cell::Time(r.entry_time),
+        Field::EntryPrice => Cell::Number(r.entry_price),
+        Field::Size => Cell::Number(r.size),
+        Field::SlTime => Cell::Time(r.sl_time),
+        Field::SlPrice => Cell::Number(r.sl_price),
+        Field::SlTriggered => Cell::Flag(r.sl_triggered),
+        Field::ExitTime => Cell::Time(r.exit_time),
+        Field::ExitPrice => Cell::Number(r.exit_price),
+        Field::Fees => Cell::Number(r.fees),
+        Field::Slippage => Cell::Number(r.slippage),
+        Field::Pnl => Cell::Number(r.pnl),
+        Field::FinalStatus => Cell::Text(&r.final_status),
+        Field::Notes => Cell::Text(&r.notes),
+    }
+}
+
+fn set(r: &mut TradeRecord, field: Field, text: &str, format: &Format) {
+    let text = text.trim();
+    if text.is_empty() || text == "-" {
+        return;
+    }
+    let number = || text.parse::<f64>().ok();
+    let time = || parse_time(text, format);
+    match field {
+        Field::Title => r.title = text.to_string(),
+        Field::Link => r.link = text.to_string(),
+        Field::Status => r.status = text.to_string(),
+        Field::Market => r.market = text.to_string(),
+        Field::Strategy => r.strategy = text.to_string(),
+        Field::Side => r.side = text.to_string(),
+        Field::TokenId => r.token_id = text.to_string(),
+        Field::OrderId => r.order_id = text.to_string(),
+        Field::EntryTime => r.entry_time = time(),
+        Field::EntryPrice => r.entry_price = number(),
+        Field::Size => r.size = number(),
+        Field::SlTime => r.sl_time = time(),
+        Field::SlPrice => r.sl_price = number(),
+        Field::SlTriggered => r.sl_triggered = text.parse().ok(),
+        Field::ExitTime => r.exit_time = time(),
+        Field::ExitPrice => r.exit_price = number(),
+        Field::Fees => r.fees = number(),
+        Field::Slippage => r.slippage = number(),
+        Field::Pnl => r.pnl = number(),
+        Field::FinalStatus => r.final_status = text.to_string(),
+        Field::Notes => r.notes = text.to_string(),
+    }
+}
+
+/// Unix seconds or RFC3339, or `format`'s pattern read as UTC.
+fn parse_time(text: &str, format: &Format) -> Option<u64> {
+    if let Ok(unix) = text.parse::<u64>() {
+        return Some(unix);
+    }
+    if let Ok(t) = DateTime::parse_from_rfc3339(text) {
+        return u64::try_from(t.timestamp()).ok();
+    }
+    let Format::Pattern(pattern) = format else { return None };
+    let t = NaiveDateTime::parse_from_str(text, pattern).ok()
+        .or_else(|| NaiveDate::parse_from_str(text, pattern).ok().and_then(|d| d.and_hms_opt(0, 0, 0)))?;
+    u64::try_from(t.and_utc().timestamp()).ok()
+}
+
+/// The original journal layout.
+pub fn default_columns() -> Vec<Column> {
+    [
+        Field::Title, Field::Link, Field::Status, Field::EntryTime, Field::Side, Field::EntryPrice,
+        Field::Size, Field::SlTime, Field::SlPrice, Field::FinalStatus, Field::Notes, Field::SlTriggered,
+    ].into_iter().map(Column::new).collect()
+}
+
+/// Comma-separated `name[:format]` list.
+pub fn parse_columns(list: &str) -> Result<Vec<Column>, String> {
+    let columns = list.split(',').filter(|c| !c.trim().is_empty()).map(Column::parse).collect::<Result<Vec<_>, _>>()?;
+    if columns.is_empty() {
+        return Err("no columns given".to_string());
+    }
+    Ok(columns)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    Csv,
+    Jsonl,
+}
+
+impl LogFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "csv" => Some(Self::Csv),
+            "jsonl" | "json" => Some(Self::Jsonl),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeLog {
+    path: String,
+    format: LogFormat,
+    columns: Vec<Column>,
+}
+
+impl TradeLog {
+    pub fn new(path: &str, format: LogFormat, columns: Vec<Column>) -> Self {
+        Self { path: path.to_string(), format, columns }
+    }
+
+    /// From BOT_LOG_COLUMNS / BOT_LOG_FORMAT values; `csv_path` is where the
+    /// CSV journal lives, JSON lines go beside it.
+    pub fn configure(csv_path: &str, columns: Option<&str>, format: Option<&str>) -> Result<Self, String> {
+        let columns = match columns {
+            Some(list) => parse_columns(list).map_err(|e| format!("Invalid BOT_LOG_COLUMNS: {}", e))?,
+            None => default_columns(),
+        };
+        let format = match format {
+            Some(f) => LogFormat::parse(f).ok_or_else(|| format!("Invalid BOT_LOG_FORMAT '{}': use csv or jsonl", f))?,
+            None => LogFormat::Csv,
+        };
+        let path = match format {
+            LogFormat::Csv => csv_path.to_string(),
+            LogFormat::Jsonl => Path::new(csv_path).with_extension("jsonl").to_string_lossy().into_owned(),
+        };
+        Ok(Self::new(&path, format, columns))
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn format(&self) -> LogFormat {
+        self.format
+    }
+
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    pub fn header(&self) -> Vec<&'static str> {
+        self.columns.iter().map(|c| c.field.header()).collect()
+    }
+
+    /// Create the journal if needed. Returns where an existing CSV with a
+    /// different header was moved to, if it was.
+    pub fn init(&self, now: u64) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        if self.format == LogFormat::Jsonl {
+            return Ok(None);
+        }
+        let header = self.header().join(",");
+        let mut moved = None;
+        if Path::new(&self.path).exists() {
+            let mut first = String::new();
+            BufReader::new(File::open(&self.path)?).read_line(&mut first)?;
+            if first.trim_end() == header {
+                return Ok(None);
+            }
+            let path = Path::new(&self.path);
+            let stem = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+            let aside = path.with_file_name(format!("{}.{}.csv", stem, now)).to_string_lossy().into_owned();
+            fs::rename(&self.path, &aside)?;
+            moved = Some(aside);
+        }
+        let mut file = File::create(&self.path)?;
+        writeln!(file, "{}", header)?;
+        Ok(moved)
+    }
+
+    /// The row or object `record` becomes.
+    pub fn render(&self, record: &TradeRecord) -> String {
+        match self.format {
+            LogFormat::Csv => {
+                let mut writer = csv::WriterBuilder::new().has_headers(false).from_writer(Vec::new());
+                let row: Vec<String> = self.columns.iter().map(|c| c.text(record)).collect();
+                let _ = writer.write_record(&row);
+                String::from_utf8(writer.into_inner().unwrap_or_default()).unwrap_or_default()
+            }
+            LogFormat::Jsonl => {
+                // Written by hand so keys keep the configured order
+                let fields: Vec<String> = self.columns.iter()
+                    .map(|c| format!("{}:{}", Value::from(c.field.name()), c.json(record)))
+                    .collect();
+                format!("{{{}}}\n", fields.join(","))
+            }
+        }
+    }
+
+    pub fn append(&self, record: &TradeRecord) -> Result<(), Box<dyn std::error::Error>> {
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        file.write_all(self.render(record).as_bytes())?;
+        Ok(())
+    }
+
+    /// Every row of the journal, oldest first; no file is an empty journal.
+    /// CSV columns are matched by header, JSON lines by key, and unknown
+    /// ones are skipped.
+    pub fn read(&self) -> Result<Vec<TradeRecord>, String> {
+        if !Path::new(&self.path).exists() {
+            return Ok(Vec::new());
+        }
+        let file = File::open(&self.path).map_err(|e| format!("Cannot read {}: {}", self.path, e))?;
+        match self.format {
+            LogFormat::Csv => self.read_csv(file),
+            LogFormat::Jsonl => self.read_jsonl(file),
+        }
+    }
+
+    // How `field` was written, for reading pattern times back
+    fn format_of(&self, field: Field) -> Format {
+        self.columns.iter().find(|c| c.field == field).map(|c| c.format.clone()).unwrap_or(Format::Plain)
+    }
+
+    fn read_csv(&self, file: File) -> Result<Vec<TradeRecord>, String> {
+        let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(file);
+        let headers = reader.headers().map_err(|e| format!("{}: {}", self.path, e))?.clone();
+        let columns: Vec<Option<(Field, Format)>> = headers.iter()
+            .map(|h| Field::all().find(|f| f.header() == h || f.name() == h).map(|f| (f, self.format_of(f))))
+            .collect();
+        let mut records = Vec::new();
+        for (n, row) in reader.records().enumerate() {
+            let row = row.map_err(|e| format!("{} row {}: {}", self.path, n + 2, e))?;
+            let mut record = TradeRecord::default();
+            for (text, column) in row.iter().zip(&columns) {
+                if let Some((field, format)) = column {
+                    set(&mut record, *field, text, format);
+                }
+            }
+            records.push(record);
+        }
+        Ok(records)
+    }
+
+    fn read_jsonl(&self, file: File) -> Result<Vec<TradeRecord>, String> {
+        let mut records = Vec::new();
+        for (n, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.map_err(|e| format!("Cannot read {}: {}", self.path, e))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let object: serde_json::Map<String, Value> = serde_json::from_str(&line)
+                .map_err(|e| format!("{} line {}: {}", self.path, n + 1, e))?;
+            let mut record = TradeRecord::default();
+            for (key, value) in &object {
+                let Some(field) = Field::parse(key) else { continue };
+                let text = match value {
+                    Value::String(s) => s.clone(),
+                    Value::Null => continue,
+                    other => other.to_string(),
+                };
+                set(&mut record, field, &text, &self.format_of(field));
+            }
+            records.push(record);
+        }
+        Ok(records)
+    }
+}
@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// Why the bot is done with a market.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradedMarket {
+    pub marked_at: u64,
+    pub reason: String,
+}
+
+/// Markets the bot already traded, aborted or gave up on, keyed by slug.
+/// Saved after every change so a restart inside a window doesn't re-enter.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TradedMarkets {
+    pub markets: HashMap<String, TradedMarket>,
+}
+
+impl TradedMarkets {
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        if !Path::new(path).exists() {
+            return Ok(Self::default());
+        }
+        let file = File::open(path)?;
+        Ok(serde_json::from_reader(BufReader::new(file))?)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        // Same temp-file-and-rename as the market cache: never half-written
+        let tmp_path = format!("{}.tmp", path);
+        let file = File::create(&tmp_path)?;
+        serde_json::to_writer(BufWriter::new(file), self)?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// Keeps the first reason when a market is marked twice.
+    pub fn insert(&mut self, slug: &str, now: u64, reason: &str) {
+        self.markets.entry(slug.to_string()).or_insert_with(|| TradedMarket {
+            marked_at: now,
+            reason: reason.to_string(),
+        });
+    }
+
+    pub fn contains(&self, slug: &str) -> bool {
+        self.markets.contains_key(slug)
+    }
+
+    /// Drop entries marked more than `max_age` seconds before `now`; their
+    /// markets closed long ago. Returns how many were dropped.
+    pub fn prune(&mut self, now: u64, max_age: u64) -> usize {
+        let before = self.markets.len();
+        self.markets.retain(|_, m| now.saturating_sub(m.marked_at) <= max_age);
+        before - self.markets.len()
+    }
+}
@@ -0,0 +1,320 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Address, Block, Bytes, Eip1559TransactionRequest, TransactionReceipt, H256, U256};
+use serde_json::json;
+
+use crate::chain::RpcClient;
+use crate::nonce_manager::NonceManager;
+use crate::revert;
+
+// Polygon validators reject tips under 30 gwei
+const MIN_PRIORITY_FEE_GWEI: u64 = 30;
+// Geth-style nodes require >= 10% bump to replace; use a bit more
+const FEE_BUMP_PERCENT: u64 = 20;
+const MAX_SEND_ATTEMPTS: u32 = 4;
+const RECEIPT_POLL_INTERVAL: Duration = Duration::from_secs(3);
+const GAS_LIMIT_MARGIN_PERCENT: u64 = 20;
+
+/// How long to wait and how deep a transaction must be before it counts.
+/// Defaults can be overridden with TX_CONFIRMATIONS, TX_STUCK_SECS,
+/// TX_TIMEOUT_SECS and TX_MAX_REPLACEMENTS.
+#[derive(Debug, Clone)]
+pub struct TxConfig {
+    pub confirmations: u64,
+    // Not mined after this long => re-send at the same nonce with higher fees
+    pub stuck_after: Duration,
+    // Give up (with every sent hash in the error) after this long
+    pub timeout: Duration,
+    pub max_replacements: u32,
+}
+
+impl Default for TxConfig {
+    fn default() -> Self {
+        Self {
+            confirmations: 5,
+            stuck_after: Duration::from_secs(45),
+            timeout: Duration::from_secs(600),
+            max_replacements: 5,
+        }
+    }
+}
+
+impl TxConfig {
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        let var = |name: &str| std::env::var(name).ok().and_then(|v| v.parse::<u64>().ok());
+        if let Some(v) = var("TX_CONFIRMATIONS") {
+            config.confirmations = v;
+        }
+        if let Some(v) = var("TX_STUCK_SECS") {
+            config.stuck_after = Duration::from_secs(v);
+        }
+        if let Some(v) = var("TX_TIMEOUT_SECS") {
+            config.timeout = Duration::from_secs(v);
+        }
+        if let Some(v) = var("TX_MAX_REPLACEMENTS") {
+            config.max_replacements = v as u32;
+        }
+        config
+    }
+}
+
+/// An on-chain call to make from the bot's EOA.
+#[derive(Debug, Clone)]
+pub struct TxRequest {
+    pub label: String,
+    pub to: Address,
+    pub data: Bytes,
+    pub value: U256,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FeeQuote {
+    pub max_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+}
+
+impl FeeQuote {
+    fn bumped(&self) -> Self {
+        let bump = |v: U256| v * (100 + FEE_BUMP_PERCENT) / 100;
+        Self {
+            max_fee_per_gas: bump(self.max_fee_per_gas),
+            max_priority_fee_per_gas: bump(self.max_priority_fee_per_gas),
+        }
+    }
+}
+
+/// Sends EIP-1559 transactions with Polygon-appropriate fees, re-sending
+/// with bumped fees when the node rejects the price or the transaction
+/// sits unmined, then waits for the configured confirmation depth.
+pub struct TxManager {
+    wallet: LocalWallet,
+    chain_id: u64,
+    config: TxConfig,
+    nonces: NonceManager,
+}
+
+impl TxManager {
+    pub fn new(wallet: LocalWallet, chain_id: u64, config: TxConfig) -> Self {
+        let nonces = NonceManager::new(wallet.address());
+        Self { wallet: wallet.with_chain_id(chain_id), chain_id, config, nonces }
+    }
+
+    pub fn nonces(&self) -> &NonceManager {
+        &self.nonces
+    }
+
+    pub fn address(&self) -> Address {
+        self.wallet.address()
+    }
+
+    pub fn quote_fees(&self, rpc: &RpcClient) -> Result<FeeQuote, Box<dyn std::error::Error>> {
+        let block: Block<H256> = rpc.call("eth_getBlockByNumber", json!(["latest", false]))?;
+        let base_fee = block.base_fee_per_gas.ok_or("latest block has no base fee")?;
+
+        let suggested: U256 = rpc.call("eth_maxPriorityFeePerGas", json!([])).unwrap_or_default();
+        let floor = U256::from(MIN_PRIORITY_FEE_GWEI) * U256::exp10(9);
+        let priority = suggested.max(floor);
+
+        // 2x base fee covers several full blocks of base-fee growth
+        Ok(FeeQuote {
+            max_fee_per_gas: base_fee * 2 + priority,
+            max_priority_fee_per_gas: priority,
+        })
+    }
+
+    fn build(&self, rpc: &RpcClient, req: &TxRequest, nonce: U256, fees: FeeQuote) -> Result<TypedTransaction, Box<dyn std::error::Error>> {
+        let mut tx: TypedTransaction = Eip1559TransactionRequest::new()
+            .from(self.address())
+            .to(req.to)
+            .data(req.data.clone())
+            .value(req.value)
+            .nonce(nonce)
+            .chain_id(self.chain_id)
+            .max_fee_per_gas(fees.max_fee_per_gas)
+            .max_priority_fee_per_gas(fees.max_priority_fee_per_gas)
+            .into();
+
+        // "pending" so a queued approve is visible to the redeem behind it
+        let estimate: U256 = rpc.call("eth_estimateGas", json!([&tx, "pending"]))
+            .map_err(|e| revert::describe_failure(&req.label, e.as_ref()))?;
+        tx.set_gas(estimate * (100 + GAS_LIMIT_MARGIN_PERCENT) / 100);
+        Ok(tx)
+    }
+
+    /// Dry-run the call from our address so a revert costs nothing. The
+    /// error names the revert reason and, for the usual suspects, what to fix.
+    pub fn simulate(&self, rpc: &RpcClient, req: &TxRequest) -> Result<Bytes, Box<dyn std::error::Error>> {
+        let call = json!({
+            "from": format!("{:?}", self.address()),
+            "to": format!("{:?}", req.to),
+            "data": format!("0x{}", hex::encode(&req.data)),
+            "value": req.value,
+        });
+        rpc.call("eth_call", json!([call, "pending"]))
+            .map_err(|e| revert::describe_failure(&req.label, e.as_ref()).into())
+    }
+
+    fn send_raw(&self, rpc: &RpcClient, tx: &TypedTransaction) -> Result<H256, Box<dyn std::error::Error>> {
+        let signature = self.wallet.sign_transaction_sync(tx)?;
+        let raw = tx.rlp_signed(&signature);
+        rpc.call("eth_sendRawTransaction", json!([raw]))
+    }
+
+    /// Sign and send at `nonce`, bumping fees while the node rejects the price.
+    fn submit(&self, rpc: &RpcClient, req: &TxRequest, nonce: U256, fees: &mut FeeQuote) -> Result<H256, Box<dyn std::error::Error>> {
+        for attempt in 1..=MAX_SEND_ATTEMPTS {
+            let tx = self.build(rpc, req, nonce, *fees)?;
+            match self.send_raw(rpc, &tx) {
+                Ok(tx_hash) => {
+                    println!("   ⛓️ {} sent: {:?} (nonce {}, tip {} gwei)", req.label, tx_hash, nonce,
+                        fees.max_priority_fee_per_gas / U256::exp10(9));
+                    self.nonces.record_broadcast(nonce, tx_hash);
+                    return Ok(tx_hash);
+                }
+                Err(e) if is_fee_rejection(&e.to_string()) && attempt < MAX_SEND_ATTEMPTS => {
+                    println!("   ⚠️ {} underpriced ({}); bumping fees {}%", req.label, e, FEE_BUMP_PERCENT);
+                    *fees = fees.bumped();
+                }
+                Err(e) => return Err(format!("{} failed to send: {}", req.label, e).into()),
+            }
+        }
+
+        Err(format!("{} not accepted after {} attempts", req.label, MAX_SEND_ATTEMPTS).into())
+    }
+
+    /// Reserve a nonce and broadcast, resyncing once if the node says our
+    /// local nonce is already used.
+    fn broadcast(&self, rpc: &RpcClient, req: &TxRequest, fees: &mut FeeQuote) -> Result<(U256, H256), Box<dyn std::error::Error>> {
+        for _ in 0..2 {
+            let nonce = self.nonces.reserve(rpc, &req.label)?;
+            match self.submit(rpc, req, nonce, fees) {
+                Ok(tx_hash) => return Ok((nonce, tx_hash)),
+                Err(e) => {
+                    self.nonces.release(nonce);
+                    if !e.to_string().to_lowercase().contains("nonce too low") {
+                        return Err(e);
+                    }
+                    self.nonces.resync();
+                }
+            }
+        }
+        Err(format!("{}: nonce still rejected after resync", req.label).into())
+    }
+
+    /// Send and wait until the transaction is `confirmations` blocks deep.
+    pub fn send(&self, rpc: &RpcClient, req: &TxRequest) -> Result<TransactionReceipt, Box<dyn std::error::Error>> {
+        self.simulate(rpc, req)?;
+        let mut fees = self.quote_fees(rpc)?;
+        let (nonce, tx_hash) = self.broadcast(rpc, req, &mut fees)?;
+        self.wait_mined(rpc, req, nonce, vec![tx_hash], fees)
+    }
+
+    /// Queue several transactions at consecutive nonces, then wait for each.
+    pub fn send_all(&self, rpc: &RpcClient, reqs: &[TxRequest]) -> Result<Vec<TransactionReceipt>, Box<dyn std::error::Error>> {
+        let mut fees = self.quote_fees(rpc)?;
+        let mut in_flight = Vec::new();
+        for req in reqs {
+            // Simulated against "pending", so earlier requests in the batch count
+            self.simulate(rpc, req)?;
+            let (nonce, tx_hash) = self.broadcast(rpc, req, &mut fees)?;
+            in_flight.push((req, nonce, tx_hash));
+        }
+
+        in_flight.into_iter()
+            .map(|(req, nonce, tx_hash)| self.wait_mined(rpc, req, nonce, vec![tx_hash], fees))
+            .collect()
+    }
+
+    /// A transaction that stays unmined past `stuck_after` is replaced at the
+    /// same nonce with bumped fees; whichever version mines first wins.
+    fn wait_mined(&self, rpc: &RpcClient, req: &TxRequest, nonce: U256, mut sent: Vec<H256>, mut fees: FeeQuote)
+        -> Result<TransactionReceipt, Box<dyn std::error::Error>> {
+        let started = Instant::now();
+        let mut last_send = Instant::now();
+        let mut replacements = 0;
+
+        while started.elapsed() < self.config.timeout {
+            if let Some(receipt) = self.find_receipt(rpc, &sent).inspect_err(|_| self.nonces.confirm(nonce))? {
+                self.nonces.confirm(nonce);
+                return self.await_confirmations(rpc, receipt);
+            }
+
+            if last_send.elapsed() >= self.config.stuck_after && replacements < self.config.max_replacements {
+                replacements += 1;
+                fees = fees.bumped();
+                println!("   🐌 {} stuck for {}s; replacement {}/{}", req.label,
+                    last_send.elapsed().as_secs(), replacements, self.config.max_replacements);
+                match self.submit(rpc, req, nonce, &mut fees) {
+                    Ok(tx_hash) => sent.push(tx_hash),
+                    // "nonce too low" means one of the earlier versions just mined
+                    Err(e) if e.to_string().to_lowercase().contains("nonce too low") => {}
+                    Err(e) => println!("   ⚠️ Replacement failed: {}", e),
+                }
+                last_send = Instant::now();
+            }
+
+            thread::sleep(RECEIPT_POLL_INTERVAL);
+        }
+
+        Err(format!("{} not mined after {}s (nonce {}, sent: {:?})", req.label, self.config.timeout.as_secs(), nonce, sent).into())
+    }
+
+    fn find_receipt(&self, rpc: &RpcClient, hashes: &[H256]) -> Result<Option<TransactionReceipt>, Box<dyn std::error::Error>> {
+        for tx_hash in hashes {
+            let receipt: Option<TransactionReceipt> = rpc.call_optional("eth_getTransactionReceipt", json!([tx_hash]))?;
+            if let Some(receipt) = receipt {
+                if receipt.status == Some(0u64.into()) {
+                    return Err(format!("transaction {:?} reverted", tx_hash).into());
+                }
+                return Ok(Some(receipt));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Wait for depth, re-checking the receipt so a reorged-out transaction
+    /// isn't reported as done.
+    fn await_confirmations(&self, rpc: &RpcClient, receipt: TransactionReceipt) -> Result<TransactionReceipt, Box<dyn std::error::Error>> {
+        let mined_at = receipt.block_number.map(|b| b.as_u64()).unwrap_or(0);
+        let started = Instant::now();
+
+        loop {
+            let head = rpc.block_number()?;
+            if head.saturating_sub(mined_at) + 1 >= self.config.confirmations {
+                let current = self.find_receipt(rpc, &[receipt.transaction_hash])?;
+                return match current {
+                    Some(r) if r.block_hash == receipt.block_hash => Ok(r),
+                    Some(r) => self.await_confirmations(rpc, r),
+                    None => Err(format!("transaction {:?} dropped by reorg", receipt.transaction_hash).into()),
+                };
+            }
+            if started.elapsed() >= self.config.timeout {
+                return Err(format!("transaction {:?} mined but not {} deep after {}s",
+                    receipt.transaction_hash, self.config.confirmations, self.config.timeout.as_secs()).into());
+            }
+            thread::sleep(RECEIPT_POLL_INTERVAL);
+        }
+    }
+
+    pub fn wait_for_receipt(&self, rpc: &RpcClient, tx_hash: H256) -> Result<TransactionReceipt, Box<dyn std::error::Error>> {
+        let started = Instant::now();
+        while started.elapsed() < self.config.timeout {
+            if let Some(receipt) = self.find_receipt(rpc, &[tx_hash])? {
+                return self.await_confirmations(rpc, receipt);
+            }
+            thread::sleep(RECEIPT_POLL_INTERVAL);
+        }
+        Err(format!("no receipt for {:?} after {}s", tx_hash, self.config.timeout.as_secs()).into())
+    }
+}
+
+fn is_fee_rejection(message: &str) -> bool {
+    let message = message.to_lowercase();
+    message.contains("underpriced")
+        || message.contains("fee too low")
+        || message.contains("max fee per gas less than block base fee")
+}